@@ -0,0 +1,52 @@
+/* C bindings for tauri-dumper. */
+
+#ifndef TAURI_DUMPER_H
+#define TAURI_DUMPER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opened binary. */
+typedef struct TauriDumper TauriDumper;
+
+/* The assets found by tauri_dumper_scan. */
+typedef struct TauriDumperAssets TauriDumperAssets;
+
+/* Returns the message of the last error on this thread, or NULL. The string
+ * stays valid until the next failing call on the same thread. */
+const char *tauri_dumper_last_error(void);
+
+/* Opens the executable at a UTF-8 path. Returns NULL on error. */
+TauriDumper *tauri_dumper_open(const char *path);
+
+/* Opens an executable held in memory. The bytes are copied. */
+TauriDumper *tauri_dumper_open_bytes(const uint8_t *data, size_t len);
+
+void tauri_dumper_free(TauriDumper *dumper);
+
+/* Scans for embedded Tauri assets. Returns NULL on error. */
+TauriDumperAssets *tauri_dumper_scan(const TauriDumper *dumper);
+
+size_t tauri_dumper_assets_count(const TauriDumperAssets *assets);
+
+/* Returns the name of an asset, owned by assets, or NULL if index is out of
+ * range. */
+const char *tauri_dumper_assets_name(const TauriDumperAssets *assets, size_t index);
+
+/* Decompresses an asset and stores its length in len. Release the buffer with
+ * tauri_dumper_bytes_free. Returns NULL on error. */
+uint8_t *tauri_dumper_assets_data(const TauriDumperAssets *assets, size_t index, size_t *len);
+
+void tauri_dumper_assets_free(TauriDumperAssets *assets);
+
+void tauri_dumper_bytes_free(uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TAURI_DUMPER_H */
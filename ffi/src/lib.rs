@@ -0,0 +1,245 @@
+//! C bindings for tauri-dumper.
+//!
+//! Handles returned by this library are owned by the caller and released with
+//! the matching `*_free` function. Functions that fail return null and record
+//! a message that [`tauri_dumper_last_error`] returns. A panic while parsing a
+//! binary or decoding an asset is reported the same way rather than unwinding
+//! into the caller.
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use tauri_dumper::{AssetTable, Dumper};
+
+/// An opened binary.
+pub struct TauriDumper {
+    dumper: Dumper,
+}
+
+/// The assets found by [`tauri_dumper_scan`].
+pub struct TauriDumperAssets {
+    table: AssetTable,
+    names: Vec<CString>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', " "))
+        .expect("interior NUL bytes were replaced");
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+/// Runs `body`, turning a panic into a null result and a last error, since
+/// unwinding out of an `extern "C"` function aborts the host process.
+#[doc(hidden)]
+pub fn catch_panic<T>(body: impl FnOnce() -> *mut T) -> *mut T {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|payload| {
+        set_last_error(format!("internal error: {}", panic_message(&*payload)));
+        ptr::null_mut()
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("panic")
+}
+
+fn open(result: tauri_dumper::Result<Dumper>) -> *mut TauriDumper {
+    match result {
+        Ok(dumper) => Box::into_raw(Box::new(TauriDumper { dumper })),
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the message of the last error on this thread, or null.
+///
+/// The string stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn tauri_dumper_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |e| e.as_ptr()))
+}
+
+/// Opens the executable at `path`.
+///
+/// # Safety
+///
+/// `path` must be a valid NUL-terminated UTF-8 string.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_open(path: *const c_char) -> *mut TauriDumper {
+    if path.is_null() {
+        set_last_error("path is null");
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(path).to_str() {
+        Ok(path) => catch_panic(|| open(Dumper::from_path(path))),
+        Err(_) => {
+            set_last_error("path is not valid UTF-8");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Opens an executable held in memory. The bytes are copied.
+///
+/// # Safety
+///
+/// `data` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_open_bytes(data: *const u8, len: usize) -> *mut TauriDumper {
+    if data.is_null() {
+        set_last_error("data is null");
+        return ptr::null_mut();
+    }
+    let data = std::slice::from_raw_parts(data, len);
+    catch_panic(|| open(Dumper::from_bytes(data)))
+}
+
+/// Releases a handle returned by [`tauri_dumper_open`] or
+/// [`tauri_dumper_open_bytes`]. Null is ignored.
+///
+/// # Safety
+///
+/// `dumper` must be null or a handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_free(dumper: *mut TauriDumper) {
+    if !dumper.is_null() {
+        drop(Box::from_raw(dumper));
+    }
+}
+
+/// Scans for embedded Tauri assets.
+///
+/// # Safety
+///
+/// `dumper` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_scan(dumper: *const TauriDumper) -> *mut TauriDumperAssets {
+    let Some(dumper) = dumper.as_ref() else {
+        set_last_error("dumper is null");
+        return ptr::null_mut();
+    };
+    catch_panic(|| match dumper.dumper.scan() {
+        Ok(table) => {
+            let names = table
+                .assets()
+                .iter()
+                .map(|asset| CString::new(asset.name()))
+                .collect::<Result<Vec<_>, _>>();
+            match names {
+                Ok(names) => Box::into_raw(Box::new(TauriDumperAssets { table, names })),
+                Err(err) => {
+                    set_last_error(format!(
+                        "asset name has a NUL byte at {}",
+                        err.nul_position()
+                    ));
+                    ptr::null_mut()
+                }
+            }
+        }
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    })
+}
+
+/// Returns the number of assets.
+///
+/// # Safety
+///
+/// `assets` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_assets_count(assets: *const TauriDumperAssets) -> usize {
+    assets.as_ref().map_or(0, |assets| assets.table.len())
+}
+
+/// Returns the name of the asset at `index`, or null if it is out of range.
+///
+/// The string is owned by `assets`.
+///
+/// # Safety
+///
+/// `assets` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_assets_name(
+    assets: *const TauriDumperAssets,
+    index: usize,
+) -> *const c_char {
+    assets
+        .as_ref()
+        .and_then(|assets| assets.names.get(index))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Decompresses the asset at `index` and stores its length in `len`.
+///
+/// The returned buffer is released with [`tauri_dumper_bytes_free`].
+///
+/// # Safety
+///
+/// `assets` must be null or a live handle, and `len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_assets_data(
+    assets: *const TauriDumperAssets,
+    index: usize,
+    len: *mut usize,
+) -> *mut u8 {
+    let Some(asset) = assets
+        .as_ref()
+        .and_then(|assets| assets.table.assets().get(index))
+    else {
+        set_last_error(format!("asset index {index} is out of range"));
+        return ptr::null_mut();
+    };
+    if len.is_null() {
+        set_last_error("len is null");
+        return ptr::null_mut();
+    }
+    catch_panic(|| match tauri_dumper::extract::decompress_asset(asset) {
+        Ok(data) => {
+            *len = data.len();
+            Box::into_raw(data.into_boxed_slice()).cast()
+        }
+        Err(err) => {
+            set_last_error(err);
+            ptr::null_mut()
+        }
+    })
+}
+
+/// Releases a handle returned by [`tauri_dumper_scan`]. Null is ignored.
+///
+/// # Safety
+///
+/// `assets` must be null or a handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_assets_free(assets: *mut TauriDumperAssets) {
+    if !assets.is_null() {
+        drop(Box::from_raw(assets));
+    }
+}
+
+/// Releases a buffer returned by [`tauri_dumper_assets_data`]. Null is
+/// ignored.
+///
+/// # Safety
+///
+/// `data` and `len` must be a buffer and length returned together that have
+/// not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn tauri_dumper_bytes_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
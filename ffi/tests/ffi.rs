@@ -0,0 +1,129 @@
+use std::ffi::{CStr, CString};
+use std::io::Write;
+use tauri_dumper_ffi::*;
+
+const BASE: u64 = 0x40_0000;
+const HTML: &[u8] = b"<!DOCTYPE html><html></html>";
+
+fn elf_with_index_html() -> Vec<u8> {
+    elf_with_asset(b"/index.html")
+}
+
+/// Builds an ELF without section headers whose single read-only segment holds
+/// one asset header, its name and its Brotli data.
+fn elf_with_asset(name: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer.write_all(HTML).unwrap();
+    }
+    let header_at = 128;
+    let name_at = header_at + 32;
+    let data_at = name_at + name.len();
+    let mut elf = vec![0; data_at + compressed.len()];
+
+    elf[..8].copy_from_slice(b"\x7fELF\x02\x01\x01\0");
+    elf[16..18].copy_from_slice(&2u16.to_le_bytes());
+    elf[18..20].copy_from_slice(&62u16.to_le_bytes());
+    elf[20..24].copy_from_slice(&1u32.to_le_bytes());
+    elf[32..40].copy_from_slice(&64u64.to_le_bytes());
+    elf[52..54].copy_from_slice(&64u16.to_le_bytes());
+    elf[54..56].copy_from_slice(&56u16.to_le_bytes());
+    elf[56..58].copy_from_slice(&1u16.to_le_bytes());
+
+    let len = elf.len() as u64;
+    let mut segment = Vec::new();
+    segment.extend(1u32.to_le_bytes());
+    segment.extend(4u32.to_le_bytes());
+    for value in [0, BASE, BASE, len, len, 0x1000] {
+        segment.extend(value.to_le_bytes());
+    }
+    elf[64..120].copy_from_slice(&segment);
+
+    let mut header = Vec::new();
+    for value in [
+        BASE + name_at as u64,
+        name.len() as u64,
+        BASE + data_at as u64,
+        compressed.len() as u64,
+    ] {
+        header.extend(value.to_le_bytes());
+    }
+    elf[header_at..name_at].copy_from_slice(&header);
+    elf[name_at..data_at].copy_from_slice(name);
+    elf[data_at..].copy_from_slice(&compressed);
+    elf
+}
+
+#[test]
+fn scans_assets_through_c_api() {
+    let elf = elf_with_index_html();
+    unsafe {
+        let dumper = tauri_dumper_open_bytes(elf.as_ptr(), elf.len());
+        assert!(!dumper.is_null());
+        let assets = tauri_dumper_scan(dumper);
+        assert!(!assets.is_null());
+        assert_eq!(tauri_dumper_assets_count(assets), 1);
+        assert_eq!(
+            CStr::from_ptr(tauri_dumper_assets_name(assets, 0)),
+            c"/index.html"
+        );
+        assert!(tauri_dumper_assets_name(assets, 1).is_null());
+
+        let mut len = 0;
+        let data = tauri_dumper_assets_data(assets, 0, &mut len);
+        assert_eq!(std::slice::from_raw_parts(data, len), HTML);
+        tauri_dumper_bytes_free(data, len);
+
+        tauri_dumper_assets_free(assets);
+        tauri_dumper_free(dumper);
+    }
+}
+
+#[test]
+fn rejects_asset_names_with_nul_bytes() {
+    // A NUL would cut the name short for C callers, so the asset is not
+    // accepted and the scan neither panics nor returns it.
+    let elf = elf_with_asset(b"/index\0.html");
+    unsafe {
+        let dumper = tauri_dumper_open_bytes(elf.as_ptr(), elf.len());
+        assert!(!dumper.is_null());
+        let assets = tauri_dumper_scan(dumper);
+        if assets.is_null() {
+            assert!(!tauri_dumper_last_error().is_null());
+        } else {
+            assert_eq!(tauri_dumper_assets_count(assets), 0);
+            tauri_dumper_assets_free(assets);
+        }
+        tauri_dumper_free(dumper);
+    }
+}
+
+#[test]
+fn reports_errors_through_last_error() {
+    let path = CString::new("/nonexistent/app").unwrap();
+    unsafe {
+        assert!(tauri_dumper_open(path.as_ptr()).is_null());
+        let error = CStr::from_ptr(tauri_dumper_last_error());
+        assert!(error.to_str().unwrap().contains("No such file"));
+
+        let mut len = 0;
+        assert!(tauri_dumper_assets_data(std::ptr::null(), 0, &mut len).is_null());
+        assert_eq!(
+            CStr::from_ptr(tauri_dumper_last_error()),
+            c"asset index 0 is out of range"
+        );
+    }
+}
+
+#[test]
+fn reports_panics_through_last_error() {
+    let result = catch_panic::<u8>(|| panic!("corrupt header"));
+    assert!(result.is_null());
+    let error = unsafe { CStr::from_ptr(tauri_dumper_last_error()) };
+    assert_eq!(error, c"internal error: corrupt header");
+
+    // Calls that do not panic are unaffected.
+    let mut value = 7u8;
+    assert_eq!(catch_panic(|| &mut value as *mut u8), &mut value as *mut u8);
+}
@@ -1,11 +1,15 @@
 use crate::binary::{BinaryMetadata, ScanRange};
-use serde::{Deserialize, Serialize};
+use crate::codec::{default_codec, AssetCodec};
+use crate::mime::mime_type;
+use serde::{Deserialize, Serialize, Serializer};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
 pub const ASSET_HEADER_SIZE: usize = size_of::<AssetHeader>();
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AssetHeader {
     pub name_ptr: u64,
     pub name_len: u64,
@@ -34,25 +38,102 @@ pub struct AssetLocation {
     pub data_size_offset: usize,
     pub original_compressed_size: usize,
     pub scan_range: ScanRange,
+    /// Index of the asset table the header belongs to, in scan order.
+    ///
+    /// Binaries with several `generate_context!` invocations, or plugins that
+    /// embed their own assets, contain more than one header array.
+    #[serde(default)]
+    pub table_index: usize,
+    /// Offset of a SHA-256 of the decoded data stored next to the asset,
+    /// which must be updated along with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash_offset: Option<usize>,
+    /// Width in bytes of the header fields: 8, or 4 in 32-bit binaries.
+    #[serde(
+        default = "default_word_size",
+        skip_serializing_if = "is_default_word_size"
+    )]
+    pub word_size: usize,
+}
+
+fn default_word_size() -> usize {
+    8
+}
+
+fn is_default_word_size(word_size: &usize) -> bool {
+    *word_size == default_word_size()
+}
+
+/// How asset data is stored in the binary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetEncoding {
+    /// Brotli-compressed, as emitted by Tauri.
+    #[default]
+    Brotli,
+    /// Stored as is, as emitted by `rust-embed` without compression.
+    Raw,
+    /// A single gzip member.
+    Gzip,
+    /// A single zstd frame.
+    Zstd,
+    /// A single LZ4 frame.
+    Lz4,
+    /// An encoding handled by a codec registered outside this crate.
+    Custom,
 }
 
 #[derive(Debug, Clone)]
 pub struct Asset {
     id: AssetId,
     name: String,
-    compressed_data: Vec<u8>,
+    compressed_data: Arc<[u8]>,
     decompressed_size: usize,
     location: AssetLocation,
     compressed_sha256: String,
+    alias_of: Option<String>,
+    codec: Arc<dyn AssetCodec>,
+    confidence: u8,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Where an asset was found and how it is stored, without its data.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetInfo {
+    pub name: String,
+    /// File offset of the header, or `rust-embed` record, describing the asset.
+    pub header_offset: usize,
+    /// File offset of the name; for `rust-embed`, of the slice pointing at it.
+    pub name_offset: usize,
+    pub name_len: usize,
+    /// File offset of the stored data.
+    pub data_offset: usize,
+    /// Size of the stored data.
+    pub compressed_size: usize,
+    /// Size of the decoded data, measured when the asset was validated.
+    pub decompressed_size: usize,
+    /// The codec that validated the data.
+    pub encoding: AssetEncoding,
+    pub table_index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    /// See [`Asset::confidence`].
+    #[serde(default)]
+    pub confidence: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetSummary {
     pub name: String,
     pub compressed_size: usize,
     pub decompressed_size: usize,
     pub compressed_sha256: String,
     pub location: AssetLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    pub encoding: AssetEncoding,
+    /// See [`Asset::confidence`].
+    #[serde(default)]
+    pub confidence: u8,
 }
 
 impl Asset {
@@ -66,13 +147,22 @@ impl Asset {
         Self {
             id: AssetId::new(name.clone()),
             name,
-            compressed_data,
+            compressed_data: compressed_data.into(),
             decompressed_size,
             location,
             compressed_sha256,
+            alias_of: None,
+            codec: default_codec(),
+            confidence: 0,
         }
     }
 
+    /// Sets the codec the asset data is stored with.
+    pub fn with_codec(mut self, codec: Arc<dyn AssetCodec>) -> Self {
+        self.codec = codec;
+        self
+    }
+
     pub fn id(&self) -> &AssetId {
         &self.id
     }
@@ -101,10 +191,66 @@ impl Asset {
         &self.compressed_sha256
     }
 
+    pub fn encoding(&self) -> AssetEncoding {
+        self.codec.encoding()
+    }
+
+    pub fn codec(&self) -> &dyn AssetCodec {
+        self.codec.as_ref()
+    }
+
+    pub fn table_index(&self) -> usize {
+        self.location.table_index
+    }
+
+    pub(crate) fn set_table_index(&mut self, table_index: usize) {
+        self.location.table_index = table_index;
+    }
+
+    /// Returns the name of the first asset whose header points at the same
+    /// compressed data, if this asset is an alias.
+    ///
+    /// Tauri deduplicates identical files, so several names can share a blob.
+    pub fn alias_of(&self) -> Option<&str> {
+        self.alias_of.as_deref()
+    }
+
     pub fn safe_relative_path(&self) -> Option<PathBuf> {
         safe_relative_path(&self.name)
     }
 
+    /// How likely the asset is a real embedded file rather than bytes that
+    /// happen to parse as a header, from 0 to 100. Set when the asset is
+    /// added to an [`AssetTable`]:
+    ///
+    /// - 40 when a compressing codec decoded the data or its stored SHA-256
+    ///   matched, 25 for a custom codec and 10 for raw data, which any bytes
+    ///   pass;
+    /// - 10 each for a name that is printable ASCII, a path that can be
+    ///   exported and a known file extension;
+    /// - 15 for other headers in the same table and 15 when the header, name
+    ///   and data do not overlap.
+    pub fn confidence(&self) -> u8 {
+        self.confidence
+    }
+
+    /// Returns the asset's offsets, sizes and codec.
+    pub fn info(&self) -> AssetInfo {
+        AssetInfo {
+            name: self.name.clone(),
+            header_offset: self.location.header_offset,
+            name_offset: self.location.name_offset,
+            name_len: self.name.len(),
+            data_offset: self.location.data_offset,
+            compressed_size: self.compressed_size(),
+            decompressed_size: self.decompressed_size,
+            encoding: self.encoding(),
+            table_index: self.table_index(),
+            alias_of: self.alias_of.clone(),
+            confidence: self.confidence,
+        }
+    }
+
     pub fn summary(&self) -> AssetSummary {
         AssetSummary {
             name: self.name.clone(),
@@ -112,6 +258,9 @@ impl Asset {
             decompressed_size: self.decompressed_size,
             compressed_sha256: self.compressed_sha256.clone(),
             location: self.location.clone(),
+            alias_of: self.alias_of.clone(),
+            encoding: self.encoding(),
+            confidence: self.confidence,
         }
     }
 }
@@ -120,20 +269,46 @@ impl Asset {
 pub struct AssetTable {
     metadata: BinaryMetadata,
     assets: Vec<Asset>,
+    conflicts: Vec<AssetConflict>,
+}
+
+/// Headers of one table that resolve to the same asset name, as in a
+/// corrupt table. Only one of them is kept, so exports do not overwrite
+/// one with another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetConflict {
+    pub name: String,
+    pub table_index: usize,
+    /// The asset kept: the one with the highest [`Asset::confidence`], or
+    /// the first found of equally confident ones.
+    pub kept: AssetInfo,
+    pub dropped: Vec<AssetInfo>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetTableSummary {
     pub binary: BinaryMetadata,
     pub asset_count: usize,
+    pub table_count: usize,
     pub total_compressed_size: usize,
     pub total_decompressed_size: usize,
     pub assets: Vec<AssetSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<AssetConflict>,
 }
 
 impl AssetTable {
-    pub fn new(metadata: BinaryMetadata, assets: Vec<Asset>) -> Self {
-        Self { metadata, assets }
+    /// Creates a table of `assets`, keeping one asset per name and table.
+    /// See [`conflicts`](Self::conflicts).
+    pub fn new(metadata: BinaryMetadata, mut assets: Vec<Asset>) -> Self {
+        score_confidence(&mut assets);
+        let conflicts = drop_duplicates(&mut assets);
+        link_aliases(&mut assets);
+        Self {
+            metadata,
+            assets,
+            conflicts,
+        }
     }
 
     pub fn metadata(&self) -> &BinaryMetadata {
@@ -152,35 +327,327 @@ impl AssetTable {
         self.assets.is_empty()
     }
 
+    /// The names that several headers of one table resolved to, with the
+    /// asset kept and the ones dropped.
+    pub fn conflicts(&self) -> &[AssetConflict] {
+        &self.conflicts
+    }
+
+    /// Returns the number of distinct asset tables found in the binary.
+    pub fn table_count(&self) -> usize {
+        self.assets
+            .iter()
+            .map(Asset::table_index)
+            .max()
+            .map_or(0, |index| index + 1)
+    }
+
     pub fn find(&self, name: &str) -> Option<&Asset> {
         self.assets.iter().find(|asset| asset.name() == name)
     }
 
+    /// The assets whose name starts with `prefix`, such as `/assets/`.
+    pub fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a Asset> + 'a {
+        self.assets
+            .iter()
+            .filter(move |asset| asset.name().starts_with(prefix))
+    }
+
+    /// Finds the asset for `path` after [`normalize_asset_path`], ignoring
+    /// case if no name matches exactly.
+    pub fn find_normalized(&self, path: &str) -> Option<&Asset> {
+        let path = normalize_asset_path(path);
+        self.find(&path).or_else(|| {
+            let path = path.to_lowercase();
+            self.assets
+                .iter()
+                .find(|asset| asset.name().to_lowercase() == path)
+        })
+    }
+
+    /// Finds the asset Tauri would serve for a request to `path`: the
+    /// normalized path itself, then `{path}.html`, then `{path}/index.html`
+    /// and finally `/index.html`, which single-page apps fall back to.
+    pub fn resolve(&self, path: &str) -> Option<&Asset> {
+        let path = normalize_asset_path(path);
+        if path == "/" {
+            return self.find("/index.html");
+        }
+        self.find(&path)
+            .or_else(|| self.find(&format!("{path}.html")))
+            .or_else(|| self.find(&format!("{path}/index.html")))
+            .or_else(|| self.find("/index.html"))
+    }
+
+    /// Drops assets whose [`Asset::confidence`] is below `min`.
+    pub fn retain_confident(&mut self, min: u8) {
+        let before = self.assets.len();
+        self.assets.retain(|asset| asset.confidence >= min);
+        if self.assets.len() < before {
+            log::info!(
+                "dropped {} assets below confidence {min}",
+                before - self.assets.len()
+            );
+        }
+    }
+
     pub fn summary(&self) -> AssetTableSummary {
         AssetTableSummary {
             binary: self.metadata.clone(),
             asset_count: self.len(),
+            table_count: self.table_count(),
             total_compressed_size: self.assets.iter().map(Asset::compressed_size).sum(),
             total_decompressed_size: self.assets.iter().map(Asset::decompressed_size).sum(),
             assets: self.assets.iter().map(Asset::summary).collect(),
+            conflicts: self.conflicts.clone(),
+        }
+    }
+}
+
+/// Serializes as [`AssetInfo`]; the data is left out.
+impl Serialize for Asset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.info().serialize(serializer)
+    }
+}
+
+/// Serializes as [`AssetTableSummary`].
+impl Serialize for AssetTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.summary().serialize(serializer)
+    }
+}
+
+/// Marks assets sharing a blob, the same data offset and size, as aliases
+/// of the first one and lets them share its compressed bytes.
+fn link_aliases(assets: &mut [Asset]) {
+    let mut first_by_blob: HashMap<(usize, usize), usize> = HashMap::new();
+    for index in 0..assets.len() {
+        let location = &assets[index].location;
+        let blob = (location.data_offset, location.original_compressed_size);
+        match first_by_blob.get(&blob) {
+            Some(&first) if first != index => {
+                let (name, data) = (
+                    assets[first].name.clone(),
+                    Arc::clone(&assets[first].compressed_data),
+                );
+                let asset = &mut assets[index];
+                asset.alias_of = Some(name);
+                asset.compressed_data = data;
+            }
+            _ => {
+                first_by_blob.insert(blob, index);
+            }
+        }
+    }
+}
+
+/// Keeps the most confident of the assets of a table that share a name,
+/// and the first of equally confident ones. The same header found twice is
+/// dropped without a conflict.
+fn drop_duplicates(assets: &mut Vec<Asset>) -> Vec<AssetConflict> {
+    let mut keep = vec![true; assets.len()];
+    let mut groups = HashMap::<(usize, &str), Vec<usize>>::new();
+    let mut order = Vec::new();
+    for (index, asset) in assets.iter().enumerate() {
+        let key = (asset.table_index(), asset.name());
+        let group = groups.entry(key).or_default();
+        if group.is_empty() {
+            order.push(key);
+        }
+        let header = asset.location.header_offset;
+        if group
+            .iter()
+            .any(|&other| assets[other].location.header_offset == header)
+        {
+            keep[index] = false;
+        } else {
+            group.push(index);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for key in order {
+        let group = &groups[&key];
+        let Some(kept) = group.iter().copied().reduce(|best, index| {
+            if assets[index].confidence > assets[best].confidence {
+                index
+            } else {
+                best
+            }
+        }) else {
+            continue;
+        };
+        if group.len() < 2 {
+            continue;
+        }
+        let dropped = group
+            .iter()
+            .filter(|&&index| index != kept)
+            .map(|&index| {
+                keep[index] = false;
+                assets[index].info()
+            })
+            .collect::<Vec<_>>();
+        log::warn!(
+            "{} headers of table {} are named {}; keeping the one at {:#x}",
+            group.len(),
+            key.0,
+            key.1,
+            assets[kept].location.header_offset
+        );
+        conflicts.push(AssetConflict {
+            name: key.1.to_string(),
+            table_index: key.0,
+            kept: assets[kept].info(),
+            dropped,
+        });
+    }
+
+    let mut keep = keep.into_iter();
+    assets.retain(|_| keep.next().unwrap_or(true));
+    conflicts
+}
+
+/// Sets [`Asset::confidence`] for every asset.
+fn score_confidence(assets: &mut [Asset]) {
+    let mut table_sizes = HashMap::<usize, usize>::new();
+    for asset in assets.iter() {
+        *table_sizes.entry(asset.table_index()).or_default() += 1;
+    }
+    for asset in assets {
+        let mut score = if asset.location.content_hash_offset.is_some() {
+            40
+        } else {
+            match asset.encoding() {
+                AssetEncoding::Brotli
+                | AssetEncoding::Gzip
+                | AssetEncoding::Zstd
+                | AssetEncoding::Lz4 => 40,
+                AssetEncoding::Custom => 25,
+                AssetEncoding::Raw => 10,
+            }
+        };
+        if asset
+            .name
+            .bytes()
+            .all(|byte| byte.is_ascii_graphic() || byte == b' ')
+        {
+            score += 10;
+        }
+        if asset.safe_relative_path().is_some() {
+            score += 10;
+        }
+        if mime_type(&asset.name) != "application/octet-stream" {
+            score += 10;
+        }
+        if table_sizes[&asset.table_index()] > 1 {
+            score += 15;
+        }
+        let location = &asset.location;
+        let spans = [
+            (location.header_offset, 4 * location.word_size),
+            (location.name_offset, asset.name.len()),
+            (location.data_offset, asset.compressed_size()),
+        ];
+        let overlaps =
+            |(a, a_len): (usize, usize), (b, b_len): (usize, usize)| a < b + b_len && b < a + a_len;
+        if !overlaps(spans[0], spans[1])
+            && !overlaps(spans[0], spans[2])
+            && !overlaps(spans[1], spans[2])
+        {
+            score += 15;
+        }
+        asset.confidence = score;
+    }
+}
+
+/// Where the fields of an asset header sit within it.
+///
+/// Tauri versions differ in field order and in extra per-asset fields stored
+/// next to the name and data, so the scanner tries several known layouts.
+/// The layouts below are for 64-bit binaries; 32-bit binaries use their
+/// [`narrow`](Self::narrow) form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderLayout {
+    pub name: &'static str,
+    /// Width in bytes of each field.
+    pub word: usize,
+    /// Distance from one header to the next in a table.
+    pub size: usize,
+    pub name_ptr: usize,
+    pub name_len: usize,
+    pub data_ptr: usize,
+    pub data_size: usize,
+}
+
+impl HeaderLayout {
+    /// Each slice as pointer then length, as Tauri 2 emits them.
+    pub const POINTER_FIRST: Self = Self {
+        name: "pointer-first",
+        word: 8,
+        size: ASSET_HEADER_SIZE,
+        name_ptr: 0,
+        name_len: 8,
+        data_ptr: 16,
+        data_size: 24,
+    };
+    /// Name and data followed by a slice of CSP hashes for the asset, as
+    /// emitted by versions that precompute hashes of inline scripts.
+    pub const WITH_CSP_HASHES: Self = Self {
+        name: "with-csp-hashes",
+        size: ASSET_HEADER_SIZE + 16,
+        ..Self::POINTER_FIRST
+    };
+
+    /// Returns this layout with 4-byte pointers and lengths, as 32-bit
+    /// targets such as i686 Windows and armv7 Linux emit it. A 32-byte
+    /// header becomes 16 bytes.
+    pub const fn narrow(self) -> Self {
+        if self.word != 8 {
+            return self;
+        }
+        Self {
+            word: 4,
+            size: self.size / 2,
+            name_ptr: self.name_ptr / 2,
+            name_len: self.name_len / 2,
+            data_ptr: self.data_ptr / 2,
+            data_size: self.data_size / 2,
+            ..self
         }
     }
 }
 
-pub(crate) fn read_header(data: &[u8], offset: usize) -> Option<AssetHeader> {
+/// Reads the header at `offset` field by field, as little-endian words of
+/// `layout.word` bytes, so it makes no assumption about the alignment of
+/// `data` or the byte order of the host. Returns `None` if the header runs
+/// past the end of `data`.
+pub(crate) fn read_header(
+    data: &[u8],
+    offset: usize,
+    layout: &HeaderLayout,
+) -> Option<AssetHeader> {
     Some(AssetHeader {
-        name_ptr: read_u64(data, offset)?,
-        name_len: read_u64(data, offset + 8)?,
-        data_ptr: read_u64(data, offset + 16)?,
-        data_size: read_u64(data, offset + 24)?,
+        name_ptr: read_word(data, offset + layout.name_ptr, layout.word)?,
+        name_len: read_word(data, offset + layout.name_len, layout.word)?,
+        data_ptr: read_word(data, offset + layout.data_ptr, layout.word)?,
+        data_size: read_word(data, offset + layout.data_size, layout.word)?,
     })
 }
 
-pub(crate) fn write_u64(data: &mut [u8], offset: usize, value: u64) -> bool {
-    let Some(bytes) = data.get_mut(offset..offset + 8) else {
+/// Writes `value` as a little-endian integer of `width` bytes, 4 or 8.
+/// Returns false if it is out of bounds or does not fit.
+#[cfg(feature = "fs")]
+pub(crate) fn write_word(data: &mut [u8], offset: usize, value: u64, width: usize) -> bool {
+    let bytes = value.to_le_bytes();
+    if bytes[width.min(8)..].iter().any(|&byte| byte != 0) {
+        return false;
+    }
+    let Some(target) = data.get_mut(offset..offset + width) else {
         return false;
     };
-    bytes.copy_from_slice(&value.to_le_bytes());
+    target.copy_from_slice(&bytes[..width]);
     true
 }
 
@@ -190,6 +657,43 @@ pub(crate) fn sha256_hex(data: &[u8]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Normalizes a request path the way Tauri's asset resolver does before it
+/// looks the path up: the query and fragment are dropped, percent-escapes
+/// decoded, backslashes turned into slashes, a leading `/` added and a
+/// trailing one removed.
+pub fn normalize_asset_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let path = percent_decode(path).replace('\\', "/");
+    let path = path.trim_end_matches('/');
+    match path.strip_prefix('/') {
+        Some(_) => path.to_string(),
+        None => format!("/{path}"),
+    }
+}
+
+pub(crate) fn percent_decode(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| bytes.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
 pub fn safe_relative_path(asset_name: &str) -> Option<PathBuf> {
     let stripped = asset_name.strip_prefix('/').unwrap_or(asset_name);
     if stripped.is_empty() {
@@ -217,7 +721,9 @@ pub fn safe_relative_path(asset_name: &str) -> Option<PathBuf> {
     }
 }
 
-fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
-    let bytes = data.get(offset..offset + 8)?;
-    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+fn read_word(data: &[u8], offset: usize, width: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(width)?)?;
+    let mut word = [0; 8];
+    word.get_mut(..width)?.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(word))
 }
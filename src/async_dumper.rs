@@ -0,0 +1,108 @@
+//! Async counterpart of [`Dumper`](crate::Dumper) for use inside tokio
+//! services.
+//!
+//! Scanning a large binary can take minutes of CPU time. Files are read with
+//! `tokio::fs`, and parsing, scanning, decompression and export run on tokio's
+//! blocking thread pool, so runtime worker threads stay free.
+
+use crate::asset::{Asset, AssetTable};
+use crate::codec::CodecRegistry;
+use crate::error::Result;
+use crate::export::{ExportOptions, ExportSummary};
+use crate::extract::{decompress_asset, AssetScanner, ScanOptions};
+use crate::image::BinaryImage;
+use crate::roundtrip::{self, VerifyReport};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct AsyncDumper {
+    image: Arc<BinaryImage>,
+    options: ScanOptions,
+}
+
+impl AsyncDumper {
+    pub async fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let data = tokio::fs::read(&path).await?;
+        let image = spawn_blocking(move || BinaryImage::from_data(data, Some(path))).await?;
+        Ok(Self::from_image(image))
+    }
+
+    pub async fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let image = spawn_blocking(move || BinaryImage::from_data(data, None)).await?;
+        Ok(Self::from_image(image))
+    }
+
+    /// Wraps an image parsed elsewhere, for example with a custom parser.
+    pub fn from_image(image: BinaryImage) -> Self {
+        Self {
+            image: Arc::new(image),
+            options: ScanOptions::default(),
+        }
+    }
+
+    /// Validates asset data with `codecs` instead of Brotli alone.
+    pub fn with_codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.options.codecs = codecs;
+        self
+    }
+
+    /// Scans with `options` instead of the defaults.
+    pub fn with_options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub async fn scan(&self) -> Result<AssetTable> {
+        let image = Arc::clone(&self.image);
+        let options = self.options.clone();
+        spawn_blocking(move || AssetScanner::scan_with_options(&image, &options)).await
+    }
+
+    pub async fn decompress_asset(&self, asset: &Asset) -> Result<Vec<u8>> {
+        let asset = asset.clone();
+        spawn_blocking(move || decompress_asset(&asset)).await
+    }
+
+    /// Exports `table` like [`AssetTable::export`].
+    pub async fn export(
+        &self,
+        table: &AssetTable,
+        options: &ExportOptions,
+    ) -> Result<ExportSummary> {
+        let table = table.clone();
+        let options = options.clone();
+        spawn_blocking(move || table.export(&options)).await
+    }
+
+    /// Compares the embedded assets with `dist` like
+    /// [`Dumper::verify_roundtrip`](crate::Dumper::verify_roundtrip).
+    pub async fn verify_roundtrip(&self, dist: &Path) -> Result<VerifyReport> {
+        let image = Arc::clone(&self.image);
+        let options = self.options.clone();
+        let dist = dist.to_path_buf();
+        spawn_blocking(move || {
+            roundtrip::verify(&AssetScanner::scan_with_options(&image, &options)?, &dist)
+        })
+        .await
+    }
+
+    pub fn image(&self) -> &BinaryImage {
+        &self.image
+    }
+}
+
+/// Runs `f` on the blocking pool, re-raising its panic if it panics.
+async fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(error) => match error.try_into_panic() {
+            Ok(panic) => std::panic::resume_unwind(panic),
+            // Blocking tasks are only cancelled when the runtime shuts down.
+            Err(error) => Err(std::io::Error::other(error).into()),
+        },
+    }
+}
@@ -0,0 +1,881 @@
+//! Security findings for the `audit` command.
+//!
+//! An audit reads the decoded text assets and the binary's own strings,
+//! where Tauri keeps its compiled config and the commands its capabilities
+//! allow, and reports what makes the app easier to attack: a weak or
+//! missing content security policy, permissions that reach the shell or the
+//! file system, scripts loaded from other origins, cleartext endpoints,
+//! `eval` in the bundles, credentials shipped with the app and assets as
+//! random as encrypted data.
+
+use crate::asset::AssetTable;
+use crate::entropy::AssetEntropy;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::frontend::{self, FrontendReport};
+use crate::html::{attribute, tags};
+use crate::image::BinaryImage;
+use crate::mime::is_text_asset;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::fmt;
+
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "html", "htm"];
+const HTML_EXTENSIONS: &[&str] = &["html", "htm"];
+
+/// Longest policy read from the binary's strings.
+const MAX_POLICY_LEN: usize = 4096;
+/// Longest evidence snippet kept from bundled code.
+const MAX_SNIPPET_LEN: usize = 80;
+/// Characters of a secret kept in its finding; the rest is masked.
+const SECRET_PREFIX_LEN: usize = 8;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Hosts of URLs that are namespaces or license links rather than
+/// endpoints the app talks to.
+const IGNORED_HOSTS: &[&str] = &[
+    "www.w3.org",
+    "w3.org",
+    "ns.adobe.com",
+    "purl.org",
+    "www.apache.org",
+    "opensource.org",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// A missing or permissive content security policy.
+    Csp,
+    /// A Tauri permission that reaches the shell or the file system.
+    Capability,
+    /// A script loaded from another origin.
+    RemoteScript,
+    /// An `http://` URL.
+    InsecureEndpoint,
+    /// `eval` or `new Function` in a bundle.
+    Eval,
+    /// A credential or private key.
+    Secret,
+    /// An asset as random as encrypted data, although its type is not.
+    HighEntropy,
+}
+
+impl Category {
+    pub const ALL: [Self; 7] = [
+        Self::Csp,
+        Self::Capability,
+        Self::RemoteScript,
+        Self::InsecureEndpoint,
+        Self::Eval,
+        Self::Secret,
+        Self::HighEntropy,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Csp => "csp",
+            Self::Capability => "capability",
+            Self::RemoteScript => "remote_script",
+            Self::InsecureEndpoint => "insecure_endpoint",
+            Self::Eval => "eval",
+            Self::Secret => "secret",
+            Self::HighEntropy => "high_entropy",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Csp => "Missing or permissive content security policy",
+            Self::Capability => "Permission that reaches the shell or the file system",
+            Self::RemoteScript => "Script loaded from another origin",
+            Self::InsecureEndpoint => "Cleartext HTTP endpoint",
+            Self::Eval => "Dynamic code evaluation",
+            Self::Secret => "Embedded credential or private key",
+            Self::HighEntropy => "Asset as random as encrypted data",
+        }
+    }
+}
+
+/// Where the evidence for a finding is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    /// The asset the evidence is in, or `None` for the binary itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+    /// Offset of the evidence in the decoded asset, or in the binary.
+    pub offset: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = self.asset.as_deref().unwrap_or("binary");
+        write!(f, "{source}+{:#x}", self.offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub category: Category,
+    pub message: String,
+    /// The text that raised the finding, with secrets masked.
+    pub evidence: String,
+    /// `None` for findings about something missing, such as the policy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<Location>,
+}
+
+/// The findings of [`audit`], with the frontend stack for context.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Findings, most severe first.
+    pub findings: Vec<Finding>,
+    pub frontend: FrontendReport,
+}
+
+impl AuditReport {
+    /// Counts the findings of `severity`.
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+
+    /// Converts the findings to a SARIF 2.1.0 log, the format code scanning
+    /// services import.
+    ///
+    /// Findings in an asset point at its path without the leading `/`, and
+    /// those in the binary at `artifact`, usually the path it was read from.
+    /// Offsets become byte regions. Severities map to the `error`,
+    /// `warning` and `note` levels.
+    pub fn to_sarif(&self, artifact: &str) -> Value {
+        let rules = Category::ALL
+            .iter()
+            .map(|category| {
+                json!({
+                    "id": category.as_str(),
+                    "shortDescription": { "text": category.description() },
+                    "properties": { "tags": ["security"] },
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let mut text = finding.message.clone();
+                if !finding.evidence.is_empty() {
+                    text = format!("{text}: {}", finding.evidence);
+                }
+                let mut result = json!({
+                    "ruleId": finding.category.as_str(),
+                    "ruleIndex": Category::ALL
+                        .iter()
+                        .position(|category| *category == finding.category),
+                    "level": match finding.severity {
+                        Severity::High => "error",
+                        Severity::Medium => "warning",
+                        Severity::Low => "note",
+                    },
+                    "message": { "text": text },
+                    "properties": { "severity": finding.severity.as_str() },
+                });
+                if let Some(location) = &finding.location {
+                    let uri = match &location.asset {
+                        Some(asset) => asset.trim_start_matches('/'),
+                        None => artifact,
+                    };
+                    result["locations"] = json!([{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri },
+                            "region": { "byteOffset": location.offset },
+                        },
+                    }]);
+                }
+                result
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+}
+
+/// Audits the assets of `table` and the strings of the binary they were
+/// scanned from.
+pub fn audit(image: &BinaryImage, table: &AssetTable) -> Result<AuditReport> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() {
+            continue;
+        }
+        assets.push((asset.name().to_string(), decompress_asset(asset)?));
+    }
+    Ok(audit_in(
+        image.data(),
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`audit`], for a binary's bytes and its decoded assets given as
+/// name and content.
+pub fn audit_in<'a>(
+    binary: &[u8],
+    assets: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> AuditReport {
+    let assets = assets.into_iter().collect::<Vec<_>>();
+    let mut findings = Findings::default();
+
+    let mut policies = Vec::new();
+    for &(name, data) in &assets {
+        if has_extension(name, HTML_EXTENSIONS) {
+            for (offset, policy) in meta_policies(data) {
+                policies.push((policy, at(Some(name), offset)));
+            }
+        }
+    }
+    for (offset, policy) in binary_policies(binary) {
+        policies.push((policy, at(None, offset)));
+    }
+    check_policies(&policies, &mut findings);
+
+    check_permissions(binary, &mut findings);
+    for &(name, data) in &assets {
+        if has_extension(name, HTML_EXTENSIONS) {
+            check_remote_scripts(name, data, &mut findings);
+        }
+    }
+    for &(name, data) in &assets {
+        if is_text_asset(name) {
+            check_endpoints(Some(name), data, &mut findings);
+        }
+    }
+    check_endpoints(None, binary, &mut findings);
+    for &(name, data) in &assets {
+        if has_extension(name, SCRIPT_EXTENSIONS) {
+            check_eval(name, data, &mut findings);
+        }
+    }
+    for &(name, data) in &assets {
+        if is_text_asset(name) {
+            check_secrets(Some(name), data, &mut findings);
+        }
+    }
+    check_secrets(None, binary, &mut findings);
+    for &(name, data) in &assets {
+        check_entropy(name, data, &mut findings);
+    }
+
+    let mut findings = findings.list;
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+    AuditReport {
+        findings,
+        frontend: frontend::detect_in(assets),
+    }
+}
+
+/// Findings kept once per category and key, in the order they were found.
+#[derive(Default)]
+struct Findings {
+    list: Vec<Finding>,
+    seen: BTreeSet<(Category, String)>,
+}
+
+impl Findings {
+    fn push(&mut self, key: &str, finding: Finding) {
+        if self.seen.insert((finding.category, key.to_string())) {
+            self.list.push(finding);
+        }
+    }
+}
+
+fn at(asset: Option<&str>, offset: usize) -> Option<Location> {
+    Some(Location {
+        asset: asset.map(str::to_string),
+        offset,
+    })
+}
+
+/// Reads the policies of `<meta http-equiv="Content-Security-Policy">`
+/// tags, which Tauri injects into the HTML it embeds.
+fn meta_policies(html: &[u8]) -> Vec<(usize, String)> {
+    let lower = html.to_ascii_lowercase();
+    let mut policies = Vec::new();
+    for (start, end) in tags(&lower, b"<meta") {
+        let tag = &lower[start..end];
+        // Report-only policies are not enforced.
+        if find(tag, b"content-security-policy").is_none() || find(tag, b"report-only").is_some() {
+            continue;
+        }
+        if let Some((offset, policy)) = attribute(&html[start..end], tag, b"content") {
+            policies.push((start + offset, policy));
+        }
+    }
+    policies
+}
+
+/// Reads policies from the binary's strings, where Tauri keeps the `csp`
+/// of its config.
+fn binary_policies(data: &[u8]) -> Vec<(usize, String)> {
+    policy_spans(data)
+        .into_iter()
+        .map(|(start, end)| {
+            (
+                start,
+                String::from_utf8_lossy(&data[start..end]).into_owned(),
+            )
+        })
+        .collect()
+}
+
+/// Finds the policies in the binary's strings. A policy starts with a
+/// script or default directive followed by a source and runs to the end of
+/// the printable text, without its trailing spaces.
+pub(crate) fn policy_spans(data: &[u8]) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    for directive in [&b"default-src "[..], b"script-src "] {
+        let mut search = 0;
+        while let Some(position) = find(&data[search..], directive) {
+            let start = search + position;
+            search = start + directive.len();
+            let length = data[start..]
+                .iter()
+                .take(MAX_POLICY_LEN)
+                .take_while(|&&byte| matches!(byte, 0x20..=0x7e) && !matches!(byte, b'"' | b'`'))
+                .count();
+            let sourced = data
+                .get(search)
+                .is_some_and(|&byte| byte != b';' && byte.is_ascii_graphic());
+            if sourced {
+                found.push((start, start + length));
+            }
+        }
+    }
+    // A policy starting with `script-src` may have a `default-src` after it.
+    found.sort();
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in found {
+        if spans.last().is_some_and(|&(_, covered)| start < covered) {
+            continue;
+        }
+        let end = start + data[start..end].trim_ascii_end().len();
+        spans.push((start, end));
+    }
+    spans
+}
+
+fn check_policies(policies: &[(String, Option<Location>)], findings: &mut Findings) {
+    if policies.is_empty() {
+        findings.push(
+            "",
+            Finding {
+                severity: Severity::Medium,
+                category: Category::Csp,
+                message: "no content security policy found".to_string(),
+                evidence: String::new(),
+                location: None,
+            },
+        );
+    }
+    for (policy, location) in policies {
+        let mut found = |severity, message: String| {
+            findings.push(
+                &format!("{policy}\0{message}"),
+                Finding {
+                    severity,
+                    category: Category::Csp,
+                    message,
+                    evidence: policy.clone(),
+                    location: location.clone(),
+                },
+            );
+        };
+        let directives = policy
+            .split(';')
+            .filter_map(|directive| {
+                let mut parts = directive.split_ascii_whitespace();
+                let name = parts.next()?.to_ascii_lowercase();
+                let sources = parts.map(str::to_ascii_lowercase).collect::<Vec<_>>();
+                Some((name, sources))
+            })
+            .collect::<Vec<_>>();
+        let scripts = ["script-src", "default-src"]
+            .iter()
+            .find_map(|wanted| directives.iter().find(|(name, _)| name == wanted));
+        let Some((directive, sources)) = scripts else {
+            found(
+                Severity::Medium,
+                "policy has no script-src or default-src, so scripts are not restricted"
+                    .to_string(),
+            );
+            continue;
+        };
+        let has = |source: &str| sources.iter().any(|known| known == source);
+        if has("'unsafe-eval'") {
+            found(
+                Severity::High,
+                format!("{directive} allows eval through 'unsafe-eval'"),
+            );
+        }
+        // Browsers ignore 'unsafe-inline' when a nonce or hash is listed.
+        let pinned = sources
+            .iter()
+            .any(|source| source.starts_with("'nonce-") || source.starts_with("'sha"));
+        if has("'unsafe-inline'") && !pinned {
+            found(
+                Severity::Medium,
+                format!("{directive} allows inline scripts through 'unsafe-inline'"),
+            );
+        }
+        for wildcard in ["*", "http:", "https:", "data:"] {
+            if has(wildcard) {
+                found(
+                    Severity::Medium,
+                    format!("{directive} allows scripts from any `{wildcard}` source"),
+                );
+            }
+        }
+    }
+}
+
+struct Permission {
+    /// Permission identifiers, and the `plugin:<name>|<command>` keys of
+    /// the commands they allow, which release builds keep instead.
+    markers: &'static [&'static str],
+    severity: Severity,
+    message: &'static str,
+}
+
+const PERMISSIONS: &[Permission] = &[
+    Permission {
+        markers: &["plugin:shell|execute", "shell:allow-execute"],
+        severity: Severity::High,
+        message: "the webview may run shell commands",
+    },
+    Permission {
+        markers: &["plugin:shell|spawn", "shell:allow-spawn"],
+        severity: Severity::High,
+        message: "the webview may spawn processes",
+    },
+    Permission {
+        markers: &["plugin:shell|stdin_write", "shell:allow-stdin-write"],
+        severity: Severity::Medium,
+        message: "the webview may write to the input of spawned processes",
+    },
+    Permission {
+        markers: &["fs:write-all", "fs:allow-home-write-recursive"],
+        severity: Severity::High,
+        message: "the webview may write anywhere in its file system scope",
+    },
+    Permission {
+        markers: &["fs:scope-home-recursive"],
+        severity: Severity::Medium,
+        message: "the file system scope covers the whole home directory",
+    },
+    Permission {
+        markers: &["plugin:fs|remove", "fs:allow-remove"],
+        severity: Severity::Medium,
+        message: "the webview may delete files",
+    },
+    Permission {
+        markers: &["plugin:fs|write_file", "fs:allow-write-file"],
+        severity: Severity::Medium,
+        message: "the webview may write files",
+    },
+    Permission {
+        markers: &["plugin:opener|open_path", "opener:allow-open-path"],
+        severity: Severity::Low,
+        message: "the webview may open files with their default application",
+    },
+];
+
+fn check_permissions(binary: &[u8], findings: &mut Findings) {
+    for permission in PERMISSIONS {
+        let found = permission.markers.iter().find_map(|marker| {
+            find_all(binary, marker.as_bytes())
+                .find(|&offset| {
+                    let end = offset + marker.len();
+                    !binary.get(end).copied().is_some_and(is_identifier_byte)
+                })
+                .map(|offset| (marker, offset))
+        });
+        if let Some((marker, offset)) = found {
+            findings.push(
+                permission.message,
+                Finding {
+                    severity: permission.severity,
+                    category: Category::Capability,
+                    message: permission.message.to_string(),
+                    evidence: marker.to_string(),
+                    location: at(None, offset),
+                },
+            );
+        }
+    }
+}
+
+/// Reports `<script src>` tags loading from another origin; those scripts
+/// run with the app's IPC access.
+fn check_remote_scripts(name: &str, html: &[u8], findings: &mut Findings) {
+    let lower = html.to_ascii_lowercase();
+    for (start, end) in tags(&lower, b"<script") {
+        let Some((offset, src)) = attribute(&html[start..end], &lower[start..end], b"src") else {
+            continue;
+        };
+        let cleartext = src.starts_with("http://");
+        if !cleartext && !src.starts_with("https://") && !src.starts_with("//") {
+            continue;
+        }
+        let origin = src
+            .split_once("//")
+            .map_or("", |(_, rest)| rest)
+            .split(['/', '?', '#'])
+            .next()
+            .unwrap_or_default();
+        let (severity, message) = if cleartext {
+            (
+                Severity::High,
+                format!("script loaded over cleartext http from {origin}"),
+            )
+        } else {
+            (Severity::Medium, format!("script loaded from {origin}"))
+        };
+        findings.push(
+            &src,
+            Finding {
+                severity,
+                category: Category::RemoteScript,
+                message,
+                evidence: src.clone(),
+                location: at(Some(name), start + offset),
+            },
+        );
+    }
+}
+
+fn check_endpoints(asset: Option<&str>, data: &[u8], findings: &mut Findings) {
+    for offset in find_all(data, b"http://") {
+        let length = data[offset..]
+            .iter()
+            .take_while(|&&byte| is_url_byte(byte))
+            .count();
+        let url = String::from_utf8_lossy(&data[offset..offset + length]);
+        let url = url.trim_end_matches(['.', ',', ';', ':', ')', ']', '\'', '!', '?']);
+        let rest = &url["http://".len()..];
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = authority
+            .rsplit('@')
+            .next()
+            .unwrap_or_default()
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let plausible = host.contains('.') && !host.starts_with('.') && !host.contains(['{', '}']);
+        let local = host == "localhost"
+            || host.ends_with(".localhost")
+            || host.starts_with("127.")
+            || host == "0.0.0.0";
+        let license = rest.to_ascii_lowercase().contains("license");
+        if !plausible || local || license || IGNORED_HOSTS.contains(&host.as_str()) {
+            continue;
+        }
+        findings.push(
+            url,
+            Finding {
+                severity: Severity::Low,
+                category: Category::InsecureEndpoint,
+                message: format!("cleartext http endpoint {host}"),
+                evidence: url.to_string(),
+                location: at(asset, offset),
+            },
+        );
+    }
+}
+
+/// Reports each bundle calling `eval` or `new Function`, with the number
+/// of calls and the first one as evidence.
+fn check_eval(name: &str, data: &[u8], findings: &mut Findings) {
+    for (call, needle) in [("eval", &b"eval("[..]), ("new Function", b"new Function(")] {
+        let calls = find_all(data, needle)
+            .filter(|&offset| offset == 0 || !is_call_prefix(data[offset - 1]))
+            .collect::<Vec<_>>();
+        let Some(&first) = calls.first() else {
+            continue;
+        };
+        let times = if calls.len() == 1 { "time" } else { "times" };
+        findings.push(
+            &format!("{name}\0{call}"),
+            Finding {
+                severity: Severity::Medium,
+                category: Category::Eval,
+                message: format!("`{call}` is called {} {times}", calls.len()),
+                evidence: snippet(data, first),
+                location: at(Some(name), first),
+            },
+        );
+    }
+}
+
+struct SecretPattern {
+    name: &'static str,
+    prefixes: &'static [&'static str],
+    /// Bytes the token after a prefix is made of.
+    charset: fn(u8) -> bool,
+    min_len: usize,
+    max_len: usize,
+    severity: Severity,
+}
+
+const SECRET_PATTERNS: &[SecretPattern] = &[
+    SecretPattern {
+        name: "AWS access key ID",
+        prefixes: &["AKIA", "ASIA"],
+        charset: |byte| byte.is_ascii_uppercase() || byte.is_ascii_digit(),
+        min_len: 16,
+        max_len: 16,
+        severity: Severity::High,
+    },
+    SecretPattern {
+        name: "GitHub token",
+        prefixes: &["ghp_", "gho_", "ghu_", "ghs_", "ghr_"],
+        charset: |byte| byte.is_ascii_alphanumeric(),
+        min_len: 36,
+        max_len: 36,
+        severity: Severity::High,
+    },
+    SecretPattern {
+        name: "GitHub token",
+        prefixes: &["github_pat_"],
+        charset: |byte| byte.is_ascii_alphanumeric() || byte == b'_',
+        min_len: 60,
+        max_len: 100,
+        severity: Severity::High,
+    },
+    SecretPattern {
+        name: "Slack token",
+        prefixes: &["xoxb-", "xoxp-", "xoxa-", "xoxr-", "xoxs-"],
+        charset: |byte| byte.is_ascii_alphanumeric() || byte == b'-',
+        min_len: 10,
+        max_len: 200,
+        severity: Severity::High,
+    },
+    SecretPattern {
+        name: "Stripe secret key",
+        prefixes: &["sk_live_", "rk_live_"],
+        charset: |byte| byte.is_ascii_alphanumeric(),
+        min_len: 20,
+        max_len: 250,
+        severity: Severity::High,
+    },
+    // Browser keys for Google APIs are often meant to be public, but only
+    // if their use is restricted to the app.
+    SecretPattern {
+        name: "Google API key",
+        prefixes: &["AIza"],
+        charset: |byte| byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-'),
+        min_len: 35,
+        max_len: 35,
+        severity: Severity::Medium,
+    },
+];
+
+/// The comment opening a minisign secret key, as made by `tauri signer
+/// generate` for the updater, and its base64 form, which is how the Tauri
+/// CLI stores it.
+const UPDATER_KEY_MARKERS: &[&str] = &[
+    "untrusted comment: rsign encrypted secret key",
+    "dW50cnVzdGVkIGNvbW1lbnQ6IHJzaWduIGVuY3J5cHRlZCBzZWNyZXQga2V5",
+];
+
+fn check_secrets(asset: Option<&str>, data: &[u8], findings: &mut Findings) {
+    for pattern in SECRET_PATTERNS {
+        for prefix in pattern.prefixes {
+            for offset in find_all(data, prefix.as_bytes()) {
+                if offset > 0 && data[offset - 1].is_ascii_alphanumeric() {
+                    continue;
+                }
+                let start = offset + prefix.len();
+                let length = data[start..]
+                    .iter()
+                    .take(pattern.max_len + 1)
+                    .take_while(|&&byte| (pattern.charset)(byte))
+                    .count();
+                if !(pattern.min_len..=pattern.max_len).contains(&length) {
+                    continue;
+                }
+                let secret = String::from_utf8_lossy(&data[offset..start + length]);
+                findings.push(
+                    &secret,
+                    Finding {
+                        severity: pattern.severity,
+                        category: Category::Secret,
+                        message: format!("embedded {}", pattern.name),
+                        evidence: mask(&secret),
+                        location: at(asset, offset),
+                    },
+                );
+            }
+        }
+    }
+
+    for offset in find_all(data, b"-----BEGIN ") {
+        let header = data[offset..]
+            .iter()
+            .take(64)
+            .take_while(|&&byte| byte != b'\n' && byte != b'\r')
+            .copied()
+            .collect::<Vec<_>>();
+        let header = String::from_utf8_lossy(&header);
+        if header.ends_with("PRIVATE KEY-----") {
+            findings.push(
+                &format!("{}+{offset}", asset.unwrap_or_default()),
+                Finding {
+                    severity: Severity::High,
+                    category: Category::Secret,
+                    message: "embedded private key".to_string(),
+                    evidence: header.to_string(),
+                    location: at(asset, offset),
+                },
+            );
+        }
+    }
+
+    for marker in UPDATER_KEY_MARKERS {
+        if let Some(offset) = find(data, marker.as_bytes()) {
+            findings.push(
+                &format!("{}+{offset}", asset.unwrap_or_default()),
+                Finding {
+                    severity: Severity::High,
+                    category: Category::Secret,
+                    message: "embedded updater signing key".to_string(),
+                    evidence: mask(marker),
+                    location: at(asset, offset),
+                },
+            );
+        }
+    }
+}
+
+/// Keeps the first characters of `secret`, enough to find it again.
+/// Flags an asset whose entropy stands out for its type. Text this random
+/// cannot be what its name says.
+fn check_entropy(name: &str, data: &[u8], findings: &mut Findings) {
+    let measured = AssetEntropy::measure(name, data);
+    if !measured.outlier {
+        return;
+    }
+    let severity = if measured.is_text() {
+        Severity::High
+    } else {
+        Severity::Medium
+    };
+    findings.push(
+        name,
+        Finding {
+            severity,
+            category: Category::HighEntropy,
+            message: format!(
+                "{} is as random as encrypted or compressed data, possibly a hidden payload",
+                measured.mime
+            ),
+            evidence: format!(
+                "{:.2} bits per byte over {} bytes",
+                measured.entropy, measured.size
+            ),
+            location: at(Some(name), 0),
+        },
+    );
+}
+
+fn mask(secret: &str) -> String {
+    let kept = secret
+        .char_indices()
+        .nth(SECRET_PREFIX_LEN)
+        .map_or(secret.len(), |(index, _)| index);
+    format!("{}…", &secret[..kept])
+}
+
+/// The line of code starting at `offset`, cut to [`MAX_SNIPPET_LEN`].
+fn snippet(data: &[u8], offset: usize) -> String {
+    let line = data[offset..]
+        .iter()
+        .take(MAX_SNIPPET_LEN)
+        .take_while(|&&byte| byte != b'\n' && byte != b'\r')
+        .copied()
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&line).into_owned()
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-')
+}
+
+/// Bytes before `eval(` that make it a method or another identifier.
+fn is_call_prefix(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'$' | b'.')
+}
+
+fn is_url_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=%{}".contains(&byte)
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, extension)| {
+        extensions
+            .iter()
+            .any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,93 @@
+//! A work-stealing pool that runs a batch of inputs as one pipeline.
+//!
+//! Processing inputs one after another leaves cores idle: parsing and
+//! scanning a binary run on one thread, and a small app has too few assets
+//! to keep the others decompressing. A batch instead splits the work on each
+//! input into tasks, one per stage, and runs them on threads shared by every
+//! input. A task spawns the tasks of the next stage on its own thread, which
+//! runs the newest first, so an input flows through to its writes while its
+//! data is still in cache and few inputs are held in memory at once. A thread
+//! that runs dry takes the next input from the shared queue, or steals
+//! from the other threads.
+
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
+use std::iter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Queues the follow-up tasks of the task being run.
+pub(crate) struct Spawner<'a, T> {
+    local: &'a Worker<T>,
+    pending: &'a AtomicUsize,
+}
+
+impl<T> Spawner<'_, T> {
+    pub fn spawn(&self, task: T) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.local.push(task);
+    }
+}
+
+/// Counts a task as done when dropped, so that a panicking task does not
+/// leave the other threads waiting for it.
+struct Done<'a>(&'a AtomicUsize);
+
+impl Drop for Done<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Runs `tasks` and every task they spawn with `work` on `threads` threads,
+/// returning once all of them ran.
+pub(crate) fn run<T: Send>(
+    threads: usize,
+    tasks: impl IntoIterator<Item = T>,
+    work: impl Fn(T, &Spawner<'_, T>) + Sync,
+) {
+    let injector = Injector::new();
+    let pending = AtomicUsize::new(0);
+    for task in tasks {
+        pending.fetch_add(1, Ordering::SeqCst);
+        injector.push(task);
+    }
+    let workers = (0..threads.max(1))
+        .map(|_| Worker::new_lifo())
+        .collect::<Vec<_>>();
+    let stealers = workers.iter().map(Worker::stealer).collect::<Vec<_>>();
+    let (injector, stealers, pending, work) = (&injector, &stealers, &pending, &work);
+    thread::scope(|scope| {
+        for local in workers {
+            scope.spawn(move || loop {
+                match find_task(&local, injector, stealers) {
+                    Some(task) => {
+                        let _done = Done(pending);
+                        work(
+                            task,
+                            &Spawner {
+                                local: &local,
+                                pending,
+                            },
+                        );
+                    }
+                    None if pending.load(Ordering::SeqCst) == 0 => return,
+                    None => thread::yield_now(),
+                }
+            });
+        }
+    });
+}
+
+fn find_task<T>(local: &Worker<T>, injector: &Injector<T>, stealers: &[Stealer<T>]) -> Option<T> {
+    // One input at a time from the shared queue, so that a thread does not
+    // hold on to inputs that idle threads could start.
+    local.pop().or_else(|| {
+        iter::repeat_with(|| {
+            injector
+                .steal()
+                .or_else(|| stealers.iter().map(Stealer::steal).collect())
+        })
+        .find(|steal| !steal.is_retry())
+        .and_then(Steal::success)
+    })
+}
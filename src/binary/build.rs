@@ -0,0 +1,138 @@
+//! When a binary was linked and which OS versions it targets.
+//!
+//! PE images keep the link time in the COFF header and the oldest Windows
+//! version they load on as the subsystem version of the optional header.
+//! Mach-O images name their platform, minimum OS and SDK in
+//! `LC_BUILD_VERSION`, or in one of the older `LC_VERSION_MIN_*` commands.
+
+use crate::bytes::{le_u16, le_u32};
+use crate::time::utc_timestamp;
+use object::macho::{
+    BuildVersionCommand, MachHeader64, VersionMinCommand, LC_BUILD_VERSION,
+    LC_VERSION_MIN_IPHONEOS, LC_VERSION_MIN_MACOSX, LC_VERSION_MIN_TVOS, LC_VERSION_MIN_WATCHOS,
+};
+use object::read::macho::MachHeader;
+use object::Endianness;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Link times before this, 2000-01-01, are not taken for real ones.
+const EARLIEST_TIMESTAMP: u64 = 946_684_800;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// When a PE image was linked, as an ISO 8601 UTC timestamp.
+    /// Reproducible builds store a hash there instead, which is left out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// The platform the image targets, such as `windows`, `macos` or `ios`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// The oldest OS version the image runs on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_os_version: Option<String>,
+    /// The SDK a Mach-O image was built against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sdk_version: Option<String>,
+}
+
+/// Reads the build metadata of the PE or 64-bit Mach-O image in `data`.
+pub fn build_info(data: &[u8]) -> Option<BuildInfo> {
+    let info = if data.starts_with(b"MZ") {
+        pe_build_info(data)?
+    } else {
+        macho_build_info(data)?
+    };
+    (info != BuildInfo::default()).then_some(info)
+}
+
+fn pe_build_info(data: &[u8]) -> Option<BuildInfo> {
+    let pe = le_u32(data, 0x3c)? as usize;
+    if data.get(pe..pe.checked_add(4)?)? != b"PE\0\0" {
+        return None;
+    }
+    let seconds = u64::from(le_u32(data, pe + 8)?);
+    let latest = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs());
+    let timestamp = (EARLIEST_TIMESTAMP..=latest)
+        .contains(&seconds)
+        .then(|| utc_timestamp(UNIX_EPOCH + Duration::from_secs(seconds)));
+    let optional = pe + 24;
+    let subsystem = (le_u16(data, optional + 48)?, le_u16(data, optional + 50)?);
+    Some(BuildInfo {
+        timestamp,
+        platform: Some("windows".to_string()),
+        min_os_version: (subsystem != (0, 0)).then(|| format!("{}.{}", subsystem.0, subsystem.1)),
+        sdk_version: None,
+    })
+}
+
+fn macho_build_info(data: &[u8]) -> Option<BuildInfo> {
+    let header = MachHeader64::<Endianness>::parse(data, 0).ok()?;
+    let endian = header.endian().ok()?;
+    let mut commands = header.load_commands(endian, data, 0).ok()?;
+    let mut info = BuildInfo::default();
+    while let Ok(Some(command)) = commands.next() {
+        match command.cmd() {
+            LC_BUILD_VERSION => {
+                let command = command.data::<BuildVersionCommand<Endianness>>().ok()?;
+                // The build version is authoritative over the older commands.
+                return Some(BuildInfo {
+                    platform: Some(platform_name(command.platform.get(endian))),
+                    min_os_version: Some(version(command.minos.get(endian))),
+                    sdk_version: Some(version(command.sdk.get(endian))),
+                    ..info
+                });
+            }
+            cmd @ (LC_VERSION_MIN_MACOSX
+            | LC_VERSION_MIN_IPHONEOS
+            | LC_VERSION_MIN_TVOS
+            | LC_VERSION_MIN_WATCHOS) => {
+                let command = command.data::<VersionMinCommand<Endianness>>().ok()?;
+                let platform = match cmd {
+                    LC_VERSION_MIN_MACOSX => "macos",
+                    LC_VERSION_MIN_IPHONEOS => "ios",
+                    LC_VERSION_MIN_TVOS => "tvos",
+                    _ => "watchos",
+                };
+                info.platform = Some(platform.to_string());
+                info.min_os_version = Some(version(command.version.get(endian)));
+                info.sdk_version = Some(version(command.sdk.get(endian)));
+            }
+            _ => {}
+        }
+    }
+    Some(info)
+}
+
+/// Names the `PLATFORM_*` constant of `LC_BUILD_VERSION`.
+fn platform_name(platform: u32) -> String {
+    let name = match platform {
+        1 => "macos",
+        2 => "ios",
+        3 => "tvos",
+        4 => "watchos",
+        5 => "bridgeos",
+        6 => "maccatalyst",
+        7 => "ios-simulator",
+        8 => "tvos-simulator",
+        9 => "watchos-simulator",
+        10 => "driverkit",
+        11 => "visionos",
+        12 => "visionos-simulator",
+        other => return format!("platform {other}"),
+    };
+    name.to_string()
+}
+
+/// Formats a version packed as `xxxx.yy.zz` nibbles, leaving out a zero
+/// patch version.
+fn version(packed: u32) -> String {
+    let (major, minor, patch) = (packed >> 16, (packed >> 8) & 0xff, packed & 0xff);
+    if patch == 0 {
+        format!("{major}.{minor}")
+    } else {
+        format!("{major}.{minor}.{patch}")
+    }
+}
@@ -0,0 +1,149 @@
+//! Mach-O code signatures.
+//!
+//! `LC_CODE_SIGNATURE` points at a big-endian superblob in `__LINKEDIT` that
+//! holds the code directory, the entitlements and a CMS signature whose
+//! certificates name the signing identity.
+
+use crate::bytes::be_u32;
+use object::macho::{LinkeditDataCommand, MachHeader64, LC_CODE_SIGNATURE};
+use object::read::macho::MachHeader;
+use object::Endianness;
+use serde::{Deserialize, Serialize};
+
+const SUPERBLOB_MAGIC: u32 = 0xfade_0cc0;
+const CODE_DIRECTORY_MAGIC: u32 = 0xfade_0c02;
+const ENTITLEMENTS_MAGIC: u32 = 0xfade_7171;
+const CMS_SIGNATURE_MAGIC: u32 = 0xfade_0b01;
+
+const CODE_DIRECTORY_SLOT: u32 = 0;
+const ENTITLEMENTS_SLOT: u32 = 5;
+const CMS_SIGNATURE_SLOT: u32 = 0x10000;
+
+const FLAG_ADHOC: u32 = 0x2;
+const FLAG_RUNTIME: u32 = 0x1_0000;
+/// The first code directory version with a team ID offset.
+const TEAM_ID_VERSION: u32 = 0x2_0200;
+
+/// DER encoding of the X.520 `commonName` attribute type, 2.5.4.3.
+const COMMON_NAME_OID: &[u8] = &[0x06, 0x03, 0x55, 0x04, 0x03];
+
+/// The code signature of a Mach-O image.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSignature {
+    /// The signing identifier, usually the bundle identifier.
+    pub identifier: Option<String>,
+    pub team_id: Option<String>,
+    /// The common name of the signing certificate, such as
+    /// `Developer ID Application: Example Inc (TEAMID)`. Ad-hoc signatures
+    /// have none.
+    pub signing_identity: Option<String>,
+    pub ad_hoc: bool,
+    /// Whether the hardened runtime is enabled. Notarization requires it, but
+    /// whether a ticket was issued can only be checked with Apple.
+    pub hardened_runtime: bool,
+    /// The entitlements plist, as XML.
+    pub entitlements: Option<String>,
+}
+
+/// Reads the code signature of the 64-bit Mach-O image in `data`, if it has
+/// one.
+pub fn code_signature(data: &[u8]) -> Option<CodeSignature> {
+    let header = MachHeader64::<Endianness>::parse(data, 0).ok()?;
+    let endian = header.endian().ok()?;
+    let mut commands = header.load_commands(endian, data, 0).ok()?;
+    let (offset, size) = loop {
+        let command = commands.next().ok()??;
+        if command.cmd() == LC_CODE_SIGNATURE {
+            let command = command.data::<LinkeditDataCommand<Endianness>>().ok()?;
+            break (
+                command.dataoff.get(endian) as usize,
+                command.datasize.get(endian) as usize,
+            );
+        }
+    };
+    parse_superblob(data.get(offset..offset.checked_add(size)?)?)
+}
+
+fn parse_superblob(blob: &[u8]) -> Option<CodeSignature> {
+    if be_u32(blob, 0)? != SUPERBLOB_MAGIC {
+        return None;
+    }
+    let mut signature = CodeSignature::default();
+    let count = be_u32(blob, 8)? as usize;
+    for index in 0..count {
+        let slot = be_u32(blob, 12 + index * 8)?;
+        let offset = be_u32(blob, 16 + index * 8)? as usize;
+        let Some((magic, content)) = sub_blob(blob, offset) else {
+            log::debug!("code signature slot {slot:#x} is truncated");
+            continue;
+        };
+        match (slot, magic) {
+            (CODE_DIRECTORY_SLOT, CODE_DIRECTORY_MAGIC) => {
+                read_code_directory(blob.get(offset..)?, &mut signature);
+            }
+            (ENTITLEMENTS_SLOT, ENTITLEMENTS_MAGIC) => {
+                signature.entitlements = Some(String::from_utf8_lossy(content).into_owned());
+            }
+            (CMS_SIGNATURE_SLOT, CMS_SIGNATURE_MAGIC) => {
+                signature.signing_identity = signing_identity(content);
+            }
+            _ => {}
+        }
+    }
+    Some(signature)
+}
+
+/// Returns the magic and payload of the blob at `offset`.
+fn sub_blob(blob: &[u8], offset: usize) -> Option<(u32, &[u8])> {
+    let magic = be_u32(blob, offset)?;
+    let length = be_u32(blob, offset + 4)? as usize;
+    let end = offset.checked_add(length)?;
+    Some((magic, blob.get(offset.checked_add(8)?..end)?))
+}
+
+fn read_code_directory(directory: &[u8], signature: &mut CodeSignature) -> Option<()> {
+    let version = be_u32(directory, 8)?;
+    let flags = be_u32(directory, 12)?;
+    signature.ad_hoc = flags & FLAG_ADHOC != 0;
+    signature.hardened_runtime = flags & FLAG_RUNTIME != 0;
+    signature.identifier = c_string(directory, be_u32(directory, 20)? as usize);
+    if version >= TEAM_ID_VERSION {
+        let team_offset = be_u32(directory, 48)? as usize;
+        if team_offset != 0 {
+            signature.team_id = c_string(directory, team_offset);
+        }
+    }
+    Some(())
+}
+
+/// Returns the first certificate common name that is not an Apple
+/// certificate authority. `codesign` stores the leaf certificate first, and
+/// each certificate names its issuer before its subject.
+fn signing_identity(cms: &[u8]) -> Option<String> {
+    let mut rest = cms;
+    while let Some(found) = rest
+        .windows(COMMON_NAME_OID.len())
+        .position(|window| window == COMMON_NAME_OID)
+    {
+        rest = &rest[found + COMMON_NAME_OID.len()..];
+        // UTF8String, PrintableString or IA5String, with a short length.
+        let (&tag, &length) = (rest.first()?, rest.get(1)?);
+        if !matches!(tag, 0x0c | 0x13 | 0x16) || length >= 0x80 {
+            continue;
+        }
+        let Some(value) = rest.get(2..2 + usize::from(length)) else {
+            continue;
+        };
+        let name = String::from_utf8_lossy(value);
+        if !name.starts_with("Apple Root") && !name.contains("Certification Authority") {
+            return Some(name.into_owned());
+        }
+    }
+    None
+}
+
+fn c_string(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&byte| byte == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
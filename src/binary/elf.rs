@@ -1,6 +1,6 @@
 //! ELF binary format parser.
 
-use super::{read_u64, BinaryParser, ScanRange, SectionInfo};
+use super::{read_pointer_sized, BinaryParser, ScanRange, SectionInfo};
 use crate::error::{Error, Result};
 use std::collections::HashMap;
 
@@ -9,6 +9,7 @@ pub struct ElfParser {
     sections: Vec<SectionInfo>,
     scan_sections: Vec<SectionInfo>,
     relative_relocations: HashMap<u64, u64>,
+    pointer_width: usize,
 }
 
 impl ElfParser {
@@ -32,9 +33,16 @@ impl ElfParser {
             sections,
             scan_sections,
             relative_relocations,
+            pointer_width: 8,
         })
     }
 
+    /// Sets the pointer size, 4 for 32-bit ELF files.
+    pub fn with_pointer_width(mut self, pointer_width: usize) -> Self {
+        self.pointer_width = pointer_width;
+        self
+    }
+
     /// Converts a virtual address to a file offset.
     fn va_to_file_offset(&self, va: u64) -> Result<u64> {
         self.sections
@@ -46,18 +54,26 @@ impl ElfParser {
 }
 
 impl BinaryParser for ElfParser {
+    fn pointer_width(&self) -> usize {
+        self.pointer_width
+    }
+
     fn read_pointer(&self, data: &[u8], offset: usize) -> Result<u64> {
         if let Some(addend) = self.relative_relocations.get(&(offset as u64)) {
             return Ok(*addend);
         }
 
-        read_u64(data, offset)
+        read_pointer_sized(data, offset, self.pointer_width)
     }
 
     fn resolve_pointer(&self, raw_ptr: u64) -> Result<u64> {
         self.va_to_file_offset(raw_ptr)
     }
 
+    fn sections(&self) -> &[SectionInfo] {
+        &self.sections
+    }
+
     fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
         Ok(self
             .scan_sections
@@ -0,0 +1,138 @@
+//! FairPlay encryption of iOS Mach-O images.
+//!
+//! Apps installed from the App Store are encrypted: `LC_ENCRYPTION_INFO_64`
+//! (`LC_ENCRYPTION_INFO` for 32-bit images) names the encrypted file range,
+//! usually most of `__TEXT`, and a nonzero `cryptid` says it is still
+//! encrypted. The kernel decrypts it when mapping the image, so only a copy
+//! dumped from a running device reads as code and data. Such dumps usually
+//! keep the command with `cryptid` set to zero, but some dumpers leave it
+//! set, so a range whose start reads as code rather than ciphertext counts
+//! as decrypted either way.
+//!
+//! Dumpers that only write out the decrypted range, or the memory of the
+//! mapped image, leave the encrypted binary to be patched with
+//! [`apply_decrypted_dump`].
+
+use crate::error::{Error, Result};
+use crate::packer;
+use object::macho::{
+    EncryptionInfoCommand32, MachHeader64, LC_ENCRYPTION_INFO, LC_ENCRYPTION_INFO_64,
+};
+use object::read::macho::MachHeader;
+use object::Endianness;
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+
+/// Size of the blocks of the range whose entropy is checked.
+const ENTROPY_BLOCK: usize = 4096;
+/// Blocks at the start of the range that are checked.
+const ENTROPY_BLOCKS: usize = 16;
+/// Entropy, in bits per byte, at or above which a block is taken to be
+/// ciphertext. Machine code stays well below it.
+const CIPHERTEXT_ENTROPY: f64 = 7.0;
+/// Offset of `cryptid` in the encryption info command.
+const CRYPTID_OFFSET: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionInfo {
+    /// File offset of the encrypted range.
+    pub offset: u32,
+    /// Size of the encrypted range.
+    pub size: u32,
+    /// Whether the range is still encrypted, as opposed to a decrypted
+    /// dump.
+    pub encrypted: bool,
+}
+
+impl EncryptionInfo {
+    /// The encrypted file range.
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.offset as usize..self.offset as usize + self.size as usize
+    }
+}
+
+/// Reads the encryption info command of the 64-bit Mach-O image in `data`.
+pub fn encryption_info(data: &[u8]) -> Option<EncryptionInfo> {
+    find_command(data).map(|(_, info)| info)
+}
+
+/// Splices a decrypted dump into the encrypted Mach-O image in `data` and
+/// clears `cryptid`, so the result scans like a build that was never
+/// encrypted.
+///
+/// `dump` is either the decrypted range alone, `cryptsize` bytes, or memory
+/// read from the image's load address on, such as a whole decrypted binary:
+/// `__TEXT` maps the file from its start, so the range sits at `cryptoff` in
+/// both.
+pub fn apply_decrypted_dump(data: &[u8], dump: &[u8]) -> Result<Vec<u8>> {
+    let (command, info) = find_command(data).ok_or_else(|| {
+        Error::Message("binary has no encryption info command to apply a decrypted dump to".into())
+    })?;
+    let range = info.range();
+    if range.end > data.len() {
+        return Err(Error::Message(format!(
+            "the encrypted range ends at {:#x}, past the end of the binary",
+            range.end
+        )));
+    }
+    let decrypted = if dump.len() == range.len() {
+        dump
+    } else {
+        dump.get(range.clone()).ok_or_else(|| {
+            Error::Message(format!(
+                "the decrypted dump has {} bytes; expected the {} bytes of the encrypted range \
+                 alone, or memory from the load address covering it up to {:#x}",
+                dump.len(),
+                range.len(),
+                range.end
+            ))
+        })?
+    };
+    if decrypted.len() >= ENTROPY_BLOCK && !looks_decrypted(decrypted) {
+        return Err(Error::Message(
+            "the decrypted dump is still encrypted; dump the app while it runs on a device".into(),
+        ));
+    }
+
+    let mut patched = data.to_vec();
+    patched[range].copy_from_slice(decrypted);
+    let cryptid = command + CRYPTID_OFFSET;
+    patched[cryptid..cryptid + 4].fill(0);
+    Ok(patched)
+}
+
+/// Finds the encryption info command, returning its file offset with the
+/// info it holds.
+fn find_command(data: &[u8]) -> Option<(usize, EncryptionInfo)> {
+    let header = MachHeader64::<Endianness>::parse(data, 0).ok()?;
+    let endian = header.endian().ok()?;
+    let mut commands = header.load_commands(endian, data, 0).ok()?;
+    let mut offset = size_of::<MachHeader64<Endianness>>();
+    while let Ok(Some(command)) = commands.next() {
+        if matches!(command.cmd(), LC_ENCRYPTION_INFO | LC_ENCRYPTION_INFO_64) {
+            // The 64-bit command only adds padding.
+            let command = command.data::<EncryptionInfoCommand32<Endianness>>().ok()?;
+            let mut info = EncryptionInfo {
+                offset: command.cryptoff.get(endian),
+                size: command.cryptsize.get(endian),
+                encrypted: command.cryptid.get(endian) != 0,
+            };
+            if info.encrypted {
+                info.encrypted = !data.get(info.range()).is_some_and(looks_decrypted);
+            }
+            return Some((offset, info));
+        }
+        offset += command.cmdsize() as usize;
+    }
+    None
+}
+
+/// Returns true if one of the first blocks of `range` reads as code rather
+/// than ciphertext. Ranges shorter than a block are too short to judge and
+/// are taken to be encrypted.
+fn looks_decrypted(range: &[u8]) -> bool {
+    range
+        .chunks_exact(ENTROPY_BLOCK)
+        .take(ENTROPY_BLOCKS)
+        .any(|block| packer::entropy(block) < CIPHERTEXT_ENTROPY)
+}
@@ -0,0 +1,159 @@
+//! How a binary was built and which exploit mitigations it enables.
+//!
+//! Cargo's dev profile compiles in overflow checks and debug assertions, and
+//! both leave their panic messages behind: `attempt to add with overflow`
+//! for the first and `unsafe precondition(s) violated` for the undefined
+//! behavior checks the second turns on in the standard library's inlined
+//! code. The release profile compiles neither in. Symbols and debug info are
+//! reported alongside, as a release build may keep them too.
+//!
+//! The mitigations are read from the headers: PE `DllCharacteristics`, the
+//! ELF type and `PT_GNU_STACK`, and the Mach-O header flags.
+
+use crate::bytes::{le_u16, le_u32};
+use object::elf::{ET_DYN, PF_X, PT_GNU_STACK};
+use object::macho::{MH_ALLOW_STACK_EXECUTION, MH_EXECUTE, MH_PIE};
+use object::read::elf::{ElfFile, FileHeader, ProgramHeader};
+use object::read::macho::{MachHeader, MachOFile};
+use object::{Object, ObjectSymbol};
+use serde::{Deserialize, Serialize};
+
+/// Panic messages of arithmetic overflow checks.
+const OVERFLOW_CHECK_MESSAGES: &[&[u8]] = &[
+    b"attempt to add with overflow",
+    b"attempt to subtract with overflow",
+    b"attempt to multiply with overflow",
+];
+
+/// Panic message of the standard library's debug assertion checks.
+const DEBUG_ASSERTION_MESSAGE: &[u8] = b"unsafe precondition(s) violated";
+
+/// Sections that hold DWARF debug info.
+const DEBUG_INFO_SECTIONS: &[&str] = &[".debug_info", "__debug_info"];
+
+const IMAGE_FILE_RELOCS_STRIPPED: u16 = 0x0001;
+const IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE: u16 = 0x0040;
+const IMAGE_DLLCHARACTERISTICS_NX_COMPAT: u16 = 0x0100;
+const IMAGE_DLLCHARACTERISTICS_GUARD_CF: u16 = 0x4000;
+
+/// The cargo profile a binary appears to be built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildProfile {
+    Debug,
+    Release,
+}
+
+impl BuildProfile {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Release => "release",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hardening {
+    /// `Debug` when overflow checks or debug assertions are compiled in.
+    pub profile: BuildProfile,
+    pub overflow_checks: bool,
+    pub debug_assertions: bool,
+    /// Whether the image carries DWARF debug info.
+    pub debug_info: bool,
+    /// Entries in the symbol table; zero for a stripped image.
+    pub symbols: usize,
+    /// Whether the image can be loaded at a random address: a PIE or
+    /// shared object, a Mach-O image with `MH_PIE`, or a PE image with
+    /// `DYNAMIC_BASE` and relocations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aslr: Option<bool>,
+    /// Whether the stack, and for PE images the data, is not executable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nx: Option<bool>,
+    /// Whether a PE image is built with Control Flow Guard.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cfg: Option<bool>,
+}
+
+/// Reads the build profile and mitigations of the image in `data`.
+pub fn hardening(data: &[u8]) -> Option<Hardening> {
+    let file = object::File::parse(data).ok()?;
+    let (aslr, nx, cfg) = match &file {
+        object::File::Pe32(_) | object::File::Pe64(_) => pe_mitigations(data)?,
+        object::File::Elf32(elf) => elf_mitigations(elf),
+        object::File::Elf64(elf) => elf_mitigations(elf),
+        object::File::MachO32(macho) => macho_mitigations(macho),
+        object::File::MachO64(macho) => macho_mitigations(macho),
+        _ => (None, None, None),
+    };
+    let overflow_checks = OVERFLOW_CHECK_MESSAGES
+        .iter()
+        .any(|message| contains(data, message));
+    let debug_assertions = contains(data, DEBUG_ASSERTION_MESSAGE);
+    Some(Hardening {
+        profile: if overflow_checks || debug_assertions {
+            BuildProfile::Debug
+        } else {
+            BuildProfile::Release
+        },
+        overflow_checks,
+        debug_assertions,
+        debug_info: DEBUG_INFO_SECTIONS
+            .iter()
+            .any(|name| file.section_by_name(name).is_some()),
+        symbols: file
+            .symbols()
+            .filter(|symbol| !symbol.name_bytes().unwrap_or_default().is_empty())
+            .count(),
+        aslr,
+        nx,
+        cfg,
+    })
+}
+
+type Mitigations = (Option<bool>, Option<bool>, Option<bool>);
+
+fn pe_mitigations(data: &[u8]) -> Option<Mitigations> {
+    let pe = le_u32(data, 0x3c)? as usize;
+    let characteristics = le_u16(data, pe + 22)?;
+    // At the same offset in PE32 and PE32+ optional headers.
+    let dll_characteristics = le_u16(data, pe + 24 + 70)?;
+    let has = |flag| dll_characteristics & flag != 0;
+    Some((
+        Some(
+            has(IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE)
+                && characteristics & IMAGE_FILE_RELOCS_STRIPPED == 0,
+        ),
+        Some(has(IMAGE_DLLCHARACTERISTICS_NX_COMPAT)),
+        Some(has(IMAGE_DLLCHARACTERISTICS_GUARD_CF)),
+    ))
+}
+
+fn elf_mitigations<Elf: FileHeader>(elf: &ElfFile<'_, Elf>) -> Mitigations {
+    let endian = elf.endian();
+    let pie = elf.elf_header().e_type(endian) == ET_DYN;
+    // Without `PT_GNU_STACK`, the loader makes the stack executable.
+    let nx = elf
+        .elf_program_headers()
+        .iter()
+        .find(|segment| segment.p_type(endian) == PT_GNU_STACK)
+        .is_some_and(|segment| segment.p_flags(endian) & PF_X == 0);
+    (Some(pie), Some(nx), None)
+}
+
+fn macho_mitigations<Mach: MachHeader>(macho: &MachOFile<'_, Mach>) -> Mitigations {
+    let header = macho.macho_header();
+    let endian = macho.endian();
+    let flags = header.flags(endian);
+    // Dylibs and bundles are always loaded at a random address.
+    let pie = header.filetype(endian) != MH_EXECUTE || flags & MH_PIE != 0;
+    let nx = flags & MH_ALLOW_STACK_EXECUTION == 0;
+    (Some(pie), Some(nx), None)
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
@@ -2,7 +2,10 @@
 
 use super::{BinaryParser, ScanRange, SectionInfo};
 use crate::error::{Error, Result};
-use object::macho::{MachHeader64, SegmentCommand64, LC_DYLD_CHAINED_FIXUPS, LC_SEGMENT_64};
+use object::macho::{
+    MachHeader64, SegmentCommand64, CPU_SUBTYPE_ARM64E, CPU_SUBTYPE_MASK, CPU_TYPE_ARM64,
+    LC_DYLD_CHAINED_FIXUPS, LC_SEGMENT_64,
+};
 use object::read::macho::MachHeader;
 use object::Endianness;
 
@@ -16,6 +19,13 @@ enum FixupFormat {
     /// Pointer format: high bits contain metadata, low 43 bits contain offset from image base.
     ChainedFixups,
 
+    /// Chained fixups of an arm64e binary.
+    ///
+    /// Authenticated pointers keep a 32-bit offset from the image base below
+    /// their signing data; plain ones keep 43 bits of either the offset or
+    /// the target's address, depending on the chain's pointer format.
+    ChainedFixupsArm64e,
+
     /// Traditional rebase format (LC_DYLD_INFO_ONLY).
     ///
     /// Pointer value is the actual virtual address.
@@ -25,17 +35,30 @@ enum FixupFormat {
 /// Mach-O binary parser with support for both chained fixups and traditional formats.
 pub struct MachOParser {
     sections: Vec<SectionInfo>,
+    scan_sections: Vec<SectionInfo>,
     fixup_format: FixupFormat,
     image_base: u64,
 }
 
 impl MachOParser {
     /// Creates a new Mach-O parser from raw binary data.
-    pub fn new(data: &[u8], sections: Vec<SectionInfo>) -> Result<Self> {
+    ///
+    /// `sections` are used for pointer resolution and `scan_sections` are the
+    /// data-segment `__const` sections searched for asset headers.
+    pub fn new(
+        data: &[u8],
+        sections: Vec<SectionInfo>,
+        scan_sections: Vec<SectionInfo>,
+    ) -> Result<Self> {
+        if scan_sections.is_empty() {
+            return Err(Error::NoAssetSection("Mach-O __const".to_string()));
+        }
+
         let (fixup_format, image_base) = Self::detect_fixup_format(data)?;
 
         Ok(Self {
             sections,
+            scan_sections,
             fixup_format,
             image_base,
         })
@@ -73,7 +96,11 @@ impl MachOParser {
             }
         }
 
-        let format = if has_chained_fixups {
+        let arm64e = header.cputype(endian) == CPU_TYPE_ARM64
+            && header.cpusubtype(endian) & !CPU_SUBTYPE_MASK == CPU_SUBTYPE_ARM64E;
+        let format = if has_chained_fixups && arm64e {
+            FixupFormat::ChainedFixupsArm64e
+        } else if has_chained_fixups {
             FixupFormat::ChainedFixups
         } else {
             FixupFormat::Traditional
@@ -91,6 +118,21 @@ impl MachOParser {
                 let offset = raw_ptr & TARGET_MASK;
                 self.image_base + offset
             }
+            FixupFormat::ChainedFixupsArm64e => {
+                const AUTH_BIT: u64 = 1 << 63;
+                const AUTH_TARGET_MASK: u64 = 0xFFFF_FFFF;
+                const TARGET_MASK: u64 = 0x7FFFFFFFFFF;
+                if raw_ptr & AUTH_BIT != 0 {
+                    return self.image_base + (raw_ptr & AUTH_TARGET_MASK);
+                }
+                let target = raw_ptr & TARGET_MASK;
+                // Offsets are smaller than the image base; addresses are not.
+                if self.image_base != 0 && target >= self.image_base {
+                    target
+                } else {
+                    self.image_base + target
+                }
+            }
             FixupFormat::Traditional => {
                 // Traditional: pointer is the actual virtual address
                 raw_ptr
@@ -114,16 +156,22 @@ impl BinaryParser for MachOParser {
         self.va_to_file_offset(va)
     }
 
+    fn va_to_offset(&self, va: u64) -> Result<u64> {
+        self.va_to_file_offset(va)
+    }
+
+    fn sections(&self) -> &[SectionInfo] {
+        &self.sections
+    }
+
     fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
-        // Asset headers are stored in __DATA_CONST,__const section (last in our list)
-        let section = self
-            .sections
-            .last()
-            .ok_or_else(|| Error::NoAssetSection("Mach-O __const".to_string()))?;
-
-        Ok(vec![ScanRange {
-            start: section.file_offset as usize,
-            length: section.size as usize,
-        }])
+        Ok(self
+            .scan_sections
+            .iter()
+            .map(|section| ScanRange {
+                start: section.file_offset as usize,
+                length: section.size as usize,
+            })
+            .collect())
     }
 }
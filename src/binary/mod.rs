@@ -2,21 +2,42 @@
 //!
 //! This module provides a unified interface for parsing different binary formats
 //! (PE, Mach-O, ELF) with format-specific pointer resolution strategies.
+//! Other formats can be supported by registering a [`ParserFactory`] in a
+//! [`ParserRegistry`].
 
+mod build;
+mod codesign;
 mod elf;
+mod encryption;
+mod hardening;
 mod macho;
 mod pe;
 
+use crate::deep_link::DeepLinks;
 use crate::error::{Error, Result};
-use object::{BinaryFormat, Object, ObjectSection, Relocation, RelocationFlags};
+use crate::features::FeatureEvidence;
+use crate::fingerprint::{CrateVersion, Toolchain};
+use crate::plugins::TauriPlugin;
+use crate::webview::WebViewRuntime;
+use object::read::elf::{Dyn, ElfFile, ElfFile32, ElfFile64, FileHeader, ProgramHeader};
+use object::{
+    BinaryFormat, Endianness, Object, ObjectSection, ObjectSegment, Relocation, RelocationFlags,
+    SegmentFlags,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub use build::{build_info, BuildInfo};
+pub use codesign::{code_signature, CodeSignature};
 pub use elf::ElfParser;
+pub use encryption::{apply_decrypted_dump, encryption_info, EncryptionInfo};
+pub use hardening::{hardening, BuildProfile, Hardening};
 pub use macho::MachOParser;
 pub use pe::PeParser;
 
 /// Information about a section in the binary.
+///
+/// For PE images `virtual_address` is an RVA relative to `ImageBase`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SectionInfo {
     pub virtual_address: u64,
@@ -31,12 +52,69 @@ pub struct ScanRange {
     pub length: usize,
 }
 
+impl ScanRange {
+    fn end(&self) -> usize {
+        self.start.saturating_add(self.length)
+    }
+}
+
+/// Makes `ranges` disjoint, keeping their order: the parts of a range that
+/// an earlier one covers are dropped, and a range that touches an earlier
+/// one is merged into it, so an asset table split across two sections is
+/// scanned as one table. Empty ranges are dropped.
+pub fn normalize_ranges(ranges: impl IntoIterator<Item = ScanRange>) -> Vec<ScanRange> {
+    let mut normalized: Vec<ScanRange> = Vec::new();
+    for range in ranges {
+        let mut pieces = vec![(range.start, range.end())];
+        for known in &normalized {
+            pieces = pieces
+                .into_iter()
+                .flat_map(|(start, end)| {
+                    [(start, end.min(known.start)), (start.max(known.end()), end)]
+                })
+                .filter(|(start, end)| start < end)
+                .collect();
+        }
+        for (start, end) in pieces {
+            normalized.push(ScanRange {
+                start,
+                length: end - start,
+            });
+            merge_touching(&mut normalized);
+        }
+    }
+    normalized
+}
+
+/// Merges the last range of `ranges` into an earlier one it touches, and
+/// the result into any other, until none touch.
+fn merge_touching(ranges: &mut Vec<ScanRange>) {
+    let mut index = ranges.len() - 1;
+    while let Some(other) = (0..ranges.len()).find(|&other| {
+        other != index
+            && (ranges[other].end() == ranges[index].start
+                || ranges[index].end() == ranges[other].start)
+    }) {
+        let (keep, remove) = (other.min(index), other.max(index));
+        let start = ranges[keep].start.min(ranges[remove].start);
+        let end = ranges[keep].end().max(ranges[remove].end());
+        ranges[keep] = ScanRange {
+            start,
+            length: end - start,
+        };
+        ranges.remove(remove);
+        index = keep;
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BinaryKind {
     Pe,
     MachO,
     Elf,
+    /// A format recognized by a registered [`ParserFactory`].
+    Custom,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +124,125 @@ pub struct BinaryMetadata {
     pub file_size: usize,
     pub sha256: String,
     pub source_path: Option<String>,
+    /// The code signature of a Mach-O image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_signature: Option<CodeSignature>,
+    /// The FairPlay encryption of an iOS Mach-O image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<EncryptionInfo>,
+    /// The link time and target OS versions in the image's headers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildInfo>,
+    /// The cargo profile the image appears to be built with and the
+    /// exploit mitigations its headers enable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardening: Option<Hardening>,
+    /// The deep-link schemes and hosts in the embedded app config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deep_links: Option<DeepLinks>,
+    /// The dev server URL in the embedded app config, which dev builds load
+    /// their frontend from instead of embedding it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev_url: Option<String>,
+    /// The Tauri cargo features the binary appears to be built with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<FeatureEvidence>,
+    /// The Tauri plugins compiled into the binary.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<TauriPlugin>,
+    /// The webview runtime `wry` binds to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webview: Option<WebViewRuntime>,
+    /// The Rust compiler the binary was built with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toolchain: Option<Toolchain>,
+    /// The crates whose registry paths the binary keeps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub crates: Vec<CrateVersion>,
+    /// The `Info.plist` of the macOS bundle the image was read from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<BundleInfo>,
+    /// The containers the image was unpacked from, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub containers: Vec<ContainerStep>,
+}
+
+/// A container format an input is unpacked from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerKind {
+    AppBundle,
+    DiskImage,
+    Msi,
+    Nsis,
+    Msix,
+    Deb,
+    Rpm,
+    AppImage,
+    Snap,
+    /// An iOS app package.
+    Ipa,
+    /// An Android package.
+    Apk,
+    /// An Android app bundle.
+    Aab,
+    Zip,
+    /// A `.tar.gz` Tauri updater artifact.
+    UpdaterArchive,
+    /// A universal Mach-O binary, with one slice per architecture.
+    #[serde(rename = "fat_macho")]
+    FatMachO,
+}
+
+impl ContainerKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::AppBundle => "app bundle",
+            Self::DiskImage => "disk image",
+            Self::Msi => "MSI",
+            Self::Nsis => "NSIS installer",
+            Self::Msix => "MSIX",
+            Self::Deb => "deb",
+            Self::Rpm => "RPM",
+            Self::AppImage => "AppImage",
+            Self::Snap => "snap",
+            Self::Ipa => "iOS app package",
+            Self::Apk => "APK",
+            Self::Aab => "Android app bundle",
+            Self::Zip => "zip",
+            Self::UpdaterArchive => "updater archive",
+            Self::FatMachO => "fat Mach-O",
+        }
+    }
+}
+
+/// A container and what was taken out of it: the path of the entry or, for
+/// a fat Mach-O, the architecture of the slice.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerStep {
+    pub kind: ContainerKind,
+    pub entry: String,
+}
+
+/// Fields of a macOS or iOS bundle's `Info.plist`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleInfo {
+    /// `CFBundleIdentifier`, such as `com.example.app`.
+    pub identifier: Option<String>,
+    /// `CFBundleName`.
+    pub name: Option<String>,
+    /// `CFBundleShortVersionString`, the user-visible version.
+    pub version: Option<String>,
+    /// `CFBundleVersion`, the build number.
+    pub build: Option<String>,
+    /// `LSMinimumSystemVersion`, or `MinimumOSVersion` on iOS.
+    pub minimum_os: Option<String>,
+    /// `CFBundleExecutable`, the main executable in `Contents/MacOS`, or at
+    /// the top of an iOS bundle.
+    pub executable: Option<String>,
+    /// The `CFBundleURLSchemes` of every `CFBundleURLTypes` entry.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub url_schemes: Vec<String>,
 }
 
 pub struct ParsedBinary {
@@ -59,13 +256,18 @@ pub struct ParsedBinary {
 /// Different binary formats (PE, Mach-O) have different ways of storing
 /// and resolving pointers. This trait abstracts those differences.
 pub trait BinaryParser: Send + Sync {
+    /// Size of a pointer in bytes: 8, or 4 for 32-bit targets.
+    fn pointer_width(&self) -> usize {
+        8
+    }
+
     /// Reads a pointer-sized field from the binary.
     ///
     /// Most formats store the pointer value directly in the file. ELF files can
     /// store zeroes in relocated pointer fields and keep the target
     /// address in RELA addends, so parsers may override this.
     fn read_pointer(&self, data: &[u8], offset: usize) -> Result<u64> {
-        read_u64(data, offset)
+        read_pointer_sized(data, offset, self.pointer_width())
     }
 
     /// Converts a raw pointer value from the binary to a file offset.
@@ -73,42 +275,127 @@ pub trait BinaryParser: Send + Sync {
     /// This handles format-specific pointer encoding (e.g., Mach-O chained fixups).
     fn resolve_pointer(&self, raw_ptr: u64) -> Result<u64>;
 
-    /// Returns the scan ranges for searching assets in the binary.
+    /// Converts a virtual address to a file offset.
+    ///
+    /// Unlike [`resolve_pointer`](Self::resolve_pointer), this takes an
+    /// address rather than an encoded pointer, so formats that encode
+    /// pointers, such as Mach-O chained fixups, override it.
+    fn va_to_offset(&self, va: u64) -> Result<u64> {
+        self.resolve_pointer(va)
+    }
+
+    /// Returns the scan ranges for searching assets in the binary, in the
+    /// order to search them. Ranges may overlap or touch; scans
+    /// [normalize](normalize_ranges) them first.
     fn scan_ranges(&self) -> Result<Vec<ScanRange>>;
+
+    /// The file-backed sections used to map addresses to file offsets.
+    ///
+    /// Parsers that do not map addresses through sections return none.
+    fn sections(&self) -> &[SectionInfo] {
+        &[]
+    }
+}
+
+/// Recognizes a binary format and creates its parser.
+///
+/// Factories let downstream crates add formats such as firmware images
+/// without changing this crate.
+pub trait ParserFactory: Send + Sync {
+    /// Returns whether `data` is in this factory's format.
+    fn matches(&self, data: &[u8]) -> bool;
+
+    /// Creates a parser for `data`, which [`matches`](Self::matches) accepted.
+    fn create(&self, data: &[u8]) -> Result<ParsedBinary>;
+}
+
+/// Parser factories consulted before the built-in formats.
+#[derive(Default)]
+pub struct ParserRegistry {
+    factories: Vec<Box<dyn ParserFactory>>,
+}
+
+impl ParserRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a factory. Factories are tried in registration order.
+    pub fn register(&mut self, factory: Box<dyn ParserFactory>) -> &mut Self {
+        self.factories.push(factory);
+        self
+    }
+
+    /// Creates a parser with the first matching factory, falling back to the
+    /// built-in formats.
+    pub fn create_parser(&self, data: &[u8]) -> Result<ParsedBinary> {
+        match self.factories.iter().find(|factory| factory.matches(data)) {
+            Some(factory) => factory.create(data),
+            None => create_parser(data),
+        }
+    }
 }
 
 /// Creates the appropriate binary parser based on the detected format.
 pub fn create_parser(data: &[u8]) -> Result<ParsedBinary> {
     let obj = object::File::parse(data)?;
     let architecture = format!("{:?}", obj.architecture());
+    let pointer_width = if obj.is_64() { 8 } else { 4 };
 
     match obj.format() {
         BinaryFormat::Pe => {
-            let sections = collect_pe_sections(&obj);
+            let image_base = obj.relative_address_base();
+            let sections = collect_pe_sections(&obj, image_base);
+            let mut scan_sections = collect_pe_scan_sections(&obj, image_base);
+            if scan_sections.is_empty() {
+                log::warn!("PE has no .rdata section; scanning its data sections instead");
+                scan_sections = collect_segments(&obj)
+                    .1
+                    .into_iter()
+                    .map(|section| pe_relative_section(section, image_base))
+                    .collect();
+            }
             Ok(ParsedBinary {
-                parser: Box::new(PeParser::new(sections)?),
+                parser: Box::new(
+                    PeParser::new(image_base, sections, scan_sections)?
+                        .with_pointer_width(pointer_width),
+                ),
                 kind: BinaryKind::Pe,
                 architecture,
             })
         }
         BinaryFormat::MachO => {
-            let sections = collect_macho_sections(&obj);
+            let mut sections = collect_macho_sections(&obj);
+            let mut scan_sections = collect_macho_scan_sections(&obj);
+            if scan_sections.is_empty() {
+                log::warn!("Mach-O has no __const data sections; scanning its segments instead");
+                (sections, scan_sections) = collect_segments(&obj);
+            }
             Ok(ParsedBinary {
-                parser: Box::new(MachOParser::new(data, sections)?),
+                parser: Box::new(MachOParser::new(data, sections, scan_sections)?),
                 kind: BinaryKind::MachO,
                 architecture,
             })
         }
         BinaryFormat::Elf => {
-            let sections = collect_elf_sections(&obj);
-            let scan_sections = collect_elf_scan_sections(&obj);
-            let relative_relocations = collect_elf_relative_relocations(&obj, &sections);
+            let mut sections = collect_elf_sections(&obj);
+            let mut scan_sections = collect_elf_scan_sections(&obj);
+            if sections.is_empty() {
+                log::warn!("ELF has no section headers; mapping its program headers instead");
+                (sections, scan_sections) = collect_segments(&obj);
+            } else if scan_sections.is_empty() {
+                log::warn!("ELF has no data sections; scanning its segments instead");
+                scan_sections = collect_segments(&obj).1;
+            }
+            let mut relative_relocations = collect_elf_dynamic_relative_relocations(data);
+            for (offset, addend) in collect_elf_relative_relocations(&obj, &sections) {
+                relative_relocations.entry(offset).or_insert(addend);
+            }
             Ok(ParsedBinary {
-                parser: Box::new(ElfParser::new(
-                    sections,
-                    scan_sections,
-                    relative_relocations,
-                )?),
+                parser: Box::new(
+                    ElfParser::new(sections, scan_sections, relative_relocations)?
+                        .with_pointer_width(pointer_width),
+                ),
                 kind: BinaryKind::Elf,
                 architecture,
             })
@@ -117,6 +404,91 @@ pub fn create_parser(data: &[u8]) -> Result<ParsedBinary> {
     }
 }
 
+/// Returns the file ranges of the sections called `names`, in file order.
+///
+/// Mach-O sections can be qualified with their segment, as in
+/// `__DATA_CONST,__const`; an unqualified name matches every segment.
+pub fn section_ranges(data: &[u8], names: &[String]) -> Result<Vec<ScanRange>> {
+    let obj = object::File::parse(data)?;
+    let mut ranges = Vec::new();
+    for name in names {
+        let (segment, section_name) = match name.split_once(',') {
+            Some((segment, section)) => (Some(segment), section),
+            None => (None, name.as_str()),
+        };
+        let found = obj
+            .sections()
+            .filter(|s| s.name() == Ok(section_name))
+            .filter(|s| segment.is_none() || s.segment_name() == Ok(segment))
+            .filter_map(section_info)
+            .map(|s| ScanRange {
+                start: s.file_offset as usize,
+                length: s.size as usize,
+            })
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            return Err(Error::Message(format!("no section named {name}")));
+        }
+        ranges.extend(found);
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges.dedup_by_key(|range| range.start);
+    Ok(ranges)
+}
+
+/// Returns the file ranges of every read-only data section with its name,
+/// in file order. Mach-O names are qualified with their segment, as
+/// [`section_ranges`] accepts them.
+///
+/// Scans fall back to these when the sections the parser picks hold no
+/// assets, as in binaries built with GNU or MinGW toolchains or custom
+/// linker scripts.
+pub fn read_only_data_sections(data: &[u8]) -> Result<Vec<(String, ScanRange)>> {
+    let obj = object::File::parse(data)?;
+    let mut sections = obj
+        .sections()
+        .filter(|s| {
+            matches!(
+                s.kind(),
+                object::SectionKind::ReadOnlyData
+                    | object::SectionKind::ReadOnlyDataWithRel
+                    | object::SectionKind::ReadOnlyString
+            ) || s.name().is_ok_and(|name| name.starts_with(".data.rel.ro"))
+        })
+        .filter_map(|s| {
+            let name = match s.segment_name() {
+                Ok(Some(segment)) if obj.format() == BinaryFormat::MachO => {
+                    format!("{segment},{}", s.name().ok()?)
+                }
+                _ => s.name().ok()?.to_string(),
+            };
+            let info = section_info(s)?;
+            Some((
+                name,
+                ScanRange {
+                    start: info.file_offset as usize,
+                    length: info.size as usize,
+                },
+            ))
+        })
+        .collect::<Vec<_>>();
+    sections.sort_by_key(|(_, range)| range.start);
+    sections.dedup_by_key(|(_, range)| range.start);
+    Ok(sections)
+}
+
+/// Reads a little-endian pointer of `width` bytes, 4 or 8.
+pub(crate) fn read_pointer_sized(data: &[u8], offset: usize, width: usize) -> Result<u64> {
+    if width == 4 {
+        let end = offset.checked_add(4).ok_or(Error::PointerOutOfBounds)?;
+        let bytes = data.get(offset..end).ok_or(Error::PointerOutOfBounds)?;
+        return Ok(
+            u32::from_le_bytes(bytes.try_into().map_err(|_| Error::PointerOutOfBounds)?).into(),
+        );
+    }
+    read_u64(data, offset)
+}
+
 pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
     let end = offset.checked_add(8).ok_or(Error::PointerOutOfBounds)?;
     let bytes = data.get(offset..end).ok_or(Error::PointerOutOfBounds)?;
@@ -125,19 +497,29 @@ pub(crate) fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
     ))
 }
 
-fn collect_pe_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
+fn collect_pe_sections<'a>(obj: &object::File<'a>, image_base: u64) -> Vec<SectionInfo> {
+    obj.sections()
+        .filter_map(section_info)
+        .map(|section| pe_relative_section(section, image_base))
+        .collect()
+}
+
+fn collect_pe_scan_sections<'a>(obj: &object::File<'a>, image_base: u64) -> Vec<SectionInfo> {
     obj.sections()
         .filter(|s| s.name() == Ok(".rdata") && s.kind() == object::SectionKind::ReadOnlyData)
-        .filter_map(|s| {
-            Some(SectionInfo {
-                virtual_address: s.address(),
-                file_offset: s.file_range()?.0,
-                size: s.size(),
-            })
-        })
+        .filter_map(section_info)
+        .map(|section| pe_relative_section(section, image_base))
         .collect()
 }
 
+/// Rebases a PE section address onto its RVA.
+fn pe_relative_section(section: SectionInfo, image_base: u64) -> SectionInfo {
+    SectionInfo {
+        virtual_address: section.virtual_address.wrapping_sub(image_base),
+        ..section
+    }
+}
+
 fn collect_elf_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
     obj.sections()
         .filter(|s| s.address() != 0)
@@ -168,6 +550,47 @@ fn collect_elf_scan_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
     sections.into_iter().map(|(_, section)| section).collect()
 }
 
+/// Falls back to the segments of a binary whose section table is stripped
+/// or mangled, such as an ELF image read back from process memory: `PT_LOAD`
+/// program headers, Mach-O segment load commands, or PE sections whatever
+/// their names.
+///
+/// Every file-backed segment maps addresses, and the non-executable ones are
+/// scanned. If every segment is executable, as in some packed binaries, all
+/// of them are scanned. Mach-O `__LINKEDIT` holds only loader data and is
+/// left out.
+fn collect_segments<'a>(obj: &object::File<'a>) -> (Vec<SectionInfo>, Vec<SectionInfo>) {
+    let mut sections = Vec::new();
+    let mut scan_sections = Vec::new();
+    for segment in obj.segments() {
+        let (file_offset, size) = segment.file_range();
+        if size == 0 || segment.name() == Ok(Some("__LINKEDIT")) {
+            continue;
+        }
+        let executable = match segment.flags() {
+            SegmentFlags::Elf { p_flags } => p_flags & object::elf::PF_X != 0,
+            SegmentFlags::MachO { initprot, .. } => initprot & object::macho::VM_PROT_EXECUTE != 0,
+            SegmentFlags::Coff { characteristics } => {
+                characteristics & object::pe::IMAGE_SCN_MEM_EXECUTE != 0
+            }
+            _ => false,
+        };
+        let section = SectionInfo {
+            virtual_address: segment.address(),
+            file_offset,
+            size: size.min(segment.size()),
+        };
+        if !executable {
+            scan_sections.push(section.clone());
+        }
+        sections.push(section);
+    }
+    if scan_sections.is_empty() {
+        scan_sections = sections.clone();
+    }
+    (sections, scan_sections)
+}
+
 fn collect_elf_relative_relocations<'a>(
     obj: &object::File<'a>,
     sections: &[SectionInfo],
@@ -197,6 +620,187 @@ fn collect_elf_relative_relocations<'a>(
     relocations
 }
 
+/// `DT_RELR` tags, which `object` does not name yet.
+const DT_RELRSZ: u32 = 35;
+const DT_RELR: u32 = 36;
+const DT_RELRENT: u32 = 37;
+
+/// Collects `R_*_RELATIVE` addends from the relocation tables referenced by
+/// `PT_DYNAMIC`.
+///
+/// PIE executables and shared objects keep zero or link-time values in pointer
+/// slots and rely on the loader to apply these relocations. Reading `DT_RELA`,
+/// `DT_REL` and `DT_RELR` through the program headers finds them even when no
+/// section header describes the relocation tables. `DT_REL` and `DT_RELR`
+/// addends are the values already stored in the slots.
+fn collect_elf_dynamic_relative_relocations(data: &[u8]) -> HashMap<u64, u64> {
+    match object::FileKind::parse(data) {
+        Ok(object::FileKind::Elf32) => ElfFile32::<Endianness>::parse(data)
+            .map(|elf| dynamic_relative_relocations(&elf, data))
+            .unwrap_or_default(),
+        Ok(object::FileKind::Elf64) => ElfFile64::<Endianness>::parse(data)
+            .map(|elf| dynamic_relative_relocations(&elf, data))
+            .unwrap_or_default(),
+        _ => HashMap::new(),
+    }
+}
+
+/// A relocation table found in the dynamic segment.
+struct DynamicTable {
+    address: Option<u64>,
+    size: u64,
+    entry_size: u64,
+}
+
+impl DynamicTable {
+    fn new(entry_size: u64) -> Self {
+        Self {
+            address: None,
+            size: 0,
+            entry_size,
+        }
+    }
+
+    /// Returns the file offsets of the table's entries.
+    fn entries(&self, loads: &[SectionInfo], min_entry_size: u64) -> Vec<usize> {
+        let Some(table) = self.address.and_then(|va| va_to_file_offset(loads, va)) else {
+            return Vec::new();
+        };
+        if self.entry_size < min_entry_size {
+            return Vec::new();
+        }
+        (0..self.size / self.entry_size)
+            .map_while(|index| {
+                let offset = table.checked_add(index.checked_mul(self.entry_size)?)?;
+                usize::try_from(offset).ok()
+            })
+            .collect()
+    }
+}
+
+fn dynamic_relative_relocations<Elf>(elf: &ElfFile<'_, Elf>, data: &[u8]) -> HashMap<u64, u64>
+where
+    Elf: FileHeader<Endian = Endianness>,
+{
+    let mut relocations = HashMap::new();
+    let endian = elf.endian();
+    let machine = elf.elf_header().e_machine(endian);
+    let width: u64 = if elf.is_64() { 8 } else { 4 };
+    let loads = elf
+        .elf_program_headers()
+        .iter()
+        .filter(|segment| segment.p_type(endian) == object::elf::PT_LOAD)
+        .map(|segment| SectionInfo {
+            virtual_address: segment.p_vaddr(endian).into(),
+            file_offset: segment.p_offset(endian).into(),
+            size: segment.p_filesz(endian).into(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut rela = DynamicTable::new(3 * width);
+    let mut rel = DynamicTable::new(2 * width);
+    let mut relr = DynamicTable::new(width);
+    for segment in elf.elf_program_headers() {
+        let Ok(Some(dynamic)) = segment.dynamic(endian, data) else {
+            continue;
+        };
+        for entry in dynamic {
+            let value = entry.d_val(endian).into();
+            match entry.tag32(endian) {
+                Some(object::elf::DT_RELA) => rela.address = Some(value),
+                Some(object::elf::DT_RELASZ) => rela.size = value,
+                Some(object::elf::DT_RELAENT) => rela.entry_size = value,
+                Some(object::elf::DT_REL) => rel.address = Some(value),
+                Some(object::elf::DT_RELSZ) => rel.size = value,
+                Some(object::elf::DT_RELENT) => rel.entry_size = value,
+                Some(DT_RELR) => relr.address = Some(value),
+                Some(DT_RELRSZ) => relr.size = value,
+                Some(DT_RELRENT) => relr.entry_size = value,
+                _ => {}
+            }
+        }
+    }
+
+    let width = width as usize;
+    let read_word = |offset: usize| read_pointer_sized(data, offset, width);
+    // Records the value already stored at `address` as its addend.
+    let record_implicit = |relocations: &mut HashMap<u64, u64>, address: u64| {
+        if let Some(file_offset) = va_to_file_offset(&loads, address) {
+            if let Ok(addend) = read_word(file_offset as usize) {
+                relocations.insert(file_offset, addend);
+            }
+        }
+    };
+
+    for (table, explicit_addend) in [(&rela, true), (&rel, false)] {
+        let min_entry_size = if explicit_addend { 3 } else { 2 } * width as u64;
+        for entry in table.entries(&loads, min_entry_size) {
+            let (Ok(r_offset), Ok(r_info)) = (read_word(entry), read_word(entry + width)) else {
+                break;
+            };
+            // ELF32_R_TYPE keeps the low 8 bits, ELF64_R_TYPE the low 32.
+            let r_type = if width == 8 {
+                r_info as u32
+            } else {
+                r_info as u8 as u32
+            };
+            if !is_elf_relative_type(machine, r_type) {
+                continue;
+            }
+            if !explicit_addend {
+                record_implicit(&mut relocations, r_offset);
+                continue;
+            }
+            let Ok(r_addend) = read_word(entry + 2 * width) else {
+                break;
+            };
+            if let Some(file_offset) = va_to_file_offset(&loads, r_offset) {
+                relocations.insert(file_offset, r_addend);
+            }
+        }
+    }
+
+    // An even RELR entry is an address to relocate; an odd one is a bitmap
+    // of the `width * 8 - 1` words that follow the last relocated run.
+    let mut next = 0u64;
+    for entry in relr.entries(&loads, width as u64) {
+        let Ok(word) = read_word(entry) else {
+            break;
+        };
+        if word & 1 == 0 {
+            record_implicit(&mut relocations, word);
+            next = word.wrapping_add(width as u64);
+            continue;
+        }
+        let mut bitmap = word >> 1;
+        let mut address = next;
+        while bitmap != 0 {
+            if bitmap & 1 != 0 {
+                record_implicit(&mut relocations, address);
+            }
+            bitmap >>= 1;
+            address = address.wrapping_add(width as u64);
+        }
+        next = next.wrapping_add((width as u64 * 8 - 1) * width as u64);
+    }
+
+    relocations
+}
+
+fn is_elf_relative_type(machine: u16, r_type: u32) -> bool {
+    match machine {
+        object::elf::EM_386 => r_type == object::elf::R_386_RELATIVE,
+        object::elf::EM_ARM => r_type == object::elf::R_ARM_RELATIVE,
+        object::elf::EM_X86_64 => {
+            r_type == object::elf::R_X86_64_RELATIVE || r_type == object::elf::R_X86_64_RELATIVE64
+        }
+        object::elf::EM_AARCH64 => r_type == object::elf::R_AARCH64_RELATIVE,
+        object::elf::EM_RISCV => r_type == object::elf::R_RISCV_RELATIVE,
+        object::elf::EM_LOONGARCH => r_type == object::elf::R_LARCH_RELATIVE,
+        _ => false,
+    }
+}
+
 fn relative_relocation_file_offset(
     sections: &[SectionInfo],
     address: u64,
@@ -255,11 +859,17 @@ fn collect_macho_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
     // - __TEXT,__const: contains string literals (asset names and data)
     // - __DATA_CONST,__const: contains asset headers (modern layout)
     // - __DATA,__const: contains asset headers (alternative layout)
+    // - __AUTH_CONST,__const and __AUTH,__const: the same on arm64e, where
+    //   sections holding authenticated pointers move to these segments
     obj.sections()
         .filter(|s| {
             matches!(
                 s.segment_name(),
-                Ok(Some("__TEXT")) | Ok(Some("__DATA_CONST")) | Ok(Some("__DATA"))
+                Ok(Some("__TEXT"))
+                    | Ok(Some("__DATA_CONST"))
+                    | Ok(Some("__DATA"))
+                    | Ok(Some("__AUTH_CONST"))
+                    | Ok(Some("__AUTH"))
             )
         })
         .filter(|s| s.name() == Ok("__const"))
@@ -272,3 +882,23 @@ fn collect_macho_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
         })
         .collect()
 }
+
+fn collect_macho_scan_sections<'a>(obj: &object::File<'a>) -> Vec<SectionInfo> {
+    // Asset headers hold pointers, so they live in a data segment. Depending on
+    // the linker they end up in __DATA_CONST,__const, __DATA,__const, or both,
+    // and on arm64e in __AUTH_CONST,__const or __AUTH,__const.
+    // __TEXT,__const only holds the string and blob literals they point to.
+    obj.sections()
+        .filter(|s| {
+            matches!(
+                s.segment_name(),
+                Ok(Some("__DATA_CONST"))
+                    | Ok(Some("__DATA"))
+                    | Ok(Some("__AUTH_CONST"))
+                    | Ok(Some("__AUTH"))
+            )
+        })
+        .filter(|s| s.name() == Ok("__const"))
+        .filter_map(section_info)
+        .collect()
+}
@@ -4,44 +4,83 @@ use super::{BinaryParser, ScanRange, SectionInfo};
 use crate::error::{Error, Result};
 
 /// PE binary parser.
+///
+/// Pointers stored in a PE image are absolute virtual addresses based on the
+/// `ImageBase` chosen by the linker. They are converted to RVAs before being
+/// looked up in the section table, so images linked at a non-default base
+/// resolve the same way as default-base builds.
 pub struct PeParser {
+    image_base: u64,
     sections: Vec<SectionInfo>,
+    scan_sections: Vec<SectionInfo>,
+    pointer_width: usize,
 }
 
 impl PeParser {
-    /// Creates a new PE parser with the given sections.
-    pub fn new(sections: Vec<SectionInfo>) -> Result<Self> {
-        if sections.is_empty() {
+    /// Creates a new PE parser.
+    ///
+    /// `sections` are all file-backed sections and `scan_sections` are the
+    /// sections searched for asset headers. Section virtual addresses are RVAs.
+    pub fn new(
+        image_base: u64,
+        sections: Vec<SectionInfo>,
+        scan_sections: Vec<SectionInfo>,
+    ) -> Result<Self> {
+        if scan_sections.is_empty() {
             return Err(Error::NoAssetSection("PE .rdata".to_string()));
         }
-        Ok(Self { sections })
+        Ok(Self {
+            image_base,
+            sections,
+            scan_sections,
+            pointer_width: 8,
+        })
+    }
+
+    /// Sets the pointer size, 4 for PE32 images.
+    pub fn with_pointer_width(mut self, pointer_width: usize) -> Self {
+        self.pointer_width = pointer_width;
+        self
+    }
+
+    /// Returns the `ImageBase` read from the optional header.
+    pub fn image_base(&self) -> u64 {
+        self.image_base
+    }
+
+    /// Converts a relative virtual address to a file offset.
+    fn rva_to_file_offset(&self, rva: u64) -> Option<u64> {
+        self.sections
+            .iter()
+            .find(|s| rva >= s.virtual_address && rva < s.virtual_address + s.size)
+            .map(|s| rva - s.virtual_address + s.file_offset)
     }
 }
 
 impl BinaryParser for PeParser {
+    fn pointer_width(&self) -> usize {
+        self.pointer_width
+    }
+
     fn resolve_pointer(&self, raw_ptr: u64) -> Result<u64> {
-        // PE pointers are virtual addresses relative to image base
-        let section = self
-            .sections
-            .first()
-            .ok_or_else(|| Error::NoAssetSection("PE .rdata".to_string()))?;
-
-        if raw_ptr >= section.virtual_address && raw_ptr < section.virtual_address + section.size {
-            Ok(raw_ptr - section.virtual_address + section.file_offset)
-        } else {
-            Err(Error::AddressNotMapped(raw_ptr))
-        }
+        raw_ptr
+            .checked_sub(self.image_base)
+            .and_then(|rva| self.rva_to_file_offset(rva))
+            .ok_or(Error::AddressNotMapped(raw_ptr))
+    }
+
+    fn sections(&self) -> &[SectionInfo] {
+        &self.sections
     }
 
     fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
-        let section = self
-            .sections
-            .first()
-            .ok_or_else(|| Error::NoAssetSection("PE .rdata".to_string()))?;
-
-        Ok(vec![ScanRange {
-            start: section.file_offset as usize,
-            length: section.size as usize,
-        }])
+        Ok(self
+            .scan_sections
+            .iter()
+            .map(|section| ScanRange {
+                start: section.file_offset as usize,
+                length: section.size as usize,
+            })
+            .collect())
     }
 }
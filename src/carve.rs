@@ -0,0 +1,578 @@
+//! Carving of embedded files by their magic bytes.
+//!
+//! Applications that embed files with `include_bytes!` leave no table to
+//! follow, but many file formats are self-delimiting. Each signature found in
+//! the binary is parsed far enough to find where the file ends; candidates
+//! that do not parse are dropped. Raw Brotli streams have no signature and
+//! are not carved, and gzip members are only carved with the `gzip` feature.
+//!
+//! [`carve_assets`] goes further for binaries whose asset table is not
+//! recognized: it tries to decode a Brotli stream at every offset, keeping
+//! the streams that end cleanly and decode to text or a known file format,
+//! and returns them and any gzip members as assets.
+
+use crate::asset::{Asset, AssetLocation};
+use crate::binary::ScanRange;
+use crate::bytes::{be_u32, le_u16, le_u32};
+use crate::codec::AssetCodec;
+#[cfg(feature = "brotli")]
+use crate::codec::BrotliCodec;
+#[cfg(feature = "gzip")]
+use crate::codec::GzipCodec;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "brotli")]
+use std::cell::RefCell;
+#[cfg(feature = "gzip")]
+use std::io::Read;
+#[cfg(feature = "brotli")]
+use std::rc::Rc;
+use std::sync::Arc;
+
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+const WASM_SIGNATURE: &[u8; 8] = b"\0asm\x01\0\0\0";
+#[cfg(feature = "gzip")]
+const GZIP_SIGNATURE: &[u8; 3] = b"\x1f\x8b\x08";
+const ZIP_LOCAL_HEADER: &[u8; 4] = b"PK\x03\x04";
+const ZIP_END_OF_CENTRAL_DIRECTORY: &[u8; 4] = b"PK\x05\x06";
+const ZIP_END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+/// Highest known WebAssembly section id (`tag`).
+const WASM_MAX_SECTION_ID: u8 = 13;
+/// Decoded bytes a carved stream must start with text or a known signature
+/// within.
+const STREAM_PROBE_SIZE: usize = 512;
+/// Carved text must use at least this many distinct bytes.
+const MIN_TEXT_ALPHABET: usize = 16;
+/// Streams that decode to more than this are dropped.
+#[cfg(any(feature = "brotli", feature = "gzip"))]
+const MAX_STREAM_OUTPUT: usize = 256 * 1024 * 1024;
+/// Signatures of binary formats that carved streams may decode to, with the
+/// extension they are named with.
+const STREAM_SIGNATURES: &[(&[u8], &str)] = &[
+    (PNG_SIGNATURE, "png"),
+    (WASM_SIGNATURE, "wasm"),
+    (b"\xff\xd8\xff", "jpg"),
+    (b"GIF8", "gif"),
+    (b"wOFF", "woff"),
+    (b"wOF2", "woff2"),
+    (b"OTTO", "otf"),
+    (b"%PDF-", "pdf"),
+];
+
+/// The format of a carved file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CarveKind {
+    Png,
+    Wasm,
+    Gzip,
+    Zip,
+}
+
+impl CarveKind {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Wasm => "wasm",
+            Self::Gzip => "gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// A file found by [`carve`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CarvedFile {
+    /// Synthesized name derived from the file offset and format.
+    pub name: String,
+    pub kind: CarveKind,
+    pub offset: usize,
+    #[serde(skip)]
+    pub data: Vec<u8>,
+    pub size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CarveOptions {
+    /// Files smaller than this are ignored.
+    pub min_size: usize,
+}
+
+impl Default for CarveOptions {
+    fn default() -> Self {
+        Self { min_size: 1024 }
+    }
+}
+
+/// Finds embedded files in `data`.
+///
+/// Files are reported in offset order and never overlap; a file nested in an
+/// earlier one is part of it.
+pub fn carve(data: &[u8], options: &CarveOptions) -> Vec<CarvedFile> {
+    let mut files = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let rest = &data[offset..];
+        let found = match rest[0] {
+            0x89 => png_len(rest).map(|len| (CarveKind::Png, len)),
+            0x00 => wasm_len(rest).map(|len| (CarveKind::Wasm, len)),
+            #[cfg(feature = "gzip")]
+            0x1f => gzip_len(rest).map(|len| (CarveKind::Gzip, len)),
+            b'P' => zip_len(rest).map(|len| (CarveKind::Zip, len)),
+            _ => None,
+        };
+        match found {
+            Some((kind, len)) if len < options.min_size => {
+                log::debug!("skipping {len}-byte {kind:?} file at {offset:#x}");
+                offset += 1;
+            }
+            Some((kind, len)) => {
+                files.push(CarvedFile {
+                    name: format!("{offset:08x}.{}", kind.extension()),
+                    kind,
+                    offset,
+                    data: rest[..len].to_vec(),
+                    size: len,
+                });
+                offset += len;
+            }
+            _ => offset += 1,
+        }
+    }
+    files
+}
+
+/// Finds standalone Brotli streams and gzip members in `data` and returns
+/// them as assets named after their offset and decoded content, such as
+/// `/carved/00a1b2c0.js`.
+///
+/// Streams are reported in offset order and never overlap. Streams smaller
+/// than [`CarveOptions::min_size`] in `data` are ignored. So are text
+/// streams no smaller than their content, which no encoder emits for text
+/// but stray bytes followed by a string can parse as, and text of only a
+/// few distinct characters, which stray bytes decode to as long runs.
+pub fn carve_assets(data: &[u8], options: &CarveOptions) -> Vec<Asset> {
+    #[cfg(feature = "brotli")]
+    let brotli = BrotliProber::default();
+    let mut assets = Vec::new();
+    let mut offset = 0;
+    while offset < data.len() {
+        let rest = &data[offset..];
+        let found: Option<(Arc<dyn AssetCodec>, usize, Vec<u8>)> = match rest[0] {
+            #[cfg(feature = "gzip")]
+            0x1f => gzip_stream(rest)
+                .map(|(len, decoded)| (Arc::new(GzipCodec) as Arc<dyn AssetCodec>, len, decoded)),
+            _ => None,
+        };
+        #[cfg(feature = "brotli")]
+        let found = found.or_else(|| {
+            brotli
+                .stream(rest)
+                .map(|(len, decoded)| (Arc::new(BrotliCodec) as Arc<dyn AssetCodec>, len, decoded))
+        });
+        let Some((codec, len, decoded)) = found.filter(|(_, len, decoded)| {
+            *len >= options.min_size
+                && plausible_content(decoded)
+                && !(looks_like_text(decoded) && (*len >= decoded.len() || repetitive(decoded)))
+        }) else {
+            offset += 1;
+            continue;
+        };
+        let name = format!("/carved/{offset:08x}.{}", content_extension(&decoded));
+        log::debug!("carved {codec:?} stream {name} of {len} bytes");
+        let location = AssetLocation {
+            header_offset: offset,
+            name_offset: offset,
+            data_offset: offset,
+            data_size_offset: offset,
+            original_compressed_size: len,
+            scan_range: ScanRange {
+                start: 0,
+                length: data.len(),
+            },
+            table_index: 0,
+            content_hash_offset: None,
+            word_size: 8,
+        };
+        assets.push(
+            Asset::new(name, rest[..len].to_vec(), decoded.len(), location).with_codec(codec),
+        );
+        offset += len;
+    }
+    assets
+}
+
+/// Decodes Brotli streams at successive offsets.
+///
+/// A decoder allocates its ring buffer, of up to 16 MiB, once it has read a
+/// metablock header, and most offsets hold a valid one. Freed buffers are
+/// handed to the next decoder rather than allocated and zeroed again, and
+/// input is fed in chunks, so failing at an offset costs a microsecond or
+/// two.
+#[cfg(feature = "brotli")]
+#[derive(Default)]
+struct BrotliProber {
+    bytes: BufferPool<u8>,
+    words: BufferPool<u32>,
+    codes: BufferPool<brotli::HuffmanCode>,
+}
+
+#[cfg(feature = "brotli")]
+impl BrotliProber {
+    /// Input fed to the decoder at a time.
+    const INPUT_CHUNK: usize = 1024;
+
+    /// Decodes the Brotli stream at the start of `data`, returning the
+    /// number of bytes it occupies and its content. Decoding stops early
+    /// unless the content starts like text or a known file format.
+    fn stream(&self, data: &[u8]) -> Option<(usize, Vec<u8>)> {
+        use brotli::{BrotliDecompressStream, BrotliResult, BrotliState};
+
+        let mut state =
+            BrotliState::new(self.bytes.clone(), self.words.clone(), self.codes.clone());
+        // Large windows are a nonstandard extension no web asset uses, and
+        // one would have stray bytes allocate up to a gigabyte.
+        state.large_window = false;
+        let mut input_end = data.len().min(Self::INPUT_CHUNK);
+        let mut available_in = input_end;
+        let mut input_offset = 0;
+        let mut total_out = 0;
+        let mut buffer = vec![0; STREAM_PROBE_SIZE];
+        let mut decoded = Vec::new();
+        loop {
+            let mut available_out = buffer.len();
+            let mut output_offset = 0;
+            let result = BrotliDecompressStream(
+                &mut available_in,
+                &mut input_offset,
+                &data[..input_end],
+                &mut available_out,
+                &mut output_offset,
+                &mut buffer,
+                &mut total_out,
+                &mut state,
+            );
+            decoded.extend_from_slice(&buffer[..output_offset]);
+            match result {
+                BrotliResult::ResultSuccess => return Some((input_offset, decoded)),
+                BrotliResult::NeedsMoreOutput
+                    if decoded.len() < MAX_STREAM_OUTPUT
+                        && (decoded.len() > STREAM_PROBE_SIZE || plausible_content(&decoded)) =>
+                {
+                    if buffer.len() < 64 * 1024 {
+                        buffer.resize(64 * 1024, 0);
+                    }
+                }
+                // A stream cut short by the end of the file is not carved.
+                BrotliResult::NeedsMoreInput
+                    if input_end < data.len()
+                        && (!decoded.is_empty() || Self::plausible_pending(&state)) =>
+                {
+                    input_end = data.len().min(input_end + Self::INPUT_CHUNK.max(input_end));
+                    available_in = input_end - input_offset;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Checks what a decoder holds in its ring buffer before writing any of
+    /// it out, which it only does once the buffer wraps or a metablock ends.
+    fn plausible_pending(
+        state: &brotli::BrotliState<
+            BufferPool<u8>,
+            BufferPool<u32>,
+            BufferPool<brotli::HuffmanCode>,
+        >,
+    ) -> bool {
+        use brotli::SliceWrapper;
+
+        let pending = state.ringbuffer.slice();
+        let len = usize::try_from(state.pos).unwrap_or(0).min(pending.len());
+        // Encoders emit no metadata blocks, which the decoder skips without
+        // decoding anything.
+        len > 0 && plausible_content(&pending[..len])
+    }
+}
+
+/// A Brotli allocator that keeps freed buffers for reuse. Clones share the
+/// buffers.
+#[cfg(feature = "brotli")]
+#[derive(Default)]
+struct BufferPool<T>(Rc<RefCell<Vec<Vec<T>>>>);
+
+#[cfg(feature = "brotli")]
+impl<T> Clone for BufferPool<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+/// A buffer from a [`BufferPool`], of which the first `len` elements are
+/// in use.
+#[cfg(feature = "brotli")]
+#[derive(Default)]
+struct PooledBuffer<T> {
+    buffer: Vec<T>,
+    len: usize,
+}
+
+#[cfg(feature = "brotli")]
+impl<T> brotli::SliceWrapper<T> for PooledBuffer<T> {
+    fn slice(&self) -> &[T] {
+        &self.buffer[..self.len]
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl<T> brotli::SliceWrapperMut<T> for PooledBuffer<T> {
+    fn slice_mut(&mut self) -> &mut [T] {
+        &mut self.buffer[..self.len]
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl<T: Clone + Default> brotli::Allocator<T> for BufferPool<T> {
+    type AllocatedMemory = PooledBuffer<T>;
+
+    /// Hands out the smallest free buffer that fits. Like the C decoder's
+    /// `malloc`, it may hold stale data, which the decoder overwrites
+    /// before reading.
+    fn alloc_cell(&mut self, len: usize) -> PooledBuffer<T> {
+        let mut pool = self.0.borrow_mut();
+        let fitting = pool
+            .iter()
+            .enumerate()
+            .filter(|(_, buffer)| buffer.len() >= len)
+            .min_by_key(|(_, buffer)| buffer.len())
+            .map(|(index, _)| index);
+        let buffer = match fitting {
+            Some(index) => pool.swap_remove(index),
+            None => vec![T::default(); len],
+        };
+        PooledBuffer { buffer, len }
+    }
+
+    fn free_cell(&mut self, cell: PooledBuffer<T>) {
+        if !cell.buffer.is_empty() {
+            self.0.borrow_mut().push(cell.buffer);
+        }
+    }
+}
+
+/// Decodes the gzip member at the start of `data`, returning the number of
+/// bytes it occupies and its content.
+#[cfg(feature = "gzip")]
+fn gzip_stream(data: &[u8]) -> Option<(usize, Vec<u8>)> {
+    if !data.starts_with(GZIP_SIGNATURE) {
+        return None;
+    }
+    let mut remaining = data;
+    let mut decoder = flate2::bufread::GzDecoder::new(&mut remaining);
+    let mut decoded = Vec::new();
+    decoder
+        .by_ref()
+        .take(MAX_STREAM_OUTPUT as u64)
+        .read_to_end(&mut decoded)
+        .ok()?;
+    drop(decoder);
+    Some((data.len() - remaining.len(), decoded))
+}
+
+/// Returns true if `decoded` starts like text or a known file format.
+pub(crate) fn plausible_content(decoded: &[u8]) -> bool {
+    let probe = &decoded[..decoded.len().min(STREAM_PROBE_SIZE)];
+    !probe.is_empty()
+        && (STREAM_SIGNATURES
+            .iter()
+            .any(|(signature, _)| probe.starts_with(signature))
+            || looks_like_text(probe))
+}
+
+/// Returns true if `text` uses fewer than [`MIN_TEXT_ALPHABET`] distinct
+/// bytes, like the runs of a few characters that stray bytes decode to.
+fn repetitive(text: &[u8]) -> bool {
+    let mut seen = [false; 256];
+    let mut distinct = 0;
+    for &byte in text {
+        if !std::mem::replace(&mut seen[usize::from(byte)], true) {
+            distinct += 1;
+            if distinct >= MIN_TEXT_ALPHABET {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Returns true if `data` is UTF-8 text without control characters other
+/// than whitespace, allowing a character cut off at the end.
+fn looks_like_text(data: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(error) if error.error_len().is_none() => {
+            // Checked by `from_utf8`.
+            std::str::from_utf8(&data[..error.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    valid
+        .chars()
+        .all(|ch| !ch.is_control() || matches!(ch, '\t' | '\n' | '\r' | '\u{c}'))
+}
+
+/// Names the format of carved content from its leading bytes.
+fn content_extension(decoded: &[u8]) -> &'static str {
+    if let Some((_, extension)) = STREAM_SIGNATURES
+        .iter()
+        .find(|(signature, _)| decoded.starts_with(signature))
+    {
+        return extension;
+    }
+    let probe = String::from_utf8_lossy(&decoded[..decoded.len().min(STREAM_PROBE_SIZE)])
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .to_ascii_lowercase();
+    if probe.starts_with("<!doctype html") || probe.starts_with("<html") {
+        "html"
+    } else if probe.starts_with("<svg") || (probe.starts_with("<?xml") && probe.contains("<svg")) {
+        "svg"
+    } else if probe.starts_with("<?xml") {
+        "xml"
+    } else if (probe.starts_with('{') || probe.starts_with('['))
+        && serde_json::from_slice::<serde::de::IgnoredAny>(decoded).is_ok()
+    {
+        "json"
+    } else if [
+        "import",
+        "export",
+        "function",
+        "const ",
+        "var ",
+        "let ",
+        "(function",
+        "!function",
+        "\"use strict\"",
+    ]
+    .iter()
+    .any(|start| probe.starts_with(start))
+    {
+        "js"
+    } else if probe.starts_with('@') || probe.starts_with(":root") || css_rule(&probe) {
+        "css"
+    } else {
+        "txt"
+    }
+}
+
+/// Returns true if `text` starts with a CSS rule: a selector and a
+/// declaration block.
+fn css_rule(text: &str) -> bool {
+    text.split_once('{').is_some_and(|(selector, block)| {
+        !selector.is_empty()
+            && !selector.contains([';', '(', '='])
+            && block
+                .split_once('}')
+                .map_or(block, |(declarations, _)| declarations)
+                .contains(':')
+    })
+}
+
+/// Walks PNG chunks up to `IEND`.
+fn png_len(data: &[u8]) -> Option<usize> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return None;
+    }
+    let mut at = PNG_SIGNATURE.len();
+    loop {
+        let len = be_u32(data, at)? as usize;
+        let kind = data.get(at + 4..at + 8)?;
+        if !kind.iter().all(u8::is_ascii_alphabetic) {
+            return None;
+        }
+        // Length, type, data and CRC.
+        at = at.checked_add(12 + len)?;
+        if at > data.len() {
+            return None;
+        }
+        if kind == b"IEND" {
+            return Some(at);
+        }
+    }
+}
+
+/// Walks WebAssembly sections until the next byte is not a section.
+fn wasm_len(data: &[u8]) -> Option<usize> {
+    if !data.starts_with(WASM_SIGNATURE) {
+        return None;
+    }
+    let mut at = WASM_SIGNATURE.len();
+    let mut sections = 0;
+    while let Some(&id) = data.get(at) {
+        if id > WASM_MAX_SECTION_ID {
+            break;
+        }
+        let Some((size, size_len)) = read_leb128(&data[at + 1..]) else {
+            break;
+        };
+        let Some(end) = (at + 1 + size_len)
+            .checked_add(size)
+            .filter(|&end| end <= data.len())
+        else {
+            break;
+        };
+        at = end;
+        sections += 1;
+    }
+    (sections > 0).then_some(at)
+}
+
+fn read_leb128(data: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (index, &byte) in data.iter().take(5).enumerate() {
+        value |= usize::from(byte & 0x7f) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+    }
+    None
+}
+
+/// Decodes one gzip member and returns the number of bytes it occupies.
+#[cfg(feature = "gzip")]
+fn gzip_len(data: &[u8]) -> Option<usize> {
+    if !data.starts_with(GZIP_SIGNATURE) {
+        return None;
+    }
+    let mut remaining = data;
+    let mut decoder = flate2::bufread::GzDecoder::new(&mut remaining);
+    std::io::copy(&mut decoder.by_ref(), &mut std::io::sink()).ok()?;
+    drop(decoder);
+    Some(data.len() - remaining.len())
+}
+
+/// Finds the end of central directory record that closes the archive.
+fn zip_len(data: &[u8]) -> Option<usize> {
+    if !data.starts_with(ZIP_LOCAL_HEADER) {
+        return None;
+    }
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], ZIP_END_OF_CENTRAL_DIRECTORY) {
+        let eocd = search + found;
+        let directory_size = le_u32(data, eocd + 12)? as usize;
+        let directory_offset = le_u32(data, eocd + 16)? as usize;
+        let comment_len = usize::from(le_u16(data, eocd + 20)?);
+        let end = eocd + ZIP_END_OF_CENTRAL_DIRECTORY_SIZE + comment_len;
+        // Offsets are relative to the archive start, which is `data[0]`.
+        if directory_offset + directory_size == eocd && end <= data.len() {
+            return Some(end);
+        }
+        search = eocd + 1;
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
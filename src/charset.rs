@@ -0,0 +1,187 @@
+//! Text encodings and line endings of text assets.
+//!
+//! Frontends are almost always UTF-8, but assets copied from older projects
+//! can be UTF-16 or in a legacy code page such as windows-1252 or
+//! Shift_JIS, which `grep` and `diff` then mangle. Encodings are recognized
+//! by their byte order mark, by the zero bytes of UTF-16 without a mark, by
+//! validating as UTF-8, and otherwise guessed with `chardetng`, the
+//! detector of Firefox.
+//!
+//! Line endings can be normalized too, so assets diff cleanly against a
+//! source repository checked out with other endings.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::mime::mime_type;
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::{Deserialize, Serialize};
+
+/// The encoding of a text asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextCharset {
+    pub name: String,
+    pub mime: String,
+    /// The name of the encoding, as in the WHATWG Encoding Standard, such
+    /// as `UTF-8` or `windows-1252`.
+    pub encoding: String,
+    /// Whether the asset starts with a byte order mark.
+    pub bom: bool,
+}
+
+impl TextCharset {
+    /// Whether the asset is UTF-8 without a byte order mark, and so is left
+    /// as is by [`transcode`].
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == UTF_8.name() && !self.bom
+    }
+}
+
+/// A text asset converted to UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conversion {
+    pub name: String,
+    /// The encoding the asset was in.
+    pub from: String,
+    /// Whether the asset had bytes invalid in that encoding, which were
+    /// replaced with U+FFFD.
+    pub replaced: bool,
+}
+
+/// Text decoded from another encoding or with its byte order mark removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcoded {
+    pub from: &'static Encoding,
+    pub text: String,
+    pub replaced: bool,
+}
+
+/// Line endings text can be normalized to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+pub use crate::mime::is_text;
+
+/// Detects the encoding of `data`, returning it with the length of its byte
+/// order mark, or 0 if it has none.
+pub fn detect(data: &[u8]) -> (&'static Encoding, usize) {
+    if let Some((encoding, bom)) = Encoding::for_bom(data) {
+        return (encoding, bom);
+    }
+    // ASCII in UTF-16 is valid UTF-8 too, zero bytes and all.
+    if let Some(encoding) = utf16_without_bom(data) {
+        return (encoding, 0);
+    }
+    if std::str::from_utf8(data).is_ok() {
+        return (UTF_8, 0);
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(data, true);
+    (detector.guess(None, true), 0)
+}
+
+/// Recognizes UTF-16 without a byte order mark by its ASCII characters,
+/// which have a zero byte on the same side. Legacy code pages and UTF-8
+/// never contain zero bytes in text.
+fn utf16_without_bom(data: &[u8]) -> Option<&'static Encoding> {
+    let units = data.len() / 2;
+    if units < 2 {
+        return None;
+    }
+    let zeros = |offset: usize| {
+        data.iter()
+            .skip(offset)
+            .step_by(2)
+            .take(units)
+            .filter(|&&byte| byte == 0)
+            .count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+    // Most characters of a frontend are ASCII, but not all of them.
+    if odd * 4 >= units * 3 && even * 4 < units {
+        Some(UTF_16LE)
+    } else if even * 4 >= units * 3 && odd * 4 < units {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Decodes `data` to UTF-8 text, or returns `None` if it already is UTF-8
+/// without a byte order mark.
+pub fn transcode(data: &[u8]) -> Option<Transcoded> {
+    let (encoding, bom) = detect(data);
+    if encoding == UTF_8 && bom == 0 {
+        return None;
+    }
+    let (text, replaced) = encoding.decode_without_bom_handling(&data[bom..]);
+    Some(Transcoded {
+        from: encoding,
+        text: text.into_owned(),
+        replaced,
+    })
+}
+
+/// Rewrites every line ending of `text`, whether `\r\n`, `\n` or a lone
+/// `\r`, as `eol`, or returns `None` if they all already are.
+pub fn normalize_eol(text: &str, eol: LineEnding) -> Option<String> {
+    let mut normalized = String::with_capacity(text.len());
+    let mut changed = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let found = match c {
+            '\r' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                LineEnding::Crlf
+            }
+            '\r' => {
+                // An old Mac line ending, never what is asked for.
+                changed = true;
+                normalized.push_str(eol.as_str());
+                continue;
+            }
+            '\n' => LineEnding::Lf,
+            c => {
+                normalized.push(c);
+                continue;
+            }
+        };
+        changed |= found != eol;
+        normalized.push_str(eol.as_str());
+    }
+    changed.then_some(normalized)
+}
+
+/// Decodes every text asset of `table` and detects its encoding, in table
+/// order.
+pub fn analyze(table: &AssetTable) -> Result<Vec<TextCharset>> {
+    let mut charsets = Vec::new();
+    for asset in table.assets() {
+        let mime = mime_type(asset.name());
+        if asset.alias_of().is_some() || !is_text(mime) {
+            continue;
+        }
+        let (encoding, bom) = detect(&decompress_asset(asset)?);
+        charsets.push(TextCharset {
+            name: asset.name().to_string(),
+            mime: mime.to_string(),
+            encoding: encoding.name().to_string(),
+            bom: bom > 0,
+        });
+    }
+    Ok(charsets)
+}
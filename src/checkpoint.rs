@@ -0,0 +1,135 @@
+//! Saved progress of a Tauri scan, so that an interrupted scan of a huge
+//! binary resumes where it stopped rather than from the start.
+//!
+//! The scan saves its position and the headers found so far every
+//! [`CheckpointOptions::interval`] and removes the file once it completes. A
+//! checkpoint only resumes a scan of the same binary with the same options;
+//! any other is ignored with a warning.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointOptions {
+    pub path: PathBuf,
+    /// Resumes from `path` if it holds a checkpoint of this scan.
+    pub resume: bool,
+    pub interval: Duration,
+}
+
+impl CheckpointOptions {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            resume: false,
+            interval: DEFAULT_CHECKPOINT_INTERVAL,
+        }
+    }
+}
+
+/// Where a scan stopped and the headers it had found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ScanCheckpoint {
+    pub version: u32,
+    /// SHA-256 of the binary.
+    pub sha256: String,
+    /// Hash of the scan ranges, header layouts and options that decide what
+    /// the scan finds.
+    pub fingerprint: String,
+    /// Index of the scan range the scan was in.
+    pub range: usize,
+    /// The next offset to try.
+    pub offset: usize,
+    pub step: usize,
+    pub table_index: usize,
+    /// End of the last header found and the index of its layout.
+    pub previous_header: Option<(usize, usize)>,
+    pub headers: Vec<SavedHeader>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SavedHeader {
+    pub offset: usize,
+    /// Index of the header layout that parsed it.
+    pub layout: usize,
+    pub table_index: usize,
+}
+
+impl ScanCheckpoint {
+    pub fn new(sha256: &str, fingerprint: &str) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            sha256: sha256.to_string(),
+            fingerprint: fingerprint.to_string(),
+            range: 0,
+            offset: 0,
+            step: 0,
+            table_index: 0,
+            previous_header: None,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Reads the checkpoint at `path` if there is one for this scan.
+    pub fn load(path: &Path, sha256: &str, fingerprint: &str) -> Option<Self> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                log::warn!("cannot read scan checkpoint {}: {error}", path.display());
+                return None;
+            }
+        };
+        match serde_json::from_slice::<Self>(&data) {
+            Ok(checkpoint)
+                if checkpoint.version == CHECKPOINT_VERSION
+                    && checkpoint.sha256 == sha256
+                    && checkpoint.fingerprint == fingerprint =>
+            {
+                Some(checkpoint)
+            }
+            Ok(_) => {
+                log::warn!(
+                    "scan checkpoint {} is for another binary or other scan options; \
+                     starting over",
+                    path.display()
+                );
+                None
+            }
+            Err(error) => {
+                log::warn!("ignoring scan checkpoint {}: {error}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Removes the checkpoint at `path` once its scan has completed.
+    pub fn clear(path: &Path) {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                log::warn!("cannot remove scan checkpoint {}: {error}", path.display());
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes the checkpoint to a temporary file and renames it over `path`,
+    /// so an interruption never leaves a partial checkpoint.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, serde_json::to_vec(self)?)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+}
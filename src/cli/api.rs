@@ -0,0 +1,489 @@
+//! A REST API for submitting binaries and downloading their assets, for the
+//! `serve-api` command.
+//!
+//! | Request | Response |
+//! | --- | --- |
+//! | `POST /scans?name=<file>` with the binary as the body | `202` and the scan, which runs in the background |
+//! | `GET /scans` | Every scan |
+//! | `GET /scans/<id>` | The scan: `queued`, `scanning`, `done` or `failed` |
+//! | `GET /scans/<id>/assets` | The asset table of a finished scan |
+//! | `GET /scans/<id>/assets/<path>` | The decoded asset |
+//! | `GET /scans/<id>/archive` | A tar of every asset and the manifest |
+//! | `DELETE /scans/<id>` | `204`, forgetting the scan |
+//!
+//! `POST /scans` also takes `inner_path` and `abi` to select the executable
+//! in a package. Errors are JSON objects with an `error` message. Every
+//! connection serves one request.
+//!
+//! Scans run on a fixed number of worker threads, the rest waiting as
+//! `queued`. At most `--max-scans` scans are kept: the oldest finished one
+//! is forgotten to make room, and a submission is answered with `503` when
+//! all of them are pending, which also bounds the stored uploads.
+//! Connections past `--max-connections` are answered with `503`, and a
+//! client that stalls for [`IO_TIMEOUT`] is disconnected.
+
+use super::http::{attachment, percent_decode, read_request, write_response, Request, Response};
+use super::{print_json, ServeApiCommand};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri_dumper::binary::BinaryMetadata;
+use tauri_dumper::export::ExportOptions;
+use tauri_dumper::extract::decompress_asset;
+use tauri_dumper::input::{self, ResolveOptions};
+use tauri_dumper::{mime, AssetScanner, AssetTable, ScanOptions};
+
+/// How long a connection may stall on a read or write before it is closed.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+/// Longest name a submitted binary may have.
+const MAX_NAME_LENGTH: usize = 255;
+
+enum ScanState {
+    Queued,
+    Scanning,
+    Done(Arc<AssetTable>),
+    Failed(String),
+}
+
+struct ScanJob {
+    name: String,
+    state: ScanState,
+}
+
+/// A stored binary waiting for a scan worker.
+struct ScanTask {
+    id: u64,
+    dir: PathBuf,
+    path: PathBuf,
+    options: ResolveOptions,
+}
+
+struct Server {
+    jobs: Mutex<BTreeMap<u64, ScanJob>>,
+    next_id: AtomicU64,
+    uploads: PathBuf,
+    scan: ScanOptions,
+    max_upload_size: u64,
+    queue: Mutex<Sender<ScanTask>>,
+    connections: AtomicUsize,
+    max_connections: usize,
+    max_scans: usize,
+}
+
+/// Counts a connection as open until dropped.
+struct Connection<'a>(&'a AtomicUsize);
+
+impl Drop for Connection<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[derive(Serialize)]
+struct ScanSummary<'a> {
+    id: u64,
+    name: &'a str,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    asset_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    binary: Option<&'a BinaryMetadata>,
+}
+
+impl ScanJob {
+    fn summary(&self, id: u64) -> ScanSummary<'_> {
+        let (status, error, table) = match &self.state {
+            ScanState::Queued => ("queued", None, None),
+            ScanState::Scanning => ("scanning", None, None),
+            ScanState::Done(table) => ("done", None, Some(table)),
+            ScanState::Failed(error) => ("failed", Some(error.as_str()), None),
+        };
+        ScanSummary {
+            id,
+            name: &self.name,
+            status,
+            error,
+            asset_count: table.map(|table| table.len()),
+            binary: table.map(|table| table.metadata()),
+        }
+    }
+}
+
+/// Serves the API on `command.listen` until the process is stopped.
+pub(crate) fn run(command: &ServeApiCommand) -> Result<()> {
+    let listener = TcpListener::bind(&command.listen)
+        .with_context(|| format!("failed to listen on {}", command.listen))?;
+    let address = listener.local_addr()?;
+    let uploads = tempfile::tempdir().context("failed to create the upload directory")?;
+    let (queue, tasks) = mpsc::channel();
+    let server = Arc::new(Server {
+        jobs: Mutex::new(BTreeMap::new()),
+        next_id: AtomicU64::new(1),
+        uploads: uploads.path().to_path_buf(),
+        // Concurrent scans cannot share a checkpoint file.
+        scan: ScanOptions {
+            checkpoint: None,
+            ..command.common.scan_options()
+        },
+        max_upload_size: command.max_upload_size,
+        queue: Mutex::new(queue),
+        connections: AtomicUsize::new(0),
+        max_connections: command.max_connections.get(),
+        max_scans: command.max_scans.get(),
+    });
+    let tasks = Arc::new(Mutex::new(tasks));
+    for _ in 0..command.scan_workers.get() {
+        let server = Arc::clone(&server);
+        let tasks = Arc::clone(&tasks);
+        thread::spawn(move || scan_worker(&server, &tasks));
+    }
+
+    #[derive(Serialize)]
+    struct Listening {
+        url: String,
+    }
+
+    let url = format!("http://{address}");
+    if command.common.json {
+        print_json(&Listening { url })?;
+    } else {
+        println!("Listening on {url}");
+    }
+    io::stdout().flush()?;
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::warn!("failed to accept a connection: {error}");
+                continue;
+            }
+        };
+        if let Err(error) = stream
+            .set_read_timeout(Some(IO_TIMEOUT))
+            .and_then(|()| stream.set_write_timeout(Some(IO_TIMEOUT)))
+        {
+            log::warn!("failed to set a connection timeout: {error}");
+            continue;
+        }
+        if server.connections.fetch_add(1, Ordering::SeqCst) >= server.max_connections {
+            server.connections.fetch_sub(1, Ordering::SeqCst);
+            log::warn!("too many connections; turning one away");
+            let busy = Response::error(503, "the server is busy; retry later");
+            if let Err(error) = write_response(stream, &busy) {
+                log::debug!("failed to turn a connection away: {error}");
+            }
+            continue;
+        }
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            let _connection = Connection(&server.connections);
+            if let Err(error) = handle(&server, stream) {
+                log::debug!("failed to serve a request: {error}");
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Scans queued binaries one at a time until the queue is closed.
+fn scan_worker(server: &Server, tasks: &Mutex<Receiver<ScanTask>>) {
+    loop {
+        let task = tasks.lock().expect("scan queue lock poisoned").recv();
+        match task {
+            Ok(task) => scan(server, &task),
+            Err(_) => return,
+        }
+    }
+}
+
+fn handle(server: &Arc<Server>, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader)? {
+        Ok(request) => {
+            log::info!("{} {}", request.method, request.path);
+            route(server, &request, &mut reader)
+        }
+        Err(response) => response,
+    };
+    write_response(stream, &response)
+}
+
+fn route(server: &Arc<Server>, request: &Request, body: &mut impl Read) -> Response {
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    let method = request.method.as_str();
+    let id = match segments.get(1).map(|id| id.parse::<u64>()) {
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => return Response::error(404, "no such scan"),
+        None => None,
+    };
+    match (method, segments.as_slice(), id) {
+        ("POST", ["scans"], _) => submit(server, request, body),
+        ("GET", ["scans"], _) => {
+            let jobs = server.jobs.lock().expect("jobs lock poisoned");
+            let summaries = jobs
+                .iter()
+                .map(|(id, job)| job.summary(*id))
+                .collect::<Vec<_>>();
+            Response::json(200, &summaries)
+        }
+        ("GET", ["scans", _], Some(id)) => {
+            let jobs = server.jobs.lock().expect("jobs lock poisoned");
+            match jobs.get(&id) {
+                Some(job) => Response::json(200, &job.summary(id)),
+                None => Response::error(404, "no such scan"),
+            }
+        }
+        ("DELETE", ["scans", _], Some(id)) => {
+            match server.jobs.lock().expect("jobs lock poisoned").remove(&id) {
+                Some(_) => Response::empty(204),
+                None => Response::error(404, "no such scan"),
+            }
+        }
+        ("GET", ["scans", _, "assets"], Some(id)) => {
+            with_table(server, id, |table| Response::json(200, &table.summary()))
+        }
+        ("GET", ["scans", _, "assets", ..], Some(id)) => {
+            let prefix = format!("/scans/{id}/assets");
+            let name = percent_decode(request.path.strip_prefix(&prefix).unwrap_or_default());
+            with_table(server, id, |table| asset(table, &name))
+        }
+        ("GET", ["scans", _, "archive"], Some(id)) => {
+            with_table(server, id, |table| archive(server, id, table))
+        }
+        (_, ["scans"] | ["scans", _] | ["scans", _, "assets" | "archive", ..], _) => {
+            Response::error(405, format!("{method} is not allowed on {}", request.path))
+        }
+        _ => Response::error(404, "no such endpoint"),
+    }
+}
+
+/// Stores the uploaded binary and starts scanning it.
+fn submit(server: &Arc<Server>, request: &Request, body: &mut impl Read) -> Response {
+    let Some(length) = request.content_length else {
+        return Response::error(411, "the binary must be sent with a Content-Length");
+    };
+    if length > server.max_upload_size {
+        return Response::error(
+            413,
+            format!(
+                "the binary is larger than the {} byte upload limit",
+                server.max_upload_size
+            ),
+        );
+    }
+    let name = match request.query("name") {
+        None => "binary".to_string(),
+        Some(name) if is_safe_name(name) => name.to_string(),
+        Some(_) => {
+            // Closing on an unread body would reset the connection before
+            // the client reads the response.
+            let _ = io::copy(&mut body.take(length), &mut io::sink());
+            return Response::error(
+                400,
+                format!(
+                    "the name must be a file name of up to {MAX_NAME_LENGTH} ASCII letters, \
+                     digits, '.', '_' and '-'"
+                ),
+            );
+        }
+    };
+    let id = server.next_id.fetch_add(1, Ordering::SeqCst);
+    let job = ScanJob {
+        name,
+        state: ScanState::Queued,
+    };
+    let mut response = Response::json(202, &job.summary(id));
+    response.headers.push(("Location", format!("/scans/{id}")));
+    let path = {
+        let mut jobs = server.jobs.lock().expect("jobs lock poisoned");
+        if jobs.len() >= server.max_scans && !forget_oldest_finished(&mut jobs) {
+            drop(jobs);
+            let _ = io::copy(&mut body.take(length), &mut io::sink());
+            return Response::error(503, "too many scans are pending; retry later");
+        }
+        let path = job.name.clone();
+        // The slot is taken before the upload is stored, so that concurrent
+        // submissions cannot pass the limit.
+        jobs.insert(id, job);
+        path
+    };
+    let dir = server.uploads.join(id.to_string());
+    let path = dir.join(path);
+    let stored = fs::create_dir_all(&dir)
+        .and_then(|()| fs::File::create(&path))
+        .and_then(|mut file| io::copy(&mut body.take(length), &mut file));
+    let failure = match stored {
+        Ok(copied) if copied == length => None,
+        Ok(_) => Some(Response::error(400, "the request body ended early")),
+        Err(error) => Some(Response::error(
+            500,
+            format!("failed to store the binary: {error}"),
+        )),
+    };
+    if let Some(failure) = failure {
+        let _ = fs::remove_dir_all(&dir);
+        server.jobs.lock().expect("jobs lock poisoned").remove(&id);
+        return failure;
+    }
+
+    let options = ResolveOptions {
+        inner_path: request.query("inner_path").map(str::to_string),
+        abi: request.query("abi").map(str::to_string),
+    };
+    let task = ScanTask {
+        id,
+        dir,
+        path,
+        options,
+    };
+    if server
+        .queue
+        .lock()
+        .expect("scan queue lock poisoned")
+        .send(task)
+        .is_err()
+    {
+        set_state(
+            server,
+            id,
+            ScanState::Failed("the scan workers have stopped".to_string()),
+        );
+    }
+    response
+}
+
+/// Forgets the oldest scan that has finished, returning false if every scan
+/// is still pending.
+fn forget_oldest_finished(jobs: &mut BTreeMap<u64, ScanJob>) -> bool {
+    let finished = jobs.iter().find_map(|(id, job)| {
+        matches!(job.state, ScanState::Done(_) | ScanState::Failed(_)).then_some(*id)
+    });
+    match finished {
+        Some(id) => {
+            log::info!("forgetting scan {id} to make room for a new one");
+            jobs.remove(&id);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns true if `name` is a plain file name made of characters that are
+/// safe in paths and response headers.
+fn is_safe_name(name: &str) -> bool {
+    (1..=MAX_NAME_LENGTH).contains(&name.len())
+        && !matches!(name, "." | "..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+fn scan(server: &Server, task: &ScanTask) {
+    let id = task.id;
+    // A scan deleted while it was queued only leaves its upload behind.
+    let queued = server
+        .jobs
+        .lock()
+        .expect("jobs lock poisoned")
+        .contains_key(&id);
+    let result = queued.then(|| {
+        set_state(server, id, ScanState::Scanning);
+        input::resolve_with_options(&task.path, &task.options)
+            .and_then(input::ResolvedInput::into_image)
+            .and_then(|image| AssetScanner::scan_with_options(&image, &server.scan))
+    });
+    // The table holds what it needs of the binary.
+    if let Err(error) = fs::remove_dir_all(&task.dir) {
+        log::warn!("failed to remove {}: {error}", task.dir.display());
+    }
+    let state = match result {
+        None => return,
+        Some(Ok(table)) => {
+            log::info!("scan {id} found {} assets", table.len());
+            ScanState::Done(Arc::new(table))
+        }
+        Some(Err(error)) => {
+            log::info!("scan {id} failed: {error}");
+            ScanState::Failed(error.to_string())
+        }
+    };
+    set_state(server, id, state);
+}
+
+/// Updates the state of scan `id`, unless it was deleted meanwhile.
+fn set_state(server: &Server, id: u64, state: ScanState) {
+    if let Some(job) = server.jobs.lock().expect("jobs lock poisoned").get_mut(&id) {
+        job.state = state;
+    }
+}
+
+/// Answers with `respond` once scan `id` is done, and with a conflict
+/// before.
+fn with_table(server: &Server, id: u64, respond: impl FnOnce(&AssetTable) -> Response) -> Response {
+    let table = match server.jobs.lock().expect("jobs lock poisoned").get(&id) {
+        None => return Response::error(404, "no such scan"),
+        Some(ScanJob {
+            state: ScanState::Done(table),
+            ..
+        }) => Arc::clone(table),
+        Some(ScanJob {
+            state: ScanState::Failed(error),
+            ..
+        }) => return Response::error(409, format!("scan {id} failed: {error}")),
+        Some(_) => return Response::error(409, format!("scan {id} has not finished")),
+    };
+    respond(&table)
+}
+
+fn asset(table: &AssetTable, name: &str) -> Response {
+    let Some(asset) = table.find(name) else {
+        return Response::error(404, format!("no asset named {name}"));
+    };
+    match decompress_asset(asset) {
+        Ok(data) => Response {
+            status: 200,
+            content_type: mime::mime_type(name),
+            headers: Vec::new(),
+            body: data,
+        },
+        Err(error) => Response::error(500, format!("failed to decode {name}: {error}")),
+    }
+}
+
+fn archive(server: &Server, id: u64, table: &AssetTable) -> Response {
+    let name = server
+        .jobs
+        .lock()
+        .expect("jobs lock poisoned")
+        .get(&id)
+        .map_or_else(|| "assets".to_string(), |job| job.name.clone());
+    let mut body = Vec::new();
+    if let Err(error) = table.export_tar(&mut body, &ExportOptions::new(&name)) {
+        return Response::error(500, format!("failed to archive the assets: {error}"));
+    }
+    Response {
+        status: 200,
+        content_type: "application/x-tar",
+        headers: vec![(
+            "Content-Disposition",
+            attachment(&format!("{name}-assets.tar")),
+        )],
+        body,
+    }
+}
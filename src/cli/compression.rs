@@ -0,0 +1,167 @@
+//! Compression figures for the `compression` command.
+
+use super::{compression_ratio, format_bytes, INCOMPRESSIBLE_RATIO};
+use anyhow::Result;
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, CellAlignment, Color, Table};
+use serde::Serialize;
+use tauri_dumper::codec::{self, BROTLI_LGWIN};
+use tauri_dumper::extract::decompress_asset;
+use tauri_dumper::{Asset, AssetEncoding, AssetTable};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CompressionReport {
+    pub(crate) asset_count: usize,
+    pub(crate) total_compressed_size: usize,
+    pub(crate) total_decompressed_size: usize,
+    /// Total compressed size as a share of the total decompressed size.
+    pub(crate) overall_ratio: f64,
+    /// The Brotli quality the assets were recompressed with.
+    pub(crate) quality: u32,
+    /// Assets Brotli shrinks by less than 5%, such as images and fonts that
+    /// are compressed already.
+    pub(crate) incompressible_count: usize,
+    /// Bytes the binary would lose if the incompressible assets were left
+    /// out of it, for instance to be downloaded at runtime.
+    pub(crate) exclude_savings: usize,
+    /// Bytes the binary would lose if every Brotli asset were recompressed
+    /// at `quality`.
+    pub(crate) recompress_savings: usize,
+    pub(crate) assets: Vec<AssetCompression>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AssetCompression {
+    pub(crate) name: String,
+    pub(crate) encoding: AssetEncoding,
+    pub(crate) compressed_size: usize,
+    pub(crate) decompressed_size: usize,
+    pub(crate) ratio: f64,
+    pub(crate) incompressible: bool,
+    /// The size at the report's quality. Only Brotli assets that are not
+    /// aliases are recompressed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) recompressed_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) alias_of: Option<String>,
+}
+
+impl CompressionReport {
+    pub(crate) fn new(table: &AssetTable, quality: u32) -> Result<Self> {
+        let mut assets = Vec::with_capacity(table.len());
+        for asset in table.assets() {
+            assets.push(AssetCompression::new(asset, quality)?);
+        }
+        assets.sort_by_key(|asset| std::cmp::Reverse(asset.compressed_size));
+
+        // Aliases share the data of the asset they alias, so leaving them
+        // out or recompressing them saves nothing more.
+        let stored = || assets.iter().filter(|asset| asset.alias_of.is_none());
+        let total_compressed_size = table.assets().iter().map(Asset::compressed_size).sum();
+        let total_decompressed_size = table.assets().iter().map(Asset::decompressed_size).sum();
+        Ok(Self {
+            asset_count: assets.len(),
+            total_compressed_size,
+            total_decompressed_size,
+            overall_ratio: if total_decompressed_size == 0 {
+                1.0
+            } else {
+                total_compressed_size as f64 / total_decompressed_size as f64
+            },
+            quality,
+            incompressible_count: assets.iter().filter(|asset| asset.incompressible).count(),
+            exclude_savings: stored()
+                .filter(|asset| asset.incompressible)
+                .map(|asset| asset.compressed_size)
+                .sum(),
+            recompress_savings: stored().map(AssetCompression::recompress_savings).sum(),
+            assets,
+        })
+    }
+
+    pub(crate) fn print(&self) {
+        println!("Assets: {}", self.asset_count);
+        println!(
+            "  compressed: {} of {} decompressed ({:.0}%)",
+            format_bytes(self.total_compressed_size),
+            format_bytes(self.total_decompressed_size),
+            self.overall_ratio * 100.0
+        );
+        println!(
+            "  incompressible: {} assets, {} saved by excluding them",
+            self.incompressible_count,
+            format_bytes(self.exclude_savings)
+        );
+        println!(
+            "  recompressing at quality {}: {} saved",
+            self.quality,
+            format_bytes(self.recompress_savings)
+        );
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL_CONDENSED);
+        table.set_header(vec![
+            "Path",
+            "Compressed",
+            "Decompressed",
+            "Ratio",
+            "Recompressed",
+        ]);
+        for asset in &self.assets {
+            let mut ratio = Cell::new(format!("{:.0}%", asset.ratio * 100.0))
+                .set_alignment(CellAlignment::Right);
+            if asset.incompressible {
+                ratio = ratio.fg(Color::Magenta);
+            }
+            let recompressed = match (&asset.alias_of, asset.recompressed_size) {
+                (Some(target), _) => format!("alias of {target}"),
+                (None, Some(size)) => format_bytes(size),
+                (None, None) => "-".to_string(),
+            };
+            table.add_row(vec![
+                Cell::new(&asset.name),
+                size_cell(asset.compressed_size),
+                size_cell(asset.decompressed_size),
+                ratio,
+                Cell::new(recompressed).set_alignment(CellAlignment::Right),
+            ]);
+        }
+        println!("{table}");
+    }
+}
+
+impl AssetCompression {
+    fn new(asset: &Asset, quality: u32) -> Result<Self> {
+        let ratio = compression_ratio(asset);
+        let recompressed_size =
+            if asset.encoding() == AssetEncoding::Brotli && asset.alias_of().is_none() {
+                let data = decompress_asset(asset)?;
+                Some(
+                    codec::compress_with_params(&data, quality, BROTLI_LGWIN)?
+                        .data
+                        .len(),
+                )
+            } else {
+                None
+            };
+        Ok(Self {
+            name: asset.name().to_string(),
+            encoding: asset.encoding(),
+            compressed_size: asset.compressed_size(),
+            decompressed_size: asset.decompressed_size(),
+            ratio,
+            incompressible: ratio >= INCOMPRESSIBLE_RATIO,
+            recompressed_size,
+            alias_of: asset.alias_of().map(str::to_string),
+        })
+    }
+
+    fn recompress_savings(&self) -> usize {
+        self.recompressed_size
+            .map_or(0, |size| self.compressed_size.saturating_sub(size))
+    }
+}
+
+fn size_cell(bytes: usize) -> Cell {
+    Cell::new(format_bytes(bytes)).set_alignment(CellAlignment::Right)
+}
@@ -0,0 +1,165 @@
+//! Dumping a directory of releases into a shared corpus, for the `corpus`
+//! command.
+
+use super::{finish_spinner, spinner, CorpusCommand};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+use tauri_dumper::corpus::{AddSummary, BatchOptions, Corpus, TimelineEntry};
+use tauri_dumper::input::ResolveOptions;
+
+#[derive(Serialize)]
+struct CorpusSummary {
+    corpus_dir: PathBuf,
+    releases: Vec<ReleaseSummary>,
+    timeline: Vec<TimelineEntry>,
+}
+
+#[derive(Serialize)]
+struct ReleaseSummary {
+    name: String,
+    /// False when the release was already in the corpus with the same
+    /// binary.
+    scanned: bool,
+    #[serde(flatten)]
+    assets: AddSummary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Adds every file in `command.releases` to the corpus in `command.output`,
+/// oldest first by version-aware file name order, and prints the timeline.
+/// The releases are processed together on `--jobs` threads. Files that fail
+/// to open or scan are reported and skipped.
+pub(crate) fn run(command: &CorpusCommand) -> Result<()> {
+    let mut files = fs::read_dir(&command.releases)
+        .with_context(|| format!("failed to read {}", command.releases.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    files.retain(|path| path.is_file() || path.extension().is_some_and(|ext| ext == "app"));
+    files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    let mut corpus = Corpus::open(&command.output)
+        .with_context(|| format!("failed to open corpus {}", command.output.display()))?;
+    let inputs = files
+        .into_iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            (name, path)
+        })
+        .collect::<Vec<_>>();
+    let mut options = BatchOptions {
+        resolve: ResolveOptions {
+            inner_path: command.common.inner_path.clone(),
+            abi: command.common.abi.clone(),
+        },
+        scan: command.common.scan_options(),
+        ..BatchOptions::default()
+    };
+    if let Some(jobs) = command.jobs {
+        options.jobs = jobs.get();
+    }
+
+    let spinner = spinner(&command.common, "adding releases");
+    let outcomes = corpus.add_batch(&inputs, &options)?;
+    finish_spinner(spinner);
+    let mut releases = Vec::new();
+    for ((name, path), added) in inputs.into_iter().zip(outcomes) {
+        let mut release = ReleaseSummary {
+            name: name.clone(),
+            scanned: false,
+            assets: AddSummary::default(),
+            error: None,
+        };
+        match added {
+            Ok(Some(assets)) => {
+                release.scanned = true;
+                release.assets = assets;
+            }
+            Ok(None) => log::info!("{name} is already in the corpus"),
+            Err(error) => {
+                log::warn!("skipping {}: {error}", path.display());
+                release.error = Some(error.to_string());
+            }
+        }
+        releases.push(release);
+    }
+    corpus.sort_releases_by(|a, b| natural_cmp(&a.name, &b.name));
+    corpus.save()?;
+
+    let summary = CorpusSummary {
+        corpus_dir: command.output.clone(),
+        releases,
+        timeline: corpus.timeline(),
+    };
+    if command.common.json {
+        super::print_json(&summary)
+    } else {
+        if !command.common.quiet {
+            print_summary(&summary);
+        }
+        Ok(())
+    }
+}
+
+fn print_summary(summary: &CorpusSummary) {
+    for release in &summary.releases {
+        match &release.error {
+            Some(error) => println!("{}: skipped ({error})", release.name),
+            None if release.scanned => println!(
+                "{}: {} new blobs, {} cached",
+                release.name, release.assets.stored, release.assets.cached
+            ),
+            None => println!("{}: already in corpus", release.name),
+        }
+    }
+    println!("Timeline");
+    for entry in &summary.timeline {
+        println!(
+            "  {}: +{} -{} ~{} ({} unchanged)",
+            entry.release,
+            entry.added.len(),
+            entry.removed.len(),
+            entry.changed.len(),
+            entry.unchanged
+        );
+    }
+    println!("  corpus: {}", summary.corpus_dir.display());
+}
+
+/// Compares names with runs of digits ordered by value, so `app-1.10.0`
+/// sorts after `app-1.9.0`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        let (Some(x), Some(y)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        if x.is_ascii_digit() && y.is_ascii_digit() {
+            let a_len = a.find(|c: char| !c.is_ascii_digit()).unwrap_or(a.len());
+            let b_len = b.find(|c: char| !c.is_ascii_digit()).unwrap_or(b.len());
+            let (a_digits, b_digits) = (
+                a[..a_len].trim_start_matches('0'),
+                b[..b_len].trim_start_matches('0'),
+            );
+            let order = a_digits
+                .len()
+                .cmp(&b_digits.len())
+                .then_with(|| a_digits.cmp(b_digits));
+            if order != Ordering::Equal {
+                return order;
+            }
+            (a, b) = (&a[a_len..], &b[b_len..]);
+        } else {
+            if x != y {
+                return x.cmp(&y);
+            }
+            (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Repacking a binary whenever its extracted assets change, for the
+//! `devloop` command.
+
+use super::poll::poll;
+use super::{ensure_assets_found, scan, DevloopCommand, Scan};
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri_dumper::{AssetTable, BinaryImage, ExportOptions, Repacker};
+
+#[derive(Serialize)]
+struct RepackEvent<'a> {
+    output: &'a Path,
+    replaced: usize,
+    skipped_oversized: usize,
+}
+
+/// Repacks `command.dir` into a copy of the binary, then again every time a
+/// file in it changes, until interrupted.
+///
+/// Every repack starts from the original binary, so reverting an edit
+/// restores the original asset. A change is only picked up once the
+/// directory is unchanged between two polls, so editors saving several
+/// files at once cause a single repack.
+pub(crate) fn run(command: &DevloopCommand) -> Result<()> {
+    let Scan { image, table, .. } = scan(&command.input, &command.common)?;
+    ensure_assets_found(&table)?;
+    if !command.dir.exists() {
+        table.export(&ExportOptions::new(&command.dir))?;
+        log::info!("extracted assets to {}", command.dir.display());
+    }
+    let output = command
+        .output
+        .clone()
+        .unwrap_or_else(|| default_output(&command.input));
+    let interval = Duration::from_millis(command.interval);
+
+    let mut repacked = None;
+    let mut previous = None;
+    loop {
+        let current = poll(&command.dir, true)?;
+        let settled = previous.as_ref() == Some(&current) || repacked.is_none();
+        if settled && repacked.as_ref() != Some(&current) {
+            if let Err(error) = repack(command, &image, &table, &output) {
+                if command.once {
+                    return Err(error);
+                }
+                log::warn!("failed to repack {}: {error:#}", command.dir.display());
+            }
+            repacked = Some(current.clone());
+        }
+        if command.once {
+            return Ok(());
+        }
+        previous = Some(current);
+        thread::sleep(interval);
+    }
+}
+
+fn repack(
+    command: &DevloopCommand,
+    image: &BinaryImage,
+    table: &AssetTable,
+    output: &Path,
+) -> Result<()> {
+    let image = BinaryImage::from_bytes(image.data())?;
+    let summary = Repacker::new(image, table.clone())
+        .replace_from_dir(&command.dir)
+        .skip_oversized(true)
+        .write(output)?;
+
+    if command.common.json {
+        // One line per repack, so the stream can be consumed as it grows.
+        let event = RepackEvent {
+            output,
+            replaced: summary.replaced,
+            skipped_oversized: summary.skipped_oversized,
+        };
+        println!("{}", serde_json::to_string(&event)?);
+    } else if !command.common.quiet {
+        println!(
+            "repacked {} assets -> {}",
+            summary.replaced,
+            output.display()
+        );
+        for oversized in &summary.oversized {
+            println!(
+                "  oversized: {} is {} bytes over its original size",
+                oversized.asset, oversized.delta
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `App-dev.exe` next to `App.exe`.
+fn default_output(input: &Path) -> PathBuf {
+    let stem = input
+        .file_stem()
+        .map_or_else(|| "binary".into(), |stem| stem.to_string_lossy());
+    let name = match input.extension() {
+        Some(extension) => format!("{stem}-dev.{}", extension.to_string_lossy()),
+        None => format!("{stem}-dev"),
+    };
+    input.with_file_name(name)
+}
@@ -0,0 +1,151 @@
+use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+pub(super) const DEFAULT_MAX_DOWNLOAD_SIZE: u64 = 1 << 30;
+
+/// A downloaded input, removed when dropped.
+pub(super) struct Download {
+    _dir: TempDir,
+    pub(super) path: PathBuf,
+}
+
+pub(super) struct DownloadOptions<'a> {
+    pub(super) max_size: u64,
+    pub(super) sha256: Option<&'a str>,
+    pub(super) progress: bool,
+}
+
+pub(super) fn is_url(input: &Path) -> bool {
+    input
+        .to_str()
+        .is_some_and(|input| input.starts_with("http://") || input.starts_with("https://"))
+}
+
+pub(super) fn download(url: &str, options: &DownloadOptions) -> Result<Download> {
+    let response = ureq::get(url)
+        .call()
+        .with_context(|| format!("failed to download {url}"))?;
+    let length = response.body().content_length();
+    if let Some(length) = length.filter(|&length| length > options.max_size) {
+        anyhow::bail!(
+            "{url} is {length} bytes, larger than the {} byte download limit",
+            options.max_size
+        );
+    }
+
+    let dir = tempfile::tempdir().context("failed to create download directory")?;
+    let path = dir.path().join(file_name(url));
+    let mut file =
+        File::create(&path).with_context(|| format!("failed to create {}", path.display()))?;
+    let progress = options.progress.then(|| progress_bar(length));
+
+    let mut reader = response.into_body().into_reader();
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("failed to download {url}"))?;
+        if read == 0 {
+            break;
+        }
+        total += read as u64;
+        if total > options.max_size {
+            anyhow::bail!("{url} exceeds the {} byte download limit", options.max_size);
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])?;
+        if let Some(pb) = &progress {
+            pb.set_position(total);
+        }
+    }
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    if let Some(expected) = options.sha256 {
+        let actual = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        if !actual.eq_ignore_ascii_case(expected.trim()) {
+            anyhow::bail!("SHA-256 mismatch for {url}: expected {expected}, found {actual}");
+        }
+    }
+
+    Ok(Download { _dir: dir, path })
+}
+
+/// A file fetched into memory.
+pub(super) struct Fetched {
+    pub(super) data: Vec<u8>,
+    pub(super) content_type: Option<String>,
+}
+
+/// Fetches `url` into memory, or returns `None` if the server answers 404
+/// or 410.
+pub(super) fn fetch(url: &str, max_size: u64) -> Result<Option<Fetched>> {
+    let mut response = match ureq::get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::StatusCode(404 | 410)) => return Ok(None),
+        Err(error) => return Err(error).with_context(|| format!("failed to fetch {url}")),
+    };
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let data = response
+        .body_mut()
+        .with_config()
+        .limit(max_size)
+        .read_to_vec()
+        .with_context(|| format!("failed to fetch {url}"))?;
+    Ok(Some(Fetched { data, content_type }))
+}
+
+/// Returns the last path segment of `url`, ignoring any query or fragment.
+fn file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    match path.split_once('/') {
+        Some((_, path)) => path
+            .rsplit('/')
+            .find(|segment| !segment.is_empty())
+            .filter(|segment| !matches!(*segment, "." | "..") && !segment.contains('\\'))
+            .unwrap_or("download"),
+        None => "download",
+    }
+}
+
+fn progress_bar(length: Option<u64>) -> ProgressBar {
+    match length {
+        Some(length) => {
+            let pb = ProgressBar::new(length);
+            pb.set_style(
+                ProgressStyle::with_template(
+                    "{spinner:.green} downloading [{bar:30}] {bytes}/{total_bytes}",
+                )
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=> "),
+            );
+            pb
+        }
+        None => {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(
+                ProgressStyle::with_template("{spinner:.green} downloading {bytes}")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            );
+            pb.enable_steady_tick(std::time::Duration::from_millis(80));
+            pb
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! `--progress ndjson`: scan progress, assets and diagnostics as one JSON
+//! object per line on stderr, for GUIs and CI wrappers to render.
+
+use serde::Serialize;
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::OnceLock;
+use tauri_dumper::progress::{Progress, ProgressEvent};
+
+/// Shared by the scan and export of a `--progress ndjson` run.
+static PROGRESS: OnceLock<Progress> = OnceLock::new();
+
+pub(super) fn enable() {
+    PROGRESS.get_or_init(|| Progress::new(|event: &ProgressEvent| emit(event)));
+}
+
+pub(super) fn progress() -> Option<Progress> {
+    PROGRESS.get().cloned()
+}
+
+pub(super) fn enabled() -> bool {
+    PROGRESS.get().is_some()
+}
+
+/// Prints a diagnostic, such as a `warning`, as an event of that name.
+pub(super) fn message(event: &str, message: impl Display) {
+    #[derive(Serialize)]
+    struct Message<'a> {
+        event: &'a str,
+        message: String,
+    }
+
+    emit(&Message {
+        event,
+        message: message.to_string(),
+    });
+}
+
+fn emit<T: Serialize>(event: &T) {
+    if let Ok(line) = serde_json::to_string(event) {
+        // Lines from several threads must not interleave.
+        let _ = writeln!(std::io::stderr().lock(), "{line}");
+    }
+}
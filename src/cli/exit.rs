@@ -0,0 +1,77 @@
+//! Process exit codes.
+
+use std::process::ExitCode;
+use tauri_dumper::Error;
+
+/// Why the CLI exited. Usage errors are reported by clap with code 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExitStatus {
+    Success = 0,
+    Failure = 1,
+    NoAssets = 3,
+    UnsupportedFormat = 4,
+    PartialFailure = 5,
+    Io = 6,
+}
+
+impl ExitStatus {
+    /// Classifies `error` by the first cause that has a dedicated status.
+    pub(crate) fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if cause.is::<NoAssetsFound>() {
+                return Self::NoAssets;
+            }
+            if cause.is::<PartialFailure>() || cause.is::<HookFailure>() {
+                return Self::PartialFailure;
+            }
+            if let Some(error) = cause.downcast_ref::<Error>() {
+                match error {
+                    Error::NoAssetSection(_) | Error::Encrypted(_) => return Self::NoAssets,
+                    Error::UnsupportedFormat(_)
+                    | Error::Object(_)
+                    | Error::InvalidContainer(_)
+                    | Error::Packed(_) => return Self::UnsupportedFormat,
+                    Error::Io(_) => return Self::Io,
+                    _ => {}
+                }
+            }
+            if cause.is::<std::io::Error>() {
+                return Self::Io;
+            }
+        }
+        Self::Failure
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::NoAssets => "no_assets",
+            Self::UnsupportedFormat => "unsupported_format",
+            Self::PartialFailure => "partial_failure",
+            Self::Io => "io",
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// The input was read but contains nothing to extract.
+#[derive(Debug, thiserror::Error)]
+#[error("{0}")]
+pub(crate) struct NoAssetsFound(pub(crate) String);
+
+/// `--keep-going` extraction finished, but some assets failed.
+#[derive(Debug, thiserror::Error)]
+#[error("{0} assets could not be extracted")]
+pub(crate) struct PartialFailure(pub(crate) usize);
+
+/// `--keep-going` extraction finished, but `--exec-per-asset` failed for
+/// some assets.
+#[derive(Debug, thiserror::Error)]
+#[error("--exec-per-asset failed for {0} assets")]
+pub(crate) struct HookFailure(pub(crate) usize);
@@ -0,0 +1,39 @@
+//! Searching decompressed assets for the `grep` command.
+
+use anyhow::{Context, Result};
+use regex::bytes::Regex;
+use serde::Serialize;
+use tauri_dumper::AssetTable;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GrepMatch {
+    pub(crate) asset: String,
+    /// One-based line number.
+    pub(crate) line: usize,
+    pub(crate) text: String,
+}
+
+/// Returns every line of a decompressed asset that `pattern` matches, in
+/// table order. Assets that look binary are skipped, as grep does.
+pub(crate) fn search(table: &AssetTable, pattern: &Regex) -> Result<Vec<GrepMatch>> {
+    let mut matches = Vec::new();
+    for asset in table.assets() {
+        let data = tauri_dumper::extract::decompress_asset(asset)
+            .with_context(|| format!("failed to decompress {}", asset.name()))?;
+        if data.contains(&0) {
+            log::debug!("skipping binary asset {}", asset.name());
+            continue;
+        }
+        for (index, line) in data.split(|&byte| byte == b'\n').enumerate() {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if pattern.is_match(line) {
+                matches.push(GrepMatch {
+                    asset: asset.name().to_string(),
+                    line: index + 1,
+                    text: String::from_utf8_lossy(line).into_owned(),
+                });
+            }
+        }
+    }
+    Ok(matches)
+}
@@ -0,0 +1,121 @@
+//! Commands run on extracted files, for `--exec-per-asset` and
+//! `--exec-after`.
+
+use super::ExtractFlags;
+use anyhow::{Context, Result};
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tauri_dumper::export::ExportStatus;
+use tauri_dumper::ExportSummary;
+
+/// A command line with `{placeholder}`s. It is split into arguments the way
+/// a POSIX shell would, but run without a shell, so substituted paths are
+/// never interpreted.
+#[derive(Debug, Clone)]
+pub(crate) struct Hook {
+    template: String,
+    args: Vec<String>,
+}
+
+pub(crate) fn parse_hook(template: &str) -> std::result::Result<Hook, String> {
+    let args = shlex::split(template).ok_or("unbalanced quotes")?;
+    if args.is_empty() {
+        return Err("empty command".to_string());
+    }
+    Ok(Hook {
+        template: template.to_string(),
+        args,
+    })
+}
+
+impl Hook {
+    /// Runs the command with each `{key}` replaced by its value. Its output
+    /// goes to stderr, so it never mixes with `--json` output.
+    pub(crate) fn run(&self, values: &[(&str, &str)]) -> Result<()> {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| substitute(arg, values))
+            .collect::<Vec<_>>();
+        log::debug!("running {args:?}");
+        let status = Command::new(&args[0])
+            .args(&args[1..])
+            .stdin(Stdio::null())
+            .stdout(io::stderr())
+            .status()
+            .with_context(|| format!("failed to run `{}`", self.template))?;
+        if !status.success() {
+            anyhow::bail!("`{}` exited with {status}", self.template);
+        }
+        Ok(())
+    }
+}
+
+/// Replaces each `{key}` in `arg` in one pass from left to right, so
+/// placeholders inside a substituted value, such as an asset named
+/// `{output}.js`, are left as they are.
+fn substitute(arg: &str, values: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = values.iter().find_map(|(key, value)| {
+            let placeholder = rest.strip_prefix('{')?.strip_prefix(key)?;
+            Some((*value, placeholder.strip_prefix('}')?))
+        });
+        match value {
+            Some((value, after)) => {
+                result.push_str(value);
+                rest = after;
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Runs the hooks of `flags` on the files `summary` wrote into `output`,
+/// returning how many `--exec-per-asset` runs failed under `--keep-going`.
+pub(crate) fn run_hooks(
+    flags: &ExtractFlags,
+    summary: &ExportSummary,
+    output: &Path,
+) -> Result<usize> {
+    if flags.dry_run {
+        return Ok(0);
+    }
+    let output = output.to_string_lossy();
+    let mut failed = 0;
+    if let Some(hook) = &flags.exec_per_asset {
+        for record in &summary.records {
+            if record.status != ExportStatus::Exported {
+                continue;
+            }
+            let path = record.path.to_string_lossy();
+            let values = [
+                ("path", &*path),
+                ("name", &record.name),
+                ("output", &output),
+            ];
+            if let Err(error) = hook.run(&values) {
+                let error = error.context(format!("--exec-per-asset failed for {}", record.name));
+                if !flags.keep_going {
+                    return Err(error);
+                }
+                log::warn!("{error:#}");
+                failed += 1;
+            }
+        }
+    }
+    if let Some(hook) = &flags.exec_after {
+        hook.run(&[("output", &output)])
+            .context("--exec-after failed")?;
+    }
+    Ok(failed)
+}
@@ -0,0 +1,230 @@
+//! Indicators of compromise for the `ioc` command.
+
+use anyhow::{Context, Result};
+use regex::bytes::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use tauri_dumper::{AssetTable, BinaryImage};
+
+/// Where URLs found in the binary itself, rather than in an asset, come from.
+const BINARY_SOURCE: &str = "binary";
+
+/// Hosts that appear in nearly every web bundle as XML namespaces and say
+/// nothing about the application.
+const IGNORED_HOSTS: &[&str] = &["www.w3.org", "w3.org", "ns.adobe.com", "purl.org"];
+
+/// Placeholders Tauri substitutes into updater endpoint URLs.
+const UPDATER_PLACEHOLDERS: &[&str] = &["{{target}}", "{{arch}}", "{{current_version}}"];
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Indicators {
+    pub(crate) binary: String,
+    pub(crate) sha256: String,
+    pub(crate) assets: Vec<AssetIndicator>,
+    pub(crate) urls: Vec<UrlIndicator>,
+    pub(crate) domains: Vec<String>,
+    pub(crate) updater_endpoints: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AssetIndicator {
+    pub(crate) name: String,
+    /// SHA-256 of the decompressed content, as it is served to the webview.
+    pub(crate) sha256: String,
+    pub(crate) size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UrlIndicator {
+    pub(crate) url: String,
+    /// The asset the URL was found in, or `binary`.
+    pub(crate) source: String,
+}
+
+impl Indicators {
+    /// Hashes every asset and collects the URLs in the assets and in the
+    /// binary's own strings, where Tauri keeps its compiled config.
+    pub(crate) fn collect(binary: &str, image: &BinaryImage, table: &AssetTable) -> Result<Self> {
+        let pattern = Regex::new(r"https?://[A-Za-z0-9\-._~:/?#\[\]@!$&'()*+,;=%{}]+")
+            .expect("valid URL pattern");
+        let mut assets = Vec::new();
+        let mut urls = BTreeMap::<String, String>::new();
+        for asset in table.assets() {
+            let data = tauri_dumper::extract::decompress_asset(asset)
+                .with_context(|| format!("failed to decompress {}", asset.name()))?;
+            assets.push(AssetIndicator {
+                name: asset.name().to_string(),
+                sha256: format!("{:x}", Sha256::digest(&data)),
+                size: data.len(),
+            });
+            for url in find_urls(&pattern, &data) {
+                urls.entry(url).or_insert_with(|| asset.name().to_string());
+            }
+        }
+        for url in find_urls(&pattern, image.data()) {
+            urls.entry(url).or_insert_with(|| BINARY_SOURCE.to_string());
+        }
+
+        let mut domains = urls.keys().filter_map(|url| host(url)).collect::<Vec<_>>();
+        domains.sort();
+        domains.dedup();
+        let updater_endpoints = urls
+            .keys()
+            .filter(|url| is_updater_endpoint(url))
+            .cloned()
+            .collect();
+
+        Ok(Self {
+            binary: binary.to_string(),
+            sha256: image.metadata().sha256.clone(),
+            assets,
+            urls: urls
+                .into_iter()
+                .map(|(url, source)| UrlIndicator { url, source })
+                .collect(),
+            domains,
+            updater_endpoints,
+        })
+    }
+
+    /// One `type,value,source` row per indicator.
+    pub(crate) fn to_csv(&self) -> String {
+        let mut csv = String::from("type,value,source\n");
+        let mut row = |kind: &str, value: &str, source: &str| {
+            let _ = writeln!(csv, "{kind},{},{}", csv_field(value), csv_field(source));
+        };
+        row("sha256", &self.sha256, BINARY_SOURCE);
+        for asset in &self.assets {
+            row("sha256", &asset.sha256, &asset.name);
+        }
+        for url in &self.urls {
+            row("url", &url.url, &url.source);
+        }
+        for domain in &self.domains {
+            row("domain", domain, "");
+        }
+        for endpoint in &self.updater_endpoints {
+            row("updater_endpoint", endpoint, "");
+        }
+        csv
+    }
+
+    /// A rule matching the binary by hash, or by its updater endpoints and
+    /// the domains it talks to.
+    pub(crate) fn to_yara(&self) -> String {
+        let mut rule = String::from("import \"hash\"\n\n");
+        let _ = writeln!(rule, "rule {} {{", self.rule_name());
+        rule.push_str("    meta:\n");
+        let _ = writeln!(
+            rule,
+            "        description = \"Indicators dumped from {}\"",
+            yara_escape(&self.binary)
+        );
+        let _ = writeln!(rule, "        sha256 = \"{}\"", self.sha256);
+        let _ = writeln!(rule, "        generator = \"tauri-dumper\"");
+
+        let mut condition = vec![format!("hash.sha256(0, filesize) == \"{}\"", self.sha256)];
+        if !self.updater_endpoints.is_empty() || !self.domains.is_empty() {
+            rule.push_str("    strings:\n");
+            for (index, endpoint) in self.updater_endpoints.iter().enumerate() {
+                let _ = writeln!(
+                    rule,
+                    "        $updater{index} = \"{}\" ascii wide",
+                    yara_escape(endpoint)
+                );
+            }
+            for (index, domain) in self.domains.iter().enumerate() {
+                let _ = writeln!(
+                    rule,
+                    "        $domain{index} = \"{}\" ascii wide nocase",
+                    yara_escape(domain)
+                );
+            }
+        }
+        if !self.updater_endpoints.is_empty() {
+            condition.push("any of ($updater*)".to_string());
+        }
+        // A single domain is often a CDN shared by unrelated applications.
+        if self.domains.len() >= 2 {
+            condition.push("2 of ($domain*)".to_string());
+        } else if !self.domains.is_empty() {
+            condition.push("$domain0".to_string());
+        }
+        rule.push_str("    condition:\n");
+        let _ = writeln!(rule, "        {}", condition.join(" or "));
+        rule.push_str("}\n");
+        rule
+    }
+
+    fn rule_name(&self) -> String {
+        let file_name = self.binary.rsplit(['/', '\\']).next().unwrap_or_default();
+        let stem = file_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("tauri_{}_{}", stem.trim_matches('_'), &self.sha256[..12])
+    }
+}
+
+fn find_urls(pattern: &Regex, data: &[u8]) -> Vec<String> {
+    pattern
+        .find_iter(data)
+        .map(|found| {
+            let url = String::from_utf8_lossy(found.as_bytes());
+            url.trim_end_matches(['.', ',', ';', ':', ')', ']', '\'', '!', '?'])
+                .to_string()
+        })
+        .filter(|url| host(url).is_some())
+        .collect()
+}
+
+/// Returns the lower-cased host of `url`, unless it is a placeholder, a
+/// local origin or an ignored namespace host.
+fn host(url: &str) -> Option<String> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = match host_port.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next()?,
+        None => host_port.split(':').next()?,
+    }
+    .to_ascii_lowercase();
+    let plausible = host.contains('.')
+        && !host.starts_with('.')
+        && !host.ends_with('.')
+        && !host.contains(['{', '}']);
+    let local = host == "localhost" || host.ends_with(".localhost");
+    (plausible && !local && !IGNORED_HOSTS.contains(&host.as_str())).then_some(host)
+}
+
+fn is_updater_endpoint(url: &str) -> bool {
+    UPDATER_PLACEHOLDERS
+        .iter()
+        .any(|placeholder| url.contains(placeholder))
+        || url.ends_with("/latest.json")
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn yara_escape(value: &str) -> String {
+    let mut escaped = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'"' => escaped.push_str("\\\""),
+            b'\\' => escaped.push_str("\\\\"),
+            0x20..=0x7e => escaped.push(char::from(byte)),
+            _ => {
+                let _ = write!(escaped, "\\x{byte:02x}");
+            }
+        }
+    }
+    escaped
+}
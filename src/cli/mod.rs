@@ -0,0 +1,3753 @@
+mod api;
+mod compression;
+mod corpus;
+mod devloop;
+mod download;
+mod events;
+mod exit;
+mod grep;
+mod hooks;
+mod http;
+mod ioc;
+mod logger;
+mod poll;
+mod preview;
+mod profiles;
+mod raw;
+mod remote;
+mod staging;
+mod stats;
+mod tui;
+mod watch;
+
+use anyhow::{Context, Result};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use comfy_table::presets::{UTF8_FULL, UTF8_FULL_CONDENSED};
+use comfy_table::{Cell, CellAlignment, Color, Table};
+use compression::CompressionReport;
+use download::DownloadOptions;
+use exit::{ExitStatus, HookFailure, NoAssetsFound, PartialFailure};
+use hooks::Hook;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use staging::Staging;
+use stats::AssetStats;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+use tauri_dumper::asset::AssetTableSummary;
+use tauri_dumper::audit::{self, AuditReport, Severity};
+use tauri_dumper::binary::{
+    apply_decrypted_dump, BinaryKind, BinaryMetadata, BuildInfo, BundleInfo, CodeSignature,
+    Hardening,
+};
+use tauri_dumper::carve::{CarveOptions, CarvedFile};
+use tauri_dumper::charset::{self, Conversion, LineEnding, TextCharset};
+use tauri_dumper::checkpoint::CheckpointOptions;
+use tauri_dumper::entropy::{self, AssetEntropy};
+use tauri_dumper::export::{ExportStatus, IGNORE_FILE_NAME};
+use tauri_dumper::frontend::{self, FrontendReport};
+use tauri_dumper::graph::{self, AssetGraph};
+use tauri_dumper::i18n::{self, LocaleReport};
+use tauri_dumper::input::{self, PackagedFile};
+use tauri_dumper::integrity::{self, IntegrityReport};
+use tauri_dumper::ipc::{self, IpcCommand};
+use tauri_dumper::licenses::{self, LicenseFile, LicenseOrigin, LICENSES_DIR};
+use tauri_dumper::manifest::{Attestation, Manifest, MANIFEST_FILE_NAME};
+use tauri_dumper::osv::{OsvDatabase, Vulnerability};
+use tauri_dumper::packer::{self, PackingReport};
+use tauri_dumper::pe_resources::PeResources;
+use tauri_dumper::provenance::{DumpInfo, DUMP_INFO_FILE_NAME};
+use tauri_dumper::repack::config;
+use tauri_dumper::report::{self, REPORT_FILE_NAME};
+use tauri_dumper::routes::{self, ROUTES_FILE_NAME};
+use tauri_dumper::sbom::{BundledPackage, Sbom, SbomFormat};
+use tauri_dumper::scan_cache::ScanCache;
+use tauri_dumper::scripts::{self, SCRIPTS_DIR};
+use tauri_dumper::service_worker::{self, ServiceWorker};
+use tauri_dumper::signing::{self, PublicKey, SecretKey};
+use tauri_dumper::timings::{timed, Stage, TimingReport, Timings};
+use tauri_dumper::wasm::{self, WasmModule};
+use tauri_dumper::webview::{WebView2Loader, WebViewEngine, WebViewRuntime};
+use tauri_dumper::{
+    AssetScanner, BinaryImage, Dedupe, ExportFormat, ExportOptions, ExportSummary, Layout,
+    RepackSummary, Repacker, ScanOptions, ScanProfile,
+};
+
+const BUNDLE_RESOURCES_DIR: &str = "bundle-resources";
+const PE_RESOURCES_DIR: &str = "pe-resources";
+/// Carved streams smaller than this are ignored. Short byte runs decode as
+/// valid Brotli too often to trust.
+const CARVED_STREAM_MIN_SIZE: usize = 64;
+
+/// Dependencies `inspect` lists besides Tauri's own crates.
+const NOTABLE_CRATES: &[&str] = &[
+    "hyper",
+    "openssl",
+    "reqwest",
+    "ring",
+    "rustls",
+    "serde_json",
+    "sqlx",
+    "tao",
+    "tokio",
+    "tungstenite",
+    "wry",
+    "zip",
+];
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Extract and replace embedded Tauri assets")]
+#[command(args_conflicts_with_subcommands = true, args_override_self = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
+    #[arg(value_name = "BINARY")]
+    binary: Option<PathBuf>,
+
+    /// The output directory, or `-` to write a tar stream to stdout.
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    extract: ExtractFlags,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    Extract(ExtractCommand),
+    List(ListCommand),
+    Inspect(ReadCommand),
+    Verify(ReadCommand),
+    Stats(ReadCommand),
+    Grep(GrepCommand),
+    Ioc(IocCommand),
+    /// Reports weak security settings and secrets found in the assets and
+    /// the binary.
+    Audit(AuditCommand),
+    /// Lists the IPC commands the frontend invokes or the capabilities allow.
+    Ipc(ReadCommand),
+    /// Shows which assets reference which, with the assets nothing
+    /// references and the references to files that are not bundled.
+    Refs(ReadCommand),
+    /// Lists the WebAssembly modules among the assets, including those
+    /// under other extensions, and validates them.
+    Wasm(ReadCommand),
+    /// Lists the service workers among the assets and the files they
+    /// precache, and rebuilds their offline bundle.
+    ServiceWorkers(ServiceWorkerCommand),
+    /// Lists the text encodings of the text assets, flagging those that are
+    /// not UTF-8.
+    Charsets(ReadCommand),
+    /// Groups the localization files among the assets by locale and reports
+    /// how completely each locale is translated.
+    Locales(ReadCommand),
+    /// Prints every candidate asset header whose pointers resolve, accepted
+    /// or rejected, with its raw and resolved pointers, as JSON or CSV.
+    Headers(HeadersCommand),
+    /// Prints where an asset's header is and a hexdump of its header and
+    /// stored data, straight from the binary.
+    Raw(RawCommand),
+    /// Reports how well each asset compresses and how much excluding or
+    /// recompressing assets would save.
+    Compression(CompressionCommand),
+    Tui(TuiCommand),
+    /// Extracts the assets to a temporary directory and opens them in the
+    /// browser, served from a local port, or in the file manager.
+    Open(OpenCommand),
+    Watch(WatchCommand),
+    Repack(RepackCommand),
+    /// Repacks a copy of the binary every time its extracted assets change.
+    Devloop(DevloopCommand),
+    Proc(ProcCommand),
+    Carve(CarveCommand),
+    Sbom(SbomCommand),
+    /// Matches the npm packages bundled into the assets against an offline
+    /// OSV database of known vulnerabilities.
+    Vulns(VulnsCommand),
+    Corpus(CorpusCommand),
+    /// Serves a REST API for submitting binaries, polling their scans and
+    /// downloading the assets.
+    ServeApi(ServeApiCommand),
+    /// Generates a key pair for signing extraction manifests.
+    Keygen(KeygenCommand),
+    /// Checks the signature of an extraction manifest.
+    VerifyManifest(VerifyManifestCommand),
+    /// Compares the assets of a binary with a manifest the vendor published
+    /// for the release.
+    Attest(AttestCommand),
+    /// Compares the assets of a binary with those a web origin serves under
+    /// the same paths.
+    DiffRemote(DiffRemoteCommand),
+    /// Checks the signature of a Tauri updater artifact against the updater
+    /// key of the installed app.
+    VerifyUpdate(VerifyUpdateCommand),
+    Completions(CompletionsCommand),
+}
+
+#[derive(Args, Debug, Clone)]
+struct CommonArgs {
+    #[arg(long)]
+    json: bool,
+
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    #[arg(short, long)]
+    quiet: bool,
+
+    #[arg(long, value_enum, default_value_t = Profile::Tauri)]
+    profile: Profile,
+
+    /// Apply the flags of this profile from the configuration file. The
+    /// command line overrides them.
+    #[arg(long, value_name = "NAME")]
+    config_profile: Option<String>,
+
+    /// Drop assets scored below this confidence, from 0 to 100.
+    #[arg(long, value_name = "SCORE", default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    min_confidence: u8,
+
+    /// Validate assets by decoding only their first 64 KiB while scanning.
+    #[arg(long)]
+    quick_verify: bool,
+
+    /// Report progress on stderr in this format, instead of spinners and
+    /// log lines.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    progress: Option<ProgressFormat>,
+
+    /// Stop scanning once this many assets are found.
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..))]
+    max_assets: Option<u64>,
+
+    #[arg(long, value_name = "PATH")]
+    inner_path: Option<String>,
+
+    /// Scan the native library for this ABI in an Android package, such as
+    /// `arm64-v8a` or `x86_64`.
+    #[arg(long, value_name = "ABI")]
+    abi: Option<String>,
+
+    /// Apply a decrypted dump of a FairPlay-encrypted iOS binary: either the
+    /// encrypted range alone or the app's memory from its load address.
+    #[arg(long, value_name = "FILE")]
+    decrypted: Option<PathBuf>,
+
+    #[arg(long, value_name = "HEX")]
+    sha256: Option<String>,
+
+    #[arg(long, value_name = "BYTES", default_value_t = download::DEFAULT_MAX_DOWNLOAD_SIZE)]
+    max_download_size: u64,
+
+    /// Report on stderr the time spent per stage and the candidate headers
+    /// tried.
+    #[arg(long)]
+    timings: bool,
+
+    /// Save the progress of the scan to this file every 30 seconds. It is
+    /// removed once the scan completes.
+    #[arg(long, value_name = "FILE")]
+    checkpoint: Option<PathBuf>,
+
+    /// Resume an interrupted scan from its `--checkpoint` file, if it holds
+    /// one for the same binary and options.
+    #[arg(long, requires = "checkpoint")]
+    resume_scan: bool,
+
+    /// Cache the headers found in binaries of 8 MiB or more, and read them
+    /// back instead of scanning such a binary again.
+    #[arg(long)]
+    cache: bool,
+}
+
+impl CommonArgs {
+    fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            profile: self.profile.into(),
+            min_confidence: self.min_confidence,
+            quick_verify: self.quick_verify,
+            max_assets: self.max_assets.map(|max| max as usize),
+            timings: timings(),
+            progress: events::progress(),
+            checkpoint: self.checkpoint.as_ref().map(|path| CheckpointOptions {
+                resume: self.resume_scan,
+                ..CheckpointOptions::new(path)
+            }),
+            cache: self
+                .cache
+                .then(ScanCache::default_dir)
+                .flatten()
+                .map(ScanCache::new),
+            ..ScanOptions::default()
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ProgressFormat {
+    /// One JSON object per line: `scan`, `asset_found`, `asset_written`,
+    /// and `warning` and the other log levels.
+    Ndjson,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Profile {
+    Tauri,
+    TauriV1,
+    RustEmbed,
+}
+
+impl From<Profile> for ScanProfile {
+    fn from(profile: Profile) -> Self {
+        match profile {
+            Profile::Tauri => ScanProfile::Tauri,
+            Profile::TauriV1 => ScanProfile::TauriV1,
+            Profile::RustEmbed => ScanProfile::RustEmbed,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+struct FilterArgs {
+    #[arg(long = "include", value_name = "GLOB")]
+    include: Vec<String>,
+
+    #[arg(long = "exclude", value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Skip assets matched by a gitignore-style file. Defaults to
+    /// `.dumperignore` in the working directory, if it exists.
+    #[arg(long, value_name = "FILE", conflicts_with = "no_ignore")]
+    ignore_file: Option<PathBuf>,
+
+    /// Do not read `.dumperignore`.
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Extract only assets of these types, by the MIME type of their names:
+    /// html, js, css, json, wasm, svg, image, font, audio, video or text.
+    #[arg(long, value_name = "TYPES", value_delimiter = ',')]
+    only_type: Vec<String>,
+
+    /// Skip assets smaller than this, by decompressed size.
+    #[arg(long, value_name = "BYTES")]
+    min_size: Option<usize>,
+
+    /// Skip assets larger than this, by decompressed size.
+    #[arg(long, value_name = "BYTES")]
+    max_size: Option<usize>,
+}
+
+#[derive(Args, Debug, Clone)]
+struct ExtractFlags {
+    #[arg(long, conflicts_with = "skip_existing")]
+    overwrite: bool,
+
+    #[arg(long, conflicts_with = "overwrite")]
+    skip_existing: bool,
+
+    #[arg(long)]
+    dry_run: bool,
+
+    #[arg(long)]
+    split_tables: bool,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Directory)]
+    output_format: OutputFormat,
+
+    /// Place files under their embedded paths, or under `js/`, `css/`,
+    /// `images/`, `fonts/`, `media/` and `other/` by MIME type.
+    #[arg(long, value_enum, default_value_t = LayoutMode::Original)]
+    layout: LayoutMode,
+
+    /// Write assets with identical content once, as hard links or into a
+    /// content-addressed store.
+    #[arg(long, value_enum)]
+    dedupe: Option<DedupeMode>,
+
+    /// Report assets that fail to decode or write and extract the rest,
+    /// exiting with code 5.
+    #[arg(long)]
+    keep_going: bool,
+
+    /// Decompress assets on this many threads. Defaults to the available
+    /// parallelism.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Write files on this many threads, apart from the decompression
+    /// threads. Defaults to the available parallelism, up to 8.
+    #[arg(long, value_name = "N")]
+    writers: Option<NonZeroUsize>,
+
+    /// Also write a single-file report of the assets and the audit findings,
+    /// which carries no asset data.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    report: Option<ReportFormat>,
+
+    /// Sign the manifest with this secret key, from `keygen`, writing a
+    /// minisign signature next to it.
+    #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+    sign_key: Option<PathBuf>,
+
+    /// Write assets whose names start with FROM under TO instead, e.g.
+    /// `/assets/=/static/`. The first matching rule applies.
+    #[arg(long = "map", value_name = "FROM=TO")]
+    map: Vec<String>,
+
+    /// Write the assets below this directory without it, e.g. `/dist`.
+    #[arg(long, value_name = "PREFIX")]
+    strip_prefix: Vec<String>,
+
+    /// Run this command on every extracted file, replacing `{path}` with
+    /// its path, `{name}` with the asset name and `{output}` with the
+    /// output directory.
+    #[arg(long, value_name = "COMMAND", value_parser = hooks::parse_hook)]
+    exec_per_asset: Option<Hook>,
+
+    /// Run this command once the extraction is complete, replacing
+    /// `{output}` with the output directory.
+    #[arg(long, value_name = "COMMAND", value_parser = hooks::parse_hook)]
+    exec_after: Option<Hook>,
+
+    /// Also write each WebAssembly module in the text format, next to it as
+    /// `.wat`.
+    #[arg(long)]
+    wat: bool,
+
+    /// Convert text assets in UTF-16 or a legacy code page to UTF-8, and
+    /// remove byte order marks.
+    #[arg(long)]
+    to_utf8: bool,
+
+    /// Rewrite the line endings of UTF-8 text assets. Off by default, so
+    /// extracted files match the embedded bytes.
+    #[arg(long, value_enum, value_name = "EOL")]
+    normalize_eol: Option<EolMode>,
+
+    /// Refuse to extract more than this many assets, counted after the
+    /// filters.
+    #[arg(long, value_name = "COUNT")]
+    max_files: Option<usize>,
+
+    /// Refuse to extract assets that decode to more than this many bytes in
+    /// total, counted after the filters.
+    #[arg(long, value_name = "BYTES")]
+    max_total_size: Option<u64>,
+
+    /// What to do when `--max-files` or `--max-total-size` is exceeded:
+    /// fail, or list the assets instead of extracting them.
+    #[arg(long, value_enum, value_name = "ACTION", default_value_t = OverQuota::Abort)]
+    over_quota: OverQuota,
+
+    /// When no asset table is found, carve standalone Brotli and gzip
+    /// streams from the whole file and extract them under `carved/`, named
+    /// after their offset.
+    #[arg(long)]
+    carve: bool,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OverQuota {
+    Abort,
+    List,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ReportFormat {
+    Html,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum OutputFormat {
+    Directory,
+    Sqlite,
+    Asar,
+    Zip,
+    Site,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum LayoutMode {
+    Original,
+    ByType,
+}
+
+impl From<LayoutMode> for Layout {
+    fn from(mode: LayoutMode) -> Self {
+        match mode {
+            LayoutMode::Original => Layout::Original,
+            LayoutMode::ByType => Layout::ByType,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum EolMode {
+    Lf,
+    Crlf,
+}
+
+impl From<EolMode> for LineEnding {
+    fn from(mode: EolMode) -> Self {
+        match mode {
+            EolMode::Lf => LineEnding::Lf,
+            EolMode::Crlf => LineEnding::Crlf,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum DedupeMode {
+    Hardlink,
+    Store,
+}
+
+impl From<DedupeMode> for Dedupe {
+    fn from(mode: DedupeMode) -> Self {
+        match mode {
+            DedupeMode::Hardlink => Dedupe::Hardlink,
+            DedupeMode::Store => Dedupe::Store,
+        }
+    }
+}
+
+impl From<OutputFormat> for ExportFormat {
+    fn from(format: OutputFormat) -> Self {
+        match format {
+            OutputFormat::Directory => ExportFormat::Directory,
+            OutputFormat::Sqlite => ExportFormat::Sqlite,
+            OutputFormat::Asar => ExportFormat::Asar,
+            OutputFormat::Zip => ExportFormat::Zip,
+            OutputFormat::Site => ExportFormat::Site,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+struct ExtractCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// The output directory, or `-` to write a tar stream to stdout.
+    #[arg(short, long, value_name = "DIR", default_value = "output")]
+    output: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    flags: ExtractFlags,
+}
+
+#[derive(Args, Debug)]
+struct ListCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = SortBy::Name)]
+    sort_by: SortBy,
+
+    #[arg(long, conflicts_with = "sort_by")]
+    tree: bool,
+
+    /// Print the assets as CSV, with their offsets and hashes.
+    #[arg(long, conflicts_with_all = ["tree", "json"])]
+    csv: bool,
+
+    /// Decode every asset, show its entropy and flag those as random as
+    /// encrypted data although their type is not.
+    #[arg(long, conflicts_with = "tree")]
+    entropy: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Name,
+    Size,
+    Ratio,
+}
+
+#[derive(Args, Debug)]
+struct ReadCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct ServiceWorkerCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// Write the workers and the embedded files they precache to this
+    /// directory, laid out by URL.
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct HeadersCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// Print the headers as CSV instead of JSON.
+    #[arg(long, conflicts_with = "json")]
+    csv: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct RawCommand {
+    #[arg(short, long, value_name = "BINARY")]
+    input: PathBuf,
+
+    /// The asset, such as `/index.html`.
+    #[arg(value_name = "ASSET")]
+    asset: String,
+
+    /// Bytes of the stored data to dump, or 0 for all of them.
+    #[arg(short = 'n', long, value_name = "BYTES", default_value_t = 256)]
+    length: usize,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct CompressionCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// Brotli quality to estimate recompression savings with.
+    #[arg(long, value_name = "0-11", default_value_t = 11, value_parser = clap::value_parser!(u32).range(0..=11))]
+    quality: u32,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct GrepCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(value_name = "PATTERN")]
+    pattern: String,
+
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct AuditCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// Print the findings as a SARIF log, for code scanning services.
+    #[arg(long)]
+    sarif: bool,
+
+    /// Write the SARIF log here instead of to standard output.
+    #[arg(short, long, value_name = "FILE", requires = "sarif")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct IocCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = IocFormat::Csv)]
+    format: IocFormat,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum IocFormat {
+    Csv,
+    Yara,
+}
+
+#[derive(Args, Debug)]
+struct TuiCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "output")]
+    output: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct OpenCommand {
+    #[arg(short, long, value_name = "BINARY")]
+    input: PathBuf,
+
+    /// Open the extracted files in the file manager instead of serving them.
+    #[arg(long)]
+    files: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    flags: ExtractFlags,
+}
+
+#[derive(Args, Debug)]
+struct WatchCommand {
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "dumps")]
+    output: PathBuf,
+
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    interval: u64,
+
+    #[arg(long)]
+    once: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    flags: ExtractFlags,
+}
+
+#[derive(Args, Debug)]
+struct DevloopCommand {
+    /// The binary to patch. It is never modified.
+    #[arg(short, long, value_name = "BINARY")]
+    input: PathBuf,
+
+    /// The extracted assets to watch. Extracted from the binary first if it
+    /// does not exist.
+    #[arg(short, long, value_name = "DIR")]
+    dir: PathBuf,
+
+    /// The patched copy. Defaults to the binary's name with `-dev` appended.
+    #[arg(short, long, value_name = "BINARY")]
+    output: Option<PathBuf>,
+
+    #[arg(long, value_name = "MS", default_value_t = 500)]
+    interval: u64,
+
+    /// Repack once and exit.
+    #[arg(long)]
+    once: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct KeygenCommand {
+    #[arg(long, value_name = "FILE", default_value = "tauri-dumper.key")]
+    secret_key: PathBuf,
+
+    #[arg(long, value_name = "FILE", default_value = "tauri-dumper.pub")]
+    public_key: PathBuf,
+}
+
+#[derive(Args, Debug)]
+struct VerifyManifestCommand {
+    /// The manifest, or the directory it was extracted to.
+    #[arg(value_name = "PATH")]
+    path: PathBuf,
+
+    #[arg(short, long, value_name = "FILE")]
+    public_key: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct AttestCommand {
+    #[arg(short, long, value_name = "BINARY")]
+    input: PathBuf,
+
+    /// The release manifest, or the directory it was extracted to.
+    #[arg(long, value_name = "PATH")]
+    manifest: PathBuf,
+
+    /// Check the manifest's signature with this key before comparing.
+    #[arg(short, long, value_name = "FILE")]
+    public_key: Option<PathBuf>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct DiffRemoteCommand {
+    #[arg(short, long, value_name = "BINARY")]
+    input: PathBuf,
+
+    /// The origin the frontend is deployed to, such as
+    /// `https://app.example.com`.
+    #[arg(value_name = "URL")]
+    origin: String,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct VerifyUpdateCommand {
+    /// The updater artifact, or its `.sig` file.
+    #[arg(value_name = "ARTIFACT")]
+    artifact: PathBuf,
+
+    /// The installed app, whose embedded updater key must have signed the
+    /// artifact.
+    #[arg(long, value_name = "BINARY", required_unless_present = "pubkey")]
+    app: Option<PathBuf>,
+
+    /// The updater public key, as a file or the `pubkey` value of the Tauri
+    /// config.
+    #[arg(long, value_name = "KEY", conflicts_with = "app")]
+    pubkey: Option<String>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct ProcCommand {
+    #[arg(long)]
+    pid: u32,
+
+    #[arg(long, value_name = "ADDRESS", value_parser = parse_address)]
+    base: Option<u64>,
+
+    /// The output directory, or `-` to write a tar stream to stdout.
+    #[arg(short, long, value_name = "DIR", default_value = "output")]
+    output: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+
+    #[command(flatten)]
+    flags: ExtractFlags,
+}
+
+#[derive(Args, Debug)]
+struct CarveCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "carved")]
+    output: PathBuf,
+
+    #[arg(long, value_name = "BYTES", default_value_t = CarveOptions::default().min_size)]
+    min_size: usize,
+
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+/// Lists the npm packages bundled into the assets as a CycloneDX or SPDX
+/// document.
+#[derive(Args, Debug)]
+struct SbomCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = SbomFormatArg::Cyclonedx)]
+    format: SbomFormatArg,
+
+    /// Write the document here instead of to standard output.
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum SbomFormatArg {
+    Cyclonedx,
+    Spdx,
+}
+
+#[derive(Args, Debug)]
+struct VulnsCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    /// The OSV database: a directory of OSV JSON records, a zip of them such
+    /// as osv.dev's `npm/all.zip`, or a single record.
+    #[arg(long, value_name = "PATH")]
+    osv: PathBuf,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct ServeApiCommand {
+    /// Address to listen on; port 0 picks a free port.
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:9000")]
+    listen: String,
+
+    /// Largest binary a client may submit.
+    #[arg(long, value_name = "BYTES", default_value_t = download::DEFAULT_MAX_DOWNLOAD_SIZE)]
+    max_upload_size: u64,
+
+    /// Scans to run at once; later submissions wait in the queue.
+    #[arg(long, value_name = "N", default_value = "2")]
+    scan_workers: NonZeroUsize,
+
+    /// Connections to serve at once; more are answered with 503.
+    #[arg(long, value_name = "N", default_value = "64")]
+    max_connections: NonZeroUsize,
+
+    /// Scans to keep, queued or finished. The oldest finished scan is
+    /// forgotten to make room for a new one, and submissions are answered
+    /// with 503 while every kept scan is still pending.
+    #[arg(long, value_name = "N", default_value = "32")]
+    max_scans: NonZeroUsize,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+impl From<SbomFormatArg> for SbomFormat {
+    fn from(format: SbomFormatArg) -> Self {
+        match format {
+            SbomFormatArg::Cyclonedx => SbomFormat::CycloneDx,
+            SbomFormatArg::Spdx => SbomFormat::Spdx,
+        }
+    }
+}
+
+/// Adds every release in a directory to a corpus and prints how the assets
+/// changed from one release to the next.
+#[derive(Args, Debug)]
+struct CorpusCommand {
+    #[arg(value_name = "RELEASES_DIR")]
+    releases: PathBuf,
+
+    #[arg(short, long, value_name = "DIR", default_value = "corpus")]
+    output: PathBuf,
+
+    /// Process the releases on this many threads, shared by all of them.
+    /// Defaults to the available parallelism.
+    #[arg(short, long, value_name = "N")]
+    jobs: Option<NonZeroUsize>,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+#[derive(Args, Debug)]
+struct CompletionsCommand {
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+#[derive(Args, Debug)]
+struct RepackCommand {
+    #[arg(value_name = "BINARY")]
+    binary: PathBuf,
+
+    #[arg(long, value_name = "DIR")]
+    assets: PathBuf,
+
+    #[arg(short, long, value_name = "BINARY", required_unless_present = "patch")]
+    output: Option<PathBuf>,
+
+    /// Write a bsdiff patch from BINARY to the repacked binary.
+    #[arg(long, value_name = "FILE")]
+    patch: Option<PathBuf>,
+
+    #[arg(long)]
+    strict: bool,
+
+    #[arg(long)]
+    skip_oversized: bool,
+
+    #[arg(long)]
+    dry_run: bool,
+
+    #[arg(long)]
+    allow_source_mismatch: bool,
+
+    /// Compress replacements at this Brotli quality instead of keeping the
+    /// smallest output of every quality.
+    #[arg(long, value_name = "0-11", value_parser = clap::value_parser!(u32).range(0..=11))]
+    brotli_quality: Option<u32>,
+
+    /// Brotli window size, as a power of two.
+    #[arg(long, value_name = "10-24", default_value_t = 22, value_parser = clap::value_parser!(u32).range(10..=24))]
+    brotli_window: u32,
+
+    /// Use the highest Brotli quality whose output still fits the original
+    /// asset, up to --brotli-quality.
+    #[arg(long)]
+    fit_original: bool,
+
+    /// Replace the embedded CSP. The policy must not be longer than the
+    /// original.
+    #[arg(long, value_name = "POLICY")]
+    set_csp: Option<String>,
+
+    /// Set a key of the embedded plugin config, such as
+    /// `updater.endpoints='["https://example.com"]'`. Values that are not
+    /// JSON are set as strings.
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_config_setting)]
+    set_config: Vec<(String, String)>,
+
+    #[arg(long, requires = "output")]
+    ad_hoc_sign: bool,
+
+    #[command(flatten)]
+    common: CommonArgs,
+}
+
+pub fn run() -> ExitCode {
+    let args = match profiles::expand(std::env::args_os().collect(), Cli::command()) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            return ExitStatus::Failure.into();
+        }
+    };
+    ARGUMENTS.get_or_init(|| {
+        args.iter()
+            .skip(1)
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    });
+    let cli = Cli::parse_from(args);
+    let json = cli.common().json;
+    if cli.common().progress.is_some() {
+        events::enable();
+    }
+    logger::init(cli.common().verbose, cli.common().quiet);
+    if let Some(name) = &cli.common().config_profile {
+        log::info!("applying config profile {name}");
+    }
+    if cli.common().timings {
+        TIMINGS.get_or_init(Arc::default);
+    }
+
+    let result = execute(cli);
+    if let Some(timings) = timings() {
+        print_timings(&timings.report());
+    }
+    match result {
+        Ok(()) => ExitStatus::Success.into(),
+        Err(error) => {
+            let status = ExitStatus::of(&error);
+            if events::enabled() {
+                events::message("error", format_args!("{error:#}"));
+            } else {
+                eprintln!("error: {error:#}");
+            }
+            // A partial failure already printed its summary, which lists the
+            // failed assets.
+            if json && status != ExitStatus::PartialFailure {
+                #[derive(Serialize)]
+                struct ErrorSummary {
+                    error: String,
+                    kind: &'static str,
+                    exit_code: u8,
+                }
+
+                let summary = ErrorSummary {
+                    error: format!("{error:#}"),
+                    kind: status.name(),
+                    exit_code: status as u8,
+                };
+                // Best effort: the error was already reported on stderr.
+                let _ = print_json(&summary);
+            }
+            status.into()
+        }
+    }
+}
+
+/// The arguments after config profiles were expanded, recorded in
+/// `dump-info.json`.
+static ARGUMENTS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Shared by the scan and export of a `--timings` run.
+static TIMINGS: OnceLock<Arc<Timings>> = OnceLock::new();
+
+fn timings() -> Option<Arc<Timings>> {
+    TIMINGS.get().cloned()
+}
+
+fn print_timings(report: &TimingReport) {
+    eprintln!("Timings:");
+    for timing in &report.stages {
+        eprintln!("  {:<14} {:>9.3} s", timing.stage.as_str(), timing.seconds);
+    }
+    eprintln!(
+        "Candidates: {} offsets tried, {} validated, {} rejected, {} assets kept",
+        report.candidates, report.validated, report.rejected, report.accepted
+    );
+}
+
+impl Cli {
+    fn common(&self) -> &CommonArgs {
+        match &self.command {
+            Some(Commands::Extract(command)) => &command.common,
+            Some(Commands::List(command)) => &command.common,
+            Some(
+                Commands::Inspect(command) | Commands::Verify(command) | Commands::Stats(command),
+            ) => &command.common,
+            Some(Commands::Grep(command)) => &command.common,
+            Some(Commands::Ioc(command)) => &command.common,
+            Some(Commands::Audit(command)) => &command.common,
+            Some(Commands::Ipc(command)) => &command.common,
+            Some(Commands::Refs(command)) => &command.common,
+            Some(Commands::Wasm(command)) => &command.common,
+            Some(Commands::ServiceWorkers(command)) => &command.common,
+            Some(Commands::Charsets(command)) => &command.common,
+            Some(Commands::Locales(command)) => &command.common,
+            Some(Commands::Headers(command)) => &command.common,
+            Some(Commands::Raw(command)) => &command.common,
+            Some(Commands::Compression(command)) => &command.common,
+            Some(Commands::Tui(command)) => &command.common,
+            Some(Commands::Open(command)) => &command.common,
+            Some(Commands::Watch(command)) => &command.common,
+            Some(Commands::Repack(command)) => &command.common,
+            Some(Commands::Devloop(command)) => &command.common,
+            Some(Commands::Proc(command)) => &command.common,
+            Some(Commands::Carve(command)) => &command.common,
+            Some(Commands::Sbom(command)) => &command.common,
+            Some(Commands::Vulns(command)) => &command.common,
+            Some(Commands::Corpus(command)) => &command.common,
+            Some(Commands::ServeApi(command)) => &command.common,
+            Some(Commands::VerifyManifest(command)) => &command.common,
+            Some(Commands::Attest(command)) => &command.common,
+            Some(Commands::DiffRemote(command)) => &command.common,
+            Some(Commands::VerifyUpdate(command)) => &command.common,
+            Some(Commands::Completions(_) | Commands::Keygen(_)) | None => &self.common,
+        }
+    }
+}
+
+fn execute(cli: Cli) -> Result<()> {
+    match cli.command {
+        Some(Commands::Extract(command)) => extract(
+            &command.binary,
+            &command.output,
+            &command.flags,
+            &command.common,
+        ),
+        Some(Commands::List(command)) => list(&command),
+        Some(Commands::Inspect(command)) => inspect(&command.binary, &command.common),
+        Some(Commands::Verify(command)) => verify(&command.binary, &command.common),
+        Some(Commands::Stats(command)) => stats(&command.binary, &command.common),
+        Some(Commands::Grep(command)) => grep(&command),
+        Some(Commands::Ioc(command)) => indicators(&command),
+        Some(Commands::Audit(command)) => security_audit(&command),
+        Some(Commands::Ipc(command)) => ipc_surface(&command.binary, &command.common),
+        Some(Commands::Refs(command)) => references(&command.binary, &command.common),
+        Some(Commands::Wasm(command)) => wasm_modules(&command.binary, &command.common),
+        Some(Commands::ServiceWorkers(command)) => service_workers(&command),
+        Some(Commands::Charsets(command)) => charsets(&command.binary, &command.common),
+        Some(Commands::Locales(command)) => locales(&command.binary, &command.common),
+        Some(Commands::Headers(command)) => headers(&command),
+        Some(Commands::Raw(command)) => raw::run(&command),
+        Some(Commands::Compression(command)) => compression_report(&command),
+        Some(Commands::Tui(command)) => browse(&command),
+        Some(Commands::Open(command)) => preview::run(&command),
+        Some(Commands::Watch(command)) => watch::run(&command),
+        Some(Commands::Repack(command)) => repack(command),
+        Some(Commands::Devloop(command)) => devloop::run(&command),
+        Some(Commands::Proc(command)) => dump_process(&command),
+        Some(Commands::Carve(command)) => carve(&command),
+        Some(Commands::Sbom(command)) => bill_of_materials(&command),
+        Some(Commands::Vulns(command)) => vulnerabilities(&command),
+        Some(Commands::Corpus(command)) => corpus::run(&command),
+        Some(Commands::ServeApi(command)) => api::run(&command),
+        Some(Commands::Keygen(command)) => generate_key(&command, &cli.common),
+        Some(Commands::VerifyManifest(command)) => verify_manifest(&command),
+        Some(Commands::Attest(command)) => attest(&command),
+        Some(Commands::DiffRemote(command)) => remote::run(&command),
+        Some(Commands::VerifyUpdate(command)) => verify_update(&command),
+        Some(Commands::Completions(command)) => {
+            let mut script = Vec::new();
+            clap_complete::generate(
+                command.shell,
+                &mut Cli::command(),
+                env!("CARGO_BIN_NAME"),
+                &mut script,
+            );
+            std::io::stdout()
+                .write_all(&script)
+                .context("failed to write completions")
+        }
+        None => {
+            let binary = cli
+                .binary
+                .context("missing binary path; run `tauri-dumper --help` for usage")?;
+            let output = cli.output.unwrap_or_else(|| PathBuf::from("output"));
+            extract(&binary, &output, &cli.extract, &cli.common)
+        }
+    }
+}
+
+fn extract(binary: &Path, output: &Path, flags: &ExtractFlags, common: &CommonArgs) -> Result<()> {
+    export(scan(binary, common)?, output, flags, common)
+}
+
+/// Writes the icon, version info and manifest of a PE image into `dir`.
+/// A broken resource directory does not fail the export.
+fn export_pe_resources(image: &BinaryImage, dir: &Path) -> Result<usize> {
+    match PeResources::parse(image.data()) {
+        Ok(resources) => Ok(resources.write(dir)?.len()),
+        Err(error) => {
+            log::warn!("skipping PE resources: {error}");
+            Ok(0)
+        }
+    }
+}
+
+/// Gathers license files from the assets and the package into `dir`.
+fn export_licenses(
+    table: &tauri_dumper::AssetTable,
+    package_files: Vec<PackagedFile>,
+    dir: &Path,
+) -> Result<usize> {
+    let mut files = licenses::from_assets(table)?;
+    files.extend(package_files.into_iter().map(|file| LicenseFile {
+        origin: LicenseOrigin::Package,
+        path: file.path,
+        data: file.data,
+    }));
+    let summary = licenses::write(&files, dir)?;
+    if !summary.licenses.is_empty() {
+        log::info!(
+            "recognized licenses: {}",
+            summary
+                .licenses
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(summary.files.len())
+}
+
+/// Writes the app's entry points and routes to `path`, returning the number
+/// of routes, or `None` when the assets have neither.
+fn export_routes(table: &tauri_dumper::AssetTable, path: &Path) -> Result<Option<usize>> {
+    let map = routes::map_routes(table)?;
+    if map.is_empty() {
+        return Ok(None);
+    }
+    map.write(path)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(Some(map.routes.len()))
+}
+
+fn dump_process(command: &ProcCommand) -> Result<()> {
+    let spinner = spinner(&command.common, "reading process memory");
+    let image = tauri_dumper::process::dump_image(command.pid, command.base)
+        .with_context(|| format!("failed to read process {}", command.pid))?;
+    let table = AssetScanner::scan_with_options(&image, &command.common.scan_options())?;
+    finish_spinner(spinner);
+    let scan = Scan {
+        image,
+        table,
+        package: PackageFiles::default(),
+    };
+    export(scan, &command.output, &command.flags, &command.common)
+}
+
+fn export(scan: Scan, output: &Path, flags: &ExtractFlags, common: &CommonArgs) -> Result<()> {
+    let Scan {
+        image,
+        table,
+        package,
+    } = scan;
+    let started = SystemTime::now();
+    let table = if flags.carve && table.is_empty() {
+        carve_streams(&image, &table, common)
+    } else {
+        table
+    };
+    ensure_assets_found(&table)?;
+    if flags.over_quota == OverQuota::List && !flags.dry_run {
+        if let Err(tauri_dumper::Error::QuotaExceeded(reason)) =
+            export_options(output, flags)?.check_quota(&table)
+        {
+            log::warn!("not extracting {reason}; listing the assets instead");
+            return if common.json {
+                print_json(&table.summary())
+            } else if common.quiet {
+                Ok(())
+            } else {
+                print_asset_table(&table, SortBy::Name, None);
+                Ok(())
+            };
+        }
+    }
+    if output == Path::new("-") {
+        return stream_tar(&table, flags, common);
+    }
+
+    let sign_key = flags
+        .sign_key
+        .as_ref()
+        .map(|path| {
+            SecretKey::read(path)
+                .with_context(|| format!("failed to read secret key {}", path.display()))
+        })
+        .transpose()?;
+
+    let staging = Staging::new(output, flags.dry_run)?;
+    let target = staging.path();
+    let spinner = spinner(common, "exporting assets");
+    let mut summary = table.export(&export_options(target, flags)?)?;
+    let signature = sign_key
+        .map(|key| signing::sign_file(&target.join(MANIFEST_FILE_NAME), &key))
+        .transpose()?;
+    let bundle_resources = match package.resources_dir {
+        Some(dir) if !flags.dry_run => input::copy_dir(&dir, &target.join(BUNDLE_RESOURCES_DIR))?,
+        _ => 0,
+    };
+    let pe_resources = if image.metadata().kind == BinaryKind::Pe && !flags.dry_run {
+        export_pe_resources(&image, &target.join(PE_RESOURCES_DIR))?
+    } else {
+        0
+    };
+    let scripts = if flags.dry_run {
+        0
+    } else {
+        let scripts = scripts::find_injected_scripts(image.data());
+        scripts::write(&scripts, &target.join(SCRIPTS_DIR))?.len()
+    };
+    let licenses = if flags.dry_run {
+        0
+    } else {
+        export_licenses(&table, package.license_files, &target.join(LICENSES_DIR))?
+    };
+    let routes = if flags.dry_run {
+        None
+    } else {
+        export_routes(&table, &target.join(ROUTES_FILE_NAME))?
+    };
+    let wat = if flags.wat && !flags.dry_run {
+        export_wat(&table, &summary, flags)?
+    } else {
+        0
+    };
+    let text = if (flags.to_utf8 || flags.normalize_eol.is_some()) && !flags.dry_run {
+        rewrite_text(&table, &summary, flags)?
+    } else {
+        TextRewrites::default()
+    };
+    let report = match flags.report {
+        Some(ReportFormat::Html) if !flags.dry_run => {
+            let path = target.join(REPORT_FILE_NAME);
+            let html = report::html_report(&table, &audit::audit(&image, &table)?);
+            fs::write(&path, html)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            Some(path)
+        }
+        _ => None,
+    };
+    if !flags.dry_run {
+        let path = target.join(DUMP_INFO_FILE_NAME);
+        let arguments = ARGUMENTS.get().cloned().unwrap_or_default();
+        DumpInfo::new(image.metadata(), arguments, started, SystemTime::now())
+            .write(&path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    let report = report.map(|path| staging.relocate(&path));
+    let signature = signature.map(|path| staging.relocate(&path));
+    summary.output_dir = output.to_path_buf();
+    for record in &mut summary.records {
+        record.path = staging.relocate(&record.path);
+    }
+    staging.commit()?;
+    finish_spinner(spinner);
+    let hook_failures = hooks::run_hooks(flags, &summary, output)?;
+
+    if common.json {
+        #[derive(Serialize)]
+        struct ExtractSummary<'a> {
+            #[serde(flatten)]
+            export: &'a ExportSummary,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            bundle_resources: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pe_resources: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            injected_scripts: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            licenses: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            routes: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            wat: Option<usize>,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            transcoded: &'a [Conversion],
+            #[serde(skip_serializing_if = "Option::is_none")]
+            eol_normalized: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            report: Option<&'a Path>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            signature: Option<&'a Path>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            hook_failures: Option<usize>,
+        }
+
+        print_json(&ExtractSummary {
+            export: &summary,
+            bundle_resources: (bundle_resources > 0).then_some(bundle_resources),
+            pe_resources: (pe_resources > 0).then_some(pe_resources),
+            injected_scripts: (scripts > 0).then_some(scripts),
+            licenses: (licenses > 0).then_some(licenses),
+            routes,
+            wat: (wat > 0).then_some(wat),
+            transcoded: &text.transcoded,
+            eol_normalized: (text.normalized > 0).then_some(text.normalized),
+            report: report.as_deref(),
+            signature: signature.as_deref(),
+            hook_failures: (hook_failures > 0).then_some(hook_failures),
+        })?;
+    } else if !common.quiet {
+        print_export_summary(&summary);
+        if bundle_resources > 0 {
+            println!(
+                "  bundle resources: {bundle_resources} files in {}",
+                output.join(BUNDLE_RESOURCES_DIR).display()
+            );
+        }
+        if pe_resources > 0 {
+            println!(
+                "  PE resources: {pe_resources} files in {}",
+                output.join(PE_RESOURCES_DIR).display()
+            );
+        }
+        if scripts > 0 {
+            println!(
+                "  injected scripts: {scripts} files in {}",
+                output.join(SCRIPTS_DIR).display()
+            );
+        }
+        if licenses > 0 {
+            println!(
+                "  licenses: {licenses} files in {}",
+                output.join(LICENSES_DIR).display()
+            );
+        }
+        if let Some(routes) = routes {
+            println!(
+                "  routes: {routes} routes in {}",
+                output.join(ROUTES_FILE_NAME).display()
+            );
+        }
+        if wat > 0 {
+            println!("  WAT: {wat} modules printed as text next to them");
+        }
+        if !text.transcoded.is_empty() {
+            println!("  UTF-8: {} text assets transcoded", text.transcoded.len());
+            for conversion in &text.transcoded {
+                let replaced = if conversion.replaced {
+                    ", invalid bytes replaced"
+                } else {
+                    ""
+                };
+                println!(
+                    "    {} (from {}{replaced})",
+                    conversion.name, conversion.from
+                );
+            }
+        }
+        if text.normalized > 0 {
+            let eol = match flags.normalize_eol {
+                Some(EolMode::Crlf) => "CRLF",
+                _ => "LF",
+            };
+            println!(
+                "  line endings: {} text assets normalized to {eol}",
+                text.normalized
+            );
+        }
+        if let Some(report) = &report {
+            println!("  report: {}", report.display());
+        }
+        if let Some(signature) = &signature {
+            println!("  signature: {}", signature.display());
+        }
+    }
+    if summary.failed > 0 {
+        return Err(PartialFailure(summary.failed).into());
+    }
+    if hook_failures > 0 {
+        return Err(HookFailure(hook_failures).into());
+    }
+    Ok(())
+}
+
+/// Writes the assets as a tar stream to stdout, for `--output -`.
+fn stream_tar(
+    table: &tauri_dumper::AssetTable,
+    flags: &ExtractFlags,
+    common: &CommonArgs,
+) -> Result<()> {
+    if common.json {
+        anyhow::bail!(
+            "--json cannot be used with --output -, which writes the tar stream to stdout"
+        );
+    }
+    let unsupported = [
+        ("--dry-run", flags.dry_run),
+        (
+            "--output-format",
+            !matches!(flags.output_format, OutputFormat::Directory),
+        ),
+        ("--sign-key", flags.sign_key.is_some()),
+        ("--report", flags.report.is_some()),
+        ("--exec-per-asset", flags.exec_per_asset.is_some()),
+        ("--exec-after", flags.exec_after.is_some()),
+        ("--wat", flags.wat),
+        ("--to-utf8", flags.to_utf8),
+        ("--normalize-eol", flags.normalize_eol.is_some()),
+    ];
+    if let Some((flag, _)) = unsupported.iter().find(|(_, set)| *set) {
+        anyhow::bail!("{flag} cannot be used with --output -");
+    }
+
+    let spinner = spinner(common, "streaming assets");
+    let summary = table.export_tar(
+        std::io::stdout().lock(),
+        &export_options(Path::new("-"), flags)?,
+    )?;
+    finish_spinner(spinner);
+    if !common.quiet {
+        eprintln!("Streamed {} assets to stdout", summary.exported);
+    }
+    if summary.failed > 0 {
+        return Err(PartialFailure(summary.failed).into());
+    }
+    Ok(())
+}
+
+fn generate_key(command: &KeygenCommand, common: &CommonArgs) -> Result<()> {
+    let key = SecretKey::generate()?;
+    key.write(&command.secret_key)
+        .with_context(|| format!("failed to write {}", command.secret_key.display()))?;
+    key.public_key()
+        .write(&command.public_key)
+        .with_context(|| format!("failed to write {}", command.public_key.display()))?;
+    if !common.quiet {
+        println!(
+            "Key {}: secret key {}, public key {}",
+            signing::key_id_hex(&key.key_id()),
+            command.secret_key.display(),
+            command.public_key.display()
+        );
+    }
+    Ok(())
+}
+
+fn verify_manifest(command: &VerifyManifestCommand) -> Result<()> {
+    let path = if command.path.is_dir() {
+        command.path.join(MANIFEST_FILE_NAME)
+    } else {
+        command.path.clone()
+    };
+    let key = PublicKey::read(&command.public_key)
+        .with_context(|| format!("failed to read public key {}", command.public_key.display()))?;
+    let signature = signing::verify_file(&path, &key)?;
+    let manifest = Manifest::read(&path)?;
+
+    if command.common.json {
+        #[derive(Serialize)]
+        struct ManifestVerification<'a> {
+            manifest: &'a Path,
+            key_id: String,
+            trusted_comment: &'a str,
+            source_sha256: &'a str,
+            assets: usize,
+        }
+
+        print_json(&ManifestVerification {
+            manifest: &path,
+            key_id: signing::key_id_hex(&signature.key_id),
+            trusted_comment: &signature.trusted_comment,
+            source_sha256: &manifest.source.sha256,
+            assets: manifest.assets.len(),
+        })
+    } else {
+        if !command.common.quiet {
+            println!(
+                "Signature verified with key {}",
+                signing::key_id_hex(&signature.key_id)
+            );
+            println!("  trusted comment: {}", signature.trusted_comment);
+            println!("  source SHA-256: {}", manifest.source.sha256);
+            println!("  assets: {}", manifest.assets.len());
+        }
+        Ok(())
+    }
+}
+
+fn attest(command: &AttestCommand) -> Result<()> {
+    let path = if command.manifest.is_dir() {
+        command.manifest.join(MANIFEST_FILE_NAME)
+    } else {
+        command.manifest.clone()
+    };
+    let signature = match &command.public_key {
+        Some(key) => {
+            let key = PublicKey::read(key)
+                .with_context(|| format!("failed to read public key {}", key.display()))?;
+            Some(signing::verify_file(&path, &key)?)
+        }
+        None => {
+            if signing::signature_path(&path).exists() {
+                log::warn!(
+                    "{} is signed but its signature was not checked; pass --public-key to check it",
+                    path.display()
+                );
+            }
+            None
+        }
+    };
+    let manifest = Manifest::read(&path)
+        .with_context(|| format!("failed to read manifest {}", path.display()))?;
+    let Scan { table, .. } = scan(&command.input, &command.common)?;
+    let attestation = manifest.attest(&table);
+
+    #[derive(Serialize)]
+    struct AttestSummary<'a> {
+        ok: bool,
+        manifest: &'a Path,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        trusted_comment: Option<&'a str>,
+        #[serde(flatten)]
+        attestation: &'a Attestation,
+    }
+
+    if command.common.json {
+        print_json(&AttestSummary {
+            ok: attestation.is_intact(),
+            manifest: &path,
+            key_id: signature
+                .as_ref()
+                .map(|signature| signing::key_id_hex(&signature.key_id)),
+            trusted_comment: signature
+                .as_ref()
+                .map(|signature| signature.trusted_comment.as_str()),
+            attestation: &attestation,
+        })?;
+    } else if !command.common.quiet {
+        if let Some(signature) = &signature {
+            println!(
+                "Manifest signature verified with key {}",
+                signing::key_id_hex(&signature.key_id)
+            );
+        }
+        if attestation.is_intact() {
+            println!(
+                "OK: all {} assets match {}",
+                attestation.matched,
+                path.display()
+            );
+        } else {
+            println!(
+                "{} of {} manifest assets match {}",
+                attestation.matched,
+                manifest.assets.len(),
+                path.display()
+            );
+        }
+        if attestation.binary_matches {
+            println!("  the binary is identical to the one the manifest was made from");
+        }
+        for divergence in &attestation.divergences {
+            let hashes = match (&divergence.expected, &divergence.actual) {
+                (Some(expected), Some(actual)) => format!(": expected {expected}, found {actual}"),
+                _ => String::new(),
+            };
+            println!(
+                "  {} {}{hashes}",
+                divergence.kind.as_str().to_uppercase(),
+                divergence.asset
+            );
+        }
+    }
+    if !attestation.is_intact() {
+        anyhow::bail!(
+            "{} assets diverge from {}",
+            attestation.divergences.len(),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+fn verify_update(command: &VerifyUpdateCommand) -> Result<()> {
+    let artifact = if command
+        .artifact
+        .extension()
+        .is_some_and(|extension| extension == signing::UPDATER_SIGNATURE_EXTENSION)
+    {
+        command.artifact.with_extension("")
+    } else {
+        command.artifact.clone()
+    };
+    let keys = match (&command.app, &command.pubkey) {
+        (Some(app), _) => {
+            let (image, _) = open(app, &command.common)?;
+            let keys = signing::embedded_public_keys(image.data());
+            if keys.is_empty() {
+                anyhow::bail!("{} embeds no updater public key", app.display());
+            }
+            keys
+        }
+        (None, Some(pubkey)) => {
+            let text = if Path::new(pubkey).is_file() {
+                fs::read_to_string(pubkey)
+                    .with_context(|| format!("failed to read public key {pubkey}"))?
+            } else {
+                pubkey.clone()
+            };
+            vec![PublicKey::parse(&text).context("invalid updater public key")?]
+        }
+        (None, None) => unreachable!("clap requires --app or --pubkey"),
+    };
+    let (signature, error) = match signing::verify_updater_artifact(&artifact, &keys) {
+        Ok(signature) => (Some(signature), None),
+        Err(tauri_dumper::Error::Signature(error)) => (None, Some(error)),
+        Err(error) => return Err(error.into()),
+    };
+
+    #[derive(Serialize)]
+    struct UpdateVerification<'a> {
+        artifact: &'a Path,
+        valid: bool,
+        trusted_keys: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        trusted_comment: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    }
+
+    if command.common.json {
+        print_json(&UpdateVerification {
+            artifact: &artifact,
+            valid: signature.is_some(),
+            trusted_keys: keys
+                .iter()
+                .map(|key| signing::key_id_hex(&key.key_id()))
+                .collect(),
+            key_id: signature
+                .as_ref()
+                .map(|signature| signing::key_id_hex(&signature.key_id)),
+            trusted_comment: signature
+                .as_ref()
+                .map(|signature| signature.trusted_comment.as_str()),
+            error: error.as_deref(),
+        })?;
+    } else if !command.common.quiet {
+        if let Some(signature) = &signature {
+            println!(
+                "Signature valid: {} was signed with updater key {}",
+                artifact.display(),
+                signing::key_id_hex(&signature.key_id)
+            );
+            println!("  trusted comment: {}", signature.trusted_comment);
+        }
+    }
+    if let Some(error) = error {
+        anyhow::bail!(
+            "invalid updater signature for {}: {error}",
+            artifact.display()
+        );
+    }
+    Ok(())
+}
+
+fn export_options(output: &Path, flags: &ExtractFlags) -> Result<ExportOptions> {
+    let mut options = ExportOptions::new(output);
+    options.overwrite = !flags.skip_existing;
+    options.skip_existing = flags.skip_existing;
+    options.dry_run = flags.dry_run;
+    options.split_tables = flags.split_tables;
+    options.format = flags.output_format.into();
+    options.layout = flags.layout.into();
+    options.dedupe = flags.dedupe.map_or(Dedupe::None, Dedupe::from);
+    options.keep_going = flags.keep_going;
+    options.min_size = flags.filter.min_size;
+    options.max_size = flags.filter.max_size;
+    options.max_files = flags.max_files;
+    options.max_total_size = flags.max_total_size;
+    options.timings = timings();
+    options.progress = events::progress();
+    if let Some(jobs) = flags.jobs {
+        options.jobs = jobs.get();
+    }
+    if let Some(writers) = flags.writers {
+        options.writers = writers.get();
+    }
+    let mut options = options
+        .include_globs(&flags.filter.include)?
+        .exclude_globs(&flags.filter.exclude)?
+        .only_types(&flags.filter.only_type)?
+        .map_paths(&flags.map)?;
+    for prefix in &flags.strip_prefix {
+        options = options.strip_prefix(prefix);
+    }
+
+    let default_ignore = Path::new(IGNORE_FILE_NAME);
+    let ignore_file = match &flags.filter.ignore_file {
+        Some(path) => Some(path.as_path()),
+        None if !flags.filter.no_ignore && default_ignore.is_file() => Some(default_ignore),
+        None => None,
+    };
+    if let Some(path) = ignore_file {
+        log::info!("reading ignore patterns from {}", path.display());
+        options = options.ignore_file(path)?;
+    }
+    Ok(options)
+}
+
+fn carve(command: &CarveCommand) -> Result<()> {
+    let (image, _) = open(&command.binary, &command.common)?;
+
+    let spinner = spinner(&command.common, "carving embedded files");
+    let options = CarveOptions {
+        min_size: command.min_size,
+    };
+    let files = tauri_dumper::carve::carve(image.data(), &options);
+    if files.is_empty() {
+        finish_spinner(spinner);
+        return Err(NoAssetsFound("no embedded files found".to_string()).into());
+    }
+    if !command.dry_run {
+        fs::create_dir_all(&command.output)
+            .with_context(|| format!("failed to create {}", command.output.display()))?;
+        for file in &files {
+            let path = command.output.join(&file.name);
+            fs::write(&path, &file.data)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+        }
+    }
+    finish_spinner(spinner);
+
+    #[derive(Serialize)]
+    struct CarveSummary<'a> {
+        output_dir: &'a Path,
+        dry_run: bool,
+        files: &'a [CarvedFile],
+    }
+
+    if command.common.json {
+        print_json(&CarveSummary {
+            output_dir: &command.output,
+            dry_run: command.dry_run,
+            files: &files,
+        })
+    } else if !command.common.quiet {
+        println!("Carve complete");
+        println!("  output: {}", command.output.display());
+        for file in &files {
+            println!(
+                "  {} ({:?}, {} at {:#x})",
+                file.name,
+                file.kind,
+                format_bytes(file.size),
+                file.offset
+            );
+        }
+        println!("  carved: {}", files.len());
+        if command.dry_run {
+            println!("  dry run: no files were written");
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+/// Builds a table of the compressed streams carved from the whole binary,
+/// for `--carve` when the scan found no asset table.
+fn carve_streams(
+    image: &BinaryImage,
+    table: &tauri_dumper::AssetTable,
+    common: &CommonArgs,
+) -> tauri_dumper::AssetTable {
+    let spinner = spinner(common, "carving compressed streams");
+    let options = CarveOptions {
+        min_size: CARVED_STREAM_MIN_SIZE,
+    };
+    let assets = tauri_dumper::carve::carve_assets(image.data(), &options);
+    finish_spinner(spinner);
+    if !assets.is_empty() {
+        log::warn!(
+            "no asset table found; extracting {} carved streams",
+            assets.len()
+        );
+    }
+    tauri_dumper::AssetTable::new(table.metadata().clone(), assets)
+}
+
+fn list(command: &ListCommand) -> Result<()> {
+    let common = &command.common;
+    let Scan { table, .. } = scan(&command.binary, common)?;
+    ensure_assets_found(&table)?;
+    let entropy = if command.entropy {
+        let spinner = spinner(common, "measuring entropy");
+        let measured = entropy::analyze(&table)?;
+        finish_spinner(spinner);
+        Some(measured)
+    } else {
+        None
+    };
+
+    #[derive(Serialize)]
+    struct ListSummary {
+        #[serde(flatten)]
+        table: AssetTableSummary,
+        entropy: Vec<AssetEntropy>,
+    }
+
+    if common.json {
+        match entropy {
+            Some(entropy) => print_json(&ListSummary {
+                table: table.summary(),
+                entropy,
+            }),
+            None => print_json(&table.summary()),
+        }
+    } else if common.quiet {
+        Ok(())
+    } else if command.tree {
+        print_asset_tree(&table);
+        Ok(())
+    } else if command.csv {
+        print_asset_csv(&table, command.sort_by, entropy.as_deref())
+    } else {
+        print_asset_table(&table, command.sort_by, entropy.as_deref());
+        Ok(())
+    }
+}
+
+/// The entropy of each asset by name, if it was measured.
+fn entropy_by_name(entropy: Option<&[AssetEntropy]>) -> HashMap<&str, &AssetEntropy> {
+    entropy
+        .unwrap_or_default()
+        .iter()
+        .map(|measured| (measured.name.as_str(), measured))
+        .collect()
+}
+
+fn inspect(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let (image, _) = open(binary, common)?;
+    let spinner = spinner(common, "scanning binary");
+    // A packed or encrypted binary has no readable assets, but its packing
+    // and encryption are still worth reporting.
+    let table = match AssetScanner::scan_with_options(&image, &common.scan_options()) {
+        Err(tauri_dumper::Error::Packed(_) | tauri_dumper::Error::Encrypted(_)) => {
+            tauri_dumper::AssetTable::new(image.metadata().clone(), Vec::new())
+        }
+        result => result?,
+    };
+    let packing = packer::analyze(image.data());
+    finish_spinner(spinner);
+
+    #[derive(Serialize)]
+    struct InspectSummary {
+        #[serde(flatten)]
+        table: AssetTableSummary,
+        frontend: FrontendReport,
+        packing: PackingReport,
+    }
+
+    let summary = InspectSummary {
+        table: table.summary(),
+        frontend: frontend::detect(&table)?,
+        packing,
+    };
+
+    if common.json {
+        print_json(&summary)
+    } else if !common.quiet {
+        print_inspect_summary(&summary.table, &summary.frontend, &summary.packing);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn verify(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { image, table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "checking integrity hashes");
+    let integrity = integrity::verify_integrity(image.data(), &table)?;
+    finish_spinner(spinner);
+    let mismatches = integrity.mismatches().count();
+
+    #[derive(Serialize)]
+    struct VerifySummary {
+        ok: bool,
+        asset_count: usize,
+        binary: tauri_dumper::binary::BinaryMetadata,
+        integrity: IntegrityReport,
+    }
+
+    let summary = VerifySummary {
+        ok: mismatches == 0,
+        asset_count: table.len(),
+        binary: table.metadata().clone(),
+        integrity,
+    };
+
+    if common.json {
+        print_json(&summary)?;
+    } else if !common.quiet {
+        println!("OK: found {} valid embedded assets", table.len());
+        print_integrity(&summary.integrity);
+    }
+    if mismatches > 0 {
+        anyhow::bail!("{mismatches} integrity hashes do not match the extracted assets");
+    }
+    Ok(())
+}
+
+fn print_integrity(integrity: &IntegrityReport) {
+    if integrity.checks.is_empty() {
+        return;
+    }
+    println!(
+        "Integrity: {} of {} hashes match",
+        integrity.verified(),
+        integrity.checks.len()
+    );
+    for check in integrity.mismatches() {
+        match &check.expected {
+            Some(expected) => println!(
+                "  MISMATCH {} ({}): expected {expected}, extracted {}",
+                check.asset,
+                check.kind.as_str(),
+                check.actual
+            ),
+            None => println!(
+                "  MISMATCH {} ({}): no embedded hash matches {}",
+                check.asset,
+                check.kind.as_str(),
+                check.actual
+            ),
+        }
+    }
+}
+
+fn stats(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let stats = AssetStats::new(&table);
+
+    if common.json {
+        print_json(&stats)
+    } else if !common.quiet {
+        stats.print();
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn grep(command: &GrepCommand) -> Result<()> {
+    let pattern = regex::bytes::RegexBuilder::new(&command.pattern)
+        .case_insensitive(command.ignore_case)
+        .build()
+        .with_context(|| format!("invalid pattern {:?}", command.pattern))?;
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+
+    let spinner = spinner(&command.common, "searching assets");
+    let matches = grep::search(&table, &pattern)?;
+    finish_spinner(spinner);
+
+    if command.common.json {
+        print_json(&matches)
+    } else if !command.common.quiet {
+        for found in &matches {
+            println!("{}:{}:{}", found.asset, found.line, found.text);
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn indicators(command: &IocCommand) -> Result<()> {
+    let Scan { image, table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(&command.common, "collecting indicators");
+    let indicators = ioc::Indicators::collect(&command.binary.to_string_lossy(), &image, &table)?;
+    finish_spinner(spinner);
+
+    if command.common.json {
+        print_json(&indicators)
+    } else if !command.common.quiet {
+        match command.format {
+            IocFormat::Csv => print!("{}", indicators.to_csv()),
+            IocFormat::Yara => print!("{}", indicators.to_yara()),
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn security_audit(command: &AuditCommand) -> Result<()> {
+    let common = &command.common;
+    let Scan { image, table, .. } = scan(&command.binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "auditing assets");
+    let report = audit::audit(&image, &table)?;
+    finish_spinner(spinner);
+
+    if command.sarif {
+        let sarif = report.to_sarif(&command.binary.to_string_lossy());
+        let Some(output) = &command.output else {
+            return print_json(&sarif);
+        };
+        fs::write(output, serde_json::to_vec_pretty(&sarif)?)
+            .with_context(|| format!("failed to write {}", output.display()))?;
+        if !common.quiet && !common.json {
+            println!(
+                "Wrote {} findings to {}",
+                report.findings.len(),
+                output.display()
+            );
+        }
+        return Ok(());
+    }
+    if common.json {
+        print_json(&report)
+    } else if !common.quiet {
+        print_audit_report(&report);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn print_audit_report(report: &AuditReport) {
+    if !report.findings.is_empty() {
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL_CONDENSED);
+        if std::env::var_os("NO_COLOR").is_some() {
+            table.force_no_tty();
+        }
+        table.set_header(vec!["Severity", "Category", "Location", "Finding"]);
+        for finding in &report.findings {
+            let color = match finding.severity {
+                Severity::High => Color::Red,
+                Severity::Medium => Color::Yellow,
+                Severity::Low => Color::Reset,
+            };
+            let location = finding
+                .location
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let mut text = finding.message.clone();
+            if !finding.evidence.is_empty() {
+                text = format!("{text}\n{}", finding.evidence);
+            }
+            table.add_row(vec![
+                Cell::new(finding.severity.as_str()).fg(color),
+                Cell::new(finding.category.as_str()),
+                Cell::new(location),
+                Cell::new(text),
+            ]);
+        }
+        println!("{table}");
+    }
+    println!(
+        "Findings: {} high, {} medium, {} low",
+        report.count(Severity::High),
+        report.count(Severity::Medium),
+        report.count(Severity::Low)
+    );
+    let stack = report
+        .frontend
+        .frameworks
+        .iter()
+        .chain(&report.frontend.bundlers)
+        .map(|detection| match &detection.version {
+            Some(version) => format!("{} {version}", detection.name),
+            None => detection.name.clone(),
+        })
+        .collect::<Vec<_>>();
+    if !stack.is_empty() {
+        println!("Frontend: {}", stack.join(", "));
+    }
+}
+
+fn ipc_surface(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { image, table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "finding IPC commands");
+    let commands = ipc::ipc_commands(&image, &table)?;
+    finish_spinner(spinner);
+
+    if common.json {
+        print_json(&commands)
+    } else if !common.quiet {
+        print_ipc_commands(&commands);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn print_ipc_commands(commands: &[IpcCommand]) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        table.force_no_tty();
+    }
+    table.set_header(vec!["Command", "Plugin", "In binary", "Referenced at"]);
+    for command in commands {
+        let references = command
+            .references
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        table.add_row(vec![
+            Cell::new(&command.name),
+            Cell::new(command.plugin.as_deref().unwrap_or_default()),
+            Cell::new(if command.in_binary { "yes" } else { "no" }),
+            Cell::new(references.join("\n")),
+        ]);
+    }
+    println!("IPC commands: {}", commands.len());
+    println!("{table}");
+}
+
+fn wasm_modules(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "finding WebAssembly modules");
+    let modules = wasm::find_modules(&table)?;
+    finish_spinner(spinner);
+
+    if common.json {
+        print_json(&modules)
+    } else if !common.quiet {
+        print_wasm_modules(&modules);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn print_wasm_modules(modules: &[WasmModule]) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        table.force_no_tty();
+    }
+    table.set_header(vec![
+        "Path",
+        "Size",
+        "Imports",
+        "Exports",
+        "Functions",
+        "Status",
+    ]);
+    for module in modules {
+        let mut status = match &module.error {
+            Some(error) => Cell::new(format!("invalid: {error}")).fg(Color::Red),
+            None => Cell::new("valid"),
+        };
+        if module.is_valid() && module.is_mislabeled() {
+            status = Cell::new("valid, not named .wasm").fg(Color::Yellow);
+        }
+        table.add_row(vec![
+            Cell::new(&module.name),
+            Cell::new(format_bytes(module.size)).set_alignment(CellAlignment::Right),
+            Cell::new(module.imports).set_alignment(CellAlignment::Right),
+            Cell::new(module.exports).set_alignment(CellAlignment::Right),
+            Cell::new(module.functions).set_alignment(CellAlignment::Right),
+            status,
+        ]);
+    }
+    println!("WebAssembly modules: {}", modules.len());
+    println!("{table}");
+}
+
+fn locales(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "comparing localization files");
+    let report = i18n::analyze(&table)?;
+    finish_spinner(spinner);
+
+    if common.json {
+        print_json(&report)
+    } else if !common.quiet {
+        print_locale_report(&report);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn print_locale_report(report: &LocaleReport) {
+    /// Missing keys printed per file; `--json` lists more.
+    const SHOWN_KEYS: usize = 5;
+
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        table.force_no_tty();
+    }
+    table.set_header(vec!["Locale", "Files", "Translated", "Coverage"]);
+    for coverage in &report.locales {
+        let percent = coverage.percent();
+        let mut files = coverage.files.clone();
+        files.extend(
+            coverage
+                .missing_bundles
+                .iter()
+                .map(|pattern| format!("no {pattern}")),
+        );
+        let mut cell = Cell::new(format!("{percent}%")).set_alignment(CellAlignment::Right);
+        if percent < 100 {
+            cell = cell.fg(Color::Yellow);
+        }
+        table.add_row(vec![
+            Cell::new(&coverage.locale),
+            Cell::new(files.join("\n")),
+            Cell::new(format!("{}/{}", coverage.translated, coverage.keys))
+                .set_alignment(CellAlignment::Right),
+            cell,
+        ]);
+    }
+    println!("Locales: {}", report.locales.len());
+    println!("{table}");
+    for bundle in &report.bundles {
+        let incomplete = bundle
+            .files
+            .iter()
+            .filter(|file| file.missing_count > 0)
+            .collect::<Vec<_>>();
+        if incomplete.is_empty() {
+            continue;
+        }
+        println!("{} ({} keys)", bundle.pattern, bundle.keys);
+        for file in incomplete {
+            let mut keys = file
+                .missing
+                .iter()
+                .take(SHOWN_KEYS)
+                .cloned()
+                .collect::<Vec<_>>();
+            if file.missing_count > SHOWN_KEYS {
+                keys.push(format!("and {} more", file.missing_count - SHOWN_KEYS));
+            }
+            println!(
+                "  {} misses {}: {}",
+                file.locale,
+                file.missing_count,
+                keys.join(", ")
+            );
+        }
+    }
+}
+
+fn service_workers(command: &ServiceWorkerCommand) -> Result<()> {
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(&command.common, "finding service workers");
+    let workers = service_worker::find_service_workers(&table)?;
+    finish_spinner(spinner);
+    let written = match &command.output {
+        Some(output) => Some(write_offline_bundle(&table, &workers, output)?),
+        None => None,
+    };
+
+    #[derive(Serialize)]
+    struct ServiceWorkerSummary<'a> {
+        workers: &'a [ServiceWorker],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output_dir: Option<&'a Path>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        written: Option<usize>,
+    }
+
+    if command.common.json {
+        print_json(&ServiceWorkerSummary {
+            workers: &workers,
+            output_dir: command.output.as_deref(),
+            written,
+        })?;
+    } else if !command.common.quiet {
+        print_service_workers(&workers);
+        if let (Some(output), Some(written)) = (&command.output, written) {
+            println!(
+                "Wrote {written} files of the offline bundle to {}",
+                output.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_service_workers(workers: &[ServiceWorker]) {
+    println!("Service workers: {}", workers.len());
+    for worker in workers {
+        let mut about = Vec::new();
+        if worker.workbox {
+            about.push("Workbox".to_string());
+        }
+        if !worker.registered_by.is_empty() {
+            about.push(format!("registered by {}", worker.registered_by.join(", ")));
+        }
+        if about.is_empty() {
+            println!("{}", worker.name);
+        } else {
+            println!("{} ({})", worker.name, about.join(", "));
+        }
+        if worker.precache.is_empty() {
+            continue;
+        }
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL_CONDENSED);
+        if std::env::var_os("NO_COLOR").is_some() {
+            table.force_no_tty();
+        }
+        table.set_header(vec!["Precached URL", "Revision", "Status"]);
+        for entry in &worker.precache {
+            let status = match (&entry.path, entry.embedded) {
+                (Some(path), true) => Cell::new(path),
+                (Some(_), false) => Cell::new("not embedded").fg(Color::Red),
+                (None, _) => Cell::new("other origin"),
+            };
+            table.add_row(vec![
+                Cell::new(&entry.url),
+                Cell::new(entry.revision.as_deref().unwrap_or("-")),
+                status,
+            ]);
+        }
+        println!("{table}");
+        let missing = worker.missing().count();
+        if missing > 0 {
+            println!("  {missing} precached files are not embedded");
+        }
+    }
+}
+
+/// Writes each worker, its imports and the embedded files it precaches
+/// under `output`, returning how many files were written.
+fn write_offline_bundle(
+    table: &tauri_dumper::AssetTable,
+    workers: &[ServiceWorker],
+    output: &Path,
+) -> Result<usize> {
+    let mut names = BTreeSet::new();
+    for worker in workers {
+        names.insert(worker.name.as_str());
+        names.extend(worker.imports.iter().map(String::as_str));
+        names.extend(
+            worker
+                .precache
+                .iter()
+                .filter(|entry| entry.embedded)
+                .filter_map(|entry| entry.path.as_deref()),
+        );
+    }
+    let mut written = 0;
+    for name in names {
+        let Some(asset) = table.find(name) else {
+            continue;
+        };
+        let Some(relative) = asset.safe_relative_path() else {
+            log::warn!("skipping {name}: unsafe path");
+            continue;
+        };
+        let path = output.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, tauri_dumper::extract::decompress_asset(asset)?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Writes the text format of each extracted WebAssembly module next to it,
+/// returning how many were written. Modules that do not print are skipped
+/// with a warning.
+fn export_wat(
+    table: &tauri_dumper::AssetTable,
+    summary: &ExportSummary,
+    flags: &ExtractFlags,
+) -> Result<usize> {
+    if !writes_per_file(flags) {
+        log::warn!("--wat only applies to directory and site exports without --dedupe store");
+        return Ok(0);
+    }
+    let mut written = 0;
+    for record in &summary.records {
+        if record.status != ExportStatus::Exported {
+            continue;
+        }
+        let Some(asset) = table.find(&record.name) else {
+            continue;
+        };
+        let data = tauri_dumper::extract::decompress_asset(asset)
+            .with_context(|| format!("failed to decompress {}", asset.name()))?;
+        if !wasm::is_wasm(&data) {
+            continue;
+        }
+        let text = match wasm::to_wat(&data) {
+            Ok(text) => text,
+            Err(error) => {
+                log::warn!("skipping {}: {error}", asset.name());
+                continue;
+            }
+        };
+        let path = wasm::wat_path(&record.path);
+        fs::write(&path, text).with_context(|| format!("failed to write {}", path.display()))?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Whether the export writes each asset to its own file, which the
+/// extraction can then add files next to or rewrite.
+fn writes_per_file(flags: &ExtractFlags) -> bool {
+    matches!(
+        flags.output_format,
+        OutputFormat::Directory | OutputFormat::Site
+    ) && !matches!(flags.dedupe, Some(DedupeMode::Store))
+}
+
+fn charsets(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "detecting text encodings");
+    let charsets = charset::analyze(&table)?;
+    finish_spinner(spinner);
+
+    if common.json {
+        print_json(&charsets)
+    } else if !common.quiet {
+        print_charsets(&charsets);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn print_charsets(charsets: &[TextCharset]) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        table.force_no_tty();
+    }
+    table.set_header(vec!["Path", "Type", "Encoding", "BOM"]);
+    for text in charsets {
+        let mut encoding = Cell::new(&text.encoding);
+        if !text.is_utf8() {
+            encoding = encoding.fg(Color::Yellow);
+        }
+        table.add_row(vec![
+            Cell::new(&text.name),
+            Cell::new(&text.mime),
+            encoding,
+            Cell::new(if text.bom { "yes" } else { "" }),
+        ]);
+    }
+    let other = charsets.iter().filter(|text| !text.is_utf8()).count();
+    println!("Text assets: {} ({other} not plain UTF-8)", charsets.len());
+    println!("{table}");
+    if other > 0 {
+        println!("Extract with --to-utf8 to convert them");
+    }
+}
+
+/// How the extracted text assets were rewritten.
+#[derive(Default)]
+struct TextRewrites {
+    transcoded: Vec<Conversion>,
+    /// How many assets had their line endings normalized.
+    normalized: usize,
+}
+
+/// Rewrites the extracted text assets for `--to-utf8`, as UTF-8 if they are
+/// not or start with a byte order mark, and for `--normalize-eol`. Line
+/// endings are only normalized in UTF-8 text, since the bytes of a line
+/// ending differ in other encodings.
+fn rewrite_text(
+    table: &tauri_dumper::AssetTable,
+    summary: &ExportSummary,
+    flags: &ExtractFlags,
+) -> Result<TextRewrites> {
+    let mut rewrites = TextRewrites::default();
+    if !writes_per_file(flags) {
+        log::warn!(
+            "--to-utf8 and --normalize-eol only apply to directory and site exports \
+             without --dedupe store"
+        );
+        return Ok(rewrites);
+    }
+    for record in &summary.records {
+        if record.status != ExportStatus::Exported
+            || !charset::is_text(tauri_dumper::mime::mime_type(&record.name))
+        {
+            continue;
+        }
+        let Some(asset) = table.find(&record.name) else {
+            continue;
+        };
+        let data = tauri_dumper::extract::decompress_asset(asset)
+            .with_context(|| format!("failed to decompress {}", asset.name()))?;
+        let transcoded = if flags.to_utf8 {
+            charset::transcode(&data)
+        } else {
+            None
+        };
+        let current = match &transcoded {
+            Some(transcoded) => Some(transcoded.text.as_str()),
+            None => std::str::from_utf8(&data).ok(),
+        };
+        let normalized = match (flags.normalize_eol, current) {
+            (Some(eol), Some(current)) => charset::normalize_eol(current, eol.into()),
+            (Some(_), None) => {
+                log::warn!(
+                    "not normalizing the line endings of {}: not UTF-8",
+                    record.name
+                );
+                None
+            }
+            (None, _) => None,
+        };
+        if normalized.is_some() {
+            rewrites.normalized += 1;
+        }
+        let text = normalized.or_else(|| {
+            transcoded
+                .as_ref()
+                .map(|transcoded| transcoded.text.clone())
+        });
+        if let Some(text) = text {
+            fs::write(&record.path, text)
+                .with_context(|| format!("failed to write {}", record.path.display()))?;
+        }
+        if let Some(transcoded) = transcoded {
+            rewrites.transcoded.push(Conversion {
+                name: record.name.clone(),
+                from: transcoded.from.name().to_string(),
+                replaced: transcoded.replaced,
+            });
+        }
+    }
+    Ok(rewrites)
+}
+
+fn references(binary: &Path, common: &CommonArgs) -> Result<()> {
+    let Scan { table, .. } = scan(binary, common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(common, "following asset references");
+    let graph = graph::asset_graph(&table)?;
+    finish_spinner(spinner);
+
+    if common.json {
+        print_json(&graph)
+    } else if !common.quiet {
+        print_asset_graph(&graph);
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn compression_report(command: &CompressionCommand) -> Result<()> {
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(&command.common, "recompressing assets");
+    let report = CompressionReport::new(&table, command.quality)?;
+    finish_spinner(spinner);
+
+    if command.common.json {
+        print_json(&report)
+    } else if !command.common.quiet {
+        report.print();
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+fn headers(command: &HeadersCommand) -> Result<()> {
+    let (image, _) = open(&command.binary, &command.common)?;
+    let spinner = spinner(&command.common, "parsing candidate headers");
+    let candidates = AssetScanner::header_candidates(&image, &command.common.scan_options())?;
+    finish_spinner(spinner);
+
+    if !command.csv {
+        return print_json(&candidates);
+    }
+    let mut csv = String::from(
+        "offset,layout,raw_name_pointer,raw_data_pointer,name_address,data_address,\
+         name_offset,data_offset,name_len,data_size,name,accepted,rejection\n",
+    );
+    for candidate in &candidates {
+        let _ = writeln!(
+            csv,
+            "{:#x},{},{:#x},{:#x},{:#x},{:#x},{:#x},{:#x},{},{},{},{},{}",
+            candidate.offset,
+            candidate.layout,
+            candidate.raw_name_pointer,
+            candidate.raw_data_pointer,
+            candidate.name_address,
+            candidate.data_address,
+            candidate.name_offset,
+            candidate.data_offset,
+            candidate.name_len,
+            candidate.data_size,
+            ioc::csv_field(candidate.name.as_deref().unwrap_or_default()),
+            candidate.accepted,
+            ioc::csv_field(candidate.rejection.as_deref().unwrap_or_default()),
+        );
+    }
+    print!("{csv}");
+    Ok(())
+}
+
+fn print_asset_graph(graph: &AssetGraph) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        table.force_no_tty();
+    }
+    table.set_header(vec!["Asset", "References"]);
+    let mut from = graph.references.iter().peekable();
+    while let Some(reference) = from.next() {
+        let mut targets = vec![reference.to.as_str()];
+        while let Some(next) = from.next_if(|next| next.from == reference.from) {
+            targets.push(&next.to);
+        }
+        table.add_row(vec![
+            Cell::new(&reference.from),
+            Cell::new(targets.join("\n")),
+        ]);
+    }
+    println!("References: {}", graph.references.len());
+    println!("{table}");
+    println!("Orphans: {}", graph.orphans.len());
+    for orphan in &graph.orphans {
+        println!("  {orphan}");
+    }
+    println!("Missing: {}", graph.missing.len());
+    for missing in &graph.missing {
+        println!("  {} (from {})", missing.to, missing.from);
+    }
+}
+
+fn bill_of_materials(command: &SbomCommand) -> Result<()> {
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(&command.common, "finding bundled packages");
+    let sbom = Sbom::from_table(&table)?;
+    finish_spinner(spinner);
+    let document = sbom.to_document(command.format.into(), std::time::SystemTime::now());
+
+    let Some(output) = &command.output else {
+        return print_json(&document);
+    };
+    fs::write(output, serde_json::to_vec_pretty(&document)?)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+
+    #[derive(Serialize)]
+    struct SbomSummary<'a> {
+        output: &'a Path,
+        packages: &'a [BundledPackage],
+    }
+
+    if command.common.json {
+        print_json(&SbomSummary {
+            output,
+            packages: &sbom.packages,
+        })
+    } else {
+        if !command.common.quiet {
+            println!(
+                "Wrote {} bundled packages to {}",
+                sbom.packages.len(),
+                output.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn vulnerabilities(command: &VulnsCommand) -> Result<()> {
+    let database = OsvDatabase::open(&command.osv)
+        .with_context(|| format!("failed to read OSV database {}", command.osv.display()))?;
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let spinner = spinner(&command.common, "matching bundled packages");
+    let sbom = Sbom::from_table(&table)?;
+    let vulnerabilities = database.vulnerabilities(&sbom.packages);
+    finish_spinner(spinner);
+    let unversioned = sbom
+        .packages
+        .iter()
+        .filter(|package| package.version.is_none())
+        .count();
+
+    #[derive(Serialize)]
+    struct VulnsSummary<'a> {
+        advisories: usize,
+        packages: &'a [BundledPackage],
+        vulnerabilities: &'a [Vulnerability],
+    }
+
+    if command.common.json {
+        print_json(&VulnsSummary {
+            advisories: database.len(),
+            packages: &sbom.packages,
+            vulnerabilities: &vulnerabilities,
+        })?;
+    } else if !command.common.quiet {
+        if !vulnerabilities.is_empty() {
+            let mut table = Table::new();
+            table.load_preset(UTF8_FULL_CONDENSED);
+            if std::env::var_os("NO_COLOR").is_some() {
+                table.force_no_tty();
+            }
+            table.set_header(vec![
+                "Package", "Version", "Advisory", "Severity", "Fixed in", "Summary",
+            ]);
+            for vulnerability in &vulnerabilities {
+                let color = match vulnerability.severity.as_deref() {
+                    Some("critical" | "high") => Color::Red,
+                    Some("moderate" | "medium") => Color::Yellow,
+                    _ => Color::Reset,
+                };
+                table.add_row(vec![
+                    Cell::new(&vulnerability.package),
+                    Cell::new(&vulnerability.version),
+                    Cell::new(&vulnerability.id),
+                    Cell::new(vulnerability.severity.as_deref().unwrap_or("-")).fg(color),
+                    Cell::new(vulnerability.fixed.as_deref().unwrap_or("-")),
+                    Cell::new(vulnerability.summary.as_deref().unwrap_or_default()),
+                ]);
+            }
+            println!("{table}");
+        }
+        println!(
+            "Checked {} bundled packages against {} advisories",
+            sbom.packages.len() - unversioned,
+            database.len()
+        );
+        if unversioned > 0 {
+            println!("  {unversioned} packages were found without a version and not checked");
+        }
+    }
+    if !vulnerabilities.is_empty() {
+        anyhow::bail!(
+            "{} known vulnerabilities in bundled packages",
+            vulnerabilities.len()
+        );
+    }
+    Ok(())
+}
+
+fn browse(command: &TuiCommand) -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        anyhow::bail!("tui needs an interactive terminal");
+    }
+    let Scan { table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    tui::run(&table, &command.output)
+}
+
+fn repack(command: RepackCommand) -> Result<()> {
+    let Scan { image, table, .. } = scan(&command.binary, &command.common)?;
+    ensure_assets_found(&table)?;
+    let binary_kind = table.metadata().kind;
+
+    let spinner = spinner(&command.common, "repacking assets");
+    let plan = Repacker::new(image, table)
+        .replace_from_dir(&command.assets)
+        .strict(command.strict)
+        .skip_oversized(command.skip_oversized)
+        .dry_run(command.dry_run)
+        .allow_source_mismatch(command.allow_source_mismatch)
+        .brotli_window(command.brotli_window)
+        .fit_original(command.fit_original);
+    let plan = match command.brotli_quality {
+        Some(quality) => plan.brotli_quality(quality),
+        None => plan,
+    };
+    let plan = match &command.set_csp {
+        Some(policy) => plan.set_csp(policy),
+        None => plan,
+    };
+    let plan = command.set_config.iter().fold(plan, |plan, (key, value)| {
+        plan.set_config(key, config::parse_value(value))
+    });
+    let summary = match (&command.output, &command.patch) {
+        (Some(output), Some(patch)) => plan.patch(patch).write(output)?,
+        (Some(output), None) => plan.write(output)?,
+        (None, Some(patch)) => plan.write_patch(patch)?,
+        (None, None) => unreachable!("clap requires --output or --patch"),
+    };
+    finish_spinner(spinner);
+
+    if let Some(output) = summary.output.as_ref().filter(|_| command.ad_hoc_sign) {
+        if cfg!(target_os = "macos") {
+            Command::new("codesign")
+                .args(["--force", "--deep", "--sign", "-"])
+                .arg(output)
+                .status()
+                .context("failed to run codesign")?;
+        }
+    }
+
+    // A patched binary loses its signature too, but is signed wherever the
+    // patch is applied.
+    let signing_note = summary
+        .output
+        .as_ref()
+        .filter(|_| binary_kind == BinaryKind::MachO && !command.ad_hoc_sign)
+        .map(|output| {
+            format!(
+                "macOS note: run `codesign --force --deep --sign - {}` before launching.",
+                output.display()
+            )
+        });
+    if command.common.json {
+        // Keep stdout parseable; notes go to stderr.
+        if let Some(note) = signing_note.filter(|_| !command.common.quiet) {
+            eprintln!("{note}");
+        }
+        print_json(&summary)
+    } else if !command.common.quiet {
+        print_repack_summary(&summary);
+        if let Some(note) = signing_note {
+            println!("{note}");
+        }
+        Ok(())
+    } else {
+        Ok(())
+    }
+}
+
+struct Scan {
+    image: BinaryImage,
+    table: tauri_dumper::AssetTable,
+    package: PackageFiles,
+}
+
+/// Files shipped next to the executable in its package.
+#[derive(Default)]
+struct PackageFiles {
+    resources_dir: Option<PathBuf>,
+    license_files: Vec<PackagedFile>,
+}
+
+fn scan(binary: &Path, common: &CommonArgs) -> Result<Scan> {
+    let (image, package) = open(binary, common)?;
+    let spinner = spinner(common, "scanning binary");
+    let table = AssetScanner::scan_with_options(&image, &common.scan_options())?;
+    finish_spinner(spinner);
+    Ok(Scan {
+        image,
+        table,
+        package,
+    })
+}
+
+/// Downloads and resolves `binary`, returning its image and the files shipped
+/// next to it.
+fn open(binary: &Path, common: &CommonArgs) -> Result<(BinaryImage, PackageFiles)> {
+    let download = if download::is_url(binary) {
+        let options = DownloadOptions {
+            max_size: common.max_download_size,
+            sha256: common.sha256.as_deref(),
+            progress: !common.quiet && !common.json && !events::enabled(),
+        };
+        Some(download::download(&binary.to_string_lossy(), &options)?)
+    } else if common.sha256.is_some() {
+        anyhow::bail!("--sha256 can only be used with URL inputs");
+    } else {
+        None
+    };
+    let local = download.as_ref().map_or(binary, |download| &download.path);
+
+    let spinner = spinner(common, "opening binary");
+    let options = input::ResolveOptions {
+        inner_path: common.inner_path.clone(),
+        abi: common.abi.clone(),
+    };
+    let timings = timings();
+    let mut resolved = timed(timings.as_deref(), Stage::Io, || {
+        input::resolve_with_options(local, &options)
+    })
+    .with_context(|| format!("failed to open {}", binary.display()))?;
+    // Report paths inside a download relative to its URL.
+    if let Some(rest) = download
+        .as_ref()
+        .and_then(|download| resolved.binary_path.strip_prefix(&download.path).ok())
+    {
+        resolved.binary_path = if rest.as_os_str().is_empty() {
+            binary.to_path_buf()
+        } else {
+            binary.join(rest)
+        };
+    }
+    let package = PackageFiles {
+        resources_dir: resolved.resources_dir.clone(),
+        license_files: std::mem::take(&mut resolved.license_files),
+    };
+    if let Some(dump) = &common.decrypted {
+        let dump = fs::read(dump)
+            .with_context(|| format!("failed to read decrypted dump {}", dump.display()))?;
+        resolved.data = apply_decrypted_dump(&resolved.data, &dump)
+            .with_context(|| format!("failed to apply decrypted dump to {}", binary.display()))?;
+    }
+    let binary_path = resolved.binary_path.clone();
+    let image = timed(timings.as_deref(), Stage::Parse, || resolved.into_image())
+        .with_context(|| format!("failed to open {}", binary.display()))?;
+    finish_spinner(spinner);
+    log::info!(
+        "opened {} ({:?}, {}, {} bytes)",
+        binary_path.display(),
+        image.metadata().kind,
+        image.metadata().architecture,
+        image.metadata().file_size
+    );
+    Ok((image, package))
+}
+
+fn parse_config_setting(value: &str) -> std::result::Result<(String, String), String> {
+    value
+        .split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, found {value}"))
+}
+
+fn parse_address(value: &str) -> std::result::Result<u64, String> {
+    let digits = value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+        .unwrap_or(value);
+    u64::from_str_radix(digits, 16).map_err(|err| format!("invalid hex address: {err}"))
+}
+
+fn ensure_assets_found(table: &tauri_dumper::AssetTable) -> Result<()> {
+    if table.is_empty() {
+        // A dev build loads its frontend from the dev server instead.
+        let message = match &table.metadata().dev_url {
+            Some(url) => format!("dev build, assets served from {url}"),
+            None => "no embedded Tauri assets found".to_string(),
+        };
+        return Err(NoAssetsFound(message).into());
+    }
+    Ok(())
+}
+
+fn spinner(common: &CommonArgs, message: &'static str) -> Option<ProgressBar> {
+    // Log lines would tear through the spinner.
+    if common.quiet || common.json || common.verbose > 0 || events::enabled() {
+        return None;
+    }
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::with_template("{spinner:.green} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+    );
+    pb.set_message(message);
+    pb.enable_steady_tick(std::time::Duration::from_millis(80));
+    Some(pb)
+}
+
+fn finish_spinner(spinner: Option<ProgressBar>) {
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[derive(Default)]
+struct AssetTreeNode {
+    children: BTreeMap<String, AssetTreeNode>,
+    asset: Option<AssetTreeLeaf>,
+}
+
+#[derive(Clone, Copy)]
+struct AssetTreeLeaf {
+    compressed_size: usize,
+    decompressed_size: usize,
+}
+
+fn print_asset_tree(table: &tauri_dumper::AssetTable) {
+    let mut root = AssetTreeNode::default();
+    for asset in table.assets() {
+        root.insert(
+            asset.name(),
+            AssetTreeLeaf {
+                compressed_size: asset.compressed_size(),
+                decompressed_size: asset.decompressed_size(),
+            },
+        );
+    }
+
+    println!("Assets: {}", table.len());
+    print_asset_tree_children(&root, "");
+}
+
+impl AssetTreeNode {
+    fn insert(&mut self, path: &str, asset: AssetTreeLeaf) {
+        let mut node = self;
+        for component in path
+            .trim_start_matches('/')
+            .split('/')
+            .filter(|component| !component.is_empty())
+        {
+            node = node.children.entry(component.to_string()).or_default();
+        }
+        node.asset = Some(asset);
+    }
+}
+
+fn print_asset_tree_children(node: &AssetTreeNode, prefix: &str) {
+    let child_count = node.children.len();
+    for (index, (name, child)) in node.children.iter().enumerate() {
+        let is_last = index + 1 == child_count;
+        let connector = if is_last {
+            "\u{2514}\u{2500}\u{2500} "
+        } else {
+            "\u{251c}\u{2500}\u{2500} "
+        };
+        println!(
+            "{}{}{}{}",
+            prefix,
+            connector,
+            name,
+            asset_tree_size_suffix(child.asset)
+        );
+
+        let next_prefix = if is_last {
+            format!("{prefix}    ")
+        } else {
+            format!("{prefix}\u{2502}   ")
+        };
+        print_asset_tree_children(child, &next_prefix);
+    }
+}
+
+fn asset_tree_size_suffix(asset: Option<AssetTreeLeaf>) -> String {
+    asset.map_or_else(String::new, |asset| {
+        format!(
+            " ({} compressed, {} decompressed)",
+            format_bytes(asset.compressed_size),
+            format_bytes(asset.decompressed_size)
+        )
+    })
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if value < 10.0 {
+        format!("{value:.1} {}", UNITS[unit])
+    } else {
+        format!("{value:.0} {}", UNITS[unit])
+    }
+}
+
+/// Assets at least this large are highlighted.
+const LARGE_ASSET_SIZE: usize = 1024 * 1024;
+/// Assets whose compressed size is at least this share of the original are
+/// highlighted; they may be encrypted or already compressed.
+const INCOMPRESSIBLE_RATIO: f64 = 0.95;
+/// Assets scored below this confidence are highlighted; they may be
+/// coincidental matches.
+const LOW_CONFIDENCE: u8 = 60;
+
+fn sorted_assets(table: &tauri_dumper::AssetTable, sort_by: SortBy) -> Vec<&tauri_dumper::Asset> {
+    let mut assets = table.assets().iter().collect::<Vec<_>>();
+    match sort_by {
+        SortBy::Name => assets.sort_by(|a, b| a.name().cmp(b.name())),
+        SortBy::Size => assets.sort_by_key(|asset| std::cmp::Reverse(asset.decompressed_size())),
+        SortBy::Ratio => {
+            assets.sort_by(|a, b| compression_ratio(b).total_cmp(&compression_ratio(a)))
+        }
+    }
+    assets
+}
+
+fn print_asset_table(
+    table: &tauri_dumper::AssetTable,
+    sort_by: SortBy,
+    entropy: Option<&[AssetEntropy]>,
+) {
+    let assets = sorted_assets(table, sort_by);
+    let measured = entropy_by_name(entropy);
+    let mut output = Table::new();
+    output.load_preset(UTF8_FULL_CONDENSED);
+    if std::env::var_os("NO_COLOR").is_some() {
+        output.force_no_tty();
+    }
+    let mut header = vec![
+        "Path",
+        "Type",
+        "Compressed",
+        "Decompressed",
+        "Ratio",
+        "Confidence",
+        "Offset",
+    ];
+    if entropy.is_some() {
+        header.push("Entropy");
+    }
+    output.set_header(header);
+    for asset in assets {
+        let ratio = compression_ratio(asset);
+        let mut decompressed =
+            Cell::new(format_bytes(asset.decompressed_size())).set_alignment(CellAlignment::Right);
+        if asset.decompressed_size() >= LARGE_ASSET_SIZE {
+            decompressed = decompressed.fg(Color::Yellow);
+        }
+        let mut ratio_cell =
+            Cell::new(format!("{:.0}%", ratio * 100.0)).set_alignment(CellAlignment::Right);
+        if ratio >= INCOMPRESSIBLE_RATIO {
+            ratio_cell = ratio_cell.fg(Color::Magenta);
+        }
+        let mut confidence = Cell::new(asset.confidence()).set_alignment(CellAlignment::Right);
+        if asset.confidence() < LOW_CONFIDENCE {
+            confidence = confidence.fg(Color::Red);
+        }
+        let mut row = vec![
+            Cell::new(asset.name()),
+            Cell::new(tauri_dumper::mime::mime_type(asset.name())),
+            Cell::new(format_bytes(asset.compressed_size())).set_alignment(CellAlignment::Right),
+            decompressed,
+            ratio_cell,
+            confidence,
+            Cell::new(format!("{:#x}", asset.location().data_offset))
+                .set_alignment(CellAlignment::Right),
+        ];
+        if let Some(measured) = measured.get(asset.name()) {
+            let mut cell =
+                Cell::new(format!("{:.2}", measured.entropy)).set_alignment(CellAlignment::Right);
+            if measured.outlier {
+                cell = Cell::new(format!("{:.2} !", measured.entropy))
+                    .set_alignment(CellAlignment::Right)
+                    .fg(Color::Red);
+            }
+            row.push(cell);
+        }
+        output.add_row(row);
+    }
+    println!("Assets: {}", table.len());
+    println!("{output}");
+    let outliers = measured
+        .values()
+        .filter(|measured| measured.outlier)
+        .count();
+    if outliers > 0 {
+        println!(
+            "{outliers} assets marked ! are as random as encrypted data; run `audit` for details"
+        );
+    }
+}
+
+/// One row per asset. `compressed_sha256` hashes the bytes stored in the
+/// binary and `sha256` the decoded file, as it is extracted.
+fn print_asset_csv(
+    table: &tauri_dumper::AssetTable,
+    sort_by: SortBy,
+    entropy: Option<&[AssetEntropy]>,
+) -> Result<()> {
+    let measured = entropy_by_name(entropy);
+    let mut csv = String::from(
+        "path,mime,encoding,compressed_size,decompressed_size,header_offset,name_offset,\
+         data_offset,table_index,confidence,alias_of,compressed_sha256,sha256",
+    );
+    if entropy.is_some() {
+        csv.push_str(",entropy,entropy_outlier");
+    }
+    csv.push('\n');
+    for asset in sorted_assets(table, sort_by) {
+        let data = tauri_dumper::extract::decompress_asset(asset)
+            .with_context(|| format!("failed to decompress {}", asset.name()))?;
+        let location = asset.location();
+        let _ = write!(
+            csv,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{:x}",
+            ioc::csv_field(asset.name()),
+            tauri_dumper::mime::mime_type(asset.name()),
+            format!("{:?}", asset.encoding()).to_lowercase(),
+            asset.compressed_size(),
+            asset.decompressed_size(),
+            location.header_offset,
+            location.name_offset,
+            location.data_offset,
+            asset.table_index(),
+            asset.confidence(),
+            ioc::csv_field(asset.alias_of().unwrap_or_default()),
+            asset.compressed_sha256(),
+            Sha256::digest(&data),
+        );
+        if let Some(measured) = measured.get(asset.name()) {
+            let _ = write!(csv, ",{:.4},{}", measured.entropy, measured.outlier);
+        }
+        csv.push('\n');
+    }
+    print!("{csv}");
+    Ok(())
+}
+
+/// Compressed size as a share of the decompressed size.
+fn compression_ratio(asset: &tauri_dumper::Asset) -> f64 {
+    if asset.decompressed_size() == 0 {
+        return 1.0;
+    }
+    asset.compressed_size() as f64 / asset.decompressed_size() as f64
+}
+
+fn print_inspect_summary(
+    summary: &AssetTableSummary,
+    frontend: &FrontendReport,
+    packing: &PackingReport,
+) {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec!["Field", "Value"]);
+    table.add_row(vec!["Format", &format!("{:?}", summary.binary.kind)]);
+    table.add_row(vec!["Architecture", &summary.binary.architecture]);
+    table.add_row(vec!["File size", &summary.binary.file_size.to_string()]);
+    table.add_row(vec!["SHA-256", &summary.binary.sha256]);
+    if !summary.binary.containers.is_empty() {
+        let chain = summary
+            .binary
+            .containers
+            .iter()
+            .map(|step| format!("{}: {}", step.kind.as_str(), step.entry))
+            .collect::<Vec<_>>();
+        table.add_row(vec!["Unpacked from", &chain.join("\n")]);
+    }
+    if let Some(bundle) = &summary.binary.bundle {
+        add_bundle_rows(&mut table, bundle);
+    }
+    if let Some(signature) = &summary.binary.code_signature {
+        add_code_signature_rows(&mut table, signature);
+    }
+    if let Some(encryption) = &summary.binary.encryption {
+        let state = if encryption.encrypted {
+            "encrypted (FairPlay)"
+        } else {
+            "decrypted"
+        };
+        table.add_row(vec![
+            "Encryption",
+            &format!(
+                "{state}, {} bytes at {:#x}",
+                encryption.size, encryption.offset
+            ),
+        ]);
+    }
+    if let Some(build) = &summary.binary.build {
+        add_build_rows(&mut table, build);
+    }
+    if let Some(hardening) = &summary.binary.hardening {
+        add_hardening_rows(&mut table, hardening);
+    }
+    let url_schemes = url_schemes(&summary.binary);
+    if !url_schemes.is_empty() {
+        table.add_row(vec!["URL schemes", &url_schemes.join("\n")]);
+    }
+    if let Some(deep_links) = summary.binary.deep_links.as_ref() {
+        if !deep_links.hosts.is_empty() {
+            table.add_row(vec!["Deep-link hosts", &deep_links.hosts.join("\n")]);
+        }
+    }
+    if let Some(url) = &summary.binary.dev_url {
+        if summary.asset_count == 0 {
+            table.add_row(vec!["Dev server", &format!("{url} (dev build)")]);
+        } else {
+            table.add_row(vec!["Dev server", url]);
+        }
+    }
+    if let Some(toolchain) = &summary.binary.toolchain {
+        let rustc = match (&toolchain.version, &toolchain.commit) {
+            (Some(version), Some(commit)) => format!("{version} ({commit})"),
+            (Some(version), None) => version.clone(),
+            (None, Some(commit)) => format!("commit {commit}"),
+            (None, None) => String::new(),
+        };
+        table.add_row(vec!["rustc", &rustc]);
+    }
+    let crates = summary
+        .binary
+        .crates
+        .iter()
+        .filter(|krate| is_notable_crate(&krate.name))
+        .map(|krate| format!("{} {}", krate.name, krate.version))
+        .collect::<Vec<_>>();
+    if !crates.is_empty() {
+        table.add_row(vec!["Crates", &crates.join("\n")]);
+    }
+    if let Some(webview) = &summary.binary.webview {
+        add_webview_rows(&mut table, webview);
+    }
+    if !summary.binary.plugins.is_empty() {
+        let plugins = summary
+            .binary
+            .plugins
+            .iter()
+            .map(|plugin| match &plugin.version {
+                Some(version) => format!("{} {version}", plugin.name),
+                None => plugin.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        table.add_row(vec!["Tauri plugins", &plugins.join("\n")]);
+    }
+    if !summary.binary.features.is_empty() {
+        let features = summary
+            .binary
+            .features
+            .iter()
+            .map(|found| format!("{} ({})", found.feature.as_str(), found.evidence.join(", ")))
+            .collect::<Vec<_>>();
+        table.add_row(vec!["Tauri features", &features.join("\n")]);
+    }
+    table.add_row(vec!["Assets", &summary.asset_count.to_string()]);
+    table.add_row(vec!["Asset tables", &summary.table_count.to_string()]);
+    table.add_row(vec![
+        "Compressed bytes",
+        &summary.total_compressed_size.to_string(),
+    ]);
+    table.add_row(vec![
+        "Decompressed bytes",
+        &summary.total_decompressed_size.to_string(),
+    ]);
+    for (field, detections) in [
+        ("Frameworks", &frontend.frameworks),
+        ("Bundlers", &frontend.bundlers),
+    ] {
+        if !detections.is_empty() {
+            let names = detections
+                .iter()
+                .map(|detection| match &detection.version {
+                    Some(version) => format!("{} {version}", detection.name),
+                    None => detection.name.clone(),
+                })
+                .collect::<Vec<_>>();
+            table.add_row(vec![field, &names.join("\n")]);
+        }
+    }
+    table.add_row(vec![
+        "Entropy",
+        &format!("{:.2} bits/byte", packing.entropy),
+    ]);
+    let packing = if packing.is_packed() {
+        packing
+            .indicators
+            .iter()
+            .map(|indicator| indicator.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        "none detected".to_string()
+    };
+    table.add_row(vec!["Packing", &packing]);
+    println!("{table}");
+}
+
+/// Whether `inspect` lists crate `name`: Tauri's own crates and those that
+/// handle the network, cryptography or untrusted input. `--json` lists all.
+fn is_notable_crate(name: &str) -> bool {
+    name == "tauri"
+        || (name.starts_with("tauri-") && !name.starts_with("tauri-plugin-"))
+        || NOTABLE_CRATES.contains(&name)
+}
+
+fn add_webview_rows(table: &mut Table, webview: &WebViewRuntime) {
+    if let Some(engine) = webview.engine {
+        let mut name = engine.as_str().to_string();
+        if let Some(api) = &webview.webkitgtk_api {
+            name = format!("{name} (API {api})");
+        }
+        table.add_row(vec!["Webview", &name]);
+    }
+    if let Some(version) = &webview.wry_version {
+        table.add_row(vec!["wry", version]);
+    }
+    if let Some(bindings) = &webview.bindings {
+        table.add_row(vec![bindings.name.as_str(), &bindings.version]);
+    }
+    if let Some(loader) = webview.webview2_loader {
+        let loader = match loader {
+            WebView2Loader::Static => "statically linked",
+            WebView2Loader::Dll => "WebView2Loader.dll",
+        };
+        table.add_row(vec!["WebView2 loader", loader]);
+    }
+    if webview.engine == Some(WebViewEngine::WebView2) {
+        let runtime = match &webview.webview2_fixed_runtime {
+            Some(version) => format!("fixed version {version}"),
+            None => "evergreen".to_string(),
+        };
+        table.add_row(vec!["WebView2 runtime", &runtime]);
+    }
+}
+
+/// Lists the URL schemes from the embedded config and the bundle's
+/// `Info.plist`, with where each was found.
+fn url_schemes(binary: &BinaryMetadata) -> Vec<String> {
+    let config = binary
+        .deep_links
+        .iter()
+        .flat_map(|links| &links.schemes)
+        .map(|scheme| format!("{scheme}:// (config)"));
+    let info_plist = binary
+        .bundle
+        .iter()
+        .flat_map(|bundle| &bundle.url_schemes)
+        .map(|scheme| format!("{scheme}:// (Info.plist)"));
+    config.chain(info_plist).collect()
+}
+
+fn add_bundle_rows(table: &mut Table, bundle: &BundleInfo) {
+    let rows = [
+        ("Bundle identifier", &bundle.identifier),
+        ("Bundle name", &bundle.name),
+        ("Bundle version", &bundle.version),
+        ("Bundle build", &bundle.build),
+        ("Minimum macOS", &bundle.minimum_os),
+    ];
+    for (field, value) in rows {
+        if let Some(value) = value {
+            table.add_row(vec![field, value]);
+        }
+    }
+}
+
+fn add_build_rows(table: &mut Table, build: &BuildInfo) {
+    if let Some(timestamp) = &build.timestamp {
+        table.add_row(vec!["Linked at", timestamp]);
+    }
+    if let Some(platform) = &build.platform {
+        table.add_row(vec!["Platform", platform]);
+    }
+    if let Some(version) = &build.min_os_version {
+        table.add_row(vec!["Minimum OS", version]);
+    }
+    if let Some(version) = &build.sdk_version {
+        table.add_row(vec!["SDK", version]);
+    }
+}
+
+fn add_hardening_rows(table: &mut Table, hardening: &Hardening) {
+    let checks = [
+        (hardening.overflow_checks, "overflow checks"),
+        (hardening.debug_assertions, "debug assertions"),
+    ]
+    .into_iter()
+    .filter_map(|(found, check)| found.then_some(check))
+    .collect::<Vec<_>>();
+    let profile = if checks.is_empty() {
+        hardening.profile.as_str().to_string()
+    } else {
+        format!("{} ({})", hardening.profile.as_str(), checks.join(", "))
+    };
+    table.add_row(vec!["Build profile", &profile]);
+    let symbols = match (hardening.symbols, hardening.debug_info) {
+        (0, false) => "stripped".to_string(),
+        (0, true) => "debug info only".to_string(),
+        (count, false) => count.to_string(),
+        (count, true) => format!("{count}, with debug info"),
+    };
+    table.add_row(vec!["Symbols", &symbols]);
+    let mut mitigations = Vec::new();
+    for (enabled, name) in [
+        (hardening.aslr, "ASLR"),
+        (hardening.nx, "NX"),
+        (hardening.cfg, "CFG"),
+    ] {
+        if let Some(enabled) = enabled {
+            mitigations.push(format!("{name} {}", if enabled { "yes" } else { "no" }));
+        }
+    }
+    if !mitigations.is_empty() {
+        table.add_row(vec!["Mitigations", &mitigations.join(", ")]);
+    }
+}
+
+fn add_code_signature_rows(table: &mut Table, signature: &CodeSignature) {
+    let unknown = || "-".to_string();
+    let signed_by = if signature.ad_hoc {
+        "ad-hoc".to_string()
+    } else {
+        signature.signing_identity.clone().unwrap_or_else(unknown)
+    };
+    table.add_row(vec!["Signed by", &signed_by]);
+    table.add_row(vec![
+        "Team ID",
+        &signature.team_id.clone().unwrap_or_else(unknown),
+    ]);
+    table.add_row(vec![
+        "Signing identifier",
+        &signature.identifier.clone().unwrap_or_else(unknown),
+    ]);
+    table.add_row(vec![
+        "Hardened runtime",
+        if signature.hardened_runtime {
+            "yes"
+        } else {
+            "no"
+        },
+    ]);
+    if let Some(entitlements) = &signature.entitlements {
+        table.add_row(vec!["Entitlements", &entitlement_keys(entitlements)]);
+    }
+}
+
+/// Lists the keys of an entitlements plist, one per line, or returns the raw
+/// XML if it does not parse.
+fn entitlement_keys(xml: &str) -> String {
+    match plist::Value::from_reader_xml(xml.as_bytes()) {
+        Ok(plist::Value::Dictionary(entitlements)) => entitlements
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => xml.to_string(),
+    }
+}
+
+fn print_export_summary(summary: &ExportSummary) {
+    println!("Export complete");
+    println!("  output: {}", summary.output_dir.display());
+    println!("  exported: {}", summary.exported);
+    println!("  skipped existing: {}", summary.skipped_existing);
+    println!("  skipped by filter: {}", summary.skipped_filter);
+    if summary.deduplicated > 0 {
+        println!("  deduplicated: {}", summary.deduplicated);
+    }
+    if summary.failed > 0 {
+        println!("  failed: {}", summary.failed);
+    }
+    if summary.dry_run {
+        println!("  dry run: no files were written");
+    }
+    for record in &summary.records {
+        if let Some(error) = &record.error {
+            println!("  failed: {} ({error})", record.name);
+        }
+    }
+}
+
+fn print_repack_summary(summary: &RepackSummary) {
+    println!("Repack complete");
+    if let Some(output) = &summary.output {
+        println!("  output: {}", output.display());
+    }
+    if let Some(patch) = &summary.patch {
+        println!("  patch: {}", patch.display());
+    }
+    println!("  replaced: {}", summary.replaced);
+    println!("  unchanged: {}", summary.unchanged);
+    println!("  skipped oversized: {}", summary.skipped_oversized);
+    println!(
+        "  unsupported additions: {}",
+        summary.unsupported_additions.len()
+    );
+    for edit in &summary.config_edits {
+        println!(
+            "  set {} at {:#x} ({} bytes)",
+            edit.key, edit.offset, edit.length
+        );
+    }
+    if summary.dry_run {
+        println!("  dry run: no binary was written");
+    }
+    for oversized in &summary.oversized {
+        println!(
+            "  oversized: {} ({} > {}, +{})",
+            oversized.asset,
+            oversized.new_compressed_size,
+            oversized.original_compressed_size,
+            oversized.delta
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Polling directories for changed files, for the `watch` and `devloop`
+//! commands.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// What a file looked like when it was last polled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Fingerprint {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Fingerprints the files in `dir`, and in its subdirectories if
+/// `recursive` is set.
+pub(crate) fn poll(dir: &Path, recursive: bool) -> Result<BTreeMap<PathBuf, Fingerprint>> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current)
+            .with_context(|| format!("failed to read {}", current.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() && recursive {
+                pending.push(entry.path());
+            } else if metadata.is_file() {
+                files.insert(
+                    entry.path(),
+                    Fingerprint {
+                        len: metadata.len(),
+                        modified: metadata.modified().ok(),
+                    },
+                );
+            }
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,184 @@
+//! Showing an app's frontend in one step, for the `open` command.
+//!
+//! The assets are extracted to a temporary directory, which is served over
+//! HTTP on a free local port and opened in the default browser, or opened in
+//! the file manager with `--files`. The directory is removed once Enter is
+//! pressed or stdin is closed.
+
+use super::http::{percent_decode, read_request, write_response, Request, Response};
+use super::{extract, print_json, CommonArgs, OpenCommand};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use tauri_dumper::mime;
+
+const INDEX_FILE_NAME: &str = "index.html";
+
+#[derive(Serialize)]
+struct Preview<'a> {
+    dir: &'a Path,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<&'a str>,
+}
+
+/// Extracts `command.input`, shows it, and waits for Enter before removing
+/// the extracted files.
+pub(crate) fn run(command: &OpenCommand) -> Result<()> {
+    let dir = tempfile::Builder::new()
+        .prefix("tauri-dumper-")
+        .tempdir()
+        .context("failed to create a temporary directory")?;
+    // Only the preview is reported in JSON.
+    let common = if command.common.json {
+        CommonArgs {
+            json: false,
+            quiet: true,
+            ..command.common.clone()
+        }
+    } else {
+        command.common.clone()
+    };
+    extract(&command.input, dir.path(), &command.flags, &common)?;
+
+    let url = if command.files {
+        if let Err(error) = launch(dir.path().as_os_str(), false) {
+            log::warn!(
+                "failed to open a file manager: {error:#}; the files are in {}",
+                dir.path().display()
+            );
+        }
+        None
+    } else {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .context("failed to listen on a local port for the preview")?;
+        let url = format!("http://{}/", listener.local_addr()?);
+        let root = Arc::<Path>::from(dir.path());
+        thread::spawn(move || serve(&listener, &root));
+        if let Err(error) = launch(OsStr::new(&url), true) {
+            log::warn!("failed to open a browser: {error:#}; open {url} yourself");
+        }
+        Some(url)
+    };
+
+    if command.common.json {
+        print_json(&Preview {
+            dir: dir.path(),
+            url: url.as_deref(),
+        })?;
+    } else if !command.common.quiet {
+        match &url {
+            Some(url) => println!("Serving {} at {url}", dir.path().display()),
+            None => println!("Opened {}", dir.path().display()),
+        }
+        println!("Press Enter to stop and remove the extracted files");
+    }
+    io::stdout().flush()?;
+
+    io::stdin().read_line(&mut String::new())?;
+    let path = dir.path().to_path_buf();
+    dir.close()
+        .with_context(|| format!("failed to remove {}", path.display()))
+}
+
+/// Opens a URL in the browser or a directory in the file manager: with
+/// `$BROWSER` for URLs if it is set, and otherwise with `open` on macOS,
+/// `explorer` on Windows and `xdg-open` elsewhere.
+fn launch(target: &OsStr, url: bool) -> Result<()> {
+    let program = match std::env::var_os("BROWSER").filter(|_| url) {
+        Some(browser) => browser,
+        None if cfg!(target_os = "macos") => "open".into(),
+        None if cfg!(windows) => "explorer".into(),
+        None => "xdg-open".into(),
+    };
+    // Browsers started directly keep running, so the child is not waited
+    // for.
+    Command::new(&program)
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to run {}", program.to_string_lossy()))?;
+    Ok(())
+}
+
+fn serve(listener: &TcpListener, root: &Arc<Path>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                log::warn!("failed to accept a connection: {error}");
+                continue;
+            }
+        };
+        let root = Arc::clone(root);
+        thread::spawn(move || {
+            if let Err(error) = handle(&root, stream) {
+                log::debug!("failed to serve a request: {error}");
+            }
+        });
+    }
+}
+
+fn handle(root: &Path, stream: TcpStream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let response = match read_request(&mut reader)? {
+        Ok(request) => {
+            log::info!("{} {}", request.method, request.path);
+            respond(root, &request)
+        }
+        Err(response) => response,
+    };
+    write_response(stream, &response)
+}
+
+/// Serves the file the request path names under `root`, with `index.html`
+/// standing in for directories. Single-page apps route in the browser, so a
+/// missing path without an extension gets the top-level `index.html` too.
+fn respond(root: &Path, request: &Request) -> Response {
+    if request.method != "GET" {
+        return Response::error(405, format!("{} is not allowed", request.method));
+    }
+    let path = percent_decode(&request.path);
+    let Some(file) = resolve(root, &path) else {
+        return Response::error(404, format!("no file at {path}"));
+    };
+    let file = if file.is_dir() {
+        file.join(INDEX_FILE_NAME)
+    } else if !file.exists() && file.extension().is_none() {
+        root.join(INDEX_FILE_NAME)
+    } else {
+        file
+    };
+    match fs::read(&file) {
+        Ok(body) => Response {
+            status: 200,
+            content_type: mime::mime_type(&file.to_string_lossy()),
+            headers: Vec::new(),
+            body,
+        },
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            Response::error(404, format!("no file at {path}"))
+        }
+        Err(error) => Response::error(500, format!("failed to read {path}: {error}")),
+    }
+}
+
+/// Maps a URL path to a file under `root`, or `None` if it would leave it.
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let mut file = root.to_path_buf();
+    for component in path.split('/').filter(|component| !component.is_empty()) {
+        if matches!(component, "." | "..") || component.contains(['\\', ':']) {
+            return None;
+        }
+        file.push(component);
+    }
+    Some(file)
+}
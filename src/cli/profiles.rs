@@ -0,0 +1,155 @@
+//! Named profiles of default flags, read from `config.toml` in the user's
+//! configuration directory:
+//!
+//! ```toml
+//! [profiles.audit]
+//! json = true
+//! min-confidence = 60
+//! exclude = ["*.map"]
+//! output-format = "zip"
+//! ```
+//!
+//! Each key is the long name of a flag. `--config-profile audit` inserts the
+//! flags of the profile that the command takes ahead of its command line,
+//! which overrides single values and adds to lists. Keys other commands take
+//! are left out, so one profile serves every command. `[profiles.default]`
+//! applies when no profile is named.
+
+use anyhow::{Context, Result};
+use clap::Command;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+pub(super) const CONFIG_FILE_NAME: &str = "config.toml";
+const PROFILE_FLAG: &str = "--config-profile";
+const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    #[serde(default)]
+    profiles: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+/// `tauri-dumper/config.toml` in `%APPDATA%` on Windows, and in
+/// `$XDG_CONFIG_HOME` or `~/.config` elsewhere.
+pub(super) fn config_path() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }?;
+    Some(base.join("tauri-dumper").join(CONFIG_FILE_NAME))
+}
+
+/// Inserts the flags of the selected profile into `args`, right after the
+/// subcommand of `cli` they are given to.
+pub(super) fn expand(mut args: Vec<OsString>, cli: Command) -> Result<Vec<OsString>> {
+    let selected = selected_profile(&args);
+    let Some(path) = config_path() else {
+        if let Some(name) = selected {
+            anyhow::bail!("no configuration directory to read profile `{name}` from");
+        }
+        return Ok(args);
+    };
+    let config = match fs::read_to_string(&path) {
+        Ok(text) => toml::from_str::<Config>(&text)
+            .with_context(|| format!("invalid configuration file {}", path.display()))?,
+        Err(error) if error.kind() == ErrorKind::NotFound && selected.is_none() => return Ok(args),
+        Err(error) => {
+            return Err(error).with_context(|| format!("failed to read {}", path.display()))
+        }
+    };
+    let name = selected.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(profile) = config.profiles.get(name) else {
+        if selected.is_none() {
+            return Ok(args);
+        }
+        let known = config.profiles.keys().cloned().collect::<Vec<_>>();
+        anyhow::bail!(
+            "no profile `{name}` in {}, expected one of: {}",
+            path.display(),
+            known.join(", ")
+        );
+    };
+
+    let mut command = &cli;
+    let mut at = 1;
+    while let Some(subcommand) = args
+        .get(at)
+        .and_then(|arg| arg.to_str())
+        .and_then(|arg| command.find_subcommand(arg))
+    {
+        command = subcommand;
+        at += 1;
+    }
+    let mut flags = Vec::new();
+    for (key, value) in profile {
+        if command
+            .get_arguments()
+            .any(|arg| arg.get_long() == Some(key))
+        {
+            push_flag(&mut flags, key, value)
+                .with_context(|| format!("invalid `{key}` in profile `{name}`"))?;
+        } else if !takes_flag(&cli, key) {
+            anyhow::bail!("unknown option `{key}` in profile `{name}`");
+        }
+    }
+    args.splice(at..at, flags);
+    Ok(args)
+}
+
+/// The value of `--config-profile`, before any `--`.
+fn selected_profile(args: &[OsString]) -> Option<String> {
+    let mut args = args
+        .iter()
+        .skip(1)
+        .map(|arg| arg.to_string_lossy())
+        .take_while(|arg| arg != "--");
+    while let Some(arg) = args.next() {
+        if arg == PROFILE_FLAG {
+            return args.next().map(|name| name.into_owned());
+        }
+        if let Some(name) = arg
+            .strip_prefix(PROFILE_FLAG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+/// Whether `command` or any of its subcommands takes `--<long>`.
+fn takes_flag(command: &Command, long: &str) -> bool {
+    command
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some(long))
+        || command
+            .get_subcommands()
+            .any(|subcommand| takes_flag(subcommand, long))
+}
+
+fn push_flag(flags: &mut Vec<OsString>, key: &str, value: &toml::Value) -> Result<()> {
+    match value {
+        toml::Value::Boolean(true) => flags.push(format!("--{key}").into()),
+        toml::Value::Boolean(false) => {}
+        toml::Value::String(value) => flags.push(format!("--{key}={value}").into()),
+        toml::Value::Integer(value) => flags.push(format!("--{key}={value}").into()),
+        toml::Value::Float(value) => flags.push(format!("--{key}={value}").into()),
+        toml::Value::Array(values) => {
+            for value in values {
+                push_flag(flags, key, value)?;
+            }
+        }
+        _ => anyhow::bail!("expected a boolean, string, number or list"),
+    }
+    Ok(())
+}
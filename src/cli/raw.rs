@@ -0,0 +1,135 @@
+//! Dumping the raw bytes of an asset as they sit in the binary, for the
+//! `raw` command.
+
+use super::{print_json, scan, RawCommand, Scan};
+use anyhow::Result;
+use serde::Serialize;
+use std::ops::Range;
+use tauri_dumper::{Asset, AssetEncoding, AssetLocation};
+
+#[derive(Serialize)]
+struct RawDump<'a> {
+    asset: &'a str,
+    encoding: AssetEncoding,
+    location: &'a AssetLocation,
+    /// The header fields, hex-encoded.
+    header: String,
+    /// The stored data, hex-encoded, up to `--length` bytes.
+    data: String,
+    truncated: bool,
+}
+
+/// Prints where the header of `command.asset` is and a hexdump of its
+/// header and stored data, read from the binary rather than the scan.
+pub(crate) fn run(command: &RawCommand) -> Result<()> {
+    let Scan { image, table, .. } = scan(&command.input, &command.common)?;
+    let Some(asset) = table
+        .find(&command.asset)
+        .or_else(|| table.find(&format!("/{}", command.asset)))
+    else {
+        anyhow::bail!(
+            "{} has no asset named {}",
+            command.input.display(),
+            command.asset
+        );
+    };
+    let location = asset.location();
+    let header = header_range(location);
+    let data = location.data_offset..location.data_offset + location.original_compressed_size;
+    let shown = if command.length == 0 {
+        data.clone()
+    } else {
+        data.start..data.end.min(data.start + command.length)
+    };
+    let bytes = |range: Range<usize>| image.data().get(range).unwrap_or_default();
+
+    if command.common.json {
+        return print_json(&RawDump {
+            asset: asset.name(),
+            encoding: asset.encoding(),
+            location,
+            header: hex(bytes(header)),
+            data: hex(bytes(shown.clone())),
+            truncated: shown.end < data.end,
+        });
+    }
+    print_location(asset);
+    println!("Header:");
+    print_hex_dump(header.start, bytes(header.clone()));
+    println!("Data:");
+    print_hex_dump(shown.start, bytes(shown.clone()));
+    if shown.end < data.end {
+        println!("... {} more bytes", data.end - shown.end);
+    }
+    Ok(())
+}
+
+/// The header fields, which end with the data size for Tauri headers and
+/// start before the name for rust-embed records.
+fn header_range(location: &AssetLocation) -> Range<usize> {
+    let end = (location.data_size_offset + location.word_size)
+        .max(location.header_offset + 4 * location.word_size);
+    location.header_offset..end
+}
+
+fn print_location(asset: &Asset) {
+    let location = asset.location();
+    println!(
+        "{} ({:?}, table {})",
+        asset.name(),
+        asset.encoding(),
+        location.table_index
+    );
+    println!(
+        "  header:     {:#x} in scan range {:#x}..{:#x}",
+        location.header_offset,
+        location.scan_range.start,
+        location.scan_range.start + location.scan_range.length
+    );
+    println!("  name:       {:#x}", location.name_offset);
+    println!(
+        "  data:       {:#x} ({} bytes, {} decoded)",
+        location.data_offset,
+        location.original_compressed_size,
+        asset.decompressed_size()
+    );
+    println!(
+        "  data size:  {:#x} ({}-byte word)",
+        location.data_size_offset, location.word_size
+    );
+    if let Some(offset) = location.content_hash_offset {
+        println!("  hash:       {offset:#x}");
+    }
+}
+
+fn print_hex_dump(start: usize, data: &[u8]) {
+    for (row, chunk) in data.chunks(16).enumerate() {
+        println!("{}", hex_line(start + row * 16, chunk));
+    }
+}
+
+/// One hexdump line: the offset, up to 16 bytes in hex and as ASCII.
+pub(crate) fn hex_line(offset: usize, chunk: &[u8]) -> String {
+    let ascii = chunk
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect::<String>();
+    format!(
+        "{offset:08x}  {:<47}  {ascii}",
+        chunk
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    )
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
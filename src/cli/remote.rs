@@ -0,0 +1,87 @@
+//! Comparing the embedded assets with a web deployment of the same
+//! frontend, for the `diff-remote` command.
+
+use super::download;
+use super::{finish_spinner, print_json, scan, spinner, DiffRemoteCommand, Scan};
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use tauri_dumper::mime::mime_type;
+use tauri_dumper::remote::{compare_remote, DriftKind, RemoteComparison};
+use tauri_dumper::Error;
+
+#[derive(Serialize)]
+struct DiffRemoteSummary<'a> {
+    ok: bool,
+    origin: &'a str,
+    #[serde(flatten)]
+    comparison: &'a RemoteComparison,
+}
+
+pub(crate) fn run(command: &DiffRemoteCommand) -> Result<()> {
+    let origin = command.origin.trim_end_matches('/');
+    if !download::is_url(Path::new(origin)) {
+        anyhow::bail!("{} is not an http or https URL", command.origin);
+    }
+    let Scan { table, .. } = scan(&command.input, &command.common)?;
+
+    let spinner = spinner(&command.common, "fetching assets");
+    let max_size = command.common.max_download_size;
+    let comparison = compare_remote(&table, |name| {
+        let url = format!("{origin}/{}", name.trim_start_matches('/'));
+        let fetched = download::fetch(&url, max_size)
+            .map_err(|error| Error::Message(format!("{error:#}")))?;
+        // Single-page apps answer every unknown path with their index page.
+        Ok(fetched
+            .filter(|fetched| {
+                mime_type(name) == "text/html"
+                    || !fetched
+                        .content_type
+                        .as_deref()
+                        .is_some_and(|content_type| content_type.starts_with("text/html"))
+            })
+            .map(|fetched| fetched.data))
+    })?;
+    finish_spinner(spinner);
+
+    if command.common.json {
+        print_json(&DiffRemoteSummary {
+            ok: comparison.is_in_sync(),
+            origin,
+            comparison: &comparison,
+        })?;
+    } else if !command.common.quiet {
+        if comparison.is_in_sync() {
+            println!(
+                "OK: {origin} serves all {} assets unchanged",
+                comparison.identical
+            );
+        } else {
+            println!(
+                "{} assets identical on {origin}, {} differ",
+                comparison.identical,
+                comparison.drifts.len()
+            );
+        }
+        for drift in &comparison.drifts {
+            let detail = match drift.kind {
+                DriftKind::Modified => format!(
+                    ": {} bytes embedded, {} served",
+                    drift.embedded_size.unwrap_or_default(),
+                    drift.remote_size.unwrap_or_default()
+                ),
+                DriftKind::Unreachable => format!(": {}", drift.error.as_deref().unwrap_or("")),
+                DriftKind::MissingRemote | DriftKind::RemoteOnly => String::new(),
+            };
+            println!(
+                "  {} {}{detail}",
+                drift.kind.as_str().to_uppercase(),
+                drift.asset
+            );
+        }
+    }
+    if !comparison.is_in_sync() {
+        anyhow::bail!("{} assets drift from {origin}", comparison.drifts.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+//! Extracting into a staging directory that takes the place of the output
+//! only once the extraction succeeds.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Where an extraction writes before its output is in place.
+///
+/// A new output is written into a hidden sibling directory, renamed to the
+/// output once everything is written, and removed if the extraction fails
+/// first. An existing output, which the extraction adds to, is written in
+/// place.
+pub(crate) struct Staging {
+    output: PathBuf,
+    dir: Option<PathBuf>,
+}
+
+impl Staging {
+    pub(crate) fn new(output: &Path, dry_run: bool) -> Result<Self> {
+        if dry_run || output.exists() {
+            return Ok(Self {
+                output: output.to_path_buf(),
+                dir: None,
+            });
+        }
+        let parent = match output.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+        let name = output
+            .file_name()
+            .map_or_else(|| "output".into(), |name| name.to_string_lossy());
+        for attempt in 0.. {
+            let dir = parent.join(format!(".{name}.partial-{}-{attempt}", std::process::id()));
+            match fs::create_dir(&dir) {
+                Ok(()) => {
+                    log::debug!("staging the extraction in {}", dir.display());
+                    return Ok(Self {
+                        output: output.to_path_buf(),
+                        dir: Some(dir),
+                    });
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => continue,
+                Err(error) => {
+                    return Err(error)
+                        .with_context(|| format!("failed to create {}", dir.display()))
+                }
+            }
+        }
+        unreachable!("staging directory names are unbounded")
+    }
+
+    /// The directory to write into.
+    pub(crate) fn path(&self) -> &Path {
+        self.dir.as_deref().unwrap_or(&self.output)
+    }
+
+    /// Where `path`, below [`path`](Self::path), ends up once committed.
+    pub(crate) fn relocate(&self, path: &Path) -> PathBuf {
+        match self.dir.as_deref().map(|dir| path.strip_prefix(dir)) {
+            Some(Ok(relative)) => self.output.join(relative),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Moves the staged files into place.
+    pub(crate) fn commit(mut self) -> Result<()> {
+        let Some(dir) = self.dir.take() else {
+            return Ok(());
+        };
+        if let Err(error) = fs::rename(&dir, &self.output) {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(error).with_context(|| {
+                format!(
+                    "failed to move the extraction into {}",
+                    self.output.display()
+                )
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
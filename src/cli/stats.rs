@@ -0,0 +1,152 @@
+//! Aggregate figures for the `stats` command.
+
+use super::{compression_ratio, format_bytes};
+use comfy_table::presets::UTF8_FULL_CONDENSED;
+use comfy_table::{Cell, CellAlignment, Table};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri_dumper::{Asset, AssetTable};
+
+/// How many of the largest assets are listed.
+const LARGEST_ASSETS: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct AssetStats {
+    pub(crate) asset_count: usize,
+    pub(crate) total_compressed_size: usize,
+    pub(crate) total_decompressed_size: usize,
+    /// Mean of the per-asset compressed/decompressed ratios.
+    pub(crate) average_ratio: f64,
+    pub(crate) by_extension: Vec<ExtensionStats>,
+    pub(crate) largest: Vec<LargeAsset>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ExtensionStats {
+    pub(crate) extension: String,
+    pub(crate) mime_type: &'static str,
+    pub(crate) count: usize,
+    pub(crate) compressed_size: usize,
+    pub(crate) decompressed_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct LargeAsset {
+    pub(crate) name: String,
+    pub(crate) compressed_size: usize,
+    pub(crate) decompressed_size: usize,
+}
+
+impl AssetStats {
+    pub(crate) fn new(table: &AssetTable) -> Self {
+        let assets = table.assets();
+        let mut by_extension = BTreeMap::<String, ExtensionStats>::new();
+        for asset in assets {
+            let extension = extension(asset.name());
+            let entry = by_extension
+                .entry(extension.clone())
+                .or_insert_with(|| ExtensionStats {
+                    extension,
+                    mime_type: tauri_dumper::mime::mime_type(asset.name()),
+                    count: 0,
+                    compressed_size: 0,
+                    decompressed_size: 0,
+                });
+            entry.count += 1;
+            entry.compressed_size += asset.compressed_size();
+            entry.decompressed_size += asset.decompressed_size();
+        }
+        let mut by_extension = by_extension.into_values().collect::<Vec<_>>();
+        by_extension.sort_by_key(|stats| std::cmp::Reverse(stats.decompressed_size));
+
+        let mut largest = assets.iter().collect::<Vec<_>>();
+        largest.sort_by_key(|asset| std::cmp::Reverse(asset.decompressed_size()));
+        let largest = largest
+            .into_iter()
+            .take(LARGEST_ASSETS)
+            .map(|asset| LargeAsset {
+                name: asset.name().to_string(),
+                compressed_size: asset.compressed_size(),
+                decompressed_size: asset.decompressed_size(),
+            })
+            .collect();
+
+        let average_ratio = if assets.is_empty() {
+            0.0
+        } else {
+            assets.iter().map(compression_ratio).sum::<f64>() / assets.len() as f64
+        };
+
+        Self {
+            asset_count: assets.len(),
+            total_compressed_size: assets.iter().map(Asset::compressed_size).sum(),
+            total_decompressed_size: assets.iter().map(Asset::decompressed_size).sum(),
+            average_ratio,
+            by_extension,
+            largest,
+        }
+    }
+
+    pub(crate) fn print(&self) {
+        println!("Assets: {}", self.asset_count);
+        println!(
+            "  compressed: {} ({} bytes)",
+            format_bytes(self.total_compressed_size),
+            self.total_compressed_size
+        );
+        println!(
+            "  decompressed: {} ({} bytes)",
+            format_bytes(self.total_decompressed_size),
+            self.total_decompressed_size
+        );
+        println!("  average ratio: {:.0}%", self.average_ratio * 100.0);
+
+        let mut types = Table::new();
+        types.load_preset(UTF8_FULL_CONDENSED);
+        types.set_header(vec![
+            "Extension",
+            "Type",
+            "Assets",
+            "Compressed",
+            "Decompressed",
+        ]);
+        for stats in &self.by_extension {
+            types.add_row(vec![
+                Cell::new(&stats.extension),
+                Cell::new(stats.mime_type),
+                Cell::new(stats.count).set_alignment(CellAlignment::Right),
+                size_cell(stats.compressed_size),
+                size_cell(stats.decompressed_size),
+            ]);
+        }
+        println!("{types}");
+
+        let mut largest = Table::new();
+        largest.load_preset(UTF8_FULL_CONDENSED);
+        largest.set_header(vec!["Largest assets", "Compressed", "Decompressed"]);
+        for asset in &self.largest {
+            largest.add_row(vec![
+                Cell::new(&asset.name),
+                size_cell(asset.compressed_size),
+                size_cell(asset.decompressed_size),
+            ]);
+        }
+        println!("{largest}");
+    }
+}
+
+fn size_cell(bytes: usize) -> Cell {
+    Cell::new(format_bytes(bytes)).set_alignment(CellAlignment::Right)
+}
+
+/// The lowercase extension of the file name, or `(none)`.
+fn extension(path: &str) -> String {
+    path.rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .filter(|(stem, _)| !stem.is_empty())
+        .map_or_else(
+            || "(none)".to_string(),
+            |(_, extension)| extension.to_ascii_lowercase(),
+        )
+}
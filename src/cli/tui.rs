@@ -0,0 +1,448 @@
+//! Interactive asset browser for the `tui` command.
+
+use super::format_bytes;
+use super::raw::hex_line;
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri_dumper::export::asset_output_path;
+use tauri_dumper::extract::decompress_asset;
+use tauri_dumper::AssetTable;
+
+/// Text previews stop after this many bytes.
+const PREVIEW_LIMIT: usize = 64 * 1024;
+/// Binary previews show this many bytes as a hex dump.
+const HEX_PREVIEW_LIMIT: usize = 512;
+const HELP: &str =
+    "↑↓ move  ←→ collapse/expand  space mark  a mark all  x extract  PgUp/PgDn scroll  q quit";
+
+/// Runs the browser until the user quits. Extracted files go to `output`.
+pub(crate) fn run(table: &AssetTable, output: &Path) -> Result<()> {
+    let mut app = App::new(table, output);
+    let mut terminal = ratatui::try_init().context("failed to initialize terminal")?;
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[derive(Default)]
+struct Directory {
+    directories: BTreeMap<String, Directory>,
+    files: BTreeMap<String, usize>,
+    expanded: bool,
+}
+
+impl Directory {
+    fn insert(&mut self, path: &str, index: usize) {
+        let mut components = path
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .collect::<Vec<_>>();
+        let Some(file_name) = components.pop() else {
+            return;
+        };
+        let mut directory = self;
+        for component in components {
+            directory = directory
+                .directories
+                .entry(component.to_string())
+                .or_default();
+        }
+        directory.files.insert(file_name.to_string(), index);
+    }
+
+    fn get_mut(&mut self, path: &[String]) -> Option<&mut Directory> {
+        path.iter()
+            .try_fold(self, |directory, name| directory.directories.get_mut(name))
+    }
+
+    fn get(&self, path: &[String]) -> Option<&Directory> {
+        path.iter()
+            .try_fold(self, |directory, name| directory.directories.get(name))
+    }
+
+    fn assets(&self, indices: &mut Vec<usize>) {
+        indices.extend(self.files.values());
+        for directory in self.directories.values() {
+            directory.assets(indices);
+        }
+    }
+}
+
+enum RowTarget {
+    Directory(Vec<String>),
+    Asset(usize),
+}
+
+struct Row {
+    depth: usize,
+    name: String,
+    target: RowTarget,
+}
+
+struct App<'a> {
+    table: &'a AssetTable,
+    output: &'a Path,
+    root: Directory,
+    rows: Vec<Row>,
+    state: ListState,
+    marked: HashSet<usize>,
+    preview: Option<(usize, Vec<Line<'static>>)>,
+    preview_scroll: u16,
+    status: String,
+    quit: bool,
+}
+
+impl<'a> App<'a> {
+    fn new(table: &'a AssetTable, output: &'a Path) -> Self {
+        let mut root = Directory {
+            expanded: true,
+            ..Directory::default()
+        };
+        for (index, asset) in table.assets().iter().enumerate() {
+            root.insert(asset.name(), index);
+        }
+        let mut app = Self {
+            table,
+            output,
+            root,
+            rows: Vec::new(),
+            state: ListState::default().with_selected(Some(0)),
+            marked: HashSet::new(),
+            preview: None,
+            preview_scroll: 0,
+            status: format!("{} assets", table.len()),
+            quit: false,
+        };
+        app.refresh_rows();
+        app
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.select_next(),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.set_expanded(true),
+            KeyCode::Left | KeyCode::Char('h') => self.set_expanded(false),
+            KeyCode::Char(' ') => self.toggle_mark(),
+            KeyCode::Char('a') => self.toggle_mark_all(),
+            KeyCode::Char('x') => self.extract(),
+            KeyCode::PageDown => self.preview_scroll = self.preview_scroll.saturating_add(10),
+            KeyCode::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(10),
+            _ => {}
+        }
+    }
+
+    fn refresh_rows(&mut self) {
+        fn walk(directory: &Directory, path: &mut Vec<String>, rows: &mut Vec<Row>) {
+            for (name, child) in &directory.directories {
+                path.push(name.clone());
+                rows.push(Row {
+                    depth: path.len() - 1,
+                    name: name.clone(),
+                    target: RowTarget::Directory(path.clone()),
+                });
+                if child.expanded {
+                    walk(child, path, rows);
+                }
+                path.pop();
+            }
+            for (name, &index) in &directory.files {
+                rows.push(Row {
+                    depth: path.len(),
+                    name: name.clone(),
+                    target: RowTarget::Asset(index),
+                });
+            }
+        }
+
+        let mut rows = Vec::new();
+        walk(&self.root, &mut Vec::new(), &mut rows);
+        self.rows = rows;
+        let last = self.rows.len().saturating_sub(1);
+        if self
+            .state
+            .selected()
+            .is_some_and(|selected| selected > last)
+        {
+            self.state.select(Some(last));
+        }
+    }
+
+    fn selected(&self) -> Option<&Row> {
+        self.state.selected().and_then(|index| self.rows.get(index))
+    }
+
+    fn select_previous(&mut self) {
+        self.state.select_previous();
+        self.preview_scroll = 0;
+    }
+
+    fn select_next(&mut self) {
+        if self
+            .state
+            .selected()
+            .is_some_and(|index| index + 1 < self.rows.len())
+        {
+            self.state.select_next();
+            self.preview_scroll = 0;
+        }
+    }
+
+    fn set_expanded(&mut self, expanded: bool) {
+        let Some(RowTarget::Directory(path)) = self.selected().map(|row| &row.target) else {
+            return;
+        };
+        let path = path.clone();
+        if let Some(directory) = self.root.get_mut(&path) {
+            directory.expanded = expanded;
+        }
+        self.refresh_rows();
+    }
+
+    fn selected_assets(&self) -> Vec<usize> {
+        match self.selected().map(|row| &row.target) {
+            Some(RowTarget::Asset(index)) => vec![*index],
+            Some(RowTarget::Directory(path)) => {
+                let mut indices = Vec::new();
+                if let Some(directory) = self.root.get(path) {
+                    directory.assets(&mut indices);
+                }
+                indices
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        let indices = self.selected_assets();
+        if indices.iter().all(|index| self.marked.contains(index)) {
+            for index in &indices {
+                self.marked.remove(index);
+            }
+        } else {
+            self.marked.extend(indices);
+        }
+        self.status = format!("{} marked", self.marked.len());
+    }
+
+    fn toggle_mark_all(&mut self) {
+        if self.marked.len() == self.table.len() {
+            self.marked.clear();
+        } else {
+            self.marked = (0..self.table.len()).collect();
+        }
+        self.status = format!("{} marked", self.marked.len());
+    }
+
+    /// Extracts the marked assets, or the selection when nothing is marked.
+    fn extract(&mut self) {
+        let indices = if self.marked.is_empty() {
+            self.selected_assets().into_iter().collect::<BTreeSet<_>>()
+        } else {
+            self.marked.iter().copied().collect()
+        };
+        let mut written = 0;
+        for index in indices {
+            let asset = &self.table.assets()[index];
+            match write_asset(self.output, asset) {
+                Ok(_) => written += 1,
+                Err(error) => {
+                    self.status = format!("failed to extract {}: {error:#}", asset.name());
+                    return;
+                }
+            }
+        }
+        self.status = format!("extracted {written} assets to {}", self.output.display());
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [tree, preview] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+
+        let items = self
+            .rows
+            .iter()
+            .map(|row| self.row_item(row))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(format!(" Assets ({}) ", self.table.len())))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree, &mut self.state);
+
+        let (title, lines) = self.preview();
+        let preview_widget = Paragraph::new(lines)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.preview_scroll, 0));
+        frame.render_widget(preview_widget, preview);
+
+        frame.render_widget(
+            Line::from(format!(" {}  |  {HELP}", self.status)).dim(),
+            status,
+        );
+    }
+
+    fn row_item(&self, row: &Row) -> ListItem<'static> {
+        let indent = "  ".repeat(row.depth);
+        let line = match &row.target {
+            RowTarget::Directory(path) => {
+                let expanded = self
+                    .root
+                    .get(path)
+                    .is_some_and(|directory| directory.expanded);
+                let marker = if expanded { "▾" } else { "▸" };
+                Line::from(format!("{indent}{marker} {}/", row.name)).bold()
+            }
+            RowTarget::Asset(index) => {
+                let mark = if self.marked.contains(index) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                let size = format_bytes(self.table.assets()[*index].decompressed_size());
+                Line::from(format!("{indent}{mark} {} ({size})", row.name))
+            }
+        };
+        ListItem::new(line)
+    }
+
+    fn preview(&mut self) -> (String, Vec<Line<'static>>) {
+        let Some(RowTarget::Asset(index)) = self.selected().map(|row| &row.target) else {
+            let count = self.selected_assets().len();
+            return (
+                " Directory ".to_string(),
+                vec![Line::from(format!("{count} assets"))],
+            );
+        };
+        let index = *index;
+        let asset = &self.table.assets()[index];
+        if self
+            .preview
+            .as_ref()
+            .is_none_or(|(cached, _)| *cached != index)
+        {
+            let lines = match decompress_asset(asset) {
+                Ok(data) => preview_lines(asset.name(), &data),
+                Err(error) => vec![Line::from(format!("failed to decompress: {error}")).red()],
+            };
+            self.preview = Some((index, lines));
+        }
+        let title = format!(
+            " {} ({}, {} compressed) ",
+            asset.name(),
+            tauri_dumper::mime::mime_type(asset.name()),
+            format_bytes(asset.compressed_size())
+        );
+        let lines = self
+            .preview
+            .as_ref()
+            .map(|(_, lines)| lines.clone())
+            .unwrap_or_default();
+        (title, lines)
+    }
+}
+
+fn write_asset(output: &Path, asset: &tauri_dumper::Asset) -> Result<PathBuf> {
+    let path = asset_output_path(output, asset)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, decompress_asset(asset)?)?;
+    Ok(path)
+}
+
+fn preview_lines(name: &str, data: &[u8]) -> Vec<Line<'static>> {
+    if let Some((width, height)) = image_dimensions(data) {
+        return vec![Line::from(format!(
+            "{} image, {width}x{height} pixels",
+            tauri_dumper::mime::mime_type(name)
+        ))];
+    }
+    let head = &data[..data.len().min(PREVIEW_LIMIT)];
+    if !head.contains(&0) {
+        if let Ok(text) = std::str::from_utf8(head).or_else(|error| {
+            // The limit may cut a character in half.
+            std::str::from_utf8(&head[..error.valid_up_to()])
+        }) {
+            let mut lines = text
+                .lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect::<Vec<_>>();
+            if data.len() > head.len() {
+                lines.push(Line::from(format!("... {} more bytes", data.len() - head.len())).dim());
+            }
+            return lines;
+        }
+    }
+    hex_dump(&data[..data.len().min(HEX_PREVIEW_LIMIT)])
+}
+
+fn hex_dump(data: &[u8]) -> Vec<Line<'static>> {
+    data.chunks(16)
+        .enumerate()
+        .map(|(row, chunk)| Line::from(hex_line(row * 16, chunk)))
+        .collect()
+}
+
+/// Reads the pixel size of PNG, GIF and JPEG images.
+fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |offset: usize| {
+        Some(u32::from_be_bytes(
+            data.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    let be16 = |offset: usize| {
+        Some(u16::from_be_bytes(
+            data.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+    let le16 = |offset: usize| {
+        Some(u16::from_le_bytes(
+            data.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some((le16(6)?.into(), le16(8)?.into()));
+    }
+    if data.starts_with(b"\xff\xd8") {
+        // Walk the segments to the first start-of-frame marker.
+        let mut offset = 2;
+        while *data.get(offset)? == 0xff {
+            let marker = *data.get(offset + 1)?;
+            let len = usize::from(be16(offset + 2)?);
+            if matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+                return Some((be16(offset + 7)?.into(), be16(offset + 5)?.into()));
+            }
+            offset += 2 + len;
+        }
+    }
+    None
+}
@@ -0,0 +1,104 @@
+//! Dumping binaries as they appear in a build directory, for the `watch`
+//! command.
+
+use super::poll::{poll, Fingerprint};
+use super::{export_options, hooks, WatchCommand};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri_dumper::{AssetScanner, BinaryImage};
+
+#[derive(Serialize)]
+struct WatchEvent<'a> {
+    binary: &'a Path,
+    output_dir: &'a Path,
+    exported: usize,
+}
+
+/// Polls `command.dir` and dumps every binary with embedded assets into its
+/// own `<file name>-<sha256 prefix>` directory under the output directory.
+///
+/// A file is only read once it is unchanged between two polls, so binaries
+/// still being written by the linker are not picked up half-way.
+pub(crate) fn run(command: &WatchCommand) -> Result<()> {
+    if !command.dir.is_dir() {
+        anyhow::bail!("{} is not a directory", command.dir.display());
+    }
+    let interval = Duration::from_millis(command.interval);
+    let mut observed = HashMap::<PathBuf, Fingerprint>::new();
+    let mut handled = HashMap::<PathBuf, Fingerprint>::new();
+    log::info!("watching {}", command.dir.display());
+
+    loop {
+        for (path, fingerprint) in poll(&command.dir, false)? {
+            if handled.get(&path) == Some(&fingerprint) {
+                continue;
+            }
+            if command.once || observed.get(&path) == Some(&fingerprint) {
+                if let Err(error) = dump(command, &path) {
+                    log::warn!("failed to dump {}: {error:#}", path.display());
+                }
+                handled.insert(path.clone(), fingerprint);
+            }
+            observed.insert(path, fingerprint);
+        }
+        if command.once {
+            return Ok(());
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn dump(command: &WatchCommand, path: &Path) -> Result<()> {
+    // Build directories are full of files that are not executables.
+    let image = match BinaryImage::open(path) {
+        Ok(image) => image,
+        Err(error) => {
+            log::debug!("skipping {}: {error}", path.display());
+            return Ok(());
+        }
+    };
+    let table = AssetScanner::scan_with_options(&image, &command.common.scan_options())?;
+    if table.is_empty() {
+        log::info!("no embedded assets in {}", path.display());
+        return Ok(());
+    }
+
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "binary".into(), |name| name.to_string_lossy());
+    let output_dir = command
+        .output
+        .join(format!("{file_name}-{}", &table.metadata().sha256[..12]));
+    if output_dir.exists() {
+        log::info!(
+            "{} was already dumped to {}",
+            path.display(),
+            output_dir.display()
+        );
+        return Ok(());
+    }
+    let summary = table.export(&export_options(&output_dir, &command.flags)?)?;
+    hooks::run_hooks(&command.flags, &summary, &output_dir)?;
+
+    if command.common.json {
+        // One line per dump, so the stream can be consumed as it grows.
+        let event = WatchEvent {
+            binary: path,
+            output_dir: &output_dir,
+            exported: summary.exported,
+        };
+        println!("{}", serde_json::to_string(&event)?);
+    } else if !command.common.quiet {
+        println!(
+            "{}: {} assets -> {}",
+            path.display(),
+            summary.exported,
+            output_dir.display()
+        );
+    }
+    Ok(())
+}
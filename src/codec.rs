@@ -1,9 +1,35 @@
+//! Asset encodings.
+//!
+//! An [`AssetCodec`] validates, decodes and encodes asset data stored in one
+//! encoding. Raw storage is always built in; Brotli, gzip, zstd and LZ4 are
+//! behind the `brotli`, `gzip`, `zstd` and `lz4` features. Other encodings can be registered
+//! in a [`CodecRegistry`].
+
+use crate::asset::AssetEncoding;
 use crate::error::{Error, Result};
+use std::fmt;
+#[cfg(any(
+    feature = "brotli",
+    feature = "gzip",
+    feature = "zstd",
+    feature = "lz4"
+))]
 use std::io::{Read, Write};
+use std::sync::Arc;
 
+#[cfg(feature = "brotli")]
 const BROTLI_QUALITIES: [u32; 12] = [11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
-const BROTLI_LGWIN: u32 = 22;
+/// The Brotli window size, as a power of two, that repack encodes with.
+pub const BROTLI_LGWIN: u32 = 22;
+/// The highest Brotli quality, which usually compresses best.
+pub const MAX_BROTLI_QUALITY: u32 = 11;
+/// The Brotli window sizes an encoder accepts.
+pub const BROTLI_WINDOWS: std::ops::RangeInclusive<u32> = 10..=24;
+
+/// Decoded bytes [`AssetCodec::verify_quick`] reads before accepting data.
+pub const QUICK_VERIFY_LIMIT: usize = 64 * 1024;
 
+#[cfg(feature = "brotli")]
 #[derive(Debug, Clone)]
 pub struct CompressionResult {
     pub data: Vec<u8>,
@@ -11,6 +37,7 @@ pub struct CompressionResult {
     pub lgwin: u32,
 }
 
+#[cfg(feature = "brotli")]
 pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
     let mut decompressor = brotli::Decompressor::new(data, data.len());
     let mut output = Vec::new();
@@ -20,15 +47,24 @@ pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
     Ok(output)
 }
 
+#[cfg(feature = "brotli")]
 pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
     Ok(compress_best(data)?.data)
 }
 
+#[cfg(feature = "brotli")]
 pub fn compress_best(data: &[u8]) -> Result<CompressionResult> {
+    compress_best_with_window(data, BROTLI_LGWIN)
+}
+
+/// Compresses `data` at every quality with window size `lgwin` and keeps
+/// the smallest output.
+#[cfg(feature = "brotli")]
+pub fn compress_best_with_window(data: &[u8], lgwin: u32) -> Result<CompressionResult> {
     let mut best = None;
 
     for quality in BROTLI_QUALITIES {
-        let candidate = compress_with_params(data, quality, BROTLI_LGWIN)?;
+        let candidate = compress_with_params(data, quality, lgwin)?;
         if best
             .as_ref()
             .is_none_or(|best: &CompressionResult| candidate.data.len() < best.data.len())
@@ -40,7 +76,10 @@ pub fn compress_best(data: &[u8]) -> Result<CompressionResult> {
     best.ok_or_else(|| Error::Message("failed to produce Brotli output".to_string()))
 }
 
-fn compress_with_params(data: &[u8], quality: u32, lgwin: u32) -> Result<CompressionResult> {
+/// Compresses `data` with one Brotli `quality`, from 0 to 11, and window
+/// size `lgwin`.
+#[cfg(feature = "brotli")]
+pub fn compress_with_params(data: &[u8], quality: u32, lgwin: u32) -> Result<CompressionResult> {
     let mut output = Vec::new();
     {
         let buffer_size = data.len().max(4096);
@@ -55,7 +94,374 @@ fn compress_with_params(data: &[u8], quality: u32, lgwin: u32) -> Result<Compres
     })
 }
 
-#[cfg(test)]
+/// Decodes and encodes asset data stored in one encoding.
+pub trait AssetCodec: Send + Sync {
+    /// The encoding reported for assets decoded by this codec.
+    fn encoding(&self) -> AssetEncoding;
+
+    /// Checks that `data` is in this encoding and returns its decoded size.
+    ///
+    /// Scanners use this to reject candidate headers, so it should fail fast
+    /// on data in other encodings.
+    fn verify(&self, data: &[u8]) -> Result<usize> {
+        Ok(self.decompress(data)?.len())
+    }
+
+    /// Checks only the start of `data`, decoding at most
+    /// [`QUICK_VERIFY_LIMIT`] bytes. Returns the decoded size if the whole
+    /// stream fit in that, and `None` if it was cut short.
+    ///
+    /// Corrupt data past the checked prefix goes unnoticed until the asset
+    /// is decompressed.
+    fn verify_quick(&self, data: &[u8]) -> Result<Option<usize>> {
+        self.verify(data).map(Some)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decodes `data` into `output`, which must be exactly its decoded size,
+    /// such as a memory-mapped output file.
+    ///
+    /// The default decodes into a buffer and copies it; streaming codecs
+    /// write into `output` directly.
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        let decoded = self.decompress(data)?;
+        if decoded.len() != output.len() {
+            return Err(size_mismatch(output.len(), decoded.len()));
+        }
+        output.copy_from_slice(&decoded);
+        Ok(())
+    }
+
+    /// Encodes replacement content, as small as the codec can make it.
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+impl fmt::Debug for dyn AssetCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.encoding())
+    }
+}
+
+/// Brotli, as emitted by Tauri's codegen.
+#[cfg(feature = "brotli")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BrotliCodec;
+
+#[cfg(feature = "brotli")]
+impl AssetCodec for BrotliCodec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Brotli
+    }
+
+    fn verify_quick(&self, data: &[u8]) -> Result<Option<usize>> {
+        verify_prefix(brotli::Decompressor::new(data, 4096)).ok_or(Error::InvalidBrotli)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        decompress(data)
+    }
+
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        read_into(brotli::Decompressor::new(data, 4096), output)
+            .map_err(|error| error.unwrap_or(Error::InvalidBrotli))
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        compress(data)
+    }
+}
+
+/// A single gzip member.
+#[cfg(feature = "gzip")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GzipCodec;
+
+#[cfg(feature = "gzip")]
+impl AssetCodec for GzipCodec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Gzip
+    }
+
+    fn verify_quick(&self, data: &[u8]) -> Result<Option<usize>> {
+        verify_prefix(flate2::read::GzDecoder::new(data))
+            .ok_or_else(|| Error::InvalidEncoding("gzip".to_string()))
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        flate2::read::GzDecoder::new(data)
+            .read_to_end(&mut output)
+            .map_err(|_| Error::InvalidEncoding("gzip".to_string()))?;
+        Ok(output)
+    }
+
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        read_into(flate2::read::GzDecoder::new(data), output)
+            .map_err(|error| error.unwrap_or_else(|| Error::InvalidEncoding("gzip".to_string())))
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(data)?;
+        Ok(encoder.finish()?)
+    }
+}
+
+/// A single zstd frame.
+#[cfg(feature = "zstd")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZstdCodec;
+
+#[cfg(feature = "zstd")]
+impl AssetCodec for ZstdCodec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Zstd
+    }
+
+    fn verify_quick(&self, data: &[u8]) -> Result<Option<usize>> {
+        ruzstd::decoding::StreamingDecoder::new(data)
+            .ok()
+            .and_then(verify_prefix)
+            .ok_or_else(|| Error::InvalidEncoding("zstd".to_string()))
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        ruzstd::decoding::StreamingDecoder::new(data)
+            .map_err(|_| Error::InvalidEncoding("zstd".to_string()))?
+            .read_to_end(&mut output)
+            .map_err(|_| Error::InvalidEncoding("zstd".to_string()))?;
+        Ok(output)
+    }
+
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        let decoder = ruzstd::decoding::StreamingDecoder::new(data)
+            .map_err(|_| Error::InvalidEncoding("zstd".to_string()))?;
+        read_into(decoder, output)
+            .map_err(|error| error.unwrap_or_else(|| Error::InvalidEncoding("zstd".to_string())))
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Vec::new();
+        ruzstd::encoding::compress(
+            data,
+            &mut output,
+            ruzstd::encoding::CompressionLevel::Fastest,
+        );
+        output.flush()?;
+        Ok(output)
+    }
+}
+
+/// A single LZ4 frame.
+#[cfg(feature = "lz4")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lz4Codec;
+
+#[cfg(feature = "lz4")]
+const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+
+#[cfg(feature = "lz4")]
+impl AssetCodec for Lz4Codec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Lz4
+    }
+
+    fn verify_quick(&self, data: &[u8]) -> Result<Option<usize>> {
+        if !data.starts_with(&LZ4_FRAME_MAGIC) {
+            return Err(Error::InvalidEncoding("lz4".to_string()));
+        }
+        verify_prefix(lz4_flex::frame::FrameDecoder::new(data))
+            .ok_or_else(|| Error::InvalidEncoding("lz4".to_string()))
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !data.starts_with(&LZ4_FRAME_MAGIC) {
+            return Err(Error::InvalidEncoding("lz4".to_string()));
+        }
+        let mut output = Vec::new();
+        lz4_flex::frame::FrameDecoder::new(data)
+            .read_to_end(&mut output)
+            .map_err(|_| Error::InvalidEncoding("lz4".to_string()))?;
+        Ok(output)
+    }
+
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        if !data.starts_with(&LZ4_FRAME_MAGIC) {
+            return Err(Error::InvalidEncoding("lz4".to_string()));
+        }
+        read_into(lz4_flex::frame::FrameDecoder::new(data), output)
+            .map_err(|error| error.unwrap_or_else(|| Error::InvalidEncoding("lz4".to_string())))
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(data)?;
+        encoder
+            .finish()
+            .map_err(|err| Error::Message(format!("failed to encode LZ4: {err}")))
+    }
+}
+
+/// Decodes up to [`QUICK_VERIFY_LIMIT`] bytes from `reader`. Returns `None`
+/// if decoding fails, and the decoded size if the stream ended first.
+#[cfg(any(
+    feature = "brotli",
+    feature = "gzip",
+    feature = "zstd",
+    feature = "lz4"
+))]
+fn verify_prefix(mut reader: impl Read) -> Option<Option<usize>> {
+    let mut buffer = vec![0; QUICK_VERIFY_LIMIT];
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]).ok()? {
+            0 => return Some(Some(filled)),
+            read => filled += read,
+        }
+    }
+    Some(None)
+}
+
+/// Fills `output` from `reader` and checks that the stream ends there.
+/// Fails with `None` if decoding fails and with the size mismatch if the
+/// stream is shorter or longer.
+#[cfg(any(
+    feature = "brotli",
+    feature = "gzip",
+    feature = "zstd",
+    feature = "lz4"
+))]
+fn read_into(mut reader: impl Read, output: &mut [u8]) -> std::result::Result<(), Option<Error>> {
+    let mut filled = 0;
+    while filled < output.len() {
+        match reader.read(&mut output[filled..]).map_err(|_| None)? {
+            0 => return Err(Some(size_mismatch(output.len(), filled))),
+            read => filled += read,
+        }
+    }
+    match reader.read(&mut [0; 1]).map_err(|_| None)? {
+        0 => Ok(()),
+        _ => Err(Some(Error::Message(format!(
+            "asset decodes to more than the expected {} bytes",
+            output.len()
+        )))),
+    }
+}
+
+fn size_mismatch(expected: usize, actual: usize) -> Error {
+    Error::Message(format!(
+        "asset decodes to {actual} bytes, expected {expected}"
+    ))
+}
+
+/// Data stored without encoding.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RawCodec;
+
+impl AssetCodec for RawCodec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Raw
+    }
+
+    fn verify(&self, data: &[u8]) -> Result<usize> {
+        Ok(data.len())
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+
+    fn decompress_into(&self, data: &[u8], output: &mut [u8]) -> Result<()> {
+        if data.len() != output.len() {
+            return Err(size_mismatch(output.len(), data.len()));
+        }
+        output.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_vec())
+    }
+}
+
+/// Codecs tried, in order, when validating Tauri asset data.
+///
+/// Brotli is tried first when the `brotli` feature is enabled; registered
+/// codecs follow in registration order, so a permissive codec such as
+/// [`RawCodec`] acts as a fallback.
+#[derive(Clone)]
+pub struct CodecRegistry {
+    codecs: Vec<Arc<dyn AssetCodec>>,
+}
+
+#[cfg_attr(not(feature = "brotli"), allow(clippy::derivable_impls))]
+impl Default for CodecRegistry {
+    fn default() -> Self {
+        Self {
+            #[cfg(feature = "brotli")]
+            codecs: vec![Arc::new(BrotliCodec)],
+            #[cfg(not(feature = "brotli"))]
+            codecs: Vec::new(),
+        }
+    }
+}
+
+impl CodecRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, codec: Box<dyn AssetCodec>) -> &mut Self {
+        self.codecs.push(Arc::from(codec));
+        self
+    }
+
+    /// The encodings of the registered codecs, in the order they are tried.
+    pub fn encodings(&self) -> Vec<AssetEncoding> {
+        self.codecs.iter().map(|codec| codec.encoding()).collect()
+    }
+
+    /// Returns the first codec that accepts `data`, with its decoded size.
+    pub fn verify(&self, data: &[u8]) -> Result<(Arc<dyn AssetCodec>, usize)> {
+        let mut error = None;
+        for codec in &self.codecs {
+            match codec.verify(data) {
+                Ok(size) => return Ok((Arc::clone(codec), size)),
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        Err(error.unwrap_or_else(|| Error::Message("no asset codecs are registered".to_string())))
+    }
+
+    /// Like [`verify`](Self::verify), but checks only the start of `data`
+    /// with [`AssetCodec::verify_quick`].
+    pub fn verify_quick(&self, data: &[u8]) -> Result<(Arc<dyn AssetCodec>, Option<usize>)> {
+        let mut error = None;
+        for codec in &self.codecs {
+            match codec.verify_quick(data) {
+                Ok(size) => return Ok((Arc::clone(codec), size)),
+                Err(err) => {
+                    error.get_or_insert(err);
+                }
+            }
+        }
+        Err(error.unwrap_or_else(|| Error::Message("no asset codecs are registered".to_string())))
+    }
+}
+
+/// The codec an asset is assumed to be stored with until a scanner sets it.
+pub(crate) fn default_codec() -> Arc<dyn AssetCodec> {
+    #[cfg(feature = "brotli")]
+    return Arc::new(BrotliCodec);
+    #[cfg(not(feature = "brotli"))]
+    Arc::new(RawCodec)
+}
+
+#[cfg(all(test, feature = "brotli"))]
 mod tests {
     use super::*;
 
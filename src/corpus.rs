@@ -0,0 +1,427 @@
+//! Asset history across many releases of one application.
+//!
+//! A corpus directory holds a content-addressed [`STORE_DIR`] shared by every
+//! release, an index of the releases added so far and the timeline derived
+//! from it. Decoded content is keyed by the SHA-256 of the compressed blob, so
+//! assets that did not change between releases are neither decompressed nor
+//! written again, and re-running over a grown release directory only
+//! processes the new releases.
+//!
+//! [`Corpus::add_batch`] adds many releases at once, parsing, scanning,
+//! decompressing and writing them as a pipeline on one pool of threads.
+
+use crate::asset::{sha256_hex, AssetTable};
+use crate::batch::{self, Spawner};
+use crate::error::Result;
+use crate::export::STORE_DIR;
+use crate::extract::{decompress_asset, AssetScanner, ScanOptions};
+use crate::image::BinaryImage;
+use crate::input::{self, ResolveOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Index of the releases in a corpus, written to the corpus directory.
+pub const INDEX_FILE_NAME: &str = "corpus.json";
+/// The timeline of a corpus, written to the corpus directory.
+pub const TIMELINE_FILE_NAME: &str = "timeline.json";
+
+/// One release added to a corpus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    /// The name the release was added under, usually its file name.
+    pub name: String,
+    /// SHA-256 of the scanned binary.
+    pub binary_sha256: String,
+    /// Maps asset names to the SHA-256 of their decoded content, which is
+    /// the blob's name in [`STORE_DIR`].
+    pub assets: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Index {
+    releases: Vec<Release>,
+    /// Maps the SHA-256 of compressed blobs to that of their content.
+    content_hashes: HashMap<String, String>,
+}
+
+/// What [`Corpus::add`] did for one release.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddSummary {
+    /// Assets decompressed and stored for the first time.
+    pub stored: usize,
+    /// Assets whose content was already in the store.
+    pub cached: usize,
+}
+
+/// Changes between a release and the one before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub release: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Settings for [`Corpus::add_batch`].
+#[derive(Clone)]
+pub struct BatchOptions {
+    /// Threads shared by every release, defaulting to one per core.
+    pub jobs: usize,
+    pub resolve: ResolveOptions,
+    pub scan: ScanOptions,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            jobs: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            resolve: ResolveOptions::default(),
+            scan: ScanOptions::default(),
+        }
+    }
+}
+
+pub struct Corpus {
+    dir: PathBuf,
+    index: Index,
+}
+
+/// A stage of a release in [`Corpus::add_batch`], identified by the index
+/// of the release.
+enum Stage {
+    Open(usize),
+    Scan(usize, Box<BinaryImage>),
+    Decompress(usize, Arc<AssetTable>, usize),
+    Write(usize, String, Vec<u8>),
+}
+
+/// How far a release of a batch got.
+#[derive(Default)]
+struct Progress {
+    binary_sha256: String,
+    /// `None` until the release was scanned, and for known releases.
+    table: Option<Arc<AssetTable>>,
+    /// The content hash of each asset of `table`, once known.
+    hashes: Vec<Option<String>>,
+    error: Option<crate::Error>,
+}
+
+/// State shared by the tasks of a batch.
+struct Batch<'a> {
+    corpus: &'a Corpus,
+    releases: &'a [(String, PathBuf)],
+    options: &'a BatchOptions,
+    progress: Vec<Mutex<Progress>>,
+    /// Content hashes of the compressed blobs decoded in this batch.
+    content_hashes: Mutex<HashMap<String, String>>,
+    /// Blobs this batch wrote or is writing.
+    written: Mutex<HashSet<String>>,
+}
+
+impl Batch<'_> {
+    fn run(&self, stage: Stage, spawner: &Spawner<'_, Stage>) {
+        let release = match &stage {
+            Stage::Open(release)
+            | Stage::Scan(release, _)
+            | Stage::Decompress(release, ..)
+            | Stage::Write(release, ..) => *release,
+        };
+        if self.progress[release].lock().unwrap().error.is_some() {
+            return;
+        }
+        if let Err(error) = self.step(stage, spawner) {
+            let mut progress = self.progress[release].lock().unwrap();
+            progress.error.get_or_insert(error);
+        }
+    }
+
+    fn step(&self, stage: Stage, spawner: &Spawner<'_, Stage>) -> Result<()> {
+        match stage {
+            Stage::Open(release) => {
+                let (name, path) = &self.releases[release];
+                let image =
+                    input::resolve_with_options(path, &self.options.resolve)?.into_image()?;
+                let sha256 = image.metadata().sha256.clone();
+                let known = self
+                    .corpus
+                    .release(name)
+                    .is_some_and(|known| known.binary_sha256 == sha256);
+                self.progress[release].lock().unwrap().binary_sha256 = sha256;
+                if !known {
+                    spawner.spawn(Stage::Scan(release, Box::new(image)));
+                }
+            }
+            Stage::Scan(release, image) => {
+                let table = Arc::new(AssetScanner::scan_with_options(&image, &self.options.scan)?);
+                drop(image);
+                {
+                    let mut progress = self.progress[release].lock().unwrap();
+                    progress.hashes = vec![None; table.assets().len()];
+                    progress.table = Some(Arc::clone(&table));
+                }
+                for asset in 0..table.assets().len() {
+                    spawner.spawn(Stage::Decompress(release, Arc::clone(&table), asset));
+                }
+            }
+            Stage::Decompress(release, table, index) => {
+                let asset = &table.assets()[index];
+                let compressed = asset.compressed_sha256();
+                let cached = self
+                    .corpus
+                    .index
+                    .content_hashes
+                    .get(compressed)
+                    .filter(|hash| self.corpus.blob_path(hash).is_file())
+                    .cloned()
+                    .or_else(|| {
+                        let decoded = self.content_hashes.lock().unwrap();
+                        decoded.get(compressed).cloned()
+                    });
+                let hash = match cached {
+                    Some(hash) => hash,
+                    None => {
+                        let data = decompress_asset(asset)?;
+                        let hash = sha256_hex(&data);
+                        self.content_hashes
+                            .lock()
+                            .unwrap()
+                            .insert(compressed.to_string(), hash.clone());
+                        spawner.spawn(Stage::Write(release, hash.clone(), data));
+                        hash
+                    }
+                };
+                self.progress[release].lock().unwrap().hashes[index] = Some(hash);
+            }
+            Stage::Write(_, hash, data) => {
+                let path = self.corpus.blob_path(&hash);
+                if !path.is_file() && self.written.lock().unwrap().insert(hash.clone()) {
+                    if let Err(error) = fs::write(&path, &data) {
+                        self.written.lock().unwrap().remove(&hash);
+                        return Err(error.into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Corpus {
+    /// Opens the corpus in `dir`, creating it if needed.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join(STORE_DIR))?;
+        let index_path = dir.join(INDEX_FILE_NAME);
+        let index = if index_path.is_file() {
+            serde_json::from_slice(&fs::read(&index_path)?)?
+        } else {
+            Index::default()
+        };
+        Ok(Self { dir, index })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Releases in timeline order: the order they were added in, unless
+    /// reordered with [`Corpus::sort_releases_by`].
+    pub fn releases(&self) -> &[Release] {
+        &self.index.releases
+    }
+
+    /// Returns the release named `name`, if it was added.
+    pub fn release(&self, name: &str) -> Option<&Release> {
+        self.index
+            .releases
+            .iter()
+            .find(|release| release.name == name)
+    }
+
+    /// Returns the path of the blob holding the content with `sha256`.
+    pub fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.dir.join(STORE_DIR).join(sha256)
+    }
+
+    /// Adds the assets of `table` as release `name`, replacing a release of
+    /// the same name, and saves the index.
+    pub fn add(&mut self, name: &str, table: &AssetTable) -> Result<AddSummary> {
+        let mut summary = AddSummary::default();
+        let mut assets = BTreeMap::new();
+        for asset in table.assets() {
+            let cached = self
+                .index
+                .content_hashes
+                .get(asset.compressed_sha256())
+                .filter(|hash| self.blob_path(hash).is_file())
+                .cloned();
+            let hash = match cached {
+                Some(hash) => {
+                    summary.cached += 1;
+                    hash
+                }
+                None => {
+                    let data = decompress_asset(asset)?;
+                    let hash = sha256_hex(&data);
+                    let path = self.blob_path(&hash);
+                    if path.is_file() {
+                        summary.cached += 1;
+                    } else {
+                        fs::write(&path, &data)?;
+                        summary.stored += 1;
+                    }
+                    self.index
+                        .content_hashes
+                        .insert(asset.compressed_sha256().to_string(), hash.clone());
+                    hash
+                }
+            };
+            assets.insert(asset.name().to_string(), hash);
+        }
+
+        let release = Release {
+            name: name.to_string(),
+            binary_sha256: table.metadata().sha256.clone(),
+            assets,
+        };
+        match self.index.releases.iter_mut().find(|old| old.name == name) {
+            Some(old) => *old = release,
+            None => self.index.releases.push(release),
+        }
+        self.save()?;
+        Ok(summary)
+    }
+
+    /// Adds `releases`, pairs of a name and the path of the binary or
+    /// package to read it from, running their stages on a pool of
+    /// `options.jobs` threads. Releases already added from the same binary
+    /// are not scanned again and yield `None`. Releases are added, and
+    /// their assets counted as stored or cached, in the order given, as
+    /// [`Corpus::add`] would; one that fails is reported and left out.
+    pub fn add_batch(
+        &mut self,
+        releases: &[(String, PathBuf)],
+        options: &BatchOptions,
+    ) -> Result<Vec<Result<Option<AddSummary>>>> {
+        let batch = Batch {
+            corpus: self,
+            releases,
+            options,
+            progress: releases.iter().map(|_| Mutex::default()).collect(),
+            content_hashes: Mutex::default(),
+            written: Mutex::default(),
+        };
+        batch::run(
+            options.jobs,
+            (0..releases.len()).map(Stage::Open),
+            |stage, spawner| batch.run(stage, spawner),
+        );
+        let Batch {
+            progress,
+            content_hashes,
+            written,
+            ..
+        } = batch;
+        let mut written = written.into_inner().unwrap();
+        self.index
+            .content_hashes
+            .extend(content_hashes.into_inner().unwrap());
+
+        let mut outcomes = Vec::with_capacity(releases.len());
+        for ((name, _), progress) in releases.iter().zip(progress) {
+            let progress = progress.into_inner().unwrap();
+            if let Some(error) = progress.error {
+                outcomes.push(Err(error));
+                continue;
+            }
+            let Some(table) = progress.table else {
+                outcomes.push(Ok(None));
+                continue;
+            };
+            let mut summary = AddSummary::default();
+            let mut assets = BTreeMap::new();
+            for (asset, hash) in table.assets().iter().zip(progress.hashes) {
+                let hash = hash.unwrap_or_default();
+                // Blobs count as stored for the first release that has them.
+                if written.remove(&hash) {
+                    summary.stored += 1;
+                } else {
+                    summary.cached += 1;
+                }
+                assets.insert(asset.name().to_string(), hash);
+            }
+            let release = Release {
+                name: name.clone(),
+                binary_sha256: progress.binary_sha256,
+                assets,
+            };
+            match self.index.releases.iter_mut().find(|old| old.name == *name) {
+                Some(old) => *old = release,
+                None => self.index.releases.push(release),
+            }
+            outcomes.push(Ok(Some(summary)));
+        }
+        self.save()?;
+        Ok(outcomes)
+    }
+
+    /// Orders the releases with `compare`, for timelines that should not
+    /// follow the order releases were added in.
+    pub fn sort_releases_by(
+        &mut self,
+        compare: impl FnMut(&Release, &Release) -> std::cmp::Ordering,
+    ) {
+        self.index.releases.sort_by(compare);
+    }
+
+    /// Compares every release with the one before it. The first release
+    /// lists all of its assets as added.
+    pub fn timeline(&self) -> Vec<TimelineEntry> {
+        let empty = BTreeMap::new();
+        let mut previous = &empty;
+        let mut timeline = Vec::new();
+        for release in &self.index.releases {
+            let mut entry = TimelineEntry {
+                release: release.name.clone(),
+                added: Vec::new(),
+                removed: Vec::new(),
+                changed: Vec::new(),
+                unchanged: 0,
+            };
+            for (name, hash) in &release.assets {
+                match previous.get(name) {
+                    None => entry.added.push(name.clone()),
+                    Some(old) if old != hash => entry.changed.push(name.clone()),
+                    Some(_) => entry.unchanged += 1,
+                }
+            }
+            entry.removed = previous
+                .keys()
+                .filter(|name| !release.assets.contains_key(*name))
+                .cloned()
+                .collect();
+            timeline.push(entry);
+            previous = &release.assets;
+        }
+        timeline
+    }
+
+    /// Saves the index and writes [`TIMELINE_FILE_NAME`].
+    pub fn save(&self) -> Result<()> {
+        fs::write(
+            self.dir.join(INDEX_FILE_NAME),
+            serde_json::to_vec_pretty(&self.index)?,
+        )?;
+        fs::write(
+            self.dir.join(TIMELINE_FILE_NAME),
+            serde_json::to_vec_pretty(&self.timeline())?,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+//! Deep-link configuration compiled into the binary.
+//!
+//! Tauri's deep-link plugin reads `plugins.deep-link` from the app config,
+//! which is embedded in the binary as JSON. Desktop builds register the
+//! custom schemes listed under `desktop.schemes`; mobile builds claim the
+//! hosts listed under `mobile`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const DEEP_LINK_KEY: &[u8] = b"\"deep-link\"";
+/// Config objects larger than this are not the plugin's config.
+const MAX_CONFIG_LEN: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeepLinks {
+    /// Custom URL schemes, such as `myapp` for `myapp://` links.
+    pub schemes: Vec<String>,
+    /// Hosts of the `https` links the app claims on mobile.
+    pub hosts: Vec<String>,
+}
+
+impl DeepLinks {
+    pub fn is_empty(&self) -> bool {
+        self.schemes.is_empty() && self.hosts.is_empty()
+    }
+}
+
+/// Finds the deep-link plugin config in `data` and returns the schemes and
+/// hosts it registers, if any.
+pub fn find_deep_links(data: &[u8]) -> Option<DeepLinks> {
+    let mut links = DeepLinks::default();
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], DEEP_LINK_KEY) {
+        let key_end = search + found + DEEP_LINK_KEY.len();
+        search = key_end;
+        let Some(object) = object_after_key(&data[key_end..]) else {
+            continue;
+        };
+        match serde_json::from_slice::<Value>(object) {
+            Ok(config) => read_config(&config, &mut links),
+            Err(error) => log::debug!("skipping deep-link config at {key_end:#x}: {error}"),
+        }
+    }
+    links.schemes.sort();
+    links.schemes.dedup();
+    links.hosts.sort();
+    links.hosts.dedup();
+    (!links.is_empty()).then_some(links)
+}
+
+fn read_config(config: &Value, links: &mut DeepLinks) {
+    // `desktop` is an object, or a list of them for several handlers.
+    let desktop = match config.get("desktop") {
+        Some(Value::Array(entries)) => entries.iter().collect(),
+        Some(entry) => vec![entry],
+        None => Vec::new(),
+    };
+    for entry in desktop {
+        links.schemes.extend(strings(entry.get("schemes")));
+    }
+    for entry in config
+        .get("mobile")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let schemes = strings(entry.get("scheme"));
+        let custom = schemes
+            .iter()
+            .filter(|scheme| !matches!(scheme.as_str(), "http" | "https"))
+            .cloned()
+            .collect::<Vec<_>>();
+        links.schemes.extend(custom);
+        if let Some(host) = entry.get("host").and_then(Value::as_str) {
+            links.hosts.push(host.to_string());
+        }
+    }
+}
+
+/// Reads a string or a list of strings.
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(value)) => vec![value.clone()],
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the JSON object following a key's `:` in `rest`.
+fn object_after_key(rest: &[u8]) -> Option<&[u8]> {
+    let rest = trim_start(rest).strip_prefix(b":")?;
+    let rest = trim_start(rest);
+    if rest.first() != Some(&b'{') {
+        return None;
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, &byte) in rest.iter().enumerate().take(MAX_CONFIG_LEN) {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&rest[..=index]);
+                }
+            }
+            0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn trim_start(data: &[u8]) -> &[u8] {
+    let start = data
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    &data[start..]
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
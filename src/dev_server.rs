@@ -0,0 +1,55 @@
+//! The dev server a binary loads its frontend from.
+//!
+//! `tauri dev` builds the app against `build.devUrl` (`devPath` in Tauri 1)
+//! and embeds no assets: the webview loads the frontend from the dev
+//! server, usually a Vite or webpack server on a loopback port. The config
+//! is compiled into the binary, so the URL survives as a string literal such
+//! as `http://localhost:1420/`. Release builds keep it too, so it only tells
+//! a dev build apart when no assets are found.
+
+/// Hosts a dev server listens on.
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "0.0.0.0", "[::1]"];
+
+/// Finds the dev server URL in the binary in `data`, as
+/// `scheme://host:port`.
+pub fn find_dev_url(data: &[u8]) -> Option<String> {
+    find_all(data, b"://").find_map(|offset| dev_url_at(data, offset))
+}
+
+fn dev_url_at(data: &[u8], separator: usize) -> Option<String> {
+    let scheme = if data[..separator].ends_with(b"https") {
+        "https"
+    } else if data[..separator].ends_with(b"http") {
+        "http"
+    } else {
+        return None;
+    };
+    let rest = &data[separator + 3..];
+    let authority = rest
+        .iter()
+        .take_while(|&&byte| byte.is_ascii_alphanumeric() || b".-:[]".contains(&byte))
+        .count();
+    let authority = std::str::from_utf8(&rest[..authority]).ok()?;
+    let (host, port) = authority.rsplit_once(':')?;
+    let port = port
+        .split(|byte: char| !byte.is_ascii_digit())
+        .next()
+        .filter(|port| !port.is_empty())?;
+    let loopback = LOOPBACK_HOSTS.contains(&host) || host.starts_with("127.");
+    loopback.then(|| format!("{scheme}://{host}:{port}"))
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
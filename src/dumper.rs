@@ -1,47 +1,281 @@
-use crate::asset::{Asset, AssetTable};
+use crate::asset::{Asset, AssetTable, HeaderLayout};
+use crate::binary::{ParsedBinary, ParserRegistry, ScanRange, SectionInfo};
+use crate::codec::CodecRegistry;
 use crate::error::Result;
-use crate::extract::{decompress_asset, AssetScanner};
+use crate::extract::{
+    decompress_asset, AssetScanner, HeaderCandidate, ScanOptions, ScanProfile, Strictness,
+};
 use crate::image::BinaryImage;
+use crate::progress::Progress;
+#[cfg(feature = "fs")]
+use crate::roundtrip::{self, VerifyReport};
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs::File;
+#[cfg(feature = "fs")]
 use std::io::Read;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 pub struct Dumper {
     image: BinaryImage,
+    options: ScanOptions,
 }
 
 impl Dumper {
-    pub fn new(mut file: File) -> Result<Self> {
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
-        Self::from_bytes(&data)
+    #[cfg(feature = "fs")]
+    pub fn new(file: File) -> Result<Self> {
+        Self::builder().build(file)
     }
 
+    #[cfg(feature = "fs")]
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
-        Ok(Self {
-            image: BinaryImage::open(path)?,
-        })
+        Self::builder().build_from_path(path)
     }
 
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
-        Ok(Self {
-            image: BinaryImage::from_bytes(data)?,
-        })
+        Self::builder().build_from_bytes(data)
+    }
+
+    /// Parses `data` with the formats in `registry`.
+    pub fn from_bytes_with_registry(data: &[u8], registry: &ParserRegistry) -> Result<Self> {
+        Self::builder().parsers(registry).build_from_bytes(data)
+    }
+
+    /// Uses `parsed` for `data` instead of detecting the format.
+    pub fn with_parser(data: &[u8], parsed: ParsedBinary) -> Self {
+        Self::builder().build_with_parser(data, parsed)
+    }
+
+    pub fn builder<'a>() -> DumperBuilder<'a> {
+        DumperBuilder::default()
+    }
+
+    /// Validates asset data with `codecs` instead of Brotli alone.
+    pub fn with_codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.options.codecs = codecs;
+        self
     }
 
     pub fn scan(&self) -> Result<AssetTable> {
-        AssetScanner::scan(&self.image)
+        AssetScanner::scan_with_options(&self.image, &self.options)
     }
 
     pub fn scan_assets(&self) -> Result<Vec<Asset>> {
         Ok(self.scan()?.assets().to_vec())
     }
 
+    /// Scans the binary for the assets whose name starts with `prefix`.
+    pub fn assets_with_prefix(&self, prefix: &str) -> Result<Vec<Asset>> {
+        Ok(self.scan()?.with_prefix(prefix).cloned().collect())
+    }
+
+    /// Scans the binary for the asset Tauri serves for a request to `path`.
+    /// See [`AssetTable::resolve`].
+    pub fn resolve_asset(&self, path: &str) -> Result<Option<Asset>> {
+        Ok(self.scan()?.resolve(path).cloned())
+    }
+
+    /// Finds one asset by its embedded path, stopping at its header rather
+    /// than scanning and validating the whole table. See
+    /// [`AssetScanner::find_asset`].
+    pub fn get_asset(&self, name: &str) -> Result<Option<Asset>> {
+        AssetScanner::find_asset(&self.image, &self.options, name)
+    }
+
+    /// Reports every candidate asset header to `visitor`, accepted or not.
+    /// See [`AssetScanner::scan_with_visitor`].
+    pub fn scan_with_visitor<F>(&self, visitor: F) -> Result<()>
+    where
+        F: FnMut(&HeaderCandidate, Option<&Asset>),
+    {
+        AssetScanner::scan_with_visitor(&self.image, &self.options, visitor)
+    }
+
     pub fn decompress_asset(&self, asset: &Asset) -> Result<Vec<u8>> {
         decompress_asset(asset)
     }
 
+    /// Scans the binary and decompresses every asset in memory, by name.
+    /// Nothing is read from or written to the filesystem.
+    pub fn extract_all(&self) -> Result<HashMap<String, Vec<u8>>> {
+        self.extract_iter()?.collect()
+    }
+
+    /// Scans the binary and returns the assets with their decompressed
+    /// data, decoding each one only when the iterator reaches it, so a
+    /// single asset is held in memory at a time.
+    pub fn extract_iter(&self) -> Result<impl Iterator<Item = Result<(String, Vec<u8>)>>> {
+        let table = self.scan()?;
+        Ok(table.assets().to_vec().into_iter().map(|asset| {
+            let data = decompress_asset(&asset)?;
+            Ok((asset.name().to_string(), data))
+        }))
+    }
+
+    /// Compares the embedded assets with the frontend build in `dist`, file
+    /// by file. See [`VerifyReport::is_ok`].
+    #[cfg(feature = "fs")]
+    pub fn verify_roundtrip(&self, dist: &Path) -> Result<VerifyReport> {
+        roundtrip::verify(&self.scan()?, dist)
+    }
+
+    /// Converts virtual address `va` to a file offset. See
+    /// [`BinaryImage::resolve_va`].
+    pub fn resolve_va(&self, va: u64) -> Result<u64> {
+        self.image.resolve_va(va)
+    }
+
+    /// Converts a pointer as stored in the binary to a file offset. See
+    /// [`BinaryImage::resolve_raw_pointer`].
+    pub fn resolve_raw_pointer(&self, raw_ptr: u64) -> Result<u64> {
+        self.image.resolve_raw_pointer(raw_ptr)
+    }
+
+    /// The file-backed sections of the binary. See
+    /// [`BinaryImage::sections`].
+    pub fn sections(&self) -> &[SectionInfo] {
+        self.image.sections()
+    }
+
+    /// The file ranges the scan searches for asset headers: the sections
+    /// named in the options, or else the ones the parser picks.
+    pub fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
+        AssetScanner::scan_ranges(&self.image, &self.options)
+    }
+
     pub fn image(&self) -> &BinaryImage {
         &self.image
     }
+
+    pub fn options(&self) -> &ScanOptions {
+        &self.options
+    }
+}
+
+/// Configures a [`Dumper`]. Unset options keep the defaults of
+/// [`ScanOptions`].
+#[derive(Default)]
+pub struct DumperBuilder<'a> {
+    options: ScanOptions,
+    parsers: Option<&'a ParserRegistry>,
+}
+
+impl<'a> DumperBuilder<'a> {
+    pub fn profile(mut self, profile: ScanProfile) -> Self {
+        self.options.profile = profile;
+        self
+    }
+
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.options.strictness = strictness;
+        self
+    }
+
+    /// Validates asset data with `codecs` instead of Brotli alone.
+    pub fn codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.options.codecs = codecs;
+        self
+    }
+
+    pub fn scan_step(mut self, scan_step: usize) -> Self {
+        self.options.scan_step = scan_step;
+        self
+    }
+
+    /// Scans only the named sections, such as `.rdata` or
+    /// `__DATA_CONST,__const`.
+    pub fn sections<S: Into<String>>(mut self, sections: impl IntoIterator<Item = S>) -> Self {
+        self.options.sections = sections.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Validates asset data by decoding only its start while scanning. See
+    /// [`ScanOptions::quick_verify`].
+    pub fn quick_verify(mut self, quick_verify: bool) -> Self {
+        self.options.quick_verify = quick_verify;
+        self
+    }
+
+    /// Tries `layouts`, in order, instead of the profile's header layouts.
+    pub fn header_layouts(mut self, layouts: impl IntoIterator<Item = HeaderLayout>) -> Self {
+        self.options.header_layouts = layouts.into_iter().collect();
+        self
+    }
+
+    /// Drops assets whose confidence is below `min`, from 0 to 100.
+    pub fn min_confidence(mut self, min: u8) -> Self {
+        self.options.min_confidence = min;
+        self
+    }
+
+    /// Stops the scan once `max` assets are found. See
+    /// [`ScanOptions::max_assets`].
+    pub fn max_assets(mut self, max: usize) -> Self {
+        self.options.max_assets = Some(max);
+        self
+    }
+
+    /// Stops the scan once every one of `paths` is found. See
+    /// [`ScanOptions::stop_after_paths`].
+    pub fn stop_after_paths<S: Into<String>>(mut self, paths: impl IntoIterator<Item = S>) -> Self {
+        self.options.stop_after_paths = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reports the progress of the scan and each asset found to
+    /// `progress`. See [`ScanOptions::progress`].
+    pub fn progress(mut self, progress: Progress) -> Self {
+        self.options.progress = Some(progress);
+        self
+    }
+
+    /// Replaces all scan options at once.
+    pub fn options(mut self, options: ScanOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Detects the binary format with the factories in `registry` before
+    /// the built-in formats.
+    pub fn parsers(mut self, registry: &'a ParserRegistry) -> Self {
+        self.parsers = Some(registry);
+        self
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn build(self, mut file: File) -> Result<Dumper> {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        self.build_from_bytes(&data)
+    }
+
+    #[cfg(feature = "fs")]
+    pub fn build_from_path(self, path: impl AsRef<Path>) -> Result<Dumper> {
+        let image = match self.parsers {
+            Some(registry) => BinaryImage::open_with_registry(path, registry)?,
+            None => BinaryImage::open(path)?,
+        };
+        Ok(self.finish(image))
+    }
+
+    pub fn build_from_bytes(self, data: &[u8]) -> Result<Dumper> {
+        let image = match self.parsers {
+            Some(registry) => BinaryImage::from_bytes_with_registry(data, registry)?,
+            None => BinaryImage::from_bytes(data)?,
+        };
+        Ok(self.finish(image))
+    }
+
+    /// Uses `parsed` for `data` instead of detecting the format.
+    pub fn build_with_parser(self, data: &[u8], parsed: ParsedBinary) -> Dumper {
+        self.finish(BinaryImage::with_parser(data.to_vec(), parsed))
+    }
+
+    fn finish(self, image: BinaryImage) -> Dumper {
+        Dumper {
+            image,
+            options: self.options,
+        }
+    }
 }
@@ -0,0 +1,96 @@
+//! Entropy of decoded assets.
+//!
+//! Web assets are text or media in well-known formats. A script, a
+//! stylesheet or a file of unknown type that is as random as compressed
+//! data is more likely an encrypted payload, such as the second stage of
+//! malware shipped as a Tauri app, than part of the frontend. Images, fonts
+//! and other formats that compress their content are random by design and
+//! never flagged.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::mime::mime_type;
+use crate::packer::entropy;
+use serde::{Deserialize, Serialize};
+
+/// Entropy, in bits per byte, from which an asset counts as an outlier.
+/// Minified code stays below 6.
+pub const OUTLIER_ENTROPY: f64 = 7.2;
+/// Assets smaller than this are too short for a meaningful entropy.
+pub const MIN_OUTLIER_SIZE: usize = 1024;
+
+/// MIME types and prefixes of formats whose content is compressed, and so
+/// random whatever it holds.
+const COMPRESSED_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "image/avif",
+    "image/x-icon",
+    "font/",
+    "audio/",
+    "video/",
+    "application/pdf",
+    "application/zip",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetEntropy {
+    pub name: String,
+    pub mime: String,
+    /// Size of the decoded asset.
+    pub size: usize,
+    /// Shannon entropy of the decoded asset, from 0 to 8 bits per byte.
+    pub entropy: f64,
+    /// Whether the asset is as random as encrypted data although its type
+    /// should not be. See [`is_outlier`].
+    pub outlier: bool,
+}
+
+impl AssetEntropy {
+    /// Measures the decoded content `data` of the asset `name`.
+    pub fn measure(name: &str, data: &[u8]) -> Self {
+        let mime = mime_type(name);
+        let entropy = entropy(data);
+        Self {
+            name: name.to_string(),
+            mime: mime.to_string(),
+            size: data.len(),
+            entropy,
+            outlier: is_outlier(mime, data.len(), entropy),
+        }
+    }
+
+    /// Whether the asset's type is text, which is never this random.
+    pub fn is_text(&self) -> bool {
+        self.mime.starts_with("text/")
+            || self.mime.starts_with("application/json")
+            || self.mime.starts_with("application/manifest")
+            || self.mime == "image/svg+xml"
+    }
+}
+
+/// Whether `size` bytes of type `mime` with `entropy` bits per byte stand
+/// out: at least [`MIN_OUTLIER_SIZE`] bytes, at least [`OUTLIER_ENTROPY`]
+/// and not of a compressed format.
+pub fn is_outlier(mime: &str, size: usize, entropy: f64) -> bool {
+    size >= MIN_OUTLIER_SIZE
+        && entropy >= OUTLIER_ENTROPY
+        && !COMPRESSED_TYPES.iter().any(|known| mime.starts_with(known))
+}
+
+/// Decodes every asset of `table` and measures its entropy, in table order.
+pub fn analyze(table: &AssetTable) -> Result<Vec<AssetEntropy>> {
+    table
+        .assets()
+        .iter()
+        .map(|asset| {
+            Ok(AssetEntropy::measure(
+                asset.name(),
+                &decompress_asset(asset)?,
+            ))
+        })
+        .collect()
+}
@@ -16,9 +16,26 @@ pub enum Error {
     #[error("unsupported binary format: {0}")]
     UnsupportedFormat(String),
 
+    #[error("invalid input container: {0}")]
+    InvalidContainer(String),
+
+    #[error("process error: {0}")]
+    Process(String),
+
     #[error("no supported Tauri asset section found in {0}")]
     NoAssetSection(String),
 
+    #[error("binary is packed with {0} and its assets cannot be read; {hint}", hint = .0.hint)]
+    Packed(crate::packer::Packer),
+
+    #[error(
+        "binary is encrypted with FairPlay ({} bytes at {:#x}) and its assets cannot be read; \
+         scan a decrypted dump of it, or pass one with --decrypted",
+        .0.size,
+        .0.offset
+    )]
+    Encrypted(crate::binary::EncryptionInfo),
+
     #[error("pointer offset out of bounds")]
     PointerOutOfBounds,
 
@@ -37,12 +54,18 @@ pub enum Error {
     #[error("asset data is not valid Brotli")]
     InvalidBrotli,
 
+    #[error("asset data is not valid {0}")]
+    InvalidEncoding(String),
+
     #[error("asset path escapes output directory: {asset}")]
     PathTraversal { asset: String },
 
     #[error("output already exists: {0}")]
     OutputExists(PathBuf),
 
+    #[error("export exceeds its quota: {0}")]
+    QuotaExceeded(String),
+
     #[error("source binary does not match manifest: expected {expected}, found {actual}")]
     SourceMismatch { expected: String, actual: String },
 
@@ -53,12 +76,25 @@ pub enum Error {
         max_size: usize,
     },
 
+    #[error("conflicting replacements for aliased assets {asset} and {alias}")]
+    AliasConflict { asset: String, alias: String },
+
     #[error("replacement directory contains unsupported new asset: {0}")]
     UnsupportedAddition(PathBuf),
 
+    #[error("invalid binary patch: {0}")]
+    InvalidPatch(String),
+
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
     #[error("manifest error: {0}")]
     Manifest(String),
 
+    #[error("signature error: {0}")]
+    Signature(String),
+
     #[error("{0}")]
     Message(String),
 }
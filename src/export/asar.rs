@@ -0,0 +1,165 @@
+use super::sink::{entry_path, not_open, AssetSink};
+use crate::asset::Asset;
+use crate::error::{Error, Result};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::mem;
+use std::path::{Component, Path, PathBuf};
+
+/// The name Electron gives its archives, so viewers pick the file up as is.
+pub const ASAR_FILE_NAME: &str = "app.asar";
+
+/// Block size of the integrity hashes Electron writes.
+const INTEGRITY_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Builds an asar archive in memory: a JSON directory tree whose files point
+/// into the data that follows the header.
+pub(super) struct AsarWriter {
+    path: PathBuf,
+    root: Map<String, Value>,
+    data: Vec<u8>,
+    current: Option<Pending>,
+}
+
+/// The asset being written, whose entry is added once it is complete.
+struct Pending {
+    asset: String,
+    /// The directories and file name of its entry.
+    names: Vec<String>,
+    /// Where its data starts.
+    offset: usize,
+}
+
+impl AsarWriter {
+    pub(super) fn create(path: &Path) -> Result<Self> {
+        Ok(Self {
+            path: path.to_path_buf(),
+            root: Map::new(),
+            data: Vec::new(),
+            current: None,
+        })
+    }
+}
+
+impl AssetSink for AsarWriter {
+    fn open(&mut self, asset: &Asset, name: &str) -> Result<PathBuf> {
+        let relative = entry_path(asset, name)?;
+        let names = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if names.is_empty() {
+            return Err(Error::InvalidAssetName);
+        }
+        self.current = Some(Pending {
+            asset: asset.name().to_string(),
+            names,
+            offset: self.data.len(),
+        });
+        Ok(self.path.clone())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        if self.current.is_none() {
+            return Err(not_open());
+        }
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let Pending {
+            asset,
+            mut names,
+            offset,
+        } = self.current.take().ok_or_else(not_open)?;
+        let file_name = names.pop().ok_or(Error::InvalidAssetName)?;
+        let data = &self.data[offset..];
+        let added = insert(&mut self.root, names, file_name, data, offset);
+        if added.is_none() {
+            self.data.truncate(offset);
+            return Err(conflict(&asset));
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        let header = serde_json::to_vec(&json!({ "files": mem::take(&mut self.root) }))?;
+        let padded_len = header.len().next_multiple_of(4);
+        let header_pickle_len = 8 + padded_len;
+
+        let mut archive = Vec::with_capacity(8 + header_pickle_len + self.data.len());
+        // A pickle holding the header pickle's size...
+        archive.extend(4u32.to_le_bytes());
+        archive.extend(pickle_len(header_pickle_len)?.to_le_bytes());
+        // ...then the header pickle, a length-prefixed string padded to 4 bytes.
+        archive.extend(pickle_len(4 + padded_len)?.to_le_bytes());
+        archive.extend(pickle_len(header.len())?.to_le_bytes());
+        archive.extend_from_slice(&header);
+        archive.resize(8 + header_pickle_len, 0);
+        archive.extend_from_slice(&mem::take(&mut self.data));
+        fs::write(&self.path, archive)?;
+        Ok(())
+    }
+}
+
+/// Adds a file entry for `data` under the directories `names`, or returns
+/// `None` if a file and a directory would share a name.
+fn insert(
+    root: &mut Map<String, Value>,
+    names: Vec<String>,
+    file_name: String,
+    data: &[u8],
+    offset: usize,
+) -> Option<()> {
+    let mut directory = root;
+    for name in names {
+        let entry = directory
+            .entry(name)
+            .or_insert_with(|| json!({ "files": {} }));
+        directory = entry.get_mut("files").and_then(Value::as_object_mut)?;
+    }
+    if directory
+        .get(&file_name)
+        .is_some_and(|entry| entry.get("files").is_some())
+    {
+        return None;
+    }
+    directory.insert(
+        file_name,
+        json!({
+            "size": data.len(),
+            // Offsets are strings because they may exceed 2^53.
+            "offset": offset.to_string(),
+            "integrity": integrity(data),
+        }),
+    );
+    Some(())
+}
+
+fn integrity(data: &[u8]) -> Value {
+    let blocks = data
+        .chunks(INTEGRITY_BLOCK_SIZE)
+        .map(|block| format!("{:x}", Sha256::digest(block)))
+        .collect::<Vec<_>>();
+    json!({
+        "algorithm": "SHA256",
+        "hash": format!("{:x}", Sha256::digest(data)),
+        "blockSize": INTEGRITY_BLOCK_SIZE,
+        "blocks": blocks,
+    })
+}
+
+fn pickle_len(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| Error::Message("asar header is larger than 4 GiB".to_string()))
+}
+
+fn conflict(asset: &str) -> Error {
+    Error::Message(format!(
+        "asset {asset} is both a file and a directory in the asar archive"
+    ))
+}
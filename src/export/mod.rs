@@ -0,0 +1,1097 @@
+mod asar;
+mod sink;
+mod site;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod tarball;
+mod writer;
+
+use crate::asset::{safe_relative_path, sha256_hex, Asset, AssetTable};
+use crate::error::{Error, Result};
+use crate::extract::{decompress_asset, decompress_asset_into};
+use crate::manifest::{Manifest, MANIFEST_FILE_NAME};
+use crate::mime;
+use crate::progress::{Progress, ProgressEvent};
+use crate::timings::{timed, Stage, Timings};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use memmap2::MmapMut;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+use writer::{WriterPool, Written};
+
+pub use asar::ASAR_FILE_NAME;
+pub use sink::{AssetSink, FsSink, MemorySink, ZipSink};
+pub use site::SITE_ROOT_DIR;
+#[cfg(feature = "sqlite")]
+pub use sqlite::DATABASE_FILE_NAME;
+pub use tarball::TarSink;
+
+/// The archive a zip export writes.
+pub const ZIP_FILE_NAME: &str = "assets.zip";
+
+/// How exported assets are laid out in the output directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// One file per asset, under its embedded path.
+    #[default]
+    Directory,
+    /// A single SQLite database, `assets.db`, with one row per asset.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+    /// An Electron asar archive, [`ASAR_FILE_NAME`].
+    Asar,
+    /// A zip archive, [`ZIP_FILE_NAME`].
+    Zip,
+    /// A directory export into [`SITE_ROOT_DIR`], plus nginx, Caddy and
+    /// `serve` configs for hosting it as a single-page app.
+    Site,
+}
+
+/// Where exported assets are placed below the output directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// Under their embedded path.
+    #[default]
+    Original,
+    /// Under a directory for their type, picked by MIME type, followed by
+    /// their embedded path. See [`type_dir`].
+    ByType,
+}
+
+/// The directory an asset named `name` goes into in a [`Layout::ByType`]
+/// export: `js`, `css`, `images`, `fonts`, `media` or `other`.
+pub fn type_dir(name: &str) -> &'static str {
+    match mime::mime_type(name) {
+        "text/javascript" => "js",
+        "text/css" => "css",
+        mime if mime.starts_with("image/") => "images",
+        mime if mime.starts_with("font/") => "fonts",
+        mime if mime.starts_with("audio/") || mime.starts_with("video/") => "media",
+        _ => "other",
+    }
+}
+
+/// Where a directory export stores assets whose content is identical.
+pub const STORE_DIR: &str = "store";
+/// Ignore file the CLI reads from the working directory by default.
+pub const IGNORE_FILE_NAME: &str = ".dumperignore";
+/// Maps asset names to their blobs in [`STORE_DIR`].
+pub const STORE_INDEX_FILE_NAME: &str = "store-index.json";
+
+/// How a directory export writes assets with identical content, such as
+/// locales or fonts shipped under several paths.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dedupe {
+    /// Every asset is written separately.
+    #[default]
+    None,
+    /// The first copy is written and later ones are hard links to it.
+    Hardlink,
+    /// Each distinct content is written once to [`STORE_DIR`], named by its
+    /// SHA-256, and [`STORE_INDEX_FILE_NAME`] maps asset names to blobs.
+    Store,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub output_dir: PathBuf,
+    pub overwrite: bool,
+    pub skip_existing: bool,
+    pub dry_run: bool,
+    pub write_manifest: bool,
+    /// Writes each asset table into its own `table-<index>` subdirectory.
+    pub split_tables: bool,
+    pub format: ExportFormat,
+    pub layout: Layout,
+    /// Only applies to directory and site exports.
+    pub dedupe: Dedupe,
+    /// Records assets that fail to decode or write as
+    /// [`ExportStatus::Failed`] and continues with the rest, instead of
+    /// returning the first error.
+    pub keep_going: bool,
+    /// Skips assets smaller than this many bytes. See [`filter_size`].
+    pub min_size: Option<usize>,
+    /// Skips assets larger than this many bytes. See [`filter_size`].
+    pub max_size: Option<usize>,
+    /// Refuses to export more than this many assets. See
+    /// [`ExportOptions::check_quota`].
+    pub max_files: Option<usize>,
+    /// Refuses to export assets that decode to more than this many bytes
+    /// in total. See [`ExportOptions::check_quota`].
+    pub max_total_size: Option<u64>,
+    /// How many threads decompress assets, ahead of the writes, which stay
+    /// in table order. Defaults to the available parallelism; 0 counts as 1.
+    pub jobs: usize,
+    /// How many threads write the files of a directory or site export
+    /// without dedupe, apart from the threads that decompress. Defaults to
+    /// the available parallelism, up to [`MAX_DEFAULT_WRITERS`]; 0 or 1
+    /// writes on the calling thread.
+    pub writers: usize,
+    /// Records the time spent decompressing and writing.
+    pub timings: Option<Arc<Timings>>,
+    /// Assets that decode to at least this many bytes are decoded straight
+    /// into a memory map of their output file rather than into a buffer.
+    /// Applies to directory and site exports without dedupe; `usize::MAX`
+    /// turns it off.
+    pub map_threshold: usize,
+    /// Receives each asset written.
+    pub progress: Option<Progress>,
+    types: Vec<&'static str>,
+    /// Asset name prefixes and what they are rewritten to.
+    path_rules: Vec<(String, String)>,
+    include: GlobSet,
+    exclude: GlobSet,
+    ignore: Option<Gitignore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub name: String,
+    pub path: PathBuf,
+    pub status: ExportStatus,
+    /// Why the asset failed, for [`ExportStatus::Failed`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Exported,
+    SkippedExisting,
+    SkippedFilter,
+    DryRun,
+    /// The asset could not be decoded or written; see
+    /// [`ExportOptions::keep_going`].
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportSummary {
+    pub output_dir: PathBuf,
+    pub exported: usize,
+    pub skipped_existing: usize,
+    pub skipped_filter: usize,
+    /// Assets whose content was already written under another name.
+    #[serde(default)]
+    pub deduplicated: usize,
+    #[serde(default)]
+    pub failed: usize,
+    pub dry_run: bool,
+    pub records: Vec<ExportRecord>,
+}
+
+impl ExportOptions {
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            overwrite: true,
+            skip_existing: false,
+            dry_run: false,
+            write_manifest: true,
+            split_tables: false,
+            format: ExportFormat::Directory,
+            layout: Layout::Original,
+            dedupe: Dedupe::None,
+            keep_going: false,
+            min_size: None,
+            max_size: None,
+            max_files: None,
+            max_total_size: None,
+            jobs: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            writers: thread::available_parallelism()
+                .map_or(1, NonZeroUsize::get)
+                .min(MAX_DEFAULT_WRITERS),
+            timings: None,
+            map_threshold: DEFAULT_MAP_THRESHOLD,
+            progress: None,
+            types: Vec::new(),
+            path_rules: Vec::new(),
+            include: GlobSetBuilder::new().build().expect("empty globset"),
+            exclude: GlobSetBuilder::new().build().expect("empty globset"),
+            ignore: None,
+        }
+    }
+
+    pub fn include_globs(mut self, globs: &[String]) -> Result<Self> {
+        self.include = build_globset(globs)?;
+        Ok(self)
+    }
+
+    pub fn exclude_globs(mut self, globs: &[String]) -> Result<Self> {
+        self.exclude = build_globset(globs)?;
+        Ok(self)
+    }
+
+    /// Keeps only assets of the given types, such as `js` or `image`, by
+    /// the MIME type of their names. See [`mime::ASSET_TYPES`].
+    pub fn only_types(mut self, types: &[String]) -> Result<Self> {
+        self.types.clear();
+        for name in types {
+            let mimes = mime::asset_type(name.trim()).ok_or_else(|| {
+                let known = mime::ASSET_TYPES
+                    .iter()
+                    .map(|(known, _)| *known)
+                    .collect::<Vec<_>>();
+                Error::Message(format!(
+                    "unknown asset type `{name}`, expected one of {}",
+                    known.join(", ")
+                ))
+            })?;
+            self.types.extend_from_slice(mimes);
+        }
+        Ok(self)
+    }
+
+    /// Rewrites the start of asset names before they are written, with rules
+    /// such as `/assets/=/static/`. The first rule whose prefix starts a
+    /// name applies. Filters and the manifest keep the original names.
+    pub fn map_paths(mut self, rules: &[String]) -> Result<Self> {
+        for rule in rules {
+            let (from, to) = rule
+                .split_once('=')
+                .filter(|(from, _)| !from.is_empty())
+                .ok_or_else(|| {
+                    Error::Message(format!("invalid path rule `{rule}`, expected FROM=TO"))
+                })?;
+            self.path_rules.push((from.to_string(), to.to_string()));
+        }
+        Ok(self)
+    }
+
+    /// Removes the directory `prefix` from the names of the assets below
+    /// it, so `/dist` writes `/dist/index.html` as `index.html`. It is added
+    /// after the rules already given.
+    pub fn strip_prefix(mut self, prefix: &str) -> Self {
+        let prefix = format!("{}/", prefix.trim_end_matches('/'));
+        self.path_rules.push((prefix, "/".to_string()));
+        self
+    }
+
+    /// The name `asset` is written under, after the rules of
+    /// [`map_paths`](Self::map_paths) and [`strip_prefix`](Self::strip_prefix).
+    pub fn output_name<'a>(&self, asset: &'a Asset) -> Cow<'a, str> {
+        let name = asset.name();
+        self.path_rules
+            .iter()
+            .find_map(|(from, to)| {
+                let rest = name.strip_prefix(from.as_str())?;
+                Some(Cow::Owned(format!("{to}{rest}")))
+            })
+            .unwrap_or(Cow::Borrowed(name))
+    }
+
+    /// The output name below the directory of the asset's type for
+    /// [`Layout::ByType`], and below the `table-<index>` directory of its
+    /// table when [`split_tables`](Self::split_tables) is set.
+    fn entry_name<'a>(&self, asset: &'a Asset) -> Cow<'a, str> {
+        let mut name = self.output_name(asset);
+        if self.layout == Layout::ByType {
+            name = Cow::Owned(format!(
+                "/{}/{}",
+                type_dir(asset.name()),
+                name.trim_start_matches('/')
+            ));
+        }
+        if self.split_tables {
+            name = Cow::Owned(format!(
+                "table-{}/{}",
+                asset.table_index(),
+                name.trim_start_matches('/')
+            ));
+        }
+        name
+    }
+
+    /// The manifest of `table`, with the paths of assets written anywhere
+    /// but under their name. SQLite rows and store blobs are not placed by
+    /// name, so their manifest has no paths.
+    fn manifest(&self, table: &AssetTable) -> Manifest {
+        let mut manifest = Manifest::from_asset_table(table);
+        #[cfg(feature = "sqlite")]
+        if self.format == ExportFormat::Sqlite {
+            return manifest;
+        }
+        let store = matches!(self.format, ExportFormat::Directory | ExportFormat::Site)
+            && self.dedupe == Dedupe::Store;
+        if store {
+            return manifest;
+        }
+        for (entry, asset) in manifest.assets.iter_mut().zip(table.assets()) {
+            let name = self.entry_name(asset);
+            let path = name.trim_start_matches('/');
+            if path != asset.name().trim_start_matches('/') {
+                entry.output_path = Some(path.to_string());
+            }
+        }
+        manifest
+    }
+
+    fn output_path(&self, base: &Path, asset: &Asset) -> Result<PathBuf> {
+        Ok(base.join(sink::entry_path(asset, &self.entry_name(asset))?))
+    }
+
+    /// Skips assets matched by the patterns in `path`, which follow
+    /// `.gitignore` rules: a leading `/` anchors a pattern to the asset root,
+    /// a trailing `/` matches directories only and `!` re-includes an asset
+    /// an earlier pattern excluded.
+    pub fn ignore_file(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut builder = GitignoreBuilder::new("");
+        if let Some(error) = builder.add(path) {
+            return Err(Error::Message(format!("{}: {error}", path.display())));
+        }
+        self.ignore = Some(
+            builder
+                .build()
+                .map_err(|err| Error::Message(format!("{}: {err}", path.display())))?,
+        );
+        Ok(self)
+    }
+
+    fn report_written(&self, asset: &Asset, path: &Path) {
+        if let Some(progress) = &self.progress {
+            progress.report(ProgressEvent::AssetWritten {
+                name: asset.name().to_string(),
+                path: path.to_path_buf(),
+            });
+        }
+    }
+
+    /// Returns [`Error::QuotaExceeded`] if the assets of `table` that these
+    /// options select are more than [`max_files`](Self::max_files) or
+    /// decode to more than [`max_total_size`](Self::max_total_size) bytes.
+    /// Exports check it before they write anything, except dry runs, and
+    /// stop with the same error once the bytes they decode pass the limit,
+    /// which catches assets whose size a quick verification left unknown.
+    pub fn check_quota(&self, table: &AssetTable) -> Result<()> {
+        if self.max_files.is_none() && self.max_total_size.is_none() {
+            return Ok(());
+        }
+        let (files, bytes) = table
+            .assets()
+            .iter()
+            .filter(|asset| self.includes(asset))
+            .fold((0usize, 0u64), |(files, bytes), asset| {
+                (files + 1, bytes + asset.decompressed_size() as u64)
+            });
+        if let Some(max) = self.max_files.filter(|&max| files > max) {
+            return Err(Error::QuotaExceeded(format!(
+                "{files} assets, more than the limit of {max}"
+            )));
+        }
+        if let Some(max) = self.max_total_size.filter(|&max| bytes > max) {
+            return Err(Error::QuotaExceeded(format!(
+                "{bytes} bytes, more than the limit of {max}"
+            )));
+        }
+        Ok(())
+    }
+
+    fn includes(&self, asset: &Asset) -> bool {
+        (self.include.is_empty() || self.include.is_match(asset.name()))
+            && !self.exclude.is_match(asset.name())
+            && !self.ignores(asset)
+            && self.min_size.is_none_or(|min| filter_size(asset) >= min)
+            && self.max_size.is_none_or(|max| filter_size(asset) <= max)
+            && self.matches_type(asset)
+    }
+
+    fn matches_type(&self, asset: &Asset) -> bool {
+        let mime = mime::mime_type(asset.name());
+        self.types.is_empty()
+            || self
+                .types
+                .iter()
+                .any(|known| mime == *known || (known.ends_with('/') && mime.starts_with(known)))
+    }
+
+    fn ignores(&self, asset: &Asset) -> bool {
+        let (Some(ignore), Some(path)) = (&self.ignore, safe_relative_path(asset.name())) else {
+            return false;
+        };
+        ignore.matched_path_or_any_parents(path, false).is_ignore()
+    }
+}
+
+impl AssetTable {
+    pub fn export(&self, options: &ExportOptions) -> Result<ExportSummary> {
+        if !options.dry_run {
+            options.check_quota(self)?;
+        }
+        let summary = match options.format {
+            ExportFormat::Directory => self.export_directory(options)?,
+            #[cfg(feature = "sqlite")]
+            ExportFormat::Sqlite => self.export_archive(options, DATABASE_FILE_NAME, |path| {
+                sqlite::SqliteWriter::create(path, self.metadata())
+            })?,
+            ExportFormat::Asar => {
+                self.export_archive(options, ASAR_FILE_NAME, asar::AsarWriter::create)?
+            }
+            ExportFormat::Zip => self.export_archive(options, ZIP_FILE_NAME, |path| {
+                Ok(ZipSink::new(BufWriter::new(File::create(path)?)))
+            })?,
+            ExportFormat::Site => {
+                let mut site = options.clone();
+                site.output_dir = options.output_dir.join(SITE_ROOT_DIR);
+                let mut summary = self.export_directory(&site)?;
+                summary.output_dir = options.output_dir.clone();
+                if !options.dry_run {
+                    site::write_server_configs(&options.output_dir, self)?;
+                }
+                summary
+            }
+        };
+
+        if options.write_manifest && !options.dry_run {
+            let manifest = options.manifest(self);
+            manifest.write(options.output_dir.join(MANIFEST_FILE_NAME))?;
+        }
+
+        Ok(summary)
+    }
+
+    fn export_directory(&self, options: &ExportOptions) -> Result<ExportSummary> {
+        if options.dedupe == Dedupe::Store {
+            return self.export_store(options);
+        }
+        let mut summary = ExportSummary::new(options);
+        if !options.dry_run {
+            fs::create_dir_all(&options.output_dir)?;
+        }
+
+        let mut decompressor = Decompressor::new(self, options);
+        let mut first_copies = HashMap::<String, PathBuf>::new();
+        let mut writers =
+            (options.writers > 1 && options.dedupe == Dedupe::None && !options.dry_run)
+                .then(|| WriterPool::new(options.writers));
+        let timings = options.timings.as_deref();
+        for asset in self.assets() {
+            let path = match options.output_path(&options.output_dir, asset) {
+                Ok(path) => path,
+                Err(error) => {
+                    summary.fail(asset, options.output_dir.clone(), error, options)?;
+                    continue;
+                }
+            };
+
+            if !options.includes(asset) {
+                summary.push(asset, path, ExportStatus::SkippedFilter);
+                continue;
+            }
+
+            if let Some(pool) = &mut writers {
+                let finished = if pool.conflicts(&path) {
+                    timed(timings, Stage::Io, || pool.wait())
+                } else {
+                    pool.finished()
+                };
+                for written in finished {
+                    summary.settle(written, options)?;
+                }
+            }
+
+            if path.exists() && !options.overwrite {
+                if options.skip_existing {
+                    summary.push(asset, path, ExportStatus::SkippedExisting);
+                } else {
+                    summary.fail(asset, path.clone(), Error::OutputExists(path), options)?;
+                }
+                continue;
+            }
+
+            if options.dry_run {
+                summary.push(asset, path, ExportStatus::DryRun);
+                continue;
+            }
+
+            let mapped = decompressor.maps(asset);
+            if let (Some(pool), false) = (&mut writers, mapped) {
+                let queued = decompressor
+                    .decompress_owned(asset, pool.buffer())
+                    .and_then(|data| {
+                        let index = summary.records.len();
+                        let path = path.clone();
+                        Ok(timed(timings, Stage::Io, || pool.write(index, path, data))?)
+                    });
+                match queued {
+                    Ok(()) => {
+                        options.report_written(asset, &path);
+                        summary.push(asset, path, ExportStatus::Exported);
+                    }
+                    Err(error) => summary.fail(asset, path, error, options)?,
+                }
+                continue;
+            }
+
+            let written = if mapped {
+                decompressor
+                    .charge(asset.decompressed_size())
+                    .and_then(|()| {
+                        timed(timings, Stage::Decompression, || write_mapped(asset, &path))
+                    })
+                    .map(|()| false)
+            } else {
+                write_file(
+                    asset,
+                    &path,
+                    options.dedupe,
+                    &mut decompressor,
+                    &mut first_copies,
+                    timings,
+                )
+            };
+            match written {
+                Ok(deduplicated) => {
+                    summary.deduplicated += usize::from(deduplicated);
+                    options.report_written(asset, &path);
+                    summary.push(asset, path, ExportStatus::Exported);
+                }
+                Err(error) => summary.fail(asset, path, error, options)?,
+            }
+        }
+        if let Some(pool) = &mut writers {
+            for written in timed(timings, Stage::Io, || pool.wait()) {
+                summary.settle(written, options)?;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Writes each distinct content once into [`STORE_DIR`] and every record
+    /// points at its blob. Blobs are named by content, so existing ones are
+    /// reused.
+    fn export_store(&self, options: &ExportOptions) -> Result<ExportSummary> {
+        let mut summary = ExportSummary::new(options);
+        let store_dir = options.output_dir.join(STORE_DIR);
+        if !options.dry_run {
+            fs::create_dir_all(&store_dir)?;
+        }
+
+        let mut decompressor = Decompressor::new(self, options);
+        let mut index = BTreeMap::new();
+        let mut written = HashSet::new();
+        for asset in self.assets() {
+            if !options.includes(asset) {
+                let path = options.output_path(&options.output_dir, asset)?;
+                summary.push(asset, path, ExportStatus::SkippedFilter);
+                continue;
+            }
+            if options.dry_run {
+                summary.push(asset, store_dir.clone(), ExportStatus::DryRun);
+                continue;
+            }
+            let data = match decompressor.decompress(asset) {
+                Ok(data) => data,
+                Err(error) => {
+                    summary.fail(asset, store_dir.clone(), error, options)?;
+                    continue;
+                }
+            };
+            let hash = sha256_hex(&data);
+            let path = store_dir.join(&hash);
+            if !written.insert(hash.clone()) {
+                summary.deduplicated += 1;
+            } else if !path.exists() {
+                let timings = options.timings.as_deref();
+                if let Err(error) = timed(timings, Stage::Io, || fs::write(&path, &data)) {
+                    summary.fail(asset, path, error.into(), options)?;
+                    continue;
+                }
+            }
+            index.insert(options.output_name(asset).into_owned(), hash);
+            options.report_written(asset, &path);
+            summary.push(asset, path, ExportStatus::Exported);
+        }
+        if !options.dry_run {
+            fs::write(
+                options.output_dir.join(STORE_INDEX_FILE_NAME),
+                serde_json::to_vec_pretty(&index)?,
+            )?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Exports into a single file, `file_name`, in the output directory.
+    /// Every record points at that file.
+    fn export_archive<W: AssetSink>(
+        &self,
+        options: &ExportOptions,
+        file_name: &str,
+        create: impl FnOnce(&Path) -> Result<W>,
+    ) -> Result<ExportSummary> {
+        let path = options.output_dir.join(file_name);
+        let status = if path.exists() && !options.overwrite {
+            if !options.skip_existing {
+                return Err(Error::OutputExists(path));
+            }
+            ExportStatus::SkippedExisting
+        } else if options.dry_run {
+            ExportStatus::DryRun
+        } else {
+            ExportStatus::Exported
+        };
+
+        let mut sink = if status == ExportStatus::Exported {
+            fs::create_dir_all(&options.output_dir)?;
+            Some(create(&path)?)
+        } else {
+            None
+        };
+        let sink = sink.as_mut().map(|sink| sink as &mut dyn AssetSink);
+        self.write_sink(options, Some(&path), status, sink)
+    }
+
+    /// Streams the decompressed assets `options` includes into `sink`, under
+    /// their names after the path rules, then closes it. Records point where
+    /// the sink put each asset.
+    ///
+    /// [`ExportOptions::format`], dedupe, dry runs and the manifest do not
+    /// apply, as the sink decides what is written.
+    pub fn export_to(
+        &self,
+        sink: &mut dyn AssetSink,
+        options: &ExportOptions,
+    ) -> Result<ExportSummary> {
+        options.check_quota(self)?;
+        let mut summary = self.write_sink(options, None, ExportStatus::Exported, Some(sink))?;
+        summary.dry_run = false;
+        Ok(summary)
+    }
+
+    /// Writes a tar stream of the decompressed assets to `writer`, under
+    /// their names after the path rules, followed by the manifest if
+    /// [`ExportOptions::write_manifest`] is set. Nothing touches the disk,
+    /// so the stream can go to stdout or a socket.
+    ///
+    /// Every record points at [`ExportOptions::output_dir`], and dedupe
+    /// and dry runs do not apply.
+    pub fn export_tar(&self, writer: impl Write, options: &ExportOptions) -> Result<ExportSummary> {
+        options.check_quota(self)?;
+        let mut tar = TarSink::new(writer);
+        if options.write_manifest {
+            tar = tar.with_manifest(serde_json::to_vec_pretty(&options.manifest(self))?);
+        }
+        let mut summary = self.write_sink(
+            options,
+            Some(&options.output_dir),
+            ExportStatus::Exported,
+            Some(&mut tar),
+        )?;
+        summary.dry_run = false;
+        Ok(summary)
+    }
+
+    /// Streams the assets `options` includes into `sink`, recording each at
+    /// `path`, or where the sink put it without one. Without a sink, the
+    /// assets are only recorded as `status`.
+    fn write_sink(
+        &self,
+        options: &ExportOptions,
+        path: Option<&Path>,
+        status: ExportStatus,
+        mut sink: Option<&mut dyn AssetSink>,
+    ) -> Result<ExportSummary> {
+        let mut summary = ExportSummary::new(options);
+        let mut decompressor = Decompressor::new(self, options);
+        let timings = options.timings.as_deref();
+        for asset in self.assets() {
+            let name = options.entry_name(asset);
+            let record_path = || path.map_or_else(|| PathBuf::from(&*name), Path::to_path_buf);
+            if !options.includes(asset) {
+                summary.push(asset, record_path(), ExportStatus::SkippedFilter);
+                continue;
+            }
+            let Some(sink) = sink.as_deref_mut() else {
+                summary.push(asset, record_path(), status);
+                continue;
+            };
+            let written = decompressor.decompress(asset).and_then(|data| {
+                timed(timings, Stage::Io, || {
+                    let written = sink.open(asset, &name)?;
+                    sink.write(&data)?;
+                    sink.finish()?;
+                    Ok(written)
+                })
+            });
+            match written {
+                Ok(written) => {
+                    options.report_written(asset, &written);
+                    summary.push(asset, path.map_or(written, Path::to_path_buf), status);
+                }
+                Err(error) => summary.fail(asset, record_path(), error, options)?,
+            }
+        }
+        if let Some(sink) = sink {
+            timed(timings, Stage::Io, || sink.close())?;
+        }
+
+        Ok(summary)
+    }
+}
+
+impl ExportSummary {
+    fn new(options: &ExportOptions) -> Self {
+        Self {
+            output_dir: options.output_dir.clone(),
+            exported: 0,
+            skipped_existing: 0,
+            skipped_filter: 0,
+            deduplicated: 0,
+            failed: 0,
+            dry_run: options.dry_run,
+            records: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, asset: &Asset, path: PathBuf, status: ExportStatus) {
+        match status {
+            ExportStatus::Exported => self.exported += 1,
+            ExportStatus::SkippedExisting => self.skipped_existing += 1,
+            ExportStatus::SkippedFilter => self.skipped_filter += 1,
+            ExportStatus::Failed => self.failed += 1,
+            ExportStatus::DryRun => {}
+        }
+        self.records.push(ExportRecord {
+            name: asset.name().to_string(),
+            path,
+            status,
+            error: None,
+        });
+    }
+
+    /// Records `error` for `asset` if `options.keep_going` is set, and
+    /// returns it otherwise.
+    fn fail(
+        &mut self,
+        asset: &Asset,
+        path: PathBuf,
+        error: Error,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        // Going on past the quota would defeat it.
+        if !options.keep_going || matches!(error, Error::QuotaExceeded(_)) {
+            return Err(error);
+        }
+        log::warn!("failed to export {}: {error}", asset.name());
+        self.push(asset, path, ExportStatus::Failed);
+        if let Some(record) = self.records.last_mut() {
+            record.error = Some(error.to_string());
+        }
+        Ok(())
+    }
+
+    /// Applies the outcome of a write from a [`WriterPool`] to its record,
+    /// which was pushed as exported when the write was queued.
+    fn settle(&mut self, written: Written, options: &ExportOptions) -> Result<()> {
+        let Err(error) = written.result else {
+            return Ok(());
+        };
+        let error = Error::from(error);
+        if !options.keep_going {
+            return Err(error);
+        }
+        let record = &mut self.records[written.index];
+        log::warn!("failed to export {}: {error}", record.name);
+        record.status = ExportStatus::Failed;
+        record.error = Some(error.to_string());
+        self.exported -= 1;
+        self.failed += 1;
+        Ok(())
+    }
+}
+
+/// Decompresses `asset` and writes it to `path`, returning whether it was
+/// linked to an earlier copy of the same content.
+fn write_file(
+    asset: &Asset,
+    path: &Path,
+    dedupe: Dedupe,
+    decompressor: &mut Decompressor<'_>,
+    first_copies: &mut HashMap<String, PathBuf>,
+    timings: Option<&Timings>,
+) -> Result<bool> {
+    let data = decompressor.decompress(asset)?;
+    timed(timings, Stage::Io, || {
+        write_data(path, &data, dedupe, first_copies)
+    })
+}
+
+/// Decodes `asset` into its output file through a memory map, so the
+/// content is never held in memory as a whole. The file is removed again if
+/// decoding fails.
+fn write_mapped(asset: &Asset, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+    file.set_len(asset.decompressed_size() as u64)?;
+    // SAFETY: the file was just truncated and sized by this export, and
+    // nothing else writes to it until the map is dropped below.
+    let mut map = unsafe { MmapMut::map_mut(&file)? };
+    let written = decompress_asset_into(asset, &mut map).and_then(|()| Ok(map.flush()?));
+    drop(map);
+    if written.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    written
+}
+
+fn write_data(
+    path: &Path,
+    data: &[u8],
+    dedupe: Dedupe,
+    first_copies: &mut HashMap<String, PathBuf>,
+) -> Result<bool> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if dedupe != Dedupe::Hardlink {
+        fs::write(path, data)?;
+        return Ok(false);
+    }
+    // Writing through an existing hard link would change every other name
+    // for the same file.
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    match first_copies.entry(sha256_hex(data)) {
+        Entry::Occupied(first) => {
+            if let Err(error) = fs::hard_link(first.get(), path) {
+                log::debug!("copying {}: {error}", path.display());
+                fs::write(path, data)?;
+            }
+            Ok(true)
+        }
+        Entry::Vacant(first) => {
+            fs::write(path, data)?;
+            first.insert(path.to_path_buf());
+            Ok(false)
+        }
+    }
+}
+
+/// Decompresses assets, sharing the result between aliases of the same data.
+///
+/// With more than one job, the assets the export includes are decompressed
+/// ahead in batches of [`ExportOptions::jobs`], one thread each, so at most
+/// that many decoded assets wait for their write.
+struct Decompressor<'a> {
+    aliased_offsets: HashSet<usize>,
+    cache: HashMap<usize, Vec<u8>>,
+    /// The assets to decompress, in export order.
+    queue: Vec<&'a Asset>,
+    /// The position in `queue` to search for the next asset from.
+    next: usize,
+    jobs: usize,
+    /// Decoded data by data offset, which aliases share.
+    prefetched: HashMap<usize, Vec<u8>>,
+    timings: Option<Arc<Timings>>,
+    /// See [`ExportOptions::map_threshold`]; `None` when the export cannot
+    /// write through a map.
+    map_threshold: Option<usize>,
+    /// See [`ExportOptions::max_total_size`].
+    max_total_size: Option<u64>,
+    /// Bytes decoded so far, counted against `max_total_size`.
+    decoded: u64,
+}
+
+impl<'a> Decompressor<'a> {
+    fn new(table: &'a AssetTable, options: &ExportOptions) -> Self {
+        let jobs = options.jobs.max(1);
+        let mapped = options.dedupe == Dedupe::None
+            && matches!(options.format, ExportFormat::Directory | ExportFormat::Site);
+        let mut decompressor = Self {
+            aliased_offsets: table
+                .assets()
+                .iter()
+                .filter(|asset| asset.alias_of().is_some())
+                .map(|asset| asset.location().data_offset)
+                .collect(),
+            cache: HashMap::new(),
+            queue: Vec::new(),
+            next: 0,
+            jobs,
+            prefetched: HashMap::new(),
+            timings: options.timings.clone(),
+            map_threshold: mapped.then_some(options.map_threshold),
+            max_total_size: options.max_total_size,
+            decoded: 0,
+        };
+        if jobs > 1 && !options.dry_run {
+            decompressor.queue = table
+                .assets()
+                .iter()
+                .filter(|asset| options.includes(asset) && !decompressor.maps(asset))
+                .collect();
+        }
+        decompressor
+    }
+
+    /// Whether `asset` is large enough to be written through a map. Aliased
+    /// data is decoded once into the cache instead.
+    fn maps(&self, asset: &Asset) -> bool {
+        self.map_threshold.is_some_and(|threshold| {
+            asset.decompressed_size() >= threshold.max(1)
+                && !self.aliased_offsets.contains(&asset.location().data_offset)
+        })
+    }
+
+    fn decompress(&mut self, asset: &Asset) -> Result<Cow<'_, [u8]>> {
+        let data_offset = asset.location().data_offset;
+        if !self.aliased_offsets.contains(&data_offset) {
+            return Ok(Cow::Owned(self.decode(asset)?));
+        }
+        if !self.cache.contains_key(&data_offset) {
+            let data = self.decode(asset)?;
+            self.cache.insert(data_offset, data);
+        }
+        Ok(Cow::Borrowed(&self.cache[&data_offset]))
+    }
+
+    /// Like [`Self::decompress`], into `buffer` where the data is decoded
+    /// here rather than prefetched or cached.
+    fn decompress_owned(&mut self, asset: &Asset, mut buffer: Vec<u8>) -> Result<Vec<u8>> {
+        if self.aliased_offsets.contains(&asset.location().data_offset) {
+            buffer.extend_from_slice(&self.decompress(asset)?);
+            return Ok(buffer);
+        }
+        self.decode_into(asset, Some(buffer))
+    }
+
+    fn decode(&mut self, asset: &Asset) -> Result<Vec<u8>> {
+        self.decode_into(asset, None)
+    }
+
+    fn decode_into(&mut self, asset: &Asset, buffer: Option<Vec<u8>>) -> Result<Vec<u8>> {
+        let started = Instant::now();
+        let data_offset = asset.location().data_offset;
+        if !self.prefetched.contains_key(&data_offset) {
+            self.prefetch(asset);
+        }
+        let decoded = match (self.prefetched.remove(&data_offset), buffer) {
+            (Some(data), _) => Ok(data),
+            (None, Some(mut buffer)) if asset.decompressed_size() > 0 => {
+                buffer.resize(asset.decompressed_size(), 0);
+                decompress_asset_into(asset, &mut buffer)
+                    .map(|()| buffer)
+                    .or_else(|_| decompress_asset(asset))
+            }
+            (None, _) => decompress_asset(asset),
+        };
+        if let Some(timings) = &self.timings {
+            timings.record(Stage::Decompression, started.elapsed());
+        }
+        let decoded = decoded?;
+        self.charge(decoded.len())?;
+        Ok(decoded)
+    }
+
+    /// Counts `len` decoded bytes against the byte quota. The quota is
+    /// checked up front from the sizes the scan recorded, but a quick
+    /// verification leaves them unknown, so it is enforced here as well.
+    fn charge(&mut self, len: usize) -> Result<()> {
+        self.decoded += len as u64;
+        match self.max_total_size {
+            Some(max) if self.decoded > max => Err(Error::QuotaExceeded(format!(
+                "more than the limit of {max} bytes once decoded"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decompresses `asset` and the assets queued after it, up to `jobs` of
+    /// them, in parallel. Assets that fail are left for [`Self::decompress`]
+    /// to report.
+    fn prefetch(&mut self, asset: &Asset) {
+        let Some(position) = self.queue[self.next..]
+            .iter()
+            .position(|queued| std::ptr::eq(*queued, asset))
+        else {
+            return;
+        };
+        let start = self.next + position;
+        let mut batch = Vec::with_capacity(self.jobs);
+        let mut end = start;
+        while end < self.queue.len() && batch.len() < self.jobs {
+            let queued = self.queue[end];
+            let data_offset = queued.location().data_offset;
+            let pending = batch
+                .iter()
+                .any(|other: &&Asset| other.location().data_offset == data_offset);
+            if !pending && !self.cache.contains_key(&data_offset) {
+                batch.push(queued);
+            }
+            end += 1;
+        }
+        self.next = end;
+
+        let decoded = thread::scope(|scope| {
+            let handles = batch
+                .iter()
+                .map(|queued| scope.spawn(|| decompress_asset(queued)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().ok().and_then(Result::ok))
+                .collect::<Vec<_>>()
+        });
+        for (queued, data) in batch.iter().zip(decoded) {
+            if let Some(data) = data {
+                self.prefetched.insert(queued.location().data_offset, data);
+            }
+        }
+    }
+}
+
+/// The most writer threads [`ExportOptions::writers`] defaults to.
+pub const MAX_DEFAULT_WRITERS: usize = 8;
+
+/// The default [`ExportOptions::map_threshold`].
+pub const DEFAULT_MAP_THRESHOLD: usize = 64 * 1024 * 1024;
+
+pub fn asset_output_path(base: &Path, asset: &Asset) -> Result<PathBuf> {
+    let relative = safe_relative_path(asset.name()).ok_or_else(|| Error::PathTraversal {
+        asset: asset.name().to_string(),
+    })?;
+    Ok(base.join(relative))
+}
+
+/// The size `min_size` and `max_size` compare against: the decompressed size,
+/// or the compressed size for assets whose decompressed size is unknown.
+pub fn filter_size(asset: &Asset) -> usize {
+    match asset.decompressed_size() {
+        0 => asset.compressed_size(),
+        size => size,
+    }
+}
+
+fn build_globset(globs: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).map_err(|err| Error::Message(err.to_string()))?);
+    }
+    builder
+        .build()
+        .map_err(|err| Error::Message(err.to_string()))
+}
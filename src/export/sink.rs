@@ -0,0 +1,184 @@
+//! Destinations that exported assets are streamed into, one at a time.
+
+use crate::asset::{safe_relative_path, Asset};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+/// Receives the decoded assets of an export.
+///
+/// Each asset is [`open`](Self::open)ed, its data passed to
+/// [`write`](Self::write), possibly in several pieces, and completed with
+/// [`finish`](Self::finish). [`close`](Self::close) is called once after
+/// the last asset.
+pub trait AssetSink {
+    /// Starts `asset` under `name`, its name after the path rules, and
+    /// returns where it is written, for the export record.
+    fn open(&mut self, asset: &Asset, name: &str) -> Result<PathBuf>;
+
+    fn write(&mut self, data: &[u8]) -> Result<()>;
+
+    /// Completes the asset opened last.
+    fn finish(&mut self) -> Result<()>;
+
+    /// Completes the output after the last asset.
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The relative path `name` is stored under, rejecting names that would
+/// escape the output.
+pub(super) fn entry_path(asset: &Asset, name: &str) -> Result<PathBuf> {
+    safe_relative_path(name).ok_or_else(|| Error::PathTraversal {
+        asset: asset.name().to_string(),
+    })
+}
+
+/// Writes each asset to its own file below a directory, replacing existing
+/// files.
+pub struct FsSink {
+    dir: PathBuf,
+    file: Option<BufWriter<File>>,
+}
+
+impl FsSink {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            file: None,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl AssetSink for FsSink {
+    fn open(&mut self, asset: &Asset, name: &str) -> Result<PathBuf> {
+        let path = self.dir.join(entry_path(asset, name)?);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        self.file = Some(BufWriter::new(File::create(&path)?));
+        Ok(path)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.file.as_mut().ok_or_else(not_open)?.write_all(data)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.file.take().ok_or_else(not_open)?.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes a zip archive with one deflated entry per asset.
+pub struct ZipSink<W: Write + Seek> {
+    zip: Option<ZipWriter<W>>,
+}
+
+impl<W: Write + Seek> ZipSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            zip: Some(ZipWriter::new(writer)),
+        }
+    }
+
+    fn zip(&mut self) -> Result<&mut ZipWriter<W>> {
+        self.zip
+            .as_mut()
+            .ok_or_else(|| Error::Message("the zip archive is already closed".to_string()))
+    }
+}
+
+impl<W: Write + Seek> AssetSink for ZipSink<W> {
+    fn open(&mut self, asset: &Asset, name: &str) -> Result<PathBuf> {
+        let path = entry_path(asset, name)?;
+        // Zip entries always use forward slashes.
+        let entry = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.zip()?
+            .start_file(entry, SimpleFileOptions::default())
+            .map_err(zip_error)?;
+        Ok(path)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.zip()?.write_all(data)?;
+        Ok(())
+    }
+
+    /// Entries are completed when the next one starts or the archive closes.
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some(zip) = self.zip.take() {
+            zip.finish().map_err(zip_error)?.flush()?;
+        }
+        Ok(())
+    }
+}
+
+fn zip_error(error: zip::result::ZipError) -> Error {
+    Error::Message(format!("failed to write the zip archive: {error}"))
+}
+
+/// Keeps the decoded assets in memory, by their name after the path rules.
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    files: HashMap<String, Vec<u8>>,
+    current: Option<(String, Vec<u8>)>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn files(&self) -> &HashMap<String, Vec<u8>> {
+        &self.files
+    }
+
+    pub fn into_files(self) -> HashMap<String, Vec<u8>> {
+        self.files
+    }
+}
+
+impl AssetSink for MemorySink {
+    fn open(&mut self, _asset: &Asset, name: &str) -> Result<PathBuf> {
+        self.current = Some((name.to_string(), Vec::new()));
+        Ok(PathBuf::from(name))
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.current
+            .as_mut()
+            .ok_or_else(not_open)?
+            .1
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let (name, data) = self.current.take().ok_or_else(not_open)?;
+        self.files.insert(name, data);
+        Ok(())
+    }
+}
+
+pub(super) fn not_open() -> Error {
+    Error::Message("no asset is open in the sink".to_string())
+}
@@ -0,0 +1,78 @@
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::mime::mime_type;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// The directory, inside the output directory, that the assets are served
+/// from. The server configs sit next to it so they are not served themselves.
+pub const SITE_ROOT_DIR: &str = "public";
+
+const PORT: u16 = 8080;
+
+/// Writes nginx, Caddy and `serve` configs that serve [`SITE_ROOT_DIR`] and
+/// fall back to `/index.html` for client-side routes.
+pub(super) fn write_server_configs(output_dir: &Path, table: &AssetTable) -> Result<()> {
+    fs::write(output_dir.join("nginx.conf"), nginx_config(table))?;
+    fs::write(output_dir.join("Caddyfile"), caddy_config())?;
+    let serve = json!({
+        "public": SITE_ROOT_DIR,
+        "rewrites": [{ "source": "**", "destination": "/index.html" }],
+    });
+    fs::write(
+        output_dir.join("serve.json"),
+        serde_json::to_vec_pretty(&serve)?,
+    )?;
+    Ok(())
+}
+
+fn nginx_config(table: &AssetTable) -> String {
+    // nginx only knows the types it is told about; list the ones in use.
+    let mut types = BTreeMap::<&str, BTreeSet<String>>::new();
+    for asset in table.assets() {
+        let Some((_, extension)) = asset.name().rsplit_once('.') else {
+            continue;
+        };
+        let mime = mime_type(asset.name());
+        if mime != "application/octet-stream" && !extension.contains('/') {
+            types
+                .entry(mime)
+                .or_default()
+                .insert(extension.to_ascii_lowercase());
+        }
+    }
+
+    let mut config = String::new();
+    config.push_str("# Generated by tauri-dumper. Run from this directory with:\n");
+    config.push_str("#   nginx -p \"$PWD\" -c nginx.conf\n");
+    config.push_str("daemon off;\nerror_log stderr;\npid nginx.pid;\n\nevents {}\n\nhttp {\n");
+    config.push_str("    access_log off;\n    default_type application/octet-stream;\n\n");
+    config.push_str("    types {\n");
+    for (mime, extensions) in &types {
+        let extensions = extensions.iter().cloned().collect::<Vec<_>>().join(" ");
+        let _ = writeln!(config, "        {mime} {extensions};");
+    }
+    config.push_str("    }\n\n    server {\n");
+    let _ = writeln!(config, "        listen {PORT};");
+    let _ = writeln!(config, "        root {SITE_ROOT_DIR};");
+    config.push_str(
+        "        location / {\n            try_files $uri $uri/ /index.html;\n        }\n",
+    );
+    config.push_str("    }\n}\n");
+    config
+}
+
+fn caddy_config() -> String {
+    format!(
+        "# Generated by tauri-dumper. Run from this directory with:\n\
+         #   caddy run\n\
+         :{PORT} {{\n\
+         \troot * {SITE_ROOT_DIR}\n\
+         \ttry_files {{path}} /index.html\n\
+         \tfile_server\n\
+         }}\n"
+    )
+}
@@ -0,0 +1,106 @@
+use super::sink::{not_open, AssetSink};
+use crate::asset::{sha256_hex, Asset};
+use crate::binary::BinaryMetadata;
+use crate::error::Result;
+use crate::mime::mime_type;
+use rusqlite::{params, Connection};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const DATABASE_FILE_NAME: &str = "assets.db";
+
+const SCHEMA: &str = "
+CREATE TABLE source (
+    path TEXT,
+    sha256 TEXT NOT NULL,
+    file_size INTEGER NOT NULL,
+    binary_kind TEXT NOT NULL,
+    architecture TEXT NOT NULL
+);
+CREATE TABLE assets (
+    path TEXT NOT NULL,
+    mime TEXT NOT NULL,
+    table_index INTEGER NOT NULL,
+    compressed_size INTEGER NOT NULL,
+    decompressed_size INTEGER NOT NULL,
+    compressed_sha256 TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    alias_of TEXT,
+    data BLOB NOT NULL
+);
+CREATE INDEX assets_path ON assets (path);
+";
+
+pub(super) struct SqliteWriter {
+    path: PathBuf,
+    connection: Connection,
+    current: Option<(Asset, Vec<u8>)>,
+}
+
+impl SqliteWriter {
+    /// Creates the database at `path`, replacing any existing file.
+    pub(super) fn create(path: &Path, metadata: &BinaryMetadata) -> Result<Self> {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        let connection = Connection::open(path)?;
+        connection.execute_batch(SCHEMA)?;
+        connection.execute(
+            "INSERT INTO source VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                metadata.source_path,
+                metadata.sha256,
+                metadata.file_size as i64,
+                format!("{:?}", metadata.kind),
+                metadata.architecture,
+            ],
+        )?;
+        // One transaction for all rows; committing each insert is slow.
+        connection.execute_batch("BEGIN")?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection,
+            current: None,
+        })
+    }
+}
+
+impl AssetSink for SqliteWriter {
+    /// Rows keep the original name, since the database indexes the binary.
+    fn open(&mut self, asset: &Asset, _name: &str) -> Result<PathBuf> {
+        self.current = Some((asset.clone(), Vec::new()));
+        Ok(self.path.clone())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.current
+            .as_mut()
+            .ok_or_else(not_open)?
+            .1
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let (asset, data) = self.current.take().ok_or_else(not_open)?;
+        self.connection
+            .prepare_cached("INSERT INTO assets VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)")?
+            .execute(params![
+                asset.name(),
+                mime_type(asset.name()),
+                asset.table_index() as i64,
+                asset.compressed_size() as i64,
+                data.len() as i64,
+                asset.compressed_sha256(),
+                sha256_hex(&data),
+                asset.alias_of(),
+                data,
+            ])?;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.connection.execute_batch("COMMIT")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use super::sink::{entry_path, not_open, AssetSink};
+use crate::asset::Asset;
+use crate::error::Result;
+use crate::manifest::MANIFEST_FILE_NAME;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Writes a tar stream of the assets as they complete, so nothing is
+/// written to disk. Each asset is held in memory until it is finished,
+/// since a tar header records its size.
+pub struct TarSink<W: Write> {
+    builder: Option<tar::Builder<W>>,
+    mtime: u64,
+    current: Option<(PathBuf, Vec<u8>)>,
+    manifest: Option<Vec<u8>>,
+}
+
+impl<W: Write> TarSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            builder: Some(tar::Builder::new(writer)),
+            mtime: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            current: None,
+            manifest: None,
+        }
+    }
+
+    /// Appends `manifest` as the last entry, when the sink is closed.
+    pub fn with_manifest(mut self, manifest: Vec<u8>) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    fn append(&mut self, path: &Path, data: &[u8]) -> Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(self.mtime);
+        self.builder
+            .as_mut()
+            .ok_or_else(not_open)?
+            .append_data(&mut header, path, data)?;
+        Ok(())
+    }
+}
+
+impl<W: Write> AssetSink for TarSink<W> {
+    fn open(&mut self, asset: &Asset, name: &str) -> Result<PathBuf> {
+        let path = entry_path(asset, name)?;
+        self.current = Some((path.clone(), Vec::new()));
+        Ok(path)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.current
+            .as_mut()
+            .ok_or_else(not_open)?
+            .1
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let (path, data) = self.current.take().ok_or_else(not_open)?;
+        self.append(&path, &data)
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if let Some(manifest) = self.manifest.take() {
+            self.append(Path::new(MANIFEST_FILE_NAME), &manifest)?;
+        }
+        if let Some(builder) = self.builder.take() {
+            builder.into_inner()?.flush()?;
+        }
+        Ok(())
+    }
+}
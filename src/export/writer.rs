@@ -0,0 +1,172 @@
+//! Writes exported files from a pool of threads.
+//!
+//! Creating and writing thousands of small files is slow on some file
+//! systems, NTFS in particular, so a directory export hands each decoded
+//! asset to one of a bounded number of writer threads and goes on decoding
+//! the next. Directories are created on the calling thread, once each, and
+//! the buffers of finished writes are handed back to be reused.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Writes queued per writer thread before [`WriterPool::write`] waits.
+const QUEUED_PER_WRITER: usize = 4;
+/// Buffers larger than this are dropped rather than kept for reuse.
+const MAX_REUSED_CAPACITY: usize = 1024 * 1024;
+
+struct Job {
+    index: usize,
+    path: PathBuf,
+    data: Vec<u8>,
+}
+
+/// The outcome of a write, identified by the index it was queued with.
+pub(super) struct Written {
+    pub index: usize,
+    pub path: PathBuf,
+    pub result: io::Result<()>,
+}
+
+pub(super) struct WriterPool {
+    jobs: Option<Sender<Job>>,
+    done: Receiver<(Written, Vec<u8>)>,
+    handles: Vec<JoinHandle<()>>,
+    /// Paths queued and not yet written.
+    pending: HashSet<PathBuf>,
+    created_dirs: HashSet<PathBuf>,
+    finished: Vec<Written>,
+    spare: Vec<Vec<u8>>,
+    limit: usize,
+}
+
+impl WriterPool {
+    pub fn new(threads: usize) -> Self {
+        let threads = threads.max(1);
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let (results, done) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(queue));
+        let handles = (0..threads)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                let results = results.clone();
+                thread::spawn(move || loop {
+                    let job = match queue.lock() {
+                        Ok(queue) => queue.recv(),
+                        Err(_) => return,
+                    };
+                    let Ok(Job { index, path, data }) = job else {
+                        return;
+                    };
+                    let result = fs::write(&path, &data);
+                    let written = Written {
+                        index,
+                        path,
+                        result,
+                    };
+                    if results.send((written, data)).is_err() {
+                        return;
+                    }
+                })
+            })
+            .collect();
+        Self {
+            jobs: Some(jobs),
+            done,
+            handles,
+            pending: HashSet::new(),
+            created_dirs: HashSet::new(),
+            finished: Vec::new(),
+            spare: Vec::new(),
+            limit: threads * QUEUED_PER_WRITER,
+        }
+    }
+
+    /// Whether `path` or one of its parents is still being written, so that
+    /// anything touching it has to [`wait`](Self::wait) first.
+    pub fn conflicts(&self, path: &Path) -> bool {
+        !self.pending.is_empty() && path.ancestors().any(|path| self.pending.contains(path))
+    }
+
+    /// Creates the parent directory of `path` and queues `data` to be
+    /// written to it, waiting first if too many writes are queued.
+    pub fn write(&mut self, index: usize, path: PathBuf, data: Vec<u8>) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            if !self.created_dirs.contains(parent) {
+                fs::create_dir_all(parent)?;
+                self.created_dirs.insert(parent.to_path_buf());
+            }
+        }
+        while self.pending.len() >= self.limit {
+            self.receive();
+        }
+        self.pending.insert(path.clone());
+        let job = Job { index, path, data };
+        if let Some(jobs) = &self.jobs {
+            if let Err(mpsc::SendError(job)) = jobs.send(job) {
+                // Every writer has exited; write on this thread instead.
+                self.pending.remove(&job.path);
+                let result = fs::write(&job.path, &job.data);
+                self.finished.push(Written {
+                    index: job.index,
+                    path: job.path,
+                    result,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// A buffer a finished write has returned, if any, emptied for reuse.
+    pub fn buffer(&mut self) -> Vec<u8> {
+        let mut buffer = self.spare.pop().unwrap_or_default();
+        buffer.clear();
+        buffer
+    }
+
+    /// The writes finished so far, in the order they finished.
+    pub fn finished(&mut self) -> Vec<Written> {
+        while let Ok((written, data)) = self.done.try_recv() {
+            self.complete(written, data);
+        }
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Waits for every queued write and returns the writes finished since
+    /// the last call.
+    pub fn wait(&mut self) -> Vec<Written> {
+        while !self.pending.is_empty() {
+            self.receive();
+        }
+        self.finished()
+    }
+
+    fn receive(&mut self) {
+        match self.done.recv() {
+            Ok((written, data)) => self.complete(written, data),
+            // The writers are gone, so nothing pending will finish.
+            Err(_) => self.pending.clear(),
+        }
+    }
+
+    fn complete(&mut self, written: Written, data: Vec<u8>) {
+        self.pending.remove(&written.path);
+        if data.capacity() <= MAX_REUSED_CAPACITY && self.spare.len() < self.limit {
+            self.spare.push(data);
+        }
+        self.finished.push(written);
+    }
+}
+
+impl Drop for WriterPool {
+    fn drop(&mut self) {
+        self.jobs = None;
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
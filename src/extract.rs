@@ -1,15 +1,735 @@
-use crate::asset::{read_header, Asset, AssetLocation, AssetTable, ASSET_HEADER_SIZE};
-use crate::codec;
+use crate::asset::{
+    read_header, safe_relative_path, sha256_hex, Asset, AssetEncoding, AssetLocation, AssetTable,
+    HeaderLayout,
+};
+use crate::binary::{
+    normalize_ranges, read_only_data_sections, section_ranges, EncryptionInfo, ScanRange,
+};
+use crate::carve;
+use crate::checkpoint::{CheckpointOptions, SavedHeader, ScanCheckpoint};
+use crate::codec::{AssetCodec, CodecRegistry, RawCodec};
 use crate::error::{Error, Result};
+use crate::fingerprint::{tauri_version, TauriVersion};
 use crate::image::BinaryImage;
+use crate::packer;
+use crate::progress::{Progress, ProgressEvent};
+use crate::scan_cache::ScanCache;
+use crate::timings::{timed, Counter, Stage, Timings};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
 
 pub struct AssetScanner;
 
+/// A candidate asset header whose pointers resolve, as
+/// [`AssetScanner::header_candidates`] parsed it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderCandidate {
+    pub offset: usize,
+    /// Name of the [`HeaderLayout`] it was read with.
+    pub layout: String,
+    /// The pointer fields as stored in the file.
+    pub raw_name_pointer: u64,
+    pub raw_data_pointer: u64,
+    /// The pointers after relocations and fixups are applied.
+    pub name_address: u64,
+    pub data_address: u64,
+    /// The file offsets the pointers resolve to.
+    pub name_offset: u64,
+    pub data_offset: u64,
+    pub name_len: u64,
+    pub data_size: u64,
+    /// The name, if its range is in the file.
+    pub name: Option<String>,
+    pub accepted: bool,
+    /// Why the scan would reject it.
+    pub rejection: Option<String>,
+}
+
+/// How many of `assets`, in scan order, a scan stopping at the limits of
+/// `options` would have found.
+fn within_limits(assets: &[Asset], options: &ScanOptions) -> usize {
+    let mut wanted = options
+        .stop_after_paths
+        .iter()
+        .map(String::as_str)
+        .collect::<HashSet<_>>();
+    for (index, asset) in assets.iter().enumerate() {
+        wanted.remove(asset.name());
+        let found = index + 1;
+        if (!options.stop_after_paths.is_empty() && wanted.is_empty())
+            || options.max_assets.is_some_and(|max| found >= max)
+        {
+            return found;
+        }
+    }
+    assets.len()
+}
+
+/// Reports `assets`, found without scanning for them one at a time.
+fn report_found(assets: &[Asset], options: &ScanOptions) {
+    let Some(progress) = &options.progress else {
+        return;
+    };
+    for asset in assets {
+        progress.report(ProgressEvent::AssetFound {
+            name: asset.name().to_string(),
+            offset: asset.location().header_offset,
+            table_index: asset.table_index(),
+        });
+    }
+}
+
+/// The codec that accepted a blob and its decoded size.
+type VerifiedData = (Arc<dyn AssetCodec>, usize);
+
+/// Candidate headers between checks of whether a checkpoint is due.
+const CHECKPOINT_CHECK_EVERY: usize = 4096;
+
+/// The embedding scheme an [`AssetScanner`] looks for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScanProfile {
+    /// Brotli-compressed asset headers emitted by Tauri's codegen. Binaries
+    /// fingerprinted as Tauri 1 are scanned as [`ScanProfile::TauriV1`].
+    #[default]
+    Tauri,
+    /// The asset map of Tauri 1. Data that no codec decodes is taken as
+    /// stored raw, as apps built without Tauri's `compression` feature embed
+    /// it, if it is text or starts with a known file signature.
+    TauriV1,
+    /// `(path, EmbeddedFile)` records emitted by `rust-embed` release builds.
+    RustEmbed,
+}
+
+/// How closely a Tauri asset header has to match what Tauri's codegen emits
+/// before it is accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strictness {
+    /// Accepts any UTF-8 name without control characters, for builds whose
+    /// codegen was patched to mangle asset paths.
+    Lenient,
+    /// Names must be absolute ASCII paths, as Tauri emits them.
+    #[default]
+    Normal,
+    /// Also rejects names that could not be exported, such as paths with
+    /// `..` components or control characters.
+    Strict,
+}
+
+/// Settings for [`AssetScanner::scan_with_options`].
+#[derive(Clone)]
+pub struct ScanOptions {
+    pub profile: ScanProfile,
+    /// Validates Tauri asset data. The `rust-embed` profile stores files raw
+    /// and ignores it.
+    pub codecs: CodecRegistry,
+    pub strictness: Strictness,
+    /// Distance between candidate Tauri header offsets until a header is
+    /// found. Headers of a table are contiguous, so the scan then advances
+    /// one header at a time.
+    pub scan_step: usize,
+    /// Sections to scan, by name, instead of the ones the binary parser
+    /// picks. See [`section_ranges`] for the accepted names.
+    pub sections: Vec<String>,
+    /// Validates Tauri asset data by decoding only its first
+    /// [`QUICK_VERIFY_LIMIT`](crate::codec::QUICK_VERIFY_LIMIT) bytes. Much
+    /// faster on asset-heavy binaries, at a small risk of accepting corrupt
+    /// data, which then fails to decompress at extraction. Larger assets are
+    /// reported with a decompressed size of 0.
+    pub quick_verify: bool,
+    /// Tauri header layouts to try, in order, instead of the profile's.
+    pub header_layouts: Vec<HeaderLayout>,
+    /// Drops assets whose [`Asset::confidence`] is below this.
+    pub min_confidence: u8,
+    /// Records the time spent scanning and validating, and the candidate
+    /// headers tried.
+    pub timings: Option<Arc<Timings>>,
+    /// Saves the progress of Tauri scans to a file, and resumes from it.
+    pub checkpoint: Option<CheckpointOptions>,
+    /// Keeps the headers Tauri scans find, by the binary's hash, and reads
+    /// them back instead of scanning the same binary again.
+    pub cache: Option<ScanCache>,
+    /// Stops the scan once this many assets are found, before
+    /// [`min_confidence`](Self::min_confidence) drops any.
+    pub max_assets: Option<usize>,
+    /// Stops the scan once every asset named here is found. The assets
+    /// found before them are kept too.
+    pub stop_after_paths: Vec<String>,
+    /// Receives the progress of Tauri scans and the assets found.
+    pub progress: Option<Progress>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            profile: ScanProfile::default(),
+            codecs: CodecRegistry::default(),
+            strictness: Strictness::default(),
+            scan_step: 8,
+            sections: Vec::new(),
+            quick_verify: false,
+            header_layouts: Vec::new(),
+            min_confidence: 0,
+            timings: None,
+            checkpoint: None,
+            cache: None,
+            max_assets: None,
+            stop_after_paths: Vec::new(),
+            progress: None,
+        }
+    }
+}
+
 impl AssetScanner {
     pub fn scan(image: &BinaryImage) -> Result<AssetTable> {
+        Self::scan_with_profile(image, ScanProfile::Tauri)
+    }
+
+    pub fn scan_with_profile(image: &BinaryImage, profile: ScanProfile) -> Result<AssetTable> {
+        Self::scan_with_codecs(image, profile, &CodecRegistry::default())
+    }
+
+    /// Scans with `codecs` validating Tauri asset data.
+    ///
+    /// The `rust-embed` profile stores files raw and ignores `codecs`.
+    pub fn scan_with_codecs(
+        image: &BinaryImage,
+        profile: ScanProfile,
+        codecs: &CodecRegistry,
+    ) -> Result<AssetTable> {
+        let options = ScanOptions {
+            profile,
+            codecs: codecs.clone(),
+            ..ScanOptions::default()
+        };
+        Self::scan_with_options(image, &options)
+    }
+
+    pub fn scan_with_options(image: &BinaryImage, options: &ScanOptions) -> Result<AssetTable> {
+        let Some(timings) = options.timings.as_deref() else {
+            return Self::scan_table(image, options);
+        };
+        let started = Instant::now();
+        let validation = timings.elapsed(Stage::Validation);
+        let table = Self::scan_table(image, options);
+        let validating = timings
+            .elapsed(Stage::Validation)
+            .saturating_sub(validation);
+        timings.record(Stage::Scan, started.elapsed().saturating_sub(validating));
+        if let Ok(table) = &table {
+            timings.add(Counter::Accepted, table.len());
+        }
+        table
+    }
+
+    fn scan_table(image: &BinaryImage, options: &ScanOptions) -> Result<AssetTable> {
+        let ranges = Self::scan_ranges(image, options)?;
+        let mut table = Self::scan_in(image, options, ranges.clone())?;
+        if table.is_empty() && options.sections.is_empty() {
+            if let Some(found) = Self::scan_read_only_data(image, options, &ranges)? {
+                table = found;
+            }
+        }
+        if table.is_empty() {
+            if let Some(info) = image.metadata().encryption.filter(|info| info.encrypted) {
+                return Err(Error::Encrypted(info));
+            }
+            if let Some(packer) = packer::detect(image.data()) {
+                return Err(Error::Packed(packer));
+            }
+        }
+        Ok(table)
+    }
+
+    fn scan_in(
+        image: &BinaryImage,
+        options: &ScanOptions,
+        ranges: Vec<ScanRange>,
+    ) -> Result<AssetTable> {
+        let mut table = match Self::tauri_scan(image, options) {
+            Some((options, layouts)) => Self::scan_tauri(image, ranges, &options, &layouts)?,
+            None => {
+                let mut assets = crate::rust_embed::scan(image, ranges)?;
+                assets.truncate(within_limits(&assets, options));
+                report_found(&assets, options);
+                AssetTable::new(image.metadata().clone(), assets)
+            }
+        };
+        table.retain_confident(options.min_confidence);
+        Ok(table)
+    }
+
+    /// Scans the read-only data sections that `scanned` left out, for
+    /// binaries whose linker put the asset table outside the sections the
+    /// parser picks. Returns `None` if they hold no assets either.
+    fn scan_read_only_data(
+        image: &BinaryImage,
+        options: &ScanOptions,
+        scanned: &[ScanRange],
+    ) -> Result<Option<AssetTable>> {
+        let sections = Self::unscanned_read_only_data(image, scanned);
+        if sections.is_empty() {
+            return Ok(None);
+        }
+        let names = |sections: &[&(String, ScanRange)]| {
+            sections
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        log::info!(
+            "no assets in the default sections; scanning {}",
+            names(&sections.iter().collect::<Vec<_>>())
+        );
+        let ranges = normalize_ranges(sections.iter().map(|(_, range)| *range));
+        let table = Self::scan_in(image, options, ranges)?;
+        if table.is_empty() {
+            return Ok(None);
+        }
+        let found = sections
+            .iter()
+            .filter(|(_, range)| {
+                table.assets().iter().any(|asset| {
+                    let header = asset.location().header_offset;
+                    header >= range.start && header - range.start < range.length
+                })
+            })
+            .collect::<Vec<_>>();
+        log::warn!(
+            "no assets in the default sections; found {} in {}",
+            table.len(),
+            names(&found)
+        );
+        Ok(Some(table))
+    }
+
+    /// The read-only data sections `scanned` leaves out, with their names.
+    fn unscanned_read_only_data(
+        image: &BinaryImage,
+        scanned: &[ScanRange],
+    ) -> Vec<(String, ScanRange)> {
+        // Formats added through a parser factory have no sections to list.
+        let Ok(sections) = read_only_data_sections(image.data()) else {
+            return Vec::new();
+        };
+        sections
+            .into_iter()
+            .filter(|(_, range)| !scanned.iter().any(|known| known.start == range.start))
+            .collect()
+    }
+
+    /// Parses every candidate Tauri header whose name and data pointers
+    /// resolve, in each layout the scan tries, whether it is accepted or
+    /// not. A scan keeps one layout per offset and skips over the headers
+    /// of a table; this reports them all, for debugging new layouts.
+    pub fn header_candidates(
+        image: &BinaryImage,
+        options: &ScanOptions,
+    ) -> Result<Vec<HeaderCandidate>> {
+        let mut candidates = Vec::new();
+        Self::scan_with_visitor(image, options, |candidate, _| {
+            candidates.push(candidate.clone());
+        })?;
+        Ok(candidates)
+    }
+
+    /// Calls `visitor` with each candidate Tauri header that
+    /// [`header_candidates`](Self::header_candidates) reports, in file
+    /// order, along with the asset it parses to if the scan would accept
+    /// it. Nothing is collected, so tools can gather statistics over the
+    /// candidates of large binaries or check them with validators of their
+    /// own.
+    pub fn scan_with_visitor<F>(
+        image: &BinaryImage,
+        options: &ScanOptions,
+        mut visitor: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&HeaderCandidate, Option<&Asset>),
+    {
+        let ranges = Self::scan_ranges(image, options)?;
+        let Some((options, layouts)) = Self::tauri_scan(image, options) else {
+            return Err(Error::Message(
+                "header candidates are only read for the Tauri profiles".to_string(),
+            ));
+        };
+        let parser = image.parser();
+        let data = image.data();
+        let mut decompressed_sizes = HashMap::new();
+        for range in ranges {
+            let end = range
+                .start
+                .checked_add(range.length)
+                .filter(|end| *end <= data.len())
+                .ok_or(Error::ScanRangeOutOfBounds)?;
+            let mut offset = range.start;
+            while offset < end {
+                for layout in &layouts {
+                    if offset + layout.size > end {
+                        continue;
+                    }
+                    let Some(header) = read_header(data, offset, layout) else {
+                        continue;
+                    };
+                    let resolved = (|| {
+                        let name_address = parser.read_pointer(data, offset + layout.name_ptr)?;
+                        let data_address = parser.read_pointer(data, offset + layout.data_ptr)?;
+                        Ok::<_, Error>((
+                            name_address,
+                            data_address,
+                            parser.resolve_pointer(name_address)?,
+                            parser.resolve_pointer(data_address)?,
+                        ))
+                    })();
+                    let Ok((name_address, data_address, name_offset, data_offset)) = resolved
+                    else {
+                        continue;
+                    };
+                    let parsed = Self::parse_asset(
+                        image,
+                        offset,
+                        range,
+                        &options,
+                        layout,
+                        &mut decompressed_sizes,
+                    );
+                    let name = usize::try_from(name_offset)
+                        .ok()
+                        .zip(usize::try_from(header.name_len).ok())
+                        .filter(|(_, len)| *len <= 4096)
+                        .and_then(|(start, len)| data.get(start..start.checked_add(len)?))
+                        .map(|name| String::from_utf8_lossy(name).into_owned());
+                    let candidate = HeaderCandidate {
+                        offset,
+                        layout: layout.name.to_string(),
+                        raw_name_pointer: header.name_ptr,
+                        raw_data_pointer: header.data_ptr,
+                        name_address,
+                        data_address,
+                        name_offset,
+                        data_offset,
+                        name_len: header.name_len,
+                        data_size: header.data_size,
+                        name,
+                        accepted: parsed.is_ok(),
+                        rejection: parsed.as_ref().err().map(|error| error.to_string()),
+                    };
+                    visitor(&candidate, parsed.as_ref().ok());
+                }
+                offset += options.scan_step;
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the asset named `name` without scanning the whole table: only
+    /// candidate headers with a name of the same length have their name
+    /// pointer resolved, and only the first whose name matches has its data
+    /// validated. The search stops there, so the asset's table is unknown
+    /// and its [`Asset::table_index`] is 0.
+    ///
+    /// The rust-embed profile scans every record and then looks the name up.
+    /// As in a scan, the other read-only data sections are searched last
+    /// unless [`ScanOptions::sections`] names the sections.
+    pub fn find_asset(
+        image: &BinaryImage,
+        options: &ScanOptions,
+        name: &str,
+    ) -> Result<Option<Asset>> {
+        let mut ranges = Self::scan_ranges(image, options)?;
+        if options.sections.is_empty() {
+            let fallback = Self::unscanned_read_only_data(image, &ranges);
+            ranges = normalize_ranges(
+                ranges
+                    .into_iter()
+                    .chain(fallback.into_iter().map(|(_, range)| range)),
+            );
+        }
+        let Some((options, layouts)) = Self::tauri_scan(image, options) else {
+            let assets = crate::rust_embed::scan(image, ranges)?;
+            return Ok(assets.into_iter().find(|asset| asset.name() == name));
+        };
+        let parser = image.parser();
+        let data = image.data();
+        let mut decompressed_sizes = HashMap::new();
+        for range in ranges {
+            let end = range
+                .start
+                .checked_add(range.length)
+                .filter(|end| *end <= data.len())
+                .ok_or(Error::ScanRangeOutOfBounds)?;
+            let mut offset = range.start;
+            while offset < end {
+                for layout in &layouts {
+                    if offset + layout.size > end {
+                        continue;
+                    }
+                    let Some(header) = read_header(data, offset, layout) else {
+                        continue;
+                    };
+                    if usize::try_from(header.name_len) != Ok(name.len()) {
+                        continue;
+                    }
+                    let named = parser
+                        .read_pointer(data, offset + layout.name_ptr)
+                        .and_then(|pointer| parser.resolve_pointer(pointer))
+                        .ok()
+                        .and_then(|start| usize::try_from(start).ok())
+                        .and_then(|start| data.get(start..start.checked_add(name.len())?))
+                        == Some(name.as_bytes());
+                    if !named {
+                        continue;
+                    }
+                    match Self::parse_asset(
+                        image,
+                        offset,
+                        range,
+                        &options,
+                        layout,
+                        &mut decompressed_sizes,
+                    ) {
+                        Ok(asset) => return Ok(Some(asset)),
+                        Err(err) => log::debug!("rejected asset header at {offset:#x}: {err}"),
+                    }
+                }
+                offset += options.scan_step;
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn scan_ranges(
+        image: &BinaryImage,
+        options: &ScanOptions,
+    ) -> Result<Vec<ScanRange>> {
+        if options.scan_step == 0 {
+            return Err(Error::Message("scan step must not be zero".to_string()));
+        }
+        if options.max_assets == Some(0) {
+            return Err(Error::Message("max assets must not be zero".to_string()));
+        }
+        let ranges = if options.sections.is_empty() {
+            image.parser().scan_ranges()?
+        } else {
+            section_ranges(image.data(), &options.sections)?
+        };
+        let ranges = normalize_ranges(ranges);
+        match image.metadata().encryption.filter(|info| info.encrypted) {
+            Some(info) => Ok(Self::without_encrypted(ranges, &info)),
+            None => Ok(ranges),
+        }
+    }
+
+    /// Drops the FairPlay-encrypted range from `ranges`: its bytes only read
+    /// as assets once decrypted, so scanning them is wasted work that can
+    /// only turn up false headers.
+    fn without_encrypted(ranges: Vec<ScanRange>, info: &EncryptionInfo) -> Vec<ScanRange> {
+        let encrypted = info.range();
+        let mut skipped = 0;
+        let mut kept = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            let end = range.start.saturating_add(range.length);
+            let pieces = [
+                (range.start, end.min(encrypted.start)),
+                (range.start.max(encrypted.end), end),
+            ];
+            let pieces = pieces.into_iter().filter(|(start, end)| start < end);
+            let mut length = 0;
+            for (start, end) in pieces {
+                length += end - start;
+                kept.push(ScanRange {
+                    start,
+                    length: end - start,
+                });
+            }
+            skipped += range.length - length;
+        }
+        if skipped > 0 {
+            log::warn!(
+                "skipping {skipped} bytes of the scan ranges encrypted with FairPlay at {:#x}",
+                info.offset
+            );
+        }
+        kept
+    }
+
+    /// The options and header layouts a Tauri scan of `image` uses, or
+    /// `None` for the rust-embed profile.
+    fn tauri_scan(
+        image: &BinaryImage,
+        options: &ScanOptions,
+    ) -> Option<(ScanOptions, Vec<HeaderLayout>)> {
+        let profile = match options.profile {
+            ScanProfile::Tauri if tauri_version(image.data()) == Some(TauriVersion::V1) => {
+                log::info!("binary looks like Tauri 1; using the tauri-v1 profile");
+                ScanProfile::TauriV1
+            }
+            profile => profile,
+        };
+        let mut options = options.clone();
+        let defaults = match profile {
+            ScanProfile::Tauri => &[HeaderLayout::POINTER_FIRST, HeaderLayout::WITH_CSP_HASHES][..],
+            ScanProfile::TauriV1 => {
+                options.codecs.register(Box::new(RawCodec));
+                &[HeaderLayout::POINTER_FIRST][..]
+            }
+            ScanProfile::RustEmbed => return None,
+        };
+        let layouts = match options.header_layouts.as_slice() {
+            [] => defaults,
+            layouts => layouts,
+        };
+        let layouts = layouts
+            .iter()
+            .map(|layout| match image.parser().pointer_width() {
+                4 => layout.narrow(),
+                _ => *layout,
+            })
+            .collect();
+        Some((options, layouts))
+    }
+
+    /// Scans for asset headers in any of `layouts`. Within a table the layout
+    /// of the previous header is tried first; at the start of a table the
+    /// first layout that also parses the header after this one wins, since a
+    /// short layout can match the leading fields of a longer one.
+    fn scan_tauri(
+        image: &BinaryImage,
+        ranges: Vec<ScanRange>,
+        options: &ScanOptions,
+        layouts: &[HeaderLayout],
+    ) -> Result<AssetTable> {
         let mut assets = Vec::new();
+        let mut seen_headers = HashSet::new();
+        let mut table_index = 0;
+        let mut previous_header: Option<(usize, HeaderLayout)> = None;
+        let mut decompressed_sizes = HashMap::new();
+        let min_header_size = layouts.iter().map(|layout| layout.size).min().unwrap_or(0);
+        let (mut candidates, mut rejected) = (0, 0);
+        let layout_index = |layout: HeaderLayout| {
+            layouts
+                .iter()
+                .position(|known| *known == layout)
+                .unwrap_or(0)
+        };
 
-        for range in image.parser().scan_ranges()? {
+        let sha256 = &image.metadata().sha256;
+        let fingerprint = sha256_hex(
+            format!(
+                "{ranges:?} {layouts:?} {} {:?} {} {:?}",
+                options.scan_step,
+                options.strictness,
+                options.quick_verify,
+                options.codecs.encodings()
+            )
+            .as_bytes(),
+        );
+        let cache_path = options
+            .cache
+            .as_ref()
+            .filter(|cache| image.data().len() >= cache.min_size)
+            .map(|cache| cache.path(sha256, &fingerprint));
+        if let Some(path) = &cache_path {
+            if let Some(cached) = ScanCheckpoint::load(path, sha256, &fingerprint) {
+                match Self::resume(
+                    image,
+                    &ranges,
+                    options,
+                    layouts,
+                    &cached,
+                    &mut decompressed_sizes,
+                ) {
+                    Ok(mut assets) => {
+                        log::info!(
+                            "using the cached scan {} with {} assets",
+                            path.display(),
+                            assets.len()
+                        );
+                        if let Some(cache) = &options.cache {
+                            cache.touch(path);
+                        }
+                        assets.truncate(within_limits(&assets, options));
+                        report_found(&assets, options);
+                        return Ok(AssetTable::new(image.metadata().clone(), assets));
+                    }
+                    Err(error) => log::warn!("ignoring cached scan {}: {error}", path.display()),
+                }
+            }
+        }
+
+        let mut headers = Vec::new();
+        let mut checkpoint = None;
+        let mut resume_at = None;
+        if let Some(checkpointing) = &options.checkpoint {
+            let saved = checkpointing
+                .resume
+                .then(|| ScanCheckpoint::load(&checkpointing.path, sha256, &fingerprint))
+                .flatten();
+            if let Some(saved) = saved {
+                match Self::resume(
+                    image,
+                    &ranges,
+                    options,
+                    layouts,
+                    &saved,
+                    &mut decompressed_sizes,
+                ) {
+                    Ok(resumed) => {
+                        log::info!(
+                            "resuming the scan at {:#x} with {} assets found",
+                            saved.offset,
+                            resumed.len()
+                        );
+                        report_found(&resumed, options);
+                        seen_headers.extend(saved.headers.iter().map(|header| header.offset));
+                        assets = resumed;
+                        headers = saved.headers;
+                        table_index = saved.table_index;
+                        previous_header = saved
+                            .previous_header
+                            .and_then(|(end, layout)| Some((end, *layouts.get(layout)?)));
+                        resume_at = Some((saved.range, saved.offset, saved.step));
+                    }
+                    Err(error) => log::warn!("cannot resume the scan, starting over: {error}"),
+                }
+            }
+            checkpoint = Some((checkpointing, Instant::now()));
+        }
+
+        let mut wanted = options
+            .stop_after_paths
+            .iter()
+            .map(String::as_str)
+            .collect::<HashSet<_>>();
+        for asset in &assets {
+            wanted.remove(asset.name());
+        }
+        let mut stopped = false;
+        let total = ranges.iter().map(|range| range.length).sum::<usize>();
+        let mut ranges_scanned = 0;
+        let mut percent = None;
+        let mut report_scan = |scanned: usize| {
+            let Some(progress) = &options.progress else {
+                return;
+            };
+            let event = ProgressEvent::scan(scanned, total);
+            if let ProgressEvent::Scan { percent: now, .. } = event {
+                if percent.is_some_and(|last| now <= last) {
+                    return;
+                }
+                percent = Some(now);
+            }
+            progress.report(event);
+        };
+        'ranges: for (range_index, &range) in ranges.iter().enumerate() {
+            if resume_at.is_some_and(|(resumed, _, _)| range_index < resumed) {
+                ranges_scanned += range.length;
+                continue;
+            }
             let end = range
                 .start
                 .checked_add(range.length)
@@ -19,33 +739,232 @@ impl AssetScanner {
                 return Err(Error::ScanRangeOutOfBounds);
             }
 
+            log::info!("scanning {:#x}..{:#x} for asset headers", range.start, end);
             let mut offset = range.start;
-            let mut step = 8;
-            while offset + ASSET_HEADER_SIZE <= end {
-                if let Ok(asset) = Self::parse_asset(image, offset, range) {
-                    assets.push(asset);
-                    step = ASSET_HEADER_SIZE;
+            let mut step = options.scan_step;
+            if let Some((_, resumed, resumed_step)) = resume_at.take() {
+                (offset, step) = (resumed, resumed_step);
+            }
+            while offset + min_header_size <= end {
+                if candidates % CHECKPOINT_CHECK_EVERY == 0 {
+                    if let Some((checkpointing, saved_at)) = &mut checkpoint {
+                        if saved_at.elapsed() >= checkpointing.interval {
+                            let state = ScanCheckpoint {
+                                range: range_index,
+                                offset,
+                                step,
+                                table_index,
+                                previous_header: previous_header
+                                    .map(|(end, layout)| (end, layout_index(layout))),
+                                headers: headers.clone(),
+                                ..ScanCheckpoint::new(sha256, &fingerprint)
+                            };
+                            if let Err(error) = state.save(&checkpointing.path) {
+                                log::warn!("cannot save scan checkpoint: {error}");
+                            }
+                            *saved_at = Instant::now();
+                        }
+                    }
+                }
+                candidates += 1;
+                report_scan(ranges_scanned + (offset - range.start));
+                let continued = previous_header
+                    .filter(|(header_end, _)| *header_end == offset)
+                    .map(|(_, layout)| layout);
+                let mut parse = |offset: usize, layout: &HeaderLayout| {
+                    Self::parse_asset(
+                        image,
+                        offset,
+                        range,
+                        options,
+                        layout,
+                        &mut decompressed_sizes,
+                    )
+                };
+                let mut parsed = Err(Error::InvalidAssetHeader {
+                    offset,
+                    reason: "no header layout matches".to_string(),
+                });
+                if let Some(layout) = continued {
+                    parsed = parse(offset, &layout).map(|asset| (asset, layout));
+                }
+                if parsed.is_err() {
+                    let mut first = None;
+                    for layout in layouts {
+                        if offset + layout.size > end {
+                            continue;
+                        }
+                        match parse(offset, layout) {
+                            Ok(asset) => {
+                                if parse(offset + layout.size, layout).is_ok() {
+                                    first = Some((asset, *layout));
+                                    break;
+                                }
+                                first.get_or_insert((asset, *layout));
+                            }
+                            Err(err) if first.is_none() => parsed = Err(err),
+                            Err(_) => {}
+                        }
+                    }
+                    if let Some(found) = first {
+                        parsed = Ok(found);
+                    }
+                }
+                match parsed {
+                    Ok((mut asset, layout)) => {
+                        // Headers of one table are contiguous; any gap starts a new table.
+                        if previous_header.is_some_and(|(end, _)| end != offset) {
+                            table_index += 1;
+                        }
+                        previous_header = Some((offset + layout.size, layout));
+                        if seen_headers.insert(offset) {
+                            log::debug!(
+                                "asset {} at {offset:#x}: {} -> {} bytes, table {table_index}",
+                                asset.name(),
+                                asset.compressed_size(),
+                                asset.decompressed_size()
+                            );
+                            asset.set_table_index(table_index);
+                            if let Some(progress) = &options.progress {
+                                progress.report(ProgressEvent::AssetFound {
+                                    name: asset.name().to_string(),
+                                    offset,
+                                    table_index,
+                                });
+                            }
+                            wanted.remove(asset.name());
+                            assets.push(asset);
+                            headers.push(SavedHeader {
+                                offset,
+                                layout: layout_index(layout),
+                                table_index,
+                            });
+                            let paths_found =
+                                !options.stop_after_paths.is_empty() && wanted.is_empty();
+                            if paths_found
+                                || options.max_assets.is_some_and(|max| assets.len() >= max)
+                            {
+                                log::info!("stopping the scan after {} assets", assets.len());
+                                stopped = true;
+                                break 'ranges;
+                            }
+                        }
+                        step = layout.size;
+                    }
+                    // Most offsets do not hold pointers at all; only report
+                    // candidates whose pointers resolved at the default levels.
+                    Err(
+                        err @ (Error::AddressNotMapped(_)
+                        | Error::PointerOutOfBounds
+                        | Error::InvalidAssetHeader { .. }),
+                    ) => log::trace!("no asset header at {offset:#x}: {err}"),
+                    Err(err) => {
+                        rejected += 1;
+                        log::debug!("rejected asset header at {offset:#x}: {err}");
+                    }
                 }
                 offset += step;
             }
+            ranges_scanned += range.length;
+        }
+        report_scan(total);
+        log::info!("found {} assets", assets.len());
+        if let Some((checkpointing, _)) = &checkpoint {
+            ScanCheckpoint::clear(&checkpointing.path);
+        }
+        // The headers of a scan stopped early are not all of them.
+        if let (Some(path), Some(last), false) = (&cache_path, ranges.last(), stopped) {
+            let cached = ScanCheckpoint {
+                range: ranges.len() - 1,
+                offset: last.start + last.length,
+                step: options.scan_step,
+                table_index,
+                previous_header: None,
+                headers,
+                ..ScanCheckpoint::new(sha256, &fingerprint)
+            };
+            match cached.save(path) {
+                Ok(()) => {
+                    if let Some(cache) = &options.cache {
+                        cache.evict();
+                    }
+                }
+                Err(error) => log::warn!("cannot cache the scan in {}: {error}", path.display()),
+            }
+        }
+        if let Some(timings) = &options.timings {
+            timings.add(Counter::Candidates, candidates);
+            timings.add(Counter::Rejected, rejected);
         }
 
         Ok(AssetTable::new(image.metadata().clone(), assets))
     }
 
+    /// Parses again the headers a checkpoint saved.
+    fn resume(
+        image: &BinaryImage,
+        ranges: &[ScanRange],
+        options: &ScanOptions,
+        layouts: &[HeaderLayout],
+        saved: &ScanCheckpoint,
+        decompressed_sizes: &mut HashMap<(usize, usize), VerifiedData>,
+    ) -> Result<Vec<Asset>> {
+        let invalid = |reason: &str| Error::Message(format!("invalid scan checkpoint: {reason}"));
+        let Some(range) = ranges.get(saved.range) else {
+            return Err(invalid("scan range is out of bounds"));
+        };
+        if saved.step == 0
+            || saved.offset < range.start
+            || saved.offset > range.start.saturating_add(range.length)
+        {
+            return Err(invalid("offset is outside its scan range"));
+        }
+        let mut assets = Vec::with_capacity(saved.headers.len());
+        for header in &saved.headers {
+            let layout = layouts
+                .get(header.layout)
+                .ok_or_else(|| invalid("unknown header layout"))?;
+            let range = ranges
+                .iter()
+                .copied()
+                .find(|range| {
+                    (range.start..range.start.saturating_add(range.length)).contains(&header.offset)
+                })
+                .ok_or_else(|| invalid("header is outside the scan ranges"))?;
+            let mut asset = Self::parse_asset(
+                image,
+                header.offset,
+                range,
+                options,
+                layout,
+                decompressed_sizes,
+            )?;
+            asset.set_table_index(header.table_index);
+            assets.push(asset);
+        }
+        Ok(assets)
+    }
+
     fn parse_asset(
         image: &BinaryImage,
         offset: usize,
-        range: crate::binary::ScanRange,
+        range: ScanRange,
+        options: &ScanOptions,
+        layout: &HeaderLayout,
+        decompressed_sizes: &mut HashMap<(usize, usize), VerifiedData>,
     ) -> Result<Asset> {
         let header =
-            read_header(image.data(), offset).ok_or_else(|| Error::InvalidAssetHeader {
+            read_header(image.data(), offset, layout).ok_or_else(|| Error::InvalidAssetHeader {
                 offset,
                 reason: "header is out of bounds".to_string(),
             })?;
 
-        let name_ptr = image.parser().read_pointer(image.data(), offset)?;
-        let data_ptr = image.parser().read_pointer(image.data(), offset + 16)?;
+        let name_ptr = image
+            .parser()
+            .read_pointer(image.data(), offset + layout.name_ptr)?;
+        let data_ptr = image
+            .parser()
+            .read_pointer(image.data(), offset + layout.data_ptr)?;
         let name_offset = image.parser().resolve_pointer(name_ptr)? as usize;
         let data_offset = image.parser().resolve_pointer(data_ptr)? as usize;
         let name_len = usize::try_from(header.name_len).map_err(|_| Error::InvalidAssetHeader {
@@ -103,30 +1022,79 @@ impl AssetScanner {
                     reason: "data range is outside the file".to_string(),
                 })?;
 
-        if name_bytes.first() != Some(&b'/') || !name_bytes.iter().all(u8::is_ascii) {
+        let name = String::from_utf8(name_bytes.to_vec()).map_err(|_| Error::InvalidAssetName)?;
+        if !is_plausible_name(&name, options.strictness) {
             return Err(Error::InvalidAssetName);
         }
-
-        let name = String::from_utf8(name_bytes.to_vec()).map_err(|_| Error::InvalidAssetName)?;
-        let decompressed = codec::decompress(compressed)?;
+        // Aliased headers share one blob; validate and decompress it only once.
+        let (codec, decompressed_size) = match decompressed_sizes.get(&(data_offset, data_size)) {
+            Some((codec, size)) => (Arc::clone(codec), *size),
+            None => {
+                let timings = options.timings.as_deref();
+                if let Some(timings) = timings {
+                    timings.add(Counter::Validated, 1);
+                }
+                let verified = timed(timings, Stage::Validation, || {
+                    if options.quick_verify {
+                        let (codec, size) = options.codecs.verify_quick(compressed)?;
+                        Ok::<_, Error>((codec, size.unwrap_or(0)))
+                    } else {
+                        options.codecs.verify(compressed)
+                    }
+                })?;
+                // Raw accepts any bytes; keep only data that looks like an asset.
+                if verified.0.encoding() == AssetEncoding::Raw
+                    && !carve::plausible_content(compressed)
+                {
+                    return Err(Error::InvalidAssetHeader {
+                        offset,
+                        reason: "raw data is neither text nor a known file format".to_string(),
+                    });
+                }
+                decompressed_sizes.insert((data_offset, data_size), verified.clone());
+                verified
+            }
+        };
         let location = AssetLocation {
             header_offset: offset,
             name_offset,
             data_offset,
-            data_size_offset: offset + 24,
+            data_size_offset: offset + layout.data_size,
             original_compressed_size: data_size,
             scan_range: range,
+            table_index: 0,
+            content_hash_offset: None,
+            word_size: layout.word,
         };
 
-        Ok(Asset::new(
-            name,
-            compressed.to_vec(),
-            decompressed.len(),
-            location,
-        ))
+        Ok(Asset::new(name, compressed.to_vec(), decompressed_size, location).with_codec(codec))
+    }
+}
+
+fn is_plausible_name(name: &str, strictness: Strictness) -> bool {
+    // A NUL cannot be part of a path, and would cut the name short in C.
+    let tauri_path = name.starts_with('/') && name.is_ascii() && !name.contains('\0');
+    let printable = !name.chars().any(char::is_control);
+    match strictness {
+        Strictness::Lenient => printable,
+        Strictness::Normal => tauri_path,
+        Strictness::Strict => tauri_path && printable && safe_relative_path(name).is_some(),
     }
 }
 
 pub fn decompress_asset(asset: &Asset) -> Result<Vec<u8>> {
-    codec::decompress(asset.compressed_data())
+    asset.codec().decompress(asset.compressed_data())
+}
+
+/// Decodes `asset` into `output`, which must be exactly its decompressed
+/// size.
+pub fn decompress_asset_into(asset: &Asset, output: &mut [u8]) -> Result<()> {
+    asset
+        .codec()
+        .decompress_into(asset.compressed_data(), output)
+}
+
+/// Encodes replacement content the way `asset` is stored in the binary.
+pub fn encode_asset(asset: &Asset, content: &[u8]) -> Result<Vec<u8>> {
+    asset.codec().compress(content)
 }
@@ -0,0 +1,120 @@
+//! Tauri cargo features the binary appears to be built with.
+//!
+//! Features leave code behind rather than a list, so each is recognized by
+//! strings only its code contains: the command and scripts that toggle the
+//! devtools, the events of the v1 updater, the isolation hook, or the crates
+//! and plugins a feature pulls in. Debug builds compile the devtools in
+//! without the feature, which makes no difference to whoever can open them.
+
+use crate::fingerprint::CrateVersion;
+use crate::plugins::TauriPlugin;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TauriFeature {
+    /// The webview's developer tools can be opened.
+    Devtools,
+    /// The system tray API, `system-tray` in Tauri 1.
+    TrayIcon,
+    /// The updater, built in to Tauri 1 and a plugin in Tauri 2.
+    Updater,
+    /// The isolation pattern, which checks IPC messages in a sandboxed
+    /// iframe.
+    Isolation,
+    /// The `asset:` protocol, which serves files from the file system.
+    ProtocolAsset,
+    /// Private macOS APIs, used for transparent windows.
+    MacosPrivateApi,
+}
+
+impl TauriFeature {
+    /// The name of the cargo feature.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Devtools => "devtools",
+            Self::TrayIcon => "tray-icon",
+            Self::Updater => "updater",
+            Self::Isolation => "isolation",
+            Self::ProtocolAsset => "protocol-asset",
+            Self::MacosPrivateApi => "macos-private-api",
+        }
+    }
+}
+
+/// Strings only the code of each feature contains.
+const MARKERS: &[(TauriFeature, &str)] = &[
+    // The webview plugin's command, and the Tauri 1 command, bound to the
+    // devtools shortcut.
+    (TauriFeature::Devtools, "internal_toggle_devtools"),
+    (TauriFeature::Devtools, "__toggleDevtools"),
+    // The WKWebView preference `wry` sets for the devtools.
+    (TauriFeature::Devtools, "developerExtrasEnabled"),
+    (TauriFeature::TrayIcon, "libayatana-appindicator3.so"),
+    (TauriFeature::TrayIcon, "libappindicator3.so"),
+    (TauriFeature::Updater, "tauri://update-available"),
+    (TauriFeature::Isolation, "__TAURI_ISOLATION_HOOK__"),
+    (
+        TauriFeature::ProtocolAsset,
+        "asset protocol not configured to allow the path",
+    ),
+    (TauriFeature::MacosPrivateApi, "drawsBackground"),
+];
+
+/// Crates each feature pulls in.
+const CRATES: &[(TauriFeature, &str)] = &[(TauriFeature::TrayIcon, "tray-icon")];
+
+/// Plugins that provide a feature.
+const PLUGINS: &[(TauriFeature, &str)] = &[(TauriFeature::Updater, "updater")];
+
+/// A feature and what shows it is built in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureEvidence {
+    pub feature: TauriFeature,
+    /// The strings, crates and plugins found, such as
+    /// `internal_toggle_devtools` or `tauri-plugin-updater`.
+    pub evidence: Vec<String>,
+}
+
+/// Finds the features of the binary in `data`, sorted by feature, from its
+/// strings and the `plugins` and `crates` already found in it.
+pub fn find_features(
+    data: &[u8],
+    plugins: &[TauriPlugin],
+    crates: &[CrateVersion],
+) -> Vec<FeatureEvidence> {
+    let mut found = Vec::<FeatureEvidence>::new();
+    let mut add = |feature: TauriFeature, evidence: String| match found
+        .iter_mut()
+        .find(|found| found.feature == feature)
+    {
+        Some(found) => found.evidence.push(evidence),
+        None => found.push(FeatureEvidence {
+            feature,
+            evidence: vec![evidence],
+        }),
+    };
+    for &(feature, marker) in MARKERS {
+        if find(data, marker.as_bytes()).is_some() {
+            add(feature, marker.to_string());
+        }
+    }
+    for &(feature, name) in CRATES {
+        if let Some(found) = crates.iter().find(|found| found.name == name) {
+            add(feature, format!("{}-{}", found.name, found.version));
+        }
+    }
+    for &(feature, name) in PLUGINS {
+        if plugins.iter().any(|plugin| plugin.name == name) {
+            add(feature, format!("tauri-plugin-{name}"));
+        }
+    }
+    found.sort_by_key(|found| found.feature);
+    found
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
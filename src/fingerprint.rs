@@ -0,0 +1,221 @@
+//! Telling Tauri major versions, crates and the Rust toolchain apart.
+//!
+//! Panic locations keep the path of the `tauri` crate's sources, such as
+//! `.cargo/registry/src/…/tauri-1.5.4/src/app.rs`, which names the version
+//! the app was built with. Binaries without them are told apart by the
+//! globals each version's injected scripts define. The same paths name every
+//! other dependency that can panic, and those of the standard library,
+//! `/rustc/<commit>/library/std/src/…`, name the compiler's commit.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Major version of Tauri a binary was built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TauriVersion {
+    V1,
+    V2,
+}
+
+/// Globals only one major version's scripts define.
+const V1_MARKERS: &[&[u8]] = &[b"__TAURI_IPC__", b"__TAURI_METADATA__"];
+const V2_MARKERS: &[&[u8]] = &[b"__TAURI_INTERNALS__"];
+
+/// Guesses the Tauri major version of the binary in `data`.
+pub fn tauri_version(data: &[u8]) -> Option<TauriVersion> {
+    let v1 = count_crate_paths(data, b"tauri-1.");
+    let v2 = count_crate_paths(data, b"tauri-2.");
+    if v1 != v2 {
+        return Some(if v1 > v2 {
+            TauriVersion::V1
+        } else {
+            TauriVersion::V2
+        });
+    }
+    let has = |markers: &[&[u8]]| markers.iter().any(|marker| find(data, marker).is_some());
+    match (has(V1_MARKERS), has(V2_MARKERS)) {
+        (true, false) => Some(TauriVersion::V1),
+        (false, true) => Some(TauriVersion::V2),
+        _ => None,
+    }
+}
+
+/// A crate and the version it was built from.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrateVersion {
+    pub name: String,
+    pub version: String,
+}
+
+/// Reads the version of crate `name` from the registry paths in `data`,
+/// such as `/wry-0.37.0/src/lib.rs`. The most common version wins when the
+/// paths differ.
+pub fn crate_version(data: &[u8], name: &str) -> Option<String> {
+    let mut prefix = name.as_bytes().to_vec();
+    prefix.push(b'-');
+    let mut versions = BTreeMap::<String, usize>::new();
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], &prefix) {
+        let at = search + found;
+        search = at + prefix.len();
+        if at == 0 || !matches!(data[at - 1], b'/' | b'\\') {
+            continue;
+        }
+        let rest = &data[search..];
+        let length = rest
+            .iter()
+            .take_while(|byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-' | b'+'))
+            .count();
+        let terminated = matches!(rest.get(length), Some(b'/' | b'\\'));
+        if terminated && rest.first().is_some_and(u8::is_ascii_digit) {
+            let version = String::from_utf8_lossy(&rest[..length]).into_owned();
+            *versions.entry(version).or_default() += 1;
+        }
+    }
+    versions
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(version, _)| version)
+}
+
+/// The Rust compiler a binary was built with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    /// The version, such as `1.75.0`, from the `rustc version` string ELF
+    /// linkers keep in `.comment`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The compiler's commit hash, from standard library paths, or the
+    /// short hash of the version string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+const RUSTC_VERSION_PREFIX: &[u8] = b"rustc version ";
+const RUSTC_PATH_PREFIX: &[u8] = b"/rustc/";
+const COMMIT_HASH_LEN: usize = 40;
+
+/// Finds the Rust compiler version and commit in `data`.
+pub fn toolchain(data: &[u8]) -> Option<Toolchain> {
+    let mut toolchain = Toolchain::default();
+    let mut commits = BTreeMap::<&[u8], usize>::new();
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], RUSTC_PATH_PREFIX) {
+        let start = search + found + RUSTC_PATH_PREFIX.len();
+        search = start;
+        let Some(hash) = data.get(start..start + COMMIT_HASH_LEN) else {
+            break;
+        };
+        let hex = hash.iter().all(u8::is_ascii_hexdigit);
+        if hex && data.get(start + COMMIT_HASH_LEN) == Some(&b'/') {
+            *commits.entry(hash).or_default() += 1;
+        }
+    }
+    toolchain.commit = commits
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(hash, _)| String::from_utf8_lossy(hash).into_owned());
+
+    // `rustc version 1.75.0 (82e1608df 2023-12-21)`
+    if let Some(found) = find(data, RUSTC_VERSION_PREFIX) {
+        let rest = &data[found + RUSTC_VERSION_PREFIX.len()..];
+        let line = rest
+            .iter()
+            .take(96)
+            .take_while(|byte| byte.is_ascii_graphic() || **byte == b' ')
+            .count();
+        let line = String::from_utf8_lossy(&rest[..line]);
+        let mut words = line.split_ascii_whitespace();
+        toolchain.version = words
+            .next()
+            .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+            .map(str::to_string);
+        if toolchain.commit.is_none() {
+            toolchain.commit = words
+                .next()
+                .and_then(|hash| hash.strip_prefix('('))
+                .filter(|hash| {
+                    !hash.is_empty() && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+                })
+                .map(str::to_string);
+        }
+    }
+    (toolchain != Toolchain::default()).then_some(toolchain)
+}
+
+/// Lists the crates whose registry paths are in `data`, such as
+/// `.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde-1.0.195/`,
+/// sorted by name and version.
+pub fn crate_versions(data: &[u8]) -> Vec<CrateVersion> {
+    let mut crates = BTreeSet::new();
+    for marker in [&b"registry/src/"[..], b"registry\\src\\"] {
+        let mut search = 0;
+        while let Some(found) = find(&data[search..], marker) {
+            let start = search + found + marker.len();
+            search = start;
+            let rest = &data[start..];
+            // Skip the registry index directory, then read the crate's.
+            let Some(index) = rest
+                .iter()
+                .take(128)
+                .position(|&byte| matches!(byte, b'/' | b'\\'))
+            else {
+                continue;
+            };
+            let dir = &rest[index + 1..];
+            let length = dir
+                .iter()
+                .take(128)
+                .take_while(|byte| {
+                    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'+')
+                })
+                .count();
+            let terminated = matches!(dir.get(length), Some(b'/' | b'\\'));
+            if let Some(version) = terminated
+                .then(|| split_crate_dir(&dir[..length]))
+                .flatten()
+            {
+                crates.insert(version);
+            }
+        }
+    }
+    crates.into_iter().collect()
+}
+
+/// Splits `serde-1.0.195` at the first `-` followed by a version.
+fn split_crate_dir(dir: &[u8]) -> Option<CrateVersion> {
+    let dir = std::str::from_utf8(dir).ok()?;
+    let (index, _) = dir.match_indices('-').find(|(index, _)| {
+        let version = &dir[index + 1..];
+        version.starts_with(|c: char| c.is_ascii_digit()) && version.contains('.')
+    })?;
+    Some(CrateVersion {
+        name: dir[..index].to_string(),
+        version: dir[index + 1..].to_string(),
+    })
+    .filter(|version| !version.name.is_empty())
+}
+
+/// Counts occurrences of `prefix` that start a directory name and are
+/// followed by a digit, such as `/tauri-1.5.4/`.
+fn count_crate_paths(data: &[u8], prefix: &[u8]) -> usize {
+    let mut count = 0;
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], prefix) {
+        let at = search + found;
+        search = at + prefix.len();
+        let after_separator = at > 0 && matches!(data[at - 1], b'/' | b'\\');
+        let versioned = data.get(search).is_some_and(u8::is_ascii_digit);
+        if after_separator && versioned {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Finds `needle` with `memmem`'s vectorized search, since these searches
+/// run over the whole binary on every scan.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
@@ -0,0 +1,262 @@
+//! Frontend frameworks and bundlers recognized in the embedded assets.
+//!
+//! Minified production bundles still carry globals, runtime helpers and
+//! license banners that identify the libraries they were built from, and
+//! some frameworks emit assets under fixed directories. Knowing that an app
+//! is a Vite-built Vue app or a webpack-built React app tells a reviewer
+//! which source map, decompiler or devtools to reach for next.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::mime::is_text_asset;
+use serde::{Deserialize, Serialize};
+
+/// Evidence kept per detection; further matches only confirm it.
+const MAX_EVIDENCE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    Framework,
+    Bundler,
+}
+
+struct Signature {
+    name: &'static str,
+    kind: ToolKind,
+    /// Strings found in bundles built with the tool.
+    markers: &'static [&'static str],
+    /// Asset name prefixes the tool emits.
+    paths: &'static [&'static str],
+    /// Strings followed by the tool's version.
+    versions: &'static [&'static str],
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "Next.js",
+        kind: ToolKind::Framework,
+        markers: &["__NEXT_DATA__", "__next_f", "next-route-announcer"],
+        paths: &["/_next/"],
+        versions: &["next={version:\"", "window.next = {\n    version: \""],
+    },
+    Signature {
+        name: "React",
+        kind: ToolKind::Framework,
+        markers: &[
+            "__SECRET_INTERNALS_DO_NOT_USE_OR_YOU_WILL_BE_FIRED",
+            "__REACT_DEVTOOLS_GLOBAL_HOOK__",
+            "react.production.min.js",
+        ],
+        paths: &[],
+        versions: &["reconcilerVersion:\"", "React v"],
+    },
+    Signature {
+        name: "Vue",
+        kind: ToolKind::Framework,
+        markers: &["__VUE__", "__vue_app__", "__VUE_OPTIONS_API__", "__vccOpts"],
+        paths: &[],
+        versions: &[
+            "@vue/runtime-dom v",
+            "@vue/shared v",
+            "Vue.js v",
+            "Vue.version = '",
+        ],
+    },
+    Signature {
+        name: "SvelteKit",
+        kind: ToolKind::Framework,
+        markers: &["__sveltekit_"],
+        paths: &["/_app/immutable/"],
+        versions: &[],
+    },
+    Signature {
+        name: "Svelte",
+        kind: ToolKind::Framework,
+        markers: &["__svelte", "svelte-hmr", "$$props"],
+        paths: &[],
+        versions: &[".v.add(\""],
+    },
+    Signature {
+        name: "Angular",
+        kind: ToolKind::Framework,
+        markers: &["ng-version", "ɵcmp", "ngDevMode"],
+        paths: &[],
+        versions: &["ng-version=\""],
+    },
+    Signature {
+        name: "Solid",
+        kind: ToolKind::Framework,
+        markers: &["_$HY", "solid-js"],
+        paths: &[],
+        versions: &[],
+    },
+    Signature {
+        name: "Vite",
+        kind: ToolKind::Bundler,
+        markers: &[
+            "__vite__mapDeps",
+            "__vitePreload",
+            "vite/modulepreload-polyfill",
+        ],
+        paths: &[],
+        versions: &[],
+    },
+    Signature {
+        name: "webpack",
+        kind: ToolKind::Bundler,
+        markers: &["webpackChunk", "__webpack_require__", "webpackJsonp"],
+        paths: &[],
+        versions: &[],
+    },
+    Signature {
+        name: "Parcel",
+        kind: ToolKind::Bundler,
+        markers: &["parcelRequire"],
+        paths: &[],
+        versions: &[],
+    },
+];
+
+/// Where a detection came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Evidence {
+    pub asset: String,
+    /// The string found in the asset, or the directory it is stored under.
+    pub marker: String,
+}
+
+/// A framework or bundler the assets were built with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Detection {
+    pub name: String,
+    pub kind: ToolKind,
+    /// The version found in the bundles, if any. Bundlers that record none
+    /// may still imply a major version, as webpack's chunk globals do.
+    pub version: Option<String>,
+    pub evidence: Vec<Evidence>,
+}
+
+/// The frameworks and bundlers found by [`detect`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrontendReport {
+    pub frameworks: Vec<Detection>,
+    pub bundlers: Vec<Detection>,
+}
+
+impl FrontendReport {
+    pub fn is_empty(&self) -> bool {
+        self.frameworks.is_empty() && self.bundlers.is_empty()
+    }
+}
+
+/// Detects the frameworks and bundlers used to build the assets of `table`.
+pub fn detect(table: &AssetTable) -> Result<FrontendReport> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() {
+            continue;
+        }
+        let data = if is_text_asset(asset.name()) {
+            decompress_asset(asset)?
+        } else {
+            Vec::new()
+        };
+        assets.push((asset.name().to_string(), data));
+    }
+    Ok(detect_in(
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`detect`], for decoded assets given as name and content.
+pub fn detect_in<'a>(assets: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> FrontendReport {
+    let mut detections: Vec<Option<Detection>> = SIGNATURES.iter().map(|_| None).collect();
+    for (name, data) in assets {
+        let searched = is_text_asset(name);
+        for (signature, detection) in SIGNATURES.iter().zip(&mut detections) {
+            let path = signature
+                .paths
+                .iter()
+                .find(|prefix| name.starts_with(*prefix));
+            let marker = signature
+                .markers
+                .iter()
+                .find(|marker| searched && find(data, marker.as_bytes()).is_some());
+            let Some(marker) = path.or(marker) else {
+                continue;
+            };
+            let detection = detection.get_or_insert_with(|| Detection {
+                name: signature.name.to_string(),
+                kind: signature.kind,
+                version: None,
+                evidence: Vec::new(),
+            });
+            if detection.evidence.len() < MAX_EVIDENCE {
+                detection.evidence.push(Evidence {
+                    asset: name.to_string(),
+                    marker: marker.to_string(),
+                });
+            }
+            if detection.version.is_none() && searched {
+                detection.version = signature
+                    .versions
+                    .iter()
+                    .find_map(|prefix| version_after(data, prefix.as_bytes()));
+            }
+        }
+    }
+
+    let mut report = FrontendReport::default();
+    for mut detection in detections.into_iter().flatten() {
+        if detection.name == "webpack" && detection.version.is_none() {
+            detection.version = webpack_major(&detection.evidence);
+        }
+        match detection.kind {
+            ToolKind::Framework => report.frameworks.push(detection),
+            ToolKind::Bundler => report.bundlers.push(detection),
+        }
+    }
+    report
+}
+
+/// webpack 5 names its chunk global `webpackChunk<name>`, while webpack 4
+/// and earlier used `webpackJsonp`.
+fn webpack_major(evidence: &[Evidence]) -> Option<String> {
+    evidence
+        .iter()
+        .find_map(|evidence| match evidence.marker.as_str() {
+            "webpackChunk" => Some("5".to_string()),
+            "webpackJsonp" => Some("4".to_string()),
+            _ => None,
+        })
+}
+
+/// Reads a version such as `18.2.0` or `3.4.0-beta.1` right after the first
+/// `prefix` in `data` that is followed by one.
+fn version_after(data: &[u8], prefix: &[u8]) -> Option<String> {
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], prefix) {
+        let start = search + found + prefix.len();
+        search = start;
+        let version = data[start..]
+            .iter()
+            .take_while(|&&byte| byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'-'))
+            .take(32)
+            .copied()
+            .collect::<Vec<_>>();
+        if version.first().is_some_and(u8::is_ascii_digit) {
+            return String::from_utf8(version).ok();
+        }
+    }
+    None
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,309 @@
+//! Which assets reference which.
+//!
+//! References are read from HTML attributes such as `src`, `href` and
+//! `srcset`, from `url()` in stylesheets and inline styles, from
+//! `sourceMappingURL` comments, and from string literals in scripts that
+//! name a file, such as `import("./About-1b2c.js")` or the
+//! `"assets/logo-3f4a.svg"` entries bundlers emit. Chunk URLs a bundle
+//! assembles at runtime, as webpack does from chunk ids, cannot be followed,
+//! so their chunks show up as orphans.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::html::{attribute, resolve_reference, tags};
+use crate::mime::is_text_asset;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Extensions a string literal must end with to be taken as a reference.
+const REFERENCED_EXTENSIONS: &[&str] = &[
+    "js",
+    "mjs",
+    "cjs",
+    "css",
+    "html",
+    "htm",
+    "json",
+    "map",
+    "wasm",
+    "png",
+    "jpg",
+    "jpeg",
+    "gif",
+    "svg",
+    "webp",
+    "avif",
+    "ico",
+    "bmp",
+    "woff",
+    "woff2",
+    "ttf",
+    "otf",
+    "eot",
+    "mp3",
+    "mp4",
+    "webm",
+    "wav",
+    "ogg",
+    "txt",
+    "xml",
+    "webmanifest",
+];
+
+/// HTML tags and the attributes that reference other files.
+const HTML_REFERENCES: &[(&[u8], &[&[u8]])] = &[
+    (b"<script", &[b"src"]),
+    (b"<link", &[b"href"]),
+    (b"<img", &[b"src", b"srcset"]),
+    (b"<source", &[b"src", b"srcset"]),
+    (b"<video", &[b"src", b"poster"]),
+    (b"<audio", &[b"src"]),
+    (b"<iframe", &[b"src"]),
+    (b"<a", &[b"href"]),
+    (b"<use", &[b"href", b"xlink:href"]),
+    (b"<image", &[b"href", b"xlink:href"]),
+    (b"<object", &[b"data"]),
+    (b"<embed", &[b"src"]),
+];
+
+const SOURCE_MAP_COMMENT: &[u8] = b"sourceMappingURL=";
+const URL_FUNCTION: &[u8] = b"url(";
+/// Longest string literal taken as a file reference.
+const MAX_LITERAL_LEN: usize = 256;
+
+/// Assets that are requested by name rather than referenced.
+const ROOT_FILES: &[&str] = &["/favicon.ico", "/robots.txt"];
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Reference {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetGraph {
+    /// References between assets, sorted by referencing asset.
+    pub references: Vec<Reference>,
+    /// References to files that are not among the assets.
+    pub missing: Vec<Reference>,
+    /// Assets nothing references, other than HTML pages and files such as
+    /// `/favicon.ico` that are requested by name.
+    pub orphans: Vec<String>,
+}
+
+impl AssetGraph {
+    /// The assets that reference `name`.
+    pub fn referenced_by<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.references
+            .iter()
+            .filter(move |reference| reference.to == name)
+            .map(|reference| reference.from.as_str())
+    }
+}
+
+/// Builds the reference graph of the assets in `table`.
+pub fn asset_graph(table: &AssetTable) -> Result<AssetGraph> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        let data = if asset.alias_of().is_none() && is_text_asset(asset.name()) {
+            decompress_asset(asset)?
+        } else {
+            Vec::new()
+        };
+        assets.push((asset.name().to_string(), data));
+    }
+    Ok(asset_graph_in(
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`asset_graph`], for decoded assets given as name and content.
+pub fn asset_graph_in<'a>(assets: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> AssetGraph {
+    let assets = assets.into_iter().collect::<Vec<_>>();
+    let names = assets
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<BTreeSet<_>>();
+
+    let mut references = BTreeSet::new();
+    let mut missing = BTreeSet::new();
+    for &(name, data) in &assets {
+        if !is_text_asset(name) {
+            continue;
+        }
+        for (specifier, explicit) in specifiers(name, data) {
+            // Bundlers write some paths relative to the site root rather
+            // than to the file, without a leading `./`.
+            let resolved = resolve_reference(name, &specifier);
+            let from_root = (!explicit)
+                .then(|| resolve_reference("/", &specifier))
+                .flatten();
+            let found = [&resolved, &from_root]
+                .into_iter()
+                .flatten()
+                .find(|target| names.contains(target.as_str()));
+            let reference = |to: &String| Reference {
+                from: name.to_string(),
+                to: to.clone(),
+            };
+            match (found, &resolved) {
+                (Some(target), _) if target != name => {
+                    references.insert(reference(target));
+                }
+                (Some(_), _) => {}
+                (None, Some(target))
+                    if explicit && has_extension(target, REFERENCED_EXTENSIONS) =>
+                {
+                    missing.insert(reference(target));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let referenced = references
+        .iter()
+        .map(|reference: &Reference| reference.to.as_str())
+        .collect::<BTreeSet<_>>();
+    let orphans = names
+        .iter()
+        .filter(|name| !referenced.contains(*name))
+        .filter(|name| !has_extension(name, &["html", "htm"]) && !ROOT_FILES.contains(name))
+        .map(|name| name.to_string())
+        .collect();
+    AssetGraph {
+        references: references.into_iter().collect(),
+        missing: missing.into_iter().collect(),
+        orphans,
+    }
+}
+
+/// Reads the file references in asset `name`, each with whether it is
+/// explicit, as an attribute or `url()` is, or a string literal that only
+/// looks like a path.
+fn specifiers(name: &str, data: &[u8]) -> Vec<(String, bool)> {
+    let mut found = BTreeMap::new();
+    let mut add = |specifier: String, explicit: bool| {
+        let specifier = specifier.trim().to_string();
+        if !specifier.is_empty() {
+            *found.entry(specifier).or_insert(false) |= explicit;
+        }
+    };
+
+    if has_extension(name, &["html", "htm", "svg"]) {
+        let lower = data.to_ascii_lowercase();
+        for (open, attributes) in HTML_REFERENCES {
+            for (start, end) in tags(&lower, open) {
+                for name in *attributes {
+                    let Some((_, value)) = attribute(&data[start..end], &lower[start..end], name)
+                    else {
+                        continue;
+                    };
+                    if *name == b"srcset" {
+                        for candidate in value.split(',') {
+                            let url = candidate.split_ascii_whitespace().next();
+                            add(url.unwrap_or_default().to_string(), true);
+                        }
+                    } else {
+                        add(value, true);
+                    }
+                }
+            }
+        }
+    }
+    for offset in find_all(data, URL_FUNCTION) {
+        let rest = &data[offset + URL_FUNCTION.len()..];
+        let end = rest
+            .iter()
+            .take(MAX_LITERAL_LEN)
+            .position(|&byte| byte == b')');
+        if let Some(end) = end {
+            let value = String::from_utf8_lossy(&rest[..end]);
+            let value = value.trim().trim_matches(['"', '\'']);
+            if !value.starts_with("data:") && !value.contains(['(', '+', '$', '{']) {
+                add(value.to_string(), true);
+            }
+        }
+    }
+    for offset in find_all(data, SOURCE_MAP_COMMENT) {
+        let rest = &data[offset + SOURCE_MAP_COMMENT.len()..];
+        let length = rest
+            .iter()
+            .take(MAX_LITERAL_LEN)
+            .take_while(|byte| byte.is_ascii_graphic() && **byte != b'*')
+            .count();
+        add(String::from_utf8_lossy(&rest[..length]).into_owned(), true);
+    }
+    for literal in path_literals(data) {
+        let explicit = ["/", "./", "../"]
+            .iter()
+            .any(|prefix| literal.starts_with(prefix));
+        add(literal, explicit);
+    }
+    found.into_iter().collect()
+}
+
+/// Reads string literals that name a file by its extension.
+fn path_literals(data: &[u8]) -> Vec<String> {
+    let mut literals = Vec::new();
+    let mut index = 0;
+    while index < data.len() {
+        let quote = data[index];
+        index += 1;
+        if !matches!(quote, b'"' | b'\'' | b'`') {
+            continue;
+        }
+        let rest = &data[index..];
+        let Some(length) = rest
+            .iter()
+            .take(MAX_LITERAL_LEN)
+            .position(|&byte| byte == quote || byte == b'\n')
+        else {
+            continue;
+        };
+        let literal = &rest[..length];
+        let is_path = rest[length] == quote
+            && !literal.is_empty()
+            && literal
+                .iter()
+                .all(|&byte| byte.is_ascii_graphic() && !b"<>{}\\\"'`$".contains(&byte))
+            && !literal.contains(&b':');
+        if is_path {
+            let literal = String::from_utf8_lossy(literal);
+            let path = literal.split(['?', '#']).next().unwrap_or_default();
+            if has_extension(path, REFERENCED_EXTENSIONS) && path.contains(['/', '.']) {
+                literals.push(literal.into_owned());
+            }
+            index += length + 1;
+        }
+    }
+    literals
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    file_name.rsplit_once('.').is_some_and(|(stem, extension)| {
+        !stem.is_empty()
+            && extensions
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
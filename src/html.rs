@@ -0,0 +1,105 @@
+//! Reading tags and attributes from HTML assets.
+//!
+//! Bundled pages are small and machine-written, so finding tags by name and
+//! reading quoted attributes is enough; nothing here builds a DOM.
+
+/// Returns the start and end of each tag opened by `open` in lower-cased
+/// `html`.
+pub(crate) fn tags(html: &[u8], open: &[u8]) -> Vec<(usize, usize)> {
+    find_all(html, open)
+        .filter(|&start| {
+            html.get(start + open.len())
+                .is_some_and(|&byte| byte.is_ascii_whitespace() || byte == b'>')
+        })
+        .filter_map(|start| find(&html[start..], b">").map(|end| (start, start + end)))
+        .collect()
+}
+
+/// Reads the value of attribute `name` from `tag`, finding it in the
+/// lower-cased `lower_tag`. Returns its offset in the tag and the value
+/// with common entities decoded.
+pub(crate) fn attribute(tag: &[u8], lower_tag: &[u8], name: &[u8]) -> Option<(usize, String)> {
+    let start = find_all(lower_tag, name).find(|&offset| {
+        let before = offset
+            .checked_sub(1)
+            .and_then(|before| lower_tag.get(before));
+        let after = lower_tag[offset + name.len()..]
+            .iter()
+            .find(|byte| !byte.is_ascii_whitespace());
+        before.is_some_and(u8::is_ascii_whitespace) && after == Some(&b'=')
+    })?;
+    let equals = start + find(&lower_tag[start..], b"=")?;
+    let value_start = equals
+        + 1
+        + tag[equals + 1..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+    let (value_start, value) = match tag.get(value_start) {
+        Some(&quote @ (b'"' | b'\'')) => {
+            let value = &tag[value_start + 1..];
+            let end = value.iter().position(|&byte| byte == quote)?;
+            (value_start + 1, &value[..end])
+        }
+        _ => {
+            let value = &tag[value_start..];
+            let end = value
+                .iter()
+                .position(|&byte| byte.is_ascii_whitespace() || byte == b'>')
+                .unwrap_or(value.len());
+            (value_start, &value[..end])
+        }
+    };
+    let value = String::from_utf8_lossy(value)
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+    Some((value_start, value))
+}
+
+/// Resolves `reference`, as written in asset `base`, to the name of the
+/// asset it points at. References to other origins, `data:` URLs and
+/// fragments resolve to nothing.
+pub(crate) fn resolve_reference(base: &str, reference: &str) -> Option<String> {
+    let reference = reference.trim();
+    let path = reference.split(['?', '#']).next()?;
+    let has_scheme = path
+        .split_once(':')
+        .is_some_and(|(scheme, _)| !scheme.is_empty() && !scheme.contains('/'));
+    if path.is_empty() || has_scheme || path.starts_with("//") {
+        return None;
+    }
+    let joined = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        let dir = base.rsplit_once('/').map_or("", |(dir, _)| dir);
+        format!("{dir}/{path}")
+    };
+    let mut segments = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,408 @@
+//! Localization files and how completely each locale is translated.
+//!
+//! i18n libraries load one resource file per locale, such as
+//! `locales/fr.json`, `locales/fr/common.json` or `po/fr.po`. Such files are
+//! recognized by a language code in their path and grouped into bundles by
+//! the path they share, with the locale replaced by `{locale}`. Comparing
+//! the keys of the files of a bundle gives each locale's coverage and the
+//! keys it is missing. JSON files are flattened to dotted keys, and PO files
+//! keyed by `msgid`; empty strings and fuzzy PO entries count as missing.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Stands for the locale in the path pattern of a bundle.
+pub const LOCALE_PLACEHOLDER: &str = "{locale}";
+
+/// ISO 639-1 language codes.
+const LANGUAGES: &str = "aa ab ae af ak am an ar as av ay az ba be bg bh bi bm bn bo br bs ca \
+ce ch co cr cs cu cv cy da de dv dz ee el en eo es et eu fa ff fi fj fo fr fy ga gd gl gn gu \
+gv ha he hi ho hr ht hu hy hz ia id ie ig ii ik io is it iu ja jv ka kg ki kj kk kl km kn ko \
+kr ks ku kv kw ky la lb lg li ln lo lt lu lv mg mh mi mk ml mn mr ms mt my na nb nd ne ng nl \
+nn no nr nv ny oc oj om or os pa pi pl ps pt qu rm rn ro ru rw sa sc sd se sg si sk sl sm sn \
+so sq sr ss st su sv sw ta te tg th ti tk tl tn to tr ts tt tw ty ug uk ur uz ve vi vo wa wo \
+xh yi yo za zh zu";
+/// Three-letter codes of languages without a two-letter one that apps
+/// commonly ship.
+const THREE_LETTER_LANGUAGES: &[&str] = &["ast", "ckb", "fil", "haw", "yue"];
+/// Directories that hold localization files, so that a single locale is
+/// taken as a bundle too.
+const I18N_DIRS: &[&str] = &[
+    "_locales",
+    "i18n",
+    "l10n",
+    "lang",
+    "langs",
+    "languages",
+    "lc_messages",
+    "locale",
+    "locales",
+    "messages",
+    "translations",
+];
+/// Most missing keys listed per file; the count covers all of them.
+pub const MAX_LISTED_MISSING: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocaleFormat {
+    Json,
+    Po,
+}
+
+/// The resource file of one locale in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleFile {
+    pub locale: String,
+    pub name: String,
+    /// Keys with a translation.
+    pub translated: usize,
+    /// How many keys of the bundle the file lacks or leaves untranslated.
+    pub missing_count: usize,
+    /// The first [`MAX_LISTED_MISSING`] of those keys, sorted.
+    pub missing: Vec<String>,
+}
+
+/// Resource files that differ only in their locale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleBundle {
+    /// The path of the files with [`LOCALE_PLACEHOLDER`] for the locale,
+    /// such as `/locales/{locale}.json`.
+    pub pattern: String,
+    pub format: LocaleFormat,
+    /// Keys in any of the files.
+    pub keys: usize,
+    /// The files, sorted by locale.
+    pub files: Vec<LocaleFile>,
+}
+
+/// How completely one locale is translated, across every bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleCoverage {
+    pub locale: String,
+    /// The locale's files, in bundle order.
+    pub files: Vec<String>,
+    pub translated: usize,
+    /// Keys of every bundle, including those the locale has no file for.
+    pub keys: usize,
+    /// Patterns of the bundles the locale has no file for.
+    pub missing_bundles: Vec<String>,
+}
+
+impl LocaleCoverage {
+    /// The translated share of the keys, in percent, rounded down so only
+    /// a complete locale reaches 100.
+    pub fn percent(&self) -> u8 {
+        match self.keys {
+            0 => 100,
+            keys => (self.translated * 100 / keys) as u8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocaleReport {
+    /// Coverage by locale, sorted by locale.
+    pub locales: Vec<LocaleCoverage>,
+    /// Bundles, sorted by pattern.
+    pub bundles: Vec<LocaleBundle>,
+}
+
+/// Finds the localization files among the assets of `table` and compares
+/// their keys.
+pub fn analyze(table: &AssetTable) -> Result<LocaleReport> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() || locale_path(asset.name()).is_none() {
+            continue;
+        }
+        assets.push((asset.name().to_string(), decompress_asset(asset)?));
+    }
+    Ok(analyze_in(
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`analyze`], for decoded assets given as name and content.
+pub fn analyze_in<'a>(assets: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> LocaleReport {
+    // Translated and untranslated keys of each file, by pattern.
+    type Keys = (BTreeSet<String>, BTreeSet<String>);
+    let mut groups = BTreeMap::<String, (LocaleFormat, Vec<(String, String, Keys)>)>::new();
+    for (name, data) in assets {
+        let Some((pattern, locale, format)) = locale_path(name) else {
+            continue;
+        };
+        let Ok(text) = std::str::from_utf8(data) else {
+            continue;
+        };
+        let keys = match format {
+            LocaleFormat::Json => json_keys(text),
+            LocaleFormat::Po => po_keys(text),
+        };
+        let Some(keys) = keys else {
+            continue;
+        };
+        groups
+            .entry(pattern)
+            .or_insert_with(|| (format, Vec::new()))
+            .1
+            .push((locale, name.to_string(), keys));
+    }
+
+    let mut report = LocaleReport::default();
+    for (pattern, (format, mut files)) in groups {
+        let in_i18n_dir = pattern
+            .split('/')
+            .any(|segment| I18N_DIRS.contains(&segment.to_ascii_lowercase().as_str()));
+        if files.len() < 2 && !in_i18n_dir {
+            continue;
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let all = files
+            .iter()
+            .flat_map(|(_, _, (translated, untranslated))| translated.iter().chain(untranslated))
+            .collect::<BTreeSet<_>>();
+        let files = files
+            .iter()
+            .map(|(locale, name, (translated, _))| {
+                let missing = all
+                    .iter()
+                    .filter(|key| !translated.contains(**key))
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>();
+                LocaleFile {
+                    locale: locale.clone(),
+                    name: name.clone(),
+                    translated: translated.len(),
+                    missing_count: missing.len(),
+                    missing: missing.into_iter().take(MAX_LISTED_MISSING).collect(),
+                }
+            })
+            .collect();
+        report.bundles.push(LocaleBundle {
+            pattern,
+            format,
+            keys: all.len(),
+            files,
+        });
+    }
+
+    let locales = report
+        .bundles
+        .iter()
+        .flat_map(|bundle| bundle.files.iter().map(|file| file.locale.as_str()))
+        .collect::<BTreeSet<_>>();
+    for locale in locales {
+        let mut coverage = LocaleCoverage {
+            locale: locale.to_string(),
+            files: Vec::new(),
+            translated: 0,
+            keys: 0,
+            missing_bundles: Vec::new(),
+        };
+        for bundle in &report.bundles {
+            coverage.keys += bundle.keys;
+            match bundle.files.iter().find(|file| file.locale == locale) {
+                Some(file) => {
+                    coverage.files.push(file.name.clone());
+                    coverage.translated += file.translated;
+                }
+                None => coverage.missing_bundles.push(bundle.pattern.clone()),
+            }
+        }
+        report.locales.push(coverage);
+    }
+    report
+}
+
+/// The bundle pattern, locale and format of the localization file `name`:
+/// the last segment of its path, or dot-separated part of its file name,
+/// that is a language code.
+pub fn locale_path(name: &str) -> Option<(String, String, LocaleFormat)> {
+    let (stem, extension) = name.rsplit_once('.')?;
+    let format = match extension.to_ascii_lowercase().as_str() {
+        "json" => LocaleFormat::Json,
+        "po" => LocaleFormat::Po,
+        _ => return None,
+    };
+    let (dir, file) = stem.rsplit_once('/').unwrap_or(("", stem));
+    let parts = file.split('.').collect::<Vec<_>>();
+    for index in (0..parts.len()).rev() {
+        if is_locale(parts[index]) {
+            let mut parts = parts.clone();
+            let locale = std::mem::replace(&mut parts[index], LOCALE_PLACEHOLDER);
+            let pattern = format!("{dir}/{}.{extension}", parts.join("."));
+            return Some((pattern, locale.to_string(), format));
+        }
+    }
+    let segments = dir.split('/').collect::<Vec<_>>();
+    for index in (0..segments.len()).rev() {
+        if is_locale(segments[index]) {
+            let mut segments = segments.clone();
+            let locale = std::mem::replace(&mut segments[index], LOCALE_PLACEHOLDER);
+            let pattern = format!("{}/{file}.{extension}", segments.join("/"));
+            return Some((pattern, locale.to_string(), format));
+        }
+    }
+    None
+}
+
+/// Whether `code` is a language code with optional script and region
+/// subtags, as in `fr`, `pt-BR`, `zh_Hant` or `es-419`.
+fn is_locale(code: &str) -> bool {
+    let mut subtags = code.split(['-', '_']);
+    let language = subtags.next().unwrap_or_default();
+    let known = match language.len() {
+        2 => LANGUAGES
+            .split_ascii_whitespace()
+            .any(|known| known == language),
+        3 => THREE_LETTER_LANGUAGES.contains(&language),
+        _ => false,
+    };
+    let subtags = subtags.collect::<Vec<_>>();
+    known
+        && subtags.len() <= 2
+        && subtags.iter().all(|subtag| {
+            let letters = subtag.bytes().all(|byte| byte.is_ascii_alphabetic());
+            let digits = subtag.bytes().all(|byte| byte.is_ascii_digit());
+            (matches!(subtag.len(), 2 | 4) && letters) || (subtag.len() == 3 && digits)
+        })
+}
+
+/// The translated and untranslated keys of a JSON resource, with nested
+/// objects flattened to dotted keys, or `None` if it is not an object of
+/// mostly strings.
+fn json_keys(text: &str) -> Option<(BTreeSet<String>, BTreeSet<String>)> {
+    fn flatten(
+        prefix: &str,
+        value: &serde_json::Value,
+        keys: &mut (BTreeSet<String>, BTreeSet<String>),
+        strings: &mut usize,
+    ) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, value) in object {
+                    let key = match prefix {
+                        "" => key.clone(),
+                        prefix => format!("{prefix}.{key}"),
+                    };
+                    flatten(&key, value, keys, strings);
+                }
+            }
+            serde_json::Value::String(text) => {
+                *strings += 1;
+                if text.is_empty() {
+                    keys.1.insert(prefix.to_string());
+                } else {
+                    keys.0.insert(prefix.to_string());
+                }
+            }
+            serde_json::Value::Null => {
+                keys.1.insert(prefix.to_string());
+            }
+            _ => {
+                keys.0.insert(prefix.to_string());
+            }
+        }
+    }
+
+    let value = serde_json::from_str::<serde_json::Value>(text).ok()?;
+    if !value.is_object() {
+        return None;
+    }
+    let mut keys = (BTreeSet::new(), BTreeSet::new());
+    let mut strings = 0;
+    flatten("", &value, &mut keys, &mut strings);
+    let leaves = keys.0.len() + keys.1.len();
+    (strings > 0 && strings * 2 >= leaves).then_some(keys)
+}
+
+/// The translated and untranslated `msgid`s of a PO file, or `None` if it
+/// has none.
+fn po_keys(text: &str) -> Option<(BTreeSet<String>, BTreeSet<String>)> {
+    #[derive(Default)]
+    struct Entry {
+        msgid: String,
+        translated: bool,
+        fuzzy: bool,
+        /// Whether continuation lines belong to the `msgid` or a `msgstr`.
+        in_msgid: bool,
+        in_msgstr: bool,
+    }
+
+    fn finish(entry: &mut Entry, keys: &mut (BTreeSet<String>, BTreeSet<String>)) {
+        let entry = std::mem::take(entry);
+        // The header is the entry with an empty msgid.
+        if entry.msgid.is_empty() {
+            return;
+        }
+        if entry.translated && !entry.fuzzy {
+            keys.0.insert(entry.msgid);
+        } else {
+            keys.1.insert(entry.msgid);
+        }
+    }
+
+    let mut keys = (BTreeSet::new(), BTreeSet::new());
+    let mut entry = Entry::default();
+    let mut seen_msgstr = false;
+    for line in text.lines().map(str::trim) {
+        let starts_entry = line.is_empty() || line.starts_with('#') || line.starts_with("msgctxt");
+        if seen_msgstr && (starts_entry || line.starts_with("msgid ")) {
+            finish(&mut entry, &mut keys);
+            seen_msgstr = false;
+        }
+        if let Some(flags) = line.strip_prefix("#,") {
+            entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+        } else if let Some(value) = line.strip_prefix("msgid ") {
+            entry.msgid = unquote(value);
+            (entry.in_msgid, entry.in_msgstr) = (true, false);
+        } else if line.starts_with("msgstr") {
+            let value = line.split_once(' ').map_or("", |(_, value)| value);
+            entry.translated |= !unquote(value).is_empty();
+            (entry.in_msgid, entry.in_msgstr) = (false, true);
+            seen_msgstr = true;
+        } else if line.starts_with('"') {
+            if entry.in_msgid {
+                entry.msgid.push_str(&unquote(line));
+            } else if entry.in_msgstr {
+                entry.translated |= !unquote(line).is_empty();
+            }
+        } else {
+            (entry.in_msgid, entry.in_msgstr) = (false, false);
+        }
+    }
+    if seen_msgstr {
+        finish(&mut entry, &mut keys);
+    }
+    (!keys.0.is_empty() || !keys.1.is_empty()).then_some(keys)
+}
+
+/// The content of a quoted PO string, with its escapes decoded.
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
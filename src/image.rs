@@ -1,8 +1,17 @@
 use crate::asset::sha256_hex;
-use crate::binary::{self, BinaryMetadata, BinaryParser};
+use crate::binary::{
+    self, BinaryKind, BinaryMetadata, BinaryParser, ParsedBinary, ParserRegistry, SectionInfo,
+};
+use crate::deep_link;
+use crate::dev_server;
 use crate::error::Result;
-use std::fs;
-use std::path::{Path, PathBuf};
+use crate::features;
+use crate::fingerprint;
+use crate::plugins;
+use crate::webview;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::path::PathBuf;
 
 pub struct BinaryImage {
     data: Vec<u8>,
@@ -11,31 +20,93 @@ pub struct BinaryImage {
 }
 
 impl BinaryImage {
+    #[cfg(feature = "fs")]
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref();
-        let data = fs::read(path)?;
+        let data = std::fs::read(path)?;
         Self::from_data(data, Some(path.to_path_buf()))
     }
 
+    /// Opens `path` and parses it with the formats in `registry`.
+    #[cfg(feature = "fs")]
+    pub fn open_with_registry(path: impl AsRef<Path>, registry: &ParserRegistry) -> Result<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let parsed = registry.create_parser(&data)?;
+        let mut image = Self::with_parser(data, parsed);
+        image.metadata.source_path = Some(path.display().to_string());
+        Ok(image)
+    }
+
     pub fn from_bytes(data: impl AsRef<[u8]>) -> Result<Self> {
         Self::from_data(data.as_ref().to_vec(), None)
     }
 
-    fn from_data(data: Vec<u8>, source_path: Option<PathBuf>) -> Result<Self> {
+    /// Parses `data` with the formats in `registry`.
+    pub fn from_bytes_with_registry(
+        data: impl AsRef<[u8]>,
+        registry: &ParserRegistry,
+    ) -> Result<Self> {
+        let data = data.as_ref().to_vec();
+        let parsed = registry.create_parser(&data)?;
+        Ok(Self::with_parser(data, parsed))
+    }
+
+    pub(crate) fn from_data(data: Vec<u8>, source_path: Option<PathBuf>) -> Result<Self> {
         let parsed = binary::create_parser(&data)?;
+        let mut image = Self::with_parser(data, parsed);
+        image.metadata.source_path = source_path.map(|path| path.display().to_string());
+        Ok(image)
+    }
+
+    /// Records the `Info.plist` of the bundle the image was read from.
+    #[cfg(feature = "fs")]
+    pub(crate) fn with_bundle(mut self, bundle: Option<binary::BundleInfo>) -> Self {
+        self.metadata.bundle = bundle;
+        self
+    }
+
+    /// Records the containers the image was unpacked from.
+    #[cfg(feature = "fs")]
+    pub(crate) fn with_containers(mut self, containers: Vec<binary::ContainerStep>) -> Self {
+        self.metadata.containers = containers;
+        self
+    }
+
+    /// Wraps `data` with a parser created outside this crate.
+    pub fn with_parser(data: Vec<u8>, parsed: ParsedBinary) -> Self {
+        let code_signature = match parsed.kind {
+            BinaryKind::MachO => binary::code_signature(&data),
+            _ => None,
+        };
+        let plugins = plugins::find_plugins(&data);
+        let crates = fingerprint::crate_versions(&data);
         let metadata = BinaryMetadata {
             kind: parsed.kind,
             architecture: parsed.architecture,
             file_size: data.len(),
             sha256: sha256_hex(&data),
-            source_path: source_path.map(|path| path.display().to_string()),
+            source_path: None,
+            code_signature,
+            encryption: binary::encryption_info(&data),
+            build: binary::build_info(&data),
+            hardening: binary::hardening(&data),
+            deep_links: deep_link::find_deep_links(&data),
+            dev_url: dev_server::find_dev_url(&data),
+            features: features::find_features(&data, &plugins, &crates),
+            plugins,
+            webview: webview::find_webview(&data),
+            toolchain: fingerprint::toolchain(&data),
+            crates,
+            bundle: None,
+            containers: Vec::new(),
         };
 
-        Ok(Self {
+        Self {
             data,
             parser: parsed.parser,
             metadata,
-        })
+        }
     }
 
     pub fn data(&self) -> &[u8] {
@@ -46,10 +117,43 @@ impl BinaryImage {
         &self.metadata
     }
 
-    pub(crate) fn parser(&self) -> &dyn BinaryParser {
+    /// The format parser, for resolving pointers and addresses the way the
+    /// scanner does.
+    pub fn parser(&self) -> &dyn BinaryParser {
         self.parser.as_ref()
     }
 
+    /// The file-backed sections of the binary. For PE images their virtual
+    /// addresses are RVAs.
+    pub fn sections(&self) -> &[SectionInfo] {
+        self.parser.sections()
+    }
+
+    /// Converts virtual address `va` to a file offset.
+    pub fn resolve_va(&self, va: u64) -> Result<u64> {
+        self.parser.va_to_offset(va)
+    }
+
+    /// Converts a pointer as stored in the binary, which may be encoded as
+    /// a chained fixup, to a file offset.
+    pub fn resolve_raw_pointer(&self, raw_ptr: u64) -> Result<u64> {
+        self.parser.resolve_pointer(raw_ptr)
+    }
+
+    /// Reads the pointer stored at file offset `offset`, applying the
+    /// relocations of formats that keep its value elsewhere, such as ELF
+    /// RELA addends. The result is still raw; see
+    /// [`resolve_raw_pointer`](Self::resolve_raw_pointer).
+    pub fn read_pointer(&self, offset: usize) -> Result<u64> {
+        self.parser.read_pointer(&self.data, offset)
+    }
+
+    /// Reads the pointer at file offset `offset` and returns the file
+    /// offset it points at.
+    pub fn follow_pointer(&self, offset: usize) -> Result<u64> {
+        self.resolve_raw_pointer(self.read_pointer(offset)?)
+    }
+
     pub fn into_bytes(self) -> Vec<u8> {
         self.data
     }
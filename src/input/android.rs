@@ -0,0 +1,121 @@
+//! Android package input.
+//!
+//! Tauri builds the Rust side of an Android app as a shared library that the
+//! Kotlin activity loads, and packages it once per ABI: as
+//! `lib/<abi>/lib<name>.so` in an `.apk`, and under `base/lib/<abi>/` in an
+//! `.aab` app bundle. The library is an ELF image with the embedded assets.
+
+use super::archive;
+use super::package::PackagedFile;
+use crate::error::{Error, Result};
+use std::io::{Cursor, Read};
+
+const APK_MANIFEST: &str = "AndroidManifest.xml";
+const BUNDLE_MANIFEST: &str = "base/manifest/AndroidManifest.xml";
+
+/// ABIs in the order they are picked when none is requested.
+const ABI_PREFERENCE: [&str; 4] = ["arm64-v8a", "armeabi-v7a", "x86_64", "x86"];
+
+type Archive<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+/// Returns true if `data` is an `.apk` or `.aab`: a zip archive with an
+/// Android manifest.
+pub fn is_android_package(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+        && zip::ZipArchive::new(Cursor::new(data)).is_ok_and(|archive| {
+            archive.index_for_name(APK_MANIFEST).is_some()
+                || archive.index_for_name(BUNDLE_MANIFEST).is_some()
+        })
+}
+
+/// Returns true if `data` is an `.aab` app bundle rather than an `.apk`.
+pub fn is_app_bundle(data: &[u8]) -> bool {
+    zip::ZipArchive::new(Cursor::new(data))
+        .is_ok_and(|archive| archive.index_for_name(BUNDLE_MANIFEST).is_some())
+}
+
+/// Locates the native library with the embedded assets, for `abi` or, by
+/// default, the first of arm64-v8a, armeabi-v7a, x86_64 and x86 the package
+/// ships. Among the ABI's libraries, the largest one with embedded Tauri
+/// assets is selected, falling back to the largest.
+pub fn open_app(data: &[u8], abi: Option<&str>) -> Result<PackagedFile> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|err| invalid(format!("failed to open Android package: {err}")))?;
+    let mut libraries = Vec::new();
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|err| invalid(format!("failed to read zip entry: {err}")))?;
+        if let Some(entry_abi) = library_abi(entry.name()) {
+            libraries.push((
+                entry_abi.to_string(),
+                entry.size(),
+                entry.name().to_string(),
+                index,
+            ));
+        }
+    }
+    if libraries.is_empty() {
+        return Err(invalid("Android package contains no native libraries"));
+    }
+
+    let mut abis = libraries
+        .iter()
+        .map(|(abi, ..)| abi.as_str())
+        .collect::<Vec<_>>();
+    abis.sort_unstable();
+    abis.dedup();
+    let selected = match abi {
+        Some(abi) if abis.contains(&abi) => abi.to_string(),
+        Some(abi) => {
+            return Err(invalid(format!(
+                "Android package has no {abi} libraries; it ships {}",
+                abis.join(", ")
+            )))
+        }
+        None => ABI_PREFERENCE
+            .iter()
+            .find(|preferred| abis.contains(preferred))
+            .map_or(abis[0], |preferred| preferred)
+            .to_string(),
+    };
+
+    let mut candidates = libraries
+        .into_iter()
+        .filter(|(abi, ..)| *abi == selected)
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.2.cmp(&b.2)));
+    let mut fallback = None;
+    for (_, _, path, index) in candidates {
+        let data = read_entry(&mut archive, index)?;
+        if archive::has_assets(&data) {
+            return Ok(PackagedFile { path, data });
+        }
+        fallback.get_or_insert(PackagedFile { path, data });
+    }
+    fallback.ok_or_else(|| invalid("Android package contains no native libraries"))
+}
+
+/// Returns the ABI of a native library entry: `lib/<abi>/<name>.so`,
+/// optionally under the module directory of an app bundle.
+fn library_abi(name: &str) -> Option<&str> {
+    let path = match name.split_once('/') {
+        Some((_, rest)) if !name.starts_with("lib/") => rest,
+        _ => name,
+    };
+    let (abi, file) = path.strip_prefix("lib/")?.split_once('/')?;
+    (!abi.is_empty() && !file.contains('/') && file.ends_with(".so")).then_some(abi)
+}
+
+fn read_entry(archive: &mut Archive, index: usize) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_index(index)
+        .map_err(|err| invalid(format!("failed to read zip entry: {err}")))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
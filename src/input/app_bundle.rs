@@ -0,0 +1,134 @@
+//! macOS `.app` bundle input.
+
+use super::PackagedFile;
+use crate::binary::BundleInfo;
+use crate::error::{Error, Result};
+use crate::licenses;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A macOS application bundle on disk.
+#[derive(Debug, Clone)]
+pub struct AppBundle {
+    pub root: PathBuf,
+    /// Main executable, `Contents/MacOS/<CFBundleExecutable>`.
+    pub executable: PathBuf,
+    /// `Contents/Resources`, when present.
+    pub resources_dir: Option<PathBuf>,
+    /// `Contents/Info.plist`, when present.
+    pub info: Option<BundleInfo>,
+}
+
+impl AppBundle {
+    /// Returns true if `path` looks like an application bundle directory.
+    pub fn is_bundle(path: &Path) -> bool {
+        path.is_dir() && path.join("Contents").join("MacOS").is_dir()
+    }
+
+    /// Opens the bundle at `root` and locates its main executable.
+    ///
+    /// The executable is named by `CFBundleExecutable` in `Info.plist`. Bundles
+    /// without that key fall back to the only file in `Contents/MacOS`.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        let contents = root.join("Contents");
+        let macos_dir = contents.join("MacOS");
+
+        let info_plist = contents.join("Info.plist");
+        let info = if info_plist.is_file() {
+            Some(bundle_info(&fs::read(info_plist)?)?)
+        } else {
+            None
+        };
+        let executable = match info.as_ref().and_then(|info| info.executable.as_ref()) {
+            Some(name) => macos_dir.join(name),
+            None => single_file(&macos_dir)?,
+        };
+        if !executable.is_file() {
+            return Err(Error::InvalidContainer(format!(
+                "bundle executable {} does not exist",
+                executable.display()
+            )));
+        }
+
+        let resources_dir = Some(contents.join("Resources")).filter(|dir| dir.is_dir());
+        Ok(Self {
+            root,
+            executable,
+            resources_dir,
+            info,
+        })
+    }
+
+    /// Reads the license and notice files anywhere under `Contents`, with
+    /// paths relative to the bundle root.
+    pub fn license_files(&self) -> Result<Vec<PackagedFile>> {
+        let mut files = Vec::new();
+        collect_license_files(&self.root, &self.root.join("Contents"), &mut files)?;
+        Ok(files)
+    }
+}
+
+fn collect_license_files(root: &Path, dir: &Path, files: &mut Vec<PackagedFile>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_license_files(root, &path, files)?;
+        } else if file_type.is_file() && licenses::is_license_file(&path.to_string_lossy()) {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push(PackagedFile {
+                path: relative.to_string_lossy().replace('\\', "/"),
+                data: fs::read(&path)?,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Reads the fields of an `Info.plist`. `CFBundleExecutable` is dropped
+/// unless it is a plain file name.
+pub(crate) fn bundle_info(info_plist: &[u8]) -> Result<BundleInfo> {
+    let info = plist::Value::from_reader(std::io::Cursor::new(info_plist))
+        .map_err(|err| Error::InvalidContainer(format!("failed to parse Info.plist: {err}")))?;
+    let dict = info.as_dictionary();
+    let string = |key: &str| {
+        dict.and_then(|dict| dict.get(key))
+            .and_then(plist::Value::as_string)
+            .map(str::to_string)
+    };
+    Ok(BundleInfo {
+        identifier: string("CFBundleIdentifier"),
+        name: string("CFBundleName"),
+        version: string("CFBundleShortVersionString"),
+        build: string("CFBundleVersion"),
+        minimum_os: string("LSMinimumSystemVersion").or_else(|| string("MinimumOSVersion")),
+        executable: string("CFBundleExecutable")
+            .filter(|name| !name.is_empty() && !name.contains(['/', '\\'])),
+        url_schemes: dict
+            .and_then(|dict| dict.get("CFBundleURLTypes"))
+            .and_then(plist::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|url_type| url_type.as_dictionary()?.get("CFBundleURLSchemes"))
+            .filter_map(plist::Value::as_array)
+            .flatten()
+            .filter_map(plist::Value::as_string)
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+fn single_file(dir: &Path) -> Result<PathBuf> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file());
+    match (files.next(), files.next()) {
+        (Some(file), None) => Ok(file),
+        _ => Err(Error::InvalidContainer(format!(
+            "cannot determine the main executable in {}: Info.plist has no CFBundleExecutable",
+            dir.display()
+        ))),
+    }
+}
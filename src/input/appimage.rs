@@ -0,0 +1,62 @@
+//! AppImage input.
+//!
+//! A type 2 AppImage is an ELF runtime with a squashfs image appended right
+//! after the runtime's section header table.
+
+use super::package::{self, PackagedFile};
+use super::squashfs::{is_squashfs, SquashFs};
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+const APPIMAGE_TYPE_1: &[u8; 3] = b"AI\x01";
+const APPIMAGE_TYPE_2: &[u8; 3] = b"AI\x02";
+
+/// Returns true if `data` is an ELF runtime carrying an AppImage marker.
+pub fn is_appimage(data: &[u8]) -> bool {
+    data.starts_with(ELF_MAGIC)
+        && matches!(data.get(8..11), Some(marker) if marker == APPIMAGE_TYPE_1 || marker == APPIMAGE_TYPE_2)
+}
+
+/// Locates the application executable inside the AppImage's filesystem.
+pub fn open_app(data: &[u8]) -> Result<PackagedFile> {
+    if data.get(8..11) == Some(APPIMAGE_TYPE_1) {
+        return Err(invalid("type 1 (ISO 9660) AppImages are not supported"));
+    }
+    let offset = squashfs_offset(data)?;
+    let image = SquashFs::parse(&data[offset..])?;
+    package::pick_from_squashfs(&image, "AppImage")
+}
+
+/// Returns the offset of the embedded squashfs image: the end of the ELF
+/// runtime, computed from its section header table.
+pub fn squashfs_offset(data: &[u8]) -> Result<usize> {
+    let truncated = || invalid("truncated AppImage runtime");
+    let offset = match data.get(4) {
+        Some(2) => {
+            let table = data
+                .get(0x28..0x30)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or_else(truncated)?;
+            let entry_size = le_u16(data, 0x3a).ok_or_else(truncated)?;
+            let entries = le_u16(data, 0x3c).ok_or_else(truncated)?;
+            table + u64::from(entry_size) * u64::from(entries)
+        }
+        Some(1) => {
+            let table = le_u32(data, 0x20).ok_or_else(truncated)?;
+            let entry_size = le_u16(data, 0x2e).ok_or_else(truncated)?;
+            let entries = le_u16(data, 0x30).ok_or_else(truncated)?;
+            u64::from(table) + u64::from(entry_size) * u64::from(entries)
+        }
+        _ => return Err(invalid("unknown ELF class in AppImage runtime")),
+    };
+    let offset = usize::try_from(offset).map_err(|_| truncated())?;
+    match data.get(offset..) {
+        Some(image) if is_squashfs(image) => Ok(offset),
+        _ => Err(invalid("AppImage has no squashfs image after its runtime")),
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
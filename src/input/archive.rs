@@ -0,0 +1,99 @@
+//! Zip archive input.
+//!
+//! Releases are often distributed as a plain zip holding the executable, or a
+//! zipped `.app` bundle. Entries with an executable magic are candidates; the
+//! first one, largest first, that carries embedded assets is selected.
+//! Windows updater artifacts zip an installer, which is a candidate too and is
+//! opened by the caller.
+
+use super::package::PackagedFile;
+use crate::error::{Error, Result};
+use crate::extract::AssetScanner;
+use crate::image::BinaryImage;
+use std::io::{Cursor, Read};
+
+const ZIP_MAGIC: &[u8; 4] = b"PK\x03\x04";
+
+/// Directory macOS archivers add for resource forks.
+const RESOURCE_FORK_DIR: &str = "__MACOSX/";
+
+/// Returns true if `data` starts with a zip local file header.
+pub fn is_zip(data: &[u8]) -> bool {
+    data.starts_with(ZIP_MAGIC)
+}
+
+/// Locates the executable to scan inside a zip archive.
+///
+/// With `inner_path`, that entry is returned as is. Otherwise the largest
+/// executable with embedded Tauri assets is selected, falling back to the
+/// largest executable.
+pub fn open_app(data: &[u8], inner_path: Option<&str>) -> Result<PackagedFile> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|err| invalid(format!("failed to read zip archive: {err}")))?;
+
+    if let Some(inner_path) = inner_path {
+        let path = inner_path.trim_start_matches("./").trim_start_matches('/');
+        let index = archive
+            .index_for_name(path)
+            .ok_or_else(|| invalid(format!("archive has no entry named {path}")))?;
+        return Ok(PackagedFile {
+            path: path.to_string(),
+            data: read_entry(&mut archive, index)?,
+        });
+    }
+
+    let mut candidates = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|err| invalid(format!("failed to read zip entry: {err}")))?;
+        if !entry.is_file() || entry.name().starts_with(RESOURCE_FORK_DIR) {
+            continue;
+        }
+        let mut magic = [0; 4];
+        if entry.read_exact(&mut magic).is_ok() && is_executable(&magic) {
+            candidates.push((entry.size(), entry.name().to_string(), index));
+        }
+    }
+    candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let mut fallback = None;
+    for (_, path, index) in candidates {
+        let data = read_entry(&mut archive, index)?;
+        if has_assets(&data) {
+            return Ok(PackagedFile { path, data });
+        }
+        fallback.get_or_insert(PackagedFile { path, data });
+    }
+    fallback.ok_or_else(|| invalid("archive contains no executable"))
+}
+
+/// Returns true if `magic` starts an executable or an MSI installer.
+pub(super) fn is_executable(magic: &[u8; 4]) -> bool {
+    magic.starts_with(b"MZ")
+        || magic == b"\x7fELF"
+        || magic == b"\xd0\xcf\x11\xe0"
+        || matches!(
+            u32::from_be_bytes(*magic),
+            0xfeed_face | 0xfeed_facf | 0xcefa_edfe | 0xcffa_edfe | 0xcafe_babe
+        )
+}
+
+pub(super) fn has_assets(data: &[u8]) -> bool {
+    BinaryImage::from_data(data.to_vec(), None)
+        .and_then(|image| AssetScanner::scan(&image))
+        .is_ok_and(|table| !table.is_empty())
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<Cursor<&[u8]>>, index: usize) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_index(index)
+        .map_err(|err| invalid(format!("failed to read zip entry: {err}")))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
@@ -0,0 +1,204 @@
+//! Microsoft Cabinet (`.cab`) archives.
+//!
+//! Only single-volume cabinets with uncompressed or MSZIP folders are
+//! supported, which covers the cabinets WiX embeds in MSI installers by
+//! default.
+
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+use miniz_oxide::inflate::TINFLStatus;
+
+const SIGNATURE: &[u8; 4] = b"MSCF";
+
+const FLAG_PREV_CABINET: u16 = 0x0001;
+const FLAG_NEXT_CABINET: u16 = 0x0002;
+const FLAG_RESERVE_PRESENT: u16 = 0x0004;
+
+const COMPRESS_NONE: u16 = 0;
+const COMPRESS_MSZIP: u16 = 1;
+const COMPRESS_QUANTUM: u16 = 2;
+const COMPRESS_LZX: u16 = 3;
+
+/// Returns true if `data` starts with a cabinet header.
+pub fn is_cab(data: &[u8]) -> bool {
+    data.starts_with(SIGNATURE)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Folder {
+    data_offset: usize,
+    block_count: u16,
+    compression: u16,
+}
+
+/// A file stored in a cabinet.
+#[derive(Debug, Clone)]
+pub struct CabFile {
+    pub name: String,
+    pub size: u32,
+    folder: u16,
+    folder_offset: u32,
+}
+
+/// A parsed cabinet backed by its raw bytes.
+pub struct Cabinet<'a> {
+    data: &'a [u8],
+    folders: Vec<Folder>,
+    files: Vec<CabFile>,
+    data_reserve: usize,
+}
+
+impl<'a> Cabinet<'a> {
+    /// Parses the cabinet header, folder table and file table.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        if !is_cab(data) {
+            return Err(invalid("missing MSCF cabinet header"));
+        }
+        let files_offset = le_u32(data, 16).ok_or_else(truncated)? as usize;
+        let folder_count = le_u16(data, 26).ok_or_else(truncated)?;
+        let file_count = le_u16(data, 28).ok_or_else(truncated)?;
+        let flags = le_u16(data, 30).ok_or_else(truncated)?;
+        if flags & (FLAG_PREV_CABINET | FLAG_NEXT_CABINET) != 0 {
+            return Err(invalid("multi-volume cabinets are not supported"));
+        }
+
+        let mut cursor = 36;
+        let (folder_reserve, data_reserve) = if flags & FLAG_RESERVE_PRESENT != 0 {
+            let header_reserve = usize::from(le_u16(data, cursor).ok_or_else(truncated)?);
+            let folder_reserve = usize::from(*data.get(cursor + 2).ok_or_else(truncated)?);
+            let data_reserve = usize::from(*data.get(cursor + 3).ok_or_else(truncated)?);
+            cursor += 4 + header_reserve;
+            (folder_reserve, data_reserve)
+        } else {
+            (0, 0)
+        };
+
+        let mut folders = Vec::with_capacity(usize::from(folder_count));
+        for _ in 0..folder_count {
+            folders.push(Folder {
+                data_offset: le_u32(data, cursor).ok_or_else(truncated)? as usize,
+                block_count: le_u16(data, cursor + 4).ok_or_else(truncated)?,
+                compression: le_u16(data, cursor + 6).ok_or_else(truncated)?,
+            });
+            cursor += 8 + folder_reserve;
+        }
+
+        let mut files = Vec::with_capacity(usize::from(file_count));
+        let mut cursor = files_offset;
+        for _ in 0..file_count {
+            let size = le_u32(data, cursor).ok_or_else(truncated)?;
+            let folder_offset = le_u32(data, cursor + 4).ok_or_else(truncated)?;
+            let folder = le_u16(data, cursor + 8).ok_or_else(truncated)?;
+            let name_start = cursor + 16;
+            let name_len = data
+                .get(name_start..)
+                .and_then(|rest| rest.iter().position(|&byte| byte == 0))
+                .ok_or_else(truncated)?;
+            let name = String::from_utf8_lossy(&data[name_start..name_start + name_len]);
+            files.push(CabFile {
+                name: name.into_owned(),
+                size,
+                folder,
+                folder_offset,
+            });
+            cursor = name_start + name_len + 1;
+        }
+
+        Ok(Self {
+            data,
+            folders,
+            files,
+            data_reserve,
+        })
+    }
+
+    /// Files stored in the cabinet, in table order.
+    pub fn files(&self) -> &[CabFile] {
+        &self.files
+    }
+
+    /// Returns the file named `name`, if present.
+    pub fn file(&self, name: &str) -> Option<&CabFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+
+    /// Decompresses the contents of `file`.
+    pub fn read(&self, file: &CabFile) -> Result<Vec<u8>> {
+        let folder = self
+            .folders
+            .get(usize::from(file.folder))
+            .ok_or_else(|| invalid(format!("{} spans cabinet volumes", file.name)))?;
+        let start = file.folder_offset as usize;
+        let end = start + file.size as usize;
+        let contents = self.read_folder(folder, end)?;
+        contents
+            .get(start..end)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(truncated)
+    }
+
+    /// Decodes the data blocks of `folder` until at least `needed` bytes are
+    /// available.
+    fn read_folder(&self, folder: &Folder, needed: usize) -> Result<Vec<u8>> {
+        let compression = folder.compression & 0x000f;
+        match compression {
+            COMPRESS_NONE | COMPRESS_MSZIP => {}
+            COMPRESS_QUANTUM => {
+                return Err(invalid("Quantum-compressed cabinets are not supported"))
+            }
+            COMPRESS_LZX => return Err(invalid("LZX-compressed cabinets are not supported")),
+            other => return Err(invalid(format!("unknown cabinet compression {other}"))),
+        }
+
+        let mut out = Vec::new();
+        let mut cursor = folder.data_offset;
+        for _ in 0..folder.block_count {
+            if out.len() >= needed {
+                break;
+            }
+            let packed_len = usize::from(le_u16(self.data, cursor + 4).ok_or_else(truncated)?);
+            let unpacked_len = usize::from(le_u16(self.data, cursor + 6).ok_or_else(truncated)?);
+            let block_start = cursor + 8 + self.data_reserve;
+            let block = self
+                .data
+                .get(block_start..block_start + packed_len)
+                .ok_or_else(truncated)?;
+            cursor = block_start + packed_len;
+
+            if compression == COMPRESS_NONE {
+                out.extend_from_slice(block);
+                continue;
+            }
+
+            // Each MSZIP block is an independent deflate stream prefixed with
+            // "CK", but back-references may reach into the previous block's
+            // output, so blocks are inflated into one flat buffer.
+            let deflate = block
+                .strip_prefix(b"CK")
+                .ok_or_else(|| invalid("MSZIP block is missing its CK signature"))?;
+            let position = out.len();
+            out.resize(position + unpacked_len, 0);
+            let mut inflater = DecompressorOxide::new();
+            let (status, _, written) = decompress(
+                &mut inflater,
+                deflate,
+                &mut out,
+                position,
+                inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+            );
+            if status != TINFLStatus::Done || written != unpacked_len {
+                return Err(invalid("corrupt MSZIP block"));
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated cabinet")
+}
@@ -0,0 +1,111 @@
+//! Debian package (`.deb`) input.
+//!
+//! A `.deb` is an `ar` archive whose `data.tar.*` member holds the installed
+//! file tree.
+
+use super::package::{self, ExecutablePicker, PackagedFile};
+use crate::error::{Error, Result};
+use crate::licenses;
+
+const AR_MAGIC: &[u8; 8] = b"!<arch>\n";
+const AR_HEADER_SIZE: usize = 60;
+
+/// Returns true if `data` is an `ar` archive starting with `debian-binary`.
+pub fn is_deb(data: &[u8]) -> bool {
+    data.starts_with(AR_MAGIC)
+        && data
+            .get(AR_MAGIC.len()..AR_MAGIC.len() + 16)
+            .is_some_and(|name| name.starts_with(b"debian-binary"))
+}
+
+/// Locates the application executable in the package's data archive.
+pub fn open_app(data: &[u8]) -> Result<PackagedFile> {
+    let mut picker = ExecutablePicker::default();
+    for_each_file(
+        data,
+        |path| ExecutablePicker::rank(path).is_some(),
+        |path, contents| picker.offer(path, contents),
+    )?;
+    picker.finish("package")
+}
+
+/// Reads the license and copyright files the package installs, such as
+/// `usr/share/doc/<package>/copyright`.
+pub fn license_files(data: &[u8]) -> Result<Vec<PackagedFile>> {
+    let mut files = Vec::new();
+    for_each_file(data, licenses::is_license_file, |path, data| {
+        files.push(PackagedFile { path, data })
+    })?;
+    Ok(files)
+}
+
+/// Reads every regular file of the package's data archive whose normalized
+/// path `select` accepts and passes it to `visit`.
+fn for_each_file(
+    data: &[u8],
+    mut select: impl FnMut(&str) -> bool,
+    mut visit: impl FnMut(String, Vec<u8>),
+) -> Result<()> {
+    let data_tar = ar_members(data)?
+        .into_iter()
+        .find(|(name, _)| name.starts_with("data.tar"))
+        .map(|(_, contents)| contents)
+        .ok_or_else(|| invalid("package has no data.tar member"))?;
+    let tar = package::decompress(data_tar)?;
+
+    let mut archive = tar::Archive::new(tar.as_slice());
+    let entries = archive
+        .entries()
+        .map_err(|err| invalid(format!("failed to read data.tar: {err}")))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|err| invalid(format!("failed to read data.tar: {err}")))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = path.trim_start_matches("./").trim_start_matches('/');
+        if !select(path) {
+            continue;
+        }
+        let path = path.to_string();
+        let mut contents = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut contents)?;
+        visit(path, contents);
+    }
+    Ok(())
+}
+
+/// Returns the `(name, contents)` of every member of an `ar` archive.
+fn ar_members(data: &[u8]) -> Result<Vec<(String, &[u8])>> {
+    if !data.starts_with(AR_MAGIC) {
+        return Err(invalid("missing ar archive magic"));
+    }
+    let mut members = Vec::new();
+    let mut cursor = AR_MAGIC.len();
+    while cursor + AR_HEADER_SIZE <= data.len() {
+        let header = &data[cursor..cursor + AR_HEADER_SIZE];
+        let name = String::from_utf8_lossy(&header[..16])
+            .trim_end()
+            .trim_end_matches('/')
+            .to_string();
+        let size = std::str::from_utf8(&header[48..58])
+            .ok()
+            .and_then(|size| size.trim().parse::<usize>().ok())
+            .ok_or_else(|| invalid("invalid ar member size"))?;
+        let start = cursor + AR_HEADER_SIZE;
+        let contents = data
+            .get(start..start + size)
+            .ok_or_else(|| invalid("truncated ar archive"))?;
+        members.push((name, contents));
+        // Members are padded to an even offset.
+        cursor = start + size + (size & 1);
+    }
+    Ok(members)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
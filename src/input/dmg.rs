@@ -0,0 +1,265 @@
+//! Apple disk image (UDIF `.dmg`) input.
+//!
+//! The `koly` trailer points at an XML property list whose `blkx` entries
+//! describe how each partition is stored in the data fork. Partitions are
+//! decoded into a flat disk image and the HFS+ volume inside is searched for
+//! an application bundle.
+
+use super::app_bundle;
+use super::hfs::{is_hfs_plus, HfsVolume};
+use crate::binary::BundleInfo;
+use crate::bytes::{be_u32, be_u64};
+use crate::error::{Error, Result};
+use std::io::Read;
+
+const KOLY_SIGNATURE: &[u8; 4] = b"koly";
+const KOLY_SIZE: usize = 512;
+const MISH_SIGNATURE: &[u8; 4] = b"mish";
+const SECTOR_SIZE: u64 = 512;
+
+const CHUNK_ZERO: u32 = 0x0000_0000;
+const CHUNK_RAW: u32 = 0x0000_0001;
+const CHUNK_IGNORE: u32 = 0x0000_0002;
+const CHUNK_ADC: u32 = 0x8000_0004;
+const CHUNK_ZLIB: u32 = 0x8000_0005;
+const CHUNK_BZIP2: u32 = 0x8000_0006;
+const CHUNK_LZFSE: u32 = 0x8000_0007;
+const CHUNK_LZMA: u32 = 0x8000_0008;
+const CHUNK_COMMENT: u32 = 0x7fff_fffe;
+const CHUNK_TERMINATOR: u32 = 0xffff_ffff;
+
+/// Returns true if `data` ends with a UDIF `koly` trailer.
+pub fn is_dmg(data: &[u8]) -> bool {
+    data.len() >= KOLY_SIZE && data[data.len() - KOLY_SIZE..].starts_with(KOLY_SIGNATURE)
+}
+
+/// A partition decoded from a disk image.
+#[derive(Debug, Clone)]
+pub struct Partition {
+    /// Partition name from the `blkx` table, e.g. `disk image (Apple_HFS : 4)`.
+    pub name: String,
+    /// Decoded partition contents.
+    pub data: Vec<u8>,
+}
+
+/// A decoded UDIF disk image.
+#[derive(Debug, Clone)]
+pub struct DiskImage {
+    pub partitions: Vec<Partition>,
+}
+
+impl DiskImage {
+    /// Decodes every partition of the disk image in `data`.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if !is_dmg(data) {
+            return Err(invalid("missing UDIF koly trailer"));
+        }
+        let koly = &data[data.len() - KOLY_SIZE..];
+        let data_fork_offset = be_u64(koly, 24).ok_or_else(truncated)?;
+        let xml_offset = to_usize(be_u64(koly, 216).ok_or_else(truncated)?)?;
+        let xml_length = to_usize(be_u64(koly, 224).ok_or_else(truncated)?)?;
+        let xml = xml_offset
+            .checked_add(xml_length)
+            .and_then(|end| data.get(xml_offset..end))
+            .filter(|xml| !xml.is_empty())
+            .ok_or_else(|| invalid("disk image has no XML resource table"))?;
+
+        let plist = plist::Value::from_reader_xml(xml)
+            .map_err(|err| invalid(format!("failed to parse disk image resource table: {err}")))?;
+        let blkx = plist
+            .as_dictionary()
+            .and_then(|dict| dict.get("resource-fork"))
+            .and_then(plist::Value::as_dictionary)
+            .and_then(|fork| fork.get("blkx"))
+            .and_then(plist::Value::as_array)
+            .ok_or_else(|| invalid("disk image resource table has no blkx entries"))?;
+
+        let mut partitions = Vec::new();
+        for entry in blkx {
+            let Some(entry) = entry.as_dictionary() else {
+                continue;
+            };
+            let name = entry
+                .get("Name")
+                .or_else(|| entry.get("CFName"))
+                .and_then(plist::Value::as_string)
+                .unwrap_or_default()
+                .to_string();
+            let Some(mish) = entry.get("Data").and_then(plist::Value::as_data) else {
+                continue;
+            };
+            let data = decode_partition(data, data_fork_offset, mish)
+                .map_err(|err| invalid(format!("partition {name:?}: {err}")))?;
+            partitions.push(Partition { name, data });
+        }
+        Ok(Self { partitions })
+    }
+
+    /// Returns the first partition holding an HFS+ volume.
+    pub fn hfs_volume(&self) -> Result<HfsVolume<'_>> {
+        if let Some(partition) = self
+            .partitions
+            .iter()
+            .find(|partition| is_hfs_plus(&partition.data))
+        {
+            return HfsVolume::parse(&partition.data);
+        }
+        if self.partitions.iter().any(|partition| {
+            partition.name.contains("APFS") || partition.data.get(32..36) == Some(b"NXSB")
+        }) {
+            return Err(invalid("APFS disk images are not supported"));
+        }
+        Err(invalid("disk image contains no HFS+ volume"))
+    }
+}
+
+/// The application bundle found inside a disk image.
+#[derive(Debug, Clone)]
+pub struct DmgApp {
+    /// Bundle directory name, e.g. `Example.app`.
+    pub bundle_name: String,
+    /// Executable path inside the bundle, e.g. `Contents/MacOS/Example`.
+    pub executable_path: String,
+    /// Executable contents.
+    pub executable: Vec<u8>,
+    /// The bundle's `Info.plist`, when present.
+    pub info: Option<BundleInfo>,
+}
+
+/// Locates the application bundle at the root of a disk image's volume and
+/// reads its main executable.
+pub fn open_app(data: &[u8]) -> Result<DmgApp> {
+    let image = DiskImage::parse(data)?;
+    let volume = image.hfs_volume()?;
+
+    let mut bundles = volume
+        .list_dir("/")?
+        .into_iter()
+        .filter(|entry| entry.is_dir && entry.name.ends_with(".app"));
+    let bundle_name = match (bundles.next(), bundles.next()) {
+        (Some(bundle), None) => bundle.name,
+        (None, _) => return Err(invalid("disk image contains no .app bundle")),
+        (Some(_), Some(_)) => return Err(invalid("disk image contains more than one .app bundle")),
+    };
+
+    let contents = format!("{bundle_name}/Contents");
+    let info_plist = format!("{contents}/Info.plist");
+    let info = if volume.is_file(&info_plist) {
+        Some(app_bundle::bundle_info(&volume.read_file(&info_plist)?)?)
+    } else {
+        None
+    };
+    let name = match info.as_ref().and_then(|info| info.executable.clone()) {
+        Some(name) => name,
+        None => {
+            let files = volume
+                .list_dir(&format!("{contents}/MacOS"))?
+                .into_iter()
+                .filter(|entry| !entry.is_dir)
+                .collect::<Vec<_>>();
+            match files.as_slice() {
+                [file] => file.name.clone(),
+                _ => {
+                    return Err(invalid(format!(
+                        "cannot determine the main executable of {bundle_name}: Info.plist has no CFBundleExecutable"
+                    )))
+                }
+            }
+        }
+    };
+
+    let executable_path = format!("Contents/MacOS/{name}");
+    let executable = volume.read_file(&format!("{bundle_name}/{executable_path}"))?;
+    Ok(DmgApp {
+        bundle_name,
+        executable_path,
+        executable,
+        info,
+    })
+}
+
+fn decode_partition(image: &[u8], data_fork_offset: u64, mish: &[u8]) -> Result<Vec<u8>> {
+    if !mish.starts_with(MISH_SIGNATURE) {
+        return Err(invalid("blkx entry is missing its mish header"));
+    }
+    let sector_count = be_u64(mish, 16).ok_or_else(truncated)?;
+    let base_offset = data_fork_offset
+        .checked_add(be_u64(mish, 24).ok_or_else(truncated)?)
+        .ok_or_else(truncated)?;
+    let chunk_count = to_usize(u64::from(be_u32(mish, 200).ok_or_else(truncated)?))?;
+
+    let size = to_usize(
+        sector_count
+            .checked_mul(SECTOR_SIZE)
+            .ok_or_else(truncated)?,
+    )?;
+    if size > image.len().saturating_mul(1024) {
+        return Err(invalid("partition size is implausibly large"));
+    }
+    let mut out = vec![0; size];
+
+    for index in 0..chunk_count {
+        let chunk = 204 + index * 40;
+        let kind = be_u32(mish, chunk).ok_or_else(truncated)?;
+        let sector = be_u64(mish, chunk + 8).ok_or_else(truncated)?;
+        let sectors = be_u64(mish, chunk + 16).ok_or_else(truncated)?;
+        let offset = be_u64(mish, chunk + 24).ok_or_else(truncated)?;
+        let length = be_u64(mish, chunk + 32).ok_or_else(truncated)?;
+
+        match kind {
+            CHUNK_ZERO | CHUNK_IGNORE | CHUNK_COMMENT => continue,
+            CHUNK_TERMINATOR => break,
+            _ => {}
+        }
+
+        let start = to_usize(sector.checked_mul(SECTOR_SIZE).ok_or_else(truncated)?)?;
+        let end = to_usize(sectors.checked_mul(SECTOR_SIZE).ok_or_else(truncated)?)?
+            .checked_add(start)
+            .ok_or_else(truncated)?;
+        let target = out.get_mut(start..end).ok_or_else(truncated)?;
+
+        let source_start = to_usize(base_offset.checked_add(offset).ok_or_else(truncated)?)?;
+        let source = source_start
+            .checked_add(to_usize(length)?)
+            .and_then(|end| image.get(source_start..end))
+            .ok_or_else(truncated)?;
+
+        match kind {
+            CHUNK_RAW => {
+                let len = source.len().min(target.len());
+                target[..len].copy_from_slice(&source[..len]);
+            }
+            CHUNK_ZLIB => read_exact_into(flate2::read::ZlibDecoder::new(source), target)?,
+            CHUNK_BZIP2 => read_exact_into(bzip2::read::BzDecoder::new(source), target)?,
+            CHUNK_ADC => return Err(invalid("ADC-compressed (UDCO) chunks are not supported")),
+            CHUNK_LZFSE => return Err(invalid("LZFSE-compressed (ULFO) chunks are not supported")),
+            CHUNK_LZMA => return Err(invalid("LZMA-compressed (ULMO) chunks are not supported")),
+            other => return Err(invalid(format!("unknown chunk type {other:#010x}"))),
+        }
+    }
+    Ok(out)
+}
+
+fn read_exact_into(mut reader: impl Read, target: &mut [u8]) -> Result<()> {
+    let mut filled = 0;
+    while filled < target.len() {
+        match reader.read(&mut target[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) => return Err(invalid(format!("failed to decompress chunk: {err}"))),
+        }
+    }
+    Ok(())
+}
+
+fn to_usize(value: u64) -> Result<usize> {
+    usize::try_from(value).map_err(|_| truncated())
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated disk image")
+}
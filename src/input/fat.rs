@@ -0,0 +1,77 @@
+//! Universal (fat) Mach-O input.
+//!
+//! A fat binary holds one Mach-O image per architecture. Tauri's
+//! `universal-apple-darwin` target embeds the same assets in each slice, so
+//! any of them can be scanned; the arm64 slice is preferred.
+
+use crate::error::{Error, Result};
+use object::macho::{FatArch32, FatArch64};
+use object::read::macho::{FatArch, MachOFatFile};
+use object::{Architecture, FileKind};
+
+/// The slice taken out of a fat binary.
+#[derive(Debug, Clone)]
+pub struct FatSlice {
+    /// Architecture name, e.g. `arm64` or `x86_64`.
+    pub architecture: String,
+    pub data: Vec<u8>,
+}
+
+/// Returns true if `data` is a fat binary whose slices are all Mach-O
+/// images. Java class files share the `cafebabe` magic.
+pub fn is_fat(data: &[u8]) -> bool {
+    slices(data).is_ok_and(|slices| {
+        !slices.is_empty()
+            && slices.iter().all(|(_, slice)| {
+                matches!(
+                    FileKind::parse(*slice),
+                    Ok(FileKind::MachO32 | FileKind::MachO64)
+                )
+            })
+    })
+}
+
+/// The architecture and image of every slice, in the order of the header.
+pub fn slices(data: &[u8]) -> Result<Vec<(Architecture, &[u8])>> {
+    match FileKind::parse(data) {
+        Ok(FileKind::MachOFat32) => slices_of::<FatArch32>(data),
+        Ok(FileKind::MachOFat64) => slices_of::<FatArch64>(data),
+        _ => Err(Error::InvalidContainer("not a fat Mach-O binary".into())),
+    }
+}
+
+fn slices_of<Fat: FatArch>(data: &[u8]) -> Result<Vec<(Architecture, &[u8])>> {
+    let invalid = |error: object::Error| Error::InvalidContainer(error.to_string());
+    MachOFatFile::<Fat>::parse(data)
+        .map_err(invalid)?
+        .arches()
+        .iter()
+        .map(|arch| Ok((arch.architecture(), arch.data(data).map_err(invalid)?)))
+        .collect()
+}
+
+/// Takes the arm64 slice out of a fat binary, or the first one if it has
+/// none.
+pub fn open_slice(data: &[u8]) -> Result<FatSlice> {
+    let slices = slices(data)?;
+    let (architecture, slice) = slices
+        .iter()
+        .find(|(architecture, _)| *architecture == Architecture::Aarch64)
+        .or_else(|| slices.first())
+        .ok_or_else(|| Error::InvalidContainer("the fat binary has no slices".into()))?;
+    Ok(FatSlice {
+        architecture: architecture_name(*architecture),
+        data: slice.to_vec(),
+    })
+}
+
+/// The name Apple's tools use for `architecture`.
+fn architecture_name(architecture: Architecture) -> String {
+    match architecture {
+        Architecture::Aarch64 => "arm64".to_string(),
+        Architecture::X86_64 => "x86_64".to_string(),
+        Architecture::I386 => "i386".to_string(),
+        Architecture::Arm => "arm".to_string(),
+        other => format!("{other:?}").to_lowercase(),
+    }
+}
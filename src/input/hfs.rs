@@ -0,0 +1,331 @@
+//! Read-only HFS+ volume access.
+//!
+//! Only what is needed to pull files out of a disk image is implemented: the
+//! catalog B-tree is walked through its leaf chain and file data is read from
+//! the data fork, consulting the extents overflow tree for fragmented files.
+
+use crate::bytes::{be_u16, be_u32, be_u64};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+
+const VOLUME_HEADER_OFFSET: usize = 1024;
+const SIGNATURE_HFS_PLUS: u16 = 0x482b;
+const SIGNATURE_HFSX: u16 = 0x4858;
+
+const ROOT_FOLDER_ID: u32 = 2;
+const LEAF_NODE: u8 = 0xff;
+
+const FOLDER_RECORD: u16 = 1;
+const FILE_RECORD: u16 = 2;
+
+/// `UF_COMPRESSED`: file contents live in a decmpfs attribute instead of the
+/// data fork.
+const UF_COMPRESSED: u8 = 0x20;
+
+/// Returns true if `data` starts with an HFS+ or HFSX volume.
+pub fn is_hfs_plus(data: &[u8]) -> bool {
+    matches!(
+        be_u16(data, VOLUME_HEADER_OFFSET),
+        Some(SIGNATURE_HFS_PLUS | SIGNATURE_HFSX)
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Extent {
+    start_block: u32,
+    block_count: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Fork {
+    logical_size: u64,
+    total_blocks: u32,
+    extents: Vec<Extent>,
+}
+
+impl Fork {
+    fn parse(data: &[u8], offset: usize) -> Result<Self> {
+        let logical_size = be_u64(data, offset).ok_or_else(truncated)?;
+        let total_blocks = be_u32(data, offset + 12).ok_or_else(truncated)?;
+        let extents = parse_extents(data, offset + 16)?;
+        Ok(Self {
+            logical_size,
+            total_blocks,
+            extents,
+        })
+    }
+
+    fn mapped_blocks(&self) -> u64 {
+        self.extents
+            .iter()
+            .map(|extent| u64::from(extent.block_count))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Folder {
+        id: u32,
+    },
+    File {
+        id: u32,
+        fork: Fork,
+        compressed: bool,
+    },
+}
+
+/// A directory entry returned by [`HfsVolume::list_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// An HFS+ volume backed by an in-memory partition.
+pub struct HfsVolume<'a> {
+    data: &'a [u8],
+    block_size: u64,
+    /// Catalog children keyed by parent folder ID.
+    children: HashMap<u32, Vec<(String, Node)>>,
+    /// Overflow extents keyed by data-fork file ID, ordered by start block.
+    overflow: HashMap<u32, Vec<(u32, Vec<Extent>)>>,
+}
+
+impl<'a> HfsVolume<'a> {
+    /// Parses the volume header and catalog of the volume in `data`.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        if !is_hfs_plus(data) {
+            return Err(invalid("missing HFS+ volume header"));
+        }
+        let header = VOLUME_HEADER_OFFSET;
+        let block_size = u64::from(be_u32(data, header + 40).ok_or_else(truncated)?);
+        if block_size == 0 || !block_size.is_power_of_two() {
+            return Err(invalid("invalid HFS+ block size"));
+        }
+
+        let mut volume = Self {
+            data,
+            block_size,
+            children: HashMap::new(),
+            overflow: HashMap::new(),
+        };
+
+        let extents_file = Fork::parse(data, header + 192)?;
+        let extents_tree = volume.read_fork(&extents_file, None)?;
+        for (key, record) in leaf_records(&extents_tree)? {
+            // HFSPlusExtentKey: keyLength, forkType, pad, fileID, startBlock.
+            let (Some(fork_type), Some(file_id), Some(start_block)) =
+                (key.get(2), be_u32(key, 4), be_u32(key, 8))
+            else {
+                continue;
+            };
+            if *fork_type == 0 {
+                volume
+                    .overflow
+                    .entry(file_id)
+                    .or_default()
+                    .push((start_block, parse_extents(record, 0)?));
+            }
+        }
+        for runs in volume.overflow.values_mut() {
+            runs.sort_by_key(|(start_block, _)| *start_block);
+        }
+
+        let catalog_file = Fork::parse(data, header + 272)?;
+        let catalog = volume.read_fork(&catalog_file, None)?;
+        for (key, record) in leaf_records(&catalog)? {
+            let Some(parent_id) = be_u32(key, 2) else {
+                continue;
+            };
+            let node = match be_u16(record, 0) {
+                Some(FOLDER_RECORD) => Node::Folder {
+                    id: be_u32(record, 8).ok_or_else(truncated)?,
+                },
+                Some(FILE_RECORD) => Node::File {
+                    id: be_u32(record, 8).ok_or_else(truncated)?,
+                    fork: Fork::parse(record, 88)?,
+                    compressed: record
+                        .get(41)
+                        .is_some_and(|flags| flags & UF_COMPRESSED != 0),
+                },
+                _ => continue,
+            };
+            volume
+                .children
+                .entry(parent_id)
+                .or_default()
+                .push((catalog_name(key)?, node));
+        }
+
+        Ok(volume)
+    }
+
+    /// Lists the entries of the folder at `path`, relative to the volume root.
+    pub fn list_dir(&self, path: &str) -> Result<Vec<DirEntry>> {
+        let id = match self.lookup(path)? {
+            Some(Node::Folder { id }) => *id,
+            None if path.trim_matches('/').is_empty() => ROOT_FOLDER_ID,
+            _ => return Err(invalid(format!("{path} is not a folder"))),
+        };
+        Ok(self
+            .children
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|(name, node)| DirEntry {
+                name: name.clone(),
+                is_dir: matches!(node, Node::Folder { .. }),
+            })
+            .collect())
+    }
+
+    /// Returns true if `path` names a regular file.
+    pub fn is_file(&self, path: &str) -> bool {
+        matches!(self.lookup(path), Ok(Some(Node::File { .. })))
+    }
+
+    /// Reads the data fork of the file at `path`.
+    pub fn read_file(&self, path: &str) -> Result<Vec<u8>> {
+        match self.lookup(path)? {
+            Some(Node::File {
+                compressed: true, ..
+            }) => Err(invalid(format!(
+                "{path} uses HFS+ file compression, which is not supported"
+            ))),
+            Some(Node::File { id, fork, .. }) => self.read_fork(fork, Some(*id)),
+            _ => Err(invalid(format!("{path} does not exist in the volume"))),
+        }
+    }
+
+    fn lookup(&self, path: &str) -> Result<Option<&Node>> {
+        let mut parent = ROOT_FOLDER_ID;
+        let mut found: Option<&Node> = None;
+        for component in path.split('/').filter(|component| !component.is_empty()) {
+            if let Some(Node::File { .. }) = found {
+                return Ok(None);
+            }
+            let Some((_, node)) = self
+                .children
+                .get(&parent)
+                .into_iter()
+                .flatten()
+                .find(|(name, _)| name == component)
+            else {
+                return Ok(None);
+            };
+            if let Node::Folder { id } = node {
+                parent = *id;
+            }
+            found = Some(node);
+        }
+        Ok(found)
+    }
+
+    fn read_fork(&self, fork: &Fork, file_id: Option<u32>) -> Result<Vec<u8>> {
+        let mut extents = fork.extents.clone();
+        if u64::from(fork.total_blocks) > fork.mapped_blocks() {
+            let runs = file_id.and_then(|id| self.overflow.get(&id));
+            extents.extend(runs.into_iter().flatten().flat_map(|(_, run)| run.clone()));
+        }
+
+        let size = usize::try_from(fork.logical_size).map_err(|_| truncated())?;
+        let mut out = Vec::with_capacity(size.min(self.data.len()));
+        for extent in extents {
+            if out.len() >= size {
+                break;
+            }
+            let start = u64::from(extent.start_block) * self.block_size;
+            let length = u64::from(extent.block_count) * self.block_size;
+            let start = usize::try_from(start).map_err(|_| truncated())?;
+            let end = usize::try_from(length)
+                .ok()
+                .and_then(|length| start.checked_add(length))
+                .ok_or_else(truncated)?;
+            let chunk = self.data.get(start..end).ok_or_else(truncated)?;
+            out.extend_from_slice(chunk);
+        }
+        if out.len() < size {
+            return Err(invalid("HFS+ fork extends past its mapped extents"));
+        }
+        out.truncate(size);
+        Ok(out)
+    }
+}
+
+fn parse_extents(data: &[u8], offset: usize) -> Result<Vec<Extent>> {
+    let mut extents = Vec::new();
+    for index in 0..8 {
+        let at = offset + index * 8;
+        let start_block = be_u32(data, at).ok_or_else(truncated)?;
+        let block_count = be_u32(data, at + 4).ok_or_else(truncated)?;
+        if block_count == 0 {
+            break;
+        }
+        extents.push(Extent {
+            start_block,
+            block_count,
+        });
+    }
+    Ok(extents)
+}
+
+/// Walks the leaf chain of a B-tree and yields `(key, record data)` pairs.
+fn leaf_records(tree: &[u8]) -> Result<Vec<(&[u8], &[u8])>> {
+    // Header node: descriptor (14 bytes) followed by BTHeaderRec.
+    let Some(first_leaf) = be_u32(tree, 14 + 10) else {
+        return Ok(Vec::new());
+    };
+    let node_size = usize::from(be_u16(tree, 14 + 18).ok_or_else(truncated)?);
+    let total_nodes = be_u32(tree, 14 + 22).ok_or_else(truncated)?;
+    if node_size < 512 {
+        return Err(invalid("invalid HFS+ B-tree node size"));
+    }
+
+    let mut records = Vec::new();
+    let mut current = first_leaf;
+    let mut visited = 0;
+    while current != 0 {
+        visited += 1;
+        if visited > total_nodes {
+            return Err(invalid("HFS+ B-tree leaf chain loops"));
+        }
+        let start = usize::try_from(current).map_err(|_| truncated())? * node_size;
+        let node = tree.get(start..start + node_size).ok_or_else(truncated)?;
+        if node[8] != LEAF_NODE {
+            return Err(invalid("HFS+ B-tree leaf chain reaches a non-leaf node"));
+        }
+
+        let count = usize::from(be_u16(node, 10).ok_or_else(truncated)?);
+        for index in 0..count {
+            // Record offsets are stored backwards from the end of the node.
+            let offset_at = node_size - 2 * (index + 1);
+            let offset = usize::from(be_u16(node, offset_at).ok_or_else(truncated)?);
+            let key_length = usize::from(be_u16(node, offset).ok_or_else(truncated)?);
+            let key_end = offset + 2 + key_length;
+            // Keys are padded so that record data starts on an even offset.
+            let data_start = key_end + (key_end & 1);
+            let key = node.get(offset..key_end).ok_or_else(truncated)?;
+            let data = node.get(data_start..).ok_or_else(truncated)?;
+            records.push((key, data));
+        }
+        current = be_u32(node, 0).ok_or_else(truncated)?;
+    }
+    Ok(records)
+}
+
+fn catalog_name(key: &[u8]) -> Result<String> {
+    let length = usize::from(be_u16(key, 6).ok_or_else(truncated)?);
+    let units = (0..length)
+        .map(|index| be_u16(key, 8 + index * 2).ok_or_else(truncated))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated HFS+ volume")
+}
@@ -0,0 +1,78 @@
+//! iOS app package input.
+//!
+//! An `.ipa` is a zip archive with the app bundle under `Payload/`. iOS
+//! bundles are flat: the `Info.plist` and the executable it names as
+//! `CFBundleExecutable` sit at the top of `Payload/<Name>.app/`. Tauri links
+//! the Rust side of the app into that executable, a Mach-O image.
+
+use super::app_bundle;
+use crate::binary::BundleInfo;
+use crate::error::{Error, Result};
+use std::io::{Cursor, Read};
+
+const PAYLOAD_DIR: &str = "Payload/";
+
+type Archive<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+/// The application read out of an `.ipa`.
+#[derive(Debug, Clone)]
+pub struct IpaApp {
+    /// Path of the executable inside the package, e.g.
+    /// `Payload/Example.app/Example`.
+    pub path: String,
+    pub data: Vec<u8>,
+    pub info: BundleInfo,
+}
+
+/// Returns true if `data` is a zip archive with an app bundle under
+/// `Payload/`.
+pub fn is_ipa(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+        && zip::ZipArchive::new(Cursor::new(data))
+            .is_ok_and(|archive| archive.file_names().any(|name| bundle_of(name).is_some()))
+}
+
+/// Locates the app executable through the bundle's `Info.plist`.
+pub fn open_app(data: &[u8]) -> Result<IpaApp> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|err| invalid(format!("failed to open iOS app package: {err}")))?;
+    let bundle = archive
+        .file_names()
+        .filter_map(bundle_of)
+        .min()
+        .map(str::to_string)
+        .ok_or_else(|| invalid("iOS app package has no app bundle under Payload/"))?;
+    let info =
+        app_bundle::bundle_info(&read_entry(&mut archive, &format!("{bundle}/Info.plist"))?)?;
+    let executable = info
+        .executable
+        .clone()
+        .ok_or_else(|| invalid(format!("{bundle}/Info.plist has no CFBundleExecutable")))?;
+    let path = format!("{bundle}/{executable}");
+    Ok(IpaApp {
+        data: read_entry(&mut archive, &path)?,
+        path,
+        info,
+    })
+}
+
+/// Returns the bundle directory, such as `Payload/Example.app`, if `name` is
+/// its `Info.plist`.
+fn bundle_of(name: &str) -> Option<&str> {
+    let bundle = name.strip_suffix("/Info.plist")?;
+    let app = bundle.strip_prefix(PAYLOAD_DIR)?;
+    (app.ends_with(".app") && !app.contains('/')).then_some(bundle)
+}
+
+fn read_entry(archive: &mut Archive, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|err| invalid(format!("failed to read {name}: {err}")))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
@@ -0,0 +1,339 @@
+//! Resolution of packaged application inputs.
+//!
+//! Users frequently have an application package rather than the bare
+//! executable. This module locates the executable to scan inside such inputs
+//! and falls back to reading the path as a plain binary.
+
+pub mod android;
+mod app_bundle;
+pub mod appimage;
+pub mod archive;
+pub mod cab;
+pub mod deb;
+pub mod dmg;
+pub mod fat;
+pub mod hfs;
+pub mod ipa;
+pub mod msi;
+pub mod msix;
+pub mod nsis;
+mod package;
+pub mod rpm;
+pub mod snap;
+pub mod squashfs;
+pub mod updater;
+
+use crate::binary::{BundleInfo, ContainerKind, ContainerStep};
+use crate::error::{Error, Result};
+use crate::image::BinaryImage;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use app_bundle::AppBundle;
+pub use package::PackagedFile;
+
+/// The executable selected from an input path.
+#[derive(Debug, Clone)]
+pub struct ResolvedInput {
+    /// Path of the selected executable.
+    pub binary_path: PathBuf,
+    /// Contents of the selected executable.
+    pub data: Vec<u8>,
+    /// Resource directory shipped alongside the executable, if any.
+    pub resources_dir: Option<PathBuf>,
+    /// `Info.plist` of the macOS bundle containing the executable, if any.
+    pub bundle_info: Option<BundleInfo>,
+    /// License and notice files shipped in the `.app` bundle or `.deb`
+    /// package alongside the executable.
+    pub license_files: Vec<PackagedFile>,
+    /// The containers the executable was unpacked from, outermost first.
+    pub containers: Vec<ContainerStep>,
+}
+
+impl ResolvedInput {
+    /// Parses the selected executable. The bundle's `Info.plist` and the
+    /// containers are recorded in the image metadata.
+    pub fn into_image(self) -> Result<BinaryImage> {
+        Ok(BinaryImage::from_data(self.data, Some(self.binary_path))?
+            .with_bundle(self.bundle_info)
+            .with_containers(self.containers))
+    }
+}
+
+/// Options controlling how an input path is resolved.
+#[derive(Debug, Clone, Default)]
+pub struct ResolveOptions {
+    /// Path of the entry to scan inside a zip archive, bypassing automatic
+    /// selection.
+    pub inner_path: Option<String>,
+    /// ABI whose native library to scan in an Android package, such as
+    /// `arm64-v8a`.
+    pub abi: Option<String>,
+}
+
+/// Containers nested deeper than this are rejected, so an archive that
+/// contains itself cannot loop forever.
+pub const MAX_CONTAINER_DEPTH: usize = 8;
+
+/// Resolves `path` to the executable that should be scanned.
+///
+/// macOS `.app` bundles are resolved through their `Info.plist`. Other
+/// inputs are identified by their magic: disk images are searched for the
+/// bundle they ship, Windows installers and packages (MSI, NSIS, MSIX),
+/// Linux packages, iOS and Android packages, zip archives and Tauri updater artifacts for the
+/// executable they install, and fat Mach-O binaries are sliced. What comes
+/// out is identified again, so nested containers such as an NSIS installer
+/// in a zip archive are followed down to the executable, and the chain is
+/// recorded in [`ResolvedInput::containers`]. Any other input is read as a
+/// binary. The `.sig` file of an updater artifact resolves to the artifact.
+pub fn resolve(path: impl AsRef<Path>) -> Result<ResolvedInput> {
+    resolve_with_options(path, &ResolveOptions::default())
+}
+
+/// Resolves `path` like [`resolve`], applying `options`.
+pub fn resolve_with_options(
+    path: impl AsRef<Path>,
+    options: &ResolveOptions,
+) -> Result<ResolvedInput> {
+    let path = path.as_ref();
+    let artifact = updater::signed_artifact(path);
+    let path = artifact.as_deref().unwrap_or(path);
+
+    let mut resolved = if AppBundle::is_bundle(path) {
+        let bundle = AppBundle::open(path)?;
+        let entry = bundle
+            .executable
+            .strip_prefix(path)
+            .unwrap_or(&bundle.executable)
+            .to_string_lossy()
+            .into_owned();
+        ResolvedInput {
+            data: fs::read(&bundle.executable)?,
+            license_files: bundle.license_files()?,
+            binary_path: bundle.executable,
+            resources_dir: bundle.resources_dir,
+            bundle_info: bundle.info,
+            containers: vec![ContainerStep {
+                kind: ContainerKind::AppBundle,
+                entry,
+            }],
+        }
+    } else {
+        let data = fs::read(path)?;
+        if options.inner_path.is_some()
+            && (msix::is_msix(&data)
+                || ipa::is_ipa(&data)
+                || android::is_android_package(&data)
+                || !archive::is_zip(&data))
+        {
+            return Err(Error::InvalidContainer(
+                "an inner path can only be selected in zip archives".into(),
+            ));
+        }
+        if options.abi.is_some() && !android::is_android_package(&data) {
+            return Err(Error::InvalidContainer(
+                "an ABI can only be selected in Android packages".into(),
+            ));
+        }
+        ResolvedInput {
+            binary_path: path.to_path_buf(),
+            data,
+            resources_dir: None,
+            bundle_info: None,
+            license_files: Vec::new(),
+            containers: Vec::new(),
+        }
+    };
+
+    let mut inner_path = options.inner_path.as_deref();
+    let mut abi = options.abi.as_deref();
+    while let Some(unpacked) = unpack(&resolved.data, inner_path.take(), abi.take())? {
+        if resolved.containers.len() >= MAX_CONTAINER_DEPTH {
+            return Err(Error::InvalidContainer(format!(
+                "containers are nested more than {MAX_CONTAINER_DEPTH} levels deep"
+            )));
+        }
+        log::info!(
+            "unpacked {} from the {}",
+            unpacked.entry,
+            unpacked.kind.as_str()
+        );
+        // A slice is the same file for another architecture.
+        if unpacked.kind != ContainerKind::FatMachO {
+            resolved.binary_path = resolved.binary_path.join(&unpacked.entry);
+        }
+        resolved.data = unpacked.data;
+        resolved.bundle_info = unpacked.bundle_info.or(resolved.bundle_info);
+        resolved.license_files.extend(unpacked.license_files);
+        resolved
+            .containers
+            .extend(container_steps(unpacked.kind, unpacked.entry));
+    }
+    Ok(resolved)
+}
+
+/// One level of a container, unpacked.
+struct Unpacked {
+    kind: ContainerKind,
+    entry: String,
+    data: Vec<u8>,
+    bundle_info: Option<BundleInfo>,
+    license_files: Vec<PackagedFile>,
+}
+
+impl Unpacked {
+    fn new(kind: ContainerKind, entry: String, data: Vec<u8>) -> Self {
+        Self {
+            kind,
+            entry,
+            data,
+            bundle_info: None,
+            license_files: Vec::new(),
+        }
+    }
+}
+
+/// Identifies the container `data` is by its magic and takes the executable
+/// out of it, or returns `None` if it is not a container.
+fn unpack(data: &[u8], inner_path: Option<&str>, abi: Option<&str>) -> Result<Option<Unpacked>> {
+    let unpacked = if dmg::is_dmg(data) {
+        let app = dmg::open_app(data)?;
+        Unpacked {
+            bundle_info: app.info,
+            ..Unpacked::new(
+                ContainerKind::DiskImage,
+                format!("{}/{}", app.bundle_name, app.executable_path),
+                app.executable,
+            )
+        }
+    } else if msi::is_msi(data) {
+        let app = msi::open_app(data)?;
+        Unpacked::new(ContainerKind::Msi, app.file_name, app.executable)
+    } else if nsis::is_nsis(data) {
+        let app = nsis::open_app(data)?;
+        Unpacked::new(ContainerKind::Nsis, app.file_name, app.executable)
+    } else if updater::is_tar_gz(data) {
+        let app = updater::open_app(data)?;
+        Unpacked {
+            bundle_info: app.info,
+            ..Unpacked::new(ContainerKind::UpdaterArchive, app.path, app.data)
+        }
+    } else if msix::is_msix(data) {
+        let app = msix::open_app(data)?;
+        Unpacked::new(ContainerKind::Msix, app.path, app.data)
+    } else if deb::is_deb(data) {
+        let app = deb::open_app(data)?;
+        Unpacked {
+            license_files: deb::license_files(data)?,
+            ..Unpacked::new(ContainerKind::Deb, app.path, app.data)
+        }
+    } else if rpm::is_rpm(data) {
+        let app = rpm::open_app(data)?;
+        Unpacked::new(ContainerKind::Rpm, app.path, app.data)
+    } else if appimage::is_appimage(data) {
+        let app = appimage::open_app(data)?;
+        Unpacked::new(ContainerKind::AppImage, app.path, app.data)
+    } else if snap::is_snap(data) {
+        let app = snap::open_app(data)?;
+        Unpacked::new(ContainerKind::Snap, app.path, app.data)
+    } else if android::is_android_package(data) {
+        let kind = if android::is_app_bundle(data) {
+            ContainerKind::Aab
+        } else {
+            ContainerKind::Apk
+        };
+        let app = android::open_app(data, abi)?;
+        Unpacked::new(kind, app.path, app.data)
+    } else if ipa::is_ipa(data) {
+        let app = ipa::open_app(data)?;
+        Unpacked {
+            bundle_info: Some(app.info),
+            ..Unpacked::new(ContainerKind::Ipa, app.path, app.data)
+        }
+    } else if archive::is_zip(data) {
+        let app = archive::open_app(data, inner_path)?;
+        Unpacked::new(ContainerKind::Zip, app.path, app.data)
+    } else if fat::is_fat(data) {
+        let slice = fat::open_slice(data)?;
+        Unpacked::new(ContainerKind::FatMachO, slice.architecture, slice.data)
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(unpacked))
+}
+
+/// The steps of taking `entry` out of a `kind` container. An executable
+/// inside a `.app` bundle is reported as the bundle, then the executable in
+/// the bundle.
+fn container_steps(kind: ContainerKind, entry: String) -> Vec<ContainerStep> {
+    match entry.split_once(".app/") {
+        Some((bundle, executable)) if kind != ContainerKind::AppBundle => vec![
+            ContainerStep {
+                kind,
+                entry: format!("{bundle}.app"),
+            },
+            ContainerStep {
+                kind: ContainerKind::AppBundle,
+                entry: executable.to_string(),
+            },
+        ],
+        _ => vec![ContainerStep { kind, entry }],
+    }
+}
+
+/// Recursively copies `source` into `destination`, returning the number of
+/// files copied.
+///
+/// Symlinks, such as a framework's `Versions/Current`, are copied as what
+/// they point to. Links that lead outside `source`, or back into a directory
+/// being copied, are skipped.
+pub fn copy_dir(source: &Path, destination: &Path) -> Result<usize> {
+    let root = fs::canonicalize(source)?;
+    copy_tree(&root, &mut vec![root.clone()], source, destination)
+}
+
+fn copy_tree(
+    root: &Path,
+    ancestors: &mut Vec<PathBuf>,
+    source: &Path,
+    destination: &Path,
+) -> Result<usize> {
+    fs::create_dir_all(destination)?;
+    let mut copied = 0;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let target = destination.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            ancestors.push(fs::canonicalize(&path)?);
+            copied += copy_tree(root, ancestors, &path, &target)?;
+            ancestors.pop();
+        } else if file_type.is_file() {
+            fs::copy(path, target)?;
+            copied += 1;
+        } else if file_type.is_symlink() {
+            let resolved = match fs::canonicalize(&path) {
+                Ok(resolved) if resolved.starts_with(root) => resolved,
+                _ => {
+                    log::warn!(
+                        "skipping {}, which leads outside the bundle",
+                        path.display()
+                    );
+                    continue;
+                }
+            };
+            if resolved.is_file() {
+                fs::copy(&resolved, target)?;
+                copied += 1;
+            } else if ancestors.contains(&resolved) {
+                log::warn!("skipping {}, which loops back on itself", path.display());
+            } else {
+                ancestors.push(resolved.clone());
+                copied += copy_tree(root, ancestors, &resolved, &target)?;
+                ancestors.pop();
+            }
+        }
+    }
+    Ok(copied)
+}
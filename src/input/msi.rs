@@ -0,0 +1,270 @@
+//! Windows Installer (`.msi`) input.
+//!
+//! An MSI is a compound file whose streams hold the installer database
+//! tables and, usually, the cabinet with the installed files. The `File`
+//! table maps the cabinet's member keys to real file names, which is how the
+//! application executable is located.
+
+use super::cab::{is_cab, Cabinet};
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+const CFB_SIGNATURE: &[u8; 8] = b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1";
+
+const NAME_ALPHABET: &[u8; 64] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz._";
+
+const COLUMN_STRING: u16 = 0x0800;
+const LONG_STRING_REFS: u32 = 0x8000_0000;
+
+/// Returns true if `data` starts with a compound file header.
+///
+/// Other compound documents share the signature; [`open_app`] reports an
+/// error for files without installer tables.
+pub fn is_msi(data: &[u8]) -> bool {
+    data.starts_with(CFB_SIGNATURE)
+}
+
+/// The application executable found inside an installer.
+#[derive(Debug, Clone)]
+pub struct MsiApp {
+    /// Long file name from the `File` table.
+    pub file_name: String,
+    /// Executable contents.
+    pub executable: Vec<u8>,
+}
+
+/// Locates the application executable inside an installer and extracts it
+/// from the embedded cabinet.
+///
+/// When the installer ships several executables, the largest is selected.
+pub fn open_app(data: &[u8]) -> Result<MsiApp> {
+    let streams = read_streams(data)?;
+    let stream = |name: &str| {
+        streams
+            .get(name)
+            .ok_or_else(|| invalid(format!("installer has no {name} stream")))
+    };
+
+    let strings = StringPool::parse(stream("!_StringPool")?, stream("!_StringData")?)?;
+    let files = file_table(&strings, stream("!_Columns")?, stream("!File")?)?;
+    let executable = files
+        .iter()
+        .filter(|file| file.name.to_ascii_lowercase().ends_with(".exe"))
+        .max_by_key(|file| file.size)
+        .ok_or_else(|| invalid("installer contains no .exe file"))?;
+
+    for cabinet in streams.values().filter(|stream| is_cab(stream)) {
+        let cabinet = Cabinet::parse(cabinet)?;
+        if let Some(member) = cabinet.file(&executable.key) {
+            return Ok(MsiApp {
+                file_name: executable.name.clone(),
+                executable: cabinet.read(member)?,
+            });
+        }
+    }
+    Err(invalid(format!(
+        "{} is not stored in an embedded cabinet",
+        executable.name
+    )))
+}
+
+fn read_streams(data: &[u8]) -> Result<HashMap<String, Vec<u8>>> {
+    let mut compound = cfb::CompoundFile::open(Cursor::new(data))
+        .map_err(|err| invalid(format!("failed to open installer: {err}")))?;
+    let paths = compound
+        .read_root_storage()
+        .filter(|entry| entry.is_stream())
+        .map(|entry| entry.path().to_path_buf())
+        .collect::<Vec<_>>();
+
+    let mut streams = HashMap::new();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|name| decode_stream_name(&name.to_string_lossy()))
+            .unwrap_or_default();
+        let mut contents = Vec::new();
+        compound
+            .open_stream(&path)
+            .and_then(|mut stream| stream.read_to_end(&mut contents))
+            .map_err(|err| invalid(format!("failed to read installer stream {name}: {err}")))?;
+        streams.insert(name, contents);
+    }
+    Ok(streams)
+}
+
+/// Reverses the name compression MSI applies to stream names, which packs
+/// two characters of a restricted alphabet into one UTF-16 code unit.
+fn decode_stream_name(name: &str) -> String {
+    let mut decoded = String::new();
+    for c in name.chars() {
+        let code = c as u32;
+        match code {
+            0x3800..=0x47ff => {
+                let value = code - 0x3800;
+                decoded.push(char::from(NAME_ALPHABET[(value & 0x3f) as usize]));
+                decoded.push(char::from(NAME_ALPHABET[((value >> 6) & 0x3f) as usize]));
+            }
+            0x4800..=0x483f => decoded.push(char::from(NAME_ALPHABET[(code - 0x4800) as usize])),
+            0x4840 => decoded.push('!'),
+            _ => decoded.push(c),
+        }
+    }
+    decoded
+}
+
+struct StringPool {
+    strings: Vec<String>,
+    ref_size: usize,
+}
+
+impl StringPool {
+    fn parse(pool: &[u8], data: &[u8]) -> Result<Self> {
+        let header = le_u32(pool, 0).ok_or_else(truncated)?;
+        let ref_size = if header & LONG_STRING_REFS != 0 { 3 } else { 2 };
+
+        let entries = pool[4..]
+            .chunks_exact(4)
+            .map(|entry| (le_u16(entry, 0).unwrap_or(0), le_u16(entry, 2).unwrap_or(0)))
+            .collect::<Vec<_>>();
+        let mut strings = Vec::new();
+        let mut offset = 0usize;
+        let mut index = 0;
+        while index < entries.len() {
+            let (len, refs) = entries[index];
+            let len = if len == 0 && refs != 0 {
+                // Strings over 64 KiB store the high length word in the
+                // following entry's reference count.
+                let (low, high) = entries.get(index + 1).copied().ok_or_else(truncated)?;
+                index += 1;
+                (usize::from(high) << 16) | usize::from(low)
+            } else {
+                usize::from(len)
+            };
+            index += 1;
+            let bytes = data.get(offset..offset + len).ok_or_else(truncated)?;
+            strings.push(String::from_utf8_lossy(bytes).into_owned());
+            offset += len;
+        }
+        Ok(Self { strings, ref_size })
+    }
+
+    fn get(&self, id: usize) -> Option<&str> {
+        id.checked_sub(1)
+            .and_then(|index| self.strings.get(index))
+            .map(String::as_str)
+    }
+
+    fn read_ref(&self, data: &[u8], offset: usize) -> Option<usize> {
+        let bytes = data.get(offset..offset + self.ref_size)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0usize, |value, &byte| (value << 8) | usize::from(byte)),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct InstalledFile {
+    key: String,
+    name: String,
+    size: u32,
+}
+
+/// Reads the `File` table using the column layout recorded in `_Columns`.
+fn file_table(strings: &StringPool, columns: &[u8], table: &[u8]) -> Result<Vec<InstalledFile>> {
+    // _Columns: Table (string), Number (i2), Name (string), Type (i2).
+    let row_size = 2 * strings.ref_size + 4;
+    let rows = columns.len() / row_size;
+    let names_at = rows * (strings.ref_size + 2);
+    let types_at = names_at + rows * strings.ref_size;
+
+    let mut layout = Vec::new();
+    for row in 0..rows {
+        let table_name = strings.read_ref(columns, row * strings.ref_size);
+        if table_name.and_then(|id| strings.get(id)) != Some("File") {
+            continue;
+        }
+        let number =
+            le_u16(columns, rows * strings.ref_size + row * 2).ok_or_else(truncated)? ^ 0x8000;
+        let name = strings
+            .read_ref(columns, names_at + row * strings.ref_size)
+            .and_then(|id| strings.get(id))
+            .unwrap_or_default()
+            .to_string();
+        let column_type = le_u16(columns, types_at + row * 2).ok_or_else(truncated)? ^ 0x8000;
+        layout.push((number, name, column_type));
+    }
+    if layout.is_empty() {
+        return Err(invalid("installer has no File table"));
+    }
+    layout.sort_by_key(|(number, _, _)| *number);
+
+    let widths = layout
+        .iter()
+        .map(|(_, _, column_type)| {
+            if column_type & COLUMN_STRING != 0 {
+                strings.ref_size
+            } else if column_type & 0xff == 4 {
+                4
+            } else {
+                2
+            }
+        })
+        .collect::<Vec<_>>();
+    let row_width: usize = widths.iter().sum();
+    let rows = table.len() / row_width;
+
+    let mut column_offsets = HashMap::new();
+    let mut offset = 0;
+    for ((_, name, _), width) in layout.iter().zip(&widths) {
+        column_offsets.insert(name.as_str(), (offset, *width));
+        offset += rows * width;
+    }
+    let column = |name: &str| {
+        column_offsets
+            .get(name)
+            .copied()
+            .ok_or_else(|| invalid(format!("File table has no {name} column")))
+    };
+    let (key_at, _) = column("File")?;
+    let (name_at, _) = column("FileName")?;
+    let (size_at, size_width) = column("FileSize")?;
+
+    let string_at = |base: usize, row: usize| {
+        strings
+            .read_ref(table, base + row * strings.ref_size)
+            .and_then(|id| strings.get(id))
+            .unwrap_or_default()
+    };
+    let mut files = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let file_name = string_at(name_at, row);
+        // FileName holds "SHORT~1.EXE|Long Name.exe" when a short name exists.
+        let name = file_name.rsplit('|').next().unwrap_or(file_name);
+        let size = match size_width {
+            4 => le_u32(table, size_at + row * 4).map(|size| size ^ 0x8000_0000),
+            _ => le_u16(table, size_at + row * 2).map(|size| u32::from(size ^ 0x8000)),
+        }
+        .unwrap_or(0);
+        files.push(InstalledFile {
+            key: string_at(key_at, row).to_string(),
+            name: name.to_string(),
+            size,
+        });
+    }
+    Ok(files)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated installer database")
+}
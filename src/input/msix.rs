@@ -0,0 +1,142 @@
+//! MSIX/AppX package input.
+//!
+//! Packages are zip archives whose `AppxManifest.xml` declares each
+//! application's `Executable`. Bundles (`.msixbundle`/`.appxbundle`) wrap one
+//! package per architecture and list them in `AppxBundleManifest.xml`.
+
+use super::package::PackagedFile;
+use crate::asset::percent_decode;
+use crate::error::{Error, Result};
+use std::io::{Cursor, Read};
+
+const PACKAGE_MANIFEST: &str = "AppxManifest.xml";
+const BUNDLE_MANIFEST: &str = "AppxMetadata/AppxBundleManifest.xml";
+
+/// Architectures in the order a bundle's packages are tried.
+const ARCHITECTURE_PREFERENCE: [&str; 4] = ["x64", "arm64", "x86", "neutral"];
+
+type Archive<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+/// Returns true if `data` is a zip archive with a package or bundle
+/// manifest.
+pub fn is_msix(data: &[u8]) -> bool {
+    data.starts_with(b"PK\x03\x04")
+        && zip::ZipArchive::new(Cursor::new(data)).is_ok_and(|archive| {
+            archive.index_for_name(PACKAGE_MANIFEST).is_some()
+                || archive.index_for_name(BUNDLE_MANIFEST).is_some()
+        })
+}
+
+/// Locates the application executable declared by the package manifest.
+pub fn open_app(data: &[u8]) -> Result<PackagedFile> {
+    let mut archive = open_archive(data)?;
+    if archive.index_for_name(BUNDLE_MANIFEST).is_some() {
+        let manifest = read_entry(&mut archive, BUNDLE_MANIFEST)?;
+        let package = bundle_package(&manifest)?;
+        let inner = read_entry(&mut archive, &package)?;
+        let app = open_package(&mut open_archive(&inner)?)?;
+        return Ok(PackagedFile {
+            path: format!("{package}/{}", app.path),
+            data: app.data,
+        });
+    }
+    open_package(&mut archive)
+}
+
+fn open_package(archive: &mut Archive) -> Result<PackagedFile> {
+    let manifest = read_entry(archive, PACKAGE_MANIFEST)?;
+    let manifest = parse_xml(&manifest, PACKAGE_MANIFEST)?;
+    let executables = manifest
+        .descendants()
+        .filter(|node| node.has_tag_name("Application"))
+        .filter_map(|node| node.attribute("Executable"))
+        .map(|executable| executable.replace('\\', "/"))
+        .collect::<Vec<_>>();
+    if executables.is_empty() {
+        return Err(invalid(
+            "AppxManifest.xml declares no application executable",
+        ));
+    }
+
+    for executable in &executables {
+        let Some(name) = entry_name(archive, executable) else {
+            continue;
+        };
+        let data = read_entry(archive, &name)?;
+        if data.starts_with(b"MZ") {
+            return Ok(PackagedFile {
+                path: executable.clone(),
+                data,
+            });
+        }
+    }
+    Err(invalid(format!(
+        "application executable {} is not in the package",
+        executables[0]
+    )))
+}
+
+/// Picks the application package from a bundle manifest, preferring x64.
+fn bundle_package(manifest: &[u8]) -> Result<String> {
+    let manifest = parse_xml(manifest, BUNDLE_MANIFEST)?;
+    let packages = manifest
+        .descendants()
+        .filter(|node| node.has_tag_name("Package"))
+        .filter(|node| {
+            node.attribute("Type")
+                .is_none_or(|kind| kind == "application")
+        })
+        .filter_map(|node| {
+            let file_name = node.attribute("FileName")?;
+            Some((
+                node.attribute("Architecture").unwrap_or("neutral"),
+                file_name,
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    packages
+        .iter()
+        .min_by_key(|(architecture, _)| {
+            ARCHITECTURE_PREFERENCE
+                .iter()
+                .position(|preferred| preferred == architecture)
+                .unwrap_or(ARCHITECTURE_PREFERENCE.len())
+        })
+        .map(|(_, file_name)| file_name.to_string())
+        .ok_or_else(|| invalid("bundle manifest lists no application package"))
+}
+
+fn parse_xml<'a>(data: &'a [u8], name: &str) -> Result<roxmltree::Document<'a>> {
+    let text =
+        std::str::from_utf8(data).map_err(|_| invalid(format!("{name} is not valid UTF-8")))?;
+    roxmltree::Document::parse(text.trim_start_matches('\u{feff}'))
+        .map_err(|err| invalid(format!("failed to parse {name}: {err}")))
+}
+
+fn open_archive(data: &[u8]) -> Result<Archive<'_>> {
+    zip::ZipArchive::new(Cursor::new(data))
+        .map_err(|err| invalid(format!("failed to open package: {err}")))
+}
+
+/// Finds the archive entry for `path`. Package entry names are
+/// percent-encoded, so `My App.exe` is stored as `My%20App.exe`.
+fn entry_name(archive: &Archive, path: &str) -> Option<String> {
+    archive
+        .file_names()
+        .find(|name| *name == path || percent_decode(name) == path)
+        .map(str::to_string)
+}
+
+fn read_entry(archive: &mut Archive, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|err| invalid(format!("failed to read {name}: {err}")))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
@@ -0,0 +1,299 @@
+//! NSIS setup executable input.
+//!
+//! NSIS appends its payload to the installer stub: a `firstheader` followed
+//! by the script header and the file data, compressed either as one solid
+//! stream or block by block. `File` commands become `EW_EXTRACTFILE` entries
+//! in the header, which name each file and point at its data.
+
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+use std::io::Read;
+
+const FIRST_HEADER_SIGNATURE: &[u8; 16] = b"\xef\xbe\xad\xdeNullsoftInst";
+const FIRST_HEADER_SIZE: usize = 28;
+const FIRST_HEADER_ALIGNMENT: usize = 512;
+
+const BLOCK_COMPRESSED: u32 = 0x8000_0000;
+
+/// Header block indices of the entry and string tables.
+const BLOCK_ENTRIES: usize = 2;
+const BLOCK_STRINGS: usize = 3;
+const ENTRY_SIZE: usize = 28;
+const EW_EXTRACTFILE: u32 = 20;
+
+/// Returns true if `data` is a PE image carrying an NSIS payload.
+pub fn is_nsis(data: &[u8]) -> bool {
+    data.starts_with(b"MZ") && find_first_header(data).is_some()
+}
+
+fn find_first_header(data: &[u8]) -> Option<usize> {
+    (0..data.len())
+        .step_by(FIRST_HEADER_ALIGNMENT)
+        .find(|&offset| data[offset..].get(4..20) == Some(FIRST_HEADER_SIGNATURE))
+}
+
+/// The application executable found inside a setup executable.
+#[derive(Debug, Clone)]
+pub struct NsisApp {
+    /// File name from the extracting `File` command.
+    pub file_name: String,
+    /// Executable contents.
+    pub executable: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    Stored,
+    Deflate,
+    Lzma,
+}
+
+/// The decoded header and a way to read file data.
+struct Payload<'a> {
+    header: Vec<u8>,
+    /// Solid payloads are decompressed once; data offsets index into this.
+    solid_data: Option<Vec<u8>>,
+    /// Non-solid payloads keep the raw blocks following the header.
+    blocks: &'a [u8],
+    method: Method,
+}
+
+impl Payload<'_> {
+    fn read_file(&self, offset: usize) -> Result<Vec<u8>> {
+        if let Some(data) = &self.solid_data {
+            let size = le_u32(data, offset).ok_or_else(truncated)? as usize;
+            return data
+                .get(offset + 4..offset + 4 + size)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(truncated);
+        }
+        read_block(self.blocks, offset, self.method).map(|(data, _)| data)
+    }
+}
+
+/// Locates the application executable among the files the installer
+/// extracts.
+///
+/// The largest `.exe` is selected, skipping WebView2 installers that Tauri
+/// may embed alongside the application.
+pub fn open_app(data: &[u8]) -> Result<NsisApp> {
+    let payload = open_payload(data)?;
+    let candidates = extract_entries(&payload.header)?
+        .into_iter()
+        .filter(|(name, _)| {
+            let name = name.to_ascii_lowercase();
+            name.ends_with(".exe") && !name.contains("webview2")
+        });
+
+    let mut best: Option<NsisApp> = None;
+    for (file_name, offset) in candidates {
+        let executable = payload.read_file(offset)?;
+        if best
+            .as_ref()
+            .is_none_or(|best| executable.len() > best.executable.len())
+        {
+            best = Some(NsisApp {
+                file_name,
+                executable,
+            });
+        }
+    }
+    best.ok_or_else(|| invalid("installer does not extract an .exe file"))
+}
+
+fn open_payload(data: &[u8]) -> Result<Payload<'_>> {
+    let first_header =
+        find_first_header(data).ok_or_else(|| invalid("missing NSIS firstheader"))?;
+    let header_len = le_u32(data, first_header + 20).ok_or_else(truncated)? as usize;
+    let total_len = le_u32(data, first_header + 24).ok_or_else(truncated)? as usize;
+    let end = first_header.saturating_add(total_len).min(data.len());
+    let payload = data
+        .get(first_header + FIRST_HEADER_SIZE..end)
+        .ok_or_else(truncated)?;
+
+    // A solid stream starts with LZMA properties or raw deflate data, while a
+    // non-solid payload starts with the header block's length word.
+    let first_word = le_u32(payload, 0).ok_or_else(truncated)?;
+    let block_len = (first_word & !BLOCK_COMPRESSED) as usize;
+    let non_solid = first_word as usize == header_len
+        || (first_word & BLOCK_COMPRESSED != 0 && block_len <= payload.len() - 4);
+    if non_solid && !is_lzma_properties(payload) {
+        let method = if first_word & BLOCK_COMPRESSED != 0 {
+            detect_method(&payload[4..])?
+        } else {
+            Method::Stored
+        };
+        let (header, consumed) = read_block(payload, 0, method)?;
+        return Ok(Payload {
+            header,
+            solid_data: None,
+            blocks: &payload[consumed..],
+            method,
+        });
+    }
+
+    let method = detect_method(payload)?;
+    let stream = decompress(payload, method)?;
+    let header_len = le_u32(&stream, 0).ok_or_else(truncated)? as usize;
+    let header = stream
+        .get(4..4 + header_len)
+        .ok_or_else(truncated)?
+        .to_vec();
+    Ok(Payload {
+        header,
+        solid_data: Some(stream[4 + header_len..].to_vec()),
+        blocks: &[],
+        method,
+    })
+}
+
+fn is_lzma_properties(data: &[u8]) -> bool {
+    data.first() == Some(&0x5d)
+        && le_u32(data, 1)
+            .is_some_and(|dictionary| dictionary.is_power_of_two() && dictionary >= 1 << 12)
+}
+
+fn detect_method(data: &[u8]) -> Result<Method> {
+    if is_lzma_properties(data) {
+        return Ok(Method::Lzma);
+    }
+    match data {
+        [0 | 1, 0x5d, ..] => Err(invalid(
+            "NSIS LZMA payloads with the x86 filter are not supported",
+        )),
+        [b'1', b'A', b'Y', b'&', ..] => Err(invalid("NSIS bzip2 payloads are not supported")),
+        _ => Ok(Method::Deflate),
+    }
+}
+
+/// Reads the length-prefixed block at `offset`, returning its contents and
+/// the offset just past it.
+fn read_block(data: &[u8], offset: usize, method: Method) -> Result<(Vec<u8>, usize)> {
+    let word = le_u32(data, offset).ok_or_else(truncated)?;
+    let len = (word & !BLOCK_COMPRESSED) as usize;
+    let start = offset + 4;
+    let block = data.get(start..start + len).ok_or_else(truncated)?;
+    let contents = if word & BLOCK_COMPRESSED != 0 {
+        decompress(block, method)?
+    } else {
+        block.to_vec()
+    };
+    Ok((contents, start + len))
+}
+
+fn decompress(data: &[u8], method: Method) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    match method {
+        Method::Stored => out.extend_from_slice(data),
+        Method::Deflate => {
+            flate2::read::DeflateDecoder::new(data)
+                .read_to_end(&mut out)
+                .map_err(|err| invalid(format!("failed to inflate NSIS data: {err}")))?;
+        }
+        Method::Lzma => {
+            // NSIS writes the LZMA properties without the uncompressed size
+            // and may omit the end marker.
+            let options = lzma_rs::decompress::Options {
+                unpacked_size: lzma_rs::decompress::UnpackedSize::UseProvided(None),
+                memlimit: None,
+                allow_incomplete: true,
+            };
+            lzma_rs::lzma_decompress_with_options(&mut &data[..], &mut out, &options)
+                .map_err(|err| invalid(format!("failed to decompress NSIS LZMA data: {err}")))?;
+        }
+    }
+    Ok(out)
+}
+
+/// Returns the `(file name, data offset)` of every `EW_EXTRACTFILE` entry.
+fn extract_entries(header: &[u8]) -> Result<Vec<(String, usize)>> {
+    let block = |index: usize| -> Result<(usize, usize)> {
+        let at = 4 + index * 8;
+        Ok((
+            le_u32(header, at).ok_or_else(truncated)? as usize,
+            le_u32(header, at + 4).ok_or_else(truncated)? as usize,
+        ))
+    };
+    let (entries_at, entry_count) = block(BLOCK_ENTRIES)?;
+    let (strings_at, _) = block(BLOCK_STRINGS)?;
+    let strings = header.get(strings_at..).ok_or_else(truncated)?;
+    // Unicode installers start the string table with an empty UTF-16 string.
+    let unicode = strings.starts_with(&[0, 0]);
+
+    let mut files = Vec::new();
+    for index in 0..entry_count {
+        let entry = entries_at + index * ENTRY_SIZE;
+        if le_u32(header, entry) != Some(EW_EXTRACTFILE) {
+            continue;
+        }
+        let name_ptr = le_u32(header, entry + 8).ok_or_else(truncated)? as usize;
+        let offset = le_u32(header, entry + 12).ok_or_else(truncated)? as usize;
+        let name = if unicode {
+            unicode_string(strings, name_ptr)
+        } else {
+            ansi_string(strings, name_ptr)
+        };
+        let name = name.rsplit('\\').next().unwrap_or_default().to_string();
+        files.push((name, offset));
+    }
+    Ok(files)
+}
+
+/// Decodes a string from a Unicode installer, replacing variable, shell and
+/// language references with `$`.
+fn unicode_string(strings: &[u8], index: usize) -> String {
+    const SKIP_CODE: u16 = 1;
+    const LANG_CODE: u16 = 4;
+
+    let mut units = Vec::new();
+    let mut at = index * 2;
+    while let Some(unit) = le_u16(strings, at) {
+        at += 2;
+        match unit {
+            0 => break,
+            SKIP_CODE => {
+                units.extend(le_u16(strings, at));
+                at += 2;
+            }
+            2..=LANG_CODE => {
+                units.push(u16::from(b'$'));
+                at += 2;
+            }
+            unit => units.push(unit),
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// Decodes a string from an ANSI installer, replacing variable, shell and
+/// language references with `$`.
+fn ansi_string(strings: &[u8], index: usize) -> String {
+    const SKIP_CODE: u8 = 252;
+
+    let mut bytes = Vec::new();
+    let mut at = index;
+    while let Some(&byte) = strings.get(at) {
+        at += 1;
+        match byte {
+            0 => break,
+            SKIP_CODE => {
+                bytes.extend(strings.get(at));
+                at += 1;
+            }
+            253..=255 => {
+                bytes.push(b'$');
+                at += 2;
+            }
+            byte => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated NSIS payload")
+}
@@ -0,0 +1,111 @@
+//! Helpers shared by Linux package inputs.
+
+use super::squashfs::SquashFs;
+use crate::error::{Error, Result};
+use std::io::Read;
+
+/// A file read out of a package.
+#[derive(Debug, Clone)]
+pub struct PackagedFile {
+    /// Path of the file inside the package, without a leading `/` or `./`.
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+/// Decompresses `data` according to its magic bytes. Data without a known
+/// magic is returned unchanged.
+pub(crate) fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let result = match data {
+        [0x1f, 0x8b, ..] => flate2::read::MultiGzDecoder::new(data).read_to_end(&mut out),
+        [b'B', b'Z', b'h', ..] => bzip2::read::MultiBzDecoder::new(data).read_to_end(&mut out),
+        [0x28, 0xb5, 0x2f, 0xfd, ..] => ruzstd::decoding::StreamingDecoder::new(data)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            .and_then(|mut decoder| decoder.read_to_end(&mut out)),
+        [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => {
+            lzma_rs::xz_decompress(&mut &data[..], &mut out)
+                .map(|()| out.len())
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+        }
+        _ => return Ok(data.to_vec()),
+    };
+    result
+        .map_err(|err| Error::InvalidContainer(format!("failed to decompress payload: {err}")))?;
+    Ok(out)
+}
+
+/// Picks the application executable among the ELF files of a Linux package.
+///
+/// Files directly under `usr/bin` are preferred over files under
+/// `usr/lib/<app>`; ties go to the larger file.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutablePicker {
+    best: Option<(u8, PackagedFile)>,
+}
+
+impl ExecutablePicker {
+    /// Normalizes `path` and returns it if the file could be the application
+    /// executable.
+    pub(crate) fn candidate(path: &str) -> Option<String> {
+        let path = path.trim_start_matches("./").trim_start_matches('/');
+        Self::rank(path).map(|_| path.to_string())
+    }
+
+    pub(crate) fn rank(path: &str) -> Option<u8> {
+        let components = path.split('/').collect::<Vec<_>>();
+        match components.as_slice() {
+            ["usr", "bin", name] if !name.is_empty() => Some(0),
+            ["usr", "lib", _, name] if !name.is_empty() => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Considers a normalized candidate path and its contents.
+    pub(crate) fn offer(&mut self, path: String, data: Vec<u8>) {
+        let Some(rank) = Self::rank(&path) else {
+            return;
+        };
+        if !data.starts_with(b"\x7fELF") {
+            return;
+        }
+        let better = self.best.as_ref().is_none_or(|(best_rank, best)| {
+            (rank, std::cmp::Reverse(data.len())) < (*best_rank, std::cmp::Reverse(best.data.len()))
+        });
+        if better {
+            self.best = Some((rank, PackagedFile { path, data }));
+        }
+    }
+
+    /// Returns the selected executable.
+    pub(crate) fn finish(self, package: &str) -> Result<PackagedFile> {
+        self.best.map(|(_, file)| file).ok_or_else(|| {
+            Error::InvalidContainer(format!(
+                "{package} contains no ELF executable under usr/bin or usr/lib"
+            ))
+        })
+    }
+}
+
+/// Picks the application executable from a squashfs image, reading
+/// candidates in rank order so that libraries under `usr/lib` are only read
+/// when `usr/bin` holds no ELF file.
+pub(crate) fn pick_from_squashfs(image: &SquashFs, package: &str) -> Result<PackagedFile> {
+    let mut candidates = image
+        .files()?
+        .into_iter()
+        .filter_map(|file| ExecutablePicker::rank(&file.path).map(|rank| (rank, file)))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(rank, _)| *rank);
+
+    let mut picker = ExecutablePicker::default();
+    let mut current_rank = None;
+    for (rank, file) in candidates {
+        if current_rank != Some(rank) && picker.best.is_some() {
+            break;
+        }
+        current_rank = Some(rank);
+        let data = image.read(&file)?;
+        picker.offer(file.path, data);
+    }
+    picker.finish(package)
+}
@@ -0,0 +1,124 @@
+//! RPM package input.
+//!
+//! An RPM is a fixed-size lead, a signature header, the main header and a
+//! compressed `cpio` payload holding the installed file tree. The headers are
+//! only skipped; the payload compressor is detected from its magic bytes.
+
+use super::package::{self, ExecutablePicker, PackagedFile};
+use crate::bytes::be_u32;
+use crate::error::{Error, Result};
+
+const LEAD_MAGIC: &[u8; 4] = b"\xed\xab\xee\xdb";
+const LEAD_SIZE: usize = 96;
+const HEADER_MAGIC: &[u8; 3] = b"\x8e\xad\xe8";
+const HEADER_INTRO_SIZE: usize = 16;
+const INDEX_ENTRY_SIZE: usize = 16;
+
+const CPIO_NEWC_MAGIC: &[u8; 6] = b"070701";
+const CPIO_CRC_MAGIC: &[u8; 6] = b"070702";
+const CPIO_HEADER_SIZE: usize = 110;
+const CPIO_TRAILER: &str = "TRAILER!!!";
+const MODE_TYPE_MASK: u32 = 0o170000;
+const MODE_REGULAR: u32 = 0o100000;
+
+/// Returns true if `data` starts with an RPM lead.
+pub fn is_rpm(data: &[u8]) -> bool {
+    data.len() >= LEAD_SIZE && data.starts_with(LEAD_MAGIC)
+}
+
+/// Locates the application executable in the package payload.
+pub fn open_app(data: &[u8]) -> Result<PackagedFile> {
+    if !is_rpm(data) {
+        return Err(invalid("missing RPM lead"));
+    }
+    let signature_len = header_len(data, LEAD_SIZE)?;
+    // The signature header is padded to an 8-byte boundary.
+    let header_start = LEAD_SIZE + signature_len.next_multiple_of(8);
+    let main_len = header_len(data, header_start)?;
+    let payload = data
+        .get(header_start + main_len..)
+        .ok_or_else(|| invalid("truncated RPM header"))?;
+
+    let cpio = package::decompress(payload)?;
+    let mut picker = ExecutablePicker::default();
+    for entry in cpio_entries(&cpio)? {
+        if entry.mode & MODE_TYPE_MASK != MODE_REGULAR {
+            continue;
+        }
+        if let Some(path) = ExecutablePicker::candidate(&entry.name) {
+            picker.offer(path, entry.data.to_vec());
+        }
+    }
+    picker.finish("package")
+}
+
+/// Returns the total length of the header structure at `offset`.
+fn header_len(data: &[u8], offset: usize) -> Result<usize> {
+    if data.get(offset..offset + 3) != Some(HEADER_MAGIC) {
+        return Err(invalid("missing RPM header magic"));
+    }
+    let entries = be_u32(data, offset + 8).ok_or_else(truncated)? as usize;
+    let store = be_u32(data, offset + 12).ok_or_else(truncated)? as usize;
+    Ok(HEADER_INTRO_SIZE + entries * INDEX_ENTRY_SIZE + store)
+}
+
+struct CpioEntry<'a> {
+    name: String,
+    mode: u32,
+    data: &'a [u8],
+}
+
+/// Parses a `newc` cpio archive.
+fn cpio_entries(data: &[u8]) -> Result<Vec<CpioEntry<'_>>> {
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+    loop {
+        let header = data
+            .get(cursor..cursor + CPIO_HEADER_SIZE)
+            .ok_or_else(|| invalid("truncated cpio archive"))?;
+        if !header.starts_with(CPIO_NEWC_MAGIC) && !header.starts_with(CPIO_CRC_MAGIC) {
+            return Err(invalid(
+                "unsupported cpio format; only newc payloads are supported",
+            ));
+        }
+        let field = |index: usize| {
+            let start = 6 + index * 8;
+            std::str::from_utf8(&header[start..start + 8])
+                .ok()
+                .and_then(|value| u32::from_str_radix(value, 16).ok())
+                .ok_or_else(|| invalid("invalid cpio header field"))
+        };
+        let mode = field(1)?;
+        let size = field(6)? as usize;
+        let name_size = field(11)? as usize;
+
+        let name_start = cursor + CPIO_HEADER_SIZE;
+        let name = data
+            .get(name_start..name_start + name_size)
+            .ok_or_else(truncated)?;
+        let name = String::from_utf8_lossy(name.strip_suffix(&[0]).unwrap_or(name)).into_owned();
+        if name == CPIO_TRAILER {
+            break;
+        }
+
+        let data_start = (name_start + name_size).next_multiple_of(4);
+        let contents = data
+            .get(data_start..data_start + size)
+            .ok_or_else(truncated)?;
+        entries.push(CpioEntry {
+            name,
+            mode,
+            data: contents,
+        });
+        cursor = (data_start + size).next_multiple_of(4);
+    }
+    Ok(entries)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated RPM payload")
+}
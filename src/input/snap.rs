@@ -0,0 +1,67 @@
+//! Snap package input.
+//!
+//! A snap is a bare squashfs image. The executables it exposes are listed as
+//! app `command`s in `meta/snap.yaml`; when none of them is an ELF file, the
+//! usual `usr/bin` search is used instead.
+
+use super::package::{self, PackagedFile};
+use super::squashfs::{is_squashfs, SquashFs};
+use crate::error::Result;
+
+const SNAP_YAML: &str = "meta/snap.yaml";
+
+/// Returns true if `data` is a squashfs image, which is how snaps are
+/// distributed.
+pub fn is_snap(data: &[u8]) -> bool {
+    is_squashfs(data)
+}
+
+/// Locates the application executable inside the snap.
+pub fn open_app(data: &[u8]) -> Result<PackagedFile> {
+    let image = SquashFs::parse(data)?;
+    let files = image.files()?;
+
+    if let Some(snap_yaml) = files.iter().find(|file| file.path == SNAP_YAML) {
+        let snap_yaml = image.read(snap_yaml)?;
+        for command in app_commands(&String::from_utf8_lossy(&snap_yaml)) {
+            let Some(file) = files.iter().find(|file| file.path == command) else {
+                continue;
+            };
+            let data = image.read(file)?;
+            if data.starts_with(b"\x7fELF") {
+                return Ok(PackagedFile {
+                    path: file.path.clone(),
+                    data,
+                });
+            }
+        }
+    }
+
+    package::pick_from_squashfs(&image, "snap")
+}
+
+/// Returns the paths mentioned by `command:` keys in `snap.yaml`, relative to
+/// the snap root.
+///
+/// Only the line-oriented subset of YAML that snapcraft emits is understood.
+/// Each whitespace-separated word of a command is returned so that wrappers
+/// such as `bin/launcher $SNAP/usr/bin/app` still yield the app.
+fn app_commands(snap_yaml: &str) -> Vec<String> {
+    snap_yaml
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("command:"))
+        .flat_map(|command| {
+            command
+                .trim()
+                .trim_matches(['\'', '"'])
+                .split_whitespace()
+                .map(|word| {
+                    word.trim_start_matches("$SNAP/")
+                        .trim_start_matches("${SNAP}/")
+                        .trim_start_matches('/')
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
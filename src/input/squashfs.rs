@@ -0,0 +1,343 @@
+//! Read-only squashfs (version 4) access.
+//!
+//! Metadata blocks are decompressed on demand while walking the directory
+//! tree; file contents are assembled from data blocks and the tail fragment.
+
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::Read;
+
+const MAGIC: &[u8; 4] = b"hsqs";
+const SUPERBLOCK_SIZE: usize = 96;
+const METADATA_SIZE: usize = 8192;
+const METADATA_UNCOMPRESSED: u16 = 0x8000;
+const DATA_UNCOMPRESSED: u32 = 0x0100_0000;
+const NO_FRAGMENT: u32 = 0xffff_ffff;
+
+const COMPRESSOR_ZLIB: u16 = 1;
+const COMPRESSOR_LZMA: u16 = 2;
+const COMPRESSOR_LZO: u16 = 3;
+const COMPRESSOR_XZ: u16 = 4;
+const COMPRESSOR_LZ4: u16 = 5;
+const COMPRESSOR_ZSTD: u16 = 6;
+
+const INODE_DIR: u16 = 1;
+const INODE_FILE: u16 = 2;
+const INODE_EXT_DIR: u16 = 8;
+const INODE_EXT_FILE: u16 = 9;
+
+/// Returns true if `data` starts with a squashfs superblock.
+pub fn is_squashfs(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// A regular file found while walking the filesystem.
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    /// Path relative to the filesystem root.
+    pub path: String,
+    pub size: u64,
+    blocks_start: u64,
+    fragment: u32,
+    fragment_offset: u32,
+    block_sizes: Vec<u32>,
+}
+
+/// A squashfs image backed by its raw bytes.
+pub struct SquashFs<'a> {
+    data: &'a [u8],
+    block_size: u32,
+    compressor: u16,
+    root_inode: u64,
+    inode_table: u64,
+    directory_table: u64,
+    fragment_table: u64,
+    /// Decompressed metadata blocks keyed by absolute position, with the
+    /// position of the following block.
+    metadata: RefCell<HashMap<u64, (Vec<u8>, u64)>>,
+}
+
+impl<'a> SquashFs<'a> {
+    /// Parses the superblock of the image in `data`.
+    pub fn parse(data: &'a [u8]) -> Result<Self> {
+        if !is_squashfs(data) || data.len() < SUPERBLOCK_SIZE {
+            return Err(invalid("missing squashfs superblock"));
+        }
+        let major = le_u16(data, 28).ok_or_else(truncated)?;
+        if major != 4 {
+            return Err(invalid(format!(
+                "squashfs version {major} is not supported"
+            )));
+        }
+        let compressor = le_u16(data, 20).ok_or_else(truncated)?;
+        match compressor {
+            COMPRESSOR_ZLIB | COMPRESSOR_XZ | COMPRESSOR_ZSTD => {}
+            COMPRESSOR_LZMA => return Err(invalid("lzma-compressed squashfs is not supported")),
+            COMPRESSOR_LZO => return Err(invalid("lzo-compressed squashfs is not supported")),
+            COMPRESSOR_LZ4 => return Err(invalid("lz4-compressed squashfs is not supported")),
+            other => return Err(invalid(format!("unknown squashfs compressor {other}"))),
+        }
+
+        Ok(Self {
+            data,
+            block_size: le_u32(data, 12).ok_or_else(truncated)?,
+            compressor,
+            root_inode: le_u64(data, 32)?,
+            inode_table: le_u64(data, 64)?,
+            directory_table: le_u64(data, 72)?,
+            fragment_table: le_u64(data, 80)?,
+            metadata: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Lists every regular file in the image.
+    pub fn files(&self) -> Result<Vec<FileEntry>> {
+        let mut files = Vec::new();
+        self.walk(self.root_inode, "", &mut files, 0)?;
+        Ok(files)
+    }
+
+    /// Returns the regular file at `path`, if present.
+    pub fn file(&self, path: &str) -> Result<Option<FileEntry>> {
+        let path = path.trim_start_matches('/');
+        Ok(self.files()?.into_iter().find(|file| file.path == path))
+    }
+
+    /// Reads the contents of `file`.
+    pub fn read(&self, file: &FileEntry) -> Result<Vec<u8>> {
+        let size = usize::try_from(file.size).map_err(|_| truncated())?;
+        let mut out = Vec::with_capacity(size.min(self.data.len()));
+        let mut position = usize::try_from(file.blocks_start).map_err(|_| truncated())?;
+        for &block in &file.block_sizes {
+            let stored = (block & !DATA_UNCOMPRESSED) as usize;
+            if stored == 0 {
+                // Sparse block.
+                out.resize(out.len() + self.block_size as usize, 0);
+                continue;
+            }
+            let raw = self
+                .data
+                .get(position..position + stored)
+                .ok_or_else(truncated)?;
+            position += stored;
+            if block & DATA_UNCOMPRESSED != 0 {
+                out.extend_from_slice(raw);
+            } else {
+                out.extend(self.decompress(raw)?);
+            }
+        }
+
+        if file.fragment != NO_FRAGMENT {
+            let fragment = self.fragment(file.fragment)?;
+            let start = file.fragment_offset as usize;
+            let tail = size.saturating_sub(out.len());
+            out.extend_from_slice(fragment.get(start..start + tail).ok_or_else(truncated)?);
+        }
+        out.truncate(size);
+        Ok(out)
+    }
+
+    fn walk(
+        &self,
+        inode: u64,
+        prefix: &str,
+        files: &mut Vec<FileEntry>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > 64 {
+            return Err(invalid("squashfs directory tree is too deep"));
+        }
+        let header = self.read_metadata(self.inode_table, inode, 56)?;
+        let (start_block, listing_size, offset) = match le_u16(&header, 0) {
+            Some(INODE_DIR) => (
+                le_u32(&header, 16).ok_or_else(truncated)?,
+                u32::from(le_u16(&header, 24).ok_or_else(truncated)?),
+                le_u16(&header, 26).ok_or_else(truncated)?,
+            ),
+            Some(INODE_EXT_DIR) => (
+                le_u32(&header, 24).ok_or_else(truncated)?,
+                le_u32(&header, 20).ok_or_else(truncated)?,
+                le_u16(&header, 34).ok_or_else(truncated)?,
+            ),
+            _ => return Err(invalid("squashfs inode is not a directory")),
+        };
+        // The stored size counts three bytes for the implicit "." and "..".
+        let listing_size = (listing_size as usize).saturating_sub(3);
+        if listing_size == 0 {
+            return Ok(());
+        }
+        let reference = (u64::from(start_block) << 16) | u64::from(offset);
+        let listing = self.read_metadata(self.directory_table, reference, listing_size)?;
+
+        let mut cursor = 0;
+        while cursor + 12 <= listing.len() {
+            let count = le_u32(&listing, cursor).ok_or_else(truncated)? as usize + 1;
+            let inode_block = le_u32(&listing, cursor + 4).ok_or_else(truncated)?;
+            cursor += 12;
+            for _ in 0..count {
+                let inode_offset = le_u16(&listing, cursor).ok_or_else(truncated)?;
+                let kind = le_u16(&listing, cursor + 4).ok_or_else(truncated)?;
+                let name_len = usize::from(le_u16(&listing, cursor + 6).ok_or_else(truncated)?) + 1;
+                let name = listing
+                    .get(cursor + 8..cursor + 8 + name_len)
+                    .ok_or_else(truncated)?;
+                let name = String::from_utf8_lossy(name);
+                cursor += 8 + name_len;
+
+                let path = if prefix.is_empty() {
+                    name.into_owned()
+                } else {
+                    format!("{prefix}/{name}")
+                };
+                let child = (u64::from(inode_block) << 16) | u64::from(inode_offset);
+                match kind {
+                    INODE_DIR => self.walk(child, &path, files, depth + 1)?,
+                    INODE_FILE => files.push(self.file_entry(child, path)?),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn file_entry(&self, inode: u64, path: String) -> Result<FileEntry> {
+        let header = self.read_metadata(self.inode_table, inode, 56)?;
+        let (blocks_start, fragment, fragment_offset, size, sizes_at) = match le_u16(&header, 0) {
+            Some(INODE_FILE) => (
+                u64::from(le_u32(&header, 16).ok_or_else(truncated)?),
+                le_u32(&header, 20).ok_or_else(truncated)?,
+                le_u32(&header, 24).ok_or_else(truncated)?,
+                u64::from(le_u32(&header, 28).ok_or_else(truncated)?),
+                32,
+            ),
+            Some(INODE_EXT_FILE) => (
+                le_u64(&header, 16)?,
+                le_u32(&header, 44).ok_or_else(truncated)?,
+                le_u32(&header, 48).ok_or_else(truncated)?,
+                le_u64(&header, 24)?,
+                56,
+            ),
+            _ => return Err(invalid("squashfs inode is not a regular file")),
+        };
+
+        let block_size = u64::from(self.block_size);
+        let block_count = if fragment == NO_FRAGMENT {
+            size.div_ceil(block_size)
+        } else {
+            size / block_size
+        } as usize;
+        let inode_len = sizes_at + block_count * 4;
+        let inode = self.read_metadata(self.inode_table, inode, inode_len)?;
+        let block_sizes = (0..block_count)
+            .map(|index| le_u32(&inode, sizes_at + index * 4).ok_or_else(truncated))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(FileEntry {
+            path,
+            size,
+            blocks_start,
+            fragment,
+            fragment_offset,
+            block_sizes,
+        })
+    }
+
+    fn fragment(&self, index: u32) -> Result<Vec<u8>> {
+        // The fragment table is a list of pointers to metadata blocks holding
+        // 16-byte fragment entries.
+        let per_block = (METADATA_SIZE / 16) as u32;
+        let pointer_at = usize::try_from(self.fragment_table).map_err(|_| truncated())?
+            + (index / per_block) as usize * 8;
+        let block = le_u64(self.data, pointer_at)?;
+        let entry = self.read_metadata(block, u64::from(index % per_block) * 16, 16)?;
+        let start = usize::try_from(le_u64(&entry, 0)?).map_err(|_| truncated())?;
+        let stored = le_u32(&entry, 8).ok_or_else(truncated)?;
+        let raw = self
+            .data
+            .get(start..start + (stored & !DATA_UNCOMPRESSED) as usize)
+            .ok_or_else(truncated)?;
+        if stored & DATA_UNCOMPRESSED != 0 {
+            Ok(raw.to_vec())
+        } else {
+            self.decompress(raw)
+        }
+    }
+
+    /// Reads `len` bytes of metadata starting at `reference`, a
+    /// `(block offset << 16) | offset` pair relative to `table`.
+    fn read_metadata(&self, table: u64, reference: u64, len: usize) -> Result<Vec<u8>> {
+        let mut position = table + (reference >> 16);
+        let mut offset = (reference & 0xffff) as usize;
+        let mut out = Vec::with_capacity(len);
+        while out.len() < len {
+            let (block, next) = self.metadata_block(position)?;
+            let available = block.get(offset..).unwrap_or_default();
+            if available.is_empty() {
+                // Inode headers are read with a fixed-size window that may run
+                // past the last block of the table.
+                if out.is_empty() {
+                    return Err(truncated());
+                }
+                break;
+            }
+            let take = available.len().min(len - out.len());
+            out.extend_from_slice(&available[..take]);
+            offset = 0;
+            position = next;
+        }
+        Ok(out)
+    }
+
+    fn metadata_block(&self, position: u64) -> Result<(Vec<u8>, u64)> {
+        if let Some(cached) = self.metadata.borrow().get(&position) {
+            return Ok(cached.clone());
+        }
+        let start = usize::try_from(position).map_err(|_| truncated())?;
+        let header = le_u16(self.data, start).ok_or_else(truncated)?;
+        let stored = usize::from(header & !METADATA_UNCOMPRESSED);
+        let raw = self
+            .data
+            .get(start + 2..start + 2 + stored)
+            .ok_or_else(truncated)?;
+        let block = if header & METADATA_UNCOMPRESSED != 0 {
+            raw.to_vec()
+        } else {
+            self.decompress(raw)?
+        };
+        let entry = (block, position + 2 + stored as u64);
+        self.metadata.borrow_mut().insert(position, entry.clone());
+        Ok(entry)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        let result = match self.compressor {
+            COMPRESSOR_ZLIB => flate2::read::ZlibDecoder::new(data).read_to_end(&mut out),
+            COMPRESSOR_XZ => lzma_rs::xz_decompress(&mut &data[..], &mut out)
+                .map(|()| 0)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+            COMPRESSOR_ZSTD => ruzstd::decoding::StreamingDecoder::new(data)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+                .and_then(|mut decoder| decoder.read_to_end(&mut out)),
+            _ => unreachable!("compressor validated in parse"),
+        };
+        result.map_err(|err| invalid(format!("failed to decompress squashfs block: {err}")))?;
+        Ok(out)
+    }
+}
+
+fn le_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(truncated)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated squashfs image")
+}
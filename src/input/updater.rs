@@ -0,0 +1,176 @@
+//! Tauri updater artifact input.
+//!
+//! The updater downloads a `.tar.gz` of the `.app` bundle on macOS and, before
+//! Tauri 2, of the AppImage on Linux, or a `.zip` of the NSIS or MSI installer
+//! on Windows. Each artifact is published with a minisign signature in a
+//! `.sig` file next to it.
+
+use super::app_bundle;
+use super::package::{self, PackagedFile};
+use super::{appimage, archive, msi, nsis};
+use crate::binary::BundleInfo;
+use crate::error::{Error, Result};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const GZIP_MAGIC: &[u8; 2] = b"\x1f\x8b";
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8; 5] = b"ustar";
+/// Extension of the signature published next to an updater artifact.
+pub const SIGNATURE_EXTENSION: &str = "sig";
+
+/// The application read out of an updater artifact.
+#[derive(Debug, Clone)]
+pub struct UpdaterApp {
+    /// Path of the executable inside the artifact, e.g.
+    /// `Example.app/Contents/MacOS/Example`.
+    pub path: String,
+    pub data: Vec<u8>,
+    /// The `Info.plist` of the `.app` bundle, for macOS artifacts.
+    pub info: Option<BundleInfo>,
+}
+
+/// Returns true if `data` is a gzip-compressed tar archive.
+pub fn is_tar_gz(data: &[u8]) -> bool {
+    if !data.starts_with(GZIP_MAGIC) {
+        return false;
+    }
+    let mut header = [0; TAR_MAGIC_OFFSET + TAR_MAGIC.len()];
+    flate2::read::GzDecoder::new(data)
+        .read_exact(&mut header)
+        .is_ok_and(|()| header[TAR_MAGIC_OFFSET..].starts_with(TAR_MAGIC))
+}
+
+/// Returns the artifact a `.sig` file signs, if `path` is one and the
+/// artifact exists next to it.
+pub fn signed_artifact(path: &Path) -> Option<PathBuf> {
+    if path.extension()? != SIGNATURE_EXTENSION {
+        return None;
+    }
+    let artifact = path.with_extension("");
+    artifact.is_file().then_some(artifact)
+}
+
+/// Locates the application executable in a `.tar.gz` updater artifact.
+///
+/// A `.app` bundle is resolved through its `Info.plist`. Otherwise the
+/// largest AppImage or executable is selected and, if it is an AppImage,
+/// opened in turn.
+pub fn open_app(data: &[u8]) -> Result<UpdaterApp> {
+    let tar = package::decompress(data)?;
+    let mut files = Vec::new();
+    let mut archive = tar::Archive::new(tar.as_slice());
+    let entries = archive
+        .entries()
+        .map_err(|err| invalid(format!("failed to read updater archive: {err}")))?;
+    for entry in entries {
+        let mut entry =
+            entry.map_err(|err| invalid(format!("failed to read updater archive: {err}")))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = path.trim_start_matches("./").trim_start_matches('/');
+        if path.starts_with("__MACOSX/") {
+            continue;
+        }
+        let path = path.to_string();
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        files.push(PackagedFile {
+            path,
+            data: contents,
+        });
+    }
+
+    if let Some(app) = open_bundle(&mut files)? {
+        return Ok(app);
+    }
+    let file = files
+        .into_iter()
+        .filter(|file| is_executable(&file.data))
+        .max_by_key(|file| file.data.len())
+        .ok_or_else(|| invalid("updater archive contains no executable"))?;
+    let (path, data) = open_installer(file.path, file.data)?;
+    Ok(UpdaterApp {
+        path,
+        data,
+        info: None,
+    })
+}
+
+/// Opens the installer or AppImage at `path`, which Windows and Linux
+/// updater artifacts wrap, returning the path and contents of the
+/// executable it installs. Any other file is returned as is.
+pub(crate) fn open_installer(path: String, data: Vec<u8>) -> Result<(String, Vec<u8>)> {
+    if nsis::is_nsis(&data) {
+        let app = nsis::open_app(&data)?;
+        Ok((format!("{path}/{}", app.file_name), app.executable))
+    } else if msi::is_msi(&data) {
+        let app = msi::open_app(&data)?;
+        Ok((format!("{path}/{}", app.file_name), app.executable))
+    } else if appimage::is_appimage(&data) {
+        let app = appimage::open_app(&data)?;
+        Ok((format!("{path}/{}", app.path), app.data))
+    } else {
+        Ok((path, data))
+    }
+}
+
+/// Takes the main executable of the `.app` bundle among `files`, if there
+/// is one.
+fn open_bundle(files: &mut Vec<PackagedFile>) -> Result<Option<UpdaterApp>> {
+    let Some(contents) = files.iter().find_map(|file| {
+        let (bundle, _) = file.path.split_once(".app/Contents/")?;
+        Some(format!("{bundle}.app/Contents"))
+    }) else {
+        return Ok(None);
+    };
+    let info = files
+        .iter()
+        .find(|file| file.path == format!("{contents}/Info.plist"))
+        .map(|file| app_bundle::bundle_info(&file.data))
+        .transpose()?;
+    let macos = format!("{contents}/MacOS/");
+    let path = match info.as_ref().and_then(|info| info.executable.as_ref()) {
+        Some(name) => format!("{macos}{name}"),
+        None => {
+            let mut executables = files.iter().filter(|file| {
+                file.path
+                    .strip_prefix(&macos)
+                    .is_some_and(|name| !name.contains('/'))
+            });
+            match (executables.next(), executables.next()) {
+                (Some(file), None) => file.path.clone(),
+                _ => {
+                    return Err(invalid(format!(
+                        "cannot determine the main executable of {contents}: Info.plist has no CFBundleExecutable"
+                    )))
+                }
+            }
+        }
+    };
+    let index = files
+        .iter()
+        .position(|file| file.path == path)
+        .ok_or_else(|| invalid(format!("updater archive has no {path}")))?;
+    let file = files.swap_remove(index);
+    Ok(Some(UpdaterApp {
+        path: file.path,
+        data: file.data,
+        info,
+    }))
+}
+
+fn is_executable(data: &[u8]) -> bool {
+    data.get(..4)
+        .and_then(|magic| magic.try_into().ok())
+        .is_some_and(archive::is_executable)
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidContainer(message.into())
+}
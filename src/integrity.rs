@@ -0,0 +1,201 @@
+//! Checks decoded assets against the integrity data a binary carries.
+//!
+//! rust-embed stores a SHA-256 of every file next to it. Tauri, when it adds
+//! hashes to the CSP of its pages, embeds a `'sha256-…'` source for each
+//! inline script and style. An asset that matches them was extracted
+//! correctly and is the one the binary was built with; a mismatch points at
+//! a scan error or a patched binary.
+
+use crate::asset::{sha256_hex, AssetTable};
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::html::{attribute, tags};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::BTreeSet;
+
+/// The CSP hash algorithms, as they prefix a hash source.
+const CSP_ALGORITHMS: &[&str] = &["sha256-", "sha384-", "sha512-"];
+/// Inline elements whose content CSP hashes cover, with their closing tags.
+const INLINE_ELEMENTS: &[(&[u8], &[u8])] = &[(b"<script", b"</script"), (b"<style", b"</style")];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityKind {
+    /// A SHA-256 of the asset stored next to it.
+    ContentHash,
+    /// A CSP hash source for an inline script or style of a page.
+    CspHash,
+}
+
+impl IntegrityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ContentHash => "content hash",
+            Self::CspHash => "CSP hash",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityCheck {
+    pub asset: String,
+    pub kind: IntegrityKind,
+    /// The hash the binary holds, if any matches.
+    pub expected: Option<String>,
+    /// The hash of the extracted content.
+    pub actual: String,
+}
+
+impl IntegrityCheck {
+    pub fn verified(&self) -> bool {
+        self.expected.as_ref() == Some(&self.actual)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub checks: Vec<IntegrityCheck>,
+    /// CSP hash sources found in the binary, including those of scripts
+    /// Tauri injects at runtime, which match no asset.
+    pub csp_hashes: usize,
+}
+
+impl IntegrityReport {
+    pub fn verified(&self) -> usize {
+        self.checks.iter().filter(|check| check.verified()).count()
+    }
+
+    pub fn mismatches(&self) -> impl Iterator<Item = &IntegrityCheck> {
+        self.checks.iter().filter(|check| !check.verified())
+    }
+}
+
+/// Checks the assets of `table` against the integrity data in `data`, the
+/// binary they were scanned from.
+///
+/// Inline scripts and styles are only checked when the binary holds CSP
+/// hashes at all, since Tauri leaves them out when CSP modification is
+/// disabled.
+pub fn verify_integrity(data: &[u8], table: &AssetTable) -> Result<IntegrityReport> {
+    let csp_hashes = csp_hashes(data);
+    let mut checks = Vec::new();
+    for asset in table.assets() {
+        if let Some(offset) = asset.location().content_hash_offset {
+            let content = decompress_asset(asset)?;
+            checks.push(IntegrityCheck {
+                asset: asset.name().to_string(),
+                kind: IntegrityKind::ContentHash,
+                expected: data.get(offset..offset + 32).map(hex),
+                actual: sha256_hex(&content),
+            });
+        }
+        let is_page = asset.name().ends_with(".html") || asset.name().ends_with(".htm");
+        if csp_hashes.is_empty() || asset.alias_of().is_some() || !is_page {
+            continue;
+        }
+        let content = decompress_asset(asset)?;
+        for inline in inline_contents(&content) {
+            let sources = [
+                format!("sha256-{}", base64(&Sha256::digest(inline))),
+                format!("sha384-{}", base64(&Sha384::digest(inline))),
+                format!("sha512-{}", base64(&Sha512::digest(inline))),
+            ];
+            let expected = sources
+                .iter()
+                .find(|source| csp_hashes.contains(source.as_str()))
+                .cloned();
+            checks.push(IntegrityCheck {
+                asset: asset.name().to_string(),
+                kind: IntegrityKind::CspHash,
+                actual: expected.clone().unwrap_or_else(|| sources[0].clone()),
+                expected,
+            });
+        }
+    }
+    Ok(IntegrityReport {
+        checks,
+        csp_hashes: csp_hashes.len(),
+    })
+}
+
+/// Reads the quoted CSP hash sources in `data`, without their quotes.
+fn csp_hashes(data: &[u8]) -> BTreeSet<&str> {
+    let mut hashes = BTreeSet::new();
+    for algorithm in CSP_ALGORITHMS {
+        let mut needle = vec![b'\''];
+        needle.extend_from_slice(algorithm.as_bytes());
+        for start in find_all(data, &needle) {
+            let source = &data[start + 1..];
+            let length = algorithm.len()
+                + source[algorithm.len()..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_alphanumeric() || b"+/=".contains(byte))
+                    .count();
+            if source.get(length) == Some(&b'\'') && length > algorithm.len() {
+                if let Ok(source) = std::str::from_utf8(&source[..length]) {
+                    hashes.insert(source);
+                }
+            }
+        }
+    }
+    hashes
+}
+
+/// The contents of the inline scripts and styles of a page, which CSP
+/// hashes cover. Scripts loaded with `src` are not inline.
+fn inline_contents(html: &[u8]) -> Vec<&[u8]> {
+    let lower = html.to_ascii_lowercase();
+    let mut contents = Vec::new();
+    for (open, close) in INLINE_ELEMENTS {
+        for (start, end) in tags(&lower, open) {
+            if attribute(&html[start..end], &lower[start..end], b"src").is_some() {
+                continue;
+            }
+            let body = end + 1;
+            if let Some(length) = find(&lower[body..], close) {
+                if length > 0 {
+                    contents.push(&html[body..body + length]);
+                }
+            }
+        }
+    }
+    contents
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0u32, |word, (index, &byte)| {
+            word | u32::from(byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(word >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
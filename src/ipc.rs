@@ -0,0 +1,216 @@
+//! The IPC commands the frontend can invoke.
+//!
+//! Handlers registered with `tauri::generate_handler!` leave no list in the
+//! binary: their names are string literals packed together with the rest of
+//! `.rodata`. The frontend names them, though, in every `invoke("greet")`
+//! call, and the injected bridge scripts do the same for Tauri's own
+//! commands. Plugin commands are also kept whole, as the
+//! `plugin:<name>|<command>` keys of the capabilities compiled into the
+//! binary.
+
+use crate::asset::AssetTable;
+use crate::audit::Location;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::image::BinaryImage;
+use crate::mime::is_text_asset;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// References kept per command; further calls only confirm it.
+const MAX_REFERENCES: usize = 5;
+
+/// Functions whose first argument is a command name: `invoke` from
+/// `@tauri-apps/api` and the globals it calls.
+const INVOKE_FUNCTIONS: &[&[u8]] = &[b"invoke", b"__TAURI_INVOKE__"];
+
+const PLUGIN_PREFIX: &[u8] = b"plugin:";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpcCommand {
+    /// The name passed to `invoke`, such as `greet` or
+    /// `plugin:fs|read_file`.
+    pub name: String,
+    /// The plugin the command belongs to, for `plugin:` commands.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    /// Where the command is invoked or allowed.
+    pub references: Vec<Location>,
+    /// Whether the binary's strings contain the name, as they do for the
+    /// app's own handlers.
+    pub in_binary: bool,
+}
+
+/// Finds the commands invoked by the assets of `table` or allowed in the
+/// binary they were scanned from, sorted by name.
+pub fn ipc_commands(image: &BinaryImage, table: &AssetTable) -> Result<Vec<IpcCommand>> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() || !is_text_asset(asset.name()) {
+            continue;
+        }
+        assets.push((asset.name().to_string(), decompress_asset(asset)?));
+    }
+    Ok(ipc_commands_in(
+        image.data(),
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`ipc_commands`], for a binary's bytes and its decoded assets given
+/// as name and content.
+pub fn ipc_commands_in<'a>(
+    binary: &[u8],
+    assets: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Vec<IpcCommand> {
+    let mut commands = BTreeMap::<String, Vec<Location>>::new();
+    let mut add = |name: String, asset: Option<&str>, offset: usize| {
+        let references = commands.entry(name).or_default();
+        let reference = Location {
+            asset: asset.map(str::to_string),
+            offset,
+        };
+        if references.len() < MAX_REFERENCES && !references.contains(&reference) {
+            references.push(reference);
+        }
+    };
+    for (name, data) in assets {
+        if !is_text_asset(name) {
+            continue;
+        }
+        for (offset, command) in invoked_commands(data) {
+            add(command, Some(name), offset);
+        }
+        for (offset, command) in plugin_commands(data) {
+            add(command, Some(name), offset);
+        }
+    }
+    for (offset, command) in invoked_commands(binary) {
+        add(command, None, offset);
+    }
+    for (offset, command) in plugin_commands(binary) {
+        add(command, None, offset);
+    }
+
+    commands
+        .into_iter()
+        .map(|(name, references)| {
+            let plugin = name
+                .strip_prefix("plugin:")
+                .and_then(|key| key.split_once('|'))
+                .map(|(plugin, _)| plugin.to_string());
+            let in_binary = references.iter().any(|reference| reference.asset.is_none())
+                || find(binary, name.as_bytes()).is_some();
+            IpcCommand {
+                name,
+                plugin,
+                references,
+                in_binary,
+            }
+        })
+        .collect()
+}
+
+/// Reads the string literal passed first to an invoke function, in calls
+/// such as `invoke("greet", …)` or, after bundling, `(0,r.invoke)("greet")`.
+fn invoked_commands(data: &[u8]) -> Vec<(usize, String)> {
+    let mut commands = Vec::new();
+    for function in INVOKE_FUNCTIONS {
+        for offset in find_all(data, function) {
+            if offset > 0 && is_identifier_byte(data[offset - 1]) {
+                continue;
+            }
+            let mut rest = &data[offset + function.len()..];
+            rest = rest.strip_prefix(b")").unwrap_or(rest);
+            let Some(rest) = rest.strip_prefix(b"(") else {
+                continue;
+            };
+            let rest = trim_start(rest);
+            let Some((&quote, rest)) = rest.split_first() else {
+                continue;
+            };
+            if !matches!(quote, b'"' | b'\'' | b'`') {
+                continue;
+            }
+            let Some(end) = rest.iter().position(|&byte| byte == quote) else {
+                continue;
+            };
+            let name = &rest[..end];
+            if is_command_name(name) {
+                let start = data.len() - rest.len();
+                commands.push((start, String::from_utf8_lossy(name).into_owned()));
+            }
+        }
+    }
+    commands
+}
+
+/// Reads `plugin:<name>|<command>` keys.
+fn plugin_commands(data: &[u8]) -> Vec<(usize, String)> {
+    find_all(data, PLUGIN_PREFIX)
+        .filter(|&offset| offset == 0 || !is_identifier_byte(data[offset - 1]))
+        .filter_map(|offset| {
+            let rest = &data[offset + PLUGIN_PREFIX.len()..];
+            let plugin = rest
+                .iter()
+                .take_while(|&&byte| is_identifier_byte(byte))
+                .count();
+            let command = rest
+                .get(plugin + 1..)?
+                .iter()
+                .take_while(|&&byte| byte.is_ascii_alphanumeric() || byte == b'_')
+                .count();
+            if plugin == 0 || rest[plugin] != b'|' || command == 0 {
+                return None;
+            }
+            let end = offset + PLUGIN_PREFIX.len() + plugin + 1 + command;
+            Some((
+                offset,
+                String::from_utf8_lossy(&data[offset..end]).into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// Accepts Rust function names and plugin command keys.
+fn is_command_name(name: &[u8]) -> bool {
+    if name.starts_with(PLUGIN_PREFIX) {
+        return plugin_commands(name)
+            .first()
+            .is_some_and(|(_, key)| key.len() == name.len());
+    }
+    name.first()
+        .is_some_and(|byte| byte.is_ascii_alphabetic() || *byte == b'_')
+        && name
+            .iter()
+            .all(|byte| byte.is_ascii_alphanumeric() || *byte == b'_')
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-')
+}
+
+fn trim_start(data: &[u8]) -> &[u8] {
+    let start = data
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    &data[start..]
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -1,20 +1,95 @@
 //! Library for extracting and replacing embedded Tauri assets.
+//!
+//! Scanning and decoding work on byte slices. Opening files, unpacking
+//! installers, exporting, repacking and reading process memory need the `fs`
+//! feature, which is enabled by default. The `brotli`, `gzip`, `zstd` and
+//! `lz4` features select the built-in codecs, and the `tokio` feature adds
+//! [`AsyncDumper`] for async services.
 
 pub mod asset;
+#[cfg(feature = "tokio")]
+pub mod async_dumper;
+pub mod audit;
+#[cfg(feature = "fs")]
+mod batch;
 pub mod binary;
+#[cfg_attr(not(feature = "fs"), allow(dead_code))]
+mod bytes;
+pub mod carve;
+#[cfg(feature = "charset")]
+pub mod charset;
+pub mod checkpoint;
 pub mod codec;
+#[cfg(feature = "fs")]
+pub mod corpus;
+pub mod deep_link;
+pub mod dev_server;
 pub mod dumper;
+pub mod entropy;
 pub mod error;
+#[cfg(feature = "fs")]
 pub mod export;
 pub mod extract;
+pub mod features;
+pub mod fingerprint;
+pub mod frontend;
+pub mod graph;
+mod html;
+pub mod i18n;
 pub mod image;
+#[cfg(feature = "fs")]
+pub mod input;
+pub mod integrity;
+pub mod ipc;
+#[cfg(feature = "fs")]
+pub mod licenses;
+#[cfg(feature = "fs")]
 pub mod manifest;
+pub mod mime;
+#[cfg(feature = "fs")]
+pub mod osv;
+pub mod packer;
+pub mod pe_resources;
+pub mod plugins;
+#[cfg(feature = "fs")]
+pub mod process;
+pub mod progress;
+#[cfg(feature = "fs")]
+pub mod provenance;
+pub mod remote;
+#[cfg(feature = "fs")]
 pub mod repack;
+pub mod report;
+#[cfg(feature = "fs")]
+pub mod roundtrip;
+pub mod routes;
+mod rust_embed;
+#[cfg(feature = "fs")]
+pub mod sbom;
+pub mod scan_cache;
+pub mod scripts;
+pub mod service_worker;
+#[cfg(feature = "signing")]
+pub mod signing;
+mod time;
+pub mod timings;
+#[cfg(feature = "wat")]
+pub mod wasm;
+pub mod webview;
 
-pub use asset::{Asset, AssetId, AssetLocation, AssetTable};
-pub use dumper::Dumper;
+pub use asset::{
+    Asset, AssetConflict, AssetEncoding, AssetId, AssetInfo, AssetLocation, AssetTable,
+};
+#[cfg(feature = "tokio")]
+pub use async_dumper::AsyncDumper;
+pub use codec::{AssetCodec, CodecRegistry};
+pub use dumper::{Dumper, DumperBuilder};
 pub use error::{Error, Result};
-pub use export::{ExportOptions, ExportSummary};
-pub use extract::AssetScanner;
+#[cfg(feature = "fs")]
+pub use export::{Dedupe, ExportFormat, ExportOptions, ExportSummary, Layout};
+pub use extract::{AssetScanner, ScanOptions, ScanProfile, Strictness};
 pub use image::BinaryImage;
+#[cfg(feature = "fs")]
 pub use repack::{RepackSummary, Repacker};
+#[cfg(feature = "fs")]
+pub use roundtrip::VerifyReport;
@@ -0,0 +1,210 @@
+//! License texts and third-party notices shipped with an application.
+//!
+//! Frontend bundlers emit notice files next to the code they minify, such as
+//! webpack's `main.js.LICENSE.txt` or Angular's `3rdpartylicenses.txt`, and
+//! packages install theirs under `usr/share/doc`. They are recognized by file
+//! name and copied into one directory for compliance reviews.
+
+use crate::asset::{safe_relative_path, AssetTable};
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory, inside an export, that license files are gathered into.
+pub const LICENSES_DIR: &str = "licenses";
+/// Summary of the gathered files, written into [`LICENSES_DIR`].
+pub const SUMMARY_FILE_NAME: &str = "summary.json";
+
+/// File name stems of license and notice files, lower-cased.
+const LICENSE_STEMS: &[&str] = &[
+    "license",
+    "licence",
+    "licenses",
+    "licences",
+    "copying",
+    "copyright",
+    "notice",
+    "notices",
+    "third_party_notices",
+    "third-party-notices",
+    "thirdpartynotices",
+    "thirdpartylicenses",
+    "third-party-licenses",
+    "3rdpartylicenses",
+];
+
+/// Extensions a license file may have. Code that merely mentions licenses,
+/// such as a `License.svelte` component, is not a license file.
+const LICENSE_EXTENSIONS: &[&str] = &["", "txt", "md", "markdown", "rst", "html"];
+
+/// Where a license file was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseOrigin {
+    /// An embedded asset.
+    Asset,
+    /// A file of the package or bundle the executable was read from.
+    Package,
+}
+
+#[derive(Debug, Clone)]
+pub struct LicenseFile {
+    pub origin: LicenseOrigin,
+    /// The asset name or the path inside the package.
+    pub path: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseSummary {
+    /// Every license identifier recognized in any file.
+    pub licenses: BTreeSet<String>,
+    pub files: Vec<LicenseSummaryEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseSummaryEntry {
+    pub origin: LicenseOrigin,
+    pub source: String,
+    /// Path of the copy, relative to the licenses directory.
+    pub path: PathBuf,
+    pub size: usize,
+    /// SPDX identifiers of the licenses recognized in the text.
+    pub licenses: Vec<String>,
+}
+
+/// Returns true if the file name of `path` names a license or notice file.
+pub fn is_license_file(path: &str) -> bool {
+    let name = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if name.ends_with(".license.txt") || name.ends_with(".licence.txt") {
+        return true;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, extension),
+        _ => (name.as_str(), ""),
+    };
+    // Suffixed names such as `LICENSE-MIT` or `license_apache.txt` count too.
+    let known_stem = LICENSE_STEMS.iter().any(|known| {
+        stem.strip_prefix(known)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['-', '_', '.']))
+    });
+    known_stem && LICENSE_EXTENSIONS.contains(&extension)
+}
+
+/// Returns the SPDX identifiers of the licenses whose text appears in
+/// `text`. Notice files bundle many licenses, so all matches are returned.
+pub fn detect_licenses(text: &[u8]) -> Vec<&'static str> {
+    let text = String::from_utf8_lossy(text).to_ascii_lowercase();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let has = |needle: &str| text.contains(needle);
+
+    let mut found = Vec::new();
+    if has("permission is hereby granted, free of charge")
+        || has("the mit license")
+        || has("@license mit")
+    {
+        found.push("MIT");
+    }
+    if has("apache license") && has("version 2.0") {
+        found.push("Apache-2.0");
+    }
+    if has("redistribution and use in source and binary forms") {
+        found.push(
+            if has("neither the name") || has("names of its contributors") {
+                "BSD-3-Clause"
+            } else {
+                "BSD-2-Clause"
+            },
+        );
+    }
+    if has("permission to use, copy, modify, and/or distribute this software") {
+        found.push("ISC");
+    }
+    if has("mozilla public license") {
+        found.push("MPL-2.0");
+    }
+    if has("gnu lesser general public license") {
+        found.push("LGPL");
+    } else if has("gnu general public license") {
+        found.push(if has("version 3") {
+            "GPL-3.0"
+        } else {
+            "GPL-2.0"
+        });
+    }
+    if has("this is free and unencumbered software released into the public domain") {
+        found.push("Unlicense");
+    }
+    if has("creative commons") {
+        found.push("CC");
+    }
+    found
+}
+
+/// Decompresses every asset that is a license or notice file.
+pub fn from_assets(table: &AssetTable) -> Result<Vec<LicenseFile>> {
+    table
+        .assets()
+        .iter()
+        .filter(|asset| asset.alias_of().is_none() && is_license_file(asset.name()))
+        .map(|asset| {
+            Ok(LicenseFile {
+                origin: LicenseOrigin::Asset,
+                path: asset.name().to_string(),
+                data: decompress_asset(asset)?,
+            })
+        })
+        .collect()
+}
+
+/// Copies `files` into `dir`, under `assets/` or `package/` by origin, and
+/// writes [`SUMMARY_FILE_NAME`]. Nothing is written when `files` is empty.
+pub fn write(files: &[LicenseFile], dir: &Path) -> Result<LicenseSummary> {
+    let mut summary = LicenseSummary {
+        licenses: BTreeSet::new(),
+        files: Vec::new(),
+    };
+    for file in files {
+        let Some(relative) = safe_relative_path(&file.path) else {
+            log::warn!("skipping license file with an unsafe path: {}", file.path);
+            continue;
+        };
+        let path = Path::new(match file.origin {
+            LicenseOrigin::Asset => "assets",
+            LicenseOrigin::Package => "package",
+        })
+        .join(relative);
+        let target = dir.join(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, &file.data)?;
+
+        let licenses = detect_licenses(&file.data)
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        summary.licenses.extend(licenses.iter().cloned());
+        summary.files.push(LicenseSummaryEntry {
+            origin: file.origin,
+            source: file.path.clone(),
+            path,
+            size: file.data.len(),
+            licenses,
+        });
+    }
+    if !summary.files.is_empty() {
+        fs::write(
+            dir.join(SUMMARY_FILE_NAME),
+            serde_json::to_vec_pretty(&summary)?,
+        )?;
+    }
+    Ok(summary)
+}
@@ -1,8 +1,5 @@
 mod cli;
 
-fn main() {
-    if let Err(error) = cli::run() {
-        eprintln!("error: {error:#}");
-        std::process::exit(1);
-    }
+fn main() -> std::process::ExitCode {
+    cli::run()
 }
@@ -1,7 +1,9 @@
-use crate::asset::{AssetLocation, AssetTable};
-use crate::binary::{BinaryKind, BinaryMetadata};
+use crate::asset::{AssetConflict, AssetLocation, AssetTable};
+use crate::binary::{BinaryKind, BinaryMetadata, BuildInfo, BundleInfo, CodeSignature};
+use crate::deep_link::DeepLinks;
 use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
@@ -13,6 +15,10 @@ pub struct Manifest {
     pub schema_version: u32,
     pub source: ManifestSource,
     pub assets: Vec<ManifestAsset>,
+    /// Names several headers of a table resolved to. Only the kept asset of
+    /// each is exported and listed in `assets`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<AssetConflict>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +28,14 @@ pub struct ManifestSource {
     pub file_size: usize,
     pub binary_kind: BinaryKind,
     pub architecture: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_signature: Option<CodeSignature>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<BuildInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deep_links: Option<DeepLinks>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<BundleInfo>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +45,66 @@ pub struct ManifestAsset {
     pub original_compressed_size: usize,
     pub decompressed_size: usize,
     pub compressed_sha256: String,
+    /// Name of the asset whose compressed data this entry shares.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    /// See [`Asset::confidence`](crate::Asset::confidence).
+    #[serde(default)]
+    pub confidence: u8,
+    /// Where the export wrote the asset, relative to the output directory,
+    /// when that is not its name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+}
+
+/// How an asset differs from the manifest entry of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DivergenceKind {
+    /// The asset's data differs from the manifest's.
+    Modified,
+    /// The manifest lists an asset the binary does not have.
+    Missing,
+    /// The binary has an asset the manifest does not list.
+    Added,
+}
+
+impl DivergenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Modified => "modified",
+            Self::Missing => "missing",
+            Self::Added => "added",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub asset: String,
+    pub kind: DivergenceKind,
+    /// The compressed SHA-256 the manifest lists.
+    pub expected: Option<String>,
+    /// The compressed SHA-256 of the asset in the binary.
+    pub actual: Option<String>,
+}
+
+/// The result of comparing a binary's assets with a manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attestation {
+    /// Whether the binary is byte for byte the one the manifest was made
+    /// from. A re-signed or notarized binary differs while its assets match.
+    pub binary_matches: bool,
+    /// Assets whose data matches the manifest.
+    pub matched: usize,
+    pub divergences: Vec<Divergence>,
+}
+
+impl Attestation {
+    /// Whether every asset matches the manifest and none was added.
+    pub fn is_intact(&self) -> bool {
+        self.divergences.is_empty()
+    }
 }
 
 impl Manifest {
@@ -44,6 +118,10 @@ impl Manifest {
                 file_size: metadata.file_size,
                 binary_kind: metadata.kind,
                 architecture: metadata.architecture.clone(),
+                code_signature: metadata.code_signature.clone(),
+                build: metadata.build.clone(),
+                deep_links: metadata.deep_links.clone(),
+                bundle: metadata.bundle.clone(),
             },
             assets: table
                 .assets()
@@ -54,8 +132,63 @@ impl Manifest {
                     original_compressed_size: asset.location().original_compressed_size,
                     decompressed_size: asset.decompressed_size(),
                     compressed_sha256: asset.compressed_sha256().to_string(),
+                    alias_of: asset.alias_of().map(str::to_string),
+                    confidence: asset.confidence(),
+                    output_path: None,
                 })
                 .collect(),
+            conflicts: table.conflicts().to_vec(),
+        }
+    }
+
+    /// Compares the assets of `table` with those the manifest lists, by name
+    /// and the SHA-256 of their compressed data.
+    pub fn attest(&self, table: &AssetTable) -> Attestation {
+        let expected = self
+            .assets
+            .iter()
+            .map(|asset| (asset.name.as_str(), asset.compressed_sha256.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        let actual = table
+            .assets()
+            .iter()
+            .map(|asset| (asset.name(), asset.compressed_sha256()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut matched = 0;
+        let mut divergences = Vec::new();
+        for (&name, &hash) in &expected {
+            let kind = match actual.get(name) {
+                Some(&actual) if actual == hash => {
+                    matched += 1;
+                    continue;
+                }
+                Some(_) => DivergenceKind::Modified,
+                None => DivergenceKind::Missing,
+            };
+            divergences.push(Divergence {
+                asset: name.to_string(),
+                kind,
+                expected: Some(hash.to_string()),
+                actual: actual.get(name).map(|hash| hash.to_string()),
+            });
+        }
+        for (&name, &hash) in &actual {
+            if !expected.contains_key(name) {
+                divergences.push(Divergence {
+                    asset: name.to_string(),
+                    kind: DivergenceKind::Added,
+                    expected: None,
+                    actual: Some(hash.to_string()),
+                });
+            }
+        }
+        divergences.sort_by(|a, b| a.asset.cmp(&b.asset));
+
+        Attestation {
+            binary_matches: table.metadata().sha256 == self.source.sha256,
+            matched,
+            divergences,
         }
     }
 
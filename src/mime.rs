@@ -0,0 +1,81 @@
+//! Content types of embedded assets, guessed from their names.
+
+/// Asset types by name, with the MIME types or MIME type prefixes they
+/// cover.
+pub const ASSET_TYPES: &[(&str, &[&str])] = &[
+    ("html", &["text/html"]),
+    ("js", &["text/javascript"]),
+    ("css", &["text/css"]),
+    ("json", &["application/json", "application/manifest+json"]),
+    ("wasm", &["application/wasm"]),
+    ("svg", &["image/svg+xml"]),
+    ("image", &["image/"]),
+    ("font", &["font/"]),
+    ("audio", &["audio/"]),
+    ("video", &["video/"]),
+    ("text", &["text/plain", "application/xml"]),
+];
+
+/// Returns the MIME types of the asset type `name`, such as `js` or
+/// `image`, as MIME types or prefixes ending in `/`.
+pub fn asset_type(name: &str) -> Option<&'static [&'static str]> {
+    ASSET_TYPES
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        .map(|(_, mimes)| *mimes)
+}
+
+/// Whether assets of type `mime` are text.
+pub fn is_text(mime: &str) -> bool {
+    mime.starts_with("text/")
+        || matches!(
+            mime,
+            "application/json" | "application/manifest+json" | "application/xml" | "image/svg+xml"
+        )
+}
+
+/// Whether the asset at `path` is text, such as a script, a page or a
+/// stylesheet, judging by its extension.
+pub fn is_text_asset(path: &str) -> bool {
+    is_text(mime_type(path))
+}
+
+/// Returns the MIME type for an asset path, based on its extension.
+///
+/// Unknown extensions map to `application/octet-stream`.
+pub fn mime_type(path: &str) -> &'static str {
+    let extension = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("html" | "htm") => "text/html",
+        Some("js" | "mjs" | "cjs") => "text/javascript",
+        Some("css") => "text/css",
+        Some("json" | "map") => "application/json",
+        Some("webmanifest") => "application/manifest+json",
+        Some("txt" | "env") => "text/plain",
+        Some("xml") => "application/xml",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        Some("wasm") => "application/wasm",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("ogg") => "audio/ogg",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
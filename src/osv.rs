@@ -0,0 +1,369 @@
+//! Known vulnerabilities of the bundled npm packages, from an offline OSV
+//! database.
+//!
+//! The database is a directory of OSV JSON records, a zip of them such as
+//! the `npm/all.zip` osv.dev publishes, or a single record. Only the npm
+//! packages records name are kept; a bundled package is affected when its
+//! version is listed, or falls in one of the `SEMVER` or `ECOSYSTEM` ranges,
+//! of a record naming it. Packages found without a version are never
+//! matched.
+
+use crate::error::{Error, Result};
+use crate::input::archive;
+use crate::sbom::BundledPackage;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+const NPM_ECOSYSTEM: &str = "npm";
+
+/// An advisory affecting a bundled package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vulnerability {
+    pub package: String,
+    pub version: String,
+    /// The OSV identifier, such as `GHSA-jf85-cpcp-j695`.
+    pub id: String,
+    /// Other identifiers of the advisory, such as its CVE.
+    pub aliases: Vec<String>,
+    pub summary: Option<String>,
+    /// The severity the database gives, such as `high` or `moderate`.
+    pub severity: Option<String>,
+    /// The first version that fixes the advisory, when the range names one.
+    pub fixed: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Record {
+    id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    affected: Vec<Affected>,
+    #[serde(default)]
+    database_specific: Option<DatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Affected {
+    package: Option<Package>,
+    #[serde(default)]
+    ranges: Vec<Range>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Range {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Event {
+    Introduced(String),
+    Fixed(String),
+    LastAffected(String),
+    Limit(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct DatabaseSpecific {
+    severity: Option<String>,
+}
+
+/// What one record says about one npm package.
+#[derive(Debug)]
+struct Entry {
+    advisory: usize,
+    ranges: Vec<Vec<Event>>,
+    versions: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Advisory {
+    id: String,
+    aliases: Vec<String>,
+    summary: Option<String>,
+    severity: Option<String>,
+}
+
+/// The npm advisories of an OSV database, by package name.
+#[derive(Debug, Default)]
+pub struct OsvDatabase {
+    advisories: Vec<Advisory>,
+    packages: HashMap<String, Vec<Entry>>,
+}
+
+impl OsvDatabase {
+    /// Loads the database at `path`: a directory of `.json` records, searched
+    /// recursively, a zip of them, or a single record. Records that fail to
+    /// parse are skipped with a warning.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut database = Self::default();
+        if path.is_dir() {
+            database.add_dir(path)?;
+        } else {
+            let data = fs::read(path)?;
+            if archive::is_zip(&data) {
+                database.add_zip(&data)?;
+            } else {
+                database.add_record(&path.display().to_string(), &data);
+            }
+        }
+        Ok(database)
+    }
+
+    /// Builds a database from OSV records given as JSON.
+    pub fn from_records<'a>(records: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let mut database = Self::default();
+        for (index, record) in records.into_iter().enumerate() {
+            database.add_record(&format!("record {index}"), record);
+        }
+        database
+    }
+
+    /// The number of advisories that name an npm package.
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Returns the advisories affecting `packages`, by package, version and
+    /// advisory identifier.
+    pub fn vulnerabilities(&self, packages: &[BundledPackage]) -> Vec<Vulnerability> {
+        let mut found = Vec::new();
+        for package in packages {
+            let Some(version) = &package.version else {
+                continue;
+            };
+            let Some(entries) = self.packages.get(&package.name) else {
+                continue;
+            };
+            for entry in entries {
+                let Some(fixed) = entry.affects(version) else {
+                    continue;
+                };
+                let advisory = &self.advisories[entry.advisory];
+                found.push(Vulnerability {
+                    package: package.name.clone(),
+                    version: version.clone(),
+                    id: advisory.id.clone(),
+                    aliases: advisory.aliases.clone(),
+                    summary: advisory.summary.clone(),
+                    severity: advisory.severity.clone(),
+                    fixed,
+                });
+            }
+        }
+        found.sort_by(|a, b| (&a.package, &a.version, &a.id).cmp(&(&b.package, &b.version, &b.id)));
+        found.dedup_by(|a, b| (&a.package, &a.version, &a.id) == (&b.package, &b.version, &b.id));
+        found
+    }
+
+    fn add_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.path());
+        for entry in entries {
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                self.add_dir(&path)?;
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                self.add_record(&path.display().to_string(), &fs::read(&path)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn add_zip(&mut self, data: &[u8]) -> Result<()> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(data))
+            .map_err(|err| invalid(format!("failed to read OSV database: {err}")))?;
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|err| invalid(format!("failed to read zip entry: {err}")))?;
+            if !entry.is_file() || !entry.name().ends_with(".json") {
+                continue;
+            }
+            let name = entry.name().to_string();
+            let mut record = Vec::new();
+            entry.read_to_end(&mut record)?;
+            self.add_record(&name, &record);
+        }
+        Ok(())
+    }
+
+    fn add_record(&mut self, name: &str, data: &[u8]) {
+        let record = match serde_json::from_slice::<Record>(data) {
+            Ok(record) => record,
+            Err(error) => {
+                log::warn!("skipping OSV record {name}: {error}");
+                return;
+            }
+        };
+        let advisory = self.advisories.len();
+        let mut named = false;
+        for affected in record.affected {
+            let Some(package) = affected.package else {
+                continue;
+            };
+            if package.ecosystem != NPM_ECOSYSTEM {
+                continue;
+            }
+            let ranges = affected
+                .ranges
+                .into_iter()
+                .filter(|range| range.kind == "SEMVER" || range.kind == "ECOSYSTEM")
+                .map(|range| range.events)
+                .collect();
+            self.packages.entry(package.name).or_default().push(Entry {
+                advisory,
+                ranges,
+                versions: affected.versions,
+            });
+            named = true;
+        }
+        if named {
+            self.advisories.push(Advisory {
+                id: record.id,
+                aliases: record.aliases,
+                summary: record.summary,
+                severity: record
+                    .database_specific
+                    .and_then(|specific| specific.severity)
+                    .map(|severity| severity.to_ascii_lowercase()),
+            });
+        }
+    }
+}
+
+impl Entry {
+    /// Returns whether `version` is affected, and with which fixed version.
+    fn affects(&self, version: &str) -> Option<Option<String>> {
+        if self.versions.iter().any(|listed| listed == version) {
+            return Some(None);
+        }
+        let version = SemVer::parse(version)?;
+        self.ranges
+            .iter()
+            .find_map(|events| range_affects(events, &version))
+    }
+}
+
+/// Evaluates a range's events in version order, as the OSV schema
+/// describes: `introduced` opens the range and `fixed`, `last_affected` and
+/// `limit` close it.
+fn range_affects(events: &[Event], version: &SemVer) -> Option<Option<String>> {
+    let mut events = events
+        .iter()
+        .filter_map(|event| {
+            let text = match event {
+                Event::Introduced(text)
+                | Event::Fixed(text)
+                | Event::LastAffected(text)
+                | Event::Limit(text) => text,
+            };
+            Some((SemVer::parse(text)?, event))
+        })
+        .collect::<Vec<_>>();
+    events.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut affected = false;
+    for (at, event) in &events {
+        match event {
+            Event::Introduced(_) if version >= at => affected = true,
+            Event::Fixed(_) | Event::Limit(_) if version >= at => affected = false,
+            Event::LastAffected(_) if version > at => affected = false,
+            _ => {}
+        }
+    }
+    affected.then(|| {
+        events.iter().find_map(|(at, event)| match event {
+            Event::Fixed(fixed) if at > version => Some(fixed.clone()),
+            _ => None,
+        })
+    })
+}
+
+/// A semantic version, ordered by precedence. Build metadata is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SemVer {
+    core: [u64; 3],
+    prerelease: Vec<String>,
+}
+
+impl SemVer {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.strip_prefix('v').unwrap_or(text);
+        let text = text.split('+').next()?;
+        let (core, prerelease) = match text.split_once('-') {
+            Some((core, prerelease)) => (core, prerelease.split('.').map(str::to_string).collect()),
+            None => (text, Vec::new()),
+        };
+        let mut parts = core.split('.');
+        let mut version = [0; 3];
+        for (index, part) in parts.by_ref().take(3).enumerate() {
+            version[index] = part.parse().ok()?;
+        }
+        parts.next().is_none().then_some(Self {
+            core: version,
+            prerelease,
+        })
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.core.cmp(&other.core).then_with(|| {
+            match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => {
+                    for (a, b) in self.prerelease.iter().zip(&other.prerelease) {
+                        let order = match (a.parse::<u64>(), b.parse::<u64>()) {
+                            (Ok(a), Ok(b)) => a.cmp(&b),
+                            (Ok(_), Err(_)) => Ordering::Less,
+                            (Err(_), Ok(_)) => Ordering::Greater,
+                            (Err(_), Err(_)) => a.cmp(b),
+                        };
+                        if order != Ordering::Equal {
+                            return order;
+                        }
+                    }
+                    self.prerelease.len().cmp(&other.prerelease.len())
+                }
+            }
+        })
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Message(message.into())
+}
@@ -0,0 +1,320 @@
+//! Detection of executable packers and protectors.
+//!
+//! A packed binary keeps its sections compressed until it runs, so its asset
+//! headers and their data cannot be found by a scan. UPX is the packer Tauri
+//! apps meet most, through release scripts that shrink the executable. It
+//! leaves its `UPX!` magic in the loader, its version in a copyright string
+//! and, in PE files, its `UPX0` and `UPX1` section names. Other Windows
+//! packers and protectors are known by the sections they add.
+//!
+//! [`analyze`] also looks for the traces packing and encryption leave
+//! whatever the tool: code as random as compressed data, code sections that
+//! only exist once the binary runs, and a stripped section table. Data
+//! sections are not judged by their entropy, since the Brotli assets of a
+//! Tauri app make `.rodata` look compressed anyway.
+
+use object::{Object, ObjectSection, ObjectSegment, SectionFlags};
+use serde::{Deserialize, Serialize};
+
+const UPX_MAGIC: &[u8] = b"UPX!";
+const UPX_ID: &[u8] = b"$Id: UPX ";
+const UPX_SECTIONS: [&[u8]; 2] = [b"UPX0\0\0\0\0", b"UPX1\0\0\0\0"];
+/// Bytes at the start and end of a file searched for the `UPX!` magic,
+/// which sits in the headers of a PE and in the loader and trailer of ELF
+/// and Mach-O files.
+const UPX_MAGIC_WINDOW: usize = 4096;
+/// PE section names that packers and protectors add, with the tool's name.
+const PACKER_SECTIONS: &[(&str, &str)] = &[
+    (".MPRESS1", "MPRESS"),
+    (".aspack", "ASPack"),
+    (".themida", "Themida"),
+    (".winlice", "WinLicense"),
+    (".vmp0", "VMProtect"),
+    (".enigma1", "Enigma Protector"),
+    ("PEC2", "PECompact"),
+    (".petite", "Petite"),
+    (".nsp0", "NsPack"),
+];
+/// Entropy, in bits per byte, above which code is taken to be compressed or
+/// encrypted. Machine code stays well below it.
+const CODE_ENTROPY_THRESHOLD: f64 = 7.0;
+/// Sections smaller than this are too short for a meaningful entropy.
+const MIN_ENTROPY_SIZE: usize = 1024;
+
+/// A packer found in a binary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Packer {
+    /// The packer's name, such as `UPX`.
+    pub name: String,
+    /// The packer version that packed the binary, when it is recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// How to get a binary that can be scanned.
+    pub hint: String,
+}
+
+impl std::fmt::Display for Packer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{} {version}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// How random the bytes of a section are.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SectionEntropy {
+    /// The section name, or the segment's for a binary without sections.
+    pub name: String,
+    pub file_offset: u64,
+    /// Bytes of the section stored in the file.
+    pub file_size: u64,
+    /// Bytes of the section once loaded.
+    pub virtual_size: u64,
+    pub executable: bool,
+    /// Shannon entropy of the stored bytes, from 0 to 8 bits per byte.
+    pub entropy: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndicatorKind {
+    /// A known packer or protector left its signature.
+    Packer,
+    /// Code is as random as compressed or encrypted data.
+    EncryptedCode,
+    /// Code exists only in memory, filled in when the binary runs.
+    RuntimeCode,
+    /// The section table was removed.
+    StrippedSections,
+}
+
+/// A sign that the binary is packed, encrypted or obfuscated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackingIndicator {
+    pub kind: IndicatorKind,
+    pub message: String,
+}
+
+/// The packing signs [`analyze`] found in a binary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PackingReport {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packers: Vec<Packer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indicators: Vec<PackingIndicator>,
+    /// Entropy of the whole file, in bits per byte.
+    pub entropy: f64,
+    pub sections: Vec<SectionEntropy>,
+}
+
+impl PackingReport {
+    /// Whether anything suggests the binary is packed or protected.
+    pub fn is_packed(&self) -> bool {
+        !self.indicators.is_empty()
+    }
+}
+
+/// Checks a binary for known packers and the traces packing and encryption
+/// leave. The sections are those of the binary's section table, or its
+/// segments if the table was stripped.
+pub fn analyze(data: &[u8]) -> PackingReport {
+    let packers = detect_all(data);
+    let mut indicators = packers
+        .iter()
+        .map(|packer| PackingIndicator {
+            kind: IndicatorKind::Packer,
+            message: format!("packed with {packer}"),
+        })
+        .collect::<Vec<_>>();
+
+    let (sections, stripped) = sections(data);
+    if stripped {
+        indicators.push(PackingIndicator {
+            kind: IndicatorKind::StrippedSections,
+            message: "the section table was stripped".to_string(),
+        });
+    }
+    for section in sections.iter().filter(|section| section.executable) {
+        if section.file_size as usize >= MIN_ENTROPY_SIZE
+            && section.entropy >= CODE_ENTROPY_THRESHOLD
+        {
+            indicators.push(PackingIndicator {
+                kind: IndicatorKind::EncryptedCode,
+                message: format!(
+                    "executable section {} has an entropy of {:.2} bits per byte, like compressed or encrypted data",
+                    section.name, section.entropy
+                ),
+            });
+        }
+        if section.file_size == 0 && section.virtual_size > 0 {
+            indicators.push(PackingIndicator {
+                kind: IndicatorKind::RuntimeCode,
+                message: format!(
+                    "executable section {} has no data in the file and is filled in at run time",
+                    section.name
+                ),
+            });
+        }
+    }
+
+    PackingReport {
+        packers,
+        indicators,
+        entropy: entropy(data),
+        sections,
+    }
+}
+
+/// Detects a known packer or protector, UPX first.
+pub fn detect(data: &[u8]) -> Option<Packer> {
+    detect_all(data).into_iter().next()
+}
+
+fn detect_all(data: &[u8]) -> Vec<Packer> {
+    let mut packers = detect_upx(data).into_iter().collect::<Vec<_>>();
+    let Ok(object) = object::File::parse(data) else {
+        return packers;
+    };
+    for (section, name) in PACKER_SECTIONS {
+        let found = object.sections().any(|s| s.name() == Ok(section));
+        if found && !packers.iter().any(|packer| packer.name == *name) {
+            packers.push(Packer {
+                name: name.to_string(),
+                version: None,
+                hint: "unpack it with a tool for this packer, or dump the running app with `tauri-dumper proc`".to_string(),
+            });
+        }
+    }
+    packers
+}
+
+/// Shannon entropy of `data`, in bits per byte.
+pub fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[usize::from(byte)] += 1;
+    }
+    let length = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / length;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+/// The sections of `data` with their entropy, and whether the section table
+/// was stripped, in which case the segments stand in for them.
+fn sections(data: &[u8]) -> (Vec<SectionEntropy>, bool) {
+    let Ok(object) = object::File::parse(data) else {
+        return (Vec::new(), false);
+    };
+    let stored = |offset: u64, size: u64| {
+        usize::try_from(offset)
+            .ok()
+            .zip(usize::try_from(size).ok())
+            .and_then(|(offset, size)| data.get(offset..offset.checked_add(size)?))
+            .unwrap_or_default()
+    };
+    let mut sections = Vec::new();
+    for section in object.sections() {
+        let name = section.name().unwrap_or_default();
+        if name.is_empty() {
+            continue;
+        }
+        let (file_offset, file_size) = section.file_range().unwrap_or_default();
+        let executable = match section.flags() {
+            SectionFlags::Elf { sh_flags } => sh_flags & u64::from(object::elf::SHF_EXECINSTR) != 0,
+            SectionFlags::MachO { flags } => {
+                flags
+                    & (object::macho::S_ATTR_PURE_INSTRUCTIONS
+                        | object::macho::S_ATTR_SOME_INSTRUCTIONS)
+                    != 0
+            }
+            SectionFlags::Coff { characteristics } => {
+                characteristics & object::pe::IMAGE_SCN_MEM_EXECUTE != 0
+            }
+            _ => section.kind() == object::SectionKind::Text,
+        };
+        sections.push(SectionEntropy {
+            name: name.to_string(),
+            file_offset,
+            file_size,
+            virtual_size: section.size(),
+            executable,
+            entropy: entropy(stored(file_offset, file_size)),
+        });
+    }
+    if !sections.is_empty() || object.format() != object::BinaryFormat::Elf {
+        return (sections, false);
+    }
+    for (index, segment) in object.segments().enumerate() {
+        let (file_offset, file_size) = segment.file_range();
+        let executable = matches!(
+            segment.flags(),
+            object::SegmentFlags::Elf { p_flags } if p_flags & object::elf::PF_X != 0
+        );
+        sections.push(SectionEntropy {
+            name: format!("segment {index}"),
+            file_offset,
+            file_size,
+            virtual_size: segment.size(),
+            executable,
+            entropy: entropy(stored(file_offset, file_size)),
+        });
+    }
+    (sections, true)
+}
+
+/// Detects a UPX-packed binary.
+pub fn detect_upx(data: &[u8]) -> Option<Packer> {
+    let head = &data[..data.len().min(UPX_MAGIC_WINDOW)];
+    let tail = &data[data.len().saturating_sub(UPX_MAGIC_WINDOW)..];
+    let has_magic = find(head, UPX_MAGIC).is_some() || find(tail, UPX_MAGIC).is_some();
+    let has_sections = UPX_SECTIONS.iter().all(|name| find(head, name).is_some());
+    let id_version = find(data, UPX_ID).map(|at| version_at(&data[at + UPX_ID.len()..]));
+    let is_upx = has_sections || (has_magic && id_version.is_some());
+    if !is_upx {
+        return None;
+    }
+    // PE files keep the version right before the magic, as in `4.24\0UPX!`.
+    let version = id_version.flatten().or_else(|| {
+        let at = find(head, UPX_MAGIC)?;
+        let start = head[..at.checked_sub(1)?]
+            .iter()
+            .rposition(|byte| !(byte.is_ascii_digit() || *byte == b'.'))
+            .map_or(0, |index| index + 1);
+        version_at(&head[start..at - 1])
+    });
+    Some(Packer {
+        name: "UPX".to_string(),
+        version,
+        hint: "unpack it with `upx -d` and scan the unpacked copy".to_string(),
+    })
+}
+
+/// Reads a dotted version number such as `4.2.4` at the start of `data`.
+fn version_at(data: &[u8]) -> Option<String> {
+    let length = data
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit() || **byte == b'.')
+        .count();
+    let version = std::str::from_utf8(&data[..length])
+        .ok()?
+        .trim_end_matches('.');
+    (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+        .then(|| version.to_string())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
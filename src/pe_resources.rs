@@ -0,0 +1,313 @@
+//! Resources in the `.rsrc` section of PE images.
+//!
+//! Tauri's Windows bundler embeds the application icon, a `VERSIONINFO`
+//! resource built from the app's config and an application manifest. They are
+//! read straight from the resource directory; other resource types are
+//! ignored.
+
+use crate::bytes::{le_u16, le_u32};
+use crate::error::{Error, Result};
+use object::pe::{
+    ImageNtHeaders32, ImageNtHeaders64, RT_GROUP_ICON, RT_ICON, RT_MANIFEST, RT_VERSION,
+};
+use object::read::pe::{ImageNtHeaders, PeFile, ResourceDirectory, ResourceDirectoryEntryData};
+use object::FileKind;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "fs")]
+const ICON_FILE_NAME: &str = "icon.ico";
+#[cfg(feature = "fs")]
+const VERSION_FILE_NAME: &str = "version.json";
+#[cfg(feature = "fs")]
+const APP_MANIFEST_FILE_NAME: &str = "app.manifest";
+
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef_04bd;
+
+#[derive(Debug, Clone, Default)]
+pub struct PeResources {
+    /// The first icon group, assembled into an `.ico` file.
+    pub icon: Option<Vec<u8>>,
+    pub version: Option<VersionInfo>,
+    /// The application manifest XML.
+    pub manifest: Option<String>,
+}
+
+/// The `VERSIONINFO` resource.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    /// The binary file version from `VS_FIXEDFILEINFO`, as `a.b.c.d`.
+    pub file_version: Option<String>,
+    /// The binary product version from `VS_FIXEDFILEINFO`, as `a.b.c.d`.
+    pub product_version: Option<String>,
+    /// The first string table, such as `ProductName`, `FileVersion` and
+    /// `CompanyName`.
+    pub strings: BTreeMap<String, String>,
+}
+
+impl PeResources {
+    /// Reads the resources of the PE image in `data`. Images without a
+    /// resource directory yield no resources.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        match FileKind::parse(data)? {
+            FileKind::Pe32 => Self::parse_pe::<ImageNtHeaders32>(data),
+            FileKind::Pe64 => Self::parse_pe::<ImageNtHeaders64>(data),
+            other => Err(Error::UnsupportedFormat(format!(
+                "{other:?} has no PE resources"
+            ))),
+        }
+    }
+
+    fn parse_pe<Pe: ImageNtHeaders>(data: &[u8]) -> Result<Self> {
+        let file = PeFile::<Pe>::parse(data)?;
+        let sections = file.section_table();
+        let Some(directory) = file
+            .data_directories()
+            .resource_directory(data, &sections)?
+        else {
+            return Ok(Self::default());
+        };
+        let resources = |kind| -> Result<Vec<(Option<u16>, &[u8])>> {
+            let mut found = Vec::new();
+            for (id, entry) in resource_entries(directory, kind)? {
+                let rva = entry.offset_to_data.get(object::LittleEndian);
+                let size = entry.size.get(object::LittleEndian) as usize;
+                match sections
+                    .pe_data_at(data, rva)
+                    .and_then(|data| data.get(..size))
+                {
+                    Some(bytes) => found.push((id, bytes)),
+                    None => log::debug!("resource data at RVA {rva:#x} is outside the file"),
+                }
+            }
+            Ok(found)
+        };
+
+        let icons = resources(RT_ICON)?
+            .into_iter()
+            .filter_map(|(id, data)| Some((id?, data)))
+            .collect::<BTreeMap<_, _>>();
+        let icon = resources(RT_GROUP_ICON)?
+            .first()
+            .and_then(|(_, group)| assemble_icon(group, &icons));
+        let version = resources(RT_VERSION)?
+            .first()
+            .and_then(|(_, data)| parse_version_info(data));
+        let manifest = resources(RT_MANIFEST)?.first().map(|(_, data)| {
+            let text = String::from_utf8_lossy(data);
+            text.trim_start_matches('\u{feff}').to_string()
+        });
+
+        Ok(Self {
+            icon,
+            version,
+            manifest,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.icon.is_none() && self.version.is_none() && self.manifest.is_none()
+    }
+
+    /// Writes `icon.ico`, `version.json` and `app.manifest` into `dir`, for
+    /// the resources present, and returns the written paths.
+    #[cfg(feature = "fs")]
+    pub fn write(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        if self.is_empty() {
+            return Ok(files);
+        }
+        fs::create_dir_all(dir)?;
+        if let Some(icon) = &self.icon {
+            files.push(dir.join(ICON_FILE_NAME));
+            fs::write(dir.join(ICON_FILE_NAME), icon)?;
+        }
+        if let Some(version) = &self.version {
+            files.push(dir.join(VERSION_FILE_NAME));
+            fs::write(
+                dir.join(VERSION_FILE_NAME),
+                serde_json::to_vec_pretty(version)?,
+            )?;
+        }
+        if let Some(manifest) = &self.manifest {
+            files.push(dir.join(APP_MANIFEST_FILE_NAME));
+            fs::write(dir.join(APP_MANIFEST_FILE_NAME), manifest)?;
+        }
+        Ok(files)
+    }
+}
+
+/// Returns the data entries of every resource of type `kind`, with their
+/// numeric IDs. Only the first language of each resource is used.
+fn resource_entries(
+    directory: ResourceDirectory<'_>,
+    kind: u16,
+) -> Result<Vec<(Option<u16>, &object::pe::ImageResourceDataEntry)>> {
+    let mut entries = Vec::new();
+    let root = directory.root()?;
+    let Some(type_entry) = root
+        .entries
+        .iter()
+        .find(|entry| entry.name_or_id().id() == Some(kind))
+    else {
+        return Ok(entries);
+    };
+    let Some(names) = type_entry.data(directory)?.table() else {
+        return Ok(entries);
+    };
+    for name_entry in names.entries {
+        let Some(languages) = name_entry.data(directory)?.table() else {
+            continue;
+        };
+        let Some(language) = languages.entries.first() else {
+            continue;
+        };
+        if let ResourceDirectoryEntryData::Data(data) = language.data(directory)? {
+            entries.push((name_entry.name_or_id().id(), data));
+        }
+    }
+    Ok(entries)
+}
+
+/// Builds an `.ico` file from a `GRPICONDIR` and the `RT_ICON` images it
+/// refers to by ID.
+fn assemble_icon(group: &[u8], icons: &BTreeMap<u16, &[u8]>) -> Option<Vec<u8>> {
+    const GROUP_ENTRY_SIZE: usize = 14;
+    const FILE_ENTRY_SIZE: usize = 16;
+
+    let count = usize::from(le_u16(group, 4)?);
+    let entries = (0..count)
+        .map(|index| {
+            let entry =
+                group.get(6 + index * GROUP_ENTRY_SIZE..6 + (index + 1) * GROUP_ENTRY_SIZE)?;
+            let image = *icons.get(&le_u16(entry, 12)?)?;
+            Some((entry, image))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if entries.is_empty() {
+        return None;
+    }
+
+    let mut ico = Vec::new();
+    ico.extend_from_slice(&[0, 0, 1, 0]);
+    ico.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    let mut image_offset = 6 + entries.len() * FILE_ENTRY_SIZE;
+    for (entry, image) in &entries {
+        // Width, height, colours, reserved, planes and bit count are shared;
+        // the group's resource ID becomes the image's file offset.
+        ico.extend_from_slice(&entry[..8]);
+        ico.extend_from_slice(&(image.len() as u32).to_le_bytes());
+        ico.extend_from_slice(&(image_offset as u32).to_le_bytes());
+        image_offset += image.len();
+    }
+    for (_, image) in entries {
+        ico.extend_from_slice(image);
+    }
+    Some(ico)
+}
+
+/// A node of the `VS_VERSIONINFO` tree: a key, a value and child nodes.
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = version_block(data)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+    let mut info = VersionInfo::default();
+    if le_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+        info.file_version = fixed_version(root.value, 8);
+        info.product_version = fixed_version(root.value, 16);
+    }
+    for child in version_children(root.children) {
+        if child.key != "StringFileInfo" {
+            continue;
+        }
+        let Some(table) = version_children(child.children).next() else {
+            continue;
+        };
+        for string in version_children(table.children) {
+            info.strings
+                .insert(string.key, utf16_until_nul(string.value));
+        }
+    }
+    Some(info)
+}
+
+/// Parses the block at the start of `data` and returns it with its length.
+fn version_block(data: &[u8]) -> Option<(VersionBlock<'_>, usize)> {
+    let length = usize::from(le_u16(data, 0)?);
+    let value_length = usize::from(le_u16(data, 2)?);
+    let is_text = le_u16(data, 4)? == 1;
+    let block = data.get(..length)?;
+    let key_units = block
+        .get(6..)?
+        .chunks_exact(2)
+        .position(|unit| unit == [0, 0])?;
+    let key = utf16_until_nul(&block[6..]);
+    let value_start = align4(6 + (key_units + 1) * 2).min(length);
+
+    let (value, children_start) = if value_length == 0 {
+        (&block[..0], value_start)
+    } else if is_text {
+        // Text lengths count UTF-16 units, but some resource compilers write
+        // bytes instead, so string values run to their terminator. Strings
+        // have no children.
+        (&block[value_start..], length)
+    } else {
+        let value_end = value_start.checked_add(value_length)?;
+        (
+            block.get(value_start..value_end)?,
+            align4(value_end).min(length),
+        )
+    };
+    Some((
+        VersionBlock {
+            key,
+            value,
+            children: &block[children_start..],
+        },
+        length,
+    ))
+}
+
+fn version_children(mut data: &[u8]) -> impl Iterator<Item = VersionBlock<'_>> {
+    std::iter::from_fn(move || {
+        let (block, length) = version_block(data)?;
+        data = &data[align4(length).min(data.len())..];
+        Some(block)
+    })
+}
+
+fn fixed_version(info: &[u8], offset: usize) -> Option<String> {
+    let most = le_u32(info, offset)?;
+    let least = le_u32(info, offset + 4)?;
+    Some(format!(
+        "{}.{}.{}.{}",
+        most >> 16,
+        most & 0xffff,
+        least >> 16,
+        least & 0xffff
+    ))
+}
+
+fn utf16_until_nul(data: &[u8]) -> String {
+    let units = data
+        .chunks_exact(2)
+        .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect::<Vec<_>>();
+    String::from_utf16_lossy(&units)
+}
+
+fn align4(offset: usize) -> usize {
+    offset.next_multiple_of(4)
+}
@@ -0,0 +1,221 @@
+//! Tauri plugins compiled into the binary.
+//!
+//! Each `tauri-plugin-*` crate leaves its registry path in panic locations,
+//! such as `…/tauri-plugin-fs-2.0.1/src/lib.rs`, which also names its
+//! version, and its module path, `tauri_plugin_fs`, in log targets and type
+//! names. Capabilities add the `plugin:fs|read_file` keys of the commands
+//! they allow and, in debug builds, permission identifiers such as
+//! `fs:allow-read-file`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Plugins of the official plugins workspace, whose permission identifiers
+/// are recognized. Other plugins are found by their crates and commands.
+const KNOWN_PLUGINS: &[&str] = &[
+    "autostart",
+    "barcode-scanner",
+    "biometric",
+    "cli",
+    "clipboard-manager",
+    "deep-link",
+    "dialog",
+    "fs",
+    "geolocation",
+    "global-shortcut",
+    "haptics",
+    "http",
+    "localhost",
+    "log",
+    "nfc",
+    "notification",
+    "opener",
+    "os",
+    "persisted-scope",
+    "positioner",
+    "process",
+    "shell",
+    "single-instance",
+    "sql",
+    "store",
+    "stronghold",
+    "updater",
+    "upload",
+    "websocket",
+    "window-state",
+];
+
+/// Plugins built into Tauri itself, whose commands are also keyed
+/// `plugin:<name>|<command>`.
+const CORE_PLUGINS: &[&str] = &[
+    "app",
+    "event",
+    "image",
+    "menu",
+    "path",
+    "resources",
+    "tray",
+    "webview",
+    "window",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginSource {
+    /// A registry path naming the crate and its version.
+    CratePath,
+    /// The crate's name or module path.
+    CrateName,
+    /// A `plugin:<name>|<command>` key.
+    CommandKey,
+    /// A permission identifier, such as `fs:default`.
+    Permission,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TauriPlugin {
+    /// The plugin's name, such as `fs` or `clipboard-manager`.
+    pub name: String,
+    /// The version in the crate's registry paths, the most common one if
+    /// they differ.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub sources: BTreeSet<PluginSource>,
+}
+
+#[derive(Default)]
+struct Found {
+    versions: BTreeMap<String, usize>,
+    sources: BTreeSet<PluginSource>,
+}
+
+/// Finds the plugins compiled into the binary in `data`, sorted by name.
+pub fn find_plugins(data: &[u8]) -> Vec<TauriPlugin> {
+    let mut plugins = BTreeMap::<String, Found>::new();
+    let mut add = |name: String, source, version: Option<String>| {
+        let found = plugins.entry(name).or_default();
+        found.sources.insert(source);
+        if let Some(version) = version {
+            *found.versions.entry(version).or_default() += 1;
+        }
+    };
+
+    for offset in find_all(data, b"plugin") {
+        let after = offset + b"plugin".len();
+        if offset >= 6 && &data[offset - 6..offset] == b"tauri-" {
+            if offset > 6 && is_name_byte(data[offset - 7]) {
+                continue;
+            }
+            if let Some((name, version)) = crate_dir(&data[after..]) {
+                let in_path = offset > 6 && matches!(data[offset - 7], b'/' | b'\\');
+                match version {
+                    Some(version) if in_path => add(name, PluginSource::CratePath, Some(version)),
+                    _ => add(name, PluginSource::CrateName, None),
+                }
+            }
+        } else if offset >= 6 && &data[offset - 6..offset] == b"tauri_" {
+            if let Some(name) = module_name(&data[after..]) {
+                add(name, PluginSource::CrateName, None);
+            }
+        } else if data.get(after) == Some(&b':') && (offset == 0 || !is_name_byte(data[offset - 1]))
+        {
+            let rest = &data[after + 1..];
+            let length = rest.iter().take_while(|&&byte| is_name_byte(byte)).count();
+            if length > 0 && rest.get(length) == Some(&b'|') {
+                let name = String::from_utf8_lossy(&rest[..length]).into_owned();
+                if !CORE_PLUGINS.contains(&name.as_str()) {
+                    add(name, PluginSource::CommandKey, None);
+                }
+            }
+        }
+    }
+
+    for suffix in [&b":allow-"[..], b":deny-", b":default"] {
+        for offset in find_all(data, suffix) {
+            let end = offset + suffix.len();
+            if suffix == b":default" && data.get(end).copied().is_some_and(is_name_byte) {
+                continue;
+            }
+            let start = data[..offset]
+                .iter()
+                .rev()
+                .take_while(|&&byte| is_name_byte(byte))
+                .count();
+            let name = &data[offset - start..offset];
+            let qualified = offset > start && data[offset - start - 1] == b':';
+            let name = String::from_utf8_lossy(name);
+            if !qualified && KNOWN_PLUGINS.contains(&name.as_ref()) {
+                add(name.into_owned(), PluginSource::Permission, None);
+            }
+        }
+    }
+
+    plugins
+        .into_iter()
+        .map(|(name, found)| TauriPlugin {
+            name,
+            version: found
+                .versions
+                .into_iter()
+                .max_by_key(|(_, count)| *count)
+                .map(|(version, _)| version),
+            sources: found.sources,
+        })
+        .collect()
+}
+
+/// Splits the rest of a `tauri-plugin-<name>-<version>` directory name into
+/// the name and the version, if it has one.
+fn crate_dir(rest: &[u8]) -> Option<(String, Option<String>)> {
+    let rest = rest.strip_prefix(b"-")?;
+    let length = rest
+        .iter()
+        .take_while(|&&byte| is_name_byte(byte) || matches!(byte, b'.' | b'+'))
+        .count();
+    let dir = String::from_utf8_lossy(&rest[..length]);
+    let split = dir
+        .match_indices('-')
+        .find(|(index, _)| dir[index + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map(|(index, _)| index);
+    let (name, version) = match split {
+        Some(index) => (&dir[..index], Some(dir[index + 1..].to_string())),
+        None => (dir.as_ref(), None),
+    };
+    let valid = !name.is_empty()
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-');
+    valid.then(|| (name.to_string(), version))
+}
+
+/// Reads the plugin name from the rest of a `tauri_plugin_<name>` module
+/// path, as it is spelled in the crate name.
+fn module_name(rest: &[u8]) -> Option<String> {
+    let rest = rest.strip_prefix(b"_")?;
+    let length = rest
+        .iter()
+        .take_while(|&&byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_')
+        .count();
+    let name = String::from_utf8_lossy(&rest[..length]);
+    let name = name.trim_matches('_');
+    (!name.is_empty()).then(|| name.replace('_', "-"))
+}
+
+fn is_name_byte(byte: u8) -> bool {
+    byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-'
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,66 @@
+//! Process access through `/proc`.
+
+use super::ProcessModule;
+use crate::error::{Error, Result};
+use std::fs::{self, File};
+use std::os::unix::fs::FileExt;
+use std::path::PathBuf;
+
+pub(super) struct Process {
+    pid: u32,
+    memory: File,
+}
+
+impl Process {
+    pub(super) fn open(pid: u32) -> Result<Self> {
+        let memory = File::open(format!("/proc/{pid}/mem"))
+            .map_err(|err| Error::Process(format!("cannot open process {pid}: {err}")))?;
+        Ok(Self { pid, memory })
+    }
+
+    pub(super) fn read(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        let mut data = vec![0; len];
+        self.memory
+            .read_exact_at(&mut data, address)
+            .map_err(|err| {
+                Error::Process(format!(
+                    "cannot read {len} bytes at {address:#x} in process {}: {err}",
+                    self.pid
+                ))
+            })?;
+        Ok(data)
+    }
+
+    /// Finds the mapping of the executable's first page.
+    pub(super) fn main_module(&self) -> Result<ProcessModule> {
+        let pid = self.pid;
+        let executable = fs::read_link(format!("/proc/{pid}/exe"))?;
+        let executable = executable.to_string_lossy();
+        let executable = executable.trim_end_matches(" (deleted)");
+        let maps = fs::read_to_string(format!("/proc/{pid}/maps"))?;
+        for line in maps.lines() {
+            // start-end perms offset dev inode path, with the path padded
+            // by spaces and possibly containing spaces itself.
+            let fields = line.splitn(6, ' ').collect::<Vec<_>>();
+            let [range, _, offset, _, _, path] = fields.as_slice() else {
+                continue;
+            };
+            let path = path.trim_start().trim_end_matches(" (deleted)");
+            if path != executable || u64::from_str_radix(offset, 16) != Ok(0) {
+                continue;
+            }
+            if let Some(base) = range
+                .split_once('-')
+                .and_then(|(start, _)| u64::from_str_radix(start, 16).ok())
+            {
+                return Ok(ProcessModule {
+                    base,
+                    path: Some(PathBuf::from(executable)),
+                });
+            }
+        }
+        Err(Error::Process(format!(
+            "cannot find the executable mapping of process {pid}"
+        )))
+    }
+}
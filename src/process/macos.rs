@@ -0,0 +1,144 @@
+//! Process access through the Mach VM APIs.
+//!
+//! Reading another task requires the `com.apple.security.cs.debugger`
+//! entitlement or running as root.
+
+use super::ProcessModule;
+use crate::bytes::{le_u32, le_u64};
+use crate::error::{Error, Result};
+use std::path::PathBuf;
+
+type KernReturn = i32;
+type MachPort = u32;
+
+const KERN_SUCCESS: KernReturn = 0;
+const TASK_DYLD_INFO: u32 = 17;
+const PATH_MAX: usize = 1024;
+
+/// `task_dyld_info`, declared with 4-byte packing like all Mach structures.
+#[repr(C, packed(4))]
+#[derive(Default)]
+struct TaskDyldInfo {
+    all_image_info_addr: u64,
+    all_image_info_size: u64,
+    all_image_info_format: i32,
+}
+
+extern "C" {
+    static mach_task_self_: MachPort;
+    fn task_for_pid(target: MachPort, pid: i32, task: *mut MachPort) -> KernReturn;
+    fn task_info(task: MachPort, flavor: u32, info: *mut i32, count: *mut u32) -> KernReturn;
+    fn mach_vm_read_overwrite(
+        task: MachPort,
+        address: u64,
+        size: u64,
+        data: u64,
+        out_size: *mut u64,
+    ) -> KernReturn;
+    fn mach_port_deallocate(task: MachPort, name: MachPort) -> KernReturn;
+}
+
+pub(super) struct Process {
+    pid: u32,
+    task: MachPort,
+}
+
+impl Process {
+    pub(super) fn open(pid: u32) -> Result<Self> {
+        let mut task = 0;
+        // SAFETY: `task` is a valid out pointer.
+        let result = unsafe { task_for_pid(mach_task_self_, pid as i32, &mut task) };
+        if result != KERN_SUCCESS {
+            return Err(Error::Process(format!(
+                "task_for_pid failed for process {pid} ({result}); run as root or grant the debugger entitlement"
+            )));
+        }
+        Ok(Self { pid, task })
+    }
+
+    pub(super) fn read(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        let mut data = vec![0u8; len];
+        let mut read = 0;
+        // SAFETY: `data` is valid for writes of `len` bytes.
+        let result = unsafe {
+            mach_vm_read_overwrite(
+                self.task,
+                address,
+                len as u64,
+                data.as_mut_ptr() as u64,
+                &mut read,
+            )
+        };
+        if result != KERN_SUCCESS || read != len as u64 {
+            return Err(Error::Process(format!(
+                "cannot read {len} bytes at {address:#x} in process {} ({result})",
+                self.pid
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Returns the first image in dyld's image list, which is the executable.
+    pub(super) fn main_module(&self) -> Result<ProcessModule> {
+        let mut info = TaskDyldInfo::default();
+        let mut count = (std::mem::size_of::<TaskDyldInfo>() / 4) as u32;
+        // SAFETY: `info` is valid for writes of `count` 4-byte words.
+        let result = unsafe {
+            task_info(
+                self.task,
+                TASK_DYLD_INFO,
+                (&mut info as *mut TaskDyldInfo).cast(),
+                &mut count,
+            )
+        };
+        if result != KERN_SUCCESS {
+            return Err(Error::Process(format!(
+                "cannot read the dyld info of process {} ({result})",
+                self.pid
+            )));
+        }
+
+        // dyld_all_image_infos: version, infoArrayCount, infoArray.
+        let all_images = self.read(info.all_image_info_addr, 16)?;
+        let count = le_u32(&all_images, 4).unwrap_or_default();
+        let array = le_u64(&all_images, 8).unwrap_or_default();
+        if count == 0 || array == 0 {
+            return Err(Error::Process(format!(
+                "dyld has not loaded any images in process {}",
+                self.pid
+            )));
+        }
+        // dyld_image_info: imageLoadAddress, imageFilePath, imageFileModDate.
+        let image = self.read(array, 16)?;
+        let base = le_u64(&image, 0).unwrap_or_default();
+        let path = le_u64(&image, 8)
+            .filter(|&address| address != 0)
+            .and_then(|address| self.read_c_string(address));
+        Ok(ProcessModule { base, path })
+    }
+
+    fn read_c_string(&self, address: u64) -> Option<PathBuf> {
+        let mut bytes = Vec::new();
+        // Read small chunks so the string's page boundary is not crossed.
+        while bytes.len() < PATH_MAX {
+            let chunk = self.read(address + bytes.len() as u64, 16).ok()?;
+            match chunk.iter().position(|&byte| byte == 0) {
+                Some(end) => {
+                    bytes.extend_from_slice(&chunk[..end]);
+                    return Some(PathBuf::from(String::from_utf8_lossy(&bytes).into_owned()));
+                }
+                None => bytes.extend_from_slice(&chunk),
+            }
+        }
+        None
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        // SAFETY: the task port was returned by task_for_pid.
+        unsafe {
+            mach_port_deallocate(mach_task_self_, self.task);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Reading executable images from running processes.
+//!
+//! Packed or encrypted executables only reveal their real contents once they
+//! are loaded. The main image is read from process memory and laid back out
+//! in file order, so the regular parsers can scan it.
+
+mod rebuild;
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod platform;
+#[cfg(target_os = "macos")]
+#[path = "macos.rs"]
+mod platform;
+#[cfg(windows)]
+#[path = "windows.rs"]
+mod platform;
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+#[path = "unsupported.rs"]
+mod platform;
+
+use crate::error::Result;
+use crate::image::BinaryImage;
+use std::path::PathBuf;
+
+pub use rebuild::rebuild_image;
+
+/// Read access to the address space of a process.
+pub trait ProcessMemory {
+    /// Reads `len` bytes starting at `address`.
+    fn read(&self, address: u64, len: usize) -> Result<Vec<u8>>;
+}
+
+/// The main executable mapped into a process.
+#[derive(Debug, Clone)]
+pub struct ProcessModule {
+    /// Address the image header is mapped at.
+    pub base: u64,
+    /// Path of the executable on disk, if the platform reports it.
+    pub path: Option<PathBuf>,
+}
+
+/// An open handle to another process.
+pub struct Process {
+    inner: platform::Process,
+}
+
+impl Process {
+    /// Opens process `pid` for reading.
+    pub fn open(pid: u32) -> Result<Self> {
+        Ok(Self {
+            inner: platform::Process::open(pid)?,
+        })
+    }
+
+    /// Locates the main executable image of the process.
+    pub fn main_module(&self) -> Result<ProcessModule> {
+        self.inner.main_module()
+    }
+}
+
+impl ProcessMemory for Process {
+    fn read(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        self.inner.read(address, len)
+    }
+}
+
+/// Reads the main executable of process `pid` and parses it.
+///
+/// `base` overrides the detected image address, for packers that unpack the
+/// real executable into a separate mapping.
+pub fn dump_image(pid: u32, base: Option<u64>) -> Result<BinaryImage> {
+    let process = Process::open(pid)?;
+    let module = process.main_module()?;
+    let data = match base {
+        Some(base) => rebuild_image(&process, base)?,
+        None => rebuild_image(&process, module.base)?,
+    };
+    BinaryImage::from_data(data, module.path.filter(|_| base.is_none()))
+}
@@ -0,0 +1,316 @@
+//! Converting a mapped image back to its file layout.
+//!
+//! The loader has already applied relocations and fixups, so pointers in the
+//! image hold run-time addresses. Instead of undoing them, the rebuilt
+//! headers are rebased onto the load address and relocation metadata that
+//! the parsers would otherwise apply a second time is disabled.
+
+use super::ProcessMemory;
+use crate::bytes::{le_u16, le_u32, le_u64};
+use crate::error::{Error, Result};
+
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+const ELF_HEADER_SIZE: usize = 64;
+const ELF_PROGRAM_HEADER_SIZE: usize = 56;
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_NULL: u32 = 0;
+
+const PE_SIGNATURE: &[u8; 4] = b"PE\0\0";
+const PE_SECTION_HEADER_SIZE: usize = 40;
+const PE32_MAGIC: u16 = 0x10b;
+const PE32_PLUS_MAGIC: u16 = 0x20b;
+
+const MACHO_MAGIC_64: u32 = 0xfeed_facf;
+const MACHO_HEADER_SIZE: usize = 32;
+const LC_SEGMENT_64: u32 = 0x19;
+const LC_DYLD_CHAINED_FIXUPS: u32 = 0x8000_0034;
+const MACHO_SECTION_SIZE: usize = 80;
+
+const PAGE_SIZE: usize = 0x1000;
+
+/// Largest image rebuilt. Header fields read from another process are not
+/// trusted to size the buffer beyond this.
+const MAX_IMAGE_SIZE: u64 = 4 << 30;
+
+/// Reads the executable image mapped at `base` and returns it in file
+/// layout.
+///
+/// 64-bit ELF, PE and 64-bit Mach-O images are supported.
+pub fn rebuild_image(memory: &dyn ProcessMemory, base: u64) -> Result<Vec<u8>> {
+    let magic = memory.read(base, 4)?;
+    if magic == ELF_MAGIC {
+        rebuild_elf(memory, base)
+    } else if magic.starts_with(b"MZ") {
+        rebuild_pe(memory, base)
+    } else if le_u32(&magic, 0) == Some(MACHO_MAGIC_64) {
+        rebuild_macho(memory, base)
+    } else {
+        Err(Error::UnsupportedFormat(format!(
+            "no executable header at {base:#x}"
+        )))
+    }
+}
+
+struct ElfSegment {
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+}
+
+fn rebuild_elf(memory: &dyn ProcessMemory, base: u64) -> Result<Vec<u8>> {
+    let header = memory.read(base, ELF_HEADER_SIZE)?;
+    if header[4] != 2 || header[5] != 1 {
+        return Err(Error::UnsupportedFormat(
+            "only 64-bit little-endian ELF processes are supported".into(),
+        ));
+    }
+    let phoff = le_u64(&header, 32).ok_or_else(truncated)?;
+    let phnum = usize::from(le_u16(&header, 56).ok_or_else(truncated)?);
+    let program_headers = memory.read(base.wrapping_add(phoff), phnum * ELF_PROGRAM_HEADER_SIZE)?;
+    let phoff = to_usize(phoff)?;
+
+    let mut loads = Vec::new();
+    for index in 0..phnum {
+        let at = index * ELF_PROGRAM_HEADER_SIZE;
+        if le_u32(&program_headers, at) != Some(PT_LOAD) {
+            continue;
+        }
+        loads.push(ElfSegment {
+            offset: le_u64(&program_headers, at + 8).ok_or_else(truncated)?,
+            vaddr: le_u64(&program_headers, at + 16).ok_or_else(truncated)?,
+            filesz: le_u64(&program_headers, at + 32).ok_or_else(truncated)?,
+        });
+    }
+    let first = loads
+        .iter()
+        .min_by_key(|segment| segment.vaddr)
+        .ok_or_else(|| invalid("ELF image has no loadable segments"))?;
+    // Difference between run-time and link-time addresses.
+    let link_base = first
+        .vaddr
+        .checked_sub(first.offset)
+        .ok_or_else(|| invalid("ELF segment file offset is past its address"))?;
+    let bias = base.wrapping_sub(link_base);
+
+    let size = loads.iter().try_fold(0, |size: u64, segment| {
+        Ok::<_, Error>(size.max(end(segment.offset, segment.filesz)?))
+    })?;
+    let mut image = allocate(size)?;
+    if phoff
+        .checked_add(program_headers.len())
+        .is_none_or(|end| image.len() < end)
+    {
+        return Err(invalid("ELF program headers are not mapped"));
+    }
+    for segment in loads.iter().filter(|segment| segment.filesz > 0) {
+        let data = memory.read(segment.vaddr.wrapping_add(bias), to_usize(segment.filesz)?)?;
+        let start = to_usize(segment.offset)?;
+        image[start..start + data.len()].copy_from_slice(&data);
+    }
+
+    for index in 0..phnum {
+        let at = phoff + index * ELF_PROGRAM_HEADER_SIZE;
+        match le_u32(&image, at) {
+            Some(PT_DYNAMIC) => put_u32(&mut image, at, PT_NULL)?,
+            Some(_) => {
+                for field in [at + 16, at + 24] {
+                    let address = le_u64(&image, field).ok_or_else(truncated)?;
+                    put_u64(&mut image, field, address.wrapping_add(bias))?;
+                }
+            }
+            None => return Err(truncated()),
+        }
+    }
+    // Section headers are not mapped and would carry link-time addresses.
+    put_u64(&mut image, 40, 0)?;
+    image.get_mut(60..64).ok_or_else(truncated)?.fill(0);
+    Ok(image)
+}
+
+fn rebuild_pe(memory: &dyn ProcessMemory, base: u64) -> Result<Vec<u8>> {
+    let headers = memory.read(base, PAGE_SIZE)?;
+    let nt = le_u32(&headers, 0x3c).ok_or_else(truncated)? as usize;
+    if headers.get(nt..nt + 4) != Some(PE_SIGNATURE) {
+        return Err(invalid("missing PE signature"));
+    }
+    let section_count = usize::from(le_u16(&headers, nt + 6).ok_or_else(truncated)?);
+    let optional = nt + 24;
+    let optional_size = usize::from(le_u16(&headers, nt + 20).ok_or_else(truncated)?);
+    let magic = le_u16(&headers, optional).ok_or_else(truncated)?;
+    let headers_size = le_u32(&headers, optional + 60).ok_or_else(truncated)? as usize;
+    let headers = memory.read(base, headers_size.max(PAGE_SIZE))?;
+
+    let section_table = optional + optional_size;
+    let mut sections = Vec::with_capacity(section_count);
+    for index in 0..section_count {
+        let at = section_table + index * PE_SECTION_HEADER_SIZE;
+        let field = |offset| {
+            le_u32(&headers, at + offset)
+                .map(u64::from)
+                .ok_or_else(truncated)
+        };
+        // (virtual size, virtual address, raw size, raw pointer)
+        sections.push((field(8)?, field(12)?, field(16)?, field(20)?));
+    }
+
+    let size = sections
+        .iter()
+        .map(|&(_, _, raw_size, raw_pointer)| raw_pointer + raw_size)
+        .max()
+        .unwrap_or_default()
+        .max(headers_size as u64);
+    let mut image = allocate(size)?;
+    image[..headers_size].copy_from_slice(&headers[..headers_size]);
+    for &(virtual_size, address, raw_size, raw_pointer) in &sections {
+        // Raw data is padded to the file alignment, which may extend past the
+        // pages the section occupies in memory.
+        let mapped = if virtual_size == 0 {
+            raw_size
+        } else {
+            raw_size.min(virtual_size.next_multiple_of(PAGE_SIZE as u64))
+        };
+        if mapped == 0 {
+            continue;
+        }
+        let data = memory.read(offset_address(base, address)?, to_usize(mapped)?)?;
+        let start = to_usize(raw_pointer)?;
+        image[start..start + data.len()].copy_from_slice(&data);
+    }
+
+    match magic {
+        PE32_PLUS_MAGIC => put_u64(&mut image, optional + 24, base)?,
+        PE32_MAGIC => put_u32(
+            &mut image,
+            optional + 28,
+            u32::try_from(base).map_err(|_| invalid("PE32 image base out of range"))?,
+        )?,
+        _ => return Err(invalid("unknown PE optional header magic")),
+    }
+    Ok(image)
+}
+
+struct MachOSegment {
+    command: usize,
+    vmaddr: u64,
+    fileoff: u64,
+    filesize: u64,
+    sections: usize,
+}
+
+fn rebuild_macho(memory: &dyn ProcessMemory, base: u64) -> Result<Vec<u8>> {
+    let header = memory.read(base, MACHO_HEADER_SIZE)?;
+    let command_count = le_u32(&header, 16).ok_or_else(truncated)? as usize;
+    let commands_size = le_u32(&header, 20).ok_or_else(truncated)? as usize;
+    let commands = memory.read(
+        offset_address(base, MACHO_HEADER_SIZE as u64)?,
+        commands_size,
+    )?;
+
+    let mut segments = Vec::new();
+    let mut chained_fixups = None;
+    let mut at = 0;
+    for _ in 0..command_count {
+        let command = le_u32(&commands, at).ok_or_else(truncated)?;
+        let command_size = le_u32(&commands, at + 4).ok_or_else(truncated)? as usize;
+        match command {
+            LC_SEGMENT_64 => segments.push(MachOSegment {
+                command: at,
+                vmaddr: le_u64(&commands, at + 24).ok_or_else(truncated)?,
+                fileoff: le_u64(&commands, at + 40).ok_or_else(truncated)?,
+                filesize: le_u64(&commands, at + 48).ok_or_else(truncated)?,
+                sections: le_u32(&commands, at + 64).ok_or_else(truncated)? as usize,
+            }),
+            LC_DYLD_CHAINED_FIXUPS => chained_fixups = Some(at),
+            _ => {}
+        }
+        at += command_size;
+    }
+    let text = segments
+        .iter()
+        .find(|segment| segment.fileoff == 0 && segment.filesize > 0)
+        .ok_or_else(|| invalid("Mach-O image has no segment mapping its header"))?;
+    let slide = base.wrapping_sub(text.vmaddr);
+
+    let size = segments.iter().try_fold(0, |size: u64, segment| {
+        Ok::<_, Error>(size.max(end(segment.fileoff, segment.filesize)?))
+    })?;
+    let mut image = allocate(size)?;
+    for segment in segments.iter().filter(|segment| segment.filesize > 0) {
+        let data = memory.read(
+            segment.vmaddr.wrapping_add(slide),
+            to_usize(segment.filesize)?,
+        )?;
+        let start = to_usize(segment.fileoff)?;
+        image[start..start + data.len()].copy_from_slice(&data);
+    }
+
+    let commands_start = MACHO_HEADER_SIZE;
+    for segment in &segments {
+        let command = commands_start + segment.command;
+        put_u64(&mut image, command + 24, segment.vmaddr.wrapping_add(slide))?;
+        for index in 0..segment.sections {
+            let addr = command + 72 + index * MACHO_SECTION_SIZE + 32;
+            let address = le_u64(&image, addr).ok_or_else(truncated)?;
+            put_u64(&mut image, addr, address.wrapping_add(slide))?;
+        }
+    }
+    // Chained fixups have been resolved to plain pointers; clearing the
+    // command number makes the parser read them as such.
+    if let Some(command) = chained_fixups {
+        put_u32(&mut image, commands_start + command, 0)?;
+    }
+    Ok(image)
+}
+
+fn put_u32(data: &mut [u8], offset: usize, value: u32) -> Result<()> {
+    put(data, offset, &value.to_le_bytes())
+}
+
+fn put_u64(data: &mut [u8], offset: usize, value: u64) -> Result<()> {
+    put(data, offset, &value.to_le_bytes())
+}
+
+/// Overwrites a header field of the rebuilt image, which fails rather than
+/// panics when the headers claim more than the loaded segments hold.
+fn put(data: &mut [u8], offset: usize, value: &[u8]) -> Result<()> {
+    data.get_mut(offset..offset + value.len())
+        .ok_or_else(truncated)?
+        .copy_from_slice(value);
+    Ok(())
+}
+
+/// The address `offset` bytes past `base`.
+fn offset_address(base: u64, offset: u64) -> Result<u64> {
+    base.checked_add(offset)
+        .ok_or_else(|| invalid("address past the end of the address space"))
+}
+
+/// End of the `size` bytes at file offset `offset`.
+fn end(offset: u64, size: u64) -> Result<u64> {
+    offset
+        .checked_add(size)
+        .ok_or_else(|| invalid("segment extends past the end of the address space"))
+}
+
+/// Returns a zeroed buffer for an image of `size` bytes.
+fn allocate(size: u64) -> Result<Vec<u8>> {
+    if size > MAX_IMAGE_SIZE {
+        return Err(invalid(format!(
+            "image of {size} bytes exceeds the limit of {MAX_IMAGE_SIZE} bytes"
+        )));
+    }
+    Ok(vec![0; to_usize(size)?])
+}
+
+fn to_usize(value: u64) -> Result<usize> {
+    usize::try_from(value).map_err(|_| invalid("image size out of range"))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Process(message.into())
+}
+
+fn truncated() -> Error {
+    invalid("truncated image headers")
+}
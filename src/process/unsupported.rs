@@ -0,0 +1,20 @@
+use super::ProcessModule;
+use crate::error::{Error, Result};
+
+pub(super) enum Process {}
+
+impl Process {
+    pub(super) fn open(_pid: u32) -> Result<Self> {
+        Err(Error::Process(
+            "reading process memory is not supported on this platform".into(),
+        ))
+    }
+
+    pub(super) fn read(&self, _address: u64, _len: usize) -> Result<Vec<u8>> {
+        match *self {}
+    }
+
+    pub(super) fn main_module(&self) -> Result<ProcessModule> {
+        match *self {}
+    }
+}
@@ -0,0 +1,99 @@
+//! Process access through the Win32 debugging APIs.
+
+use super::ProcessModule;
+use crate::error::{Error, Result};
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE, HMODULE};
+use windows_sys::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+use windows_sys::Win32::System::ProcessStatus::{K32EnumProcessModules, K32GetModuleFileNameExW};
+use windows_sys::Win32::System::Threading::{
+    OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+};
+
+const MAX_PATH_LEN: usize = 32 * 1024;
+
+pub(super) struct Process {
+    pid: u32,
+    handle: HANDLE,
+}
+
+impl Process {
+    pub(super) fn open(pid: u32) -> Result<Self> {
+        // SAFETY: OpenProcess has no memory safety requirements.
+        let handle = unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid) };
+        if handle.is_null() {
+            return Err(Error::Process(format!(
+                "cannot open process {pid}: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        Ok(Self { pid, handle })
+    }
+
+    pub(super) fn read(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        let mut data = vec![0u8; len];
+        let mut read = 0;
+        // SAFETY: `data` is valid for writes of `len` bytes.
+        let ok = unsafe {
+            ReadProcessMemory(
+                self.handle,
+                address as usize as *const _,
+                data.as_mut_ptr().cast(),
+                len,
+                &mut read,
+            )
+        };
+        if ok == 0 || read != len {
+            return Err(Error::Process(format!(
+                "cannot read {len} bytes at {address:#x} in process {}: {}",
+                self.pid,
+                std::io::Error::last_os_error()
+            )));
+        }
+        Ok(data)
+    }
+
+    /// Returns the first loaded module, which is the executable.
+    pub(super) fn main_module(&self) -> Result<ProcessModule> {
+        let mut module: HMODULE = std::ptr::null_mut();
+        let mut needed = 0;
+        // SAFETY: `module` has room for exactly one handle.
+        let ok = unsafe {
+            K32EnumProcessModules(
+                self.handle,
+                &mut module,
+                std::mem::size_of::<HMODULE>() as u32,
+                &mut needed,
+            )
+        };
+        if ok == 0 || module.is_null() {
+            return Err(Error::Process(format!(
+                "cannot list the modules of process {}: {}",
+                self.pid,
+                std::io::Error::last_os_error()
+            )));
+        }
+
+        let mut name = vec![0u16; MAX_PATH_LEN];
+        // SAFETY: `name` is valid for writes of `name.len()` UTF-16 units.
+        let len = unsafe {
+            K32GetModuleFileNameExW(self.handle, module, name.as_mut_ptr(), name.len() as u32)
+        };
+        let path = (len > 0).then(|| PathBuf::from(OsString::from_wide(&name[..len as usize])));
+        Ok(ProcessModule {
+            base: module as u64,
+            path,
+        })
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        // SAFETY: the handle was returned by OpenProcess and is closed once.
+        unsafe {
+            CloseHandle(self.handle);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Live progress of scans and exports.
+//!
+//! Pass the same [`Progress`] to [`ScanOptions`](crate::ScanOptions) and
+//! [`ExportOptions`](crate::ExportOptions) and its callback receives each
+//! [`ProgressEvent`] as it happens, on the thread that scans or exports.
+//! Events serialize to JSON with an `event` tag, as the CLI's
+//! `--progress ndjson` prints them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// How much of the scan ranges a Tauri scan has searched, reported each
+    /// time the percentage grows.
+    Scan {
+        scanned: usize,
+        total: usize,
+        percent: u8,
+    },
+    /// An asset header the scan accepted. Duplicates and assets below
+    /// [`ScanOptions::min_confidence`](crate::ScanOptions::min_confidence)
+    /// are dropped after the scan, so the table can hold fewer assets.
+    AssetFound {
+        name: String,
+        offset: usize,
+        table_index: usize,
+    },
+    /// An asset the export wrote, or handed to its writers.
+    AssetWritten { name: String, path: PathBuf },
+}
+
+impl ProgressEvent {
+    /// The progress of a scan that has searched `scanned` of `total` bytes.
+    pub fn scan(scanned: usize, total: usize) -> Self {
+        let percent = match total {
+            0 => 100,
+            _ => (scanned.min(total) as u128 * 100 / total as u128) as u8,
+        };
+        Self::Scan {
+            scanned,
+            total,
+            percent,
+        }
+    }
+}
+
+/// Receives the [`ProgressEvent`]s of a scan or export.
+#[derive(Clone)]
+pub struct Progress(Arc<dyn Fn(&ProgressEvent) + Send + Sync>);
+
+impl Progress {
+    pub fn new(callback: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub fn report(&self, event: ProgressEvent) {
+        (self.0)(&event);
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Progress")
+    }
+}
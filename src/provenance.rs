@@ -0,0 +1,91 @@
+//! Provenance of a dump, written as `dump-info.json` next to the assets.
+//!
+//! The file names the binary the assets came from by hash, size, format and
+//! architecture, and the tool version and arguments that dumped them, so a
+//! dump directory says where it came from and how to make it again.
+
+use crate::binary::{BinaryKind, BinaryMetadata};
+use crate::error::{Error, Result};
+use crate::time::utc_timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+pub const DUMP_INFO_FILE_NAME: &str = "dump-info.json";
+pub const DUMP_INFO_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpInfo {
+    pub schema_version: u32,
+    pub tool: DumpTool,
+    pub source: DumpSource,
+    /// The arguments the tool ran with, after config profiles were applied.
+    pub arguments: Vec<String>,
+    /// When the dump started, as an ISO 8601 UTC timestamp.
+    pub started_at: String,
+    /// When the dump finished, as an ISO 8601 UTC timestamp.
+    pub finished_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpTool {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DumpSource {
+    pub path: Option<String>,
+    pub sha256: String,
+    pub file_size: usize,
+    pub binary_kind: BinaryKind,
+    pub architecture: String,
+}
+
+impl DumpInfo {
+    /// Describes a dump of the binary `metadata` describes, made with
+    /// `arguments` between `started` and `finished`.
+    pub fn new(
+        metadata: &BinaryMetadata,
+        arguments: Vec<String>,
+        started: SystemTime,
+        finished: SystemTime,
+    ) -> Self {
+        Self {
+            schema_version: DUMP_INFO_SCHEMA_VERSION,
+            tool: DumpTool {
+                name: env!("CARGO_PKG_NAME").to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            source: DumpSource {
+                path: metadata.source_path.clone(),
+                sha256: metadata.sha256.clone(),
+                file_size: metadata.file_size,
+                binary_kind: metadata.kind,
+                architecture: metadata.architecture.clone(),
+            },
+            arguments,
+            started_at: utc_timestamp(started),
+            finished_at: utc_timestamp(finished),
+        }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let data = fs::read(path)?;
+        let info: Self = serde_json::from_slice(&data)?;
+        if info.schema_version != DUMP_INFO_SCHEMA_VERSION {
+            return Err(Error::Message(format!(
+                "unsupported dump info schema version {}",
+                info.schema_version
+            )));
+        }
+        Ok(info)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = serde_json::to_vec_pretty(self)?;
+        fs::write(path, data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+//! Drift between the embedded frontend and a deployment of it on the web.
+//!
+//! Many apps also serve their frontend from a web origin. Each embedded
+//! asset is fetched from the origin under its own path and compared by the
+//! SHA-256 of its decoded content. The pages and scripts the origin serves
+//! are then followed for files they reference that the binary does not
+//! embed, which is where a deployment that moved on shows up: its bundles
+//! carry other content hashes in their names.
+//!
+//! How files are fetched is up to the caller, so the comparison works
+//! against a mirror or a cache as well as over HTTP.
+
+use crate::asset::{sha256_hex, AssetTable};
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::graph::asset_graph_in;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Most files followed from the origin's pages that the binary does not
+/// embed, so an origin that generates pages cannot keep a comparison going.
+pub const MAX_REMOTE_ONLY: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// The origin serves other content under the asset's path.
+    Modified,
+    /// The origin does not serve the asset.
+    MissingRemote,
+    /// The origin's pages reference a file the binary does not embed.
+    RemoteOnly,
+    /// The asset could not be fetched.
+    Unreachable,
+}
+
+impl DriftKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Modified => "modified",
+            Self::MissingRemote => "missing_remote",
+            Self::RemoteOnly => "remote_only",
+            Self::Unreachable => "unreachable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Drift {
+    pub asset: String,
+    pub kind: DriftKind,
+    /// The SHA-256 of the decoded embedded asset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded_sha256: Option<String>,
+    /// The SHA-256 of the file the origin serves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_sha256: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_size: Option<usize>,
+    /// Why the asset could not be fetched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The result of comparing the assets of a binary with a web origin.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteComparison {
+    /// Assets the origin serves unchanged.
+    pub identical: usize,
+    /// Differences, sorted by asset.
+    pub drifts: Vec<Drift>,
+}
+
+impl RemoteComparison {
+    /// Whether the origin serves every asset unchanged and references
+    /// nothing else.
+    pub fn is_in_sync(&self) -> bool {
+        self.drifts.is_empty()
+    }
+}
+
+/// Compares the assets of `table` with the files `fetch` returns for their
+/// paths, such as `/index.html`. `fetch` returns `None` for a file the
+/// origin does not serve, and an error for one it could not fetch, which is
+/// reported rather than ending the comparison.
+pub fn compare_remote(
+    table: &AssetTable,
+    mut fetch: impl FnMut(&str) -> Result<Option<Vec<u8>>>,
+) -> Result<RemoteComparison> {
+    let mut comparison = RemoteComparison::default();
+    let mut remote = BTreeMap::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() {
+            continue;
+        }
+        let embedded = decompress_asset(asset)?;
+        let mut drift = Drift {
+            asset: asset.name().to_string(),
+            kind: DriftKind::Modified,
+            embedded_sha256: Some(sha256_hex(&embedded)),
+            remote_sha256: None,
+            embedded_size: Some(embedded.len()),
+            remote_size: None,
+            error: None,
+        };
+        match fetch(asset.name()) {
+            Ok(Some(data)) => {
+                let hash = sha256_hex(&data);
+                if drift.embedded_sha256.as_ref() == Some(&hash) {
+                    comparison.identical += 1;
+                } else {
+                    drift.remote_sha256 = Some(hash);
+                    drift.remote_size = Some(data.len());
+                    comparison.drifts.push(drift);
+                }
+                remote.insert(asset.name().to_string(), data);
+            }
+            Ok(None) => {
+                drift.kind = DriftKind::MissingRemote;
+                comparison.drifts.push(drift);
+            }
+            Err(error) => {
+                drift.kind = DriftKind::Unreachable;
+                drift.error = Some(error.to_string());
+                comparison.drifts.push(drift);
+            }
+        }
+    }
+
+    // Follow what the origin's own files reference, until they reference
+    // nothing that was not tried.
+    let mut tried = table
+        .assets()
+        .iter()
+        .map(|asset| asset.name().to_string())
+        .collect::<BTreeSet<_>>();
+    let mut followed = 0;
+    loop {
+        let graph = asset_graph_in(
+            remote
+                .iter()
+                .map(|(name, data)| (name.as_str(), data.as_slice())),
+        );
+        let unseen = graph
+            .missing
+            .into_iter()
+            .map(|reference| reference.to)
+            .filter(|target| !tried.contains(target))
+            .collect::<BTreeSet<_>>();
+        if unseen.is_empty() || followed >= MAX_REMOTE_ONLY {
+            break;
+        }
+        for name in unseen.into_iter().take(MAX_REMOTE_ONLY - followed) {
+            followed += 1;
+            tried.insert(name.clone());
+            if let Ok(Some(data)) = fetch(&name) {
+                comparison.drifts.push(Drift {
+                    asset: name.clone(),
+                    kind: DriftKind::RemoteOnly,
+                    embedded_sha256: None,
+                    remote_sha256: Some(sha256_hex(&data)),
+                    embedded_size: None,
+                    remote_size: Some(data.len()),
+                    error: None,
+                });
+                remote.insert(name, data);
+            }
+        }
+    }
+    comparison.drifts.sort_by(|a, b| a.asset.cmp(&b.asset));
+    Ok(comparison)
+}
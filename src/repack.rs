@@ -1,9 +1,20 @@
-use crate::asset::{safe_relative_path, write_u64, AssetTable};
+pub mod bsdiff;
+pub mod config;
+
+use crate::asset::{safe_relative_path, write_word, Asset, AssetTable};
+#[cfg(feature = "brotli")]
 use crate::codec;
+use crate::codec::{BROTLI_LGWIN, BROTLI_WINDOWS, MAX_BROTLI_QUALITY};
 use crate::error::{Error, Result};
+use crate::extract::{decompress_asset, encode_asset};
 use crate::image::BinaryImage;
 use crate::manifest::{Manifest, MANIFEST_FILE_NAME};
-use serde::Serialize;
+use crate::provenance::DUMP_INFO_FILE_NAME;
+use config::ConfigEdit;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -21,20 +32,32 @@ pub struct RepackPlan {
     skip_oversized: bool,
     dry_run: bool,
     allow_source_mismatch: bool,
+    patch: Option<PathBuf>,
+    brotli_quality: Option<u32>,
+    brotli_window: u32,
+    fit_original: bool,
+    csp: Option<String>,
+    config: Vec<(String, Value)>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepackSummary {
     pub output: Option<PathBuf>,
+    /// The bsdiff patch from the original binary to the repacked one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patch: Option<PathBuf>,
     pub replaced: usize,
     pub unchanged: usize,
     pub skipped_oversized: usize,
     pub oversized: Vec<OversizedReplacement>,
     pub unsupported_additions: Vec<PathBuf>,
+    /// The CSP and config values rewritten in place.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config_edits: Vec<ConfigEdit>,
     pub dry_run: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OversizedReplacement {
     pub asset: String,
     pub original_compressed_size: usize,
@@ -56,6 +79,12 @@ impl Repacker {
             skip_oversized: false,
             dry_run: false,
             allow_source_mismatch: false,
+            patch: None,
+            brotli_quality: None,
+            brotli_window: BROTLI_LGWIN,
+            fit_original: false,
+            csp: None,
+            config: Vec::new(),
         }
     }
 }
@@ -81,8 +110,75 @@ impl RepackPlan {
         self
     }
 
+    /// Compresses Brotli replacements at `quality`, from 0 to 11, instead of
+    /// trying every quality and keeping the smallest output.
+    pub fn brotli_quality(mut self, quality: u32) -> Self {
+        self.brotli_quality = Some(quality);
+        self
+    }
+
+    /// Sets the Brotli window size, as a power of two from 10 to 24.
+    /// Defaults to 22.
+    pub fn brotli_window(mut self, lgwin: u32) -> Self {
+        self.brotli_window = lgwin;
+        self
+    }
+
+    /// Compresses each Brotli replacement at the highest quality whose output
+    /// still fits the original asset's allocation, starting from
+    /// [`brotli_quality`](Self::brotli_quality) or 11.
+    pub fn fit_original(mut self, fit_original: bool) -> Self {
+        self.fit_original = fit_original;
+        self
+    }
+
+    /// Replaces the CSP compiled into the binary with `policy`, which must
+    /// not be longer than the original. See [`config::set_csp`].
+    pub fn set_csp(mut self, policy: impl Into<String>) -> Self {
+        self.csp = Some(policy.into());
+        self
+    }
+
+    /// Sets `key` in the plugin config compiled into the binary, as long as
+    /// the edited config is not longer than the original. See
+    /// [`config::set_config`].
+    pub fn set_config(mut self, key: impl Into<String>, value: Value) -> Self {
+        self.config.push((key.into(), value));
+        self
+    }
+
+    /// Also writes a bsdiff patch from the original binary to the repacked
+    /// one to `patch`, so the change can be shipped without the whole binary.
+    pub fn patch(mut self, patch: impl Into<PathBuf>) -> Self {
+        self.patch = Some(patch.into());
+        self
+    }
+
     pub fn write(self, output: impl Into<PathBuf>) -> Result<RepackSummary> {
-        let output = output.into();
+        self.run(Some(output.into()))
+    }
+
+    /// Writes only the bsdiff patch to `patch`, not the repacked binary.
+    pub fn write_patch(self, patch: impl Into<PathBuf>) -> Result<RepackSummary> {
+        self.patch(patch).run(None)
+    }
+
+    fn run(self, output: Option<PathBuf>) -> Result<RepackSummary> {
+        if self
+            .brotli_quality
+            .is_some_and(|quality| quality > MAX_BROTLI_QUALITY)
+        {
+            return Err(Error::Message(format!(
+                "Brotli quality must be between 0 and {MAX_BROTLI_QUALITY}"
+            )));
+        }
+        if !BROTLI_WINDOWS.contains(&self.brotli_window) {
+            return Err(Error::Message(format!(
+                "Brotli window must be between {} and {}",
+                BROTLI_WINDOWS.start(),
+                BROTLI_WINDOWS.end()
+            )));
+        }
         self.validate_manifest()?;
 
         let known_paths = known_asset_paths(&self.table);
@@ -97,26 +193,21 @@ impl RepackPlan {
         let mut skipped_oversized = 0;
         let mut oversized = Vec::new();
 
-        for asset in self.table.assets() {
-            let Some(relative) = safe_relative_path(asset.name()) else {
-                unchanged += 1;
+        for group in alias_groups(&self.table) {
+            let Some(replacement) = self.replacement_for(&group)? else {
+                unchanged += group.len();
                 continue;
             };
-            let replacement_path = self.assets_dir.join(relative);
-            if !replacement_path.is_file() {
-                unchanged += 1;
-                continue;
-            }
+            let asset = group[0];
 
-            let replacement = fs::read(&replacement_path)?;
-            let compressed = codec::compress_best(&replacement)?;
             let max_size = asset.location().original_compressed_size;
-            if compressed.data.len() > max_size {
+            let compressed = self.encode(asset, &replacement, max_size)?;
+            if compressed.len() > max_size {
                 let record = OversizedReplacement {
                     asset: asset.name().to_string(),
                     original_compressed_size: max_size,
-                    new_compressed_size: compressed.data.len(),
-                    delta: compressed.data.len() - max_size,
+                    new_compressed_size: compressed.len(),
+                    delta: compressed.len() - max_size,
                 };
                 if self.skip_oversized {
                     skipped_oversized += 1;
@@ -136,35 +227,134 @@ impl RepackPlan {
                 let target = patched
                     .get_mut(start..end)
                     .ok_or(Error::ScanRangeOutOfBounds)?;
-                target[..compressed.data.len()].copy_from_slice(&compressed.data);
-                target[compressed.data.len()..].fill(0);
-
-                if !write_u64(
-                    &mut patched,
-                    asset.location().data_size_offset,
-                    compressed.data.len() as u64,
-                ) {
-                    return Err(Error::ScanRangeOutOfBounds);
+                target[..compressed.len()].copy_from_slice(&compressed);
+                target[compressed.len()..].fill(0);
+
+                // Every alias header must see the new size of the shared blob.
+                for alias in &group {
+                    if !write_word(
+                        &mut patched,
+                        alias.location().data_size_offset,
+                        compressed.len() as u64,
+                        alias.location().word_size,
+                    ) {
+                        return Err(Error::ScanRangeOutOfBounds);
+                    }
+                    if let Some(offset) = alias.location().content_hash_offset {
+                        patched
+                            .get_mut(offset..offset + 32)
+                            .ok_or(Error::ScanRangeOutOfBounds)?
+                            .copy_from_slice(&Sha256::digest(&replacement));
+                    }
                 }
             }
-            replaced += 1;
+            replaced += group.len();
+        }
+
+        let mut config_edits = Vec::new();
+        if let Some(policy) = &self.csp {
+            config_edits.extend(config::set_csp(&mut patched, policy)?);
+        }
+        for (key, value) in &self.config {
+            config_edits.extend(config::set_config(&mut patched, key, value)?);
         }
 
         if !self.dry_run {
-            write_atomic(&output, &patched)?;
+            if let Some(output) = &output {
+                write_atomic(output, &patched)?;
+            }
+            if let Some(patch) = &self.patch {
+                write_atomic(patch, &bsdiff::diff(self.image.data(), &patched)?)?;
+            }
         }
 
         Ok(RepackSummary {
-            output: (!self.dry_run).then_some(output),
+            output: output.filter(|_| !self.dry_run),
+            patch: self.patch.filter(|_| !self.dry_run),
             replaced,
             unchanged,
             skipped_oversized,
             oversized,
             unsupported_additions,
+            config_edits,
             dry_run: self.dry_run,
         })
     }
 
+    /// Encodes `content` with the codec `asset` was found with, applying the
+    /// Brotli settings to Brotli assets.
+    fn encode(&self, asset: &Asset, content: &[u8], max_size: usize) -> Result<Vec<u8>> {
+        #[cfg(feature = "brotli")]
+        if asset.encoding() == crate::AssetEncoding::Brotli {
+            return self.encode_brotli(content, max_size);
+        }
+        let _ = max_size;
+        encode_asset(asset, content)
+    }
+
+    #[cfg(feature = "brotli")]
+    fn encode_brotli(&self, content: &[u8], max_size: usize) -> Result<Vec<u8>> {
+        let lgwin = self.brotli_window;
+        if !self.fit_original {
+            return Ok(match self.brotli_quality {
+                Some(quality) => codec::compress_with_params(content, quality, lgwin)?.data,
+                None => codec::compress_best_with_window(content, lgwin)?.data,
+            });
+        }
+        // Nothing fits if even the smallest output does not, which is then
+        // reported as oversized.
+        let mut smallest: Option<Vec<u8>> = None;
+        for quality in (0..=self.brotli_quality.unwrap_or(MAX_BROTLI_QUALITY)).rev() {
+            let data = codec::compress_with_params(content, quality, lgwin)?.data;
+            if data.len() <= max_size {
+                return Ok(data);
+            }
+            if smallest
+                .as_ref()
+                .is_none_or(|smallest| data.len() < smallest.len())
+            {
+                smallest = Some(data);
+            }
+        }
+        Ok(smallest.unwrap_or_default())
+    }
+
+    /// Picks the replacement content for a group of assets sharing one blob.
+    ///
+    /// Alias files that still hold the original content don't count as edits,
+    /// so editing a single alias replaces the shared data for all of them.
+    fn replacement_for(&self, group: &[&Asset]) -> Result<Option<Vec<u8>>> {
+        let mut replacements = Vec::new();
+        for asset in group {
+            let Some(relative) = safe_relative_path(asset.name()) else {
+                continue;
+            };
+            let replacement_path = self.assets_dir.join(relative);
+            if replacement_path.is_file() {
+                replacements.push((*asset, fs::read(&replacement_path)?));
+            }
+        }
+
+        if replacements.len() <= 1 {
+            return Ok(replacements.pop().map(|(_, data)| data));
+        }
+
+        let original = decompress_asset(group[0])?;
+        let mut seen = HashSet::new();
+        let edited = replacements
+            .iter()
+            .filter(|(_, data)| *data != original && seen.insert(data.as_slice()))
+            .collect::<Vec<_>>();
+        match edited.as_slice() {
+            [] => Ok(Some(original)),
+            [(_, data)] => Ok(Some(data.clone())),
+            [(first, _), (second, _), ..] => Err(Error::AliasConflict {
+                asset: first.name().to_string(),
+                alias: second.name().to_string(),
+            }),
+        }
+    }
+
     fn validate_manifest(&self) -> Result<()> {
         let manifest_path = self.assets_dir.join(MANIFEST_FILE_NAME);
         if !manifest_path.is_file() {
@@ -182,6 +372,24 @@ impl RepackPlan {
     }
 }
 
+/// Groups assets by the compressed blob their headers point at, its data
+/// offset and size, keeping table order.
+fn alias_groups(table: &AssetTable) -> Vec<Vec<&Asset>> {
+    let mut groups: Vec<Vec<&Asset>> = Vec::new();
+    let mut group_by_blob: HashMap<(usize, usize), usize> = HashMap::new();
+    for asset in table.assets() {
+        let location = asset.location();
+        match group_by_blob.entry((location.data_offset, location.original_compressed_size)) {
+            Entry::Occupied(entry) => groups[*entry.get()].push(asset),
+            Entry::Vacant(entry) => {
+                entry.insert(groups.len());
+                groups.push(vec![asset]);
+            }
+        }
+    }
+    groups
+}
+
 fn known_asset_paths(table: &AssetTable) -> HashMap<PathBuf, String> {
     table
         .assets()
@@ -202,12 +410,14 @@ fn find_unsupported_additions(
     let known = known_paths.keys().cloned().collect::<HashSet<_>>();
     Ok(files
         .into_iter()
-        .filter(|path| path != Path::new(MANIFEST_FILE_NAME))
+        .filter(|path| {
+            path != Path::new(MANIFEST_FILE_NAME) && path != Path::new(DUMP_INFO_FILE_NAME)
+        })
         .filter(|path| !known.contains(path))
         .collect())
 }
 
-fn collect_files(root: &Path, current: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+pub(crate) fn collect_files(root: &Path, current: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
     if !current.exists() {
         return Ok(());
     }
@@ -0,0 +1,151 @@
+//! Binary patches in the `BSDIFF40` format read by `bspatch`.
+//!
+//! A repack replaces asset bytes in place, so the patched binary lines up with
+//! the original byte for byte. The patch therefore needs no suffix sorting: a
+//! single control entry adds the bytewise difference over the common length
+//! and appends whatever the new file has beyond it. The difference is zero
+//! outside the replaced assets, which bzip2 compresses to almost nothing.
+
+use crate::error::{Error, Result};
+use bzip2::read::BzDecoder;
+use bzip2::write::BzEncoder;
+use bzip2::Compression;
+use std::io::{Read, Write};
+
+const MAGIC: &[u8; 8] = b"BSDIFF40";
+const HEADER_SIZE: usize = 32;
+
+/// Creates a patch that turns `old` into `new`.
+pub fn diff(old: &[u8], new: &[u8]) -> Result<Vec<u8>> {
+    let common = old.len().min(new.len());
+    let mut control = Vec::with_capacity(24);
+    control.extend_from_slice(&encode_offset(common as i64));
+    control.extend_from_slice(&encode_offset((new.len() - common) as i64));
+    control.extend_from_slice(&encode_offset(0));
+    let difference = old
+        .iter()
+        .zip(new)
+        .map(|(old, new)| new.wrapping_sub(*old))
+        .collect::<Vec<_>>();
+
+    let control = compress(&control)?;
+    let difference = compress(&difference)?;
+    let extra = compress(&new[common..])?;
+
+    let mut patch =
+        Vec::with_capacity(HEADER_SIZE + control.len() + difference.len() + extra.len());
+    patch.extend_from_slice(MAGIC);
+    patch.extend_from_slice(&encode_offset(control.len() as i64));
+    patch.extend_from_slice(&encode_offset(difference.len() as i64));
+    patch.extend_from_slice(&encode_offset(new.len() as i64));
+    patch.extend_from_slice(&control);
+    patch.extend_from_slice(&difference);
+    patch.extend_from_slice(&extra);
+    Ok(patch)
+}
+
+/// Applies a `BSDIFF40` patch to `old`.
+pub fn apply(old: &[u8], patch: &[u8]) -> Result<Vec<u8>> {
+    if patch.len() < HEADER_SIZE || &patch[..8] != MAGIC {
+        return Err(invalid("missing BSDIFF40 header"));
+    }
+    let control_len = length(&patch[8..16])?;
+    let difference_len = length(&patch[16..24])?;
+    let new_len = length(&patch[24..32])?;
+    let control_end = HEADER_SIZE
+        .checked_add(control_len)
+        .filter(|&end| end <= patch.len())
+        .ok_or_else(|| invalid("truncated control block"))?;
+    let difference_end = control_end
+        .checked_add(difference_len)
+        .filter(|&end| end <= patch.len())
+        .ok_or_else(|| invalid("truncated diff block"))?;
+    let control = decompress(&patch[HEADER_SIZE..control_end])?;
+    let difference = decompress(&patch[control_end..difference_end])?;
+    let extra = decompress(&patch[difference_end..])?;
+
+    let mut new = Vec::with_capacity(new_len);
+    let (mut old_pos, mut difference_pos, mut extra_pos) = (0i64, 0usize, 0usize);
+    for entry in control.chunks(24) {
+        if entry.len() != 24 {
+            return Err(invalid("truncated control entry"));
+        }
+        let add = length(&entry[..8])?;
+        let copy = length(&entry[8..16])?;
+        let seek = decode_offset(&entry[16..24]);
+        if new.len() + add + copy > new_len {
+            return Err(invalid("control entry writes past the new file"));
+        }
+
+        let added = difference
+            .get(difference_pos..difference_pos + add)
+            .ok_or_else(|| invalid("diff block is too short"))?;
+        for (index, delta) in added.iter().enumerate() {
+            let old_byte = usize::try_from(old_pos + index as i64)
+                .ok()
+                .and_then(|pos| old.get(pos))
+                .copied()
+                .unwrap_or(0);
+            new.push(old_byte.wrapping_add(*delta));
+        }
+        difference_pos += add;
+        old_pos += add as i64;
+
+        new.extend_from_slice(
+            extra
+                .get(extra_pos..extra_pos + copy)
+                .ok_or_else(|| invalid("extra block is too short"))?,
+        );
+        extra_pos += copy;
+        old_pos += seek;
+    }
+    if new.len() != new_len {
+        return Err(invalid("patch does not produce the declared size"));
+    }
+    Ok(new)
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = BzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    BzDecoder::new(data)
+        .read_to_end(&mut out)
+        .map_err(|err| invalid(format!("bad bzip2 block: {err}")))?;
+    Ok(out)
+}
+
+/// Encodes `value` as bsdiff does: little-endian magnitude with the sign in
+/// the top bit.
+fn encode_offset(value: i64) -> [u8; 8] {
+    let mut bytes = value.unsigned_abs().to_le_bytes();
+    if value < 0 {
+        bytes[7] |= 0x80;
+    }
+    bytes
+}
+
+fn decode_offset(bytes: &[u8]) -> i64 {
+    let mut magnitude = [0; 8];
+    magnitude.copy_from_slice(&bytes[..8]);
+    let negative = magnitude[7] & 0x80 != 0;
+    magnitude[7] &= 0x7f;
+    let value = u64::from_le_bytes(magnitude) as i64;
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+fn length(bytes: &[u8]) -> Result<usize> {
+    usize::try_from(decode_offset(bytes)).map_err(|_| invalid("negative length"))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::InvalidPatch(message.into())
+}
@@ -0,0 +1,201 @@
+//! In-place edits of the app config compiled into a binary.
+//!
+//! Tauri keeps the CSP of its config as a string and plugin config as JSON
+//! text. Both are read through a pointer and a length stored elsewhere, so an
+//! edit can only shrink them: the new text is written over the old one and
+//! the rest is filled with spaces, which a policy and JSON both ignore.
+
+use crate::audit::policy_spans;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Config values larger than this are not part of the app config.
+const MAX_VALUE_LEN: usize = 64 * 1024;
+
+/// An edit written into the binary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigEdit {
+    /// `csp`, or the config key that was set.
+    pub key: String,
+    /// File offset of the rewritten text.
+    pub offset: usize,
+    /// Bytes the old text took up, which the new one fills.
+    pub length: usize,
+}
+
+/// Replaces every CSP in `data` with `policy`.
+pub fn set_csp(data: &mut [u8], policy: &str) -> Result<Vec<ConfigEdit>> {
+    let spans = policy_spans(data);
+    if spans.is_empty() {
+        return Err(Error::Message("no embedded CSP found".to_string()));
+    }
+    let mut edits = Vec::new();
+    for (start, end) in spans {
+        write_padded(data, start, end, policy.as_bytes(), "csp")?;
+        edits.push(ConfigEdit {
+            key: "csp".to_string(),
+            offset: start,
+            length: end - start,
+        });
+    }
+    Ok(edits)
+}
+
+/// Sets `key`, a dotted path such as `updater.endpoints`, to `value` in the
+/// plugin config JSON of `data`. A leading `plugins.` is optional. The
+/// objects along the path must exist; the last key may be new.
+pub fn set_config(data: &mut [u8], key: &str, value: &Value) -> Result<Vec<ConfigEdit>> {
+    let path = key.strip_prefix("plugins.").unwrap_or(key);
+    let mut segments = path.split('.');
+    let Some(first) = segments.next().filter(|segment| !segment.is_empty()) else {
+        return Err(Error::Message(format!("invalid config key {key}")));
+    };
+    let rest = segments.collect::<Vec<_>>();
+
+    let needle = serde_json::to_vec(first)?;
+    let mut edits = Vec::new();
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], &needle) {
+        let key_end = search + found + needle.len();
+        search = key_end;
+        let Some((start, end)) = value_after_key(data, key_end) else {
+            continue;
+        };
+        let Ok(mut current) = serde_json::from_slice::<Value>(&data[start..end]) else {
+            continue;
+        };
+        if !set_path(&mut current, &rest, value.clone()) {
+            continue;
+        }
+        write_padded(data, start, end, &serde_json::to_vec(&current)?, key)?;
+        edits.push(ConfigEdit {
+            key: key.to_string(),
+            offset: start,
+            length: end - start,
+        });
+    }
+    if edits.is_empty() {
+        return Err(Error::Message(format!("no embedded config has {key}")));
+    }
+    Ok(edits)
+}
+
+/// Reads `value` as JSON, or as a string if it is not valid JSON, so that
+/// `key=text` needs no quotes.
+pub fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+/// Sets the value at `path` below `root`, if its parent exists.
+fn set_path(root: &mut Value, path: &[&str], value: Value) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        *root = value;
+        return true;
+    };
+    let mut current = root;
+    for segment in parents {
+        match current.get_mut(*segment) {
+            Some(next) => current = next,
+            None => return false,
+        }
+    }
+    match current.as_object_mut() {
+        Some(object) => {
+            object.insert((*last).to_string(), value);
+            true
+        }
+        None => false,
+    }
+}
+
+fn write_padded(data: &mut [u8], start: usize, end: usize, text: &[u8], key: &str) -> Result<()> {
+    let target = &mut data[start..end];
+    if text.len() > target.len() {
+        return Err(Error::ReplacementTooLarge {
+            asset: key.to_string(),
+            new_size: text.len(),
+            max_size: target.len(),
+        });
+    }
+    target[..text.len()].copy_from_slice(text);
+    target[text.len()..].fill(b' ');
+    Ok(())
+}
+
+/// Returns the span of the JSON value following a key's `:` at `offset`.
+fn value_after_key(data: &[u8], offset: usize) -> Option<(usize, usize)> {
+    let mut index = offset + leading_whitespace(&data[offset..]);
+    if data.get(index) != Some(&b':') {
+        return None;
+    }
+    index += 1;
+    let start = index + leading_whitespace(&data[index..]);
+    let rest = &data[start..data.len().min(start + MAX_VALUE_LEN)];
+    let length = match rest.first()? {
+        b'{' | b'[' => nested_length(rest)?,
+        b'"' => string_length(rest)?,
+        _ => rest
+            .iter()
+            .position(|byte| matches!(byte, b',' | b'}' | b']') || byte.is_ascii_whitespace())?,
+    };
+    Some((start, start + length))
+}
+
+/// The length of the object or array at the start of `data`.
+fn nested_length(data: &[u8]) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, &byte) in data.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The length of the string at the start of `data`, with its quotes.
+fn string_length(data: &[u8]) -> Option<usize> {
+    let mut escaped = false;
+    for (index, &byte) in data.iter().enumerate().skip(1) {
+        match byte {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(index + 1),
+            0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn leading_whitespace(data: &[u8]) -> usize {
+    data.iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(data.len())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,320 @@
+//! A single-file HTML report of an asset table and its audit findings.
+//!
+//! The report carries no asset data, so it can be shared where the dump
+//! cannot. Styles and the script that sorts and filters the asset table are
+//! inlined, and the size treemap is an SVG drawn here, so the file opens
+//! offline and without the script.
+
+use crate::asset::{Asset, AssetTable};
+use crate::audit::{AuditReport, Severity};
+use crate::mime::mime_type;
+use std::fmt::Write;
+
+pub const REPORT_FILE_NAME: &str = "report.html";
+
+const TREEMAP_WIDTH: f64 = 960.0;
+const TREEMAP_HEIGHT: f64 = 480.0;
+/// Rectangles narrower than this are drawn without a label.
+const LABEL_MIN_WIDTH: f64 = 60.0;
+
+/// Treemap colors by MIME type prefix; other types use the last one.
+const TREEMAP_COLORS: &[(&str, &str)] = &[
+    ("text/html", "#e67e22"),
+    ("text/javascript", "#f1c40f"),
+    ("application/javascript", "#f1c40f"),
+    ("text/css", "#3498db"),
+    ("image/", "#2ecc71"),
+    ("font/", "#9b59b6"),
+    ("application/json", "#1abc9c"),
+    ("application/wasm", "#e74c3c"),
+    ("", "#95a5a6"),
+];
+
+const STYLE: &str = "\
+body{font:14px/1.4 system-ui,sans-serif;margin:2em;color:#222}\
+h1{font-size:1.5em}h2{font-size:1.2em;margin-top:2em}\
+table{border-collapse:collapse;width:100%}\
+th,td{border:1px solid #ddd;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}table.sortable th{cursor:pointer;user-select:none}\
+th[data-order=asc]::after{content:\" \\25B2\"}th[data-order=desc]::after{content:\" \\25BC\"}\
+td.number{text-align:right;font-variant-numeric:tabular-nums}\
+code,td.hash{font-family:ui-monospace,monospace;font-size:12px;word-break:break-all}\
+.high{color:#c0392b;font-weight:bold}.medium{color:#d35400}.low{color:#7f8c8d}\
+svg text{font-size:11px;pointer-events:none}svg rect{stroke:#fff}\
+input{margin-bottom:8px;padding:4px;width:20em}";
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach((th, column) => {
+  th.addEventListener("click", () => {
+    const body = th.closest("table").tBodies[0];
+    const ascending = th.dataset.order !== "asc";
+    th.parentElement.querySelectorAll("th").forEach(other => delete other.dataset.order);
+    th.dataset.order = ascending ? "asc" : "desc";
+    const key = row => row.cells[column].dataset.value ?? row.cells[column].textContent;
+    const numeric = th.dataset.type === "number";
+    [...body.rows]
+      .sort((a, b) => {
+        const order = numeric ? key(a) - key(b) : key(a).localeCompare(key(b));
+        return ascending ? order : -order;
+      })
+      .forEach(row => body.append(row));
+  });
+});
+document.getElementById("filter").addEventListener("input", event => {
+  const text = event.target.value.toLowerCase();
+  for (const row of document.getElementById("assets").tBodies[0].rows) {
+    row.hidden = !row.cells[0].textContent.toLowerCase().includes(text);
+  }
+});
+"#;
+
+/// Renders the report for the assets of `table` and the findings of
+/// `audit`.
+pub fn html_report(table: &AssetTable, audit: &AuditReport) -> String {
+    let metadata = table.metadata();
+    let title = metadata
+        .source_path
+        .as_deref()
+        .and_then(|path| path.rsplit(['/', '\\']).next())
+        .unwrap_or("binary");
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>{} - tauri-dumper report</title><style>{STYLE}</style></head><body>\n\
+         <h1>{}</h1>\n",
+        escape(title),
+        escape(title)
+    );
+
+    html.push_str("<table>\n");
+    let mut row = |field: &str, value: &str| {
+        let _ = writeln!(html, "<tr><th>{field}</th><td>{}</td></tr>", escape(value));
+    };
+    row("Format", &format!("{:?}", metadata.kind));
+    row("Architecture", &metadata.architecture);
+    row("File size", &metadata.file_size.to_string());
+    row("SHA-256", &metadata.sha256);
+    row("Assets", &table.len().to_string());
+    let decompressed = table
+        .assets()
+        .iter()
+        .map(Asset::decompressed_size)
+        .sum::<usize>();
+    row("Total size", &decompressed.to_string());
+    html.push_str("</table>\n");
+
+    let _ = writeln!(
+        html,
+        "<h2>Audit findings</h2>\n<p>{} high, {} medium, {} low</p>",
+        audit.count(Severity::High),
+        audit.count(Severity::Medium),
+        audit.count(Severity::Low)
+    );
+    if !audit.findings.is_empty() {
+        html.push_str(
+            "<table class=\"sortable\"><thead><tr><th>Severity</th><th>Category</th>\
+             <th>Finding</th><th>Evidence</th><th>Location</th></tr></thead><tbody>\n",
+        );
+        for finding in &audit.findings {
+            let severity = finding.severity.as_str();
+            let location = finding
+                .location
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr><td class=\"{severity}\" data-value=\"{}\">{severity}</td><td>{}</td>\
+                 <td>{}</td><td><code>{}</code></td><td><code>{}</code></td></tr>",
+                finding.severity as u8,
+                finding.category.as_str(),
+                escape(&finding.message),
+                escape(&finding.evidence),
+                escape(&location)
+            );
+        }
+        html.push_str("</tbody></table>\n");
+    }
+
+    html.push_str("<h2>Size treemap</h2>\n");
+    treemap(&mut html, table);
+
+    html.push_str(
+        "<h2>Assets</h2>\n<input id=\"filter\" type=\"search\" placeholder=\"Filter by name\">\n\
+         <table id=\"assets\" class=\"sortable\"><thead><tr><th>Name</th><th>Type</th>\
+         <th>Encoding</th><th data-type=\"number\">Compressed</th>\
+         <th data-type=\"number\">Size</th><th data-type=\"number\">Offset</th>\
+         <th>SHA-256 (compressed)</th></tr></thead><tbody>\n",
+    );
+    for asset in table.assets() {
+        let encoding = match asset.alias_of() {
+            Some(original) => format!("alias of {original}"),
+            None => format!("{:?}", asset.encoding()),
+        };
+        let offset = asset.location().data_offset;
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td>\
+             <td class=\"number\" data-value=\"{compressed}\">{compressed}</td>\
+             <td class=\"number\" data-value=\"{size}\">{size}</td>\
+             <td class=\"number\" data-value=\"{offset}\">{offset:#x}</td>\
+             <td class=\"hash\">{}</td></tr>",
+            escape(asset.name()),
+            mime_type(asset.name()),
+            escape(&encoding),
+            asset.compressed_sha256(),
+            compressed = asset.compressed_size(),
+            size = asset.decompressed_size(),
+        );
+    }
+    let _ = write!(
+        html,
+        "</tbody></table>\n<script>{SCRIPT}</script>\n</body></html>\n"
+    );
+    html
+}
+
+/// Draws the assets, other than aliases, as rectangles sized by their
+/// decompressed size.
+fn treemap(html: &mut String, table: &AssetTable) {
+    let mut assets = table
+        .assets()
+        .iter()
+        .filter(|asset| asset.alias_of().is_none() && asset.decompressed_size() > 0)
+        .collect::<Vec<_>>();
+    assets.sort_by_key(|asset| std::cmp::Reverse(asset.decompressed_size()));
+    let sizes = assets
+        .iter()
+        .map(|asset| asset.decompressed_size() as f64)
+        .collect::<Vec<_>>();
+    let bounds = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: TREEMAP_WIDTH,
+        height: TREEMAP_HEIGHT,
+    };
+
+    let _ = writeln!(
+        html,
+        "<svg width=\"{TREEMAP_WIDTH}\" height=\"{TREEMAP_HEIGHT}\" \
+         viewBox=\"0 0 {TREEMAP_WIDTH} {TREEMAP_HEIGHT}\" role=\"img\">"
+    );
+    for (asset, rect) in assets.iter().zip(squarify(&sizes, bounds)) {
+        let mime = mime_type(asset.name());
+        let color = TREEMAP_COLORS
+            .iter()
+            .find(|(prefix, _)| mime.starts_with(prefix))
+            .map_or("#95a5a6", |(_, color)| color);
+        let name = escape(asset.name());
+        let _ = write!(
+            html,
+            "<g><title>{name} ({} bytes)</title><rect x=\"{:.1}\" y=\"{:.1}\" \
+             width=\"{:.1}\" height=\"{:.1}\" fill=\"{color}\"/>",
+            asset.decompressed_size(),
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height
+        );
+        if rect.width >= LABEL_MIN_WIDTH && rect.height >= 14.0 {
+            let label = asset.name().rsplit('/').next().unwrap_or_default();
+            let _ = write!(
+                html,
+                "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>",
+                rect.x + 3.0,
+                rect.y + 12.0,
+                escape(label)
+            );
+        }
+        html.push_str("</g>\n");
+    }
+    html.push_str("</svg>\n");
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Lays out `sizes`, sorted largest first, in `bounds` with the squarified
+/// treemap algorithm, which keeps the rectangles close to square.
+fn squarify(sizes: &[f64], mut bounds: Rect) -> Vec<Rect> {
+    let total = sizes.iter().sum::<f64>();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let scale = bounds.width * bounds.height / total;
+    let areas = sizes.iter().map(|size| size * scale).collect::<Vec<_>>();
+
+    let mut rects = Vec::with_capacity(areas.len());
+    let mut start = 0;
+    while start < areas.len() {
+        let side = bounds.width.min(bounds.height);
+        let mut end = start + 1;
+        while end < areas.len()
+            && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side)
+        {
+            end += 1;
+        }
+
+        let row = &areas[start..end];
+        let thickness = row.iter().sum::<f64>() / side;
+        let mut offset = 0.0;
+        for area in row {
+            let length = area / thickness;
+            rects.push(if bounds.width >= bounds.height {
+                Rect {
+                    x: bounds.x,
+                    y: bounds.y + offset,
+                    width: thickness,
+                    height: length,
+                }
+            } else {
+                Rect {
+                    x: bounds.x + offset,
+                    y: bounds.y,
+                    width: length,
+                    height: thickness,
+                }
+            });
+            offset += length;
+        }
+        if bounds.width >= bounds.height {
+            bounds.x += thickness;
+            bounds.width -= thickness;
+        } else {
+            bounds.y += thickness;
+            bounds.height -= thickness;
+        }
+        start = end;
+    }
+    rects
+}
+
+/// The largest aspect ratio in a row of `areas` laid along `side`.
+fn worst_ratio(areas: &[f64], side: f64) -> f64 {
+    let sum = areas.iter().sum::<f64>();
+    let max = areas.iter().copied().fold(f64::MIN, f64::max);
+    let min = areas.iter().copied().fold(f64::MAX, f64::min);
+    let side = side * side;
+    (side * max / (sum * sum)).max(sum * sum / (side * min))
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
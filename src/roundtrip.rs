@@ -0,0 +1,125 @@
+//! Checks that a binary embeds exactly the files of a frontend build.
+//!
+//! Tauri embeds the `frontendDist` directory at compile time. Comparing the
+//! decoded assets with that directory catches stale builds, files dropped by
+//! the bundler config and assets the build pipeline altered afterwards.
+
+use crate::asset::{safe_relative_path, sha256_hex, AssetTable};
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::repack::collect_files;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path};
+
+/// How an embedded asset compares to the file at the same path in the dist
+/// directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// Both exist with the same content.
+    Match,
+    /// Both exist with different content.
+    Changed,
+    /// The dist file is not embedded in the binary.
+    MissingFromBinary,
+    /// The binary embeds an asset the dist directory does not have.
+    MissingFromDist,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileResult {
+    /// Path relative to the dist directory, with `/` separators.
+    pub path: String,
+    pub status: FileStatus,
+    /// SHA-256 of the decoded asset, if it is embedded.
+    pub embedded_sha256: Option<String>,
+    /// SHA-256 of the dist file, if it exists.
+    pub dist_sha256: Option<String>,
+}
+
+/// Per-file results of [`verify`], sorted by path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub files: Vec<FileResult>,
+}
+
+impl VerifyReport {
+    /// Returns true if every file matches.
+    pub fn is_ok(&self) -> bool {
+        self.files
+            .iter()
+            .all(|file| file.status == FileStatus::Match)
+    }
+
+    /// Returns the files that do not match.
+    pub fn mismatches(&self) -> impl Iterator<Item = &FileResult> {
+        self.files
+            .iter()
+            .filter(|file| file.status != FileStatus::Match)
+    }
+
+    /// Returns the number of files with `status`.
+    pub fn count(&self, status: FileStatus) -> usize {
+        self.files
+            .iter()
+            .filter(|file| file.status == status)
+            .count()
+    }
+}
+
+/// Compares the assets of `table` with the files under `dist`. Assets whose
+/// names are not safe relative paths are skipped.
+pub fn verify(table: &AssetTable, dist: &Path) -> Result<VerifyReport> {
+    let mut embedded = BTreeMap::new();
+    for asset in table.assets() {
+        let Some(path) = safe_relative_path(asset.name()) else {
+            log::warn!("skipping asset with an unsafe name: {}", asset.name());
+            continue;
+        };
+        embedded.insert(slash_path(&path), sha256_hex(&decompress_asset(asset)?));
+    }
+
+    let mut dist_files = Vec::new();
+    collect_files(dist, dist, &mut dist_files)?;
+    let mut on_disk = BTreeMap::new();
+    for path in dist_files {
+        let hash = sha256_hex(&fs::read(dist.join(&path))?);
+        on_disk.insert(slash_path(&path), hash);
+    }
+
+    let mut files = Vec::new();
+    for (path, embedded_sha256) in &embedded {
+        let dist_sha256 = on_disk.remove(path);
+        let status = match &dist_sha256 {
+            Some(hash) if hash == embedded_sha256 => FileStatus::Match,
+            Some(_) => FileStatus::Changed,
+            None => FileStatus::MissingFromDist,
+        };
+        files.push(FileResult {
+            path: path.clone(),
+            status,
+            embedded_sha256: Some(embedded_sha256.clone()),
+            dist_sha256,
+        });
+    }
+    files.extend(on_disk.into_iter().map(|(path, hash)| FileResult {
+        path,
+        status: FileStatus::MissingFromBinary,
+        embedded_sha256: None,
+        dist_sha256: Some(hash),
+    }));
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(VerifyReport { files })
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,297 @@
+//! Routes and entry points of a single-page app, written as `routes.json`.
+//!
+//! The HTML pages are the entry points: each loads its scripts, stylesheets
+//! and preloaded modules. Client-side routers then declare their routes in
+//! the bundles as objects such as
+//! `{path:"/about",component:()=>import("./About-1a2b.js")}`, a shape
+//! vue-router, React Router, Angular and Solid share, with the chunk each
+//! route loads lazily. Next.js keeps one chunk per page under
+//! `_next/static/chunks/pages/` instead.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::html::{attribute, resolve_reference, tags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const ROUTES_FILE_NAME: &str = "routes.json";
+
+/// How far past a route's path its object is searched for a component
+/// and the chunks it imports.
+const ROUTE_WINDOW: usize = 400;
+/// Keys that make an object with a `path` a route rather than, say, a file.
+const ROUTE_KEYS: &[&[u8]] = &[
+    b"component:",
+    b"components:",
+    b"element:",
+    b"loadComponent:",
+    b"loadChildren:",
+    b"children:",
+    b"redirect:",
+    b"redirectTo:",
+    b"lazy:",
+];
+/// `path` keys as minifiers and hand-written code spell them.
+const PATH_KEYS: &[&[u8]] = &[b"path:", b"\"path\":"];
+const IMPORT_CALL: &[u8] = b"import(";
+const NEXT_PAGES_DIR: &str = "/_next/static/chunks/pages/";
+/// Next.js pages that wrap every route instead of being one.
+const NEXT_SPECIAL_PAGES: &[&str] = &["_app", "_document", "_error"];
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "mjs", "cjs"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryPoint {
+    /// The HTML page, such as `/index.html`.
+    pub page: String,
+    /// The route the page is served at, such as `/` or `/about`.
+    pub route: String,
+    pub scripts: Vec<String>,
+    pub stylesheets: Vec<String>,
+    /// Modules preloaded with `<link rel="modulepreload">`.
+    pub preloads: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    /// The path as the router declares it, such as `/users/:id`, or
+    /// `settings` for a child route.
+    pub path: String,
+    /// The asset declaring the route.
+    pub source: String,
+    /// The chunks the route loads.
+    pub chunks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteMap {
+    pub entry_points: Vec<EntryPoint>,
+    pub routes: Vec<Route>,
+}
+
+impl RouteMap {
+    pub fn is_empty(&self) -> bool {
+        self.entry_points.is_empty() && self.routes.is_empty()
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Maps the pages and routes of the assets in `table`.
+pub fn map_routes(table: &AssetTable) -> Result<RouteMap> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() {
+            continue;
+        }
+        let searched = is_html(asset.name()) || has_extension(asset.name(), SCRIPT_EXTENSIONS);
+        let data = if searched {
+            decompress_asset(asset)?
+        } else {
+            Vec::new()
+        };
+        assets.push((asset.name().to_string(), data));
+    }
+    Ok(map_routes_in(
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`map_routes`], for decoded assets given as name and content.
+pub fn map_routes_in<'a>(assets: impl IntoIterator<Item = (&'a str, &'a [u8])>) -> RouteMap {
+    let mut map = RouteMap::default();
+    for (name, data) in assets {
+        if is_html(name) {
+            map.entry_points.push(entry_point(name, data));
+        } else if let Some(route) = next_page_route(name) {
+            map.routes.push(route);
+        } else if has_extension(name, SCRIPT_EXTENSIONS) {
+            map.routes.extend(declared_routes(name, data));
+        }
+    }
+    map
+}
+
+fn entry_point(name: &str, html: &[u8]) -> EntryPoint {
+    let lower = html.to_ascii_lowercase();
+    let mut entry = EntryPoint {
+        page: name.to_string(),
+        route: page_route(name),
+        scripts: Vec::new(),
+        stylesheets: Vec::new(),
+        preloads: Vec::new(),
+    };
+    for (start, end) in tags(&lower, b"<script") {
+        if let Some((_, src)) = attribute(&html[start..end], &lower[start..end], b"src") {
+            entry.scripts.extend(resolve_reference(name, &src));
+        }
+    }
+    for (start, end) in tags(&lower, b"<link") {
+        let (tag, lower_tag) = (&html[start..end], &lower[start..end]);
+        let Some((_, href)) = attribute(tag, lower_tag, b"href") else {
+            continue;
+        };
+        let rel = attribute(tag, lower_tag, b"rel")
+            .map(|(_, rel)| rel.to_ascii_lowercase())
+            .unwrap_or_default();
+        let list = match rel.as_str() {
+            "stylesheet" => &mut entry.stylesheets,
+            "modulepreload" => &mut entry.preloads,
+            _ => continue,
+        };
+        list.extend(resolve_reference(name, &href));
+    }
+    entry
+}
+
+/// The route a static page is served at: `/about/index.html` and
+/// `/about.html` both serve `/about`.
+fn page_route(name: &str) -> String {
+    let path = name
+        .strip_suffix("index.html")
+        .or_else(|| name.strip_suffix("index.htm"))
+        .unwrap_or(name);
+    let path = path
+        .strip_suffix(".html")
+        .or_else(|| path.strip_suffix(".htm"))
+        .unwrap_or(path);
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    }
+}
+
+/// Reads the route of a Next.js page chunk, such as
+/// `/_next/static/chunks/pages/blog/[slug]-5f3c9a1e.js`.
+fn next_page_route(name: &str) -> Option<Route> {
+    let page = name.strip_prefix(NEXT_PAGES_DIR)?.strip_suffix(".js")?;
+    let page = match page.rsplit_once('-') {
+        Some((page, hash))
+            if hash.len() >= 8 && hash.bytes().all(|byte| byte.is_ascii_hexdigit()) =>
+        {
+            page
+        }
+        _ => page,
+    };
+    if NEXT_SPECIAL_PAGES.contains(&page) {
+        return None;
+    }
+    let path = match page.strip_suffix("index") {
+        Some(parent) if parent.is_empty() || parent.ends_with('/') => {
+            format!("/{}", parent.trim_end_matches('/'))
+        }
+        _ => format!("/{page}"),
+    };
+    Some(Route {
+        path,
+        source: name.to_string(),
+        chunks: vec![name.to_string()],
+    })
+}
+
+/// Reads route objects from a bundle.
+fn declared_routes(name: &str, data: &[u8]) -> Vec<Route> {
+    let mut paths = Vec::new();
+    for key in PATH_KEYS {
+        for offset in find_all(data, key) {
+            if offset > 0 && is_identifier_byte(data[offset - 1]) {
+                continue;
+            }
+            if let Some((path, end)) = string_literal(data, offset + key.len()) {
+                paths.push((offset, path, end));
+            }
+        }
+    }
+    paths.sort_by_key(|(offset, _, _)| *offset);
+
+    let mut routes = Vec::<Route>::new();
+    for (index, (_, path, end)) in paths.iter().enumerate() {
+        let limit = paths
+            .get(index + 1)
+            .map_or(data.len(), |(next, _, _)| *next)
+            .min(end + ROUTE_WINDOW)
+            .min(data.len());
+        let object = &data[*end..limit];
+        if !ROUTE_KEYS.iter().any(|key| find(object, key).is_some()) {
+            continue;
+        }
+        let chunks = find_all(object, IMPORT_CALL)
+            .filter_map(|offset| string_literal(object, offset + IMPORT_CALL.len()))
+            .filter_map(|(specifier, _)| resolve_reference(name, &specifier))
+            .collect();
+        let route = Route {
+            path: path.clone(),
+            source: name.to_string(),
+            chunks,
+        };
+        if !routes.contains(&route) {
+            routes.push(route);
+        }
+    }
+    routes
+}
+
+/// Reads a quoted string starting at `start`, after optional whitespace,
+/// if it could be a route path or module specifier. Returns it and the
+/// offset past its closing quote.
+fn string_literal(data: &[u8], start: usize) -> Option<(String, usize)> {
+    let start = start
+        + data
+            .get(start..)?
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+    let quote = *data.get(start)?;
+    if !matches!(quote, b'"' | b'\'' | b'`') {
+        return None;
+    }
+    let value = &data[start + 1..];
+    let length = value.iter().take(256).position(|&byte| byte == quote)?;
+    let value = &value[..length];
+    let plausible = value
+        .iter()
+        .all(|&byte| byte.is_ascii_alphanumeric() || b"/_-.:*?+()[]@~".contains(&byte));
+    plausible.then(|| {
+        (
+            String::from_utf8_lossy(value).into_owned(),
+            start + 1 + length + 1,
+        )
+    })
+}
+
+fn is_html(name: &str) -> bool {
+    has_extension(name, &["html", "htm"])
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, extension)| {
+        extensions
+            .iter()
+            .any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'$')
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
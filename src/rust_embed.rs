@@ -0,0 +1,195 @@
+//! Scan profile for assets embedded with `rust-embed`.
+//!
+//! Release builds of `rust-embed` keep a sorted array of `(path,
+//! EmbeddedFile)` pairs. Each file borrows its raw bytes and carries metadata
+//! that includes the SHA-256 of those bytes. The compiler is free to reorder
+//! the fields, so a record is recognized as a path slice and a data slice
+//! whose hash is stored next to them, and the layout shared by most records
+//! in a range wins.
+
+use crate::asset::{Asset, AssetLocation};
+use crate::binary::{read_u64, ScanRange};
+use crate::codec::RawCodec;
+use crate::error::{Error, Result};
+use crate::image::BinaryImage;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Distance from a path slice within which the data slice and hash are
+/// searched; covers `EmbeddedFile` with the optional MIME type.
+const RECORD_WINDOW: usize = 136;
+const MAX_PATH_LEN: usize = 1024;
+const HASH_SIZE: usize = 32;
+
+/// A record found at `name_at`, with the data slice and hash relative to it.
+struct Record {
+    name_at: usize,
+    data_at: isize,
+    hash_at: isize,
+    name: String,
+    data_offset: usize,
+    data_size: usize,
+}
+
+impl Record {
+    fn layout(&self) -> (isize, isize) {
+        (self.data_at, self.hash_at)
+    }
+
+    fn span(&self) -> isize {
+        let start = self.data_at.min(self.hash_at).min(0);
+        let end = (self.data_at + 16)
+            .max(self.hash_at + HASH_SIZE as isize)
+            .max(16);
+        end - start
+    }
+}
+
+pub(crate) fn scan(image: &BinaryImage, ranges: Vec<ScanRange>) -> Result<Vec<Asset>> {
+    let mut assets = Vec::new();
+    let mut hashes = HashMap::new();
+    for range in ranges {
+        let end = range
+            .start
+            .checked_add(range.length)
+            .ok_or(Error::ScanRangeOutOfBounds)?;
+        if end > image.data().len() {
+            return Err(Error::ScanRangeOutOfBounds);
+        }
+
+        let mut records = Vec::new();
+        let mut offset = range.start.next_multiple_of(8);
+        while offset + 16 <= end {
+            if let Some(name) = read_slice(image, offset)
+                .and_then(|(start, len)| path_name(image.data(), start, len))
+            {
+                records.extend(find_records(image, range, offset, &name, &mut hashes));
+            }
+            offset += 8;
+        }
+
+        let mut counts: HashMap<(isize, isize), (usize, isize)> = HashMap::new();
+        for record in &records {
+            let entry = counts.entry(record.layout()).or_insert((0, record.span()));
+            entry.0 += 1;
+        }
+        let Some(layout) = counts
+            .into_iter()
+            .max_by_key(|(_, (count, span))| (*count, -span))
+            .map(|(layout, _)| layout)
+        else {
+            continue;
+        };
+
+        log::info!(
+            "using rust-embed record layout {layout:?} in {:#x}..{end:#x}",
+            range.start
+        );
+        for record in records
+            .into_iter()
+            .filter(|record| record.layout() == layout)
+        {
+            log::debug!("rust-embed file {} at {:#x}", record.name, record.name_at);
+            let data_at = record.name_at.wrapping_add_signed(record.data_at);
+            let header_offset = record
+                .name_at
+                .wrapping_add_signed(record.data_at.min(record.hash_at).min(0));
+            let data = &image.data()[record.data_offset..record.data_offset + record.data_size];
+            let location = AssetLocation {
+                header_offset,
+                name_offset: record.name_at,
+                data_offset: record.data_offset,
+                data_size_offset: data_at + 8,
+                original_compressed_size: record.data_size,
+                scan_range: range,
+                table_index: 0,
+                word_size: 8,
+                content_hash_offset: Some(record.name_at.wrapping_add_signed(record.hash_at)),
+            };
+            assets.push(
+                Asset::new(
+                    format!("/{}", record.name),
+                    data.to_vec(),
+                    data.len(),
+                    location,
+                )
+                .with_codec(Arc::new(RawCodec)),
+            );
+        }
+    }
+    Ok(assets)
+}
+
+/// Finds data slices around the path slice at `name_at` whose hash is
+/// stored within the same window.
+fn find_records(
+    image: &BinaryImage,
+    range: ScanRange,
+    name_at: usize,
+    name: &str,
+    hashes: &mut HashMap<(usize, usize), [u8; HASH_SIZE]>,
+) -> Vec<Record> {
+    let window_start = name_at.saturating_sub(RECORD_WINDOW).max(range.start);
+    let window_end = (name_at + 16 + RECORD_WINDOW).min(range.start + range.length);
+    let window = &image.data()[window_start..window_end];
+
+    let mut records = Vec::new();
+    let mut data_at = window_start.next_multiple_of(8);
+    while data_at + 16 <= window_end {
+        if data_at.abs_diff(name_at) < 16 {
+            data_at += 8;
+            continue;
+        }
+        if let Some((start, len)) = read_slice(image, data_at) {
+            let hash = *hashes
+                .entry((start, len))
+                .or_insert_with(|| Sha256::digest(&image.data()[start..start + len]).into());
+            if let Some(hash_at) = window
+                .windows(HASH_SIZE)
+                .position(|candidate| candidate == hash)
+                .map(|index| window_start + index)
+            {
+                records.push(Record {
+                    name_at,
+                    data_at: data_at as isize - name_at as isize,
+                    hash_at: hash_at as isize - name_at as isize,
+                    name: name.to_string(),
+                    data_offset: start,
+                    data_size: len,
+                });
+            }
+        }
+        data_at += 8;
+    }
+    records
+}
+
+/// Reads a `(pointer, length)` slice at `offset` and returns its file range.
+fn read_slice(image: &BinaryImage, offset: usize) -> Option<(usize, usize)> {
+    let parser = image.parser();
+    let pointer = parser.read_pointer(image.data(), offset).ok()?;
+    let len = usize::try_from(read_u64(image.data(), offset + 8).ok()?).ok()?;
+    if len == 0 || len > image.data().len() {
+        return None;
+    }
+    let start = usize::try_from(parser.resolve_pointer(pointer).ok()?).ok()?;
+    (start.checked_add(len)? <= image.data().len()).then_some((start, len))
+}
+
+/// Returns the string at `start` if it looks like a relative file path.
+fn path_name(data: &[u8], start: usize, len: usize) -> Option<String> {
+    if len > MAX_PATH_LEN {
+        return None;
+    }
+    let name = std::str::from_utf8(&data[start..start + len]).ok()?;
+    let plausible = !name.starts_with('/')
+        && !name.ends_with('/')
+        && !name
+            .chars()
+            .any(|c| c.is_control() || "<>\"|?*\\{};".contains(c))
+        && name
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    plausible.then(|| name.to_string())
+}
@@ -0,0 +1,617 @@
+//! Software bills of materials for the npm packages bundled into the assets.
+//!
+//! Bundlers inline dependencies into a few minified files, but traces of each
+//! package survive: the `/*! name vX.Y.Z */` banners minifiers keep, the
+//! `node_modules` paths listed in source maps' `sources`, the runtime
+//! signatures [`frontend`](crate::frontend) recognizes, and strings only a
+//! known library's code contains. The packages found are written as a
+//! CycloneDX or SPDX document, or matched against known vulnerabilities with
+//! [`osv`](crate::osv).
+
+use crate::asset::AssetTable;
+use crate::binary::BinaryMetadata;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::frontend;
+use crate::licenses::{detect_licenses, is_license_file};
+use crate::time::utc_timestamp;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::SystemTime;
+
+/// Evidence kept per package.
+const MAX_EVIDENCE: usize = 5;
+
+/// Bytes of a banner comment read for its name, version and license.
+const MAX_BANNER_LEN: usize = 300;
+
+/// SPDX identifiers reported by license detection that SPDX documents accept.
+const SPDX_IDS: &[&str] = &[
+    "MIT",
+    "Apache-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "ISC",
+    "MPL-2.0",
+    "GPL-2.0",
+    "GPL-3.0",
+    "Unlicense",
+];
+
+/// Banner names that differ from the package name.
+const BANNER_ALIASES: &[(&str, &str)] = &[("vue.js", "vue")];
+
+/// Packages implied by the frameworks [`frontend::detect_in`] recognizes.
+const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[
+    ("React", "react"),
+    ("Vue", "vue"),
+    ("Svelte", "svelte"),
+    ("SvelteKit", "@sveltejs/kit"),
+    ("Next.js", "next"),
+    ("Angular", "@angular/core"),
+    ("Solid", "solid-js"),
+];
+
+/// Code of a well-known library that minifiers leave recognizable.
+struct LibraryFingerprint {
+    package: &'static str,
+    /// Strings only the library's code contains.
+    markers: &'static [&'static str],
+    /// How many bytes before a marker the version is looked for, as the
+    /// last quoted version there. Zero when the library's code does not keep
+    /// its version next to the marker.
+    version_window: usize,
+}
+
+const LIBRARY_FINGERPRINTS: &[LibraryFingerprint] = &[
+    // `var VERSION = '4.17.21'` is declared a few constants before
+    // `HASH_UNDEFINED`.
+    LibraryFingerprint {
+        package: "lodash",
+        markers: &["__lodash_hash_undefined__"],
+        version_window: 400,
+    },
+    // `var version = "3.7.1"` comes right before `jQuery.fn = { jquery: version`.
+    LibraryFingerprint {
+        package: "jquery",
+        markers: &[".prototype={jquery:"],
+        version_window: 200,
+    },
+    // `DOMPurify.version = VERSION` is followed by `DOMPurify.removed = []`.
+    LibraryFingerprint {
+        package: "dompurify",
+        markers: &[".removed=[]"],
+        version_window: 40,
+    },
+    LibraryFingerprint {
+        package: "axios",
+        markers: &["AxiosHeaders", "[Axios v"],
+        version_window: 0,
+    },
+    LibraryFingerprint {
+        package: "moment",
+        markers: &["momentProperties"],
+        version_window: 0,
+    },
+];
+
+/// Output format of [`Sbom::to_document`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON.
+    #[default]
+    CycloneDx,
+    /// SPDX 2.3 JSON.
+    Spdx,
+}
+
+/// How a package was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceSource {
+    /// A `/*! … */` or `@license` banner.
+    Banner,
+    /// A `node_modules` path in a source map's `sources`.
+    SourceMap,
+    /// A runtime signature of a known framework.
+    Signature,
+    /// A string only a known library's code contains.
+    Fingerprint,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageEvidence {
+    pub asset: String,
+    pub source: EvidenceSource,
+}
+
+/// An npm package bundled into the assets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledPackage {
+    pub name: String,
+    pub version: Option<String>,
+    /// SPDX identifiers of the licenses named in the package's banners.
+    pub licenses: BTreeSet<String>,
+    pub evidence: Vec<PackageEvidence>,
+}
+
+impl BundledPackage {
+    /// The package URL, such as `pkg:npm/%40vue/shared@3.4.21`.
+    pub fn purl(&self) -> String {
+        let name = self.name.replace('@', "%40");
+        match &self.version {
+            Some(version) => format!("pkg:npm/{name}@{version}"),
+            None => format!("pkg:npm/{name}"),
+        }
+    }
+}
+
+/// The packages bundled into one binary's assets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sbom {
+    pub binary: BinaryMetadata,
+    pub packages: Vec<BundledPackage>,
+}
+
+impl Sbom {
+    /// Finds the packages bundled into the assets of `table`.
+    pub fn from_table(table: &AssetTable) -> Result<Self> {
+        let mut assets = Vec::new();
+        for asset in table.assets() {
+            if asset.alias_of().is_some() || !is_searched(asset.name()) {
+                continue;
+            }
+            assets.push((asset.name().to_string(), decompress_asset(asset)?));
+        }
+        Ok(Self {
+            binary: table.metadata().clone(),
+            packages: packages_in(
+                assets
+                    .iter()
+                    .map(|(name, data)| (name.as_str(), data.as_slice())),
+            ),
+        })
+    }
+
+    /// Renders the SBOM in `format`. SPDX documents record `created` as
+    /// their creation time.
+    pub fn to_document(&self, format: SbomFormat, created: SystemTime) -> Value {
+        match format {
+            SbomFormat::CycloneDx => self.to_cyclonedx(),
+            SbomFormat::Spdx => self.to_spdx(created),
+        }
+    }
+
+    fn application_name(&self) -> String {
+        self.binary
+            .bundle
+            .as_ref()
+            .and_then(|bundle| bundle.name.clone())
+            .or_else(|| {
+                let path = self.binary.source_path.as_deref()?;
+                Some(path.rsplit(['/', '\\']).next()?.to_string())
+            })
+            .unwrap_or_else(|| "application".to_string())
+    }
+
+    fn to_cyclonedx(&self) -> Value {
+        let mut application = json!({
+            "type": "application",
+            "bom-ref": "application",
+            "name": self.application_name(),
+            "hashes": [{ "alg": "SHA-256", "content": self.binary.sha256 }],
+        });
+        if let Some(version) = self.binary.bundle.as_ref().and_then(|b| b.version.clone()) {
+            application["version"] = json!(version);
+        }
+        let components = self
+            .packages
+            .iter()
+            .map(|package| {
+                let mut component = json!({
+                    "type": "library",
+                    "bom-ref": package.purl(),
+                    "name": package.name,
+                    "purl": package.purl(),
+                    "evidence": {
+                        "occurrences": package
+                            .evidence
+                            .iter()
+                            .map(|evidence| json!({ "location": evidence.asset }))
+                            .collect::<Vec<_>>(),
+                    },
+                });
+                if let Some(version) = &package.version {
+                    component["version"] = json!(version);
+                }
+                if !package.licenses.is_empty() {
+                    component["licenses"] = package
+                        .licenses
+                        .iter()
+                        .map(|license| {
+                            if SPDX_IDS.contains(&license.as_str()) {
+                                json!({ "license": { "id": license } })
+                            } else {
+                                json!({ "license": { "name": license } })
+                            }
+                        })
+                        .collect();
+                }
+                component
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "version": 1,
+            "metadata": {
+                "tools": {
+                    "components": [{
+                        "type": "application",
+                        "name": env!("CARGO_PKG_NAME"),
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }],
+                },
+                "component": application,
+            },
+            "components": components,
+            "dependencies": [{
+                "ref": "application",
+                "dependsOn": self.packages.iter().map(BundledPackage::purl).collect::<Vec<_>>(),
+            }],
+        })
+    }
+
+    fn to_spdx(&self, created: SystemTime) -> Value {
+        let name = self.application_name();
+        let mut packages = vec![json!({
+            "name": name,
+            "SPDXID": "SPDXRef-Application",
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "checksums": [{ "algorithm": "SHA256", "checksumValue": self.binary.sha256 }],
+        })];
+        let mut relationships = vec![json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": "SPDXRef-Application",
+        })];
+        for (index, package) in self.packages.iter().enumerate() {
+            let id = format!("SPDXRef-Package-{}", index + 1);
+            let licenses = package
+                .licenses
+                .iter()
+                .filter(|license| SPDX_IDS.contains(&license.as_str()))
+                .cloned()
+                .collect::<Vec<_>>();
+            packages.push(json!({
+                "name": package.name,
+                "SPDXID": id,
+                "versionInfo": package.version.as_deref().unwrap_or("NOASSERTION"),
+                "downloadLocation": "NOASSERTION",
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": if licenses.is_empty() {
+                    "NOASSERTION".to_string()
+                } else {
+                    licenses.join(" AND ")
+                },
+                "externalRefs": [{
+                    "referenceCategory": "PACKAGE-MANAGER",
+                    "referenceType": "purl",
+                    "referenceLocator": package.purl(),
+                }],
+            }));
+            relationships.push(json!({
+                "spdxElementId": "SPDXRef-Application",
+                "relationshipType": "CONTAINS",
+                "relatedSpdxElement": id,
+            }));
+        }
+        json!({
+            "spdxVersion": "SPDX-2.3",
+            "dataLicense": "CC0-1.0",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": format!("{name} bundled packages"),
+            "documentNamespace": format!(
+                "https://spdx.org/spdxdocs/{}-{}",
+                env!("CARGO_PKG_NAME"),
+                self.binary.sha256
+            ),
+            "creationInfo": {
+                "created": utc_timestamp(created),
+                "creators": [format!(
+                    "Tool: {}-{}",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                )],
+            },
+            "packages": packages,
+            "relationships": relationships,
+        })
+    }
+}
+
+/// Finds the packages in decoded assets given as name and content, sorted
+/// by name and version. A package found both with and without a version is
+/// reported once, with it.
+pub fn packages_in<'a>(
+    assets: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Vec<BundledPackage> {
+    let mut found = BTreeMap::<String, BTreeMap<Option<String>, BundledPackage>>::new();
+    let mut add = |name: String, version: Option<String>, licenses: Vec<String>, evidence| {
+        let package = found
+            .entry(name.clone())
+            .or_default()
+            .entry(version.clone())
+            .or_insert_with(|| BundledPackage {
+                name,
+                version,
+                licenses: BTreeSet::new(),
+                evidence: Vec::new(),
+            });
+        package.licenses.extend(licenses);
+        if package.evidence.len() < MAX_EVIDENCE && !package.evidence.contains(&evidence) {
+            package.evidence.push(evidence);
+        }
+    };
+
+    let mut scripts = Vec::new();
+    for (asset, data) in assets {
+        let evidence = |source| PackageEvidence {
+            asset: asset.to_string(),
+            source,
+        };
+        if asset.ends_with(".map") {
+            for (name, version) in source_map_packages(data) {
+                add(
+                    name,
+                    version,
+                    Vec::new(),
+                    evidence(EvidenceSource::SourceMap),
+                );
+            }
+            continue;
+        }
+        for (name, version, licenses) in banners(data) {
+            add(name, version, licenses, evidence(EvidenceSource::Banner));
+        }
+        for (name, version) in fingerprints(data) {
+            add(
+                name.to_string(),
+                version,
+                Vec::new(),
+                evidence(EvidenceSource::Fingerprint),
+            );
+        }
+        scripts.push((asset, data));
+    }
+    let report = frontend::detect_in(scripts);
+    for detection in &report.frameworks {
+        let Some((_, package)) = FRAMEWORK_PACKAGES
+            .iter()
+            .find(|(framework, _)| *framework == detection.name)
+        else {
+            continue;
+        };
+        for evidence in &detection.evidence {
+            add(
+                package.to_string(),
+                detection.version.clone(),
+                Vec::new(),
+                PackageEvidence {
+                    asset: evidence.asset.clone(),
+                    source: EvidenceSource::Signature,
+                },
+            );
+        }
+    }
+
+    let mut packages = Vec::new();
+    for (_, mut versions) in found {
+        if versions.len() > 1 {
+            if let Some(unversioned) = versions.remove(&None) {
+                let first = versions
+                    .values_mut()
+                    .next()
+                    .expect("another version exists");
+                first.licenses.extend(unversioned.licenses);
+                for evidence in unversioned.evidence {
+                    if first.evidence.len() < MAX_EVIDENCE && !first.evidence.contains(&evidence) {
+                        first.evidence.push(evidence);
+                    }
+                }
+            }
+        }
+        packages.extend(versions.into_values());
+    }
+    packages
+}
+
+fn is_searched(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    [".js", ".mjs", ".cjs", ".map", ".html"]
+        .iter()
+        .any(|extension| lower.ends_with(extension))
+        || is_license_file(name)
+}
+
+/// Returns the packages named by `node_modules` paths in a source map.
+/// pnpm's `.pnpm/<name>@<version>` directories also give the version.
+fn source_map_packages(data: &[u8]) -> Vec<(String, Option<String>)> {
+    #[derive(Deserialize)]
+    struct SourceMap {
+        #[serde(default)]
+        sources: Vec<String>,
+    }
+
+    let Ok(map) = serde_json::from_slice::<SourceMap>(data) else {
+        return Vec::new();
+    };
+    let mut packages = Vec::new();
+    for source in &map.sources {
+        let Some((_, path)) = source.rsplit_once("node_modules/") else {
+            continue;
+        };
+        let mut segments = path.split('/');
+        let Some(name) = (match segments.next() {
+            Some(scope) if scope.starts_with('@') => {
+                segments.next().map(|name| format!("{scope}/{name}"))
+            }
+            Some(name) if !name.is_empty() && !name.starts_with('.') => Some(name.to_string()),
+            _ => None,
+        }) else {
+            continue;
+        };
+        let version = source
+            .split("node_modules/.pnpm/")
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|directory| {
+                let (pnpm_name, version) = directory.rsplit_once('@')?;
+                let version = version.split('_').next()?;
+                (pnpm_name.replace('+', "/") == name && is_version(version))
+                    .then(|| version.to_string())
+            });
+        packages.push((name, version));
+    }
+    packages
+}
+
+/// Returns the name, version and licenses of each `/*! name vX.Y.Z */` or
+/// `@license name vX.Y.Z` banner in `data`.
+fn banners(data: &[u8]) -> Vec<(String, Option<String>, Vec<String>)> {
+    let mut found = Vec::new();
+    for opener in [&b"/*!"[..], b"@license"] {
+        let mut search = 0;
+        while let Some(at) = find(&data[search..], opener) {
+            let start = search + at + opener.len();
+            search = start;
+            let end = find(&data[start..], b"*/")
+                .map(|end| start + end)
+                .unwrap_or(data.len())
+                .min(start + MAX_BANNER_LEN);
+            let comment = String::from_utf8_lossy(&data[start..end]);
+            let tokens = comment
+                .split_whitespace()
+                .map(|token| token.trim_matches(|c| matches!(c, '*' | ',' | ':' | '|')))
+                .filter(|token| !token.is_empty())
+                .take(4)
+                .collect::<Vec<_>>();
+            let Some((name, version)) = tokens.windows(2).find_map(|pair| {
+                let version = pair[1].strip_prefix('v').unwrap_or(pair[1]);
+                (is_package_name(pair[0]) && is_version(version))
+                    .then(|| (pair[0].to_ascii_lowercase(), version.to_string()))
+            }) else {
+                continue;
+            };
+            let name = BANNER_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == name)
+                .map_or(name.clone(), |(_, package)| package.to_string());
+            let mut licenses = detect_licenses(comment.as_bytes())
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            licenses.extend(
+                comment
+                    .split(|c: char| c.is_whitespace() || matches!(c, '|' | ',' | '(' | ')'))
+                    .filter(|token| SPDX_IDS.contains(token))
+                    .map(str::to_string),
+            );
+            found.push((name, Some(version), licenses));
+        }
+    }
+    found
+}
+
+/// Returns the libraries whose fingerprints `data` contains, with the
+/// version found next to the first marker that has one.
+fn fingerprints(data: &[u8]) -> Vec<(&'static str, Option<String>)> {
+    let mut found = Vec::new();
+    for fingerprint in LIBRARY_FINGERPRINTS {
+        let mut present = false;
+        let mut version = None;
+        for marker in fingerprint.markers {
+            let mut search = 0;
+            while let Some(at) = find(&data[search..], marker.as_bytes()) {
+                let at = search + at;
+                search = at + marker.len();
+                present = true;
+                if fingerprint.version_window == 0 {
+                    break;
+                }
+                version = quoted_version_before(data, at, fingerprint.version_window);
+                if version.is_some() {
+                    break;
+                }
+            }
+            if version.is_some() {
+                break;
+            }
+        }
+        if present {
+            found.push((fingerprint.package, version));
+        }
+    }
+    found
+}
+
+/// Returns the last quoted version, such as `"4.17.21"`, within the `window`
+/// bytes before `end`.
+fn quoted_version_before(data: &[u8], end: usize, window: usize) -> Option<String> {
+    let text = &data[end.saturating_sub(window)..end];
+    let mut found = None;
+    for (start, &quote) in text.iter().enumerate() {
+        if quote != b'"' && quote != b'\'' {
+            continue;
+        }
+        let rest = &text[start + 1..];
+        let Some(len) = rest.iter().take(32).position(|&byte| byte == quote) else {
+            continue;
+        };
+        if let Ok(candidate) = std::str::from_utf8(&rest[..len]) {
+            if is_version(candidate) {
+                found = Some(candidate.to_string());
+            }
+        }
+    }
+    found
+}
+
+fn is_package_name(token: &str) -> bool {
+    let name = match token.strip_prefix('@') {
+        Some(scoped) => match scoped.split_once('/') {
+            Some((scope, name)) if !scope.is_empty() => name,
+            _ => return false,
+        },
+        None => token,
+    };
+    !name.is_empty()
+        && name.len() <= 214
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+}
+
+/// Accepts versions such as `3.4.21` or `1.0.0-beta.2`.
+fn is_version(text: &str) -> bool {
+    let core = text.split(['-', '+']).next().unwrap_or_default();
+    let parts = core.split('.').collect::<Vec<_>>();
+    parts.len() >= 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
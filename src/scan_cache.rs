@@ -0,0 +1,93 @@
+//! Headers found by earlier scans, so that repeated commands on the same
+//! large binary, such as `list`, then `grep`, then `extract`, scan it once.
+//!
+//! Entries are keyed by the SHA-256 of the binary and a hash of the scan
+//! options, so a changed binary or other options scan again. Asset data is
+//! still read from the binary and validated; only the search for headers is
+//! skipped. Past [`ScanCache::max_entries`], the least recently used entries
+//! are removed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Binaries smaller than this scan in well under a second and are not
+/// cached by default.
+pub const DEFAULT_CACHE_MIN_SIZE: usize = 8 * 1024 * 1024;
+
+/// Entries kept before the least recently used are removed.
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanCache {
+    pub dir: PathBuf,
+    /// Binaries smaller than this many bytes are scanned every time.
+    pub min_size: usize,
+    /// Entries kept in `dir`; older ones are removed as new ones are saved.
+    pub max_entries: usize,
+}
+
+impl ScanCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            min_size: DEFAULT_CACHE_MIN_SIZE,
+            max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+        }
+    }
+
+    /// `tauri-dumper` in the user's cache directory: `$XDG_CACHE_HOME` or
+    /// `~/.cache` on Unix, `%LOCALAPPDATA%` on Windows.
+    pub fn default_dir() -> Option<PathBuf> {
+        let base = if cfg!(windows) {
+            std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("XDG_CACHE_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        }?;
+        Some(base.join("tauri-dumper"))
+    }
+
+    pub(crate) fn path(&self, sha256: &str, fingerprint: &str) -> PathBuf {
+        let options = fingerprint.get(..16).unwrap_or(fingerprint);
+        self.dir.join(format!("{sha256}-{options}.json"))
+    }
+
+    /// Marks the entry at `path` as just used, so eviction keeps it longest.
+    pub(crate) fn touch(&self, path: &Path) {
+        let touched = fs::File::options()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        if let Err(error) = touched {
+            log::debug!("cannot update {}: {error}", path.display());
+        }
+    }
+
+    /// Removes the least recently used entries past `max_entries`.
+    pub(crate) fn evict(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let path = entry.path();
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                (path.extension()? == "json").then_some((modified, path))
+            })
+            .collect::<Vec<_>>();
+        if entries.len() <= self.max_entries {
+            return;
+        }
+        entries.sort();
+        let excess = entries.len() - self.max_entries;
+        for (_, path) in entries.into_iter().take(excess) {
+            if let Err(error) = fs::remove_file(&path) {
+                log::warn!("cannot remove cached scan {}: {error}", path.display());
+            }
+        }
+    }
+}
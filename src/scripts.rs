@@ -0,0 +1,154 @@
+//! Scripts Tauri injects into the webview.
+//!
+//! Besides the frontend assets, Tauri compiles its own JavaScript into the
+//! binary with `include_str!`: the IPC bridge, the isolation pattern's
+//! iframe payload and the initialization scripts that set up
+//! `window.__TAURI__`. They are stored as plain text, so each one is found by
+//! a marker it is known to contain and extended to the surrounding run of
+//! text. String literals the compiler placed next to a script end up in the
+//! same run.
+
+#[cfg(feature = "fs")]
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "fs")]
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+
+/// Directory, inside an export, that injected scripts are written to.
+pub const SCRIPTS_DIR: &str = "injected-scripts";
+
+/// Runs of text shorter than this are identifiers or messages that mention a
+/// marker, not scripts.
+const MIN_SCRIPT_LEN: usize = 128;
+
+/// Markers of each script, most specific first. Templates that Tauri fills
+/// in at runtime keep their `__TEMPLATE_*__` and `__RAW_*__` placeholders.
+const MARKERS: &[(ScriptKind, &[u8])] = &[
+    (ScriptKind::Isolation, b"__TAURI_ISOLATION_HOOK__"),
+    (ScriptKind::Isolation, b"__TEMPLATE_isolation_"),
+    (ScriptKind::Ipc, b"__TAURI_POST_MESSAGE__"),
+    (ScriptKind::Ipc, b"__RAW_process_ipc_message_fn__"),
+    (ScriptKind::Ipc, b"window.__TAURI_IPC__"),
+    (
+        ScriptKind::FreezePrototype,
+        b"Object.freeze(Object.prototype",
+    ),
+    (ScriptKind::Init, b"__TAURI_INTERNALS__"),
+    (ScriptKind::Init, b"__TAURI_METADATA__"),
+    (ScriptKind::Init, b"__TEMPLATE_"),
+];
+
+/// What an injected script does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptKind {
+    /// The bridge that posts `invoke` calls to the core process.
+    Ipc,
+    /// The isolation pattern's iframe payload and hook.
+    Isolation,
+    /// Freezes `Object.prototype` when `freezePrototype` is enabled.
+    FreezePrototype,
+    /// Scripts that set up `window.__TAURI__` and its internals.
+    Init,
+}
+
+impl ScriptKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Ipc => "ipc",
+            Self::Isolation => "isolation",
+            Self::FreezePrototype => "freeze-prototype",
+            Self::Init => "init",
+        }
+    }
+}
+
+/// A script found by [`find_injected_scripts`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InjectedScript {
+    pub kind: ScriptKind,
+    /// File offset of the first byte of the script.
+    pub offset: usize,
+    pub source: String,
+}
+
+/// Finds the scripts Tauri injects into the webview in `data`, in offset
+/// order. A run of text containing markers of several kinds is reported
+/// once, as the kind listed first in [`MARKERS`].
+pub fn find_injected_scripts(data: &[u8]) -> Vec<InjectedScript> {
+    let mut runs = Vec::<(usize, usize, ScriptKind)>::new();
+    for &(kind, marker) in MARKERS {
+        let mut search = 0;
+        while let Some(found) = find(&data[search..], marker) {
+            let at = search + found;
+            search = at + marker.len();
+            if runs
+                .iter()
+                .any(|&(start, end, _)| (start..end).contains(&at))
+            {
+                continue;
+            }
+            let start = data[..at]
+                .iter()
+                .rposition(|&byte| !is_text(byte))
+                .map_or(0, |index| index + 1);
+            let end = data[at..]
+                .iter()
+                .position(|&byte| !is_text(byte))
+                .map_or(data.len(), |index| at + index);
+            search = end;
+            if end - start >= MIN_SCRIPT_LEN {
+                runs.push((start, end, kind));
+            } else {
+                log::debug!("skipping {}-byte text run at {start:#x}", end - start);
+            }
+        }
+    }
+    runs.sort_unstable();
+    runs.into_iter()
+        .map(|(start, end, kind)| InjectedScript {
+            kind,
+            offset: start,
+            source: String::from_utf8_lossy(&data[start..end]).into_owned(),
+        })
+        .collect()
+}
+
+/// Writes each script to `dir` as `<kind>.js`, numbering scripts of the same
+/// kind after the first, and returns the written paths.
+#[cfg(feature = "fs")]
+pub fn write(scripts: &[InjectedScript], dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if scripts.is_empty() {
+        return Ok(files);
+    }
+    fs::create_dir_all(dir)?;
+    let mut counts = BTreeMap::<ScriptKind, usize>::new();
+    for script in scripts {
+        let count = counts.entry(script.kind).or_default();
+        let path = match *count {
+            0 => dir.join(format!("{}.js", script.kind.name())),
+            n => dir.join(format!("{}-{}.js", script.kind.name(), n + 1)),
+        };
+        *count += 1;
+        fs::write(&path, &script.source)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+/// Bytes that may appear in an embedded script. Scripts are ASCII, with
+/// non-ASCII characters escaped by the bundler.
+fn is_text(byte: u8) -> bool {
+    matches!(byte, b'\t' | b'\n' | b'\r' | 0x20..=0x7e)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -0,0 +1,364 @@
+//! Service workers and the files they precache.
+//!
+//! A frontend that works offline registers a service worker, which caches
+//! the app shell when it installs. Workbox, which the PWA plugins of Vite
+//! and webpack build on, lists those files in a precache manifest inlined
+//! into the worker, as in
+//! `precacheAndRoute([{url:"assets/index-4f2a.js",revision:null}, …])`, or
+//! in Workbox 4 in an imported `precache-manifest.<hash>.js`. Hand-written
+//! workers pass the list to `cache.addAll`. Entries resolve against the
+//! worker's path to assets, so the offline bundle can be rebuilt from the
+//! binary and the entries it does not embed stand out.
+
+use crate::asset::AssetTable;
+use crate::error::Result;
+use crate::extract::decompress_asset;
+use crate::html::resolve_reference;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Calls that register a worker, followed by its URL.
+const REGISTER_CALL: &[u8] = b"serviceWorker.register(";
+/// Code only a worker runs: Workbox's precaching and the lifecycle events.
+const WORKER_MARKERS: &[&[u8]] = &[
+    b"precacheAndRoute(",
+    b"addEventListener(\"install\"",
+    b"addEventListener('install'",
+    b"self.oninstall",
+];
+/// Where a list of precached URLs follows, within [`ARRAY_WINDOW`] bytes.
+const MANIFEST_ANCHORS: &[&[u8]] = &[b"precacheAndRoute(", b"__precacheManifest", b"addAll("];
+/// Calls and module wrappers that load other scripts into a worker.
+const IMPORT_ANCHORS: &[&[u8]] = &[b"importScripts(", b"define(["];
+const ARRAY_WINDOW: usize = 64;
+/// Longest precache manifest read, in bytes.
+const MAX_MANIFEST_LEN: usize = 4 << 20;
+const SCRIPT_EXTENSIONS: &[&str] = &["js", "mjs", "cjs"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecacheEntry {
+    /// The URL as the manifest lists it.
+    pub url: String,
+    /// The Workbox revision, which is `None` for URLs with a content hash
+    /// in their name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    /// The asset the URL resolves to, or `None` for other origins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Whether the binary embeds the asset.
+    pub embedded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceWorker {
+    pub name: String,
+    /// The pages and scripts that register the worker.
+    pub registered_by: Vec<String>,
+    /// Whether the worker is built with Workbox.
+    pub workbox: bool,
+    /// The embedded scripts the worker imports.
+    pub imports: Vec<String>,
+    /// The files the worker precaches, in manifest order.
+    pub precache: Vec<PrecacheEntry>,
+}
+
+impl ServiceWorker {
+    /// Precached files of the app's own origin that the binary does not
+    /// embed, so the app misses them offline.
+    pub fn missing(&self) -> impl Iterator<Item = &PrecacheEntry> {
+        self.precache
+            .iter()
+            .filter(|entry| entry.path.is_some() && !entry.embedded)
+    }
+}
+
+/// Finds the service workers among the assets of `table`.
+pub fn find_service_workers(table: &AssetTable) -> Result<Vec<ServiceWorker>> {
+    let mut assets = Vec::new();
+    for asset in table.assets() {
+        let searched = is_html(asset.name()) || has_extension(asset.name(), SCRIPT_EXTENSIONS);
+        let data = if asset.alias_of().is_none() && searched {
+            decompress_asset(asset)?
+        } else {
+            Vec::new()
+        };
+        assets.push((asset.name().to_string(), data));
+    }
+    Ok(find_service_workers_in(
+        assets
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice())),
+    ))
+}
+
+/// Like [`find_service_workers`], for decoded assets given as name and
+/// content.
+pub fn find_service_workers_in<'a>(
+    assets: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Vec<ServiceWorker> {
+    let assets = assets.into_iter().collect::<BTreeMap<_, _>>();
+    let names = assets.keys().copied().collect::<BTreeSet<_>>();
+
+    let mut registered_by = BTreeMap::<String, Vec<String>>::new();
+    for (&name, &data) in &assets {
+        // Scripts register workers by URLs relative to the page, which
+        // is at the root for a Tauri app.
+        let base = if is_html(name) { name } else { "/" };
+        for offset in find_all(data, REGISTER_CALL) {
+            let Some((url, _)) = string_literal(data, offset + REGISTER_CALL.len()) else {
+                continue;
+            };
+            if let Some(worker) = resolve_reference(base, &url) {
+                let pages = registered_by.entry(worker).or_default();
+                if !pages.contains(&name.to_string()) {
+                    pages.push(name.to_string());
+                }
+            }
+        }
+    }
+
+    let candidates = assets
+        .iter()
+        .filter(|(name, data)| {
+            registered_by.contains_key(**name)
+                || (has_extension(name, SCRIPT_EXTENSIONS)
+                    && WORKER_MARKERS
+                        .iter()
+                        .any(|marker| find(data, marker).is_some()))
+        })
+        .map(|(&name, _)| (name, imports(name, assets[name], &names)))
+        .collect::<Vec<_>>();
+    // Workbox's own runtime handles the lifecycle events too, but is only
+    // loaded by the worker.
+    let imported = candidates
+        .iter()
+        .flat_map(|(_, imports)| imports.iter().map(String::as_str))
+        .collect::<BTreeSet<_>>();
+
+    let mut workers = Vec::new();
+    for (name, imports) in &candidates {
+        if imported.contains(name) && !registered_by.contains_key(*name) {
+            continue;
+        }
+        let data = assets[name];
+        let mut precache = Vec::<PrecacheEntry>::new();
+        let sources = std::iter::once(data).chain(imports.iter().map(|path| assets[path.as_str()]));
+        for source in sources {
+            for (url, revision) in manifest_entries(source) {
+                if precache.iter().any(|entry| entry.url == url) {
+                    continue;
+                }
+                let resolved = resolve_reference(name, &url);
+                let embedded = resolved
+                    .as_deref()
+                    .and_then(|path| embedded_name(&names, path));
+                precache.push(PrecacheEntry {
+                    embedded: embedded.is_some(),
+                    path: embedded.map(str::to_string).or(resolved),
+                    url,
+                    revision,
+                });
+            }
+        }
+        workers.push(ServiceWorker {
+            name: name.to_string(),
+            registered_by: registered_by.get(*name).cloned().unwrap_or_default(),
+            workbox: find(data, b"workbox").is_some()
+                || imports.iter().any(|path| path.contains("workbox")),
+            imports: imports.clone(),
+            precache,
+        });
+    }
+    workers
+}
+
+/// The embedded scripts the worker `name` loads with `importScripts` or as
+/// the dependencies of its `define` wrapper.
+fn imports(name: &str, data: &[u8], names: &BTreeSet<&str>) -> Vec<String> {
+    let mut imports = Vec::new();
+    for anchor in IMPORT_ANCHORS {
+        for offset in find_all(data, anchor) {
+            let mut at = offset + anchor.len();
+            while let Some((url, end)) = string_literal(data, at) {
+                let found = resolve_reference(name, &url).and_then(|path| {
+                    embedded_name(names, &path)
+                        .or_else(|| embedded_name(names, &format!("{path}.js")))
+                        .map(str::to_string)
+                });
+                if let Some(path) = found.filter(|path| !imports.contains(path)) {
+                    imports.push(path);
+                }
+                match next_token(data, end) {
+                    Some((Token::Punct(b','), next)) => at = next,
+                    _ => break,
+                }
+            }
+        }
+    }
+    imports
+}
+
+/// The embedded asset `path` names, the `index.html` of a directory.
+fn embedded_name<'a>(names: &BTreeSet<&'a str>, path: &str) -> Option<&'a str> {
+    let index = format!("{}/index.html", path.trim_end_matches('/'));
+    names
+        .get(path)
+        .or_else(|| names.get(index.as_str()))
+        .copied()
+}
+
+/// The URLs, with their revisions, of the precache manifests in `data`.
+fn manifest_entries(data: &[u8]) -> Vec<(String, Option<String>)> {
+    let mut entries = Vec::<(String, Option<String>)>::new();
+    for anchor in MANIFEST_ANCHORS {
+        for offset in find_all(data, anchor) {
+            let start = offset + anchor.len();
+            let window = &data[start..(start + ARRAY_WINDOW).min(data.len())];
+            // Skip the empty array of `(self.__precacheManifest || [])`.
+            let Some(open) = (0..window.len())
+                .find(|&index| window[index] == b'[' && window.get(index + 1) != Some(&b']'))
+            else {
+                continue;
+            };
+            for entry in array_entries(data, start + open).unwrap_or_default() {
+                if !entries.iter().any(|(url, _)| *url == entry.0) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// Reads the array literal opening at `open`, of URL strings or of objects
+/// with `url` and `revision` keys. Returns `None` if it holds anything
+/// else.
+fn array_entries(data: &[u8], open: usize) -> Option<Vec<(String, Option<String>)>> {
+    let data = &data[..(open + MAX_MANIFEST_LEN).min(data.len())];
+    let mut entries = Vec::new();
+    let mut at = open + 1;
+    loop {
+        let (token, next) = next_token(data, at)?;
+        at = next;
+        match token {
+            Token::Punct(b']') => return Some(entries),
+            Token::Punct(b',') => {}
+            Token::Str(url) => entries.push((url, None)),
+            Token::Punct(b'{') => {
+                let (mut url, mut revision) = (None, None);
+                loop {
+                    let (key, next) = next_token(data, at)?;
+                    at = next;
+                    let key = match key {
+                        Token::Punct(b'}') => break,
+                        Token::Punct(b',') => continue,
+                        Token::Str(key) | Token::Word(key) => key,
+                        Token::Punct(_) => return None,
+                    };
+                    let (Token::Punct(b':'), next) = next_token(data, at)? else {
+                        return None;
+                    };
+                    let (value, next) = next_token(data, next)?;
+                    at = next;
+                    match (key.as_str(), value) {
+                        ("url", Token::Str(value)) => url = Some(value),
+                        ("revision", Token::Str(value)) => revision = Some(value),
+                        (_, Token::Str(_) | Token::Word(_)) => {}
+                        (_, Token::Punct(_)) => return None,
+                    }
+                }
+                entries.push((url?, revision));
+            }
+            Token::Word(_) | Token::Punct(_) => return None,
+        }
+    }
+}
+
+enum Token {
+    Str(String),
+    /// An identifier, keyword or number.
+    Word(String),
+    Punct(u8),
+}
+
+/// Reads the token at `at`, after any whitespace, returning it and the
+/// offset past it.
+fn next_token(data: &[u8], at: usize) -> Option<(Token, usize)> {
+    let at = at
+        + data
+            .get(at..)?
+            .iter()
+            .take_while(|byte| byte.is_ascii_whitespace())
+            .count();
+    let byte = *data.get(at)?;
+    if matches!(byte, b'"' | b'\'' | b'`') {
+        let mut value = Vec::new();
+        let mut index = at + 1;
+        loop {
+            match *data.get(index)? {
+                b'\\' => {
+                    value.push(*data.get(index + 1)?);
+                    index += 2;
+                }
+                end if end == byte => {
+                    let value = String::from_utf8_lossy(&value).into_owned();
+                    return Some((Token::Str(value), index + 1));
+                }
+                other => {
+                    value.push(other);
+                    index += 1;
+                }
+            }
+        }
+    }
+    if is_identifier_byte(byte) {
+        let length = data[at..]
+            .iter()
+            .take_while(|&&byte| is_identifier_byte(byte) || byte == b'.')
+            .count();
+        let word = String::from_utf8_lossy(&data[at..at + length]).into_owned();
+        return Some((Token::Word(word), at + length));
+    }
+    Some((Token::Punct(byte), at + 1))
+}
+
+/// Reads a quoted string starting at `start`, after optional whitespace.
+/// Returns it and the offset past its closing quote.
+fn string_literal(data: &[u8], start: usize) -> Option<(String, usize)> {
+    match next_token(data, start)? {
+        (Token::Str(value), end) => Some((value, end)),
+        _ => None,
+    }
+}
+
+fn is_html(name: &str) -> bool {
+    has_extension(name, &["html", "htm"])
+}
+
+fn has_extension(name: &str, extensions: &[&str]) -> bool {
+    name.rsplit_once('.').is_some_and(|(_, extension)| {
+        extensions
+            .iter()
+            .any(|known| extension.eq_ignore_ascii_case(known))
+    })
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'$')
+}
+
+fn find_all<'a>(haystack: &'a [u8], needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+    let mut search = 0;
+    std::iter::from_fn(move || {
+        let found = search + find(&haystack[search..], needle)?;
+        search = found + needle.len();
+        Some(found)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
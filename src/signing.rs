@@ -0,0 +1,420 @@
+//! Ed25519 signatures over extraction manifests and Tauri updater artifacts.
+//!
+//! Signatures and public keys use the minisign text formats, so a manifest
+//! signed here can also be checked with `minisign -Vm <manifest> -p <key>`.
+//! Signatures made here are of the legacy `Ed` kind, over the file itself
+//! rather than its BLAKE2b hash, and carry a trusted comment with the signing
+//! time and the file name that the signature covers too. Both kinds are
+//! verified.
+//!
+//! The Tauri updater signs its artifacts with minisign too, but stores the
+//! public key in the app config and the signature in a `.sig` file as the
+//! base64 of the minisign text. Both are accepted wherever keys and
+//! signatures are read.
+//!
+//! Secret keys are stored unencrypted, as the base64 of their key ID and
+//! 32-byte seed, and have to be kept private by the file system.
+
+use crate::error::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use blake2::{Blake2b512, Digest};
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The extension of signature files, added to the name of the signed file.
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+/// The extension of the signatures the Tauri CLI writes next to updater
+/// artifacts.
+pub const UPDATER_SIGNATURE_EXTENSION: &str = "sig";
+
+const ALGORITHM: &[u8; 2] = b"Ed";
+/// Signatures of the BLAKE2b hash, which minisign makes by default.
+const PREHASHED_ALGORITHM: &[u8; 2] = b"ED";
+const UNTRUSTED_COMMENT: &str = "untrusted comment: ";
+const TRUSTED_COMMENT: &str = "trusted comment: ";
+/// `untrusted comment:` in base64, which starts the config value of a Tauri
+/// updater public key.
+const ENCODED_UNTRUSTED_COMMENT: &[u8] = b"dW50cnVzdGVkIGNvbW1lbnQ6";
+
+pub type KeyId = [u8; 8];
+
+pub struct SecretKey {
+    key_id: KeyId,
+    key: SigningKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKey {
+    key_id: KeyId,
+    key: VerifyingKey,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub key_id: KeyId,
+    /// Whether `signature` is over the BLAKE2b-512 hash of the file, the
+    /// `ED` kind minisign and the Tauri CLI make by default.
+    pub prehashed: bool,
+    pub signature: [u8; 64],
+    /// Text signed along with the file, such as its name and when it was
+    /// signed.
+    pub trusted_comment: String,
+    /// Signature over `signature` and `trusted_comment`.
+    pub global_signature: [u8; 64],
+}
+
+impl SecretKey {
+    /// Generates a key from the operating system's random source.
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0; 32];
+        let mut key_id = [0; 8];
+        getrandom::getrandom(&mut seed)
+            .and_then(|()| getrandom::getrandom(&mut key_id))
+            .map_err(|error| Error::Signature(format!("no random source: {error}")))?;
+        Ok(Self {
+            key_id,
+            key: SigningKey::from_bytes(&seed),
+        })
+    }
+
+    pub fn key_id(&self) -> KeyId {
+        self.key_id
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            key_id: self.key_id,
+            key: self.key.verifying_key(),
+        }
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let payload = read_payload(&fs::read_to_string(path)?, 2 + 8 + 32)?;
+        if &payload[..2] != ALGORITHM {
+            return Err(Error::Signature(
+                "unsupported secret key algorithm".to_string(),
+            ));
+        }
+        let seed: [u8; 32] = payload[10..].try_into().expect("length checked");
+        Ok(Self {
+            key_id: payload[2..10].try_into().expect("length checked"),
+            key: SigningKey::from_bytes(&seed),
+        })
+    }
+
+    /// Writes the key to `path`, which must not exist yet. On Unix, only the
+    /// owner can read it.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut payload = ALGORITHM.to_vec();
+        payload.extend_from_slice(&self.key_id);
+        payload.extend_from_slice(self.key.as_bytes());
+        let text = format!(
+            "{UNTRUSTED_COMMENT}tauri-dumper secret key {}\n{}\n",
+            key_id_hex(&self.key_id),
+            STANDARD.encode(payload)
+        );
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::AlreadyExists => Error::OutputExists(path.to_path_buf()),
+            _ => error.into(),
+        })?;
+        std::io::Write::write_all(&mut file, text.as_bytes())?;
+        Ok(())
+    }
+
+    /// Signs `data`, with `trusted_comment` signed along with it.
+    pub fn sign(&self, data: &[u8], trusted_comment: &str) -> Signature {
+        let signature = self.key.sign(data).to_bytes();
+        let mut global = signature.to_vec();
+        global.extend_from_slice(trusted_comment.as_bytes());
+        Signature {
+            key_id: self.key_id,
+            prehashed: false,
+            signature,
+            trusted_comment: trusted_comment.to_string(),
+            global_signature: self.key.sign(&global).to_bytes(),
+        }
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretKey")
+            .field("key_id", &key_id_hex(&self.key_id))
+            .finish_non_exhaustive()
+    }
+}
+
+impl PublicKey {
+    pub fn key_id(&self) -> KeyId {
+        self.key_id
+    }
+
+    /// Reads a minisign public key file, the base64 line on its own, or the
+    /// `pubkey` of a Tauri updater config.
+    pub fn parse(text: &str) -> Result<Self> {
+        let payload = read_payload(&unwrap_base64(text), 2 + 8 + 32)?;
+        if &payload[..2] != ALGORITHM {
+            return Err(Error::Signature(
+                "unsupported public key algorithm".to_string(),
+            ));
+        }
+        let key: [u8; 32] = payload[10..].try_into().expect("length checked");
+        Ok(Self {
+            key_id: payload[2..10].try_into().expect("length checked"),
+            key: VerifyingKey::from_bytes(&key)
+                .map_err(|_| Error::Signature("invalid public key".to_string()))?,
+        })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Checks that `signature` was made over `data` by this key.
+    pub fn verify(&self, data: &[u8], signature: &Signature) -> Result<()> {
+        if signature.key_id != self.key_id {
+            return Err(Error::Signature(format!(
+                "signed with key {}, not {}",
+                key_id_hex(&signature.key_id),
+                key_id_hex(&self.key_id)
+            )));
+        }
+        let invalid = |_| Error::Signature("signature does not match".to_string());
+        let file_signature = ed25519_dalek::Signature::from_bytes(&signature.signature);
+        if signature.prehashed {
+            let hash = Blake2b512::digest(data);
+            self.key.verify(&hash, &file_signature).map_err(invalid)?;
+        } else {
+            self.key.verify(data, &file_signature).map_err(invalid)?;
+        }
+        let mut global = signature.signature.to_vec();
+        global.extend_from_slice(signature.trusted_comment.as_bytes());
+        let global_signature = ed25519_dalek::Signature::from_bytes(&signature.global_signature);
+        self.key.verify(&global, &global_signature).map_err(invalid)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut payload = ALGORITHM.to_vec();
+        payload.extend_from_slice(&self.key_id);
+        payload.extend_from_slice(self.key.as_bytes());
+        writeln!(
+            f,
+            "{UNTRUSTED_COMMENT}minisign public key {}",
+            key_id_hex(&self.key_id)
+        )?;
+        writeln!(f, "{}", STANDARD.encode(payload))
+    }
+}
+
+impl Signature {
+    /// Reads a minisign signature file, or the `.sig` file of a Tauri
+    /// updater artifact.
+    pub fn parse(text: &str) -> Result<Self> {
+        let malformed = || Error::Signature("malformed signature file".to_string());
+        let text = unwrap_base64(text);
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.starts_with(UNTRUSTED_COMMENT));
+        let payload = decode(lines.next().ok_or_else(malformed)?, 2 + 8 + 64)?;
+        let prehashed = &payload[..2] == PREHASHED_ALGORITHM;
+        if !prehashed && &payload[..2] != ALGORITHM {
+            return Err(Error::Signature(
+                "unsupported signature algorithm".to_string(),
+            ));
+        }
+        let trusted_comment = lines
+            .next()
+            .and_then(|line| line.strip_prefix(TRUSTED_COMMENT))
+            .ok_or_else(malformed)?;
+        let global_signature = decode(lines.next().ok_or_else(malformed)?, 64)?;
+        Ok(Self {
+            key_id: payload[2..10].try_into().expect("length checked"),
+            prehashed,
+            signature: payload[10..].try_into().expect("length checked"),
+            trusted_comment: trusted_comment.to_string(),
+            global_signature: global_signature.try_into().expect("length checked"),
+        })
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = if self.prehashed {
+            PREHASHED_ALGORITHM
+        } else {
+            ALGORITHM
+        };
+        let mut payload = algorithm.to_vec();
+        payload.extend_from_slice(&self.key_id);
+        payload.extend_from_slice(&self.signature);
+        writeln!(
+            f,
+            "{UNTRUSTED_COMMENT}signature from tauri-dumper secret key"
+        )?;
+        writeln!(f, "{}", STANDARD.encode(payload))?;
+        writeln!(f, "{TRUSTED_COMMENT}{}", self.trusted_comment)?;
+        writeln!(f, "{}", STANDARD.encode(self.global_signature))
+    }
+}
+
+/// The signature file of `path`: `path` with `.minisig` added.
+pub fn signature_path(path: &Path) -> PathBuf {
+    with_extension_added(path, SIGNATURE_EXTENSION)
+}
+
+/// Signs the file at `path` and writes the signature next to it. The
+/// trusted comment records the signing time and the file name.
+pub fn sign_file(path: &Path, key: &SecretKey) -> Result<PathBuf> {
+    let data = fs::read(path)?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let file = path
+        .file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    let signature = key.sign(&data, &format!("timestamp:{timestamp}\tfile:{file}"));
+    let signature_path = signature_path(path);
+    fs::write(&signature_path, signature.to_string())?;
+    Ok(signature_path)
+}
+
+/// Checks the signature next to the file at `path` and returns it.
+pub fn verify_file(path: &Path, key: &PublicKey) -> Result<Signature> {
+    let signature = read_signature(path, &signature_path(path))?;
+    key.verify(&fs::read(path)?, &signature)?;
+    Ok(signature)
+}
+
+/// Checks the signature in the `.sig` file next to a Tauri updater
+/// artifact against `keys`, such as the keys [`embedded_public_keys`]
+/// finds in the installed app, and returns it.
+pub fn verify_updater_artifact(artifact: &Path, keys: &[PublicKey]) -> Result<Signature> {
+    let signature = read_signature(
+        artifact,
+        &with_extension_added(artifact, UPDATER_SIGNATURE_EXTENSION),
+    )?;
+    let key = keys
+        .iter()
+        .find(|key| key.key_id == signature.key_id)
+        .ok_or_else(|| {
+            Error::Signature(format!(
+                "signed with key {}, which is not a trusted updater key",
+                key_id_hex(&signature.key_id)
+            ))
+        })?;
+    key.verify(&fs::read(artifact)?, &signature)?;
+    Ok(signature)
+}
+
+/// Finds the updater public keys in a binary's strings: the base64 `pubkey`
+/// values of its Tauri config, in the order they appear and without
+/// duplicates.
+pub fn embedded_public_keys(data: &[u8]) -> Vec<PublicKey> {
+    let mut keys: Vec<PublicKey> = Vec::new();
+    let mut search = 0;
+    while let Some(found) = find(&data[search..], ENCODED_UNTRUSTED_COMMENT) {
+        let start = search + found;
+        let length = data[start..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_alphanumeric() || b"+/=".contains(byte))
+            .count();
+        search = start + length;
+        let Ok(text) = std::str::from_utf8(&data[start..start + length]) else {
+            continue;
+        };
+        if let Ok(key) = PublicKey::parse(text) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+/// The key ID as minisign prints it: the little-endian number in hex.
+pub fn key_id_hex(key_id: &KeyId) -> String {
+    format!("{:016X}", u64::from_le_bytes(*key_id))
+}
+
+fn with_extension_added(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+fn read_signature(path: &Path, signature_path: &Path) -> Result<Signature> {
+    if !signature_path.is_file() {
+        return Err(Error::Signature(format!(
+            "{} is not signed: {} does not exist",
+            path.display(),
+            signature_path.display()
+        )));
+    }
+    Signature::read(signature_path)
+}
+
+/// Decodes `text` if it is the base64 of a minisign file, as Tauri stores
+/// updater keys and signatures.
+fn unwrap_base64(text: &str) -> Cow<'_, str> {
+    let trimmed = text.trim();
+    if trimmed.starts_with(UNTRUSTED_COMMENT) {
+        return Cow::Borrowed(text);
+    }
+    STANDARD
+        .decode(trimmed)
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .filter(|decoded| decoded.starts_with(UNTRUSTED_COMMENT))
+        .map_or(Cow::Borrowed(text), Cow::Owned)
+}
+
+/// Decodes the first line of `text` that is not an untrusted comment.
+fn read_payload(text: &str, length: usize) -> Result<Vec<u8>> {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with(UNTRUSTED_COMMENT))
+        .ok_or_else(|| Error::Signature("key file holds no key".to_string()))?;
+    decode(line, length)
+}
+
+fn decode(line: &str, length: usize) -> Result<Vec<u8>> {
+    let bytes = STANDARD
+        .decode(line.trim())
+        .map_err(|error| Error::Signature(format!("invalid base64: {error}")))?;
+    if bytes.len() != length {
+        return Err(Error::Signature(format!(
+            "expected {length} bytes, found {}",
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
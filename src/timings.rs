@@ -0,0 +1,145 @@
+//! Where the time of a scan and an export goes.
+//!
+//! Pass the same [`Timings`] to [`ScanOptions`](crate::ScanOptions) and
+//! [`ExportOptions`](crate::ExportOptions) and read a [`TimingReport`] when
+//! they are done. Stages are timed as wall time on the calling thread, so
+//! decompression on several threads counts once.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Parsing the binary's format and sections.
+    Parse,
+    /// Searching the scan ranges for asset headers, other than validation.
+    Scan,
+    /// Decoding candidate asset data to validate it.
+    Validation,
+    /// Decompressing assets for export.
+    Decompression,
+    /// Reading the input and writing exported files.
+    Io,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Parse,
+        Stage::Scan,
+        Stage::Validation,
+        Stage::Decompression,
+        Stage::Io,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Parse => "parsing",
+            Self::Scan => "scanning",
+            Self::Validation => "validation",
+            Self::Decompression => "decompression",
+            Self::Io => "I/O",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Counter {
+    /// Offsets tried as asset headers.
+    Candidates,
+    /// Candidates whose data was decoded to validate it.
+    Validated,
+    /// Candidates whose pointers resolved but that were rejected.
+    Rejected,
+    /// Assets the scan kept.
+    Accepted,
+}
+
+impl Counter {
+    pub const ALL: [Counter; 4] = [
+        Counter::Candidates,
+        Counter::Validated,
+        Counter::Rejected,
+        Counter::Accepted,
+    ];
+}
+
+/// Time per [`Stage`] and [`Counter`] totals, shared by the threads that
+/// record them.
+#[derive(Debug, Default)]
+pub struct Timings {
+    nanos: [AtomicU64; Stage::ALL.len()],
+    counts: [AtomicUsize; Counter::ALL.len()],
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, stage: Stage, elapsed: Duration) {
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        self.nanos[stage as usize].fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// Runs `f`, adding the time it takes to `stage`.
+    pub fn time<T>(&self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let value = f();
+        self.record(stage, started.elapsed());
+        value
+    }
+
+    pub fn elapsed(&self, stage: Stage) -> Duration {
+        Duration::from_nanos(self.nanos[stage as usize].load(Ordering::Relaxed))
+    }
+
+    pub fn add(&self, counter: Counter, count: usize) {
+        self.counts[counter as usize].fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn count(&self, counter: Counter) -> usize {
+        self.counts[counter as usize].load(Ordering::Relaxed)
+    }
+
+    pub fn report(&self) -> TimingReport {
+        TimingReport {
+            stages: Stage::ALL
+                .into_iter()
+                .map(|stage| StageTiming {
+                    stage,
+                    seconds: self.elapsed(stage).as_secs_f64(),
+                })
+                .collect(),
+            candidates: self.count(Counter::Candidates),
+            validated: self.count(Counter::Validated),
+            rejected: self.count(Counter::Rejected),
+            accepted: self.count(Counter::Accepted),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: Stage,
+    pub seconds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingReport {
+    pub stages: Vec<StageTiming>,
+    pub candidates: usize,
+    pub validated: usize,
+    pub rejected: usize,
+    pub accepted: usize,
+}
+
+/// Runs `f`, adding the time it takes to `stage` of `timings`, if any.
+pub fn timed<T>(timings: Option<&Timings>, stage: Stage, f: impl FnOnce() -> T) -> T {
+    match timings {
+        Some(timings) => timings.time(stage, f),
+        None => f(),
+    }
+}
@@ -0,0 +1,128 @@
+//! WebAssembly modules among the assets.
+//!
+//! Frontends ship modules as `.wasm` files, but a module can be embedded
+//! under any name, so assets are also recognized by the `\0asm` magic of
+//! their decoded content. Modules are validated with `wasmparser` and can be
+//! printed in the WebAssembly text format for review.
+
+use crate::asset::AssetTable;
+use crate::error::{Error, Result};
+use crate::extract::decompress_asset;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use wasmparser::{Parser, Payload, Validator};
+
+/// The magic a WebAssembly binary module starts with.
+pub const WASM_MAGIC: &[u8] = b"\0asm";
+/// Extension of the text format files written next to modules.
+pub const WAT_EXTENSION: &str = "wat";
+
+/// A WebAssembly module found among the assets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmModule {
+    pub name: String,
+    /// Size of the decoded module.
+    pub size: usize,
+    /// Whether the asset is named `.wasm`. Modules under other names are
+    /// found by their magic.
+    pub named_wasm: bool,
+    pub imports: u32,
+    pub exports: u32,
+    /// Functions the module defines, not counting imported ones.
+    pub functions: u32,
+    /// Why the module does not validate, or `None` if it does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WasmModule {
+    pub fn is_valid(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Whether the asset holds a module under another extension.
+    pub fn is_mislabeled(&self) -> bool {
+        !self.named_wasm
+    }
+}
+
+/// Whether `data` starts like a WebAssembly binary module.
+pub fn is_wasm(data: &[u8]) -> bool {
+    data.len() >= 8 && data.starts_with(WASM_MAGIC)
+}
+
+fn has_wasm_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("wasm"))
+}
+
+/// Validates the module `data` of the asset `name` and counts its imports,
+/// exports and functions.
+pub fn inspect(name: &str, data: &[u8]) -> WasmModule {
+    let mut module = WasmModule {
+        name: name.to_string(),
+        size: data.len(),
+        named_wasm: has_wasm_extension(Path::new(name)),
+        imports: 0,
+        exports: 0,
+        functions: 0,
+        error: None,
+    };
+    if !is_wasm(data) {
+        module.error = Some("missing the \\0asm magic".to_string());
+        return module;
+    }
+    if let Err(error) = Validator::new().validate_all(data) {
+        module.error = Some(error.to_string());
+        return module;
+    }
+    for payload in Parser::new(0).parse_all(data) {
+        match payload {
+            Ok(Payload::ImportSection(reader)) => module.imports = reader.count(),
+            Ok(Payload::ExportSection(reader)) => module.exports = reader.count(),
+            Ok(Payload::FunctionSection(reader)) => module.functions = reader.count(),
+            Ok(_) => {}
+            Err(error) => {
+                module.error = Some(error.to_string());
+                break;
+            }
+        }
+    }
+    module
+}
+
+/// Finds the modules among the assets of `table`: assets named `.wasm`,
+/// whether or not they hold one, and assets of any name that start with
+/// the magic. Every asset is decoded to check.
+pub fn find_modules(table: &AssetTable) -> Result<Vec<WasmModule>> {
+    let mut modules = Vec::new();
+    for asset in table.assets() {
+        if asset.alias_of().is_some() {
+            continue;
+        }
+        let data = decompress_asset(asset)?;
+        if has_wasm_extension(Path::new(asset.name())) || is_wasm(&data) {
+            modules.push(inspect(asset.name(), &data));
+        }
+    }
+    Ok(modules)
+}
+
+/// Prints the module `data` in the WebAssembly text format.
+pub fn to_wat(data: &[u8]) -> Result<String> {
+    wasmprinter::print_bytes(data)
+        .map_err(|error| Error::Message(format!("failed to print the module as text: {error}")))
+}
+
+/// Where the text format of the module at `path` goes: `app.wasm` becomes
+/// `app.wat`, and a module under another extension keeps it, as in
+/// `blob.bin.wat`.
+pub fn wat_path(path: &Path) -> PathBuf {
+    if has_wasm_extension(path) {
+        return path.with_extension(WAT_EXTENSION);
+    }
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(WAT_EXTENSION);
+    PathBuf::from(path)
+}
@@ -0,0 +1,182 @@
+//! The webview runtime the binary renders its frontend with.
+//!
+//! Tauri draws its windows through `wry`, which binds to the platform's
+//! webview: WebView2 on Windows, WebKitGTK on Linux, `WKWebView` on Apple
+//! platforms and the system WebView on Android. The `wry` version and the
+//! version of its binding crate come from registry paths. Known webview
+//! vulnerabilities are tracked against the engine, so for WebView2 the
+//! binary also tells whether it ships a Fixed Version runtime, which does
+//! not update itself, and how it loads the WebView2 loader.
+
+use crate::fingerprint::{crate_version, CrateVersion};
+use serde::{Deserialize, Serialize};
+
+/// Names the Fixed Version runtime's directory, followed by its version.
+const FIXED_RUNTIME_PREFIX: &str = "Microsoft.WebView2.FixedVersionRuntime.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebViewEngine {
+    #[serde(rename = "webview2")]
+    WebView2,
+    #[serde(rename = "webkitgtk")]
+    WebkitGtk,
+    #[serde(rename = "wkwebview")]
+    WkWebView,
+    #[serde(rename = "android_webview")]
+    AndroidWebView,
+}
+
+impl WebViewEngine {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WebView2 => "WebView2",
+            Self::WebkitGtk => "WebKitGTK",
+            Self::WkWebView => "WKWebView",
+            Self::AndroidWebView => "Android System WebView",
+        }
+    }
+}
+
+/// How a Windows binary loads the WebView2 loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebView2Loader {
+    /// Linked in, as `webview2-com` does by default.
+    Static,
+    /// Imported from a `WebView2Loader.dll` shipped next to the executable.
+    Dll,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebViewRuntime {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub engine: Option<WebViewEngine>,
+    /// The `wry` version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wry_version: Option<String>,
+    /// The binding crate for the engine, such as `webview2-com`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bindings: Option<CrateVersion>,
+    /// The WebKitGTK API version linked against, `4.0` or `4.1`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webkitgtk_api: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webview2_loader: Option<WebView2Loader>,
+    /// The version of the Fixed Version WebView2 runtime the app ships
+    /// with. Apps without one use the evergreen runtime installed on the
+    /// system.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webview2_fixed_runtime: Option<String>,
+}
+
+struct Engine {
+    engine: WebViewEngine,
+    /// Binding crates, whose registry paths name the engine.
+    crates: &'static [&'static str],
+    /// Strings only binaries using the engine contain.
+    markers: &'static [&'static str],
+}
+
+const ENGINES: &[Engine] = &[
+    Engine {
+        engine: WebViewEngine::WebView2,
+        crates: &["webview2-com"],
+        markers: &["WebView2Loader.dll", "CreateCoreWebView2Environment"],
+    },
+    Engine {
+        engine: WebViewEngine::WebkitGtk,
+        crates: &["webkit2gtk"],
+        markers: &["libwebkit2gtk-"],
+    },
+    Engine {
+        engine: WebViewEngine::AndroidWebView,
+        crates: &["jni"],
+        markers: &["android/webkit/WebView", "RustWebViewClient"],
+    },
+    Engine {
+        engine: WebViewEngine::WkWebView,
+        crates: &["objc2-web-kit", "cocoa"],
+        markers: &["WKWebView"],
+    },
+];
+
+/// Finds the webview runtime of the binary in `data`, if anything names it.
+pub fn find_webview(data: &[u8]) -> Option<WebViewRuntime> {
+    let mut runtime = WebViewRuntime {
+        wry_version: crate_version(data, "wry"),
+        ..WebViewRuntime::default()
+    };
+    for engine in ENGINES {
+        let bindings = engine.crates.iter().find_map(|name| {
+            Some(CrateVersion {
+                name: name.to_string(),
+                version: crate_version(data, name)?,
+            })
+        });
+        let marked = engine
+            .markers
+            .iter()
+            .any(|marker| find_text(data, marker).is_some());
+        if bindings.is_some() || marked {
+            runtime.engine = Some(engine.engine);
+            runtime.bindings = bindings;
+            break;
+        }
+    }
+
+    match runtime.engine {
+        Some(WebViewEngine::WebkitGtk) => {
+            runtime.webkitgtk_api = ["4.1", "4.0"]
+                .into_iter()
+                .find(|api| find(data, format!("libwebkit2gtk-{api}.so").as_bytes()).is_some())
+                .map(str::to_string);
+        }
+        Some(WebViewEngine::WebView2) => {
+            runtime.webview2_loader = if find(data, b"WebView2Loader.dll").is_some() {
+                Some(WebView2Loader::Dll)
+            } else if runtime.bindings.is_some()
+                || find_text(data, "EmbeddedBrowserWebView").is_some()
+            {
+                Some(WebView2Loader::Static)
+            } else {
+                None
+            };
+            runtime.webview2_fixed_runtime = fixed_runtime_version(data);
+        }
+        _ => {}
+    }
+    (runtime != WebViewRuntime::default()).then_some(runtime)
+}
+
+/// Reads the version after [`FIXED_RUNTIME_PREFIX`], in ASCII or UTF-16.
+fn fixed_runtime_version(data: &[u8]) -> Option<String> {
+    let offset = find_text(data, FIXED_RUNTIME_PREFIX)?;
+    let ascii = find(data, FIXED_RUNTIME_PREFIX.as_bytes()) == Some(offset);
+    let step = if ascii { 1 } else { 2 };
+    let start = offset + FIXED_RUNTIME_PREFIX.len() * step;
+    let version = data[start..]
+        .iter()
+        .step_by(step)
+        .take_while(|byte| byte.is_ascii_digit() || **byte == b'.')
+        .map(|&byte| char::from(byte))
+        .collect::<String>();
+    let version = version.trim_end_matches('.');
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Finds `text` as ASCII or as UTF-16LE, the encoding of Windows APIs.
+fn find_text(data: &[u8], text: &str) -> Option<usize> {
+    find(data, text.as_bytes()).or_else(|| {
+        let wide = text
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        find(data, &wide)
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
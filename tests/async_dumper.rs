@@ -0,0 +1,37 @@
+#![cfg(all(feature = "brotli", feature = "tokio"))]
+
+mod common;
+
+use std::fs;
+use tauri_dumper::{AsyncDumper, ExportOptions};
+
+#[tokio::test]
+async fn scans_and_exports_without_blocking_the_runtime() {
+    let temp = tempfile::tempdir().unwrap();
+    let input = temp.path().join("app");
+    let output = temp.path().join("out");
+    fs::write(&input, common::nested_desktop_elf()).unwrap();
+
+    let dumper = AsyncDumper::from_path(&input).await.unwrap();
+    let table = dumper.scan().await.unwrap();
+    assert_eq!(table.len(), 3);
+    let data = dumper
+        .decompress_asset(table.find("/index.html").unwrap())
+        .await
+        .unwrap();
+    assert_eq!(data, b"<!DOCTYPE html><html></html>");
+
+    let summary = dumper
+        .export(&table, &ExportOptions::new(&output))
+        .await
+        .unwrap();
+    assert_eq!(summary.exported, 3);
+    assert!(output.join("_app/immutable/chunks/app.js").is_file());
+}
+
+#[tokio::test]
+async fn reports_invalid_binaries() {
+    assert!(AsyncDumper::from_bytes(b"not a binary".to_vec())
+        .await
+        .is_err());
+}
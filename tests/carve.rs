@@ -0,0 +1,210 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
+mod common;
+
+#[cfg(feature = "cli")]
+use assert_cmd::Command;
+use common::packages::{self, PayloadCompression};
+#[cfg(feature = "cli")]
+use predicates::str::contains;
+#[cfg(feature = "cli")]
+use std::fs;
+use tauri_dumper::carve::{carve, carve_assets, CarveKind, CarveOptions};
+use tauri_dumper::codec;
+
+fn png(pixels: usize) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (kind, data) in [
+        (&b"IHDR"[..], vec![0; 13]),
+        (b"IDAT", vec![0x5a; pixels]),
+        (b"IEND", Vec::new()),
+    ] {
+        png.extend((data.len() as u32).to_be_bytes());
+        png.extend(kind);
+        png.extend(&data);
+        png.extend([0; 4]);
+    }
+    png
+}
+
+fn wasm(code_size: usize) -> Vec<u8> {
+    let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+    // A custom section padded out to `code_size` bytes.
+    wasm.push(0);
+    wasm.extend([
+        0x80 | (code_size & 0x7f) as u8,
+        0x80 | ((code_size >> 7) & 0x7f) as u8,
+        (code_size >> 14) as u8,
+    ]);
+    wasm.extend(vec![0x61; code_size]);
+    wasm
+}
+
+fn payloads() -> Vec<Vec<u8>> {
+    let text = "carved payload ".repeat(200);
+    vec![
+        png(2048),
+        wasm(4096),
+        packages::compress_payload(text.as_bytes(), PayloadCompression::Gzip),
+        packages::zip_archive(&[("index.html", text.as_bytes())]),
+    ]
+}
+
+#[test]
+fn carves_embedded_files_by_magic() {
+    let payloads = payloads();
+    let mut data = vec![0xcc; 100];
+    let mut offsets = Vec::new();
+    for payload in &payloads {
+        offsets.push(data.len());
+        data.extend(payload);
+        data.extend([0xcc; 37]);
+    }
+
+    let files = carve(&data, &CarveOptions { min_size: 0 });
+    let kinds: Vec<_> = files.iter().map(|file| file.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            CarveKind::Png,
+            CarveKind::Wasm,
+            CarveKind::Gzip,
+            CarveKind::Zip
+        ]
+    );
+    for ((file, payload), offset) in files.iter().zip(&payloads).zip(offsets) {
+        assert_eq!(file.offset, offset);
+        assert_eq!(file.data, *payload);
+    }
+    assert_eq!(files[0].name, format!("{:08x}.png", files[0].offset));
+}
+
+#[test]
+fn skips_truncated_and_small_files() {
+    let mut truncated = png(2048);
+    truncated.truncate(1000);
+    assert!(carve(&truncated, &CarveOptions::default()).is_empty());
+
+    let small = png(16);
+    assert!(carve(&small, &CarveOptions::default()).is_empty());
+    assert_eq!(carve(&small, &CarveOptions { min_size: 16 }).len(), 1);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_carves_files_from_binary() {
+    let temp = tempfile::tempdir().unwrap();
+    let input = temp.path().join("app");
+    let output = temp.path().join("carved");
+    let rodata = payloads().concat();
+    fs::write(&input, common::desktop_elf_from_sections(&rodata, &[])).unwrap();
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["carve", input.to_str().unwrap(), "--min-size", "16", "-o"])
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout(contains("carved: 4"));
+
+    let mut extensions: Vec<_> = fs::read_dir(&output)
+        .unwrap()
+        .map(|entry| entry.unwrap().path().extension().unwrap().to_owned())
+        .collect();
+    extensions.sort();
+    assert_eq!(extensions, ["gz", "png", "wasm", "zip"]);
+}
+
+/// Bytes that look like the code and strings around embedded assets.
+fn filler(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u32;
+    let mut filler = b"panicked at src/main.rs\0called `Option::unwrap()`\0".to_vec();
+    filler.extend((0..len).map(|_| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as u8
+    }));
+    filler
+}
+
+fn compressed_streams() -> Vec<(&'static str, Vec<u8>)> {
+    let lines = |line: &dyn Fn(usize) -> String| (0..60).map(line).collect::<String>();
+    // Large enough that the stream is decoded from several chunks of input.
+    let many_lines = |line: &dyn Fn(usize) -> String| (0..4000).map(line).collect::<String>();
+    let html = format!(
+        "<!doctype html><html><body>{}</body></html>",
+        lines(&|i| format!("<p id=\"p{i}\">{}</p>\n", i * 7919 % 1000))
+    );
+    let js = format!(
+        "import {{ mount }} from './mount.js';\n{}",
+        many_lines(&|i| format!("export const v{i} = {};\n", i * 7919 % 1000))
+    );
+    let css = lines(&|i| format!(".c{i} {{ margin: {}px; }}\n", i * 7919 % 1000));
+    vec![
+        ("html", codec::compress(html.as_bytes()).unwrap()),
+        ("js", codec::compress(js.as_bytes()).unwrap()),
+        (
+            "css",
+            packages::compress_payload(css.as_bytes(), PayloadCompression::Gzip),
+        ),
+    ]
+}
+
+#[test]
+fn carves_compressed_streams_as_assets() {
+    let streams = compressed_streams();
+    let mut data = filler(64 * 1024);
+    let mut expected = Vec::new();
+    for (extension, stream) in &streams {
+        expected.push(format!("/carved/{:08x}.{extension}", data.len()));
+        data.extend(stream);
+        data.extend(filler(4096));
+    }
+
+    let assets = carve_assets(&data, &CarveOptions { min_size: 64 });
+    let names: Vec<_> = assets
+        .iter()
+        .map(|asset| asset.name().to_string())
+        .collect();
+    assert_eq!(names, expected);
+    for (asset, (_, stream)) in assets.iter().zip(&streams) {
+        assert_eq!(asset.compressed_data(), stream.as_slice());
+    }
+    let html = tauri_dumper::extract::decompress_asset(&assets[0]).unwrap();
+    assert!(html.starts_with(b"<!doctype html>"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_extracts_carved_streams_without_asset_table() {
+    let temp = tempfile::tempdir().unwrap();
+    let input = temp.path().join("app");
+    let output = temp.path().join("out");
+    let mut rodata = filler(8 * 1024);
+    for (_, stream) in compressed_streams() {
+        rodata.extend(stream);
+        rodata.extend(filler(512));
+    }
+    fs::write(&input, common::desktop_elf_from_sections(&rodata, &[])).unwrap();
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", input.to_str().unwrap(), "-o"])
+        .arg(&output)
+        .assert()
+        .code(3);
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", input.to_str().unwrap(), "--carve", "-o"])
+        .arg(&output)
+        .assert()
+        .success();
+
+    let mut extensions: Vec<_> = fs::read_dir(output.join("carved"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().extension().unwrap().to_owned())
+        .collect();
+    extensions.sort();
+    assert_eq!(extensions, ["css", "html", "js"]);
+}
@@ -1,5 +1,8 @@
 #![allow(dead_code)]
 
+#[cfg(feature = "fs")]
+pub mod packages;
+
 use serde::Deserialize;
 use std::fs;
 use std::io::Write;
@@ -51,6 +54,18 @@ pub fn desktop_elf() -> Vec<u8> {
     desktop_elf_with_assets(&[("/index.html", b"<!DOCTYPE html><html></html>" as &[u8])])
 }
 
+/// Appends each script to `desktop_elf()`, separated by NUL bytes like
+/// unrelated data in `.rodata`.
+pub fn desktop_elf_with_scripts(scripts: &[&str]) -> Vec<u8> {
+    let mut elf = desktop_elf();
+    for script in scripts {
+        elf.extend_from_slice(&[0, 0xff, 0]);
+        elf.extend_from_slice(script.as_bytes());
+    }
+    elf.push(0);
+    elf
+}
+
 pub fn nested_desktop_elf() -> Vec<u8> {
     desktop_elf_with_assets(&[
         ("/index.html", b"<!DOCTYPE html><html></html>" as &[u8]),
@@ -66,46 +81,291 @@ pub fn nested_desktop_elf() -> Vec<u8> {
 }
 
 fn desktop_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
-    const ELF_HEADER_SIZE: usize = 64;
-    const SECTION_HEADER_SIZE: usize = 64;
-    const RODATA_ADDR: u64 = 0x400000;
-    const DATA_REL_RO_ADDR: u64 = 0x500000;
+    desktop_elf_with_tables(&[assets])
+}
+
+/// Builds a desktop ELF with one asset header array per entry in `tables`,
+/// separated by an unused header-sized gap.
+pub fn desktop_elf_with_tables(tables: &[&[(&str, &[u8])]]) -> Vec<u8> {
+    desktop_elf_with_encoded_tables(tables, brotli_compress)
+}
+
+/// Builds a desktop ELF whose assets are gzip members instead of Brotli.
+#[cfg(feature = "gzip")]
+pub fn gzip_desktop_elf(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    desktop_elf_with_encoded_tables(&[assets], |content| {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        encoder.finish().unwrap()
+    })
+}
+
+/// Builds a desktop ELF with assets encoded by `encode`.
+pub fn desktop_elf_with_encoded_tables(
+    tables: &[&[(&str, &[u8])]],
+    encode: impl Fn(&[u8]) -> Vec<u8>,
+) -> Vec<u8> {
+    const RODATA_ADDR: u64 = DESKTOP_RODATA_ADDR;
+
+    let mut rodata = Vec::new();
+    let mut data_rel_ro = Vec::new();
+    for (table_index, assets) in tables.iter().enumerate() {
+        if table_index > 0 {
+            data_rel_ro.extend_from_slice(&[0; 32]);
+        }
+        for (name, content) in assets.iter() {
+            let name_addr = RODATA_ADDR + rodata.len() as u64;
+            rodata.extend_from_slice(name.as_bytes());
+
+            let compressed = encode(content);
+            let data_addr = RODATA_ADDR + rodata.len() as u64;
+            rodata.extend_from_slice(&compressed);
+
+            data_rel_ro.extend_from_slice(&name_addr.to_le_bytes());
+            data_rel_ro.extend_from_slice(&(name.len() as u64).to_le_bytes());
+            data_rel_ro.extend_from_slice(&data_addr.to_le_bytes());
+            data_rel_ro.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+        }
+    }
+
+    desktop_elf_from_sections(&rodata, &data_rel_ro)
+}
+
+/// Builds a 32-bit ARM ELF whose asset headers hold 4-byte pointers and
+/// lengths, as armv7 Linux builds emit them.
+pub fn armv7_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    const ELF_HEADER_SIZE: usize = 52;
+    const SECTION_HEADER_SIZE: usize = 40;
+    const RODATA_ADDR: u32 = 0x10000;
+    const DATA_REL_RO_ADDR: u32 = 0x20000;
     const RODATA_OFF: usize = 0x1000;
-    const DATA_REL_RO_OFF: usize = 0x2000;
-    const SHSTRTAB_OFF: usize = 0x3000;
 
     let mut rodata = Vec::new();
-    let mut headers = Vec::new();
+    let mut data_rel_ro = Vec::new();
     for (name, content) in assets {
-        let name_addr = RODATA_ADDR + rodata.len() as u64;
+        let name_addr = RODATA_ADDR + rodata.len() as u32;
         rodata.extend_from_slice(name.as_bytes());
+        let compressed = brotli_compress(content);
+        let data_addr = RODATA_ADDR + rodata.len() as u32;
+        rodata.extend_from_slice(&compressed);
+        for word in [
+            name_addr,
+            name.len() as u32,
+            data_addr,
+            compressed.len() as u32,
+        ] {
+            data_rel_ro.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    let data_rel_ro_off = (RODATA_OFF + rodata.len()).next_multiple_of(0x1000);
+    let shstrtab_off = data_rel_ro_off + data_rel_ro.len();
+    let shstrtab = b"\0.rodata\0.data.rel.ro\0.shstrtab\0";
+    let section_header_off = (shstrtab_off + shstrtab.len()).next_multiple_of(4);
+    let mut elf = vec![0; section_header_off + SECTION_HEADER_SIZE * 4];
+
+    elf[0..4].copy_from_slice(b"\x7fELF");
+    elf[4] = 1;
+    elf[5] = 1;
+    elf[6] = 1;
+    write_u16(&mut elf, 16, 2);
+    write_u16(&mut elf, 18, 40);
+    write_u32(&mut elf, 20, 1);
+    write_u32(&mut elf, 32, section_header_off as u32);
+    write_u32(&mut elf, 36, 0x0500_0000);
+    write_u16(&mut elf, 40, ELF_HEADER_SIZE as u64);
+    write_u16(&mut elf, 46, SECTION_HEADER_SIZE as u64);
+    write_u16(&mut elf, 48, 4);
+    write_u16(&mut elf, 50, 3);
+
+    elf[RODATA_OFF..RODATA_OFF + rodata.len()].copy_from_slice(&rodata);
+    elf[data_rel_ro_off..data_rel_ro_off + data_rel_ro.len()].copy_from_slice(&data_rel_ro);
+    elf[shstrtab_off..shstrtab_off + shstrtab.len()].copy_from_slice(shstrtab);
 
+    // name, type, flags, addr, offset, size, link, info, align, entsize
+    let sections = [
+        [
+            1,
+            1,
+            2,
+            RODATA_ADDR,
+            RODATA_OFF as u32,
+            rodata.len() as u32,
+            0,
+            0,
+            1,
+            0,
+        ],
+        [
+            9,
+            1,
+            3,
+            DATA_REL_RO_ADDR,
+            data_rel_ro_off as u32,
+            data_rel_ro.len() as u32,
+            0,
+            0,
+            4,
+            0,
+        ],
+        [
+            22,
+            3,
+            0,
+            0,
+            shstrtab_off as u32,
+            shstrtab.len() as u32,
+            0,
+            0,
+            1,
+            0,
+        ],
+    ];
+    for (index, fields) in sections.iter().enumerate() {
+        let offset = section_header_off + SECTION_HEADER_SIZE * (index + 1);
+        for (field, value) in fields.iter().enumerate() {
+            write_u32(&mut elf, offset + field * 4, *value);
+        }
+    }
+    elf
+}
+
+/// Builds a desktop ELF whose 48-byte asset headers end with a slice of CSP
+/// hashes, one `'sha256-…'` source per asset.
+pub fn desktop_elf_with_csp_hashes(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut rodata = Vec::new();
+    let mut data_rel_ro = Vec::new();
+    for (name, content) in assets {
+        let name_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(name.as_bytes());
         let compressed = brotli_compress(content);
-        let data_addr = RODATA_ADDR + rodata.len() as u64;
+        let data_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
         rodata.extend_from_slice(&compressed);
+        let hash_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(b"'sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU='");
 
-        headers.push((
+        for word in [
             name_addr,
             name.len() as u64,
             data_addr,
             compressed.len() as u64,
-        ));
+            hash_addr,
+            1,
+        ] {
+            data_rel_ro.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    desktop_elf_from_sections(&rodata, &data_rel_ro)
+}
+
+/// Builds a desktop ELF laid out like a Tauri 1 app built without
+/// compression: raw assets behind the usual headers, and the `tauri`
+/// crate's source path in a panic location.
+pub fn tauri_v1_elf(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut rodata =
+        b"/root/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tauri-1.5.4/src/app.rs\0"
+            .to_vec();
+    let mut data_rel_ro = Vec::new();
+    for (name, content) in assets {
+        let name_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(name.as_bytes());
+        let data_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(content);
+
+        data_rel_ro.extend_from_slice(&name_addr.to_le_bytes());
+        data_rel_ro.extend_from_slice(&(name.len() as u64).to_le_bytes());
+        data_rel_ro.extend_from_slice(&data_addr.to_le_bytes());
+        data_rel_ro.extend_from_slice(&(content.len() as u64).to_le_bytes());
+    }
+    desktop_elf_from_sections(&rodata, &data_rel_ro)
+}
+
+/// Builds a desktop ELF with a `rust-embed` entry array for `files`, followed
+/// by the array of names `rust-embed` keeps for iteration.
+///
+/// Each entry is laid out as `EmbeddedFile { data, metadata }` followed by the
+/// path, with `Metadata` holding `last_modified`, `created` and the hash.
+pub fn rust_embed_elf(files: &[(&str, &[u8])]) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+    const COW_BORROWED: u64 = 0x8000_0000_0000_0000;
+
+    let mut rodata = Vec::new();
+    let mut entries = Vec::new();
+    let mut names = Vec::new();
+    for (name, content) in files {
+        let name_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(name.as_bytes());
+        let data_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+        rodata.extend_from_slice(content);
+
+        for word in [
+            COW_BORROWED,
+            data_addr,
+            content.len() as u64,
+            1,
+            1_700_000_000,
+            0,
+            0,
+        ] {
+            entries.extend_from_slice(&word.to_le_bytes());
+        }
+        entries.extend_from_slice(&Sha256::digest(content));
+        entries.extend_from_slice(&name_addr.to_le_bytes());
+        entries.extend_from_slice(&(name.len() as u64).to_le_bytes());
+
+        names.extend_from_slice(&name_addr.to_le_bytes());
+        names.extend_from_slice(&(name.len() as u64).to_le_bytes());
     }
+    entries.extend_from_slice(&names);
+
+    desktop_elf_from_sections(&rodata, &entries)
+}
+
+/// Two asset names sharing a single compressed blob, as emitted when Tauri
+/// deduplicates identical files.
+pub fn desktop_elf_with_aliases(content: &[u8]) -> Vec<u8> {
+    desktop_elf_sharing_data_offset(content, 0)
+}
 
-    let mut data_rel_ro = vec![0; 32 * headers.len()];
-    for (index, (name_addr, name_len, data_addr, data_size)) in headers.into_iter().enumerate() {
-        let offset = index * 32;
-        write_u64(&mut data_rel_ro, offset, name_addr);
-        write_u64(&mut data_rel_ro, offset + 8, name_len);
-        write_u64(&mut data_rel_ro, offset + 16, data_addr);
-        write_u64(&mut data_rel_ro, offset + 24, data_size);
+/// Two asset names whose blobs start at the same offset, the second
+/// `extra` bytes longer than the first.
+pub fn desktop_elf_sharing_data_offset(content: &[u8], extra: usize) -> Vec<u8> {
+    let compressed = brotli_compress(content);
+    let mut rodata = Vec::new();
+    rodata.extend_from_slice(b"/a.txt/b.txt");
+    let data_addr = DESKTOP_RODATA_ADDR + rodata.len() as u64;
+    rodata.extend_from_slice(&compressed);
+    rodata.resize(rodata.len() + extra, 0);
+
+    let mut data_rel_ro = Vec::new();
+    for (name_offset, size) in [(0u64, compressed.len()), (6, compressed.len() + extra)] {
+        data_rel_ro.extend_from_slice(&(DESKTOP_RODATA_ADDR + name_offset).to_le_bytes());
+        data_rel_ro.extend_from_slice(&6u64.to_le_bytes());
+        data_rel_ro.extend_from_slice(&data_addr.to_le_bytes());
+        data_rel_ro.extend_from_slice(&(size as u64).to_le_bytes());
     }
 
+    desktop_elf_from_sections(&rodata, &data_rel_ro)
+}
+
+pub const DESKTOP_RODATA_ADDR: u64 = 0x400000;
+
+/// Builds a desktop ELF with the given `.rodata` (mapped at
+/// [`DESKTOP_RODATA_ADDR`]) and `.data.rel.ro` contents.
+pub fn desktop_elf_from_sections(rodata: &[u8], data_rel_ro: &[u8]) -> Vec<u8> {
+    const ELF_HEADER_SIZE: usize = 64;
+    const SECTION_HEADER_SIZE: usize = 64;
+    const RODATA_ADDR: u64 = DESKTOP_RODATA_ADDR;
+    const DATA_REL_RO_ADDR: u64 = 0x500000;
+    const RODATA_OFF: usize = 0x1000;
+
+    let data_rel_ro_off = (RODATA_OFF + rodata.len()).next_multiple_of(0x1000);
+    let shstrtab_off = (data_rel_ro_off + data_rel_ro.len()).next_multiple_of(0x1000);
     let shstrtab = b"\0.rodata\0.data.rel.ro\0.shstrtab\0";
     let rodata_name = 1;
     let data_rel_ro_name = rodata_name + b".rodata\0".len();
     let shstrtab_name = data_rel_ro_name + b".data.rel.ro\0".len();
-    let section_header_off = SHSTRTAB_OFF + shstrtab.len();
+    let section_header_off = shstrtab_off + shstrtab.len();
     let mut elf = vec![0; section_header_off + SECTION_HEADER_SIZE * 4];
 
     write_elf_header(
@@ -128,15 +388,15 @@ fn desktop_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
             offset: 0,
             vaddr: 0,
             paddr: 0,
-            filesz: (DATA_REL_RO_OFF + data_rel_ro.len()) as u64,
-            memsz: (DATA_REL_RO_OFF + data_rel_ro.len()) as u64,
+            filesz: (data_rel_ro_off + data_rel_ro.len()) as u64,
+            memsz: (data_rel_ro_off + data_rel_ro.len()) as u64,
             align: 0x1000,
         },
     );
 
-    elf[RODATA_OFF..RODATA_OFF + rodata.len()].copy_from_slice(&rodata);
-    elf[DATA_REL_RO_OFF..DATA_REL_RO_OFF + data_rel_ro.len()].copy_from_slice(&data_rel_ro);
-    elf[SHSTRTAB_OFF..SHSTRTAB_OFF + shstrtab.len()].copy_from_slice(shstrtab);
+    elf[RODATA_OFF..RODATA_OFF + rodata.len()].copy_from_slice(rodata);
+    elf[data_rel_ro_off..data_rel_ro_off + data_rel_ro.len()].copy_from_slice(data_rel_ro);
+    elf[shstrtab_off..shstrtab_off + shstrtab.len()].copy_from_slice(shstrtab);
 
     let shdr = section_header_off;
     write_section_header(
@@ -163,7 +423,7 @@ fn desktop_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
             typ: 1,
             flags: 3,
             addr: DATA_REL_RO_ADDR,
-            offset: DATA_REL_RO_OFF as u64,
+            offset: data_rel_ro_off as u64,
             size: data_rel_ro.len() as u64,
             link: 0,
             info: 0,
@@ -179,7 +439,7 @@ fn desktop_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
             typ: 3,
             flags: 0,
             addr: 0,
-            offset: SHSTRTAB_OFF as u64,
+            offset: shstrtab_off as u64,
             size: shstrtab.len() as u64,
             link: 0,
             info: 0,
@@ -191,6 +451,558 @@ fn desktop_elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
     elf
 }
 
+pub const PE_RDATA_RVA: u32 = 0x1000;
+
+pub fn pe_with_assets(image_base: u64, assets: &[(&str, &[u8])]) -> Vec<u8> {
+    build_pe(
+        image_base,
+        &[PeSection::rdata(pe_assets_rdata(image_base, assets))],
+    )
+}
+
+/// Builds the `.rdata` section of [`pe_with_assets`], mapped at
+/// [`PE_RDATA_RVA`].
+pub fn pe_assets_rdata(image_base: u64, assets: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut rdata = Vec::new();
+    let mut headers = Vec::new();
+    for (name, content) in assets {
+        let name_addr = image_base + u64::from(PE_RDATA_RVA) + rdata.len() as u64;
+        rdata.extend_from_slice(name.as_bytes());
+
+        let compressed = brotli_compress(content);
+        let data_addr = image_base + u64::from(PE_RDATA_RVA) + rdata.len() as u64;
+        rdata.extend_from_slice(&compressed);
+
+        headers.push((
+            name_addr,
+            name.len() as u64,
+            data_addr,
+            compressed.len() as u64,
+        ));
+    }
+
+    rdata.resize(rdata.len().next_multiple_of(8), 0);
+    for (name_addr, name_len, data_addr, data_size) in headers {
+        rdata.extend_from_slice(&name_addr.to_le_bytes());
+        rdata.extend_from_slice(&name_len.to_le_bytes());
+        rdata.extend_from_slice(&data_addr.to_le_bytes());
+        rdata.extend_from_slice(&data_size.to_le_bytes());
+    }
+    rdata
+}
+
+/// Like [`pe_with_assets`], with a `.rsrc` section holding `resources`,
+/// given as `(type, id, data)`.
+pub fn pe_with_resources(
+    image_base: u64,
+    assets: &[(&str, &[u8])],
+    resources: &[(u16, u16, &[u8])],
+) -> Vec<u8> {
+    const RESOURCE_DIRECTORY: usize = 0x40 + 4 + 20 + 112 + 2 * 8;
+
+    let rdata = pe_assets_rdata(image_base, assets);
+    let rsrc_rva = PE_RDATA_RVA + (rdata.len() as u32).next_multiple_of(0x1000);
+    let rsrc = resource_section(rsrc_rva, resources);
+    let rsrc_size = rsrc.len() as u32;
+    let mut pe = build_pe(
+        image_base,
+        &[
+            PeSection::rdata(rdata),
+            PeSection {
+                name: ".rsrc",
+                characteristics: 0x4000_0040,
+                data: rsrc,
+            },
+        ],
+    );
+    write_u32(&mut pe, RESOURCE_DIRECTORY, rsrc_rva);
+    write_u32(&mut pe, RESOURCE_DIRECTORY + 4, rsrc_size);
+    pe
+}
+
+/// Lays out a three-level resource directory (type, ID, language) mapped at
+/// `rva`, with one language per resource.
+fn resource_section(rva: u32, resources: &[(u16, u16, &[u8])]) -> Vec<u8> {
+    let mut types = std::collections::BTreeMap::<u16, Vec<(u16, &[u8])>>::new();
+    for &(kind, id, data) in resources {
+        types.entry(kind).or_default().push((id, data));
+    }
+    let directory_size = |entries: usize| 16 + 8 * entries;
+
+    let mut offset = directory_size(types.len());
+    let mut name_directories = Vec::new();
+    for ids in types.values() {
+        name_directories.push(offset);
+        offset += directory_size(ids.len());
+    }
+    let language_directories = (0..resources.len())
+        .map(|index| offset + index * directory_size(1))
+        .collect::<Vec<_>>();
+    offset += resources.len() * directory_size(1);
+    let data_entries = (0..resources.len())
+        .map(|index| offset + index * 16)
+        .collect::<Vec<_>>();
+    offset += resources.len() * 16;
+
+    let mut rsrc = vec![0; offset];
+    let write_directory = |rsrc: &mut Vec<u8>, at: usize, entries: &[(u16, usize, bool)]| {
+        write_u16(rsrc, at + 14, entries.len() as u64);
+        for (index, &(id, target, is_directory)) in entries.iter().enumerate() {
+            let entry = at + 16 + index * 8;
+            write_u32(rsrc, entry, u32::from(id));
+            let flag = if is_directory { 0x8000_0000 } else { 0 };
+            write_u32(rsrc, entry + 4, target as u32 | flag);
+        }
+    };
+
+    let root = types
+        .keys()
+        .zip(&name_directories)
+        .map(|(&kind, &at)| (kind, at, true))
+        .collect::<Vec<_>>();
+    write_directory(&mut rsrc, 0, &root);
+    let mut index = 0;
+    for (ids, &at) in types.values().zip(&name_directories) {
+        let names = ids
+            .iter()
+            .enumerate()
+            .map(|(offset, &(id, _))| (id, language_directories[index + offset], true))
+            .collect::<Vec<_>>();
+        write_directory(&mut rsrc, at, &names);
+        for &(_, data) in ids {
+            write_directory(
+                &mut rsrc,
+                language_directories[index],
+                &[(0x409, data_entries[index], false)],
+            );
+            rsrc.resize(rsrc.len().next_multiple_of(8), 0);
+            let data_entry = data_entries[index];
+            let data_rva = rva + rsrc.len() as u32;
+            write_u32(&mut rsrc, data_entry, data_rva);
+            write_u32(&mut rsrc, data_entry + 4, data.len() as u32);
+            rsrc.extend_from_slice(data);
+            index += 1;
+        }
+    }
+    rsrc
+}
+
+/// Builds a `VS_VERSIONINFO` resource with a fixed file and product version
+/// and one string table.
+pub fn version_info(version: [u16; 4], strings: &[(&str, &str)]) -> Vec<u8> {
+    let most = (u32::from(version[0]) << 16) | u32::from(version[1]);
+    let least = (u32::from(version[2]) << 16) | u32::from(version[3]);
+    let mut fixed = Vec::new();
+    for value in [0xfeef_04bd, 0x1_0000, most, least, most, least] {
+        fixed.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    fixed.resize(52, 0);
+
+    let strings = strings
+        .iter()
+        .map(|(key, value)| version_block(key, &utf16z(value), true, &[]))
+        .collect::<Vec<_>>();
+    let table = version_block("040904b0", &[], true, &strings);
+    let string_file_info = version_block("StringFileInfo", &[], true, &[table]);
+    version_block("VS_VERSION_INFO", &fixed, false, &[string_file_info])
+}
+
+fn version_block(key: &str, value: &[u8], is_text: bool, children: &[Vec<u8>]) -> Vec<u8> {
+    let mut block = vec![0; 6];
+    block.extend_from_slice(&utf16z(key));
+    block.resize(block.len().next_multiple_of(4), 0);
+    block.extend_from_slice(value);
+    for child in children {
+        block.resize(block.len().next_multiple_of(4), 0);
+        block.extend_from_slice(child);
+    }
+    let value_length = if is_text {
+        value.len() / 2
+    } else {
+        value.len()
+    };
+    let length = block.len();
+    write_u16(&mut block, 0, length as u64);
+    write_u16(&mut block, 2, value_length as u64);
+    write_u16(&mut block, 4, u64::from(is_text));
+    block
+}
+
+fn utf16z(text: &str) -> Vec<u8> {
+    text.encode_utf16()
+        .chain([0])
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+pub struct PeSection {
+    pub name: &'static str,
+    pub characteristics: u32,
+    pub data: Vec<u8>,
+}
+
+impl PeSection {
+    pub fn rdata(data: Vec<u8>) -> Self {
+        Self {
+            name: ".rdata",
+            characteristics: 0x4000_0040,
+            data,
+        }
+    }
+}
+
+/// Builds a minimal PE32+ image. Sections are mapped at consecutive
+/// page-aligned RVAs starting at [`PE_RDATA_RVA`].
+pub fn build_pe(image_base: u64, sections: &[PeSection]) -> Vec<u8> {
+    const PE_OFF: usize = 0x40;
+    const OPTIONAL_HEADER_SIZE: usize = 240;
+    const HEADERS_SIZE: usize = 0x400;
+    const FILE_ALIGNMENT: usize = 0x200;
+    const SECTION_ALIGNMENT: u32 = 0x1000;
+
+    let mut pe = vec![0; HEADERS_SIZE];
+    pe[0..2].copy_from_slice(b"MZ");
+    write_u32(&mut pe, 0x3c, PE_OFF as u32);
+    pe[PE_OFF..PE_OFF + 4].copy_from_slice(b"PE\0\0");
+
+    let coff = PE_OFF + 4;
+    write_u16(&mut pe, coff, 0x8664);
+    write_u16(&mut pe, coff + 2, sections.len() as u64);
+    write_u16(&mut pe, coff + 16, OPTIONAL_HEADER_SIZE as u64);
+    write_u16(&mut pe, coff + 18, 0x22);
+
+    let optional = coff + 20;
+    let mut rva = PE_RDATA_RVA;
+    let mut section_headers = Vec::new();
+    for section in sections {
+        let raw_size = section.data.len().next_multiple_of(FILE_ALIGNMENT);
+        let raw_offset = pe.len();
+        pe.extend_from_slice(&section.data);
+        pe.resize(raw_offset + raw_size, 0);
+        section_headers.push((rva, raw_offset, raw_size));
+        rva += (section.data.len().max(1) as u32).next_multiple_of(SECTION_ALIGNMENT);
+    }
+
+    write_u16(&mut pe, optional, 0x20b);
+    write_u64(&mut pe, optional + 24, image_base);
+    write_u32(&mut pe, optional + 32, SECTION_ALIGNMENT);
+    write_u32(&mut pe, optional + 36, FILE_ALIGNMENT as u32);
+    write_u16(&mut pe, optional + 40, 6);
+    write_u16(&mut pe, optional + 48, 6);
+    write_u32(&mut pe, optional + 56, rva);
+    write_u32(&mut pe, optional + 60, HEADERS_SIZE as u32);
+    write_u16(&mut pe, optional + 68, 2);
+    write_u32(&mut pe, optional + 108, 16);
+
+    let table = optional + OPTIONAL_HEADER_SIZE;
+    for (index, (section, (rva, raw_offset, raw_size))) in
+        sections.iter().zip(section_headers).enumerate()
+    {
+        let header = table + index * 40;
+        pe[header..header + section.name.len()].copy_from_slice(section.name.as_bytes());
+        write_u32(&mut pe, header + 8, section.data.len() as u32);
+        write_u32(&mut pe, header + 12, rva);
+        write_u32(&mut pe, header + 16, raw_size as u32);
+        write_u32(&mut pe, header + 20, raw_offset as u32);
+        write_u32(&mut pe, header + 36, section.characteristics);
+    }
+
+    pe
+}
+
+pub const MACHO_IMAGE_BASE: u64 = 0x1_0000_0000;
+const MACHO_SEGMENT_STRIDE: u64 = 0x4000;
+
+pub struct MachOSection {
+    pub segment: &'static str,
+    pub name: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// Returns the virtual address of the section at `index` in [`build_macho`].
+pub fn macho_section_addr(index: usize) -> u64 {
+    MACHO_IMAGE_BASE + MACHO_SEGMENT_STRIDE * (index as u64 + 1)
+}
+
+/// Places names and data in `__TEXT,__const` and distributes the asset
+/// headers round-robin across the `__const` sections of `header_segments`.
+pub fn macho_with_assets(header_segments: &[&'static str], assets: &[(&str, &[u8])]) -> Vec<u8> {
+    macho_with_assets_and_commands(header_segments, assets, &[], |_, addr| addr)
+}
+
+/// Builds an arm64e binary with chained fixups whose asset headers are in
+/// `__AUTH_CONST,__const`. Name pointers are authenticated rebases holding
+/// an offset from the image base, where `__TEXT` starts; data pointers are
+/// plain rebases holding the target address.
+pub fn arm64e_macho_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    const LC_DYLD_CHAINED_FIXUPS: u32 = 0x8000_0034;
+    let mut command = vec![0; 16];
+    write_u32(&mut command, 0, LC_DYLD_CHAINED_FIXUPS);
+    write_u32(&mut command, 4, 16);
+    let mut macho =
+        macho_with_assets_and_commands(&["__AUTH_CONST"], assets, &[command], |field, addr| {
+            if field == 0 {
+                // auth, key DA, diversity 0x1234
+                (1 << 63) | (2 << 49) | (0x1234 << 32) | (addr - macho_section_addr(0))
+            } else {
+                addr
+            }
+        });
+    // CPU_SUBTYPE_ARM64E with the pointer authentication ABI flag.
+    write_u32(&mut macho, 8, 0x8000_0002);
+    macho
+}
+
+/// `encode` turns the address in each pointer field of a header, 0 for the
+/// name and 2 for the data, into the value stored for it.
+fn macho_with_assets_and_commands(
+    header_segments: &[&'static str],
+    assets: &[(&str, &[u8])],
+    extra_commands: &[Vec<u8>],
+    encode: impl Fn(usize, u64) -> u64,
+) -> Vec<u8> {
+    let (text, header_sections) =
+        macho_asset_sections(macho_section_addr(0), header_segments.len(), assets, encode);
+    let mut sections = vec![MachOSection {
+        segment: "__TEXT",
+        name: "__const",
+        data: text,
+    }];
+    sections.extend(
+        header_segments
+            .iter()
+            .zip(header_sections)
+            .map(|(segment, data)| MachOSection {
+                segment,
+                name: "__const",
+                data,
+            }),
+    );
+    build_macho(&sections, extra_commands)
+}
+
+/// Builds an App Store binary: `__TEXT,__text` holds `code`, followed by the
+/// asset names and data in `__TEXT,__const` and the headers in
+/// `__DATA_CONST,__const`. An `LC_ENCRYPTION_INFO_64` command with `cryptid`
+/// 1 covers both `__TEXT` sections. The range is left decrypted and returned
+/// with the binary, for tests to encrypt.
+pub fn fairplay_macho_with_assets(
+    code: &[u8],
+    assets: &[(&str, &[u8])],
+) -> (Vec<u8>, std::ops::Range<usize>) {
+    const DATA_OFF: usize = 0x1000;
+    let (text, mut headers) =
+        macho_asset_sections(macho_section_addr(1), 1, assets, |_, addr| addr);
+    let start = DATA_OFF;
+    let end = start + code.len().next_multiple_of(0x10) + text.len();
+    let mut command = Vec::new();
+    for value in [0x2c, 24, start as u32, (end - start) as u32, 1, 0] {
+        command.extend_from_slice(&value.to_le_bytes());
+    }
+    let sections = [
+        MachOSection {
+            segment: "__TEXT",
+            name: "__text",
+            data: code.to_vec(),
+        },
+        MachOSection {
+            segment: "__TEXT",
+            name: "__const",
+            data: text,
+        },
+        MachOSection {
+            segment: "__DATA_CONST",
+            name: "__const",
+            data: headers.remove(0),
+        },
+    ];
+    (build_macho(&sections, &[command]), start..end)
+}
+
+/// Lays out the names and data of `assets` for a `__TEXT,__const` section
+/// at `text_addr`, and their headers round-robin across `header_sections`
+/// sections.
+fn macho_asset_sections(
+    text_addr: u64,
+    header_sections: usize,
+    assets: &[(&str, &[u8])],
+    encode: impl Fn(usize, u64) -> u64,
+) -> (Vec<u8>, Vec<Vec<u8>>) {
+    let mut text = Vec::new();
+    let mut header_sections = vec![Vec::new(); header_sections];
+    for (index, (name, content)) in assets.iter().enumerate() {
+        let name_addr = text_addr + text.len() as u64;
+        text.extend_from_slice(name.as_bytes());
+        let compressed = brotli_compress(content);
+        let data_addr = text_addr + text.len() as u64;
+        text.extend_from_slice(&compressed);
+
+        let count = header_sections.len();
+        let headers = &mut header_sections[index % count];
+        headers.extend_from_slice(&encode(0, name_addr).to_le_bytes());
+        headers.extend_from_slice(&(name.len() as u64).to_le_bytes());
+        headers.extend_from_slice(&encode(2, data_addr).to_le_bytes());
+        headers.extend_from_slice(&(compressed.len() as u64).to_le_bytes());
+    }
+    (text, header_sections)
+}
+
+/// Signs `macho_with_assets(&["__DATA_CONST"], assets)` with a superblob
+/// holding a code directory, the entitlements and a CMS blob naming
+/// `signing_identity`. `flags` are the code directory flags.
+pub fn signed_macho(
+    assets: &[(&str, &[u8])],
+    identifier: &str,
+    team_id: &str,
+    flags: u32,
+    entitlements: &str,
+    signing_identity: &str,
+) -> Vec<u8> {
+    let mut command = vec![0; 16];
+    write_u32(&mut command, 0, 0x1d);
+    write_u32(&mut command, 4, 16);
+    let mut macho =
+        macho_with_assets_and_commands(&["__DATA_CONST"], assets, &[command], |_, addr| addr);
+    let superblob =
+        code_signature_superblob(identifier, team_id, flags, entitlements, signing_identity);
+
+    let sizeofcmds = u32::from_le_bytes(macho[20..24].try_into().unwrap()) as usize;
+    let command_offset = 32 + sizeofcmds - 16;
+    let dataoff = macho.len() as u32;
+    write_u32(&mut macho, command_offset + 8, dataoff);
+    write_u32(&mut macho, command_offset + 12, superblob.len() as u32);
+    macho.extend_from_slice(&superblob);
+    macho
+}
+
+fn code_signature_superblob(
+    identifier: &str,
+    team_id: &str,
+    flags: u32,
+    entitlements: &str,
+    signing_identity: &str,
+) -> Vec<u8> {
+    const CODE_DIRECTORY_HEADER_SIZE: usize = 88;
+
+    // Offsets in the code directory count from its blob magic.
+    let mut directory = vec![0; CODE_DIRECTORY_HEADER_SIZE];
+    write_be_u32(&mut directory, 0, 0xfade_0c02);
+    write_be_u32(&mut directory, 8, 0x2_0400);
+    write_be_u32(&mut directory, 12, flags);
+    let ident_offset = directory.len() as u32;
+    write_be_u32(&mut directory, 20, ident_offset);
+    directory.extend_from_slice(identifier.as_bytes());
+    directory.push(0);
+    let team_offset = directory.len() as u32;
+    write_be_u32(&mut directory, 48, team_offset);
+    directory.extend_from_slice(team_id.as_bytes());
+    directory.push(0);
+    let directory_len = directory.len() as u32;
+    write_be_u32(&mut directory, 4, directory_len);
+
+    // Certificates name their issuer before their subject.
+    let mut cms = Vec::new();
+    for name in ["Apple Root CA", signing_identity] {
+        cms.extend_from_slice(&[0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, name.len() as u8]);
+        cms.extend_from_slice(name.as_bytes());
+    }
+
+    let blobs = [
+        (0, directory),
+        (5, code_signing_blob(0xfade_7171, entitlements.as_bytes())),
+        (0x1_0000, code_signing_blob(0xfade_0b01, &cms)),
+    ];
+    let mut offset = 12 + blobs.len() * 8;
+    let mut superblob = vec![0; offset];
+    write_be_u32(&mut superblob, 0, 0xfade_0cc0);
+    write_be_u32(&mut superblob, 8, blobs.len() as u32);
+    for (index, (slot, blob)) in blobs.iter().enumerate() {
+        write_be_u32(&mut superblob, 12 + index * 8, *slot);
+        write_be_u32(&mut superblob, 16 + index * 8, offset as u32);
+        offset += blob.len();
+    }
+    for (_, blob) in &blobs {
+        superblob.extend_from_slice(blob);
+    }
+    let length = superblob.len() as u32;
+    write_be_u32(&mut superblob, 4, length);
+    superblob
+}
+
+fn code_signing_blob(magic: u32, payload: &[u8]) -> Vec<u8> {
+    let mut blob = vec![0; 8];
+    write_be_u32(&mut blob, 0, magic);
+    write_be_u32(&mut blob, 4, (payload.len() + 8) as u32);
+    blob.extend_from_slice(payload);
+    blob
+}
+
+/// Builds a minimal arm64 Mach-O executable with one section per segment.
+///
+/// `extra_commands` are appended verbatim after the segment commands.
+pub fn build_macho(sections: &[MachOSection], extra_commands: &[Vec<u8>]) -> Vec<u8> {
+    const HEADER_SIZE: usize = 32;
+    const SEGMENT_COMMAND_SIZE: usize = 72;
+    const SECTION_SIZE: usize = 80;
+    const DATA_OFF: usize = 0x1000;
+
+    let mut commands = Vec::new();
+    let mut contents = Vec::new();
+    for (index, section) in sections.iter().enumerate() {
+        let addr = macho_section_addr(index);
+        let offset = DATA_OFF + contents.len();
+        contents.extend_from_slice(&section.data);
+        contents.resize(contents.len().next_multiple_of(0x10), 0);
+
+        let mut command = vec![0; SEGMENT_COMMAND_SIZE + SECTION_SIZE];
+        write_u32(&mut command, 0, 0x19);
+        write_u32(
+            &mut command,
+            4,
+            (SEGMENT_COMMAND_SIZE + SECTION_SIZE) as u32,
+        );
+        command[8..8 + section.segment.len()].copy_from_slice(section.segment.as_bytes());
+        write_u64(&mut command, 24, addr);
+        write_u64(&mut command, 32, MACHO_SEGMENT_STRIDE);
+        write_u64(&mut command, 40, offset as u64);
+        write_u64(&mut command, 48, section.data.len() as u64);
+        write_u32(&mut command, 56, 3);
+        write_u32(&mut command, 60, 3);
+        write_u32(&mut command, 64, 1);
+
+        let sect = SEGMENT_COMMAND_SIZE;
+        command[sect..sect + section.name.len()].copy_from_slice(section.name.as_bytes());
+        command[sect + 16..sect + 16 + section.segment.len()]
+            .copy_from_slice(section.segment.as_bytes());
+        write_u64(&mut command, sect + 32, addr);
+        write_u64(&mut command, sect + 40, section.data.len() as u64);
+        write_u32(&mut command, sect + 48, offset as u32);
+        write_u32(&mut command, sect + 52, 3);
+        commands.push(command);
+    }
+    commands.extend(extra_commands.iter().cloned());
+
+    let sizeofcmds = commands.iter().map(Vec::len).sum::<usize>();
+    assert!(
+        HEADER_SIZE + sizeofcmds <= DATA_OFF,
+        "load commands too large"
+    );
+
+    let mut macho = vec![0; DATA_OFF];
+    write_u32(&mut macho, 0, 0xfeed_facf);
+    write_u32(&mut macho, 4, 0x0100_000c);
+    write_u32(&mut macho, 12, 2);
+    write_u32(&mut macho, 16, commands.len() as u32);
+    write_u32(&mut macho, 20, sizeofcmds as u32);
+    let mut offset = HEADER_SIZE;
+    for command in commands {
+        macho[offset..offset + command.len()].copy_from_slice(&command);
+        offset += command.len();
+    }
+    macho.extend_from_slice(&contents);
+    macho
+}
+
 pub fn android_elf_with_rela() -> Vec<u8> {
     android_elf(false)
 }
@@ -199,6 +1011,135 @@ pub fn android_elf_with_later_asset_section() -> Vec<u8> {
     android_elf(true)
 }
 
+/// Android library whose `.rela.dyn` is only reachable through `PT_DYNAMIC`.
+pub fn android_elf_with_unlisted_rela() -> Vec<u8> {
+    let mut elf = android_elf(false);
+    let section_header_off = u64::from_le_bytes(elf[40..48].try_into().unwrap()) as usize;
+    write_u32(&mut elf, section_header_off + 64 * 2 + 4, 1);
+    elf
+}
+
+/// The relocation table a [`pie_with_dynamic_relocations`] fixture uses.
+pub enum DynamicRelocations {
+    /// `DT_RELA` entries of the given type, with zeroed pointer slots.
+    Rela(u32),
+    /// `DT_REL` entries of the given type, with link-time values in the slots.
+    Rel(u32),
+    /// `DT_RELR` packed relocations, with link-time values in the slots.
+    Relr,
+}
+
+/// Builds a PIE without section headers whose asset header pointers are
+/// relocated only through the `PT_DYNAMIC` table.
+pub fn pie_with_dynamic_relocations(
+    is_64: bool,
+    machine: u16,
+    relocations: DynamicRelocations,
+) -> Vec<u8> {
+    const RODATA_ADDR: u64 = 0x1000;
+    const HEADER_ADDR: u64 = 0x2000;
+    const TABLE_ADDR: u64 = 0x3000;
+    const DYNAMIC_ADDR: u64 = 0x3800;
+
+    let width = if is_64 { 8 } else { 4 };
+    let word = |elf: &mut Vec<u8>, offset: u64, value: u64| {
+        let offset = offset as usize;
+        elf[offset..offset + width].copy_from_slice(&value.to_le_bytes()[..width]);
+    };
+    let mut elf = vec![0; DYNAMIC_ADDR as usize + 8 * width];
+
+    elf[0..4].copy_from_slice(b"\x7fELF");
+    elf[4] = if is_64 { 2 } else { 1 };
+    elf[5] = 1;
+    elf[6] = 1;
+    write_u16(&mut elf, 16, 3);
+    write_u16(&mut elf, 18, u64::from(machine));
+    write_u32(&mut elf, 20, 1);
+    let (ehsize, phentsize) = if is_64 { (64, 56) } else { (52, 32) };
+    word(&mut elf, 24 + width as u64, ehsize);
+    write_u16(&mut elf, 3 * width + 28, ehsize);
+    write_u16(&mut elf, 3 * width + 30, phentsize);
+    write_u16(&mut elf, 3 * width + 32, 2);
+
+    let dynamic_size = 8 * width as u64;
+    for (index, (typ, offset, size)) in [
+        (1, 0, DYNAMIC_ADDR + dynamic_size),
+        (2, DYNAMIC_ADDR, dynamic_size),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let at = ehsize + index as u64 * phentsize;
+        write_u32(&mut elf, at as usize, typ);
+        let fields = if is_64 {
+            write_u32(&mut elf, at as usize + 4, 6);
+            at + 8
+        } else {
+            write_u32(&mut elf, at as usize + 24, 6);
+            at + 4
+        };
+        for (field, value) in [offset, offset, offset, size, size].into_iter().enumerate() {
+            word(&mut elf, fields + (field * width) as u64, value);
+        }
+    }
+
+    let html = b"<!DOCTYPE html><html></html>";
+    let compressed = brotli_compress(html);
+    let name = b"/index.html";
+    let data_addr = RODATA_ADDR + name.len() as u64;
+    elf[RODATA_ADDR as usize..data_addr as usize].copy_from_slice(name);
+    elf[data_addr as usize..data_addr as usize + compressed.len()].copy_from_slice(&compressed);
+
+    let name_slot = HEADER_ADDR;
+    let data_slot = HEADER_ADDR + 2 * width as u64;
+    word(&mut elf, HEADER_ADDR + width as u64, name.len() as u64);
+    word(
+        &mut elf,
+        HEADER_ADDR + 3 * width as u64,
+        compressed.len() as u64,
+    );
+    if !matches!(relocations, DynamicRelocations::Rela(_)) {
+        word(&mut elf, name_slot, RODATA_ADDR);
+        word(&mut elf, data_slot, data_addr);
+    }
+
+    let (tags, table) = match relocations {
+        DynamicRelocations::Rela(r_type) => (
+            [7, 8, 9],
+            [
+                name_slot,
+                u64::from(r_type),
+                RODATA_ADDR,
+                data_slot,
+                u64::from(r_type),
+                data_addr,
+            ]
+            .to_vec(),
+        ),
+        DynamicRelocations::Rel(r_type) => (
+            [17, 18, 19],
+            [name_slot, u64::from(r_type), data_slot, u64::from(r_type)].to_vec(),
+        ),
+        // The address entry relocates the name slot and moves on a word; bit
+        // 2 of the bitmap is the data slot one word later.
+        DynamicRelocations::Relr => ([36, 35, 37], [name_slot, 0b101].to_vec()),
+    };
+    for (index, value) in table.iter().enumerate() {
+        word(&mut elf, TABLE_ADDR + (index * width) as u64, *value);
+    }
+    let entry_size = (table.len() / 2).max(1) * width;
+    for (index, (tag, value)) in tags
+        .into_iter()
+        .zip([TABLE_ADDR, (table.len() * width) as u64, entry_size as u64])
+        .enumerate()
+    {
+        let at = DYNAMIC_ADDR + (2 * index * width) as u64;
+        word(&mut elf, at, tag);
+        word(&mut elf, at + width as u64, value);
+    }
+    elf
+}
+
 fn android_elf(use_empty_prefix: bool) -> Vec<u8> {
     const ELF_HEADER_SIZE: usize = 64;
     const SECTION_HEADER_SIZE: usize = 64;
@@ -392,7 +1333,7 @@ fn android_elf(use_empty_prefix: bool) -> Vec<u8> {
     elf
 }
 
-fn brotli_compress(data: &[u8]) -> Vec<u8> {
+pub fn brotli_compress(data: &[u8]) -> Vec<u8> {
     let mut compressed = Vec::new();
     let mut compressor = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
     compressor.write_all(data).unwrap();
@@ -494,6 +1435,50 @@ fn write_u32(data: &mut [u8], offset: usize, value: u32) {
     data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
 }
 
+fn write_be_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+}
+
 fn write_u64(data: &mut [u8], offset: usize, value: u64) {
     data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
 }
+
+/// A Tauri updater key made from `seed`, as the base64 `pubkey` of the app
+/// config.
+#[cfg(feature = "signing")]
+pub fn tauri_updater_pubkey(seed: u8) -> String {
+    use base64::Engine;
+    let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+    let mut payload = b"Ed".to_vec();
+    payload.extend_from_slice(&[seed; 8]);
+    payload.extend_from_slice(key.verifying_key().as_bytes());
+    let text = format!(
+        "untrusted comment: minisign public key: {seed:016X}\n{}\n",
+        base64::engine::general_purpose::STANDARD.encode(payload)
+    );
+    base64::engine::general_purpose::STANDARD.encode(text)
+}
+
+/// Signs `data` with the updater key of `seed` the way the Tauri CLI does:
+/// a prehashed minisign signature, base64 encoded for the `.sig` file.
+#[cfg(feature = "signing")]
+pub fn tauri_updater_signature(seed: u8, file: &str, data: &[u8]) -> String {
+    use base64::Engine;
+    use blake2::Digest;
+    use ed25519_dalek::Signer;
+    let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+    let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+    let signature = key.sign(&blake2::Blake2b512::digest(data)).to_bytes();
+    let trusted_comment = format!("timestamp:1700000000\tfile:{file}");
+    let mut global = signature.to_vec();
+    global.extend_from_slice(trusted_comment.as_bytes());
+    let mut payload = b"ED".to_vec();
+    payload.extend_from_slice(&[seed; 8]);
+    payload.extend_from_slice(&signature);
+    let text = format!(
+        "untrusted comment: signature from tauri secret key\n{}\ntrusted comment: {trusted_comment}\n{}\n",
+        encode(&payload),
+        encode(&key.sign(&global).to_bytes())
+    );
+    encode(text.as_bytes())
+}
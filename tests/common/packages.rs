@@ -0,0 +1,867 @@
+//! Builders for the package formats accepted as input.
+
+use flate2::write::{DeflateEncoder, ZlibEncoder};
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+const HFS_BLOCK_SIZE: usize = 4096;
+const HFS_NODE_SIZE: usize = 4096;
+const HFS_FIRST_CNID: u32 = 16;
+const HFS_ROOT_FOLDER_ID: u32 = 2;
+
+/// Builds an HFS+ volume containing `files`, creating intermediate folders.
+pub fn hfs_volume(files: &[(&str, &[u8])]) -> Vec<u8> {
+    // Blocks: 0 volume header, 1 extents tree, 2-3 catalog tree, then data.
+    let mut folders = BTreeMap::from([(String::new(), HFS_ROOT_FOLDER_ID)]);
+    let mut next_id = HFS_FIRST_CNID;
+    let mut records = Vec::new();
+    let mut data_blocks = Vec::new();
+    let mut next_block = 4u32;
+
+    for (path, content) in files {
+        let components = path.split('/').collect::<Vec<_>>();
+        let mut parent = String::new();
+        for folder in &components[..components.len() - 1] {
+            let folder_path = format!("{parent}/{folder}");
+            if !folders.contains_key(&folder_path) {
+                let id = next_id;
+                next_id += 1;
+                folders.insert(folder_path.clone(), id);
+                records.push((catalog_key(folders[&parent], folder), folder_record(id)));
+            }
+            parent = folder_path;
+        }
+
+        let blocks = content.len().div_ceil(HFS_BLOCK_SIZE) as u32;
+        let file_id = next_id;
+        next_id += 1;
+        records.push((
+            catalog_key(folders[&parent], components[components.len() - 1]),
+            file_record(file_id, content.len() as u64, next_block, blocks),
+        ));
+        let mut padded = content.to_vec();
+        padded.resize(blocks as usize * HFS_BLOCK_SIZE, 0);
+        data_blocks.extend(padded);
+        next_block += blocks;
+    }
+
+    let mut volume = vec![0u8; 4 * HFS_BLOCK_SIZE];
+    let header = 1024;
+    volume[header..header + 2].copy_from_slice(b"H+");
+    volume[header + 2..header + 4].copy_from_slice(&4u16.to_be_bytes());
+    volume[header + 40..header + 44].copy_from_slice(&(HFS_BLOCK_SIZE as u32).to_be_bytes());
+    volume[header + 44..header + 48].copy_from_slice(&next_block.to_be_bytes());
+    write_fork(&mut volume, header + 192, HFS_NODE_SIZE as u64, 1, 1);
+    write_fork(&mut volume, header + 272, 2 * HFS_NODE_SIZE as u64, 2, 2);
+
+    let extents_header = btree_header_node(0, 1, 0);
+    volume[HFS_BLOCK_SIZE..2 * HFS_BLOCK_SIZE].copy_from_slice(&extents_header);
+    let catalog_header = btree_header_node(1, 2, records.len() as u32);
+    volume[2 * HFS_BLOCK_SIZE..3 * HFS_BLOCK_SIZE].copy_from_slice(&catalog_header);
+    volume[3 * HFS_BLOCK_SIZE..4 * HFS_BLOCK_SIZE].copy_from_slice(&leaf_node(&records));
+    volume.extend(data_blocks);
+    volume
+}
+
+/// Wraps `volume` in a zlib-compressed (UDZO) UDIF disk image.
+pub fn udif_image(volume: &[u8]) -> Vec<u8> {
+    assert_eq!(volume.len() % 512, 0);
+    let sectors = (volume.len() / 512) as u64;
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(volume).unwrap();
+    let mut image = encoder.finish().unwrap();
+    let compressed_len = image.len() as u64;
+
+    let mut mish = vec![0u8; 204];
+    mish[..4].copy_from_slice(b"mish");
+    mish[4..8].copy_from_slice(&1u32.to_be_bytes());
+    mish[16..24].copy_from_slice(&sectors.to_be_bytes());
+    mish[200..204].copy_from_slice(&2u32.to_be_bytes());
+    mish.extend(udif_chunk(0x8000_0005, 0, sectors, 0, compressed_len));
+    mish.extend(udif_chunk(0xffff_ffff, sectors, 0, compressed_len, 0));
+
+    let mut partition = plist::Dictionary::new();
+    partition.insert("Attributes".into(), "0x0050".into());
+    partition.insert("Data".into(), plist::Value::Data(mish));
+    partition.insert("ID".into(), "0".into());
+    partition.insert("Name".into(), "whole disk (Apple_HFS : 0)".into());
+    let mut resource_fork = plist::Dictionary::new();
+    resource_fork.insert(
+        "blkx".into(),
+        plist::Value::Array(vec![plist::Value::Dictionary(partition)]),
+    );
+    let mut root = plist::Dictionary::new();
+    root.insert(
+        "resource-fork".into(),
+        plist::Value::Dictionary(resource_fork),
+    );
+    let mut xml = Vec::new();
+    plist::Value::Dictionary(root)
+        .to_writer_xml(&mut xml)
+        .unwrap();
+
+    let xml_offset = image.len() as u64;
+    image.extend_from_slice(&xml);
+
+    let mut koly = vec![0u8; 512];
+    koly[..4].copy_from_slice(b"koly");
+    koly[4..8].copy_from_slice(&4u32.to_be_bytes());
+    koly[8..12].copy_from_slice(&512u32.to_be_bytes());
+    koly[32..40].copy_from_slice(&compressed_len.to_be_bytes());
+    koly[216..224].copy_from_slice(&xml_offset.to_be_bytes());
+    koly[224..232].copy_from_slice(&(xml.len() as u64).to_be_bytes());
+    koly[492..500].copy_from_slice(&sectors.to_be_bytes());
+    image.extend(koly);
+    image
+}
+
+/// Builds a disk image whose volume holds `Example.app` wrapping `executable`.
+pub fn dmg_with_app(executable: &[u8]) -> Vec<u8> {
+    let info_plist = info_plist("Example");
+    udif_image(&hfs_volume(&[
+        ("Example.app/Contents/Info.plist", info_plist.as_bytes()),
+        ("Example.app/Contents/MacOS/Example", executable),
+        ("Example.app/Contents/MacOS/helper", b"not the app"),
+        ("Example.app/Contents/Resources/icon.icns", b"icns"),
+    ]))
+}
+
+pub fn info_plist(executable: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>CFBundleExecutable</key>
+  <string>{executable}</string>
+  <key>CFBundleIdentifier</key>
+  <string>com.example.app</string>
+  <key>CFBundleShortVersionString</key>
+  <string>1.4.2</string>
+  <key>CFBundleVersion</key>
+  <string>20241001</string>
+  <key>LSMinimumSystemVersion</key>
+  <string>10.15</string>
+  <key>CFBundleURLTypes</key>
+  <array>
+    <dict>
+      <key>CFBundleURLName</key>
+      <string>com.example.app</string>
+      <key>CFBundleURLSchemes</key>
+      <array>
+        <string>example</string>
+      </array>
+    </dict>
+  </array>
+</dict>
+</plist>"#
+    )
+}
+
+fn udif_chunk(kind: u32, sector: u64, sectors: u64, offset: u64, length: u64) -> Vec<u8> {
+    let mut chunk = Vec::with_capacity(40);
+    chunk.extend(kind.to_be_bytes());
+    chunk.extend(0u32.to_be_bytes());
+    chunk.extend(sector.to_be_bytes());
+    chunk.extend(sectors.to_be_bytes());
+    chunk.extend(offset.to_be_bytes());
+    chunk.extend(length.to_be_bytes());
+    chunk
+}
+
+fn write_fork(buf: &mut [u8], offset: usize, size: u64, start_block: u32, blocks: u32) {
+    buf[offset..offset + 8].copy_from_slice(&size.to_be_bytes());
+    buf[offset + 12..offset + 16].copy_from_slice(&blocks.to_be_bytes());
+    buf[offset + 16..offset + 20].copy_from_slice(&start_block.to_be_bytes());
+    buf[offset + 20..offset + 24].copy_from_slice(&blocks.to_be_bytes());
+}
+
+fn catalog_key(parent_id: u32, name: &str) -> Vec<u8> {
+    let units = name.encode_utf16().collect::<Vec<_>>();
+    let mut key = Vec::new();
+    key.extend((6 + 2 * units.len() as u16).to_be_bytes());
+    key.extend(parent_id.to_be_bytes());
+    key.extend((units.len() as u16).to_be_bytes());
+    for unit in units {
+        key.extend(unit.to_be_bytes());
+    }
+    key
+}
+
+fn folder_record(id: u32) -> Vec<u8> {
+    let mut record = vec![0u8; 88];
+    record[..2].copy_from_slice(&1u16.to_be_bytes());
+    record[8..12].copy_from_slice(&id.to_be_bytes());
+    record
+}
+
+fn file_record(id: u32, size: u64, start_block: u32, blocks: u32) -> Vec<u8> {
+    let mut record = vec![0u8; 248];
+    record[..2].copy_from_slice(&2u16.to_be_bytes());
+    record[8..12].copy_from_slice(&id.to_be_bytes());
+    write_fork(&mut record, 88, size, start_block, blocks);
+    record
+}
+
+fn btree_header_node(first_leaf: u32, total_nodes: u32, leaf_records: u32) -> Vec<u8> {
+    let mut node = vec![0u8; HFS_NODE_SIZE];
+    node[8] = 1;
+    node[10..12].copy_from_slice(&3u16.to_be_bytes());
+    let header = 14;
+    node[header..header + 2].copy_from_slice(&u16::from(first_leaf != 0).to_be_bytes());
+    node[header + 2..header + 6].copy_from_slice(&first_leaf.to_be_bytes());
+    node[header + 6..header + 10].copy_from_slice(&leaf_records.to_be_bytes());
+    node[header + 10..header + 14].copy_from_slice(&first_leaf.to_be_bytes());
+    node[header + 14..header + 18].copy_from_slice(&first_leaf.to_be_bytes());
+    node[header + 18..header + 20].copy_from_slice(&(HFS_NODE_SIZE as u16).to_be_bytes());
+    node[header + 20..header + 22].copy_from_slice(&516u16.to_be_bytes());
+    node[header + 22..header + 26].copy_from_slice(&total_nodes.to_be_bytes());
+    node
+}
+
+fn leaf_node(records: &[(Vec<u8>, Vec<u8>)]) -> Vec<u8> {
+    let mut node = vec![0u8; HFS_NODE_SIZE];
+    node[8] = 0xff;
+    node[9] = 1;
+    node[10..12].copy_from_slice(&(records.len() as u16).to_be_bytes());
+    let mut offset = 14;
+    for (index, (key, data)) in records.iter().enumerate() {
+        let slot = HFS_NODE_SIZE - 2 * (index + 1);
+        node[slot..slot + 2].copy_from_slice(&(offset as u16).to_be_bytes());
+        node[offset..offset + key.len()].copy_from_slice(key);
+        offset += key.len();
+        node[offset..offset + data.len()].copy_from_slice(data);
+        offset += data.len();
+    }
+    let slot = HFS_NODE_SIZE - 2 * (records.len() + 1);
+    assert!(offset <= slot, "catalog records overflow one leaf node");
+    node[slot..slot + 2].copy_from_slice(&(offset as u16).to_be_bytes());
+    node
+}
+
+/// Builds a single-folder MSZIP cabinet holding `files`.
+pub fn cab_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let folder_data = files
+        .iter()
+        .flat_map(|(_, content)| content.iter().copied())
+        .collect::<Vec<_>>();
+    let blocks = folder_data
+        .chunks(32 * 1024)
+        .map(|block| {
+            let mut encoder = DeflateEncoder::new(b"CK".to_vec(), Compression::default());
+            encoder.write_all(block).unwrap();
+            (encoder.finish().unwrap(), block.len())
+        })
+        .collect::<Vec<_>>();
+
+    let mut file_table = Vec::new();
+    let mut offset = 0u32;
+    for (name, content) in files {
+        file_table.extend((content.len() as u32).to_le_bytes());
+        file_table.extend(offset.to_le_bytes());
+        file_table.extend([0u8; 8]);
+        file_table.extend(name.as_bytes());
+        file_table.push(0);
+        offset += content.len() as u32;
+    }
+
+    let files_offset = 36 + 8;
+    let data_offset = files_offset + file_table.len();
+    let mut cab = vec![0u8; 36];
+    cab[..4].copy_from_slice(b"MSCF");
+    cab[16..20].copy_from_slice(&(files_offset as u32).to_le_bytes());
+    cab[24] = 3;
+    cab[25] = 1;
+    cab[26..28].copy_from_slice(&1u16.to_le_bytes());
+    cab[28..30].copy_from_slice(&(files.len() as u16).to_le_bytes());
+    cab.extend((data_offset as u32).to_le_bytes());
+    cab.extend((blocks.len() as u16).to_le_bytes());
+    cab.extend(1u16.to_le_bytes());
+    cab.extend(file_table);
+    for (packed, unpacked_len) in blocks {
+        cab.extend(0u32.to_le_bytes());
+        cab.extend((packed.len() as u16).to_le_bytes());
+        cab.extend((unpacked_len as u16).to_le_bytes());
+        cab.extend(packed);
+    }
+    let total = cab.len() as u32;
+    cab[8..12].copy_from_slice(&total.to_le_bytes());
+    cab
+}
+
+/// An installed file: `File` table key, long file name and contents.
+pub type MsiFile<'a> = (&'a str, &'a str, &'a [u8]);
+
+/// Builds an installer database whose `File` table lists `files`, stored in
+/// an embedded cabinet.
+pub fn msi_with_files(files: &[MsiFile]) -> Vec<u8> {
+    let mut strings = Vec::<String>::new();
+    let mut intern = |value: &str| {
+        let position = strings.iter().position(|existing| existing == value);
+        position.unwrap_or_else(|| {
+            strings.push(value.to_string());
+            strings.len() - 1
+        }) as u16
+            + 1
+    };
+
+    // File (s72), Component_ (s72), FileName (l255), FileSize (i4), Sequence (i2).
+    let schema = [
+        ("File", 0x1d48u16),
+        ("Component_", 0x0d48),
+        ("FileName", 0x0fff),
+        ("FileSize", 0x0104),
+        ("Sequence", 0x0502),
+    ];
+    let table_id = intern("File");
+    let mut columns = Vec::new();
+    columns.extend(schema.iter().flat_map(|_| table_id.to_le_bytes()));
+    columns.extend((1..=schema.len() as u16).flat_map(|number| (number ^ 0x8000).to_le_bytes()));
+    let name_ids = schema.map(|(name, _)| intern(name));
+    columns.extend(name_ids.iter().flat_map(|id| id.to_le_bytes()));
+    columns.extend(
+        schema
+            .iter()
+            .flat_map(|(_, kind)| (kind ^ 0x8000).to_le_bytes()),
+    );
+
+    let keys = files
+        .iter()
+        .map(|(key, _, _)| intern(key))
+        .collect::<Vec<_>>();
+    let component = intern("MainComponent");
+    let names = files
+        .iter()
+        .map(|(_, name, _)| intern(&format!("SHORT~1.EXE|{name}")))
+        .collect::<Vec<_>>();
+    let mut table = Vec::new();
+    table.extend(keys.iter().flat_map(|id| id.to_le_bytes()));
+    table.extend(files.iter().flat_map(|_| component.to_le_bytes()));
+    table.extend(names.iter().flat_map(|id| id.to_le_bytes()));
+    table.extend(
+        files
+            .iter()
+            .flat_map(|(_, _, content)| (content.len() as u32 ^ 0x8000_0000).to_le_bytes()),
+    );
+    table.extend((1..=files.len() as u16).flat_map(|sequence| (sequence ^ 0x8000).to_le_bytes()));
+
+    let mut pool = 0u32.to_le_bytes().to_vec();
+    let mut string_data = Vec::new();
+    for string in &strings {
+        pool.extend((string.len() as u16).to_le_bytes());
+        pool.extend(1u16.to_le_bytes());
+        string_data.extend(string.as_bytes());
+    }
+
+    let cab = cab_archive(
+        &files
+            .iter()
+            .map(|(key, _, content)| (*key, *content))
+            .collect::<Vec<_>>(),
+    );
+
+    let mut compound = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new())).unwrap();
+    for (name, contents, table) in [
+        ("_StringPool", pool, true),
+        ("_StringData", string_data, true),
+        ("_Columns", columns, true),
+        ("File", table, true),
+        ("cab1.cab", cab, false),
+    ] {
+        let mut stream = compound
+            .create_stream(format!("/{}", encode_msi_stream_name(name, table)))
+            .unwrap();
+        stream.write_all(&contents).unwrap();
+    }
+    compound.flush().unwrap();
+    compound.into_inner().into_inner()
+}
+
+fn encode_msi_stream_name(name: &str, table: bool) -> String {
+    const ALPHABET: &[u8; 64] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz._";
+    let index = |c: u8| ALPHABET.iter().position(|&a| a == c).unwrap() as u32;
+
+    let mut encoded = String::new();
+    if table {
+        encoded.push('\u{4840}');
+    }
+    for pair in name.as_bytes().chunks(2) {
+        let code = match pair {
+            [first, second] => 0x3800 + index(*first) + (index(*second) << 6),
+            [single] => 0x4800 + index(*single),
+            _ => unreachable!(),
+        };
+        encoded.push(char::from_u32(code).unwrap());
+    }
+    encoded
+}
+
+/// How an NSIS fixture stores its payload.
+#[derive(Debug, Clone, Copy)]
+pub enum NsisCompression {
+    /// One LZMA stream covering the header and all files (`/SOLID lzma`).
+    SolidLzma,
+    /// The header and each file deflated separately (`zlib`).
+    Deflate,
+}
+
+/// Builds an NSIS setup executable whose script extracts `files` into
+/// `$INSTDIR`.
+pub fn nsis_installer(files: &[(&str, &[u8])], compression: NsisCompression) -> Vec<u8> {
+    let entries_at = 4 + 8 * 8;
+    let strings_at = entries_at + files.len() * 28;
+
+    // Unicode string table: an empty string, then "$INSTDIR\<name>" for each
+    // file, with $INSTDIR stored as a variable reference.
+    let mut strings = vec![0u16];
+    let mut name_ptrs = Vec::new();
+    for (name, _) in files {
+        name_ptrs.push(strings.len() as u32);
+        strings.extend([2, 0x8015, u16::from(b'\\')]);
+        strings.extend(name.encode_utf16());
+        strings.push(0);
+    }
+
+    let mut data_offsets = Vec::new();
+    let mut data = Vec::new();
+    for (_, content) in files {
+        data_offsets.push(data.len() as u32);
+        match compression {
+            NsisCompression::SolidLzma => {
+                data.extend((content.len() as u32).to_le_bytes());
+                data.extend(*content);
+            }
+            NsisCompression::Deflate => data.extend(nsis_deflate_block(content)),
+        }
+    }
+
+    let mut header = vec![0u8; entries_at];
+    header[4 + 2 * 8..4 + 2 * 8 + 4].copy_from_slice(&(entries_at as u32).to_le_bytes());
+    header[4 + 2 * 8 + 4..4 + 2 * 8 + 8].copy_from_slice(&(files.len() as u32).to_le_bytes());
+    header[4 + 3 * 8..4 + 3 * 8 + 4].copy_from_slice(&(strings_at as u32).to_le_bytes());
+    for (name_ptr, data_offset) in name_ptrs.iter().zip(&data_offsets) {
+        header.extend(20u32.to_le_bytes());
+        header.extend(0u32.to_le_bytes());
+        header.extend(name_ptr.to_le_bytes());
+        header.extend(data_offset.to_le_bytes());
+        header.extend([0u8; 12]);
+    }
+    header.extend(strings.iter().flat_map(|unit| unit.to_le_bytes()));
+
+    let payload = match compression {
+        NsisCompression::SolidLzma => {
+            let mut stream = (header.len() as u32).to_le_bytes().to_vec();
+            stream.extend(&header);
+            stream.extend(data);
+            let mut compressed = Vec::new();
+            let options = lzma_rs::compress::Options {
+                unpacked_size: lzma_rs::compress::UnpackedSize::SkipWritingToHeader,
+            };
+            lzma_rs::lzma_compress_with_options(&mut &stream[..], &mut compressed, &options)
+                .unwrap();
+            compressed
+        }
+        NsisCompression::Deflate => {
+            let mut payload = nsis_deflate_block(&header);
+            payload.extend(data);
+            payload
+        }
+    };
+
+    let mut installer = b"MZ".to_vec();
+    installer.resize(1024, 0);
+    installer.extend(0u32.to_le_bytes());
+    installer.extend(b"\xef\xbe\xad\xdeNullsoftInst");
+    installer.extend((header.len() as u32).to_le_bytes());
+    installer.extend((28 + payload.len() as u32).to_le_bytes());
+    installer.extend(payload);
+    installer
+}
+
+fn nsis_deflate_block(data: &[u8]) -> Vec<u8> {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let mut block = (compressed.len() as u32 | 0x8000_0000)
+        .to_le_bytes()
+        .to_vec();
+    block.extend(compressed);
+    block
+}
+
+/// Compression applied to a package payload fixture.
+#[derive(Debug, Clone, Copy)]
+pub enum PayloadCompression {
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+/// Compresses `data` as a package payload.
+pub fn compress_payload(data: &[u8], compression: PayloadCompression) -> Vec<u8> {
+    match compression {
+        PayloadCompression::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(data).unwrap();
+            encoder.finish().unwrap()
+        }
+        PayloadCompression::Xz => {
+            let mut out = Vec::new();
+            lzma_rs::xz_compress(&mut &data[..], &mut out).unwrap();
+            out
+        }
+        PayloadCompression::Zstd => {
+            ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest)
+        }
+    }
+}
+
+/// Builds a tar archive of regular files, with paths stored as `./<path>`.
+pub fn tar_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("./{path}"), *content)
+            .unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+/// Builds a `.deb` whose data archive holds `files`.
+pub fn deb_package(files: &[(&str, &[u8])], compression: PayloadCompression) -> Vec<u8> {
+    let extension = match compression {
+        PayloadCompression::Gzip => "gz",
+        PayloadCompression::Xz => "xz",
+        PayloadCompression::Zstd => "zst",
+    };
+    let control = compress_payload(
+        &tar_archive(&[("control", b"Package: example\n")]),
+        PayloadCompression::Gzip,
+    );
+    let data = compress_payload(&tar_archive(files), compression);
+
+    let mut deb = b"!<arch>\n".to_vec();
+    for (name, contents) in [
+        ("debian-binary".to_string(), b"2.0\n".to_vec()),
+        ("control.tar.gz".to_string(), control),
+        (format!("data.tar.{extension}"), data),
+    ] {
+        deb.extend(
+            format!(
+                "{name:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                0,
+                0,
+                0,
+                "100644",
+                contents.len()
+            )
+            .as_bytes(),
+        );
+        deb.extend(&contents);
+        if contents.len() % 2 == 1 {
+            deb.push(b'\n');
+        }
+    }
+    deb
+}
+
+/// Builds a `newc` cpio archive of regular files, with paths stored as
+/// `./<path>`.
+pub fn cpio_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut cpio = Vec::new();
+    let mut append = |name: &str, mode: u32, content: &[u8]| {
+        let name = format!("{name}\0");
+        cpio.extend(b"070701");
+        for value in [
+            0,
+            mode,
+            0,
+            0,
+            1,
+            0,
+            content.len() as u32,
+            0,
+            0,
+            0,
+            0,
+            name.len() as u32,
+            0,
+        ] {
+            cpio.extend(format!("{value:08x}").as_bytes());
+        }
+        cpio.extend(name.as_bytes());
+        cpio.resize(cpio.len().next_multiple_of(4), 0);
+        cpio.extend(content);
+        cpio.resize(cpio.len().next_multiple_of(4), 0);
+    };
+    for (path, content) in files {
+        append(&format!("./{path}"), 0o100755, content);
+    }
+    append("TRAILER!!!", 0, &[]);
+    cpio
+}
+
+/// Builds an `.rpm` with empty headers whose payload holds `files`.
+pub fn rpm_package(files: &[(&str, &[u8])], compression: PayloadCompression) -> Vec<u8> {
+    let mut rpm = vec![0u8; 96];
+    rpm[..4].copy_from_slice(b"\xed\xab\xee\xdb");
+    rpm[4] = 3;
+    // Signature header with one 4-byte entry, padded to 8 bytes, then an
+    // empty main header.
+    rpm.extend(b"\x8e\xad\xe8\x01\0\0\0\0");
+    rpm.extend(1u32.to_be_bytes());
+    rpm.extend(4u32.to_be_bytes());
+    rpm.extend([0u8; 16]);
+    rpm.extend([0u8; 4]);
+    rpm.extend([0u8; 4]);
+    rpm.extend(b"\x8e\xad\xe8\x01\0\0\0\0");
+    rpm.extend(0u32.to_be_bytes());
+    rpm.extend(0u32.to_be_bytes());
+    rpm.extend(compress_payload(&cpio_archive(files), compression));
+    rpm
+}
+
+const SQUASHFS_BLOCK_SIZE: usize = 4096;
+
+enum SquashNode<'a> {
+    File(&'a [u8]),
+    Dir(BTreeMap<String, SquashNode<'a>>),
+}
+
+struct SquashWriter {
+    data: Vec<u8>,
+    inodes: Vec<u8>,
+    directories: Vec<u8>,
+    inode_count: u32,
+}
+
+impl SquashWriter {
+    fn write_file(&mut self, content: &[u8]) -> u32 {
+        let blocks_start = self.data.len() as u32;
+        let mut block_sizes = Vec::new();
+        for block in content.chunks(SQUASHFS_BLOCK_SIZE) {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(block).unwrap();
+            let compressed = encoder.finish().unwrap();
+            if compressed.len() < block.len() {
+                block_sizes.push(compressed.len() as u32);
+                self.data.extend(compressed);
+            } else {
+                block_sizes.push(block.len() as u32 | 0x0100_0000);
+                self.data.extend(block);
+            }
+        }
+
+        self.inode_count += 1;
+        let reference = self.inodes.len() as u32;
+        self.inodes
+            .extend(squashfs_inode_header(2, self.inode_count));
+        self.inodes.extend(blocks_start.to_le_bytes());
+        self.inodes.extend(0xffff_ffffu32.to_le_bytes());
+        self.inodes.extend(0u32.to_le_bytes());
+        self.inodes.extend((content.len() as u32).to_le_bytes());
+        self.inodes
+            .extend(block_sizes.iter().flat_map(|size| size.to_le_bytes()));
+        reference
+    }
+
+    fn write_dir(&mut self, children: &BTreeMap<String, SquashNode>) -> u32 {
+        let mut entries = Vec::new();
+        for (name, node) in children {
+            let (reference, kind) = match node {
+                SquashNode::File(content) => (self.write_file(content), 2u16),
+                SquashNode::Dir(children) => (self.write_dir(children), 1),
+            };
+            entries.push((name, reference, kind));
+        }
+
+        let listing_offset = self.directories.len() as u16;
+        if !entries.is_empty() {
+            self.directories
+                .extend((entries.len() as u32 - 1).to_le_bytes());
+            self.directories.extend(0u32.to_le_bytes());
+            self.directories.extend(1u32.to_le_bytes());
+            for (name, reference, kind) in &entries {
+                self.directories.extend((*reference as u16).to_le_bytes());
+                self.directories.extend(0i16.to_le_bytes());
+                self.directories.extend(kind.to_le_bytes());
+                self.directories
+                    .extend((name.len() as u16 - 1).to_le_bytes());
+                self.directories.extend(name.as_bytes());
+            }
+        }
+        let listing_size = self.directories.len() as u16 - listing_offset + 3;
+
+        self.inode_count += 1;
+        let reference = self.inodes.len() as u32;
+        self.inodes
+            .extend(squashfs_inode_header(1, self.inode_count));
+        self.inodes.extend(0u32.to_le_bytes());
+        self.inodes.extend(2u32.to_le_bytes());
+        self.inodes.extend(listing_size.to_le_bytes());
+        self.inodes.extend(listing_offset.to_le_bytes());
+        self.inodes.extend(1u32.to_le_bytes());
+        reference
+    }
+}
+
+fn squashfs_inode_header(kind: u16, number: u32) -> Vec<u8> {
+    let mut header = Vec::with_capacity(16);
+    header.extend(kind.to_le_bytes());
+    header.extend(0o755u16.to_le_bytes());
+    header.extend([0u8; 8]);
+    header.extend(number.to_le_bytes());
+    header
+}
+
+fn squashfs_metadata_block(contents: &[u8]) -> Vec<u8> {
+    assert!(contents.len() <= 8192, "fixture metadata exceeds one block");
+    let mut block = (contents.len() as u16 | 0x8000).to_le_bytes().to_vec();
+    block.extend(contents);
+    block
+}
+
+/// Builds a zlib-compressed squashfs image containing `files`, without
+/// fragments and with uncompressed metadata.
+pub fn squashfs_image(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut root = BTreeMap::new();
+    for (path, content) in files {
+        let components = path.split('/').collect::<Vec<_>>();
+        let mut dir = &mut root;
+        for component in &components[..components.len() - 1] {
+            let node = dir
+                .entry(component.to_string())
+                .or_insert_with(|| SquashNode::Dir(BTreeMap::new()));
+            let SquashNode::Dir(children) = node else {
+                panic!("{component} is a file");
+            };
+            dir = children;
+        }
+        dir.insert(
+            components[components.len() - 1].to_string(),
+            SquashNode::File(content),
+        );
+    }
+
+    let mut writer = SquashWriter {
+        data: vec![0u8; 96],
+        inodes: Vec::new(),
+        directories: Vec::new(),
+        inode_count: 0,
+    };
+    let root_inode = writer.write_dir(&root);
+
+    let mut image = writer.data;
+    let inode_table = image.len() as u64;
+    image.extend(squashfs_metadata_block(&writer.inodes));
+    let directory_table = image.len() as u64;
+    image.extend(squashfs_metadata_block(&writer.directories));
+    let id_table = image.len() as u64;
+    image.extend((id_table + 8).to_le_bytes());
+    image.extend(squashfs_metadata_block(&0u32.to_le_bytes()));
+
+    let bytes_used = image.len() as u64;
+    let superblock = &mut image[..96];
+    superblock[..4].copy_from_slice(b"hsqs");
+    superblock[4..8].copy_from_slice(&writer.inode_count.to_le_bytes());
+    superblock[12..16].copy_from_slice(&(SQUASHFS_BLOCK_SIZE as u32).to_le_bytes());
+    superblock[20..22].copy_from_slice(&1u16.to_le_bytes());
+    superblock[22..24].copy_from_slice(&12u16.to_le_bytes());
+    superblock[26..28].copy_from_slice(&1u16.to_le_bytes());
+    superblock[28..30].copy_from_slice(&4u16.to_le_bytes());
+    superblock[32..40].copy_from_slice(&u64::from(root_inode).to_le_bytes());
+    superblock[40..48].copy_from_slice(&bytes_used.to_le_bytes());
+    superblock[48..56].copy_from_slice(&id_table.to_le_bytes());
+    superblock[56..64].copy_from_slice(&u64::MAX.to_le_bytes());
+    superblock[64..72].copy_from_slice(&inode_table.to_le_bytes());
+    superblock[72..80].copy_from_slice(&directory_table.to_le_bytes());
+    superblock[80..88].copy_from_slice(&u64::MAX.to_le_bytes());
+    superblock[88..96].copy_from_slice(&u64::MAX.to_le_bytes());
+    image
+}
+
+/// Builds a type 2 AppImage: a minimal ELF runtime followed by a squashfs
+/// image of `files`.
+pub fn appimage(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut runtime = vec![0u8; 128];
+    runtime[..4].copy_from_slice(b"\x7fELF");
+    runtime[4] = 2;
+    runtime[5] = 1;
+    runtime[6] = 1;
+    runtime[8..11].copy_from_slice(b"AI\x02");
+    runtime[0x28..0x30].copy_from_slice(&64u64.to_le_bytes());
+    runtime[0x3a..0x3c].copy_from_slice(&64u16.to_le_bytes());
+    runtime[0x3c..0x3e].copy_from_slice(&1u16.to_le_bytes());
+    runtime.extend(squashfs_image(files));
+    runtime
+}
+
+/// Builds a deflated zip archive of `files`.
+pub fn zip_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(content).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// Builds an MSIX package declaring `Example App.exe` as its application.
+pub fn msix_package(executable: &[u8]) -> Vec<u8> {
+    let manifest = br#"<?xml version="1.0" encoding="utf-8"?>
+<Package xmlns="http://schemas.microsoft.com/appx/manifest/foundation/windows10">
+  <Identity Name="com.example.app" Version="0.1.0.0" ProcessorArchitecture="x64" />
+  <Applications>
+    <Application Id="App" Executable="Example App.exe" EntryPoint="Windows.FullTrustApplication" />
+  </Applications>
+</Package>"#;
+    zip_archive(&[
+        ("AppxManifest.xml", manifest),
+        ("Example%20App.exe", executable),
+        ("resources.dll", b"MZ resources"),
+    ])
+}
+
+/// Builds an MSIX bundle with an x86 package and an x64 package.
+pub fn msix_bundle(x86: &[u8], x64: &[u8]) -> Vec<u8> {
+    let manifest = br#"<?xml version="1.0" encoding="utf-8"?>
+<Bundle xmlns="http://schemas.microsoft.com/appx/2013/bundle">
+  <Packages>
+    <Package Type="application" Architecture="x86" FileName="Example_x86.msix" />
+    <Package Type="application" Architecture="x64" FileName="Example_x64.msix" />
+  </Packages>
+</Bundle>"#;
+    zip_archive(&[
+        ("AppxMetadata/AppxBundleManifest.xml", manifest),
+        ("Example_x86.msix", &msix_package(x86)),
+        ("Example_x64.msix", &msix_package(x64)),
+    ])
+}
+
+/// Builds a universal Mach-O binary with a slice per `(cputype, image)`.
+pub fn fat_macho(slices: &[(u32, &[u8])]) -> Vec<u8> {
+    const ALIGN: usize = 0x1000;
+    let mut fat = Vec::new();
+    fat.extend_from_slice(&0xcafe_babe_u32.to_be_bytes());
+    fat.extend_from_slice(&(slices.len() as u32).to_be_bytes());
+    let mut offset = ALIGN;
+    for (cputype, image) in slices {
+        for field in [*cputype, 0, offset as u32, image.len() as u32, 12] {
+            fat.extend_from_slice(&field.to_be_bytes());
+        }
+        offset = (offset + image.len()).next_multiple_of(ALIGN);
+    }
+    for (_, image) in slices {
+        fat.resize(fat.len().next_multiple_of(ALIGN), 0);
+        fat.extend_from_slice(image);
+    }
+    fat
+}
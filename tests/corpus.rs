@@ -0,0 +1,150 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
+mod common;
+
+#[cfg(feature = "cli")]
+use assert_cmd::Command;
+#[cfg(feature = "cli")]
+use predicates::str::contains;
+use std::fs;
+use tauri_dumper::corpus::{BatchOptions, Corpus, TIMELINE_FILE_NAME};
+use tauri_dumper::{AssetScanner, BinaryImage};
+
+fn release(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    common::desktop_elf_with_tables(&[assets])
+}
+
+#[test]
+fn records_asset_changes_between_releases() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut corpus = Corpus::open(temp.path().join("corpus")).unwrap();
+    let releases = [
+        release(&[("/index.html", b"<p>v1</p>"), ("/app.js", b"one()")]),
+        release(&[("/index.html", b"<p>v1</p>"), ("/app.js", b"two()")]),
+        release(&[("/index.html", b"<p>v3</p>"), ("/logo.svg", b"<svg/>")]),
+    ];
+
+    let mut summaries = Vec::new();
+    for (index, data) in releases.iter().enumerate() {
+        let image = BinaryImage::from_bytes(data).unwrap();
+        let table = AssetScanner::scan(&image).unwrap();
+        summaries.push(corpus.add(&format!("v{}", index + 1), &table).unwrap());
+    }
+    // Unchanged assets come from the cache instead of being decoded again.
+    assert_eq!((summaries[1].stored, summaries[1].cached), (1, 1));
+
+    let timeline = corpus.timeline();
+    assert_eq!(timeline[0].added, ["/app.js", "/index.html"]);
+    assert_eq!(timeline[1].changed, ["/app.js"]);
+    assert_eq!(timeline[1].unchanged, 1);
+    assert_eq!(timeline[2].added, ["/logo.svg"]);
+    assert_eq!(timeline[2].removed, ["/app.js"]);
+    assert_eq!(timeline[2].changed, ["/index.html"]);
+
+    let hash = &corpus.release("v2").unwrap().assets["/app.js"];
+    assert_eq!(fs::read(corpus.blob_path(hash)).unwrap(), b"two()");
+
+    let reopened = Corpus::open(corpus.dir()).unwrap();
+    assert_eq!(reopened.releases().len(), 3);
+    assert!(corpus.dir().join(TIMELINE_FILE_NAME).is_file());
+}
+
+#[test]
+fn adds_releases_as_a_batch() {
+    let temp = tempfile::tempdir().unwrap();
+    let mut inputs = Vec::new();
+    let releases = [
+        release(&[("/index.html", b"<p>v1</p>"), ("/app.js", b"one()")]),
+        release(&[("/index.html", b"<p>v1</p>"), ("/app.js", b"two()")]),
+        release(&[("/index.html", b"<p>v1</p>"), ("/app.js", b"two()")]),
+        b"not a binary".to_vec(),
+    ];
+    for (index, data) in releases.iter().enumerate() {
+        let path = temp.path().join(format!("v{}", index + 1));
+        fs::write(&path, data).unwrap();
+        inputs.push((format!("v{}", index + 1), path));
+    }
+    let options = BatchOptions {
+        jobs: 4,
+        ..BatchOptions::default()
+    };
+
+    let mut corpus = Corpus::open(temp.path().join("corpus")).unwrap();
+    let outcomes = corpus.add_batch(&inputs, &options).unwrap();
+    // Counted in release order, as adding them one by one would.
+    let counts = outcomes[..3]
+        .iter()
+        .map(|outcome| {
+            let summary = outcome.as_ref().unwrap().unwrap();
+            (summary.stored, summary.cached)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [(2, 0), (1, 1), (0, 2)]);
+    assert!(outcomes[3].is_err());
+    let names = corpus
+        .releases()
+        .iter()
+        .map(|release| release.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["v1", "v2", "v3"]);
+    let hash = &corpus.release("v3").unwrap().assets["/app.js"];
+    assert_eq!(fs::read(corpus.blob_path(hash)).unwrap(), b"two()");
+    assert_eq!(corpus.timeline()[1].changed, ["/app.js"]);
+
+    // Releases already added from the same binary are not scanned again.
+    let mut reopened = Corpus::open(corpus.dir()).unwrap();
+    let outcomes = reopened.add_batch(&inputs[..3], &options).unwrap();
+    assert!(outcomes.iter().all(|outcome| matches!(outcome, Ok(None))));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_builds_corpus_in_version_order() {
+    let temp = tempfile::tempdir().unwrap();
+    let releases = temp.path().join("releases");
+    let corpus = temp.path().join("corpus");
+    fs::create_dir_all(&releases).unwrap();
+    fs::write(
+        releases.join("app-1.9.0"),
+        release(&[("/index.html", b"<p>old</p>")]),
+    )
+    .unwrap();
+    fs::write(
+        releases.join("app-1.10.0"),
+        release(&[("/index.html", b"<p>new</p>")]),
+    )
+    .unwrap();
+    fs::write(releases.join("notes.txt"), b"not a binary").unwrap();
+
+    let output = Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "corpus",
+            releases.to_str().unwrap(),
+            "-o",
+            corpus.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let timeline = summary["timeline"].as_array().unwrap();
+    assert_eq!(timeline[0]["release"], "app-1.9.0");
+    assert_eq!(timeline[1]["release"], "app-1.10.0");
+    assert_eq!(timeline[1]["changed"][0], "/index.html");
+    assert!(summary["releases"][2]["error"].is_string());
+
+    // A second run finds every release in the corpus already.
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "corpus",
+            releases.to_str().unwrap(),
+            "-o",
+            corpus.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("app-1.10.0: already in corpus"));
+}
@@ -0,0 +1,948 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
+mod common;
+
+use std::fs;
+use tauri_dumper::export::{
+    type_dir, ExportStatus, FsSink, MemorySink, STORE_DIR, STORE_INDEX_FILE_NAME,
+};
+use tauri_dumper::i18n::{self, LocaleFormat};
+use tauri_dumper::licenses::{self, LicenseOrigin};
+use tauri_dumper::manifest::{Manifest, MANIFEST_FILE_NAME};
+use tauri_dumper::routes::{self, ROUTES_FILE_NAME};
+use tauri_dumper::service_worker::find_service_workers_in;
+use tauri_dumper::{
+    AssetScanner, BinaryImage, Dedupe, Dumper, ExportFormat, ExportOptions, Layout,
+};
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn exports_assets_to_sqlite_database() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_aliases(b"shared")).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.format = ExportFormat::Sqlite;
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.exported, 2);
+
+    let database = rusqlite::Connection::open(temp.path().join("assets.db")).unwrap();
+    let rows = database
+        .prepare("SELECT path, mime, decompressed_size, alias_of, data FROM assets ORDER BY path")
+        .unwrap()
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Vec<u8>>(4)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        rows,
+        [
+            (
+                "/a.txt".into(),
+                "text/plain".into(),
+                6,
+                None,
+                b"shared".to_vec()
+            ),
+            (
+                "/b.txt".into(),
+                "text/plain".into(),
+                6,
+                Some("/a.txt".into()),
+                b"shared".to_vec()
+            ),
+        ]
+    );
+    let sha256: String = database
+        .query_row("SELECT sha256 FROM source", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(sha256, table.metadata().sha256);
+
+    options.overwrite = false;
+    options.skip_existing = true;
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.skipped_existing, 2);
+    assert!(summary
+        .records
+        .iter()
+        .all(|record| record.status == ExportStatus::SkippedExisting));
+}
+
+#[test]
+fn exports_assets_to_asar_archive() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.format = ExportFormat::Asar;
+    table.export(&options).unwrap();
+
+    let archive = fs::read(temp.path().join("app.asar")).unwrap();
+    let u32_at = |offset: usize| {
+        u32::from_le_bytes(archive[offset..offset + 4].try_into().unwrap()) as usize
+    };
+    let header_size = u32_at(4);
+    let json_len = u32_at(12);
+    let header: serde_json::Value = serde_json::from_slice(&archive[16..16 + json_len]).unwrap();
+    let file = |path: &[&str]| {
+        let entry = path
+            .iter()
+            .fold(&header, |entry, name| &entry["files"][name]);
+        let offset = 8 + header_size + entry["offset"].as_str().unwrap().parse::<usize>().unwrap();
+        &archive[offset..offset + entry["size"].as_u64().unwrap() as usize]
+    };
+    assert_eq!(file(&["index.html"]), b"<!DOCTYPE html><html></html>");
+    assert_eq!(
+        file(&["_app", "immutable", "assets", "style.css"]),
+        b"body{color:#111}"
+    );
+}
+
+#[test]
+fn lays_out_assets_by_type() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.layout = Layout::ByType;
+    table.export(&options).unwrap();
+
+    assert!(temp.path().join("other/index.html").is_file());
+    assert!(temp
+        .path()
+        .join("js/_app/immutable/chunks/app.js")
+        .is_file());
+    assert!(temp
+        .path()
+        .join("css/_app/immutable/assets/style.css")
+        .is_file());
+    let manifest = Manifest::read(temp.path().join(MANIFEST_FILE_NAME)).unwrap();
+    let style = manifest
+        .assets
+        .iter()
+        .find(|asset| asset.name == "/_app/immutable/assets/style.css")
+        .unwrap();
+    assert_eq!(
+        style.output_path.as_deref(),
+        Some("css/_app/immutable/assets/style.css")
+    );
+    assert_eq!(type_dir("/fonts/inter.woff2"), "fonts");
+    assert_eq!(type_dir("/intro.mp4"), "media");
+    assert_eq!(type_dir("/logo.svg"), "images");
+}
+
+#[test]
+fn refuses_exports_over_quota() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>"),
+        ("/app.js", &[b'x'; 4096]),
+        ("/app.css", b"body{}"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("out");
+
+    let mut options = ExportOptions::new(&output);
+    options.max_files = Some(2);
+    let error = table.export(&options).unwrap_err();
+    assert!(matches!(error, tauri_dumper::Error::QuotaExceeded(_)));
+    assert_eq!(
+        error.to_string(),
+        "export exceeds its quota: 3 assets, more than the limit of 2"
+    );
+    assert!(!output.exists());
+    assert!(table.export_tar(Vec::new(), &options).is_err());
+
+    // Quotas count the assets the filters select.
+    let mut options = options.exclude_globs(&["*.css".to_string()]).unwrap();
+    options.max_total_size = Some(4096);
+    assert!(matches!(
+        options.check_quota(&table),
+        Err(tauri_dumper::Error::QuotaExceeded(_))
+    ));
+    options.max_total_size = Some(8192);
+    options.check_quota(&table).unwrap();
+
+    options.dry_run = true;
+    options.max_files = Some(1);
+    table.export(&options).unwrap();
+}
+
+#[test]
+fn enforces_byte_quota_on_quick_verified_scans() {
+    // Past the quick verification limit, so the scan leaves its size
+    // unknown and only decoding it shows the quota is exceeded.
+    let large = vec![b'x'; tauri_dumper::codec::QUICK_VERIFY_LIMIT * 2];
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>"),
+        ("/app.js", &large),
+    ]]);
+    let table = Dumper::builder()
+        .quick_verify(true)
+        .build_from_bytes(&binary)
+        .unwrap()
+        .scan()
+        .unwrap();
+    assert_eq!(table.find("/app.js").unwrap().decompressed_size(), 0);
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path().join("out"));
+    options.max_total_size = Some(4096);
+    options.keep_going = true;
+    options.check_quota(&table).unwrap();
+    assert!(matches!(
+        table.export(&options),
+        Err(tauri_dumper::Error::QuotaExceeded(_))
+    ));
+    assert!(matches!(
+        table.export_tar(Vec::new(), &options),
+        Err(tauri_dumper::Error::QuotaExceeded(_))
+    ));
+    assert!(matches!(
+        table.export_to(&mut MemorySink::new(), &options),
+        Err(tauri_dumper::Error::QuotaExceeded(_))
+    ));
+
+    options.max_total_size = Some(large.len() as u64 + 64);
+    table.export(&options).unwrap();
+}
+
+#[test]
+fn exports_assets_into_sinks() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let options = ExportOptions::new("unused")
+        .strip_prefix("/_app/immutable")
+        .exclude_globs(&["**/*.js".to_string()])
+        .unwrap();
+    let mut memory = MemorySink::new();
+    let summary = table.export_to(&mut memory, &options).unwrap();
+    assert_eq!(summary.exported, 2);
+    assert_eq!(summary.skipped_filter, 1);
+    let files = memory.into_files();
+    assert_eq!(files["/index.html"], b"<!DOCTYPE html><html></html>");
+    assert_eq!(files["/assets/style.css"], b"body{color:#111}");
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut directory = FsSink::new(temp.path());
+    let summary = table.export_to(&mut directory, &options).unwrap();
+    assert_eq!(summary.records[0].path, temp.path().join("index.html"));
+    assert_eq!(
+        fs::read(temp.path().join("assets/style.css")).unwrap(),
+        b"body{color:#111}"
+    );
+
+    let mut options = ExportOptions::new(temp.path());
+    options.format = ExportFormat::Zip;
+    options.split_tables = true;
+    table.export(&options).unwrap();
+    let mut archive =
+        zip::ZipArchive::new(fs::File::open(temp.path().join("assets.zip")).unwrap()).unwrap();
+    let mut data = String::new();
+    std::io::Read::read_to_string(
+        &mut archive
+            .by_name("table-0/_app/immutable/assets/style.css")
+            .unwrap(),
+        &mut data,
+    )
+    .unwrap();
+    assert_eq!(data, "body{color:#111}");
+}
+
+#[test]
+fn streams_assets_as_tar() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let options = ExportOptions::new(temp.path());
+    let mut stream = Vec::new();
+    let summary = table.export_tar(&mut stream, &options).unwrap();
+    assert_eq!(summary.exported, table.len());
+    assert_eq!(fs::read_dir(temp.path()).unwrap().count(), 0);
+
+    let mut files = std::collections::BTreeMap::new();
+    for entry in tar::Archive::new(stream.as_slice()).entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        std::io::Read::read_to_end(&mut entry, &mut data).unwrap();
+        files.insert(path, data);
+    }
+    assert_eq!(files["index.html"], b"<!DOCTYPE html><html></html>");
+    assert_eq!(
+        files["_app/immutable/assets/style.css"],
+        b"body{color:#111}"
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&files["tauri-dumper.manifest.json"]).unwrap();
+    assert_eq!(manifest["assets"].as_array().unwrap().len(), table.len());
+}
+
+#[test]
+fn exports_static_site_with_server_configs() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.format = ExportFormat::Site;
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.exported, 3);
+
+    assert!(temp.path().join("public/index.html").is_file());
+    assert!(temp
+        .path()
+        .join("public/_app/immutable/chunks/app.js")
+        .is_file());
+    let nginx = fs::read_to_string(temp.path().join("nginx.conf")).unwrap();
+    assert!(nginx.contains("text/javascript js;"));
+    assert!(nginx.contains("try_files $uri $uri/ /index.html;"));
+    let caddy = fs::read_to_string(temp.path().join("Caddyfile")).unwrap();
+    assert!(caddy.contains("try_files {path} /index.html"));
+    let serve: serde_json::Value =
+        serde_json::from_slice(&fs::read(temp.path().join("serve.json")).unwrap()).unwrap();
+    assert_eq!(serve["public"], "public");
+    assert_eq!(serve["rewrites"][0]["destination"], "/index.html");
+}
+
+#[test]
+fn gathers_license_files_from_assets() {
+    let notice = b"/*! @license MIT */\n/** Apache License, Version 2.0 */";
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html>"),
+        ("/assets/main.js.LICENSE.txt", notice),
+        (
+            "/3rdpartylicenses.txt",
+            b"Redistribution and use in source and binary forms",
+        ),
+        ("/license.js", b"export const license = 'MIT License';"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let files = licenses::from_assets(&table).unwrap();
+    assert_eq!(
+        files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>(),
+        ["/assets/main.js.LICENSE.txt", "/3rdpartylicenses.txt"]
+    );
+    assert!(files.iter().all(|file| file.origin == LicenseOrigin::Asset));
+
+    let temp = tempfile::tempdir().unwrap();
+    let summary = licenses::write(&files, temp.path()).unwrap();
+    assert_eq!(
+        summary
+            .licenses
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["Apache-2.0", "BSD-2-Clause", "MIT"]
+    );
+    assert_eq!(
+        fs::read(temp.path().join("assets/assets/main.js.LICENSE.txt")).unwrap(),
+        notice
+    );
+    assert!(temp.path().join(licenses::SUMMARY_FILE_NAME).is_file());
+    assert!(licenses::is_license_file("LICENSE-APACHE"));
+    assert!(!licenses::is_license_file("/_app/License.svelte"));
+}
+
+#[test]
+fn maps_routes_to_chunks() {
+    let html = br#"<!doctype html><html><head>
+        <script type="module" crossorigin src="/assets/index-4f2a.js"></script>
+        <link rel="modulepreload" crossorigin href="/assets/vendor-9c1d.js">
+        <link rel="stylesheet" href="./assets/index-77aa.css">
+        </head><body><div id="app"></div></body></html>"#;
+    let bundle = br#"const r=[{path:"/",name:"home",component:Home},
+        {path:"/users/:id",component:()=>__vitePreload(()=>import("./User-1b2c.js"),__vite__mapDeps([0]))},
+        {path:"/settings",children:[{path:"profile",component:()=>import("./Profile-3d4e.js")}]}];
+        fs.readFile({path:"config.json",encoding:"utf8"});"#;
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", html),
+        ("/assets/index-4f2a.js", bundle),
+        (
+            "/_next/static/chunks/pages/blog/[slug]-5f3c9a1e2b7d.js",
+            b"export{}",
+        ),
+    ]]);
+    let image = BinaryImage::from_bytes(binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let map = routes::map_routes(&table).unwrap();
+    let entry = &map.entry_points[0];
+    assert_eq!(entry.route, "/");
+    assert_eq!(entry.scripts, ["/assets/index-4f2a.js"]);
+    assert_eq!(entry.preloads, ["/assets/vendor-9c1d.js"]);
+    assert_eq!(entry.stylesheets, ["/assets/index-77aa.css"]);
+    let routes = map
+        .routes
+        .iter()
+        .map(|route| (route.path.as_str(), route.chunks.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        routes,
+        [
+            ("/", vec![]),
+            ("/users/:id", vec!["/assets/User-1b2c.js".to_string()]),
+            ("/settings", vec![]),
+            ("profile", vec!["/assets/Profile-3d4e.js".to_string()]),
+            (
+                "/blog/[slug]",
+                vec!["/_next/static/chunks/pages/blog/[slug]-5f3c9a1e2b7d.js".to_string()]
+            ),
+        ]
+    );
+
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join(ROUTES_FILE_NAME);
+    map.write(&path).unwrap();
+    let written: routes::RouteMap = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+    assert_eq!(written, map);
+}
+
+#[test]
+fn reconstructs_service_worker_precaches() {
+    let register = br#"if("serviceWorker"in navigator)window.addEventListener("load",()=>{navigator.serviceWorker.register("/sw.js",{scope:"/"})})"#;
+    let workbox_sw = br#"define(["./workbox-5a6b"],(function(e){"use strict";self.skipWaiting(),e.clientsClaim(),e.precacheAndRoute([{url:"assets/index-4f2a.js",revision:null},{url:"index.html",revision:"0c1e4b2a"},{"revision":null,"url":"assets/gone-9f8e.css"},{url:"https://fonts.example.com/inter.woff2",revision:null}],{})}));"#;
+    let runtime =
+        br#"define(["exports"],(function(t){self.addEventListener("install",(e=>{}))}));"#;
+    let legacy = br#"importScripts("precache-manifest.77aa.js","https://storage.googleapis.com/workbox-cdn/releases/4.3.1/workbox-sw.js");
+        workbox.precaching.precacheAndRoute(self.__precacheManifest,{});"#;
+    let manifest = br#"self.__precacheManifest = (self.__precacheManifest || []).concat([
+        {"revision": "3d4e", "url": "./"}, {"revision": "5f6a", "url": "./app.js"}]);"#;
+    let plain = br#"self.addEventListener('install',e=>e.waitUntil(caches.open("v1").then(c=>c.addAll(["./","./offline.html"]))))"#;
+    let assets: &[(&str, &[u8])] = &[
+        ("/index.html", b"<script src=/registerSW.js></script>"),
+        ("/registerSW.js", register),
+        ("/sw.js", workbox_sw),
+        ("/workbox-5a6b.js", runtime),
+        ("/assets/index-4f2a.js", b"export{}"),
+        ("/legacy/service-worker.js", legacy),
+        ("/legacy/precache-manifest.77aa.js", manifest),
+        ("/legacy/index.html", b"<!doctype html>"),
+        ("/legacy/app.js", b"export{}"),
+        ("/plain/worker.js", plain),
+        ("/plain/index.html", b"<!doctype html>"),
+    ];
+
+    let workers = find_service_workers_in(assets.iter().map(|(name, data)| (*name, *data)));
+    let names = workers
+        .iter()
+        .map(|worker| worker.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["/legacy/service-worker.js", "/plain/worker.js", "/sw.js"]
+    );
+
+    let sw = &workers[2];
+    assert_eq!(sw.registered_by, ["/registerSW.js"]);
+    assert!(sw.workbox);
+    assert_eq!(sw.imports, ["/workbox-5a6b.js"]);
+    let entries = sw
+        .precache
+        .iter()
+        .map(|entry| {
+            (
+                entry.url.as_str(),
+                entry.revision.as_deref(),
+                entry.path.as_deref(),
+                entry.embedded,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            (
+                "assets/index-4f2a.js",
+                None,
+                Some("/assets/index-4f2a.js"),
+                true
+            ),
+            ("index.html", Some("0c1e4b2a"), Some("/index.html"), true),
+            (
+                "assets/gone-9f8e.css",
+                None,
+                Some("/assets/gone-9f8e.css"),
+                false
+            ),
+            ("https://fonts.example.com/inter.woff2", None, None, false),
+        ]
+    );
+    let missing = sw
+        .missing()
+        .map(|entry| entry.url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(missing, ["assets/gone-9f8e.css"]);
+
+    let legacy = &workers[0];
+    assert!(legacy.workbox);
+    assert_eq!(legacy.imports, ["/legacy/precache-manifest.77aa.js"]);
+    let paths = |worker: &tauri_dumper::service_worker::ServiceWorker| {
+        worker
+            .precache
+            .iter()
+            .map(|entry| entry.path.clone().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(paths(legacy), ["/legacy/index.html", "/legacy/app.js"]);
+    assert_eq!(legacy.precache[1].revision.as_deref(), Some("5f6a"));
+
+    let plain = &workers[1];
+    assert!(!plain.workbox);
+    assert_eq!(paths(plain), ["/plain/index.html", "/plain/offline.html"]);
+    assert_eq!(plain.missing().count(), 1);
+}
+
+#[test]
+fn reports_locale_coverage() {
+    let po_de = br#"msgid ""
+msgstr "Content-Type: text/plain; charset=UTF-8\n"
+
+msgid "Open"
+msgstr "Oeffnen"
+
+#, fuzzy
+msgid "Save"
+msgstr "Sichern"
+
+msgid "Quit"
+msgstr ""
+"Beenden"
+"#;
+    let po_fr = br#"msgid "Open"
+msgstr "Ouvrir"
+msgid "Save"
+msgstr ""
+"#;
+    let assets: &[(&str, &[u8])] = &[
+        (
+            "/locales/en.json",
+            br#"{"menu":{"open":"Open","save":"Save"},"title":"App"}"#,
+        ),
+        (
+            "/locales/fr.json",
+            br#"{"menu":{"open":"Ouvrir","save":""},"extra":"Plus"}"#,
+        ),
+        ("/locales/pt-BR.json", br#"{"menu":{"open":"Abrir"}}"#),
+        ("/po/de.po", po_de),
+        ("/po/fr.po", po_fr),
+        ("/assets/en.json", br#"{"a":"b"}"#),
+        ("/assets/index-4f2a.js", b"export{}"),
+    ];
+
+    let report = i18n::analyze_in(assets.iter().map(|(name, data)| (*name, *data)));
+    let bundles = report
+        .bundles
+        .iter()
+        .map(|bundle| (bundle.pattern.as_str(), bundle.format, bundle.keys))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bundles,
+        [
+            ("/locales/{locale}.json", LocaleFormat::Json, 4),
+            ("/po/{locale}.po", LocaleFormat::Po, 3),
+        ]
+    );
+    let files = report.bundles[0]
+        .files
+        .iter()
+        .map(|file| (file.locale.as_str(), file.translated, file.missing.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        [
+            ("en", 3, vec!["extra".to_string()]),
+            ("fr", 2, vec!["menu.save".to_string(), "title".to_string()]),
+            (
+                "pt-BR",
+                1,
+                vec![
+                    "extra".to_string(),
+                    "menu.save".to_string(),
+                    "title".to_string()
+                ]
+            ),
+        ]
+    );
+    let po = &report.bundles[1].files;
+    assert_eq!((po[0].locale.as_str(), po[0].translated), ("de", 2));
+    assert_eq!(po[0].missing, ["Save"]);
+    assert_eq!(po[1].missing, ["Quit", "Save"]);
+
+    let coverage = report
+        .locales
+        .iter()
+        .map(|coverage| {
+            (
+                coverage.locale.as_str(),
+                coverage.translated,
+                coverage.keys,
+                coverage.percent(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        coverage,
+        [
+            ("de", 2, 7, 28),
+            ("en", 3, 7, 42),
+            ("fr", 3, 7, 42),
+            ("pt-BR", 1, 7, 14),
+        ]
+    );
+    assert_eq!(report.locales[1].missing_bundles, ["/po/{locale}.po"]);
+    assert!(report.locales[2].missing_bundles.is_empty());
+}
+
+fn duplicated_locales() -> tauri_dumper::AssetTable {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        (
+            "/locales/en/fonts.css",
+            b"@font-face { font-family: Inter }",
+        ),
+        (
+            "/locales/fr/fonts.css",
+            b"@font-face { font-family: Inter }",
+        ),
+        ("/index.html", b"<html>"),
+    ]]))
+    .unwrap();
+    AssetScanner::scan(&image).unwrap()
+}
+
+#[test]
+fn hard_links_duplicated_content() {
+    let table = duplicated_locales();
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.dedupe = Dedupe::Hardlink;
+    // Exporting twice must not write through the links of the first export.
+    table.export(&options).unwrap();
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.exported, 3);
+    assert_eq!(summary.deduplicated, 1);
+
+    let en = temp.path().join("locales/en/fonts.css");
+    let fr = temp.path().join("locales/fr/fonts.css");
+    assert_eq!(fs::read(&fr).unwrap(), fs::read(&en).unwrap());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(
+            fs::metadata(&en).unwrap().ino(),
+            fs::metadata(&fr).unwrap().ino()
+        );
+        assert_eq!(fs::metadata(&en).unwrap().nlink(), 2);
+    }
+}
+
+#[test]
+fn stores_distinct_content_once() {
+    let table = duplicated_locales();
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.dedupe = Dedupe::Store;
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.exported, 3);
+    assert_eq!(summary.deduplicated, 1);
+    assert_eq!(
+        fs::read_dir(temp.path().join(STORE_DIR)).unwrap().count(),
+        2
+    );
+
+    let index: std::collections::BTreeMap<String, String> =
+        serde_json::from_slice(&fs::read(temp.path().join(STORE_INDEX_FILE_NAME)).unwrap())
+            .unwrap();
+    assert_eq!(index.len(), 3);
+    assert_eq!(
+        index["/locales/en/fonts.css"],
+        index["/locales/fr/fonts.css"]
+    );
+    assert_eq!(
+        fs::read(temp.path().join(STORE_DIR).join(&index["/index.html"])).unwrap(),
+        b"<html>"
+    );
+    assert!(!temp.path().join("index.html").exists());
+}
+
+#[test]
+fn skips_assets_matched_by_ignore_file() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html>" as &[u8]),
+        ("/media/intro.mp4", b"video"),
+        ("/media/poster.png", b"png"),
+        ("/vendor/big.js", b"vendor"),
+        ("/vendor/keep.js", b"keep"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let ignore = temp.path().join(".dumperignore");
+    fs::write(
+        &ignore,
+        "# heavy files\n*.mp4\n/vendor/\n!/vendor/keep.js\n",
+    )
+    .unwrap();
+
+    let output = temp.path().join("out");
+    let summary = table
+        .export(&ExportOptions::new(&output).ignore_file(&ignore).unwrap())
+        .unwrap();
+    assert_eq!(summary.skipped_filter, 2);
+    assert!(output.join("media/poster.png").is_file());
+    assert!(!output.join("media/intro.mp4").exists());
+    assert!(!output.join("vendor/big.js").exists());
+    assert!(output.join("vendor/keep.js").is_file());
+}
+
+#[test]
+fn filters_assets_by_decompressed_size() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/small.txt", b"tiny" as &[u8]),
+        ("/medium.txt", &[b'm'; 100]),
+        ("/large.bin", &[b'l'; 5000]),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.write_manifest = false;
+    options.min_size = Some(100);
+    options.max_size = Some(1000);
+
+    let summary = table.export(&options).unwrap();
+    assert_eq!((summary.exported, summary.skipped_filter), (1, 2));
+    assert!(temp.path().join("medium.txt").is_file());
+    assert!(!temp.path().join("large.bin").exists());
+}
+
+#[test]
+fn filters_assets_by_type() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html>" as &[u8]),
+        ("/assets/app.js", b"export{}"),
+        ("/assets/app.css", b"body{}"),
+        ("/assets/logo.png", b"\x89PNG"),
+        ("/assets/font.woff2", b"wOF2"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path())
+        .only_types(&["js".to_string(), "IMAGE".to_string()])
+        .unwrap();
+    options.write_manifest = false;
+
+    let summary = table.export(&options).unwrap();
+    assert_eq!((summary.exported, summary.skipped_filter), (2, 3));
+    assert!(temp.path().join("assets/app.js").is_file());
+    assert!(temp.path().join("assets/logo.png").is_file());
+    assert!(!temp.path().join("index.html").exists());
+
+    let error = ExportOptions::new(temp.path())
+        .only_types(&["javascript".to_string()])
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("unknown asset type `javascript`"));
+}
+
+#[test]
+fn rewrites_asset_paths_before_writing() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/dist/index.html", b"<html>" as &[u8]),
+        ("/dist/assets/app.js", b"export{}"),
+        ("/distant.txt", b"far"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let options = ExportOptions::new(temp.path())
+        .map_paths(&["/dist/assets/=/static/js/".to_string()])
+        .unwrap()
+        .strip_prefix("/dist/");
+
+    let summary = table.export(&options).unwrap();
+    assert_eq!(summary.exported, 3);
+    assert_eq!(fs::read(temp.path().join("index.html")).unwrap(), b"<html>");
+    assert!(temp.path().join("static/js/app.js").is_file());
+    assert!(temp.path().join("distant.txt").is_file());
+    assert!(!temp.path().join("dist").exists());
+    assert_eq!(summary.records[1].name, "/dist/assets/app.js");
+    assert_eq!(
+        summary.records[1].path,
+        temp.path().join("static/js/app.js")
+    );
+
+    let escaping = ExportOptions::new(temp.path().join("escaping"))
+        .map_paths(&["/dist/=/../".to_string()])
+        .unwrap();
+    assert!(table.export(&escaping).is_err());
+    let error = ExportOptions::new(temp.path())
+        .map_paths(&["/dist".to_string()])
+        .unwrap_err();
+    assert!(error.to_string().contains("expected FROM=TO"));
+}
+
+#[test]
+fn decompresses_on_several_threads_in_table_order() {
+    let assets = (0..9)
+        .map(|index| {
+            (
+                format!("/chunk-{index}.js"),
+                vec![b'a' + index as u8; 100 * index],
+            )
+        })
+        .collect::<Vec<_>>();
+    let assets = assets
+        .iter()
+        .map(|(name, data)| (name.as_str(), data.as_slice()))
+        .collect::<Vec<_>>();
+    let table = AssetScanner::scan(
+        &BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&assets])).unwrap(),
+    )
+    .unwrap();
+    let aliased = AssetScanner::scan(
+        &BinaryImage::from_bytes(common::desktop_elf_with_aliases(b"shared")).unwrap(),
+    )
+    .unwrap();
+
+    for (table, jobs) in [(&table, 1), (&table, 4), (&aliased, 1), (&aliased, 4)] {
+        let temp = tempfile::tempdir().unwrap();
+        let mut options = ExportOptions::new(temp.path());
+        options.write_manifest = false;
+        options.jobs = jobs;
+        let summary = table.export(&options).unwrap();
+        let names = summary
+            .records
+            .iter()
+            .map(|record| record.name.as_str())
+            .collect::<Vec<_>>();
+        let expected = table
+            .assets()
+            .iter()
+            .map(|asset| asset.name())
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        for asset in table.assets() {
+            let path = temp.path().join(asset.name().trim_start_matches('/'));
+            assert_eq!(
+                fs::read(path).unwrap(),
+                tauri_dumper::extract::decompress_asset(asset).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn writes_files_on_a_pool_of_threads() {
+    let assets = (0..40)
+        .map(|index| {
+            (
+                format!("/locales/{}/messages-{index}.json", index % 5),
+                format!("{{\"index\":{index}}}").into_bytes(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let assets = assets
+        .iter()
+        .map(|(name, data)| (name.as_str(), data.as_slice()))
+        .collect::<Vec<_>>();
+    let table = AssetScanner::scan(
+        &BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&assets])).unwrap(),
+    )
+    .unwrap();
+
+    for writers in [1, 4] {
+        let temp = tempfile::tempdir().unwrap();
+        let mut options = ExportOptions::new(temp.path());
+        options.write_manifest = false;
+        options.writers = writers;
+        let summary = table.export(&options).unwrap();
+        assert_eq!(summary.exported, assets.len());
+        let names = summary
+            .records
+            .iter()
+            .map(|record| record.name.as_str())
+            .collect::<Vec<_>>();
+        let expected = assets.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        for (name, data) in &assets {
+            let path = temp.path().join(name.trim_start_matches('/'));
+            assert_eq!(fs::read(path).unwrap(), *data);
+        }
+    }
+}
+
+#[test]
+fn writes_large_assets_through_a_memory_map() {
+    for binary in [
+        common::desktop_elf(),
+        common::desktop_elf_with_aliases(b"shared"),
+    ] {
+        let table = AssetScanner::scan(&BinaryImage::from_bytes(binary).unwrap()).unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let mut options = ExportOptions::new(temp.path());
+        options.write_manifest = false;
+        options.map_threshold = 1;
+        let summary = table.export(&options).unwrap();
+
+        assert_eq!(summary.records.len(), table.len());
+        for asset in table.assets() {
+            let path = temp.path().join(asset.name().trim_start_matches('/'));
+            assert_eq!(
+                fs::read(path).unwrap(),
+                tauri_dumper::extract::decompress_asset(asset).unwrap()
+            );
+        }
+    }
+}
+
+#[test]
+fn keeps_going_past_assets_that_fail_to_write() {
+    // `/data` is written as a file, so `/data/inner.txt` has no directory.
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_tables(&[&[
+        ("/data", b"file" as &[u8]),
+        ("/data/inner.txt", b"inner"),
+        ("/index.html", b"<html>"),
+    ]]))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    for writers in [1, 4] {
+        let temp = tempfile::tempdir().unwrap();
+        let mut options = ExportOptions::new(temp.path());
+        options.writers = writers;
+        assert!(table.export(&options).is_err());
+
+        options.keep_going = true;
+        let summary = table.export(&options).unwrap();
+        assert_eq!((summary.exported, summary.failed), (2, 1));
+        let failed = summary
+            .records
+            .iter()
+            .find(|record| record.status == ExportStatus::Failed)
+            .unwrap();
+        assert_eq!(failed.name, "/data/inner.txt");
+        assert!(failed.error.is_some());
+        assert!(temp.path().join("index.html").is_file());
+    }
+}
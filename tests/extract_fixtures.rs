@@ -1,8 +1,27 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
 mod common;
 
 use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
 
-use tauri_dumper::{AssetScanner, BinaryImage, Dumper};
+use tauri_dumper::asset::{
+    normalize_asset_path, AssetTableSummary, HeaderLayout, ASSET_HEADER_SIZE,
+};
+use tauri_dumper::audit;
+use tauri_dumper::checkpoint::CheckpointOptions;
+use tauri_dumper::codec::{GzipCodec, ZstdCodec};
+use tauri_dumper::fingerprint::{tauri_version, TauriVersion};
+use tauri_dumper::integrity::{self, IntegrityKind};
+use tauri_dumper::roundtrip::FileStatus;
+use tauri_dumper::scan_cache::ScanCache;
+use tauri_dumper::scripts::{find_injected_scripts, ScriptKind};
+use tauri_dumper::timings::{Counter, Timings};
+use tauri_dumper::{
+    AssetCodec, AssetEncoding, AssetInfo, AssetScanner, BinaryImage, CodecRegistry, Dumper,
+    ExportOptions, ScanOptions, ScanProfile, Strictness,
+};
 
 #[test]
 fn extracts_real_world_fixtures_when_downloaded() {
@@ -67,6 +86,33 @@ fn extracts_android_elf_with_rela_pointer_addends() {
     assert_eq!(table.assets()[0].name(), "/index.html");
 }
 
+#[test]
+fn describes_asset_offsets_sizes_and_codec() {
+    let binary = common::desktop_elf();
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let asset = &table.assets()[0];
+    let info = asset.info();
+
+    assert_eq!(info.name, "/index.html");
+    assert_eq!(info.name_len, 11);
+    assert_eq!(
+        &binary[info.name_offset..info.name_offset + info.name_len],
+        b"/index.html"
+    );
+    assert_eq!(
+        &binary[info.data_offset..info.data_offset + info.compressed_size],
+        asset.compressed_data()
+    );
+    assert_eq!(info.header_offset, asset.location().header_offset);
+    assert_eq!(
+        info.decompressed_size,
+        b"<!DOCTYPE html><html></html>".len()
+    );
+    assert_eq!(info.encoding, AssetEncoding::Brotli);
+    assert_eq!(info.alias_of, None);
+}
+
 #[test]
 fn extracts_android_elf_from_later_data_rel_ro_section() {
     let image = BinaryImage::from_bytes(common::android_elf_with_later_asset_section()).unwrap();
@@ -83,6 +129,28 @@ fn extracts_desktop_elf_with_direct_pointers() {
     assert_eq!(table.assets()[0].name(), "/index.html");
 }
 
+#[test]
+fn serializes_scan_results() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+
+    let asset = &table.assets()[0];
+    let info: AssetInfo = serde_json::from_value(serde_json::to_value(asset).unwrap()).unwrap();
+    assert_eq!(info, asset.info());
+
+    let summary: AssetTableSummary =
+        serde_json::from_str(&serde_json::to_string(&table).unwrap()).unwrap();
+    assert_eq!(summary.asset_count, 3);
+    assert_eq!(summary.binary.sha256, table.metadata().sha256);
+    assert_eq!(
+        summary.assets[2].location.data_offset,
+        table.assets()[2].location().data_offset
+    );
+
+    let profile: ScanProfile = serde_json::from_str("\"rust-embed\"").unwrap();
+    assert_eq!(profile, ScanProfile::RustEmbed);
+}
+
 #[test]
 fn rejects_invalid_binaries() {
     assert!(BinaryImage::from_bytes(b"not a valid binary").is_err());
@@ -90,3 +158,961 @@ fn rejects_invalid_binaries() {
     fs::write(temp.path(), []).unwrap();
     assert!(BinaryImage::open(temp.path()).is_err());
 }
+
+#[test]
+fn extracts_pe_with_non_default_image_base() {
+    for image_base in [0x1_4000_0000, 0x7ff6_1234_0000] {
+        let binary = common::pe_with_assets(
+            image_base,
+            &[("/index.html", b"<!DOCTYPE html><html></html>" as &[u8])],
+        );
+        let image = BinaryImage::from_bytes(binary).unwrap();
+        let table = AssetScanner::scan(&image).unwrap();
+        assert_eq!(table.len(), 1, "image base {image_base:#x}");
+        assert_eq!(table.assets()[0].name(), "/index.html");
+    }
+}
+
+#[test]
+fn extracts_macho_headers_split_across_data_segments() {
+    let binary = common::macho_with_assets(
+        &["__DATA_CONST", "__DATA"],
+        &[
+            ("/index.html", b"<!DOCTYPE html><html></html>" as &[u8]),
+            ("/app.js", b"console.log('app');" as &[u8]),
+            ("/style.css", b"body{color:#111}" as &[u8]),
+        ],
+    );
+    let image = BinaryImage::from_bytes(binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let mut names = table
+        .assets()
+        .iter()
+        .map(|asset| asset.name())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["/app.js", "/index.html", "/style.css"]);
+}
+
+#[test]
+fn extracts_macho_headers_from_legacy_data_segment() {
+    let binary = common::macho_with_assets(
+        &["__DATA"],
+        &[("/index.html", b"<!DOCTYPE html><html></html>" as &[u8])],
+    );
+    let image = BinaryImage::from_bytes(binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn extracts_macho_headers_from_arm64e_auth_segments() {
+    for segment in ["__AUTH_CONST", "__AUTH"] {
+        let binary = common::macho_with_assets(
+            &[segment],
+            &[("/index.html", b"<!DOCTYPE html><html></html>" as &[u8])],
+        );
+        let image = BinaryImage::from_bytes(binary).unwrap();
+        let table = AssetScanner::scan(&image).unwrap();
+        assert_eq!(table.len(), 1, "{segment}");
+    }
+
+    let binary = common::arm64e_macho_with_assets(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>" as &[u8]),
+        ("/app.js", b"console.log('app');" as &[u8]),
+    ]);
+    let image = BinaryImage::from_bytes(binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let names = table
+        .assets()
+        .iter()
+        .map(|asset| asset.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/app.js"]);
+    assert_eq!(
+        tauri_dumper::extract::decompress_asset(&table.assets()[1]).unwrap(),
+        b"console.log('app');"
+    );
+}
+
+#[test]
+fn extracts_android_elf_with_relocations_only_in_dynamic_segment() {
+    let image = BinaryImage::from_bytes(common::android_elf_with_unlisted_rela()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 1);
+    assert_eq!(table.assets()[0].name(), "/index.html");
+}
+
+#[test]
+fn attributes_assets_to_separate_tables() {
+    let binary = common::desktop_elf_with_tables(&[
+        &[
+            ("/index.html", b"<!DOCTYPE html><html></html>" as &[u8]),
+            ("/app.js", b"console.log('app');" as &[u8]),
+        ],
+        &[("/plugin/index.html", b"<p>plugin</p>" as &[u8])],
+    ]);
+    let image = BinaryImage::from_bytes(binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.table_count(), 2);
+    assert_eq!(table.find("/app.js").unwrap().table_index(), 0);
+    assert_eq!(table.find("/plugin/index.html").unwrap().table_index(), 1);
+
+    let temp = tempfile::tempdir().unwrap();
+    let mut options = ExportOptions::new(temp.path());
+    options.split_tables = true;
+    table.export(&options).unwrap();
+    assert!(temp.path().join("table-0/index.html").is_file());
+    assert!(temp.path().join("table-1/plugin/index.html").is_file());
+}
+
+#[test]
+fn does_not_alias_blobs_of_different_sizes_at_one_offset() {
+    let image =
+        BinaryImage::from_bytes(common::desktop_elf_sharing_data_offset(b"shared", 4)).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 2);
+    let longer = table.find("/b.txt").unwrap();
+    assert_eq!(longer.alias_of(), None);
+    assert_eq!(
+        longer.compressed_size(),
+        table.find("/a.txt").unwrap().compressed_size() + 4
+    );
+}
+
+#[test]
+fn records_aliases_sharing_compressed_data() {
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_aliases(b"shared")).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 2);
+    assert_eq!(table.find("/a.txt").unwrap().alias_of(), None);
+    assert_eq!(table.find("/b.txt").unwrap().alias_of(), Some("/a.txt"));
+
+    let temp = tempfile::tempdir().unwrap();
+    table.export(&ExportOptions::new(temp.path())).unwrap();
+    assert_eq!(fs::read(temp.path().join("a.txt")).unwrap(), b"shared");
+    assert_eq!(fs::read(temp.path().join("b.txt")).unwrap(), b"shared");
+
+    let manifest =
+        tauri_dumper::manifest::Manifest::read(temp.path().join("tauri-dumper.manifest.json"))
+            .unwrap();
+    assert_eq!(manifest.assets[1].alias_of.as_deref(), Some("/a.txt"));
+}
+
+#[test]
+fn extracts_rust_embed_entries() {
+    let binary = common::rust_embed_elf(&[
+        ("assets/app.js", b"console.log('app');"),
+        ("index.html", b"<!DOCTYPE html><html></html>"),
+        ("LICENSE", b"MIT"),
+    ]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    assert!(AssetScanner::scan(&image).unwrap().is_empty());
+
+    let table = AssetScanner::scan_with_profile(&image, ScanProfile::RustEmbed).unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/assets/app.js", "/index.html", "/LICENSE"]);
+    let asset = table.find("/index.html").unwrap();
+    assert_eq!(asset.encoding(), AssetEncoding::Raw);
+    assert_eq!(
+        tauri_dumper::extract::decompress_asset(asset).unwrap(),
+        b"<!DOCTYPE html><html></html>"
+    );
+}
+
+#[test]
+fn keeps_one_asset_per_duplicate_name() {
+    // The raw copy is less likely a real asset than the compressed one.
+    let compressed = common::brotli_compress(b"<p>compressed</p>");
+    let binary = common::tauri_v1_elf(&[
+        ("/index.html", b"<p>raw</p>"),
+        ("/index.html", &compressed),
+        ("/main.js", b"main()"),
+    ]);
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+    let table = dumper.scan().unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/main.js"]);
+    let kept = table.find("/index.html").unwrap();
+    assert_eq!(kept.encoding(), AssetEncoding::Brotli);
+    assert_eq!(dumper.decompress_asset(kept).unwrap(), b"<p>compressed</p>");
+    let [conflict] = table.conflicts() else {
+        panic!("expected one conflict, got {:?}", table.conflicts());
+    };
+    assert_eq!(conflict.name, "/index.html");
+    assert_eq!(conflict.kept.header_offset, kept.location().header_offset);
+    assert_eq!(conflict.dropped.len(), 1);
+    assert_eq!(conflict.dropped[0].encoding, AssetEncoding::Raw);
+    assert!(conflict.dropped[0].confidence < conflict.kept.confidence);
+
+    let temp = tempfile::tempdir().unwrap();
+    table.export(&ExportOptions::new(temp.path())).unwrap();
+    assert_eq!(
+        fs::read(temp.path().join("index.html")).unwrap(),
+        b"<p>compressed</p>"
+    );
+    let manifest =
+        tauri_dumper::manifest::Manifest::read(temp.path().join("tauri-dumper.manifest.json"))
+            .unwrap();
+    assert_eq!(manifest.assets.len(), 2);
+    assert_eq!(manifest.conflicts, table.conflicts());
+
+    // Equally confident duplicates keep the first; other tables are apart.
+    let binary = common::desktop_elf_with_tables(&[
+        &[("/index.html", b"first"), ("/index.html", b"second")],
+        &[("/index.html", b"plugin")],
+    ]);
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+    let table = dumper.scan().unwrap();
+    assert_eq!(table.len(), 2);
+    assert_eq!(
+        dumper.decompress_asset(&table.assets()[0]).unwrap(),
+        b"first"
+    );
+    assert_eq!(table.assets()[1].table_index(), 1);
+    assert_eq!(table.conflicts().len(), 1);
+}
+
+#[test]
+fn detects_uncompressed_tauri_v1_apps() {
+    let binary = common::tauri_v1_elf(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>"),
+        ("/main.js", b"window.__TAURI_IPC__ = ipc;"),
+    ]);
+    assert_eq!(tauri_version(&binary), Some(TauriVersion::V1));
+    assert_eq!(
+        tauri_version(&common::desktop_elf_with_scripts(&[
+            "window.__TAURI_INTERNALS__"
+        ])),
+        Some(TauriVersion::V2)
+    );
+
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/main.js"]);
+    let asset = table.find("/main.js").unwrap();
+    assert_eq!(asset.encoding(), AssetEncoding::Raw);
+    assert_eq!(
+        tauri_dumper::extract::decompress_asset(asset).unwrap(),
+        b"window.__TAURI_IPC__ = ipc;"
+    );
+}
+
+#[test]
+fn drops_raw_tauri_v1_hits_that_are_not_assets() {
+    let binary = common::tauri_v1_elf(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>"),
+        ("/logo.png", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+        ("/stray.bin", b"\x01\x02\x03\xfe\xff\x00\x10"),
+    ]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/logo.png"]);
+}
+
+#[test]
+fn scans_headers_with_extra_fields() {
+    let binary = common::desktop_elf_with_csp_hashes(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>"),
+        ("/main.js", b"console.log('main');"),
+        ("/style.css", b"body { margin: 0; }"),
+    ]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/main.js", "/style.css"]);
+    // The assets form one table with headers 48 bytes apart.
+    assert!(table.assets().iter().all(|asset| asset.table_index() == 0));
+    let offsets = table
+        .assets()
+        .iter()
+        .map(|asset| asset.location().header_offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets[1] - offsets[0], HeaderLayout::WITH_CSP_HASHES.size);
+}
+
+#[test]
+fn scans_32_bit_asset_headers() {
+    let binary = common::armv7_elf_with_assets(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>"),
+        ("/main.js", b"console.log('main');"),
+    ]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let names = table.assets().iter().map(|a| a.name()).collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/main.js"]);
+    let asset = table.find("/main.js").unwrap();
+    assert_eq!(asset.location().word_size, 4);
+    assert_eq!(
+        asset.location().header_offset - table.assets()[0].location().header_offset,
+        16
+    );
+    assert_eq!(
+        tauri_dumper::extract::decompress_asset(asset).unwrap(),
+        b"console.log('main');"
+    );
+}
+
+#[test]
+fn quick_verify_checks_only_the_start_of_asset_data() {
+    let mut seed = 1u32;
+    let mut words = || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        ["tauri", "asset", "window", "invoke", "plugin", "event"][(seed >> 16) as usize % 6]
+    };
+    let large = (0..80_000).map(|_| words()).collect::<Vec<_>>().join(" ");
+    let html = b"<!DOCTYPE html><html></html>";
+    // The second half of the large asset's stream is missing.
+    let binary = common::desktop_elf_with_encoded_tables(
+        &[&[("/index.html", html), ("/app.js", large.as_bytes())]],
+        |content| {
+            let compressed = common::brotli_compress(content);
+            match content.len() > html.len() {
+                true => compressed[..compressed.len() / 2].to_vec(),
+                false => compressed,
+            }
+        },
+    );
+
+    let full = Dumper::from_bytes(&binary).unwrap().scan().unwrap();
+    assert!(full.find("/app.js").is_none());
+
+    let dumper = Dumper::builder()
+        .quick_verify(true)
+        .build_from_bytes(&binary)
+        .unwrap();
+    let table = dumper.scan().unwrap();
+    assert_eq!(
+        table.find("/index.html").unwrap().decompressed_size(),
+        html.len()
+    );
+    let truncated = table.find("/app.js").unwrap();
+    assert_eq!(truncated.decompressed_size(), 0);
+    assert!(dumper.decompress_asset(truncated).is_err());
+}
+
+/// Stores data XORed with a fixed key.
+struct XorCodec;
+
+impl AssetCodec for XorCodec {
+    fn encoding(&self) -> AssetEncoding {
+        AssetEncoding::Custom
+    }
+
+    fn verify(&self, data: &[u8]) -> tauri_dumper::Result<usize> {
+        match self.decompress(data)?.first() {
+            Some(b'<') => Ok(data.len()),
+            _ => Err(tauri_dumper::Error::InvalidEncoding("XOR".to_string())),
+        }
+    }
+
+    fn decompress(&self, data: &[u8]) -> tauri_dumper::Result<Vec<u8>> {
+        Ok(data.iter().map(|byte| byte ^ 0x5a).collect())
+    }
+
+    fn compress(&self, data: &[u8]) -> tauri_dumper::Result<Vec<u8>> {
+        self.decompress(data)
+    }
+}
+
+#[test]
+fn extracts_assets_with_registered_codecs() {
+    let html = b"<!DOCTYPE html><html></html>";
+    let gzip = common::gzip_desktop_elf(&[("/index.html", html)]);
+    let xor = common::desktop_elf_with_encoded_tables(&[&[("/index.html", html)]], |content| {
+        XorCodec.compress(content).unwrap()
+    });
+    let zstd = common::desktop_elf_with_encoded_tables(&[&[("/index.html", html)]], |content| {
+        ZstdCodec.compress(content).unwrap()
+    });
+    assert!(Dumper::from_bytes(&gzip)
+        .unwrap()
+        .scan()
+        .unwrap()
+        .is_empty());
+
+    let mut codecs = CodecRegistry::new();
+    codecs
+        .register(Box::new(GzipCodec))
+        .register(Box::new(ZstdCodec))
+        .register(Box::new(XorCodec));
+    for (binary, encoding) in [
+        (gzip, AssetEncoding::Gzip),
+        (zstd, AssetEncoding::Zstd),
+        (xor, AssetEncoding::Custom),
+    ] {
+        let dumper = Dumper::from_bytes(&binary)
+            .unwrap()
+            .with_codecs(codecs.clone());
+        let table = dumper.scan().unwrap();
+        assert_eq!(table.len(), 1);
+        let asset = table.find("/index.html").unwrap();
+        assert_eq!(asset.encoding(), encoding);
+        assert_eq!(asset.decompressed_size(), html.len());
+        assert_eq!(dumper.decompress_asset(asset).unwrap(), html);
+    }
+}
+
+#[cfg(feature = "lz4")]
+#[test]
+fn extracts_lz4_frames() {
+    use tauri_dumper::codec::Lz4Codec;
+
+    let html = b"<!DOCTYPE html><html></html>";
+    let binary = common::desktop_elf_with_encoded_tables(&[&[("/index.html", html)]], |content| {
+        Lz4Codec.compress(content).unwrap()
+    });
+    let mut codecs = CodecRegistry::new();
+    codecs.register(Box::new(Lz4Codec));
+    let dumper = Dumper::from_bytes(&binary).unwrap().with_codecs(codecs);
+    let table = dumper.scan().unwrap();
+    let asset = table.find("/index.html").unwrap();
+    assert_eq!(asset.encoding(), AssetEncoding::Lz4);
+    assert_eq!(asset.decompressed_size(), html.len());
+    assert_eq!(dumper.decompress_asset(asset).unwrap(), html);
+    assert!(Lz4Codec.verify(&common::brotli_compress(html)).is_err());
+}
+
+#[test]
+fn verifies_roundtrip_against_dist() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<!DOCTYPE html><html></html>" as &[u8]),
+        ("/assets/app.js", b"console.log('built')"),
+        ("/assets/stale.css", b"body{}"),
+    ]]);
+    let dist = tempfile::tempdir().unwrap();
+    fs::create_dir_all(dist.path().join("assets")).unwrap();
+    fs::write(
+        dist.path().join("index.html"),
+        b"<!DOCTYPE html><html></html>",
+    )
+    .unwrap();
+    fs::write(dist.path().join("assets/app.js"), b"console.log('edited')").unwrap();
+    fs::write(dist.path().join("assets/new.png"), b"png").unwrap();
+
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+    let report = dumper.verify_roundtrip(dist.path()).unwrap();
+    let statuses = report
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file.status))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        [
+            ("assets/app.js", FileStatus::Changed),
+            ("assets/new.png", FileStatus::MissingFromBinary),
+            ("assets/stale.css", FileStatus::MissingFromDist),
+            ("index.html", FileStatus::Match),
+        ]
+    );
+    assert!(!report.is_ok());
+    assert_eq!(report.mismatches().count(), 3);
+
+    fs::write(dist.path().join("assets/app.js"), b"console.log('built')").unwrap();
+    fs::write(dist.path().join("assets/stale.css"), b"body{}").unwrap();
+    fs::remove_file(dist.path().join("assets/new.png")).unwrap();
+    assert!(dumper.verify_roundtrip(dist.path()).unwrap().is_ok());
+}
+
+#[test]
+fn scores_asset_confidence() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>" as &[u8]),
+        ("/blob", b"no extension"),
+    ]]);
+    let table = Dumper::from_bytes(&binary).unwrap().scan().unwrap();
+    assert_eq!(table.find("/index.html").unwrap().confidence(), 100);
+    assert_eq!(table.find("/blob").unwrap().confidence(), 90);
+    assert_eq!(table.find("/blob").unwrap().info().confidence, 90);
+
+    let single = Dumper::from_bytes(&common::desktop_elf())
+        .unwrap()
+        .scan()
+        .unwrap();
+    // A lone header has no neighbours to vouch for it.
+    assert_eq!(single.assets()[0].confidence(), 85);
+
+    let confident = Dumper::builder()
+        .min_confidence(95)
+        .build_from_bytes(&binary)
+        .unwrap()
+        .scan()
+        .unwrap();
+    assert_eq!(confident.len(), 1);
+    assert!(confident.find("/blob").is_none());
+}
+
+#[test]
+fn extracts_assets_in_memory() {
+    let dumper = Dumper::from_bytes(&common::nested_desktop_elf()).unwrap();
+    let files = dumper.extract_all().unwrap();
+    assert_eq!(files.len(), 3);
+    assert_eq!(files["/index.html"], b"<!DOCTYPE html><html></html>");
+    assert_eq!(
+        files["/_app/immutable/chunks/app.js"],
+        b"console.log('app');"
+    );
+
+    let names = dumper
+        .extract_iter()
+        .unwrap()
+        .map(|asset| asset.unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(names.len(), 3);
+    assert!(names.iter().all(|name| files.contains_key(name)));
+}
+
+#[test]
+fn visits_every_candidate_header() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>"),
+        ("mangled.js", b"console.log(1)"),
+        ("/app.css", b"body {}"),
+    ]]);
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+
+    let mut visited = Vec::new();
+    let mut accepted = Vec::new();
+    dumper
+        .scan_with_visitor(|candidate, asset| {
+            assert_eq!(candidate.accepted, asset.is_some());
+            if let Some(asset) = asset {
+                assert_eq!(candidate.name.as_deref(), Some(asset.name()));
+                assert_eq!(asset.location().header_offset, candidate.offset);
+                // Every layout that parses a header is reported.
+                if !accepted.contains(&(candidate.offset, asset.name().to_string())) {
+                    accepted.push((candidate.offset, asset.name().to_string()));
+                }
+            }
+            visited.push(candidate.clone());
+        })
+        .unwrap();
+    let names = accepted
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["/index.html", "/app.css"]);
+    let rejected = visited
+        .iter()
+        .find(|candidate| candidate.name.as_deref() == Some("mangled.js"))
+        .unwrap();
+    assert!(!rejected.accepted);
+    assert!(rejected.rejection.is_some());
+    assert_eq!(
+        visited,
+        AssetScanner::header_candidates(dumper.image(), dumper.options()).unwrap()
+    );
+
+    let rust_embed = Dumper::builder()
+        .profile(ScanProfile::RustEmbed)
+        .build_from_bytes(&binary)
+        .unwrap();
+    assert!(rust_embed.scan_with_visitor(|_, _| {}).is_err());
+}
+
+#[test]
+fn looks_up_single_assets_without_a_full_scan() {
+    let timings = Arc::new(Timings::new());
+    let dumper = Dumper::builder()
+        .options(ScanOptions {
+            timings: Some(Arc::clone(&timings)),
+            ..ScanOptions::default()
+        })
+        .build_from_bytes(&common::nested_desktop_elf())
+        .unwrap();
+    let asset = dumper.get_asset("/index.html").unwrap().unwrap();
+    assert_eq!(
+        dumper.decompress_asset(&asset).unwrap(),
+        b"<!DOCTYPE html><html></html>"
+    );
+    assert_eq!(timings.count(Counter::Validated), 1);
+
+    assert!(dumper.get_asset("/missing.html").unwrap().is_none());
+    assert!(dumper.get_asset("index.html").unwrap().is_none());
+}
+
+#[test]
+fn stops_scans_early_on_request() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>" as &[u8]),
+        ("/app.js", b"console.log(1)"),
+        ("/app.css", b"body {}"),
+        ("/logo.svg", b"<svg/>"),
+    ]]);
+    let scan = |max_assets, stop_after_paths: &[&str]| {
+        let timings = Arc::new(Timings::new());
+        let table = Dumper::builder()
+            .options(ScanOptions {
+                timings: Some(Arc::clone(&timings)),
+                max_assets,
+                stop_after_paths: stop_after_paths
+                    .iter()
+                    .map(|path| path.to_string())
+                    .collect(),
+                ..ScanOptions::default()
+            })
+            .build_from_bytes(&binary)
+            .unwrap()
+            .scan()
+            .unwrap();
+        let names = table
+            .assets()
+            .iter()
+            .map(|asset| asset.name().to_string())
+            .collect::<Vec<_>>();
+        (names, timings.count(Counter::Validated))
+    };
+
+    let (all, validated) = scan(None, &[]);
+    assert_eq!(all.len(), 4);
+    let (first, fewer) = scan(Some(2), &[]);
+    assert_eq!(first, ["/index.html", "/app.js"]);
+    assert!(fewer < validated, "{fewer} < {validated}");
+    let (until, _) = scan(None, &["/app.css", "/index.html"]);
+    assert_eq!(until, ["/index.html", "/app.js", "/app.css"]);
+    let (missing, _) = scan(None, &["/missing.js"]);
+    assert_eq!(missing.len(), 4);
+
+    let builder = Dumper::builder()
+        .max_assets(1)
+        .stop_after_paths(["/app.css"]);
+    assert_eq!(
+        builder
+            .build_from_bytes(&binary)
+            .unwrap()
+            .scan()
+            .unwrap()
+            .len(),
+        1
+    );
+    let zero = Dumper::builder().max_assets(0).build_from_bytes(&binary);
+    assert!(zero.unwrap().scan().is_err());
+}
+
+#[test]
+fn resolves_paths_like_the_tauri_runtime() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>" as &[u8]),
+        ("/about.html", b"<p>about</p>"),
+        ("/docs/index.html", b"<p>docs</p>"),
+        ("/assets/My File.css", b"body{}"),
+        ("/assets/app.js", b"console.log(1)"),
+    ]]);
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+    let names = dumper
+        .assets_with_prefix("/assets/")
+        .unwrap()
+        .iter()
+        .map(|asset| asset.name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["/assets/My File.css", "/assets/app.js"]);
+
+    assert_eq!(
+        normalize_asset_path("assets\\My%20File.css?v=2#top"),
+        "/assets/My File.css"
+    );
+    let table = dumper.scan().unwrap();
+    let resolved = |path: &str| table.resolve(path).map(|asset| asset.name());
+    assert_eq!(resolved("/"), Some("/index.html"));
+    assert_eq!(resolved("/about"), Some("/about.html"));
+    assert_eq!(resolved("/docs/"), Some("/docs/index.html"));
+    assert_eq!(
+        resolved("assets/My%20File.css"),
+        Some("/assets/My File.css")
+    );
+    assert_eq!(resolved("/settings/profile"), Some("/index.html"));
+    assert!(table.find_normalized("/assets/my%20file.CSS").is_some());
+    assert!(table.find_normalized("/settings").is_none());
+    assert_eq!(
+        dumper
+            .resolve_asset("/about?tab=1")
+            .unwrap()
+            .unwrap()
+            .name(),
+        "/about.html"
+    );
+}
+
+#[test]
+fn configures_scans_with_the_builder() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>"),
+        ("/../escape.txt", b"outside"),
+        ("mangled.js", b"console.log(1)"),
+        ("/nul\0.js", b"console.log(2)"),
+    ]]);
+    let names = |strictness| {
+        let table = Dumper::builder()
+            .strictness(strictness)
+            .build_from_bytes(&binary)
+            .unwrap()
+            .scan()
+            .unwrap();
+        table
+            .assets()
+            .iter()
+            .map(|asset| asset.name().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(Strictness::Normal), ["/index.html", "/../escape.txt"]);
+    assert_eq!(names(Strictness::Strict), ["/index.html"]);
+    assert_eq!(
+        names(Strictness::Lenient),
+        ["/index.html", "/../escape.txt", "mangled.js"]
+    );
+
+    // Headers live in .data.rel.ro; .rodata only holds what they point to.
+    let scan_sections = |section: &str| {
+        Dumper::builder()
+            .sections([section])
+            .scan_step(ASSET_HEADER_SIZE)
+            .build_from_bytes(&binary)
+            .unwrap()
+            .scan()
+    };
+    assert_eq!(scan_sections(".data.rel.ro").unwrap().len(), 2);
+    assert!(scan_sections(".rodata").unwrap().is_empty());
+    assert!(scan_sections(".missing").is_err());
+
+    let dumper = Dumper::builder().scan_step(0).build_from_bytes(&binary);
+    assert!(dumper.unwrap().scan().is_err());
+}
+
+#[test]
+fn finds_injected_scripts() {
+    let ipc = "Object.defineProperty(window, '__TAURI_POST_MESSAGE__', { \
+        value: (message) => { const { cmd, callback, error, payload } = message; \
+        window.ipc.postMessage(JSON.stringify({ cmd, callback, error, payload })) } })";
+    let isolation = "window.addEventListener('message', (event) => { \
+        if (event.origin === __TEMPLATE_isolation_origin__) { \
+        window.__TAURI_ISOLATION_HOOK__(event.data).then(post) } }, false)";
+    let binary = common::desktop_elf_with_scripts(&[ipc, "__TAURI_INTERNALS__", isolation]);
+
+    let scripts = find_injected_scripts(&binary);
+    assert_eq!(
+        scripts.iter().map(|script| script.kind).collect::<Vec<_>>(),
+        [ScriptKind::Ipc, ScriptKind::Isolation]
+    );
+    assert_eq!(scripts[0].source, ipc);
+    assert_eq!(scripts[1].source, isolation);
+    assert_eq!(&binary[scripts[1].offset..][..7], b"window.");
+    assert!(find_injected_scripts(&common::desktop_elf()).is_empty());
+}
+
+#[test]
+fn removes_scan_checkpoint_once_the_scan_completes() {
+    let image = BinaryImage::from_bytes(common::desktop_elf()).unwrap();
+    let expected = AssetScanner::scan(&image).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("scan.checkpoint");
+    // A checkpoint of another binary is ignored rather than resumed.
+    fs::write(
+        &path,
+        r#"{"version":1,"sha256":"00","fingerprint":"00","range":0,"offset":0,"step":8,
+           "table_index":0,"previous_header":null,"headers":[]}"#,
+    )
+    .unwrap();
+
+    let options = ScanOptions {
+        checkpoint: Some(CheckpointOptions {
+            resume: true,
+            interval: Duration::ZERO,
+            ..CheckpointOptions::new(&path)
+        }),
+        ..ScanOptions::default()
+    };
+    let table = AssetScanner::scan_with_options(&image, &options).unwrap();
+    let headers = |table: &tauri_dumper::AssetTable| {
+        table
+            .assets()
+            .iter()
+            .map(|asset| (asset.name().to_string(), asset.location().header_offset))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(headers(&table), headers(&expected));
+    assert!(!path.exists());
+}
+
+#[test]
+fn reads_headers_from_the_scan_cache() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let options = ScanOptions {
+        cache: Some(ScanCache {
+            min_size: 0,
+            ..ScanCache::new(temp.path())
+        }),
+        ..ScanOptions::default()
+    };
+    let scanned = AssetScanner::scan_with_options(&image, &options).unwrap();
+    let entries = fs::read_dir(temp.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(entries.len(), 1);
+    let name = entries[0].file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with(&image.metadata().sha256));
+
+    // Drop a header from the entry; the next scan reads it rather than
+    // scanning the binary again.
+    let mut entry: serde_json::Value =
+        serde_json::from_slice(&fs::read(&entries[0]).unwrap()).unwrap();
+    entry["headers"].as_array_mut().unwrap().pop();
+    fs::write(&entries[0], entry.to_string()).unwrap();
+    let cached = AssetScanner::scan_with_options(&image, &options).unwrap();
+    assert_eq!(cached.len(), scanned.len() - 1);
+    assert_eq!(cached.assets()[0].name(), scanned.assets()[0].name());
+}
+
+#[test]
+fn bounds_the_scan_cache() {
+    let image = BinaryImage::from_bytes(common::nested_desktop_elf()).unwrap();
+    let temp = tempfile::tempdir().unwrap();
+    let cache = ScanCache {
+        min_size: 0,
+        max_entries: 1,
+        ..ScanCache::new(temp.path())
+    };
+    let entries = || {
+        fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>()
+    };
+    let mut options = ScanOptions {
+        cache: Some(cache),
+        ..ScanOptions::default()
+    };
+    AssetScanner::scan_with_options(&image, &options).unwrap();
+    let first = entries();
+    assert_eq!(first.len(), 1);
+
+    // Another codec set is another entry, which replaces the first.
+    options.codecs.register(Box::new(GzipCodec));
+    AssetScanner::scan_with_options(&image, &options).unwrap();
+    let second = entries();
+    assert_eq!(second.len(), 1);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn converts_audit_findings_to_sarif() {
+    let bundle = b"eval(code);fetch('http://api.example.com/v1');";
+    let mut binary = common::desktop_elf_with_tables(&[&[("/assets/index.js", bundle)]]);
+    binary.extend_from_slice(b"\0plugin:shell|execute\0");
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let report = audit::audit(&image, &table).unwrap();
+
+    let sarif = report.to_sarif("app");
+    assert_eq!(sarif["version"], "2.1.0");
+    let rules = sarif["runs"][0]["tool"]["driver"]["rules"]
+        .as_array()
+        .unwrap();
+    assert_eq!(rules.len(), audit::Category::ALL.len());
+    let results = sarif["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| {
+            let rule = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[rule]["id"], result["ruleId"]);
+            let location = &result["locations"][0]["physicalLocation"];
+            (
+                result["ruleId"].as_str().unwrap(),
+                result["level"].as_str().unwrap(),
+                location["artifactLocation"]["uri"].as_str(),
+                location["region"]["byteOffset"].as_u64(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let capability = memchr::memmem::find(&binary, b"plugin:shell|execute").unwrap() as u64;
+    assert_eq!(
+        results,
+        [
+            ("capability", "error", Some("app"), Some(capability)),
+            // No asset sets a CSP, so the finding has no location.
+            ("csp", "warning", None, None),
+            ("eval", "warning", Some("assets/index.js"), Some(0)),
+            (
+                "insecure_endpoint",
+                "note",
+                Some("assets/index.js"),
+                Some(18)
+            ),
+        ]
+    );
+    assert!(sarif["runs"][0]["results"][1].get("locations").is_none());
+}
+
+#[test]
+fn verifies_embedded_integrity_hashes() {
+    let page: &[u8] = b"<meta http-equiv=\"Content-Security-Policy\" \
+        content=\"script-src 'sha256-bhHHL3z2vDgxUt0W3dWQOrprscmda2Y5pLsLg4GF+pI='\">\
+        <script>alert(1)</script><script src=\"/main.js\"></script><style>body{}</style>";
+    let binary = common::rust_embed_elf(&[("index.html", page), ("main.js", b"main()")]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan_with_profile(&image, ScanProfile::RustEmbed).unwrap();
+
+    let report = integrity::verify_integrity(image.data(), &table).unwrap();
+    assert_eq!(report.csp_hashes, 1);
+    let checks = report
+        .checks
+        .iter()
+        .map(|check| (check.asset.as_str(), check.kind, check.verified()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        checks,
+        [
+            ("/index.html", IntegrityKind::ContentHash, true),
+            ("/index.html", IntegrityKind::CspHash, true),
+            // The inline style has no hash.
+            ("/index.html", IntegrityKind::CspHash, false),
+            ("/main.js", IntegrityKind::ContentHash, true),
+        ]
+    );
+
+    // The CSP hash of this binary is of an empty script, not of this one.
+    let binary =
+        common::desktop_elf_with_csp_hashes(&[("/index.html", b"<script>patched()</script>")]);
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    let report = integrity::verify_integrity(image.data(), &table).unwrap();
+    let mismatches = report.mismatches().collect::<Vec<_>>();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].asset, "/index.html");
+    assert_eq!(mismatches[0].expected, None);
+}
+
+#[test]
+fn extracts_pies_relocated_through_every_dynamic_table() {
+    use common::DynamicRelocations::{Rel, Rela, Relr};
+    use object::elf::{
+        EM_386, EM_AARCH64, EM_ARM, EM_RISCV, R_386_RELATIVE, R_AARCH64_RELATIVE, R_ARM_RELATIVE,
+        R_RISCV_RELATIVE,
+    };
+
+    let cases = [
+        ("aarch64 RELA", true, EM_AARCH64, Rela(R_AARCH64_RELATIVE)),
+        ("riscv32 RELA", false, EM_RISCV, Rela(R_RISCV_RELATIVE)),
+        ("i686 REL", false, EM_386, Rel(R_386_RELATIVE)),
+        ("armv7 REL", false, EM_ARM, Rel(R_ARM_RELATIVE)),
+        ("aarch64 RELR", true, EM_AARCH64, Relr),
+        ("armv7 RELR", false, EM_ARM, Relr),
+    ];
+    for (label, is_64, machine, relocations) in cases {
+        let binary = common::pie_with_dynamic_relocations(is_64, machine, relocations);
+        let image = BinaryImage::from_bytes(binary).unwrap();
+        let table = AssetScanner::scan(&image).unwrap();
+        assert_eq!(table.len(), 1, "{label}");
+        assert_eq!(table.assets()[0].name(), "/index.html", "{label}");
+        assert_eq!(
+            table.assets()[0].compressed_data(),
+            common::brotli_compress(b"<!DOCTYPE html><html></html>"),
+            "{label}"
+        );
+    }
+}
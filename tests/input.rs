@@ -0,0 +1,920 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
+mod common;
+
+#[cfg(feature = "cli")]
+use assert_cmd::Command;
+#[cfg(feature = "cli")]
+use predicates::str::contains;
+use std::fs;
+use std::path::Path;
+
+#[cfg(feature = "cli")]
+use tauri_dumper::binary::ContainerKind;
+use tauri_dumper::input;
+
+fn write_app_bundle(root: &Path, executable: &str) {
+    let contents = root.join("Contents");
+    fs::create_dir_all(contents.join("MacOS")).unwrap();
+    fs::create_dir_all(contents.join("Resources")).unwrap();
+    fs::write(
+        contents.join("Info.plist"),
+        common::packages::info_plist(executable),
+    )
+    .unwrap();
+    fs::write(
+        contents.join("MacOS").join(executable),
+        common::desktop_elf(),
+    )
+    .unwrap();
+    fs::write(contents.join("MacOS").join("helper"), b"not the app").unwrap();
+    fs::write(contents.join("Resources").join("icon.icns"), b"icns").unwrap();
+}
+
+#[test]
+fn resolves_app_bundle_executable_from_info_plist() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path().join("Example.app");
+    write_app_bundle(&bundle, "Example");
+
+    let resolved = input::resolve(&bundle).unwrap();
+    assert_eq!(resolved.binary_path, bundle.join("Contents/MacOS/Example"));
+    assert_eq!(
+        resolved.resources_dir.as_deref(),
+        Some(bundle.join("Contents/Resources").as_path())
+    );
+    let image = resolved.into_image().unwrap();
+    let info = image.metadata().bundle.clone().unwrap();
+    assert_eq!(info.identifier.as_deref(), Some("com.example.app"));
+    assert_eq!(info.version.as_deref(), Some("1.4.2"));
+    assert_eq!(info.build.as_deref(), Some("20241001"));
+    assert_eq!(info.minimum_os.as_deref(), Some("10.15"));
+    assert_eq!(info.executable.as_deref(), Some("Example"));
+    assert_eq!(info.url_schemes, ["example"]);
+    let table = tauri_dumper::AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 1);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_extracts_app_bundle_with_resources() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path().join("Example.app");
+    let output = temp.path().join("out");
+    write_app_bundle(&bundle, "Example");
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "extract",
+            bundle.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("bundle resources: 1 files"));
+
+    assert!(output.join("index.html").is_file());
+    assert_eq!(
+        fs::read(output.join("bundle-resources/icon.icns")).unwrap(),
+        b"icns"
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&fs::read(output.join("tauri-dumper.manifest.json")).unwrap())
+            .unwrap();
+    assert_eq!(
+        manifest["source"]["bundle"]["identifier"],
+        "com.example.app"
+    );
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["inspect", bundle.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("com.example.app"))
+        .stdout(contains("Minimum macOS"))
+        .stdout(contains("example:// (Info.plist)"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_gathers_license_files_from_app_bundle() {
+    let temp = tempfile::tempdir().unwrap();
+    let bundle = temp.path().join("Example.app");
+    let output = temp.path().join("out");
+    write_app_bundle(&bundle, "Example");
+    let license = "Permission is hereby granted, free of charge, to any person";
+    fs::write(bundle.join("Contents/Resources/LICENSE"), license).unwrap();
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "extract",
+            bundle.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(contains("licenses: 1 files"));
+
+    assert_eq!(
+        fs::read_to_string(output.join("licenses/package/Contents/Resources/LICENSE")).unwrap(),
+        license
+    );
+    let summary: serde_json::Value =
+        serde_json::from_slice(&fs::read(output.join("licenses/summary.json")).unwrap()).unwrap();
+    assert_eq!(summary["licenses"], serde_json::json!(["MIT"]));
+    assert_eq!(summary["files"][0]["source"], "Contents/Resources/LICENSE");
+}
+
+#[cfg(unix)]
+#[test]
+fn copies_framework_symlinks_within_the_bundle() {
+    use std::os::unix::fs::symlink;
+
+    let temp = tempfile::tempdir().unwrap();
+    let resources = temp.path().join("Resources");
+    let framework = resources.join("Helper.framework");
+    fs::create_dir_all(framework.join("Versions/A")).unwrap();
+    fs::write(framework.join("Versions/A/Helper"), b"helper").unwrap();
+    symlink("A", framework.join("Versions/Current")).unwrap();
+    symlink("Versions/Current/Helper", framework.join("Helper")).unwrap();
+    fs::write(temp.path().join("secret"), b"secret").unwrap();
+    symlink("../secret", resources.join("escape")).unwrap();
+    symlink("..", framework.join("Versions/A/loop")).unwrap();
+
+    let output = temp.path().join("out");
+    assert_eq!(input::copy_dir(&resources, &output).unwrap(), 3);
+    let framework = output.join("Helper.framework");
+    for path in ["Helper", "Versions/A/Helper", "Versions/Current/Helper"] {
+        assert_eq!(fs::read(framework.join(path)).unwrap(), b"helper");
+    }
+    assert!(!output.join("escape").exists());
+    assert!(!framework.join("Versions/A/loop").exists());
+}
+
+#[test]
+fn resolves_executable_inside_dmg() {
+    let temp = tempfile::tempdir().unwrap();
+    let dmg = temp.path().join("Example.dmg");
+    fs::write(&dmg, common::packages::dmg_with_app(&common::desktop_elf())).unwrap();
+
+    let resolved = input::resolve(&dmg).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        dmg.join("Example.app/Contents/MacOS/Example")
+    );
+    assert_eq!(resolved.data, common::desktop_elf());
+    assert_eq!(
+        resolved.bundle_info.as_ref().unwrap().version.as_deref(),
+        Some("1.4.2")
+    );
+    let table = tauri_dumper::AssetScanner::scan(&resolved.into_image().unwrap()).unwrap();
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn records_bundle_info_in_scan_summaries() {
+    let temp = tempfile::tempdir().unwrap();
+    let dmg = temp.path().join("Example.dmg");
+    let binary = temp.path().join("Example");
+    fs::write(&dmg, common::packages::dmg_with_app(&common::desktop_elf())).unwrap();
+    fs::write(&binary, common::desktop_elf()).unwrap();
+    let summary = |path: &Path| {
+        let image = input::resolve(path).unwrap().into_image().unwrap();
+        let table = tauri_dumper::AssetScanner::scan(&image).unwrap();
+        serde_json::to_value(table.summary()).unwrap()
+    };
+
+    let bundle = &summary(&dmg)["binary"]["bundle"];
+    assert_eq!(bundle["identifier"], "com.example.app");
+    assert_eq!(bundle["version"], "1.4.2");
+    assert_eq!(bundle["build"], "20241001");
+    assert_eq!(bundle["minimum_os"], "10.15");
+    assert_eq!(bundle["executable"], "Example");
+    // A bare executable has no bundle, and the field is left out.
+    assert!(summary(&binary)["binary"].get("bundle").is_none());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn resolves_nested_containers_down_to_a_fat_slice() {
+    const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+    const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+    let temp = tempfile::tempdir().unwrap();
+    let x86 = common::macho_with_assets(&["__DATA_CONST"], &[("/x86.html", b"<html>")]);
+    let arm = common::macho_with_assets(&["__DATA_CONST"], &[("/arm64.html", b"<html>")]);
+    let fat = common::packages::fat_macho(&[(CPU_TYPE_X86_64, &x86), (CPU_TYPE_ARM64, &arm)]);
+    let dmg = temp.path().join("Example.dmg");
+    fs::write(&dmg, common::packages::dmg_with_app(&fat)).unwrap();
+
+    let resolved = input::resolve(&dmg).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        dmg.join("Example.app/Contents/MacOS/Example")
+    );
+    assert_eq!(resolved.data, arm);
+    let chain = resolved
+        .containers
+        .iter()
+        .map(|step| (step.kind, step.entry.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        chain,
+        [
+            (ContainerKind::DiskImage, "Example.app"),
+            (ContainerKind::AppBundle, "Contents/MacOS/Example"),
+            (ContainerKind::FatMachO, "arm64"),
+        ]
+    );
+    let image = resolved.into_image().unwrap();
+    assert_eq!(image.metadata().containers.len(), 3);
+    let table = tauri_dumper::AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.assets()[0].name(), "/arm64.html");
+
+    // Without an arm64 slice, the first one is taken.
+    let zip = temp.path().join("Example.zip");
+    let fat = common::packages::fat_macho(&[(CPU_TYPE_X86_64, &x86)]);
+    fs::write(&zip, common::packages::zip_archive(&[("Example", &fat)])).unwrap();
+    let resolved = input::resolve(&zip).unwrap();
+    assert_eq!(resolved.binary_path, zip.join("Example"));
+    assert_eq!(resolved.data, x86);
+    assert_eq!(resolved.containers[1].entry, "x86_64");
+
+    let output = Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["inspect", zip.to_str().unwrap(), "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        summary["binary"]["containers"],
+        serde_json::json!([
+            {"kind": "zip", "entry": "Example"},
+            {"kind": "fat_macho", "entry": "x86_64"},
+        ])
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_extracts_dmg() {
+    let temp = tempfile::tempdir().unwrap();
+    let dmg = temp.path().join("Example.dmg");
+    let output = temp.path().join("out");
+    fs::write(&dmg, common::packages::dmg_with_app(&common::desktop_elf())).unwrap();
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "extract",
+            dmg.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+
+    assert!(output.join("index.html").is_file());
+}
+
+#[test]
+fn resolves_executable_inside_msi() {
+    let temp = tempfile::tempdir().unwrap();
+    let msi = temp.path().join("Example.msi");
+    let padding = vec![b'x'; 40 * 1024];
+    fs::write(
+        &msi,
+        common::packages::msi_with_files(&[
+            ("README", "README.txt", &padding),
+            ("Example.exe", "Example.exe", &common::desktop_elf()),
+            ("Uninstall", "uninstall.exe", b"MZ"),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&msi).unwrap();
+    assert_eq!(resolved.binary_path, msi.join("Example.exe"));
+    assert_eq!(resolved.data, common::desktop_elf());
+}
+
+#[test]
+fn resolves_executable_inside_nsis_installer() {
+    let webview = vec![0u8; 64 * 1024];
+    for compression in [
+        common::packages::NsisCompression::SolidLzma,
+        common::packages::NsisCompression::Deflate,
+    ] {
+        let temp = tempfile::tempdir().unwrap();
+        let setup = temp.path().join("Example_0.1.0_x64-setup.exe");
+        fs::write(
+            &setup,
+            common::packages::nsis_installer(
+                &[
+                    ("resources.dll", b"MZ resources"),
+                    ("Example.exe", &common::desktop_elf()),
+                    ("MicrosoftEdgeWebview2Setup.exe", &webview),
+                ],
+                compression,
+            ),
+        )
+        .unwrap();
+
+        let resolved = input::resolve(&setup).unwrap();
+        assert_eq!(
+            resolved.binary_path,
+            setup.join("Example.exe"),
+            "{compression:?}"
+        );
+        assert_eq!(resolved.data, common::desktop_elf(), "{compression:?}");
+    }
+}
+
+#[test]
+fn resolves_executable_inside_deb() {
+    let helper = common::android_elf_with_rela();
+    for compression in [
+        common::packages::PayloadCompression::Gzip,
+        common::packages::PayloadCompression::Xz,
+        common::packages::PayloadCompression::Zstd,
+    ] {
+        let temp = tempfile::tempdir().unwrap();
+        let deb = temp.path().join("example_0.1.0_amd64.deb");
+        fs::write(
+            &deb,
+            common::packages::deb_package(
+                &[
+                    ("usr/share/applications/example.desktop", b"[Desktop Entry]"),
+                    ("usr/share/doc/example/copyright", b"Copyright Example"),
+                    ("usr/lib/example/helper", &helper),
+                    ("usr/bin/example", &common::desktop_elf()),
+                ],
+                compression,
+            ),
+        )
+        .unwrap();
+
+        let resolved = input::resolve(&deb).unwrap();
+        assert_eq!(
+            resolved.binary_path,
+            deb.join("usr/bin/example"),
+            "{compression:?}"
+        );
+        assert_eq!(resolved.data, common::desktop_elf(), "{compression:?}");
+        assert_eq!(
+            resolved
+                .license_files
+                .iter()
+                .map(|file| file.path.as_str())
+                .collect::<Vec<_>>(),
+            ["usr/share/doc/example/copyright"]
+        );
+    }
+}
+
+#[test]
+fn resolves_executable_inside_rpm() {
+    for compression in [
+        common::packages::PayloadCompression::Zstd,
+        common::packages::PayloadCompression::Xz,
+    ] {
+        let temp = tempfile::tempdir().unwrap();
+        let rpm = temp.path().join("example-0.1.0-1.x86_64.rpm");
+        fs::write(
+            &rpm,
+            common::packages::rpm_package(
+                &[
+                    ("usr/share/icons/example.png", b"png"),
+                    ("usr/bin/example", &common::desktop_elf()),
+                ],
+                compression,
+            ),
+        )
+        .unwrap();
+
+        let resolved = input::resolve(&rpm).unwrap();
+        assert_eq!(
+            resolved.binary_path,
+            rpm.join("usr/bin/example"),
+            "{compression:?}"
+        );
+        assert_eq!(resolved.data, common::desktop_elf(), "{compression:?}");
+    }
+}
+
+#[test]
+fn resolves_executable_inside_appimage() {
+    let temp = tempfile::tempdir().unwrap();
+    let appimage = temp.path().join("example_0.1.0_amd64.AppImage");
+    let library = common::android_elf_with_rela();
+    fs::write(
+        &appimage,
+        common::packages::appimage(&[
+            ("AppRun", b"#!/bin/sh\n"),
+            ("example.desktop", b"[Desktop Entry]"),
+            ("usr/lib/x86_64-linux-gnu/libexample.so", &library),
+            ("usr/bin/example", &common::desktop_elf()),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&appimage).unwrap();
+    assert_eq!(resolved.binary_path, appimage.join("usr/bin/example"));
+    assert_eq!(resolved.data, common::desktop_elf());
+}
+
+#[test]
+fn resolves_snap_app_command() {
+    let temp = tempfile::tempdir().unwrap();
+    let snap = temp.path().join("example_0.1.0_amd64.snap");
+    let other = common::android_elf_with_rela();
+    fs::write(
+        &snap,
+        common::packages::squashfs_image(&[
+            (
+                "meta/snap.yaml",
+                b"name: example\nversion: 0.1.0\napps:\n  example:\n    command: bin/desktop-launch $SNAP/opt/example/example\n",
+            ),
+            ("usr/bin/other", &other),
+            ("opt/example/example", &common::desktop_elf()),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&snap).unwrap();
+    assert_eq!(resolved.binary_path, snap.join("opt/example/example"));
+    assert_eq!(resolved.data, common::desktop_elf());
+}
+
+#[test]
+fn resolves_msix_manifest_executable() {
+    let temp = tempfile::tempdir().unwrap();
+    let executable = common::pe_with_assets(0x1_4000_0000, &[("/index.html", b"<html>")]);
+
+    let msix = temp.path().join("Example.msix");
+    fs::write(&msix, common::packages::msix_package(&executable)).unwrap();
+    let resolved = input::resolve(&msix).unwrap();
+    assert_eq!(resolved.binary_path, msix.join("Example App.exe"));
+    assert_eq!(resolved.data, executable);
+
+    let bundle = temp.path().join("Example.msixbundle");
+    fs::write(
+        &bundle,
+        common::packages::msix_bundle(b"MZ x86", &executable),
+    )
+    .unwrap();
+    let resolved = input::resolve(&bundle).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        bundle.join("Example_x64.msix/Example App.exe")
+    );
+    assert_eq!(resolved.data, executable);
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn resolves_ios_app_package_executable() {
+    let temp = tempfile::tempdir().unwrap();
+    let executable = common::macho_with_assets(&["__DATA_CONST"], &[("/index.html", b"<html>")]);
+    let info_plist = common::packages::info_plist("Example");
+    let ipa = temp.path().join("Example.ipa");
+    fs::write(
+        &ipa,
+        common::packages::zip_archive(&[
+            ("Payload/Example.app/Info.plist", info_plist.as_bytes()),
+            (
+                "Payload/Example.app/Frameworks/Helper.framework/Info.plist",
+                b"<plist/>",
+            ),
+            ("Payload/Example.app/Example", &executable),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&ipa).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        ipa.join("Payload/Example.app/Example")
+    );
+    assert_eq!(resolved.data, executable);
+    let chain = resolved
+        .containers
+        .iter()
+        .map(|step| (step.kind, step.entry.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        chain,
+        [
+            (ContainerKind::Ipa, "Payload/Example.app"),
+            (ContainerKind::AppBundle, "Example"),
+        ]
+    );
+    assert_eq!(
+        resolved.bundle_info.as_ref().unwrap().identifier.as_deref(),
+        Some("com.example.app")
+    );
+    let image = resolved.into_image().unwrap();
+    assert_eq!(image.metadata().encryption, None);
+    let table = tauri_dumper::AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.assets()[0].name(), "/index.html");
+
+    // An App Store build, still encrypted: LC_ENCRYPTION_INFO_64 with
+    // cryptid 1.
+    let mut command = Vec::new();
+    for value in [0x2c, 24, 0x1000, 0x1000, 1, 0] {
+        command.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    let encrypted = common::build_macho(
+        &[common::MachOSection {
+            segment: "__TEXT",
+            name: "__text",
+            data: vec![0xa5; 64],
+        }],
+        &[command],
+    );
+    fs::write(
+        &ipa,
+        common::packages::zip_archive(&[
+            ("Payload/Example.app/Info.plist", info_plist.as_bytes()),
+            ("Payload/Example.app/Example", &encrypted),
+        ]),
+    )
+    .unwrap();
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["inspect", ipa.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(contains("encrypted (FairPlay), 4096 bytes at 0x1000"));
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", ipa.to_str().unwrap(), "-o"])
+        .arg(temp.path().join("out"))
+        .assert()
+        .code(3)
+        .stderr(contains("encrypted with FairPlay"));
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn scans_decrypted_dumps_of_fairplay_binaries() {
+    // stp x29, x30, [sp, #-16]!; mov x29, sp; ret
+    let code = [
+        0xfd, 0x7b, 0xbf, 0xa9, 0xfd, 0x03, 0x00, 0x91, 0xc0, 0x03, 0x5f, 0xd6,
+    ]
+    .repeat(800);
+    let (decrypted, range) = common::fairplay_macho_with_assets(
+        &code,
+        &[("/index.html", b"<html><body>decrypted</body></html>")],
+    );
+    let mut state = 0x2545_f491_u32;
+    let mut encrypted = decrypted.clone();
+    for byte in &mut encrypted[range.clone()] {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        *byte = state as u8;
+    }
+
+    // The headers are outside the range, but the names and data they point
+    // to only read once decrypted.
+    let image = tauri_dumper::BinaryImage::from_bytes(encrypted.clone()).unwrap();
+    let info = image.metadata().encryption.unwrap();
+    assert!(info.encrypted);
+    assert_eq!(info.range(), range);
+    assert!(matches!(
+        tauri_dumper::AssetScanner::scan(&image),
+        Err(tauri_dumper::Error::Encrypted(found)) if found == info
+    ));
+
+    // Some dumpers leave cryptid set, but the range reads as code.
+    let image = tauri_dumper::BinaryImage::from_bytes(decrypted.clone()).unwrap();
+    assert!(!image.metadata().encryption.unwrap().encrypted);
+    let table = tauri_dumper::AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.assets()[0].name(), "/index.html");
+
+    // Dumps of the range alone and of the whole mapped image are both
+    // spliced in, and the patched binary no longer claims to be encrypted.
+    let range_dump = &decrypted[range.clone()];
+    for dump in [range_dump, &decrypted] {
+        let patched = tauri_dumper::binary::apply_decrypted_dump(&encrypted, dump).unwrap();
+        assert_eq!(patched[range.clone()], decrypted[range.clone()]);
+        let info = tauri_dumper::binary::encryption_info(&patched).unwrap();
+        assert!(!info.encrypted);
+    }
+    let err = tauri_dumper::binary::apply_decrypted_dump(&encrypted, &encrypted).unwrap_err();
+    assert!(err.to_string().contains("still encrypted"), "{err}");
+    let err = tauri_dumper::binary::apply_decrypted_dump(&encrypted, &[0; 16]).unwrap_err();
+    assert!(err.to_string().contains("has 16 bytes"), "{err}");
+
+    let temp = tempfile::tempdir().unwrap();
+    let binary = temp.path().join("Example");
+    let dump = temp.path().join("Example.decrypted");
+    fs::write(&binary, &encrypted).unwrap();
+    fs::write(&dump, range_dump).unwrap();
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", binary.to_str().unwrap(), "-o"])
+        .arg(temp.path().join("encrypted"))
+        .assert()
+        .code(3)
+        .stderr(contains("encrypted with FairPlay"))
+        .stderr(contains("--decrypted"));
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", binary.to_str().unwrap(), "--decrypted"])
+        .arg(&dump)
+        .arg("-o")
+        .arg(temp.path().join("out"))
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp.path().join("out/index.html")).unwrap(),
+        "<html><body>decrypted</body></html>"
+    );
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn resolves_android_native_library() {
+    let temp = tempfile::tempdir().unwrap();
+    let arm64 = common::desktop_elf();
+    let armv7 = common::armv7_elf_with_assets(&[("/index.html", b"<html>")]);
+    let runtime = vec![0x7f, b'E', b'L', b'F', 0, 0, 0, 0]
+        .into_iter()
+        .chain(std::iter::repeat_n(0, arm64.len()))
+        .collect::<Vec<_>>();
+    let apk = temp.path().join("app-universal-release.apk");
+    fs::write(
+        &apk,
+        common::packages::zip_archive(&[
+            ("AndroidManifest.xml", b"\x03\x00\x08\x00"),
+            ("classes.dex", b"dex\n035\0"),
+            ("lib/armeabi-v7a/libexample_lib.so", &armv7),
+            ("lib/arm64-v8a/libc++_shared.so", &runtime),
+            ("lib/arm64-v8a/libexample_lib.so", &arm64),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&apk).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        apk.join("lib/arm64-v8a/libexample_lib.so")
+    );
+    assert_eq!(resolved.data, arm64);
+    assert_eq!(resolved.containers[0].kind, ContainerKind::Apk);
+
+    let options = input::ResolveOptions {
+        abi: Some("armeabi-v7a".into()),
+        ..Default::default()
+    };
+    let resolved = input::resolve_with_options(&apk, &options).unwrap();
+    assert_eq!(resolved.data, armv7);
+    let options = input::ResolveOptions {
+        abi: Some("x86".into()),
+        ..Default::default()
+    };
+    let error = input::resolve_with_options(&apk, &options).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("no x86 libraries; it ships arm64-v8a, armeabi-v7a"));
+
+    let aab = temp.path().join("app-release.aab");
+    fs::write(
+        &aab,
+        common::packages::zip_archive(&[
+            ("base/manifest/AndroidManifest.xml", b"\x0a\x0b"),
+            ("base/lib/x86_64/libexample_lib.so", &arm64),
+        ]),
+    )
+    .unwrap();
+    let resolved = input::resolve(&aab).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        aab.join("base/lib/x86_64/libexample_lib.so")
+    );
+    assert_eq!(resolved.containers[0].kind, ContainerKind::Aab);
+
+    let output = temp.path().join("out");
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "extract",
+            apk.to_str().unwrap(),
+            "--abi",
+            "armeabi-v7a",
+            "-o",
+        ])
+        .arg(&output)
+        .assert()
+        .success();
+    assert_eq!(fs::read(output.join("index.html")).unwrap(), b"<html>");
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["list", aab.to_str().unwrap(), "--abi", "arm64-v8a"])
+        .assert()
+        .code(4)
+        .stderr(contains("no arm64-v8a libraries"));
+}
+
+#[test]
+fn resolves_tauri_executable_inside_zip() {
+    let temp = tempfile::tempdir().unwrap();
+    let executable = common::desktop_elf();
+    let helper = vec![0x7f, b'E', b'L', b'F', 0, 0, 0, 0]
+        .into_iter()
+        .chain(std::iter::repeat_n(0, executable.len()))
+        .collect::<Vec<_>>();
+    let zip = temp.path().join("Example-x64.zip");
+    fs::write(
+        &zip,
+        common::packages::zip_archive(&[
+            ("Example/README.txt", b"readme"),
+            ("Example/helper", &helper),
+            ("Example/example", &executable),
+        ]),
+    )
+    .unwrap();
+
+    let resolved = input::resolve(&zip).unwrap();
+    assert_eq!(resolved.binary_path, zip.join("Example/example"));
+    assert_eq!(resolved.data, executable);
+
+    let options = input::ResolveOptions {
+        inner_path: Some("Example/helper".into()),
+        ..Default::default()
+    };
+    let resolved = input::resolve_with_options(&zip, &options).unwrap();
+    assert_eq!(resolved.data, helper);
+}
+
+#[test]
+fn resolves_executable_inside_updater_artifacts() {
+    let temp = tempfile::tempdir().unwrap();
+    let info_plist = common::packages::info_plist("Example");
+    let app = temp.path().join("Example.app.tar.gz");
+    fs::write(
+        &app,
+        common::packages::compress_payload(
+            &common::packages::tar_archive(&[
+                ("Example.app/Contents/Info.plist", info_plist.as_bytes()),
+                ("Example.app/Contents/MacOS/helper", b"not the app"),
+                ("Example.app/Contents/MacOS/Example", &common::desktop_elf()),
+            ]),
+            common::packages::PayloadCompression::Gzip,
+        ),
+    )
+    .unwrap();
+    let signature = temp.path().join("Example.app.tar.gz.sig");
+    fs::write(&signature, "dW50cnVzdGVkIGNvbW1lbnQ=").unwrap();
+
+    for path in [&app, &signature] {
+        let resolved = input::resolve(path).unwrap();
+        assert_eq!(
+            resolved.binary_path,
+            app.join("Example.app/Contents/MacOS/Example")
+        );
+        assert_eq!(resolved.data, common::desktop_elf());
+        assert_eq!(
+            resolved.bundle_info.unwrap().identifier.as_deref(),
+            Some("com.example.app")
+        );
+    }
+
+    let appimage = temp.path().join("example_0.1.0_amd64.AppImage.tar.gz");
+    fs::write(
+        &appimage,
+        common::packages::compress_payload(
+            &common::packages::tar_archive(&[(
+                "example_0.1.0_amd64.AppImage",
+                &common::packages::appimage(&[("usr/bin/example", &common::desktop_elf())]),
+            )]),
+            common::packages::PayloadCompression::Gzip,
+        ),
+    )
+    .unwrap();
+    let resolved = input::resolve(&appimage).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        appimage.join("example_0.1.0_amd64.AppImage/usr/bin/example")
+    );
+    assert_eq!(resolved.data, common::desktop_elf());
+
+    let nsis = temp.path().join("Example_0.1.0_x64-setup.nsis.zip");
+    let installer = common::packages::nsis_installer(
+        &[("Example.exe", &common::desktop_elf())],
+        common::packages::NsisCompression::Deflate,
+    );
+    fs::write(
+        &nsis,
+        common::packages::zip_archive(&[("Example_0.1.0_x64-setup.exe", &installer)]),
+    )
+    .unwrap();
+    let resolved = input::resolve(&nsis).unwrap();
+    assert_eq!(
+        resolved.binary_path,
+        nsis.join("Example_0.1.0_x64-setup.exe/Example.exe")
+    );
+    assert_eq!(resolved.data, common::desktop_elf());
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_extracts_zip_inner_path() {
+    let temp = tempfile::tempdir().unwrap();
+    let zip = temp.path().join("Example.zip");
+    let output = temp.path().join("out");
+    fs::write(
+        &zip,
+        common::packages::zip_archive(&[("bin/example", &common::desktop_elf())]),
+    )
+    .unwrap();
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args([
+            "extract",
+            zip.to_str().unwrap(),
+            "--inner-path",
+            "bin/example",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    assert!(output.join("index.html").is_file());
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["list", zip.to_str().unwrap(), "--inner-path", "missing"])
+        .assert()
+        .failure()
+        .stderr(contains("archive has no entry named missing"));
+}
+
+/// Serves `body` over HTTP on a local port for `requests` connections.
+#[cfg(feature = "cli")]
+fn serve(body: Vec<u8>, requests: usize) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut request = Vec::new();
+            let mut byte = [0];
+            while !request.ends_with(b"\r\n\r\n") {
+                std::io::Read::read_exact(&mut stream, &mut byte).unwrap();
+                request.push(byte[0]);
+            }
+            let header = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            std::io::Write::write_all(&mut stream, header.as_bytes()).unwrap();
+            std::io::Write::write_all(&mut stream, &body).unwrap();
+        }
+    });
+    format!("http://{address}/releases/Example.zip")
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn cli_extracts_url_input() {
+    use sha2::{Digest, Sha256};
+
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("out");
+    let zip = common::packages::zip_archive(&[("bin/example", &common::desktop_elf())]);
+    let sha256 = Sha256::digest(&zip)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let url = serve(zip, 3);
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["extract", &url, "--sha256", &sha256, "-o"])
+        .arg(&output)
+        .assert()
+        .success();
+    assert!(output.join("index.html").is_file());
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["list", &url, "--sha256", "00"])
+        .assert()
+        .failure()
+        .stderr(contains("SHA-256 mismatch"));
+
+    Command::cargo_bin("tauri-dumper")
+        .unwrap()
+        .args(["list", &url, "--max-download-size", "16"])
+        .assert()
+        .failure()
+        .stderr(contains("download limit"));
+}
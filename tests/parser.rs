@@ -0,0 +1,792 @@
+#![cfg(feature = "brotli")]
+
+mod common;
+
+use object::pe::{RT_GROUP_ICON, RT_ICON, RT_MANIFEST, RT_VERSION};
+use object::{Object, ObjectSection};
+use tauri_dumper::binary::{
+    build_info, code_signature, hardening, normalize_ranges, BinaryKind, BinaryParser,
+    BuildProfile, ParsedBinary, ParserFactory, ParserRegistry, ScanRange, SectionInfo,
+};
+use tauri_dumper::deep_link::find_deep_links;
+use tauri_dumper::dev_server::find_dev_url;
+use tauri_dumper::features::{find_features, TauriFeature};
+use tauri_dumper::fingerprint::{crate_versions, CrateVersion};
+use tauri_dumper::packer::{self, detect_upx, IndicatorKind};
+use tauri_dumper::pe_resources::PeResources;
+use tauri_dumper::plugins::{find_plugins, PluginSource};
+use tauri_dumper::webview::{find_webview, WebView2Loader, WebViewEngine};
+use tauri_dumper::{AssetScanner, BinaryImage, Dumper, Error, Result};
+
+const FIRMWARE_MAGIC: &[u8; 4] = b"FIRM";
+
+/// A firmware image whose segments are described by a separate linker map.
+struct FirmwareParser {
+    segments: Vec<SectionInfo>,
+}
+
+impl BinaryParser for FirmwareParser {
+    fn resolve_pointer(&self, raw_ptr: u64) -> Result<u64> {
+        self.segments
+            .iter()
+            .find(|s| raw_ptr >= s.virtual_address && raw_ptr < s.virtual_address + s.size)
+            .map(|s| raw_ptr - s.virtual_address + s.file_offset)
+            .ok_or(Error::AddressNotMapped(raw_ptr))
+    }
+
+    fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
+        Ok(self
+            .segments
+            .iter()
+            .map(|s| ScanRange {
+                start: s.file_offset as usize,
+                length: s.size as usize,
+            })
+            .collect())
+    }
+}
+
+struct FirmwareFactory {
+    segments: Vec<SectionInfo>,
+}
+
+impl ParserFactory for FirmwareFactory {
+    fn matches(&self, data: &[u8]) -> bool {
+        data.starts_with(FIRMWARE_MAGIC)
+    }
+
+    fn create(&self, _data: &[u8]) -> Result<ParsedBinary> {
+        Ok(ParsedBinary {
+            parser: Box::new(FirmwareParser {
+                segments: self.segments.clone(),
+            }),
+            kind: BinaryKind::Custom,
+            architecture: "Firmware".to_string(),
+        })
+    }
+}
+
+/// Turns the desktop fixture into a firmware image and returns its segments.
+fn firmware() -> (Vec<u8>, Vec<SectionInfo>) {
+    firmware_from(common::desktop_elf())
+}
+
+/// Turns the ELF `data` into a firmware image and returns its segments.
+fn firmware_from(mut data: Vec<u8>) -> (Vec<u8>, Vec<SectionInfo>) {
+    let segments = object::File::parse(data.as_slice())
+        .unwrap()
+        .sections()
+        .filter(|section| section.address() != 0)
+        .map(|section| SectionInfo {
+            virtual_address: section.address(),
+            file_offset: section.file_range().unwrap().0,
+            size: section.size(),
+        })
+        .collect();
+    data[..4].copy_from_slice(FIRMWARE_MAGIC);
+    (data, segments)
+}
+
+#[test]
+fn registered_factory_parses_custom_format() {
+    let (data, segments) = firmware();
+    assert!(matches!(
+        BinaryImage::from_bytes(&data),
+        Err(Error::Object(_))
+    ));
+
+    let mut registry = ParserRegistry::new();
+    registry.register(Box::new(FirmwareFactory { segments }));
+    let dumper = Dumper::from_bytes_with_registry(&data, &registry).unwrap();
+    assert_eq!(dumper.image().metadata().kind, BinaryKind::Custom);
+    let table = dumper.scan().unwrap();
+    assert_eq!(table.len(), 1);
+    assert_eq!(table.assets()[0].name(), "/index.html");
+
+    // Formats without a matching factory still use the built-in parsers.
+    let image = BinaryImage::from_bytes_with_registry(common::desktop_elf(), &registry).unwrap();
+    assert_eq!(image.metadata().kind, BinaryKind::Elf);
+}
+
+#[test]
+fn dumper_accepts_explicit_parser() {
+    let (data, segments) = firmware();
+    let parsed = FirmwareFactory { segments }.create(&data).unwrap();
+    let dumper = Dumper::with_parser(&data, parsed);
+    assert_eq!(dumper.scan().unwrap().len(), 1);
+}
+
+/// A firmware image scanned through ranges of its own instead of its
+/// segments.
+struct RangedParser {
+    firmware: FirmwareParser,
+    ranges: Vec<ScanRange>,
+}
+
+impl BinaryParser for RangedParser {
+    fn resolve_pointer(&self, raw_ptr: u64) -> Result<u64> {
+        self.firmware.resolve_pointer(raw_ptr)
+    }
+
+    fn scan_ranges(&self) -> Result<Vec<ScanRange>> {
+        Ok(self.ranges.clone())
+    }
+}
+
+#[test]
+fn scans_split_and_overlapping_ranges_once() {
+    let binary = common::desktop_elf_with_tables(&[&[
+        ("/index.html", b"<html></html>"),
+        ("/app.js", b"console.log(1)"),
+        ("/app.css", b"body {}"),
+    ]]);
+    let headers = object::File::parse(binary.as_slice())
+        .unwrap()
+        .section_by_name(".data.rel.ro")
+        .unwrap()
+        .file_range()
+        .unwrap()
+        .0 as usize;
+    let (data, segments) = firmware_from(binary);
+    let range = |start: usize, end: usize| ScanRange {
+        start: headers + start,
+        length: end - start,
+    };
+    // The table as three pieces out of order, with overlaps, a repeat and
+    // an empty range.
+    let ranges = vec![
+        range(64, 96),
+        range(0, 40),
+        range(24, 72),
+        range(0, 96),
+        range(8, 8),
+    ];
+    let normalized = normalize_ranges(ranges.clone());
+    assert_eq!(normalized.len(), 1);
+    assert_eq!((normalized[0].start, normalized[0].length), (headers, 96));
+
+    let dumper = Dumper::with_parser(
+        &data,
+        ParsedBinary {
+            parser: Box::new(RangedParser {
+                firmware: FirmwareParser { segments },
+                ranges,
+            }),
+            kind: BinaryKind::Custom,
+            architecture: "Firmware".to_string(),
+        },
+    );
+    let table = dumper.scan().unwrap();
+    let names = table
+        .assets()
+        .iter()
+        .map(|asset| (asset.name(), asset.table_index()))
+        .collect::<Vec<_>>();
+    assert_eq!(names, [("/index.html", 0), ("/app.js", 0), ("/app.css", 0)]);
+
+    // Disjoint ranges keep their order.
+    let apart = normalize_ranges([range(200, 300), range(0, 100)]);
+    assert_eq!(
+        apart
+            .iter()
+            .map(|range| range.start - headers)
+            .collect::<Vec<_>>(),
+        [200, 0]
+    );
+}
+
+#[test]
+fn reads_pe_resources() {
+    let version = common::version_info(
+        [1, 2, 3, 0],
+        &[("CompanyName", "Example Corp"), ("ProductName", "Example")],
+    );
+    let icon = [0x89, b'P', b'N', b'G', 0, 1, 2, 3];
+    let mut group = vec![0, 0, 1, 0, 1, 0];
+    group.extend_from_slice(&[32, 32, 0, 0, 1, 0, 32, 0]);
+    group.extend_from_slice(&(icon.len() as u32).to_le_bytes());
+    group.extend_from_slice(&7u16.to_le_bytes());
+    let manifest = b"\xef\xbb\xbf<assembly/>";
+    let pe = common::pe_with_resources(
+        0x1_4000_0000,
+        &[("/index.html", b"<html>")],
+        &[
+            (RT_ICON, 7, &icon),
+            (RT_GROUP_ICON, 1, &group),
+            (RT_VERSION, 1, &version),
+            (RT_MANIFEST, 1, manifest),
+        ],
+    );
+    assert_eq!(Dumper::from_bytes(&pe).unwrap().scan().unwrap().len(), 1);
+
+    let resources = PeResources::parse(&pe).unwrap();
+    let ico = resources.icon.unwrap();
+    assert_eq!(&ico[..6], [0, 0, 1, 0, 1, 0]);
+    assert_eq!(&ico[6..14], &group[6..14]);
+    assert_eq!(&ico[18..22], 22u32.to_le_bytes());
+    assert_eq!(&ico[22..], icon);
+    let version = resources.version.unwrap();
+    assert_eq!(version.file_version.as_deref(), Some("1.2.3.0"));
+    assert_eq!(version.strings["CompanyName"], "Example Corp");
+    assert_eq!(version.strings["ProductName"], "Example");
+    assert_eq!(resources.manifest.as_deref(), Some("<assembly/>"));
+
+    let bare = common::pe_with_assets(0x1_4000_0000, &[("/index.html", b"<html>")]);
+    assert!(PeResources::parse(&bare).unwrap().is_empty());
+    assert!(PeResources::parse(&common::desktop_elf()).is_err());
+}
+
+#[test]
+fn reads_macho_code_signature() {
+    let entitlements = "<?xml version=\"1.0\"?><plist><dict>\
+        <key>com.apple.security.cs.allow-jit</key><true/></dict></plist>";
+    let macho = common::signed_macho(
+        &[("/index.html", b"<html>")],
+        "com.example.app",
+        "ABCDE12345",
+        0x1_0000,
+        entitlements,
+        "Developer ID Application: Example Inc (ABCDE12345)",
+    );
+    let dumper = Dumper::from_bytes(&macho).unwrap();
+    assert_eq!(dumper.scan().unwrap().len(), 1);
+
+    let signature = dumper.image().metadata().code_signature.clone().unwrap();
+    assert_eq!(signature.identifier.as_deref(), Some("com.example.app"));
+    assert_eq!(signature.team_id.as_deref(), Some("ABCDE12345"));
+    assert_eq!(
+        signature.signing_identity.as_deref(),
+        Some("Developer ID Application: Example Inc (ABCDE12345)")
+    );
+    assert!(signature.hardened_runtime);
+    assert!(!signature.ad_hoc);
+    assert_eq!(signature.entitlements.as_deref(), Some(entitlements));
+
+    let unsigned = common::macho_with_assets(&["__DATA_CONST"], &[("/index.html", b"<html>")]);
+    assert!(code_signature(&unsigned).is_none());
+}
+
+#[test]
+fn reads_build_metadata() {
+    let mut pe = common::pe_with_assets(0x1_4000_0000, &[("/index.html", b"<html>")]);
+    pe[0x48..0x4c].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+    let build = build_info(&pe).unwrap();
+    assert_eq!(build.timestamp.as_deref(), Some("2023-11-14T22:13:20Z"));
+    assert_eq!(build.platform.as_deref(), Some("windows"));
+    assert_eq!(build.min_os_version.as_deref(), Some("6.0"));
+    // Reproducible builds store a hash instead of the link time.
+    pe[0x48..0x4c].copy_from_slice(&0xfedc_ba98u32.to_le_bytes());
+    assert_eq!(build_info(&pe).unwrap().timestamp, None);
+
+    // LC_BUILD_VERSION for macOS 11.0 with the 14.2.1 SDK.
+    let mut command = Vec::new();
+    for value in [0x32, 24, 1, 0x000b_0000, 0x000e_0201, 0] {
+        command.extend_from_slice(&u32::to_le_bytes(value));
+    }
+    let macho = common::build_macho(
+        &[common::MachOSection {
+            segment: "__DATA_CONST",
+            name: "__const",
+            data: vec![0; 32],
+        }],
+        &[command],
+    );
+    let build = Dumper::from_bytes(&macho)
+        .unwrap()
+        .image()
+        .metadata()
+        .build
+        .clone()
+        .unwrap();
+    assert_eq!(build.timestamp, None);
+    assert_eq!(build.platform.as_deref(), Some("macos"));
+    assert_eq!(build.min_os_version.as_deref(), Some("11.0"));
+    assert_eq!(build.sdk_version.as_deref(), Some("14.2.1"));
+
+    assert!(build_info(&common::desktop_elf()).is_none());
+}
+
+#[test]
+fn reads_build_profile_and_mitigations() {
+    let mut pe = common::pe_with_assets(0x1_4000_0000, &[("/index.html", b"<html>")]);
+    let info = hardening(&pe).unwrap();
+    assert_eq!(info.profile, BuildProfile::Release);
+    assert_eq!(
+        (info.aslr, info.nx, info.cfg),
+        (Some(false), Some(false), Some(false))
+    );
+    // DYNAMIC_BASE, NX_COMPAT and GUARD_CF.
+    pe[0x9e..0xa0].copy_from_slice(&0x4140u16.to_le_bytes());
+    let info = hardening(&pe).unwrap();
+    assert_eq!(
+        (info.aslr, info.nx, info.cfg),
+        (Some(true), Some(true), Some(true))
+    );
+
+    let elf = common::desktop_elf_with_scripts(&[
+        "attempt to add with overflow",
+        "unsafe precondition(s) violated: slice::from_raw_parts",
+    ]);
+    let info = Dumper::from_bytes(&elf)
+        .unwrap()
+        .image()
+        .metadata()
+        .hardening
+        .clone()
+        .unwrap();
+    assert_eq!(info.profile, BuildProfile::Debug);
+    assert!(info.overflow_checks && info.debug_assertions);
+    assert_eq!(info.symbols, 0);
+    // An ET_EXEC image without PT_GNU_STACK.
+    assert_eq!(
+        (info.aslr, info.nx, info.cfg),
+        (Some(false), Some(false), None)
+    );
+
+    let mut macho = common::build_macho(
+        &[common::MachOSection {
+            segment: "__DATA_CONST",
+            name: "__const",
+            data: vec![0; 32],
+        }],
+        &[],
+    );
+    let info = hardening(&macho).unwrap();
+    assert_eq!((info.aslr, info.nx), (Some(false), Some(true)));
+    // MH_PIE.
+    macho[24..28].copy_from_slice(&0x0020_0000u32.to_le_bytes());
+    assert_eq!(hardening(&macho).unwrap().aslr, Some(true));
+}
+
+#[test]
+fn reads_deep_link_config() {
+    let config = r#"{"identifier":"com.example.app","plugins":{"deep-link":{
+        "desktop":{"schemes":["example","example-dev"]},
+        "mobile":[{"host":"example.com","pathPrefix":["/open"]},{"scheme":["example"],"host":"app"}]
+    }}}"#;
+    let binary = common::desktop_elf_with_scripts(&[config]);
+    let links = Dumper::from_bytes(&binary)
+        .unwrap()
+        .image()
+        .metadata()
+        .deep_links
+        .clone()
+        .unwrap();
+    assert_eq!(links.schemes, ["example", "example-dev"]);
+    assert_eq!(links.hosts, ["app", "example.com"]);
+
+    assert!(find_deep_links(br#"{"deep-link": "not an object"}"#).is_none());
+    assert!(find_deep_links(&common::desktop_elf()).is_none());
+}
+
+#[test]
+fn finds_compiled_tauri_plugins() {
+    let strings = [
+        "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tauri-plugin-fs-2.0.1/src/commands.rs",
+        "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tauri-plugin-fs-2.0.1/src/scope.rs",
+        "tauri_plugin_clipboard_manager::error",
+        "plugin:shell|execute",
+        "plugin:window|create",
+        "\"dialog:allow-open\"",
+        "core:default",
+    ];
+    let binary = common::desktop_elf_with_scripts(&strings);
+    let plugins = Dumper::from_bytes(&binary)
+        .unwrap()
+        .image()
+        .metadata()
+        .plugins
+        .clone();
+    let names = plugins
+        .iter()
+        .map(|plugin| (plugin.name.as_str(), plugin.version.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            ("clipboard-manager", None),
+            ("dialog", None),
+            ("fs", Some("2.0.1")),
+            ("shell", None),
+        ]
+    );
+    assert!(plugins[2].sources.contains(&PluginSource::CratePath));
+    assert!(plugins[3].sources.contains(&PluginSource::CommandKey));
+
+    assert!(find_plugins(&common::desktop_elf()).is_empty());
+}
+
+#[test]
+fn finds_dev_server_url() {
+    assert_eq!(
+        find_dev_url(b"tauri://localhost\0http://tauri.localhost/http://localhost:1420/index.html"),
+        Some("http://localhost:1420".to_string())
+    );
+    assert_eq!(
+        find_dev_url(b"https://127.0.0.1:5173"),
+        Some("https://127.0.0.1:5173".to_string())
+    );
+    assert_eq!(find_dev_url(b"http://example.com:8080/"), None);
+}
+
+#[test]
+fn finds_compiled_tauri_features() {
+    let binary = common::desktop_elf_with_scripts(&[
+        "internal_toggle_devtools",
+        "__TAURI_ISOLATION_HOOK__",
+        "libayatana-appindicator3.so.1",
+        "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tray-icon-0.14.3/src/lib.rs",
+        "tauri_plugin_updater::error",
+    ]);
+    let features = Dumper::from_bytes(&binary)
+        .unwrap()
+        .image()
+        .metadata()
+        .features
+        .clone();
+    let found = features
+        .iter()
+        .map(|found| (found.feature, found.evidence.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (
+                TauriFeature::Devtools,
+                vec!["internal_toggle_devtools".to_string()]
+            ),
+            (
+                TauriFeature::TrayIcon,
+                vec![
+                    "libayatana-appindicator3.so".to_string(),
+                    "tray-icon-0.14.3".to_string(),
+                ]
+            ),
+            (
+                TauriFeature::Updater,
+                vec!["tauri-plugin-updater".to_string()]
+            ),
+            (
+                TauriFeature::Isolation,
+                vec!["__TAURI_ISOLATION_HOOK__".to_string()]
+            ),
+        ]
+    );
+
+    assert!(find_features(&common::desktop_elf(), &[], &[]).is_empty());
+}
+
+#[test]
+fn finds_webview_runtime() {
+    let registry = "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f";
+    let linux = common::desktop_elf_with_scripts(&[
+        &format!("{registry}/wry-0.37.0/src/webkitgtk/mod.rs"),
+        &format!("{registry}/webkit2gtk-2.0.1/src/auto/web_view.rs"),
+        "libwebkit2gtk-4.1.so.0",
+    ]);
+    let webview = Dumper::from_bytes(&linux)
+        .unwrap()
+        .image()
+        .metadata()
+        .webview
+        .clone()
+        .unwrap();
+    assert_eq!(webview.engine, Some(WebViewEngine::WebkitGtk));
+    assert_eq!(webview.wry_version.as_deref(), Some("0.37.0"));
+    assert_eq!(webview.bindings.unwrap().version, "2.0.1");
+    assert_eq!(webview.webkitgtk_api.as_deref(), Some("4.1"));
+
+    let mut windows = format!("{registry}/webview2-com-0.28.0/src/lib.rs\0").into_bytes();
+    for unit in "Microsoft.WebView2.FixedVersionRuntime.120.0.2210.91.x64".encode_utf16() {
+        windows.extend_from_slice(&unit.to_le_bytes());
+    }
+    let webview = find_webview(&windows).unwrap();
+    assert_eq!(webview.engine, Some(WebViewEngine::WebView2));
+    assert_eq!(webview.webview2_loader, Some(WebView2Loader::Static));
+    assert_eq!(
+        webview.webview2_fixed_runtime.as_deref(),
+        Some("120.0.2210.91")
+    );
+
+    assert!(find_webview(&common::desktop_elf()).is_none());
+}
+
+#[test]
+fn finds_toolchain_and_crate_versions() {
+    let binary = common::desktop_elf_with_scripts(&[
+        "/rustc/82e1608dfa6e0b5569232559e3d385fea5a93112/library/std/src/io/mod.rs",
+        "rustc version 1.75.0 (82e1608df 2023-12-21)",
+        "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde_json-1.0.111/src/de.rs",
+        "C:\\Users\\ci\\.cargo\\registry\\src\\index.crates.io-6f17d22bba15001f\\x11-dl-2.21.0\\src\\lib.rs",
+        "/home/ci/.cargo/registry/src/index.crates.io-6f17d22bba15001f/tauri-2.0.0-rc.2/src/app.rs",
+    ]);
+    let metadata = Dumper::from_bytes(&binary)
+        .unwrap()
+        .image()
+        .metadata()
+        .clone();
+    let toolchain = metadata.toolchain.unwrap();
+    assert_eq!(toolchain.version.as_deref(), Some("1.75.0"));
+    assert_eq!(
+        toolchain.commit.as_deref(),
+        Some("82e1608dfa6e0b5569232559e3d385fea5a93112")
+    );
+    let crate_version = |name: &str, version: &str| CrateVersion {
+        name: name.to_string(),
+        version: version.to_string(),
+    };
+    assert_eq!(
+        metadata.crates,
+        [
+            crate_version("serde_json", "1.0.111"),
+            crate_version("tauri", "2.0.0-rc.2"),
+            crate_version("x11-dl", "2.21.0"),
+        ]
+    );
+
+    assert!(crate_versions(&common::desktop_elf()).is_empty());
+}
+
+#[test]
+fn resolves_addresses_and_pointers() {
+    for binary in [
+        common::desktop_elf(),
+        common::pe_with_assets(0x1_4000_0000, &[("/index.html", b"<html>")]),
+    ] {
+        let dumper = Dumper::from_bytes(&binary).unwrap();
+        let table = dumper.scan().unwrap();
+        let location = table.assets()[0].location();
+        let image = dumper.image();
+
+        let name_ptr = image.read_pointer(location.header_offset).unwrap();
+        assert_eq!(
+            dumper.resolve_raw_pointer(name_ptr).unwrap(),
+            location.name_offset as u64
+        );
+        assert_eq!(
+            dumper.resolve_va(name_ptr).unwrap(),
+            location.name_offset as u64
+        );
+        assert_eq!(
+            image.follow_pointer(location.header_offset + 16).unwrap(),
+            location.data_offset as u64
+        );
+        assert!(matches!(
+            dumper.resolve_va(0x10),
+            Err(Error::AddressNotMapped(0x10))
+        ));
+    }
+}
+
+#[test]
+fn exposes_sections_and_scan_ranges() {
+    let binary = common::desktop_elf();
+    let dumper = Dumper::from_bytes(&binary).unwrap();
+    let location = dumper.scan().unwrap().assets()[0].location().clone();
+
+    let sections = dumper.sections();
+    assert!(sections.iter().any(|section| {
+        let start = section.file_offset as usize;
+        (start..start + section.size as usize).contains(&location.header_offset)
+    }));
+
+    let ranges = dumper.scan_ranges().unwrap();
+    assert!(ranges
+        .iter()
+        .any(|range| (range.start..range.start + range.length).contains(&location.header_offset)));
+
+    let dumper = Dumper::builder()
+        .sections([".rodata"])
+        .build_from_bytes(&binary)
+        .unwrap();
+    let object = object::File::parse(&*binary).unwrap();
+    let rodata = object.section_by_name(".rodata").unwrap();
+    let (start, length) = rodata.file_range().unwrap();
+    let ranges = dumper.scan_ranges().unwrap();
+    assert_eq!(ranges.len(), 1);
+    assert_eq!(
+        (ranges[0].start as u64, ranges[0].length as u64),
+        (start, length)
+    );
+}
+
+#[test]
+fn scans_binaries_with_stripped_or_renamed_sections() {
+    fn replace(binary: &mut [u8], from: &[u8], to: &[u8]) {
+        let at = binary
+            .windows(from.len())
+            .position(|window| window == from)
+            .unwrap();
+        binary[at..at + to.len()].copy_from_slice(to);
+    }
+
+    let html = b"<!DOCTYPE html><html></html>";
+    let mut renamed_elf = common::desktop_elf();
+    replace(
+        &mut renamed_elf,
+        b"\0.rodata\0.data.rel.ro\0",
+        b"\0.rodatX\0.data.rel.rX\0",
+    );
+
+    // Section headers stripped, with a program header for each section.
+    let mut stripped_elf = common::desktop_elf();
+    let loads = object::File::parse(&*stripped_elf)
+        .unwrap()
+        .sections()
+        .filter(|section| section.address() != 0)
+        .map(|section| (section.file_range().unwrap(), section.address()))
+        .collect::<Vec<_>>();
+    for (index, ((offset, size), address)) in loads.iter().enumerate() {
+        let header = 64 + index * 56;
+        stripped_elf[header..header + 4].copy_from_slice(&1u32.to_le_bytes());
+        stripped_elf[header + 4..header + 8].copy_from_slice(&6u32.to_le_bytes());
+        for (field, value) in [
+            (8, offset),
+            (16, address),
+            (24, address),
+            (32, size),
+            (40, size),
+        ] {
+            stripped_elf[header + field..header + field + 8].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+    stripped_elf[56..58].copy_from_slice(&(loads.len() as u16).to_le_bytes());
+    stripped_elf[40..48].fill(0);
+    stripped_elf[60..64].fill(0);
+
+    let mut renamed_macho = common::macho_with_assets(&["__DATA_CONST"], &[("/index.html", html)]);
+    for _ in 0..2 {
+        replace(&mut renamed_macho, b"__const\0", b"__konst\0");
+    }
+
+    let mut renamed_pe = common::pe_with_assets(0x1_4000_0000, &[("/index.html", html)]);
+    replace(&mut renamed_pe, b".rdata\0", b".rdatX\0");
+
+    for (name, binary) in [
+        ("renamed ELF", renamed_elf),
+        ("stripped ELF", stripped_elf),
+        ("renamed Mach-O", renamed_macho),
+        ("renamed PE", renamed_pe),
+    ] {
+        let dumper = Dumper::from_bytes(&binary).unwrap_or_else(|error| panic!("{name}: {error}"));
+        let table = dumper.scan().unwrap();
+        assert_eq!(table.len(), 1, "{name}");
+        assert_eq!(
+            dumper.decompress_asset(&table.assets()[0]).unwrap(),
+            html,
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn falls_back_to_other_read_only_data_sections() {
+    // As a MinGW build can lay it out: the asset table in `.rodata`, before
+    // an `.rdata` without any assets.
+    let image_base = 0x1_4000_0000;
+    let html = b"<html></html>";
+    let pe = common::build_pe(
+        image_base,
+        &[
+            common::PeSection {
+                name: ".rodata",
+                ..common::PeSection::rdata(common::pe_assets_rdata(
+                    image_base,
+                    &[("/index.html", html)],
+                ))
+            },
+            common::PeSection::rdata(vec![0; 0x200]),
+        ],
+    );
+    let rodata = object::File::parse(&*pe)
+        .unwrap()
+        .section_by_name(".rodata")
+        .unwrap()
+        .file_range()
+        .unwrap()
+        .0 as usize;
+
+    let dumper = Dumper::from_bytes(&pe).unwrap();
+    assert_eq!(dumper.scan_ranges().unwrap().len(), 1);
+    let table = dumper.scan().unwrap();
+    assert_eq!(table.len(), 1);
+    assert_eq!(table.assets()[0].location().scan_range.start, rodata);
+    assert_eq!(dumper.decompress_asset(&table.assets()[0]).unwrap(), html);
+    assert!(dumper.get_asset("/index.html").unwrap().is_some());
+
+    // Sections asked for by name are scanned alone.
+    let only_rdata = Dumper::builder()
+        .sections([".rdata"])
+        .build_from_bytes(&pe)
+        .unwrap();
+    assert!(only_rdata.scan().unwrap().is_empty());
+}
+
+#[test]
+fn reports_upx_packed_binaries() {
+    let mut pe = common::build_pe(
+        0x1_4000_0000,
+        &[
+            common::PeSection {
+                name: "UPX0",
+                characteristics: 0xe000_0080,
+                data: vec![0; 0x200],
+            },
+            common::PeSection {
+                name: "UPX1",
+                characteristics: 0xe000_0040,
+                data: vec![0xa5; 0x200],
+            },
+        ],
+    );
+    pe[0x3e0..0x3e9].copy_from_slice(b"4.24\0UPX!");
+
+    let packer = detect_upx(&pe).unwrap();
+    assert_eq!(packer.to_string(), "UPX 4.24");
+    let image = BinaryImage::from_bytes(&pe).unwrap();
+    match AssetScanner::scan(&image) {
+        Err(Error::Packed(found)) => assert_eq!(found, packer),
+        other => panic!("expected a packed binary error, got {other:?}"),
+    }
+
+    assert_eq!(detect_upx(&common::desktop_elf()), None);
+}
+
+#[test]
+fn reports_packing_indicators() {
+    // Code as random as encrypted data, in a section MPRESS names.
+    let mut state = 0x2545_f491_u32;
+    let random = (0..0x2000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect::<Vec<_>>();
+    let pe = common::build_pe(
+        0x1_4000_0000,
+        &[common::PeSection {
+            name: ".MPRESS1",
+            characteristics: 0xe000_0020,
+            data: random,
+        }],
+    );
+
+    let report = packer::analyze(&pe);
+    assert_eq!(report.packers[0].name, "MPRESS");
+    assert_eq!(packer::detect(&pe).unwrap().name, "MPRESS");
+    let kinds = report
+        .indicators
+        .iter()
+        .map(|indicator| indicator.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [IndicatorKind::Packer, IndicatorKind::EncryptedCode]);
+    let section = &report.sections[0];
+    assert!(section.executable);
+    assert!(section.entropy > 7.9, "entropy {}", section.entropy);
+
+    let report = packer::analyze(&common::desktop_elf());
+    assert!(!report.is_packed(), "{:?}", report.indicators);
+    assert!(!report.sections.is_empty());
+}
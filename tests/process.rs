@@ -0,0 +1,152 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
+mod common;
+
+use object::{Object, ObjectSection};
+use tauri_dumper::binary::BinaryKind;
+use tauri_dumper::process::{self, ProcessMemory};
+use tauri_dumper::{AssetScanner, BinaryImage, Error, Result};
+
+/// A contiguous address space starting at `base`.
+struct FakeMemory {
+    base: u64,
+    data: Vec<u8>,
+}
+
+impl ProcessMemory for FakeMemory {
+    fn read(&self, address: u64, len: usize) -> Result<Vec<u8>> {
+        let start = address
+            .checked_sub(self.base)
+            .ok_or_else(|| Error::Process(format!("{address:#x} is not mapped")))?
+            as usize;
+        self.data
+            .get(start..start + len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| Error::Process(format!("{address:#x} is not mapped")))
+    }
+}
+
+#[test]
+fn rebuilds_mapped_elf_without_section_headers() {
+    // Map the file so that `.rodata` at file offset 0x1000 lands at its
+    // section address.
+    let base = common::DESKTOP_RODATA_ADDR - 0x1000;
+    let mut elf = common::desktop_elf();
+    elf[64 + 16..64 + 24].copy_from_slice(&base.to_le_bytes());
+    let memory = FakeMemory { base, data: elf };
+
+    let image = BinaryImage::from_bytes(process::rebuild_image(&memory, base).unwrap()).unwrap();
+    assert_eq!(image.metadata().kind, BinaryKind::Elf);
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 1);
+    assert_eq!(table.assets()[0].name(), "/index.html");
+}
+
+#[test]
+fn rejects_mapped_elf_with_implausible_segments() {
+    let base = common::DESKTOP_RODATA_ADDR - 0x1000;
+    let mut elf = common::desktop_elf();
+    elf[64 + 16..64 + 24].copy_from_slice(&base.to_le_bytes());
+    let rebuild = |offset: u64, filesz: u64| {
+        let mut data = elf.clone();
+        data[64 + 8..64 + 16].copy_from_slice(&offset.to_le_bytes());
+        data[64 + 32..64 + 40].copy_from_slice(&filesz.to_le_bytes());
+        process::rebuild_image(&FakeMemory { base, data }, base)
+    };
+
+    // A file offset past the address, a segment running past the end of the
+    // address space, and one far larger than any real image.
+    for (offset, filesz) in [(base + 1, 0x100), (0, u64::MAX), (0, 1 << 40)] {
+        assert!(matches!(rebuild(offset, filesz), Err(Error::Process(_))));
+    }
+}
+
+#[test]
+fn rebuilds_mapped_pe() {
+    let base = 0x1_4000_0000;
+    let pe = common::pe_with_assets(base, &[("/index.html", b"<html>")]);
+    let file = object::File::parse(pe.as_slice()).unwrap();
+    let end = file
+        .sections()
+        .map(|section| section.address() + section.size())
+        .max()
+        .unwrap();
+    // Sections occupy whole pages once mapped.
+    let mut data = vec![0; (end - base).next_multiple_of(0x1000) as usize];
+    let first_section = file
+        .sections()
+        .filter_map(|section| section.file_range())
+        .map(|(offset, _)| offset as usize)
+        .min()
+        .unwrap();
+    data[..first_section].copy_from_slice(&pe[..first_section]);
+    for section in file.sections() {
+        let (offset, size) = section.file_range().unwrap();
+        let start = (section.address() - base) as usize;
+        data[start..start + size as usize]
+            .copy_from_slice(&pe[offset as usize..(offset + size) as usize]);
+    }
+    let memory = FakeMemory { base, data };
+
+    let image = BinaryImage::from_bytes(process::rebuild_image(&memory, base).unwrap()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    assert_eq!(table.len(), 1);
+}
+
+#[test]
+fn rejects_mapped_pe_with_headers_outside_the_image() {
+    let pe = |base: u64, sections: &[[u32; 4]]| {
+        let mut data = vec![0; 0x1000];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3c..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+        data[0x46..0x48].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+        // No optional header fields past the magic; `SizeOfHeaders` is 0.
+        data[0x58..0x5a].copy_from_slice(&0x20bu16.to_le_bytes());
+        for (index, fields) in sections.iter().enumerate() {
+            for (field, value) in fields.iter().enumerate() {
+                let at = 0x58 + index * 40 + 8 + field * 4;
+                data[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            }
+        }
+        process::rebuild_image(&FakeMemory { base, data }, base)
+    };
+
+    // An empty image has no room for the image base.
+    assert!(matches!(pe(0x1_4000_0000, &[]), Err(Error::Process(_))));
+    // A section address past the end of the address space.
+    let base = u64::MAX - 0xfff;
+    let section = [0x200, 0x10000, 0x200, 0x400];
+    assert!(matches!(pe(base, &[section]), Err(Error::Process(_))));
+}
+
+#[test]
+fn rejects_mapped_macho_with_commands_outside_the_image() {
+    let base: u64 = 0x1_0000_0000;
+    let mut data = vec![0; 32 + 72];
+    data[..4].copy_from_slice(&0xfeed_facfu32.to_le_bytes());
+    data[16..20].copy_from_slice(&1u32.to_le_bytes());
+    data[20..24].copy_from_slice(&72u32.to_le_bytes());
+    let command = &mut data[32..];
+    command[..4].copy_from_slice(&0x19u32.to_le_bytes());
+    command[4..8].copy_from_slice(&72u32.to_le_bytes());
+    command[24..32].copy_from_slice(&base.to_le_bytes());
+    // `__TEXT` maps only 16 bytes, so its own load command is not in the
+    // rebuilt image.
+    command[48..56].copy_from_slice(&16u64.to_le_bytes());
+
+    let rebuilt = process::rebuild_image(&FakeMemory { base, data }, base);
+    assert!(matches!(rebuilt, Err(Error::Process(_))));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn reads_own_process_image() {
+    let process = process::Process::open(std::process::id()).unwrap();
+    let module = process.main_module().unwrap();
+    assert_eq!(module.path, std::env::current_exe().ok());
+
+    let image =
+        BinaryImage::from_bytes(process::rebuild_image(&process, module.base).unwrap()).unwrap();
+    assert_eq!(image.metadata().kind, BinaryKind::Elf);
+}
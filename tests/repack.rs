@@ -1,8 +1,14 @@
+#![cfg(all(feature = "brotli", feature = "fs"))]
+
 mod common;
 
 use std::fs;
 
-use tauri_dumper::{extract, AssetScanner, BinaryImage, Error, Repacker};
+use tauri_dumper::codec::GzipCodec;
+use tauri_dumper::repack::bsdiff;
+use tauri_dumper::{
+    extract, AssetEncoding, AssetScanner, BinaryImage, CodecRegistry, Error, Repacker, ScanProfile,
+};
 
 #[test]
 fn replaces_existing_asset_with_smaller_content() {
@@ -31,6 +37,42 @@ fn replaces_existing_asset_with_smaller_content() {
     assert_eq!(data, b"ok");
 }
 
+#[test]
+fn patch_reproduces_repacked_binary() {
+    let temp = tempfile::tempdir().unwrap();
+    let input = temp.path().join("app");
+    let assets_dir = temp.path().join("assets");
+    let output = temp.path().join("patched");
+    let patch = temp.path().join("app.bsdiff");
+    let original = common::desktop_elf();
+    fs::write(&input, &original).unwrap();
+
+    let image = BinaryImage::open(&input).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    table
+        .export(&tauri_dumper::ExportOptions::new(&assets_dir))
+        .unwrap();
+    fs::write(assets_dir.join("index.html"), b"ok").unwrap();
+
+    let summary = Repacker::new(image, table)
+        .replace_from_dir(&assets_dir)
+        .patch(&patch)
+        .write(&output)
+        .unwrap();
+    assert_eq!(summary.patch.as_deref(), Some(patch.as_path()));
+
+    let patch = fs::read(&patch).unwrap();
+    assert!(patch.starts_with(b"BSDIFF40"));
+    assert_eq!(
+        bsdiff::apply(&original, &patch).unwrap(),
+        fs::read(&output).unwrap()
+    );
+    assert!(matches!(
+        bsdiff::apply(&original, &patch[..20]),
+        Err(Error::InvalidPatch(_))
+    ));
+}
+
 #[test]
 fn treats_empty_content_as_replacement_not_deletion() {
     let temp = tempfile::tempdir().unwrap();
@@ -153,3 +195,238 @@ fn oversized_replacement_fails_unless_skipped() {
     assert_eq!(summary.replaced, 0);
     assert_eq!(summary.skipped_oversized, 1);
 }
+
+#[test]
+fn fits_brotli_replacements_into_the_original_allocation() {
+    let temp = tempfile::tempdir().unwrap();
+    let input = temp.path().join("app");
+    let assets_dir = temp.path().join("assets");
+    let output = temp.path().join("patched");
+    let script = (0..400)
+        .map(|line| {
+            format!(
+                "export const value{line} = compute({line}, \"item-{}\");\n",
+                line % 7
+            )
+        })
+        .collect::<String>();
+    fs::write(
+        &input,
+        common::desktop_elf_with_tables(&[&[("/app.js", script.as_bytes())]]),
+    )
+    .unwrap();
+
+    let image = BinaryImage::open(&input).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    table
+        .export(&tauri_dumper::ExportOptions::new(&assets_dir))
+        .unwrap();
+    let edited = script.replace("value399", "edited399");
+    fs::write(assets_dir.join("app.js"), &edited).unwrap();
+
+    let err = Repacker::new(BinaryImage::open(&input).unwrap(), table.clone())
+        .replace_from_dir(&assets_dir)
+        .brotli_quality(0)
+        .write(&output)
+        .unwrap_err();
+    assert!(matches!(err, Error::ReplacementTooLarge { .. }));
+
+    let err = Repacker::new(BinaryImage::open(&input).unwrap(), table.clone())
+        .replace_from_dir(&assets_dir)
+        .brotli_quality(12)
+        .write(&output)
+        .unwrap_err();
+    assert!(matches!(err, Error::Message(_)));
+
+    let summary = Repacker::new(image, table)
+        .replace_from_dir(&assets_dir)
+        .brotli_window(20)
+        .fit_original(true)
+        .write(&output)
+        .unwrap();
+    assert_eq!(summary.replaced, 1);
+
+    let patched = BinaryImage::open(&output).unwrap();
+    let patched_table = AssetScanner::scan(&patched).unwrap();
+    let asset = patched_table.find("/app.js").unwrap();
+    assert_eq!(
+        asset.compressed_data(),
+        tauri_dumper::codec::compress_with_params(edited.as_bytes(), 11, 20)
+            .unwrap()
+            .data
+    );
+    assert_eq!(extract::decompress_asset(asset).unwrap(), edited.as_bytes());
+}
+
+#[test]
+fn rewrites_embedded_csp_and_config_in_place() {
+    let temp = tempfile::tempdir().unwrap();
+    let assets_dir = temp.path().join("assets");
+    let output = temp.path().join("patched");
+    let mut binary = common::desktop_elf();
+    binary.extend_from_slice(b"\0default-src 'self'; script-src 'self' https://cdn.example.com\0");
+    binary.extend_from_slice(
+        br#"{"updater":{"endpoints":["https://updates.example.com/latest.json"],"pubkey":"abc"}}"#,
+    );
+    binary.push(0);
+    let image = BinaryImage::from_bytes(binary.clone()).unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    table
+        .export(&tauri_dumper::ExportOptions::new(&assets_dir))
+        .unwrap();
+
+    let summary = Repacker::new(image, table.clone())
+        .replace_from_dir(&assets_dir)
+        .set_csp("default-src 'self'")
+        .set_config(
+            "plugins.updater.endpoints",
+            serde_json::json!(["https://local.test/u.json"]),
+        )
+        .write(&output)
+        .unwrap();
+    assert_eq!(summary.config_edits.len(), 2);
+
+    let patched = fs::read(&output).unwrap();
+    assert_eq!(patched.len(), binary.len());
+    let text = String::from_utf8_lossy(&patched);
+    let original = "default-src 'self'; script-src 'self' https://cdn.example.com";
+    let padded = format!("{:<width$}", "default-src 'self'", width = original.len());
+    assert!(text.contains(&format!("\0{padded}\0")));
+    let start = text.find(r#"{"updater""#).unwrap();
+    let end = start + text[start..].find('\0').unwrap();
+    let config: serde_json::Value = serde_json::from_str(&text[start..end]).unwrap();
+    assert_eq!(
+        config["updater"]["endpoints"][0],
+        "https://local.test/u.json"
+    );
+    assert_eq!(config["updater"]["pubkey"], "abc");
+    assert_eq!(
+        AssetScanner::scan(&BinaryImage::from_bytes(patched).unwrap())
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let err = Repacker::new(
+        BinaryImage::from_bytes(binary.clone()).unwrap(),
+        table.clone(),
+    )
+    .replace_from_dir(&assets_dir)
+    .set_csp(
+        "default-src 'self' https://a-much-longer-host.example.com https://another.example.com",
+    )
+    .write(temp.path().join("long"))
+    .unwrap_err();
+    assert!(matches!(err, Error::ReplacementTooLarge { .. }));
+
+    let err = Repacker::new(BinaryImage::from_bytes(binary).unwrap(), table)
+        .replace_from_dir(&assets_dir)
+        .set_config("updater.missing.key", serde_json::json!(true))
+        .write(temp.path().join("missing"))
+        .unwrap_err();
+    assert!(matches!(err, Error::Message(_)));
+}
+
+#[test]
+fn editing_one_alias_replaces_shared_data_for_all_names() {
+    let temp = tempfile::tempdir().unwrap();
+    let assets_dir = temp.path().join("assets");
+    let output = temp.path().join("patched");
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_aliases(
+        b"shared content shared content",
+    ))
+    .unwrap();
+    let table = AssetScanner::scan(&image).unwrap();
+    table
+        .export(&tauri_dumper::ExportOptions::new(&assets_dir))
+        .unwrap();
+    fs::write(assets_dir.join("b.txt"), b"edited").unwrap();
+
+    let summary = Repacker::new(image, table.clone())
+        .replace_from_dir(&assets_dir)
+        .write(&output)
+        .unwrap();
+    assert_eq!(summary.replaced, 2);
+
+    let patched = BinaryImage::open(&output).unwrap();
+    let patched_table = AssetScanner::scan(&patched).unwrap();
+    for name in ["/a.txt", "/b.txt"] {
+        let data = extract::decompress_asset(patched_table.find(name).unwrap()).unwrap();
+        assert_eq!(data, b"edited", "{name}");
+    }
+
+    fs::write(assets_dir.join("a.txt"), b"edited").unwrap();
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_aliases(
+        b"shared content shared content",
+    ))
+    .unwrap();
+    let summary = Repacker::new(image, table.clone())
+        .replace_from_dir(&assets_dir)
+        .write(temp.path().join("same-edit"))
+        .unwrap();
+    assert_eq!(summary.replaced, 2);
+
+    fs::write(assets_dir.join("a.txt"), b"different").unwrap();
+    let image = BinaryImage::from_bytes(common::desktop_elf_with_aliases(
+        b"shared content shared content",
+    ))
+    .unwrap();
+    let err = Repacker::new(image, table)
+        .replace_from_dir(&assets_dir)
+        .write(temp.path().join("conflict"))
+        .unwrap_err();
+    assert!(matches!(err, Error::AliasConflict { .. }));
+}
+
+#[test]
+fn replaces_rust_embed_asset_and_its_hash() {
+    let temp = tempfile::tempdir().unwrap();
+    let assets_dir = temp.path().join("assets");
+    let output = temp.path().join("patched");
+    let binary = common::rust_embed_elf(&[("index.html", b"<!DOCTYPE html><html></html>")]);
+
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan_with_profile(&image, ScanProfile::RustEmbed).unwrap();
+    table
+        .export(&tauri_dumper::ExportOptions::new(&assets_dir))
+        .unwrap();
+    fs::write(assets_dir.join("index.html"), b"<html>ok</html>").unwrap();
+
+    Repacker::new(image, table)
+        .replace_from_dir(&assets_dir)
+        .write(&output)
+        .unwrap();
+    let patched = BinaryImage::open(&output).unwrap();
+    let patched_table = AssetScanner::scan_with_profile(&patched, ScanProfile::RustEmbed).unwrap();
+    let data = extract::decompress_asset(patched_table.find("/index.html").unwrap()).unwrap();
+    assert_eq!(data, b"<html>ok</html>");
+}
+
+#[test]
+fn replaces_asset_in_its_original_encoding() {
+    let temp = tempfile::tempdir().unwrap();
+    let output = temp.path().join("patched");
+    let binary = common::gzip_desktop_elf(&[("/index.html", b"<!DOCTYPE html><html></html>")]);
+    let mut codecs = CodecRegistry::new();
+    codecs.register(Box::new(GzipCodec));
+
+    let image = BinaryImage::from_bytes(&binary).unwrap();
+    let table = AssetScanner::scan_with_codecs(&image, ScanProfile::Tauri, &codecs).unwrap();
+    let assets_dir = temp.path().join("assets");
+    fs::create_dir(&assets_dir).unwrap();
+    fs::write(assets_dir.join("index.html"), b"<html>ok</html>").unwrap();
+    Repacker::new(image, table)
+        .replace_from_dir(&assets_dir)
+        .write(&output)
+        .unwrap();
+
+    let patched = BinaryImage::open(&output).unwrap();
+    let patched_table =
+        AssetScanner::scan_with_codecs(&patched, ScanProfile::Tauri, &codecs).unwrap();
+    let asset = patched_table.find("/index.html").unwrap();
+    assert_eq!(asset.encoding(), AssetEncoding::Gzip);
+    assert_eq!(
+        extract::decompress_asset(asset).unwrap(),
+        b"<html>ok</html>"
+    );
+}
@@ -0,0 +1,63 @@
+//! WebAssembly bindings for tauri-dumper.
+//!
+//! The binary is scanned from a byte array, so a page can dump assets from a
+//! file the user drops without uploading it anywhere.
+
+use tauri_dumper::{AssetScanner, AssetTable, BinaryImage, ScanProfile};
+use wasm_bindgen::prelude::*;
+
+/// The assets found by [`scan`].
+#[wasm_bindgen]
+pub struct Assets {
+    table: AssetTable,
+}
+
+/// Scans an executable for embedded assets.
+///
+/// `profile` is `"tauri"` (the default), `"tauri-v1"` or `"rust-embed"`.
+#[wasm_bindgen]
+pub fn scan(data: &[u8], profile: Option<String>) -> Result<Assets, JsError> {
+    let profile = match profile.as_deref() {
+        None | Some("tauri") => ScanProfile::Tauri,
+        Some("tauri-v1") => ScanProfile::TauriV1,
+        Some("rust-embed") => ScanProfile::RustEmbed,
+        Some(other) => return Err(JsError::new(&format!("unknown scan profile: {other}"))),
+    };
+    let image = BinaryImage::from_bytes(data)?;
+    Ok(Assets {
+        table: AssetScanner::scan_with_profile(&image, profile)?,
+    })
+}
+
+#[wasm_bindgen]
+impl Assets {
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns the name of the asset at `index`.
+    pub fn name(&self, index: usize) -> Option<String> {
+        self.table
+            .assets()
+            .get(index)
+            .map(|asset| asset.name().to_string())
+    }
+
+    /// Decompresses the asset at `index`.
+    pub fn data(&self, index: usize) -> Result<Vec<u8>, JsError> {
+        let asset = self
+            .table
+            .assets()
+            .get(index)
+            .ok_or_else(|| JsError::new(&format!("asset index {index} is out of range")))?;
+        Ok(tauri_dumper::extract::decompress_asset(asset)?)
+    }
+
+    /// Returns the binary metadata and asset summaries as JSON, in the format
+    /// of `tauri-dumper list --json`.
+    #[wasm_bindgen(js_name = summaryJson)]
+    pub fn summary_json(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(&self.table.summary())?)
+    }
+}
@@ -0,0 +1,76 @@
+use std::io::Write;
+use tauri_dumper_wasm::scan;
+
+const BASE: u64 = 0x40_0000;
+
+/// Builds an ELF without section headers whose single read-only segment holds
+/// a table of asset headers followed by each asset's name and Brotli data.
+fn elf_with_assets(assets: &[(&str, &[u8])]) -> Vec<u8> {
+    let header_at = 128;
+    let mut contents = Vec::new();
+    let mut header = Vec::new();
+    for (name, data) in assets {
+        let mut compressed = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+            writer.write_all(data).unwrap();
+        }
+        let name_at = header_at + assets.len() * 32 + contents.len();
+        contents.extend_from_slice(name.as_bytes());
+        let data_at = header_at + assets.len() * 32 + contents.len();
+        contents.extend_from_slice(&compressed);
+        for value in [
+            BASE + name_at as u64,
+            name.len() as u64,
+            BASE + data_at as u64,
+            compressed.len() as u64,
+        ] {
+            header.extend(value.to_le_bytes());
+        }
+    }
+    let mut elf = vec![0; header_at];
+    elf.extend(header);
+    elf.extend(contents);
+
+    elf[..8].copy_from_slice(b"\x7fELF\x02\x01\x01\0");
+    elf[16..18].copy_from_slice(&2u16.to_le_bytes());
+    elf[18..20].copy_from_slice(&62u16.to_le_bytes());
+    elf[20..24].copy_from_slice(&1u32.to_le_bytes());
+    elf[32..40].copy_from_slice(&64u64.to_le_bytes());
+    elf[52..54].copy_from_slice(&64u16.to_le_bytes());
+    elf[54..56].copy_from_slice(&56u16.to_le_bytes());
+    elf[56..58].copy_from_slice(&1u16.to_le_bytes());
+
+    let len = elf.len() as u64;
+    let mut segment = Vec::new();
+    segment.extend(1u32.to_le_bytes());
+    segment.extend(4u32.to_le_bytes());
+    for value in [0, BASE, BASE, len, len, 0x1000] {
+        segment.extend(value.to_le_bytes());
+    }
+    elf[64..120].copy_from_slice(&segment);
+    elf
+}
+
+// `JsError` can only be created on wasm targets, so these host-side tests
+// cover the paths that succeed.
+#[test]
+fn scans_assets_from_bytes() {
+    let elf = elf_with_assets(&[
+        ("/index.html", b"<!DOCTYPE html><html></html>"),
+        ("/main.js", b"console.log('main');"),
+    ]);
+    let assets = scan(&elf, None).unwrap();
+    assert_eq!(assets.length(), 2);
+    assert_eq!(assets.name(0).as_deref(), Some("/index.html"));
+    assert_eq!(assets.name(1).as_deref(), Some("/main.js"));
+    assert_eq!(assets.name(2), None);
+    assert_eq!(assets.data(1).unwrap(), b"console.log('main');");
+
+    let summary: serde_json::Value = serde_json::from_str(&assets.summary_json().unwrap()).unwrap();
+    assert_eq!(summary["asset_count"], 2);
+    assert_eq!(summary["assets"][0]["name"], "/index.html");
+
+    let assets = scan(&elf, Some("tauri".to_string())).unwrap();
+    assert_eq!(assets.length(), 2);
+}